@@ -1,14 +1,15 @@
-import { Plus, Triangle, X } from 'lucide-react'
+import { ArrowRightLeft, Plus, Triangle, X } from 'lucide-react'
 import { formatCount } from '@/lib/formatUtils'
 
 interface ChangeIconsProps {
   add: number | null
   modify: number | null
   del: number | null
+  move?: number | null
 }
 
-export function ChangeIcons({ add, modify, del }: ChangeIconsProps) {
-  if (!add && !modify && !del) return null
+export function ChangeIcons({ add, modify, del, move }: ChangeIconsProps) {
+  if (!add && !modify && !del && !move) return null
 
   return (
     <span className="inline-flex items-center gap-2.5 text-sm tabular-nums">
@@ -30,6 +31,12 @@ export function ChangeIcons({ add, modify, del }: ChangeIconsProps) {
           {formatCount(del)}
         </span>
       ) : null}
+      {move ? (
+        <span className="inline-flex items-center gap-1 text-amber-500">
+          <ArrowRightLeft className="h-3.5 w-3.5" />
+          {formatCount(move)}
+        </span>
+      ) : null}
     </span>
   )
 }
@@ -3,7 +3,7 @@ import { format, subDays, subMonths, subYears, startOfDay } from 'date-fns'
 import {
   File, Folder, Calendar as CalendarIcon,
   HardDrive, AlertTriangle, CircleX, ChevronDown, Eye, X,
-  ShieldCheck, ShieldOff, Plus, Triangle, Minus,
+  ShieldCheck, ShieldOff, Plus, Triangle, Minus, ArrowRightLeft,
 } from 'lucide-react'
 import { Switch } from '@/components/ui/switch'
 import { ReviewToggle } from '@/components/shared/ReviewToggle'
@@ -96,6 +96,17 @@ interface VersionEntry {
   access: number
   mod_date: number | null
   size: number | null
+  symlink_target: string | null
+  change_reason: string[] | null
+  moved_from_item_id: number | null
+  moved_from_path: string | null
+  moved_to_path: string | null
+  mode: number | null
+  uid: number | null
+  gid: number | null
+  inode: number | null
+  nlink: number | null
+  ctime: number | null
   add_count: number | null
   modify_count: number | null
   delete_count: number | null
@@ -114,6 +125,7 @@ interface HashEntry {
   scan_started_at: number
   file_hash: string
   hash_state: number
+  hash_algo: 'sha256' | 'blake3' | 'xxh3' | 'sha512'
 }
 
 interface VersionPageResponse {
@@ -140,7 +152,7 @@ interface IntegrityState {
   val_error: string | null
 }
 
-type ChangeKind = 'initial' | 'modified' | 'deleted' | 'restored'
+type ChangeKind = 'initial' | 'modified' | 'deleted' | 'restored' | 'moved_in' | 'moved_out'
 
 type TimeWindowPreset = '7d' | '30d' | '3m' | '6m' | '1y' | 'custom'
 
@@ -156,6 +168,7 @@ function accessLabel(access: number): string {
     case 0: return 'No Error'
     case 1: return 'Meta Error'
     case 2: return 'Read Error'
+    case 3: return 'Mount point, not traversed'
     default: return `Unknown (${access})`
   }
 }
@@ -179,6 +192,8 @@ function valStateLabel(val: number | null): string {
 }
 
 function classifyChange(v: VersionEntry, prev: VersionEntry | null): ChangeKind {
+  if (v.moved_from_path) return 'moved_in'
+  if (v.moved_to_path) return 'moved_out'
   if (v.is_added) return 'initial'
   if (v.is_deleted && prev && !prev.is_deleted) return 'deleted'
   if (v.is_deleted) return 'deleted'
@@ -204,11 +219,33 @@ function hasFolderCountChanges(v: VersionEntry, prev: VersionEntry): boolean {
   )
 }
 
+/** Unix fields are NULL on versions recorded before they were tracked; that isn't a change. */
+function unixChanged(a: number | null, b: number | null): boolean {
+  return a != null && b != null && a !== b
+}
+
+function formatMode(mode: number | null): string {
+  return mode == null ? 'N/A' : mode.toString(8).padStart(4, '0')
+}
+
+function hasUnixChanges(v: VersionEntry, prev: VersionEntry): boolean {
+  return (
+    unixChanged(v.mode, prev.mode) ||
+    unixChanged(v.uid, prev.uid) ||
+    unixChanged(v.gid, prev.gid) ||
+    unixChanged(v.inode, prev.inode) ||
+    unixChanged(v.nlink, prev.nlink) ||
+    unixChanged(v.ctime, prev.ctime)
+  )
+}
+
 function hasFieldChanges(v: VersionEntry, prev: VersionEntry): boolean {
   return (
     v.mod_date !== prev.mod_date ||
     v.size !== prev.size ||
     v.access !== prev.access ||
+    v.symlink_target !== prev.symlink_target ||
+    hasUnixChanges(v, prev) ||
     hasFolderCountChanges(v, prev)
   )
 }
@@ -686,11 +723,14 @@ export function ItemDetail({
       kind === 'initial' ? <Plus className={`${sp.icon} text-green-500`} /> :
       kind === 'modified' ? <Triangle className={`${sp.icon} text-blue-500`} fill="currentColor" /> :
       kind === 'deleted' ? <X className={`${sp.icon} text-red-500`} /> :
+      kind === 'moved_in' || kind === 'moved_out' ? <ArrowRightLeft className={`${sp.icon} text-amber-500`} /> :
       <Plus className={`${sp.icon} text-green-500`} /> // restored
     const label =
       kind === 'initial' ? 'Added' :
       kind === 'modified' ? 'Modified' :
       kind === 'deleted' ? 'Deleted' :
+      kind === 'moved_in' ? 'Moved In' :
+      kind === 'moved_out' ? 'Moved Out' :
       'Restored'
     return (
       <span className={`inline-flex items-center ${sp.gap} text-xs flex-shrink-0`}>
@@ -906,6 +946,7 @@ export function ItemDetail({
                                     className="font-mono text-left hover:text-foreground transition-colors flex items-center gap-1"
                                     onClick={() => setExpandedHashes(prev => ({ ...prev, [globalIdx]: !isExpanded }))}
                                   >
+                                    <span className="text-muted-foreground flex-shrink-0">{h.hash_algo}</span>
                                     <span className={isExpanded ? 'break-all' : ''}>
                                       {isExpanded ? h.file_hash : h.file_hash.slice(0, 12) + '\u2026'}
                                     </span>
@@ -1288,8 +1329,9 @@ export function ItemDetail({
                 const setIsOpen = (val: boolean) => setOpenVersions(prev => ({ ...prev, [v.item_version]: val }))
                 const hasIntegrity = v.hash_state != null || v.val_state != null
                 const hasMetadataChanges = kind === 'modified' && prev && hasFieldChanges(v, prev)
-                const hasInitialFolderCounts = kind === 'initial' && hasNonZeroFolderCounts(v)
-                const isExpandable = hasMetadataChanges || hasInitialFolderCounts || hasIntegrity
+                const hasInitialFolderCounts = (kind === 'initial' || kind === 'moved_in') && hasNonZeroFolderCounts(v)
+                const isMove = kind === 'moved_in' || kind === 'moved_out'
+                const isExpandable = hasMetadataChanges || hasInitialFolderCounts || hasIntegrity || isMove
 
                 const headerContent = (
                   <>
@@ -1326,6 +1368,15 @@ export function ItemDetail({
                           </div>
                           <CollapsibleContent className={`${isPanel ? 'mt-1' : 'mt-2'} ${sp.ml}`}>
                             <div className={`${sp.space} text-xs`}>
+                              {v.moved_from_path && (
+                                <p className="text-muted-foreground">Moved from {v.moved_from_path}</p>
+                              )}
+                              {v.moved_to_path && (
+                                <p className="text-muted-foreground">Moved to {v.moved_to_path}</p>
+                              )}
+                              {v.change_reason && v.change_reason.length > 0 && (
+                                <p className="text-muted-foreground">Changed: {v.change_reason.map(r => r.replace(/_/g, ' ')).join(', ')}</p>
+                              )}
                               {/* Metadata diffs (modified versions with predecessor) */}
                               {prev && v.mod_date !== prev.mod_date && (
                                 <div className={`bg-muted/50 ${sp.pad} rounded`}>
@@ -1339,6 +1390,42 @@ export function ItemDetail({
                                   <p className="text-muted-foreground">{formatFileSize(prev.size)} &rarr; {formatFileSize(v.size)}</p>
                                 </div>
                               )}
+                              {prev && v.symlink_target !== prev.symlink_target && (
+                                <div className={`bg-muted/50 ${sp.pad} rounded`}>
+                                  <p className="font-medium">Symlink Target</p>
+                                  <p className="font-mono break-all text-muted-foreground">{prev.symlink_target ?? 'N/A'} &rarr; {v.symlink_target ?? 'N/A'}</p>
+                                </div>
+                              )}
+                              {prev && unixChanged(v.mode, prev.mode) && (
+                                <div className={`bg-muted/50 ${sp.pad} rounded`}>
+                                  <p className="font-medium">Permissions</p>
+                                  <p className="font-mono text-muted-foreground">{formatMode(prev.mode)} &rarr; {formatMode(v.mode)}</p>
+                                </div>
+                              )}
+                              {prev && (unixChanged(v.uid, prev.uid) || unixChanged(v.gid, prev.gid)) && (
+                                <div className={`bg-muted/50 ${sp.pad} rounded`}>
+                                  <p className="font-medium">Owner (uid:gid)</p>
+                                  <p className="font-mono text-muted-foreground">{prev.uid}:{prev.gid} &rarr; {v.uid}:{v.gid}</p>
+                                </div>
+                              )}
+                              {prev && unixChanged(v.inode, prev.inode) && (
+                                <div className={`bg-muted/50 ${sp.pad} rounded`}>
+                                  <p className="font-medium">Inode</p>
+                                  <p className="font-mono text-muted-foreground">{prev.inode} &rarr; {v.inode}</p>
+                                </div>
+                              )}
+                              {prev && unixChanged(v.nlink, prev.nlink) && (
+                                <div className={`bg-muted/50 ${sp.pad} rounded`}>
+                                  <p className="font-medium">Hard Links</p>
+                                  <p className="text-muted-foreground">{prev.nlink} &rarr; {v.nlink}</p>
+                                </div>
+                              )}
+                              {prev && unixChanged(v.ctime, prev.ctime) && (
+                                <div className={`bg-muted/50 ${sp.pad} rounded`}>
+                                  <p className="font-medium">Inode Changed</p>
+                                  <p className="text-muted-foreground">{formatDateFull(prev.ctime!)} &rarr; {formatDateFull(v.ctime!)}</p>
+                                </div>
+                              )}
                               {prev && v.access !== prev.access && (
                                 <div className={`bg-muted/50 ${sp.pad} rounded`}>
                                   <p className="font-medium">Access</p>
@@ -33,10 +33,12 @@ interface ScanDetails {
   scan_state: string
   is_hash: boolean
   hash_all: boolean
+  hash_sampled: boolean
   is_val: boolean
   add_count: number
   modify_count: number
   delete_count: number
+  move_count: number
   file_count: number
   folder_count: number
   total_size: number | null
@@ -78,6 +80,8 @@ const SCAN_COLUMNS: ColumnSpec[] = [
   { name: 'hash_baseline_count', visible: true, sort_direction: 'none', position: 22 },
   { name: 'hash_suspect_count', visible: true, sort_direction: 'none', position: 23 },
   { name: 'error', visible: true, sort_direction: 'none', position: 24 },
+  { name: 'move_count', visible: true, sort_direction: 'none', position: 25 },
+  { name: 'hash_sampled', visible: true, sort_direction: 'none', position: 26 },
 ]
 
 const ROOT_COLUMNS: ColumnSpec[] = [
@@ -202,6 +206,8 @@ export function ScanDetailSheet({
           hash_baseline_count: parseInt(row[22]) || 0,
           hash_suspect_count: parseInt(row[23]) || 0,
           error: row[24] && row[24] !== '-' ? row[24] : null,
+          move_count: parseInt(row[25]) || 0,
+          hash_sampled: parseBool(row[26]),
         })
       } catch (err) {
         setError(err instanceof Error ? err.message : 'Failed to load scan details')
@@ -213,7 +219,7 @@ export function ScanDetailSheet({
     loadScanDetails()
   }, [open, scanId])
 
-  const hasChanges = details && (details.add_count > 0 || details.modify_count > 0 || details.delete_count > 0)
+  const hasChanges = details && (details.add_count > 0 || details.modify_count > 0 || details.delete_count > 0 || details.move_count > 0)
 
   return (
     <Sheet open={open} onOpenChange={onOpenChange}>
@@ -313,7 +319,7 @@ export function ScanDetailSheet({
               <p className="text-xs font-medium uppercase tracking-wide text-muted-foreground mb-2">Changes</p>
               <div className="text-sm">
                 {hasChanges ? (
-                  <ChangeIcons add={details.add_count} modify={details.modify_count} del={details.delete_count} />
+                  <ChangeIcons add={details.add_count} modify={details.modify_count} del={details.delete_count} move={details.move_count} />
                 ) : (
                   <span className="text-muted-foreground">No changes</span>
                 )}
@@ -330,7 +336,7 @@ export function ScanDetailSheet({
               <div className="flex items-center gap-4 text-sm mb-3">
                 <span>
                   <span className="text-muted-foreground">Hashing:</span>{' '}
-                  <span className="font-medium">{details.is_hash ? (details.hash_all ? 'All' : 'New / Changed') : 'None'}</span>
+                  <span className="font-medium">{details.is_hash ? (details.hash_all ? 'All' : details.hash_sampled ? 'Sampled' : 'New / Changed') : 'None'}</span>
                 </span>
                 <span>
                   <span className="text-muted-foreground">Validation:</span>{' '}
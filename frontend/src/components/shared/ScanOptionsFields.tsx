@@ -3,21 +3,48 @@
  * Used in both manual scan dialogs and schedule creation dialogs
  */
 
+import { Input } from '@/components/ui/input'
+import type { ReadLimitFields } from '@/lib/readLimitUtils'
+import { supportsReverify, type ReverifyFields } from '@/lib/reverifyUtils'
+
 interface ScanOptionsFieldsProps {
   hashMode: string
   isVal: boolean
+  subpath: string
+  reverify: ReverifyFields
+  readLimits?: ReadLimitFields  // Shown only when provided (schedules)
   onHashModeChange: (mode: string) => void
   onIsValChange: (isVal: boolean) => void
+  onSubpathChange: (subpath: string) => void
+  onReverifyChange: (reverify: ReverifyFields) => void
+  onReadLimitsChange?: (readLimits: ReadLimitFields) => void
 }
 
 export function ScanOptionsFields({
   hashMode,
   isVal,
+  subpath,
+  reverify,
+  readLimits,
   onHashModeChange,
   onIsValChange,
+  onSubpathChange,
+  onReverifyChange,
+  onReadLimitsChange,
 }: ScanOptionsFieldsProps) {
   return (
     <div className="space-y-6">
+      {/* Folder */}
+      <div className="space-y-2">
+        <label className="text-sm font-semibold">Folder</label>
+        <Input
+          value={subpath}
+          onChange={(e) => onSubpathChange(e.target.value)}
+          placeholder="Whole root, or a folder within it (e.g., Photos/2019)"
+          className="font-mono"
+        />
+      </div>
+
       {/* Hash Mode */}
       <div className="space-y-4">
         <label className="text-sm font-semibold">Hash Files</label>
@@ -46,6 +73,21 @@ export function ScanOptionsFields({
             <span className="text-sm">New or Changed</span>
           </label>
 
+          <label className="flex items-center gap-2 cursor-pointer">
+            <input
+              type="radio"
+              name="hash-mode"
+              value="Sampled"
+              checked={hashMode === 'Sampled'}
+              onChange={(e) => onHashModeChange(e.target.value)}
+              className="w-4 h-4"
+            />
+            <span className="text-sm">Sampled</span>
+            <span className="text-xs text-muted-foreground">
+              New or Changed, plus a quick sample of the rest
+            </span>
+          </label>
+
           <label className="flex items-center gap-2 cursor-pointer">
             <input
               type="radio"
@@ -60,6 +102,79 @@ export function ScanOptionsFields({
         </div>
       </div>
 
+      {/* Re-verify the stalest hashes */}
+      {supportsReverify(hashMode) && (
+        <div className="space-y-2">
+          <label className="text-sm font-semibold">Re-verify Oldest Hashes</label>
+          <p className="text-xs text-muted-foreground">
+            Also re-hash the files whose hash was checked longest ago. Leave empty to skip.
+          </p>
+          <div className="grid grid-cols-3 gap-2">
+            <div className="space-y-1">
+              <span className="text-xs text-muted-foreground">Older than (days)</span>
+              <Input
+                type="number"
+                min={0}
+                value={reverify.days}
+                onChange={(e) => onReverifyChange({ ...reverify, days: e.target.value })}
+              />
+            </div>
+            <div className="space-y-1">
+              <span className="text-xs text-muted-foreground">Up to (GB)</span>
+              <Input
+                type="number"
+                min={0}
+                step="any"
+                value={reverify.gb}
+                onChange={(e) => onReverifyChange({ ...reverify, gb: e.target.value })}
+              />
+            </div>
+            <div className="space-y-1">
+              <span className="text-xs text-muted-foreground">Up to (% of root)</span>
+              <Input
+                type="number"
+                min={0}
+                max={100}
+                step="any"
+                value={reverify.percent}
+                onChange={(e) => onReverifyChange({ ...reverify, percent: e.target.value })}
+              />
+            </div>
+          </div>
+        </div>
+      )}
+
+      {/* Read limits */}
+      {readLimits && onReadLimitsChange && (
+        <div className="space-y-2">
+          <label className="text-sm font-semibold">Read Limits</label>
+          <p className="text-xs text-muted-foreground">
+            Replace the global analysis read limits for this schedule. Leave empty to use the
+            global settings, or enter 0 for unlimited.
+          </p>
+          <div className="grid grid-cols-2 gap-2">
+            <div className="space-y-1">
+              <span className="text-xs text-muted-foreground">MB/s</span>
+              <Input
+                type="number"
+                min={0}
+                value={readLimits.mbPerSec}
+                onChange={(e) => onReadLimitsChange({ ...readLimits, mbPerSec: e.target.value })}
+              />
+            </div>
+            <div className="space-y-1">
+              <span className="text-xs text-muted-foreground">Reads/s</span>
+              <Input
+                type="number"
+                min={0}
+                value={readLimits.iops}
+                onChange={(e) => onReadLimitsChange({ ...readLimits, iops: e.target.value })}
+              />
+            </div>
+          </div>
+        </div>
+      )}
+
       {/* Validate Files */}
       <div className="space-y-4">
         <label className="flex items-center gap-2 cursor-pointer">
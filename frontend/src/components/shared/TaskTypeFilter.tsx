@@ -32,6 +32,8 @@ export function TaskTypeFilter({
           <SelectItem value="all">All Types</SelectItem>
           <SelectItem value="0">Scan</SelectItem>
           <SelectItem value="1">Compact Database</SelectItem>
+          <SelectItem value="2">Export Manifest</SelectItem>
+          <SelectItem value="3">Compare Roots</SelectItem>
         </SelectContent>
       </Select>
     </div>
@@ -139,6 +139,7 @@ export function TaskProvider({ children }: { children: React.ReactNode }) {
       phase: task.phase,
       progress_bar: task.progress_bar,
       thread_states: task.thread_states ?? [],
+      io_throughput: task.io_throughput ?? null,
     }
 
     setActiveTask(taskData)
@@ -117,6 +117,10 @@ export interface IntegrityItemSummary {
   hash_reviewed: number
   val_unreviewed: number
   val_reviewed: number
+  manifest_unreviewed: number
+  manifest_reviewed: number
+  content_unreviewed: number
+  content_reviewed: number
   latest_scan_id: number
 }
 
@@ -131,6 +135,14 @@ export interface IntegrityVersion {
   val_error: string | null
   val_reviewed_at: number | null
   hash_reviewed_at: number | null
+  manifest_state: number | null
+  manifest_path: string | null
+  manifest_algo: string | null
+  manifest_hash: string | null
+  manifest_reviewed_at: number | null
+  content_type: string | null
+  content_mismatch: boolean
+  content_reviewed_at: number | null
 }
 
 export interface IntegrityVersionsResponse {
@@ -183,6 +195,8 @@ export async function setIntegrityReviewed(
   itemVersion: number | null,
   setVal: boolean | null,
   setHash: boolean | null,
+  setManifest: boolean | null = null,
+  setContent: boolean | null = null,
 ): Promise<{ success: boolean }> {
   const response = await fetch(`${API_BASE}/integrity/review`, {
     method: 'POST',
@@ -192,6 +206,8 @@ export async function setIntegrityReviewed(
       item_version: itemVersion,
       set_val: setVal,
       set_hash: setHash,
+      set_manifest: setManifest,
+      set_content: setContent,
     }),
   })
   return handleResponse<{ success: boolean }>(response)
@@ -203,6 +219,8 @@ export async function bulkReviewIntegrity(
     filter?: IntegrityFilterParams
     set_val: boolean | null
     set_hash: boolean | null
+    set_manifest: boolean | null
+    set_content: boolean | null
   },
 ): Promise<{ success: boolean; affected: number }> {
   const response = await fetch(`${API_BASE}/integrity/bulk-review`, {
@@ -0,0 +1,37 @@
+/**
+ * Read limit helpers for the schedule form
+ */
+
+import type { ReadLimits } from '@/lib/types'
+
+/**
+ * Read limits as entered in the form (empty = use the global setting)
+ */
+export interface ReadLimitFields {
+  mbPerSec: string
+  iops: string
+}
+
+export const EMPTY_READ_LIMITS: ReadLimitFields = { mbPerSec: '', iops: '' }
+
+/**
+ * Form fields for stored limits (missing limits leave them empty)
+ */
+export function readLimitsToFields(limits: ReadLimits | null | undefined): ReadLimitFields {
+  return {
+    mbPerSec: limits?.mb_per_sec?.toString() ?? '',
+    iops: limits?.iops?.toString() ?? '',
+  }
+}
+
+/**
+ * The limits to send for the form's fields, or undefined when none are set
+ */
+export function readLimitsFromFields(fields: ReadLimitFields): ReadLimits | undefined {
+  const limits: ReadLimits = {}
+  const mbPerSec = parseInt(fields.mbPerSec)
+  const iops = parseInt(fields.iops)
+  if (mbPerSec >= 0) limits.mb_per_sec = mbPerSec
+  if (iops >= 0) limits.iops = iops
+  return Object.keys(limits).length > 0 ? limits : undefined
+}
@@ -0,0 +1,50 @@
+/**
+ * Re-verification budget helpers for the scan options form
+ */
+
+import type { ReverifyBudget } from '@/lib/types'
+
+const BYTES_PER_GB = 1024 * 1024 * 1024
+
+/**
+ * Re-verification budget as entered in the form (empty = no limit)
+ */
+export interface ReverifyFields {
+  days: string
+  gb: string
+  percent: string
+}
+
+export const EMPTY_REVERIFY: ReverifyFields = { days: '', gb: '', percent: '' }
+
+/**
+ * Only hash modes that skip unchanged files can re-verify a share of them
+ */
+export function supportsReverify(hashMode: string): boolean {
+  return hashMode === 'New or Changed' || hashMode === 'Sampled'
+}
+
+/**
+ * Form fields for a stored budget (a missing budget leaves them empty)
+ */
+export function reverifyToFields(budget: ReverifyBudget | null | undefined): ReverifyFields {
+  return {
+    days: budget?.older_than_days?.toString() ?? '',
+    gb: budget?.max_bytes ? String(budget.max_bytes / BYTES_PER_GB) : '',
+    percent: budget?.max_percent?.toString() ?? '',
+  }
+}
+
+/**
+ * The budget to send for the form's fields, or undefined when none are set
+ */
+export function reverifyFromFields(fields: ReverifyFields): ReverifyBudget | undefined {
+  const budget: ReverifyBudget = {}
+  const days = parseInt(fields.days)
+  const gb = parseFloat(fields.gb)
+  const percent = parseFloat(fields.percent)
+  if (days >= 0) budget.older_than_days = days
+  if (gb > 0) budget.max_bytes = Math.round(gb * BYTES_PER_GB)
+  if (percent > 0) budget.max_percent = Math.min(percent, 100)
+  return Object.keys(budget).length > 0 ? budget : undefined
+}
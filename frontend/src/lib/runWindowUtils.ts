@@ -0,0 +1,42 @@
+/**
+ * Run window helpers for the schedule form
+ */
+
+import type { RunWindow } from '@/lib/types'
+
+/**
+ * A run window as entered in the form (both empty = use the global setting)
+ */
+export interface RunWindowInput {
+  start: string
+  end: string
+}
+
+export const EMPTY_RUN_WINDOW: RunWindowInput = { start: '', end: '' }
+
+/**
+ * Form fields for a stored window (no window leaves them empty)
+ */
+export function runWindowToFields(window: RunWindow | null | undefined): RunWindowInput {
+  return {
+    start: window?.start ?? '',
+    end: window?.end ?? '',
+  }
+}
+
+/**
+ * Why the fields can't be saved, or null if they can
+ */
+export function runWindowError(fields: RunWindowInput): string | null {
+  if (!fields.start && !fields.end) return null
+  if (!fields.start || !fields.end) return 'Please enter both a start and an end for the run window'
+  if (fields.start === fields.end) return 'The run window must start and end at different times'
+  return null
+}
+
+/**
+ * The window to send for the form's fields, or undefined when none is set
+ */
+export function runWindowFromFields(fields: RunWindowInput): RunWindow | undefined {
+  return fields.start && fields.end ? { start: fields.start, end: fields.end } : undefined
+}
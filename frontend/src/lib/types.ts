@@ -84,7 +84,7 @@ export interface ValidateFilterResponse {
 
 // Task Progress Types (WebSocket protocol)
 
-export type TaskType = 'scan' | 'compact_database'
+export type TaskType = 'scan' | 'compact_database' | 'export_manifest' | 'compare_roots'
 
 export type TaskStatus = 'pending' | 'running' | 'pausing' | 'stopping' | 'stopped' | 'completed' | 'error'
 
@@ -104,6 +104,12 @@ export interface ThreadState {
   detail: string | null
 }
 
+// Measured read throughput of a scan's analysis phase
+export interface IoThroughput {
+  bytes_per_sec: number
+  reads_per_sec: number
+}
+
 export interface TaskProgressState {
   task_id: number
   task_type: TaskType
@@ -119,6 +125,7 @@ export interface TaskProgressState {
   phase: string | null
   progress_bar: ProgressBar | null
   thread_states: ThreadState[] | null
+  io_throughput: IoThroughput | null
 }
 
 // Pause-related types
@@ -146,6 +153,7 @@ export interface TaskData {
   phase: string | null
   progress_bar: ProgressBar | null
   thread_states: ThreadState[]
+  io_throughput: IoThroughput | null
 }
 
 // Root with Scan Info (for Scan page)
@@ -163,13 +171,37 @@ export interface RootWithScan {
   root_path: string
   last_scan?: LastScanInfo
   schedule_count: number  // Number of active schedules for this root
+  watch_status: { state: 'watching' } | { state: 'degraded'; reason: string } | null  // null unless the root is watched
 }
 
 // Scan scheduling
+
+// Budget for re-hashing the files with the stalest hashes (New and Sampled hash modes)
+export interface ReverifyBudget {
+  older_than_days?: number  // Only hashes confirmed at least this many days ago
+  max_bytes?: number  // Re-hash at most this many bytes per scan
+  max_percent?: number  // Re-hash at most this percentage of the bytes in scope per scan
+}
+
+// Analysis read limits replacing the global settings (0 = unlimited, omitted = global)
+export interface ReadLimits {
+  mb_per_sec?: number  // Megabytes (10^6 bytes) per second
+  iops?: number  // Reads per second
+}
+
+// Daily range of local time a schedule's scans may run in ('HH:MM'; end before start runs past midnight)
+export interface RunWindow {
+  start: string
+  end: string
+}
+
 export interface ScheduleScanRequest {
   root_id: number
-  hash_mode: 'All' | 'New' | 'None'
+  hash_mode: 'All' | 'New' | 'Sampled' | 'None'
   is_val: boolean
+  subpath?: string  // Root-relative folder to scan instead of the whole root
+  reverify?: ReverifyBudget
+  read_limits?: ReadLimits
 }
 
 // Schedule types
@@ -187,8 +219,12 @@ export interface Schedule {
   day_of_month?: number  // 1-31
   interval_value?: number
   interval_unit?: IntervalUnit
-  hash_mode: 'All' | 'New' | 'None'
+  hash_mode: 'All' | 'New' | 'Sampled' | 'None'
   is_val: boolean
+  subpath: string | null  // Root-relative folder to scan (null = whole root)
+  reverify: ReverifyBudget | null  // null = no re-verification
+  read_limits: ReadLimits | null  // null = global read limits
+  run_window: RunWindow | null  // null = global run window
   created_at: number  // Unix timestamp
   updated_at: number  // Unix timestamp
 }
@@ -0,0 +1,5 @@
+import { DataExplorerView } from './DataExplorerView'
+
+export function DuplicatesView() {
+  return <DataExplorerView domain="duplicates" />
+}
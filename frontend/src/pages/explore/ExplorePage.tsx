@@ -6,9 +6,10 @@ import { ScansView } from './ScansView'
 import { ItemsView } from './ItemsView'
 import { VersionsView } from './VersionsView'
 import { HashesView } from './HashesView'
+import { DuplicatesView } from './DuplicatesView'
 import { QueryView } from './QueryView'
 
-const VALID_TABS = ['roots', 'scans', 'items', 'versions', 'hashes', 'query'] as const
+const VALID_TABS = ['roots', 'scans', 'items', 'versions', 'hashes', 'duplicates', 'query'] as const
 type TabValue = typeof VALID_TABS[number]
 
 export function ExplorePage() {
@@ -47,6 +48,7 @@ export function ExplorePage() {
           <TabsTrigger value="items">Items</TabsTrigger>
           <TabsTrigger value="versions">Versions</TabsTrigger>
           <TabsTrigger value="hashes">Hashes</TabsTrigger>
+          <TabsTrigger value="duplicates">Duplicates</TabsTrigger>
           <TabsTrigger value="query">Query</TabsTrigger>
         </TabsList>
 
@@ -66,6 +68,9 @@ export function ExplorePage() {
         <div className={`mt-2 ${currentTab === 'hashes' ? '' : 'hidden'}`}>
           <HashesView />
         </div>
+        <div className={`mt-2 ${currentTab === 'duplicates' ? '' : 'hidden'}`}>
+          <DuplicatesView />
+        </div>
         <div className={`mt-2 ${currentTab === 'query' ? '' : 'hidden'}`}>
           <QueryView />
         </div>
@@ -40,6 +40,10 @@ const SAMPLE_QUERIES = [
     label: 'Suspect hashes',
     query: 'hashes where hash_state:(S) show item_path, item_version, file_hash limit 20',
   },
+  {
+    label: 'Duplicate files',
+    query: 'duplicates order by wasted_size desc limit 20',
+  },
   {
     label: 'Files by extension',
     query: 'versions where is_current:(T), item_type:(F) group by file_extension show file_extension, count(*), sum(size) order by sum(size) desc limit 20',
@@ -10,6 +10,12 @@ import {
 import { Button } from '@/components/ui/button'
 import { RootPicker } from '@/components/shared/RootPicker'
 import { ScanOptionsFields } from '@/components/shared/ScanOptionsFields'
+import {
+  EMPTY_REVERIFY,
+  reverifyFromFields,
+  supportsReverify,
+  type ReverifyFields,
+} from '@/lib/reverifyUtils'
 import { useTaskContext } from '@/contexts/TaskContext'
 import { fetchQuery } from '@/lib/api'
 import { Loader2 } from 'lucide-react'
@@ -32,6 +38,8 @@ export function ManualScanDialog({ open, onOpenChange, preselectedRootId }: Manu
   const [selectedRootId, setSelectedRootId] = useState<string>('')
   const [hashMode, setHashMode] = useState<string>('New or Changed')
   const [isVal, setIsVal] = useState<boolean>(true)
+  const [subpath, setSubpath] = useState<string>('')
+  const [reverify, setReverify] = useState<ReverifyFields>(EMPTY_REVERIFY)
 
   const [loading, setLoading] = useState(true)
   const [submitting, setSubmitting] = useState(false)
@@ -44,6 +52,8 @@ export function ManualScanDialog({ open, onOpenChange, preselectedRootId }: Manu
       setSelectedRootId(preselectedRootId ? preselectedRootId.toString() : '')
       setHashMode('New or Changed')
       setIsVal(true)
+      setSubpath('')
+      setReverify(EMPTY_REVERIFY)
       setError(null)
 
       loadRoots()
@@ -96,7 +106,7 @@ export function ManualScanDialog({ open, onOpenChange, preselectedRootId }: Manu
       // Map UI hash mode to API value
       const mapHashMode = (mode: string): string => {
         if (mode === 'New or Changed') return 'New'
-        return mode // 'All' and 'None' map directly
+        return mode // 'All', 'Sampled' and 'None' map directly
       }
 
       // Schedule the scan
@@ -109,6 +119,8 @@ export function ManualScanDialog({ open, onOpenChange, preselectedRootId }: Manu
           root_id: parseInt(selectedRootId),
           hash_mode: mapHashMode(hashMode),
           is_val: isVal,
+          subpath: subpath.trim() || undefined,
+          reverify: supportsReverify(hashMode) ? reverifyFromFields(reverify) : undefined,
         }),
       })
 
@@ -174,8 +186,12 @@ export function ManualScanDialog({ open, onOpenChange, preselectedRootId }: Manu
             <ScanOptionsFields
               hashMode={hashMode}
               isVal={isVal}
+              subpath={subpath}
+              reverify={reverify}
               onHashModeChange={setHashMode}
               onIsValChange={setIsVal}
+              onSubpathChange={setSubpath}
+              onReverifyChange={setReverify}
             />
 
             {/* Pause Warning */}
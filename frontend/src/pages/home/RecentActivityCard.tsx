@@ -38,6 +38,8 @@ const taskTypeDisplayName = (taskType: string): string => {
   switch (taskType) {
     case 'scan': return 'Scan'
     case 'compact_database': return 'Compact Database'
+    case 'export_manifest': return 'Export Manifest'
+    case 'compare_roots': return 'Compare Roots'
     default: return taskType
   }
 }
@@ -6,6 +6,7 @@ import { Card, CardContent } from '@/components/ui/card'
 import { Badge } from '@/components/ui/badge'
 import { Button } from '@/components/ui/button'
 import { InfoBar } from '@/components/shared/InfoBar'
+import { formatFileSizeCompact } from '@/lib/formatUtils'
 import { ManualScanDialog } from './ManualScanDialog'
 import { PauseDialog } from './PauseDialog'
 
@@ -165,6 +166,13 @@ export function TaskCard() {
                       {activeTask.progress_bar.message}
                     </div>
                   )}
+                  {activeTask.io_throughput && (
+                    <div className="text-sm text-muted-foreground">
+                      Reading {formatFileSizeCompact(activeTask.io_throughput.bytes_per_sec)}/s
+                      {' \u00b7 '}
+                      {activeTask.io_throughput.reads_per_sec.toLocaleString()} reads/s
+                    </div>
+                  )}
                 </div>
 
                 {/* Thread Details */}
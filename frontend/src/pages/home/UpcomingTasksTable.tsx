@@ -10,7 +10,7 @@ import {
 } from '@/components/ui/table'
 import { useTaskContext } from '@/contexts/TaskContext'
 import { shortenPath } from '@/lib/pathUtils'
-import { Clock, Calendar, CirclePause } from 'lucide-react'
+import { Clock, Calendar, CirclePause, Hourglass } from 'lucide-react'
 import { RootDetailSheet } from '@/components/shared/RootDetailSheet'
 
 interface UpcomingTask {
@@ -21,15 +21,20 @@ interface UpcomingTask {
   schedule_id: number | null
   schedule_name: string | null
   run_at: number  // Unix timestamp (0 = immediately)
-  source: string  // "Manual" or "Scheduled"
+  source: string  // "Manual", "Scheduled" or "Watch"
   is_ready: boolean  // true if run_at <= now (eligible to start)
   status: number     // TaskStatus: 0=Pending, 1=Running
+  run_window: { start: string; end: string } | null  // Window the task may run in (null = any time)
+  window_open: boolean  // false if the task is waiting for its window
+  window_opens_at: number | null  // When a closed window next opens (Unix timestamp)
 }
 
 const taskTypeDisplayName = (taskType: string): string => {
   switch (taskType) {
     case 'scan': return 'Scan'
     case 'compact_database': return 'Compact Database'
+    case 'export_manifest': return 'Export Manifest'
+    case 'compare_roots': return 'Compare Roots'
     default: return taskType
   }
 }
@@ -81,8 +86,12 @@ export function UpcomingTasksTable() {
     }
 
     // For scheduled tasks, show relative time
+    return formatRelative(runAt)
+  }
+
+  const formatRelative = (timestamp: number): string => {
     const now = Date.now() / 1000  // Convert to seconds
-    const diff = runAt - now
+    const diff = timestamp - now
 
     const minutes = Math.floor(diff / 60)
     const hours = Math.floor(diff / 3600)
@@ -160,8 +169,8 @@ export function UpcomingTasksTable() {
               </TableHeader>
               <TableBody>
                 {tasks.map((task) => {
-                  // Calculate position among ready tasks
-                  const readyTasks = tasks.filter(t => t.is_ready)
+                  // Calculate position among ready tasks (tasks waiting for their run window aren't in line)
+                  const readyTasks = tasks.filter(t => t.is_ready && t.window_open)
                   const readyPosition = readyTasks.findIndex(t => t.task_id === task.task_id)
 
                   return (
@@ -192,11 +201,19 @@ export function UpcomingTasksTable() {
                       </TableCell>
                       <TableCell className="text-center">
                         <div className="flex items-center justify-center gap-2">
-                          {task.status === 1 ? (
+                          {task.status === 1 && (isPaused || task.window_open) ? (
                             <>
                               <CirclePause className="h-4 w-4 text-purple-500" />
                               <span className="text-sm">Paused</span>
                             </>
+                          ) : !task.window_open && (task.is_ready || task.status === 1) ? (
+                            <span
+                              className="flex items-center gap-2"
+                              title={task.run_window ? `Runs ${task.run_window.start}–${task.run_window.end}` : undefined}
+                            >
+                              <Hourglass className="h-4 w-4 text-amber-500" />
+                              <span className="text-sm">{task.status === 1 ? 'Suspended' : 'Waiting'}</span>
+                            </span>
                           ) : task.is_ready ? (
                             <>
                               <Clock className="h-4 w-4 text-purple-500" />
@@ -211,11 +228,15 @@ export function UpcomingTasksTable() {
                         </div>
                       </TableCell>
                       <TableCell className="text-right font-medium">
-                        {task.status === 1
+                        {(task.status === 1 && isPaused)
                           ? 'When unpaused'
-                          : (task.is_ready && readyPosition === 0 && isPaused)
-                            ? 'When unpaused'
-                            : formatNextRun(task.run_at, task.is_ready, readyPosition)
+                          : (!task.window_open && task.window_opens_at && (task.is_ready || task.status === 1))
+                            ? `Window opens ${formatRelative(task.window_opens_at)}`
+                            : task.status === 1
+                              ? 'When unpaused'
+                              : (task.is_ready && readyPosition === 0 && isPaused)
+                                ? 'When unpaused'
+                                : formatNextRun(task.run_at, task.is_ready, readyPosition)
                         }
                       </TableCell>
                     </TableRow>
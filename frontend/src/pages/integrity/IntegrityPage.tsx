@@ -75,7 +75,8 @@ const FILE_TYPE_OPTIONS: { label: string; value: string }[] = [
   { label: 'All file types', value: 'all' },
   { label: 'Image files', value: 'jpg,jpeg,png,gif,bmp,tiff' },
   { label: 'PDF files', value: 'pdf' },
-  { label: 'Audio files', value: 'flac' },
+  { label: 'Audio files', value: 'flac,mp3,ogg,oga,opus,wav,aif,aiff,aifc,m4a,m4b,m4p' },
+  { label: 'Video files', value: 'mp4,m4v,mov,mkv,webm,mk3d' },
 ]
 
 function parentFolder(path: string): string {
@@ -326,11 +327,18 @@ export function IntegrityPage() {
   // --- Actions ---
 
   // Determine which review flags to set based on active issue_type filter
-  const reviewFlags = useCallback((setTo: boolean): { set_val: boolean | null; set_hash: boolean | null } => {
+  const reviewFlags = useCallback((setTo: boolean): {
+    set_val: boolean | null
+    set_hash: boolean | null
+    set_manifest: boolean | null
+    set_content: boolean | null
+  } => {
     const it = issueType === 'all' ? 'all' : issueType
     return {
       set_val: it === 'all' || it === 'val' ? setTo : null,
       set_hash: it === 'all' || it === 'hash' ? setTo : null,
+      set_manifest: it === 'all' || it === 'manifest' ? setTo : null,
+      set_content: it === 'all' || it === 'content' ? setTo : null,
     }
   }, [issueType])
 
@@ -349,7 +357,7 @@ export function IntegrityPage() {
   const handleReviewAll = async (item: IntegrityItemSummary) => {
     const flags = reviewFlags(true)
     await withPending(`review-all-${item.item_id}`, async () => {
-      await setIntegrityReviewed(item.item_id, null, flags.set_val, flags.set_hash)
+      await setIntegrityReviewed(item.item_id, null, flags.set_val, flags.set_hash, flags.set_manifest, flags.set_content)
       await Promise.all([refreshItemsList(), refreshExpanded(item.item_id)])
     })
   }
@@ -363,6 +371,24 @@ export function IntegrityPage() {
     })
   }
 
+  // Toggle manifest review on a specific version
+  const handleToggleManifestReview = async (itemId: number, ver: IntegrityVersion) => {
+    const setTo = ver.manifest_reviewed_at === null
+    await withPending(`${itemId}-${ver.item_version}-manifest`, async () => {
+      await setIntegrityReviewed(itemId, ver.item_version, null, null, setTo)
+      await Promise.all([refreshItemsList(), refreshExpanded(itemId)])
+    })
+  }
+
+  // Toggle content review on a specific version
+  const handleToggleContentReview = async (itemId: number, ver: IntegrityVersion) => {
+    const setTo = ver.content_reviewed_at === null
+    await withPending(`${itemId}-${ver.item_version}-content`, async () => {
+      await setIntegrityReviewed(itemId, ver.item_version, null, null, null, setTo)
+      await Promise.all([refreshItemsList(), refreshExpanded(itemId)])
+    })
+  }
+
   // Toggle val review on a specific version
   const handleToggleValReview = async (itemId: number, ver: IntegrityVersion) => {
     const setTo = ver.val_reviewed_at === null
@@ -418,6 +444,8 @@ export function IntegrityPage() {
           item_ids: Array.from(selectedItems),
           set_val: flags.set_val,
           set_hash: flags.set_hash,
+          set_manifest: flags.set_manifest,
+          set_content: flags.set_content,
         })
       } else {
         const filter = buildFilter()
@@ -426,6 +454,8 @@ export function IntegrityPage() {
           filter,
           set_val: flags.set_val,
           set_hash: flags.set_hash,
+          set_manifest: flags.set_manifest,
+          set_content: flags.set_content,
         })
       }
       setSelectedItems(new Set())
@@ -460,6 +490,8 @@ export function IntegrityPage() {
           <SelectItem value="all">All issue types</SelectItem>
           <SelectItem value="hash">Suspicious hashes</SelectItem>
           <SelectItem value="val">Validation errors</SelectItem>
+          <SelectItem value="manifest">Manifest mismatches</SelectItem>
+          <SelectItem value="content">Content mismatches</SelectItem>
         </SelectContent>
       </Select>
 
@@ -581,6 +613,8 @@ export function IntegrityPage() {
                   <TableHead className="uppercase text-xs tracking-wide">File</TableHead>
                   <TableHead className="w-[110px] uppercase text-xs tracking-wide">Hashes</TableHead>
                   <TableHead className="w-[110px] uppercase text-xs tracking-wide">Validation</TableHead>
+                  <TableHead className="w-[110px] uppercase text-xs tracking-wide">Manifest</TableHead>
+                  <TableHead className="w-[110px] uppercase text-xs tracking-wide">Content</TableHead>
                   <TableHead className="w-[100px]" />
                 </TableRow>
               </TableHeader>
@@ -589,7 +623,8 @@ export function IntegrityPage() {
                   const versionData = expandedData.get(item.item_id)
                   const isExpanded = versionData !== undefined
                   const validateInFlight = pendingOps.has(String(item.item_id))
-                  const hasUnreviewed = item.hash_unreviewed + item.val_unreviewed > 0
+                  const hasUnreviewed =
+                    item.hash_unreviewed + item.val_unreviewed + item.manifest_unreviewed + item.content_unreviewed > 0
 
                   return (
                     <Fragment key={item.item_id}>
@@ -656,6 +691,12 @@ export function IntegrityPage() {
                         <TableCell className="text-xs">
                           <CountPair unreviewed={item.val_unreviewed} reviewed={item.val_reviewed} />
                         </TableCell>
+                        <TableCell className="text-xs">
+                          <CountPair unreviewed={item.manifest_unreviewed} reviewed={item.manifest_reviewed} />
+                        </TableCell>
+                        <TableCell className="text-xs">
+                          <CountPair unreviewed={item.content_unreviewed} reviewed={item.content_reviewed} />
+                        </TableCell>
                         <TableCell className="px-2" onClick={(e) => e.stopPropagation()}>
                           <Button
                             variant="default"
@@ -673,7 +714,7 @@ export function IntegrityPage() {
                       {/* Expanded version rows */}
                       {isExpanded && (
                         <TableRow key={`${item.item_id}-detail`} className="hover:bg-transparent">
-                          <TableCell colSpan={7} className="p-0 pl-10 pr-4 py-3">
+                          <TableCell colSpan={9} className="p-0 pl-10 pr-4 py-3">
                             <div className="border border-border rounded-lg overflow-hidden text-xs">
                               <Table className="table-fixed">
                                 <TableHeader className="bg-muted">
@@ -683,16 +724,22 @@ export function IntegrityPage() {
                                     <TableHead className="w-[90px] text-xs py-1.5 h-auto">When</TableHead>
                                     <TableHead className="w-[220px] text-xs py-1.5 h-auto">Hashes</TableHead>
                                     <TableHead className="text-xs py-1.5 h-auto">Validation</TableHead>
+                                    <TableHead className="w-[200px] text-xs py-1.5 h-auto">Manifest</TableHead>
+                                    <TableHead className="w-[180px] text-xs py-1.5 h-auto">Content</TableHead>
                                   </TableRow>
                                 </TableHeader>
                                 <TableBody>
                                   {versionData.versions.map((ver) => {
                                     const hashInFlight = pendingOps.has(`${item.item_id}-${ver.item_version}-hash`)
                                     const valInFlight = pendingOps.has(`${item.item_id}-${ver.item_version}-val`)
+                                    const manifestInFlight = pendingOps.has(`${item.item_id}-${ver.item_version}-manifest`)
+                                    const contentInFlight = pendingOps.has(`${item.item_id}-${ver.item_version}-content`)
                                     const hasSuspicious = ver.hash_suspicious_count > 0
                                     const hasValError = ver.val_state === 2
                                     const hashReviewed = ver.hash_reviewed_at !== null
                                     const valReviewed = ver.val_reviewed_at !== null
+                                    const manifestReviewed = ver.manifest_reviewed_at !== null
+                                    const contentReviewed = ver.content_reviewed_at !== null
 
                                     // Hash cell: suspicious > 0 → show count + toggle
                                     //            hash_version_count > 0 but no suspicious → "Baseline"
@@ -762,6 +809,85 @@ export function IntegrityPage() {
                                       valContent = <span className="text-muted-foreground">Not validated</span>
                                     }
 
+                                    // Manifest cell: manifest_state === 2 → manifest name + toggle
+                                    //                manifest_state === 1 → "Matches"
+                                    //                null → "-" (no manifest lists the file)
+                                    const manifestName = ver.manifest_path?.split(/[\\/]/).pop()
+                                    let manifestContent: React.ReactNode
+                                    if (ver.manifest_state === 2) {
+                                      manifestContent = (
+                                        <span className="inline-flex items-center gap-1.5 max-w-full">
+                                          <HoverCard openDelay={300}>
+                                            <HoverCardTrigger asChild>
+                                              <span>
+                                                <ReviewToggle
+                                                  size="sm"
+                                                  reviewed={manifestReviewed}
+                                                  onToggle={() => handleToggleManifestReview(item.item_id, ver)}
+                                                  disabled={manifestInFlight}
+                                                />
+                                              </span>
+                                            </HoverCardTrigger>
+                                            <HoverCardContent side="bottom" className="w-56 text-xs">
+                                              {manifestReviewed
+                                                ? <p>Mark this manifest mismatch as <span className="font-semibold">unreviewed</span></p>
+                                                : <p>Mark this manifest mismatch as <span className="font-semibold">reviewed</span></p>
+                                              }
+                                            </HoverCardContent>
+                                          </HoverCard>
+                                          <span
+                                            className="truncate"
+                                            title={`${ver.manifest_path} expects ${ver.manifest_algo} ${ver.manifest_hash}`}
+                                          >
+                                            Differs from {manifestName}
+                                          </span>
+                                        </span>
+                                      )
+                                    } else if (ver.manifest_state === 1) {
+                                      manifestContent = <span className="text-muted-foreground truncate" title={ver.manifest_path!}>Matches {manifestName}</span>
+                                    } else {
+                                      manifestContent = <span className="text-muted-foreground">-</span>
+                                    }
+
+                                    // Content cell: content_mismatch → detected type + toggle
+                                    //               content_type set → detected type
+                                    //               null → "-" (contents not examined)
+                                    let contentContent: React.ReactNode
+                                    if (ver.content_mismatch) {
+                                      contentContent = (
+                                        <span className="inline-flex items-center gap-1.5 max-w-full">
+                                          <HoverCard openDelay={300}>
+                                            <HoverCardTrigger asChild>
+                                              <span>
+                                                <ReviewToggle
+                                                  size="sm"
+                                                  reviewed={contentReviewed}
+                                                  onToggle={() => handleToggleContentReview(item.item_id, ver)}
+                                                  disabled={contentInFlight}
+                                                />
+                                              </span>
+                                            </HoverCardTrigger>
+                                            <HoverCardContent side="bottom" className="w-56 text-xs">
+                                              {contentReviewed
+                                                ? <p>Mark this content mismatch as <span className="font-semibold">unreviewed</span></p>
+                                                : <p>Mark this content mismatch as <span className="font-semibold">reviewed</span></p>
+                                              }
+                                            </HoverCardContent>
+                                          </HoverCard>
+                                          <span
+                                            className="truncate"
+                                            title={`Contents are ${ver.content_type}, not .${item.file_extension}`}
+                                          >
+                                            Contents are {ver.content_type}
+                                          </span>
+                                        </span>
+                                      )
+                                    } else if (ver.content_type !== null) {
+                                      contentContent = <span className="text-muted-foreground truncate">{ver.content_type}</span>
+                                    } else {
+                                      contentContent = <span className="text-muted-foreground">-</span>
+                                    }
+
                                     return (
                                       <TableRow key={ver.item_version}>
                                         <TableCell className="py-1.5 text-muted-foreground text-xs">v{ver.item_version}</TableCell>
@@ -769,12 +895,14 @@ export function IntegrityPage() {
                                         <TableCell className="py-1.5 text-muted-foreground text-xs whitespace-nowrap">{formatTimeAgo(ver.scan_started_at)}</TableCell>
                                         <TableCell className="py-1.5 text-xs">{hashContent}</TableCell>
                                         <TableCell className="py-1.5 text-xs">{valContent}</TableCell>
+                                        <TableCell className="py-1.5 text-xs">{manifestContent}</TableCell>
+                                        <TableCell className="py-1.5 text-xs">{contentContent}</TableCell>
                                       </TableRow>
                                     )
                                   })}
                                   {versionData.total > versionData.versions.length && (
                                     <TableRow>
-                                      <TableCell colSpan={5} className="py-1.5 text-muted-foreground text-xs">
+                                      <TableCell colSpan={7} className="py-1.5 text-muted-foreground text-xs">
                                         Showing {versionData.versions.length} of {versionData.total} versions
                                       </TableCell>
                                     </TableRow>
@@ -841,7 +969,9 @@ export function IntegrityPage() {
                   {`This will mark ${
                     issueType === 'hash' ? 'suspicious hashes' :
                     issueType === 'val' ? 'validation errors' :
-                    'suspicious hashes and validation errors'
+                    issueType === 'manifest' ? 'manifest mismatches' :
+                    issueType === 'content' ? 'content mismatches' :
+                    'suspicious hashes, validation errors, manifest mismatches and content mismatches'
                   } as "reviewed" on ${
                     bulkReviewMode === 'selected'
                       ? `the ${formatCount(selectedItems.size)} selected item${selectedItems.size === 1 ? '' : 's'}`
@@ -11,6 +11,20 @@ import { Button } from '@/components/ui/button'
 import { Input } from '@/components/ui/input'
 import { RootPicker } from '@/components/shared/RootPicker'
 import { ScanOptionsFields } from '@/components/shared/ScanOptionsFields'
+import { RunWindowFields } from './RunWindowFields'
+import {
+  EMPTY_REVERIFY,
+  reverifyFromFields,
+  supportsReverify,
+  type ReverifyFields,
+} from '@/lib/reverifyUtils'
+import { EMPTY_READ_LIMITS, readLimitsFromFields, type ReadLimitFields } from '@/lib/readLimitUtils'
+import {
+  EMPTY_RUN_WINDOW,
+  runWindowError,
+  runWindowFromFields,
+  type RunWindowInput,
+} from '@/lib/runWindowUtils'
 import { fetchQuery } from '@/lib/api'
 import { Loader2 } from 'lucide-react'
 import type { ScheduleType, IntervalUnit } from '@/lib/types'
@@ -46,6 +60,10 @@ export function CreateScheduleDialog({
   const [intervalUnit, setIntervalUnit] = useState<IntervalUnit>('Hours')
   const [hashMode, setHashMode] = useState<string>('New or Changed')
   const [isVal, setIsVal] = useState<boolean>(true)
+  const [subpath, setSubpath] = useState<string>('')
+  const [reverify, setReverify] = useState<ReverifyFields>(EMPTY_REVERIFY)
+  const [readLimits, setReadLimits] = useState<ReadLimitFields>(EMPTY_READ_LIMITS)
+  const [runWindow, setRunWindow] = useState<RunWindowInput>(EMPTY_RUN_WINDOW)
 
   const [loading, setLoading] = useState(true)
   const [submitting, setSubmitting] = useState(false)
@@ -64,6 +82,10 @@ export function CreateScheduleDialog({
       setIntervalUnit('Hours')
       setHashMode('New or Changed')
       setIsVal(true)
+      setSubpath('')
+      setReverify(EMPTY_REVERIFY)
+      setReadLimits(EMPTY_READ_LIMITS)
+      setRunWindow(EMPTY_RUN_WINDOW)
       setError(null)
 
       // Set preselected root if provided
@@ -139,6 +161,12 @@ export function CreateScheduleDialog({
       return
     }
 
+    const windowError = runWindowError(runWindow)
+    if (windowError) {
+      setError(windowError)
+      return
+    }
+
     try {
       setSubmitting(true)
       setError(null)
@@ -146,7 +174,7 @@ export function CreateScheduleDialog({
       // Map UI hash mode to API value
       const mapHashMode = (mode: string): string => {
         if (mode === 'New or Changed') return 'New'
-        return mode // 'All' and 'None' map directly
+        return mode // 'All', 'Sampled' and 'None' map directly
       }
 
       // Build request body based on schedule type
@@ -156,6 +184,10 @@ export function CreateScheduleDialog({
         schedule_type: scheduleType,
         hash_mode: mapHashMode(hashMode),
         is_val: isVal,
+        subpath: subpath.trim() || undefined,
+        reverify: supportsReverify(hashMode) ? reverifyFromFields(reverify) : undefined,
+        read_limits: readLimitsFromFields(readLimits),
+        run_window: runWindowFromFields(runWindow),
       }
 
       // Add schedule-type-specific fields
@@ -346,12 +378,21 @@ export function CreateScheduleDialog({
               </div>
             )}
 
+            {/* Run Window */}
+            <RunWindowFields runWindow={runWindow} onRunWindowChange={setRunWindow} />
+
             {/* Scan Options */}
             <ScanOptionsFields
               hashMode={hashMode}
               isVal={isVal}
+              subpath={subpath}
+              reverify={reverify}
+              readLimits={readLimits}
               onHashModeChange={setHashMode}
               onIsValChange={setIsVal}
+              onSubpathChange={setSubpath}
+              onReverifyChange={setReverify}
+              onReadLimitsChange={setReadLimits}
             />
 
             {/* Error Display */}
@@ -10,6 +10,27 @@ import {
 import { Button } from '@/components/ui/button'
 import { Input } from '@/components/ui/input'
 import { ScanOptionsFields } from '@/components/shared/ScanOptionsFields'
+import { RunWindowFields } from './RunWindowFields'
+import {
+  EMPTY_REVERIFY,
+  reverifyFromFields,
+  reverifyToFields,
+  supportsReverify,
+  type ReverifyFields,
+} from '@/lib/reverifyUtils'
+import {
+  EMPTY_READ_LIMITS,
+  readLimitsFromFields,
+  readLimitsToFields,
+  type ReadLimitFields,
+} from '@/lib/readLimitUtils'
+import {
+  EMPTY_RUN_WINDOW,
+  runWindowError,
+  runWindowFromFields,
+  runWindowToFields,
+  type RunWindowInput,
+} from '@/lib/runWindowUtils'
 import { Loader2 } from 'lucide-react'
 import type { ScheduleWithRoot, ScheduleType, IntervalUnit } from '@/lib/types'
 
@@ -37,6 +58,10 @@ export function EditScheduleDialog({
   const [intervalUnit, setIntervalUnit] = useState<IntervalUnit>('Hours')
   const [hashMode, setHashMode] = useState<string>('All')
   const [isVal, setIsVal] = useState<boolean>(true)
+  const [subpath, setSubpath] = useState<string>('')
+  const [reverify, setReverify] = useState<ReverifyFields>(EMPTY_REVERIFY)
+  const [readLimits, setReadLimits] = useState<ReadLimitFields>(EMPTY_READ_LIMITS)
+  const [runWindow, setRunWindow] = useState<RunWindowInput>(EMPTY_RUN_WINDOW)
 
   const [submitting, setSubmitting] = useState(false)
   const [error, setError] = useState<string | null>(null)
@@ -64,11 +89,15 @@ export function EditScheduleDialog({
       // Map backend hash mode to UI mode
       const mapHashModeToUI = (mode: string): string => {
         if (mode === 'New') return 'New or Changed'
-        return mode // 'All' and 'None' map directly
+        return mode // 'All', 'Sampled' and 'None' map directly
       }
 
       setHashMode(mapHashModeToUI(schedule.hash_mode))
       setIsVal(schedule.is_val)
+      setSubpath(schedule.subpath ?? '')
+      setReverify(reverifyToFields(schedule.reverify))
+      setReadLimits(readLimitsToFields(schedule.read_limits))
+      setRunWindow(runWindowToFields(schedule.run_window))
       setError(null)
     }
   }, [open, schedule])
@@ -104,6 +133,12 @@ export function EditScheduleDialog({
       return
     }
 
+    const windowError = runWindowError(runWindow)
+    if (windowError) {
+      setError(windowError)
+      return
+    }
+
     try {
       setSubmitting(true)
       setError(null)
@@ -111,7 +146,7 @@ export function EditScheduleDialog({
       // Map UI hash mode to API value
       const mapHashMode = (mode: string): string => {
         if (mode === 'New or Changed') return 'New'
-        return mode // 'All' and 'None' map directly
+        return mode // 'All', 'Sampled' and 'None' map directly
       }
 
       // Build request body based on schedule type
@@ -120,6 +155,10 @@ export function EditScheduleDialog({
         schedule_type: scheduleType,
         hash_mode: mapHashMode(hashMode),
         is_val: isVal,
+        subpath: subpath.trim() || undefined,
+        reverify: supportsReverify(hashMode) ? reverifyFromFields(reverify) : undefined,
+        read_limits: readLimitsFromFields(readLimits),
+        run_window: runWindowFromFields(runWindow),
       }
 
       // Add schedule-type-specific fields
@@ -286,12 +325,21 @@ export function EditScheduleDialog({
             </div>
           )}
 
+          {/* Run Window */}
+          <RunWindowFields runWindow={runWindow} onRunWindowChange={setRunWindow} />
+
           {/* Scan Options */}
           <ScanOptionsFields
             hashMode={hashMode}
             isVal={isVal}
+            subpath={subpath}
+            reverify={reverify}
+            readLimits={readLimits}
             onHashModeChange={setHashMode}
             onIsValChange={setIsVal}
+            onSubpathChange={setSubpath}
+            onReverifyChange={setReverify}
+            onReadLimitsChange={setReadLimits}
           />
 
           {/* Error Display */}
@@ -40,6 +40,9 @@ export function RunScheduleDialog({
           root_id: schedule.root_id,
           hash_mode: schedule.hash_mode,
           is_val: schedule.is_val,
+          subpath: schedule.subpath ?? undefined,
+          reverify: schedule.reverify ?? undefined,
+          read_limits: schedule.read_limits ?? undefined,
         }),
       })
 
@@ -89,6 +92,7 @@ export function RunScheduleDialog({
                 <div className="text-sm font-medium mb-0.5">Options</div>
                 <div className="text-sm text-muted-foreground">
                   Hash: {schedule.hash_mode} &middot; Validate: {schedule.is_val ? 'Yes' : 'No'}
+                  {schedule.subpath && <> &middot; Folder: <span className="font-mono">{schedule.subpath}</span></>}
                 </div>
               </div>
             </div>
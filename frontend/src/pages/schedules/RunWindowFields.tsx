@@ -0,0 +1,39 @@
+/**
+ * Run window fields for the schedule create and edit dialogs
+ */
+
+import { Input } from '@/components/ui/input'
+import type { RunWindowInput } from '@/lib/runWindowUtils'
+
+interface RunWindowFieldsProps {
+  runWindow: RunWindowInput
+  onRunWindowChange: (runWindow: RunWindowInput) => void
+}
+
+export function RunWindowFields({ runWindow, onRunWindowChange }: RunWindowFieldsProps) {
+  return (
+    <div className="space-y-2">
+      <label className="text-sm font-semibold">Run Window</label>
+      <p className="text-xs text-muted-foreground">
+        Only run this schedule's scans between these local times. A scan still running when the
+        window closes is suspended and resumes when it next opens. Leave empty to use the global
+        setting.
+      </p>
+      <div className="flex gap-2 items-center">
+        <Input
+          type="time"
+          value={runWindow.start}
+          onChange={(e) => onRunWindowChange({ ...runWindow, start: e.target.value })}
+          className="w-40"
+        />
+        <span className="text-sm text-muted-foreground">to</span>
+        <Input
+          type="time"
+          value={runWindow.end}
+          onChange={(e) => onRunWindowChange({ ...runWindow, end: e.target.value })}
+          className="w-40"
+        />
+      </div>
+    </div>
+  )
+}
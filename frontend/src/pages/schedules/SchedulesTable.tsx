@@ -95,6 +95,13 @@ export const SchedulesTable = forwardRef<SchedulesTableRef, SchedulesTableProps>
 
     // Format schedule description
     const formatScheduleDescription = (schedule: ScheduleWithRoot): string => {
+      const description = formatScheduleTiming(schedule)
+      return schedule.run_window
+        ? `${description}, runs ${schedule.run_window.start}–${schedule.run_window.end}`
+        : description
+    }
+
+    const formatScheduleTiming = (schedule: ScheduleWithRoot): string => {
       switch (schedule.schedule_type) {
         case 'Daily':
           return `Daily at ${schedule.time_of_day}`
@@ -278,6 +285,9 @@ export const SchedulesTable = forwardRef<SchedulesTableRef, SchedulesTableProps>
                             const parts: string[] = []
                             if (schedule.hash_mode === 'All') parts.push('Hash All')
                             else if (schedule.hash_mode === 'New') parts.push('Hash Changed')
+                            else if (schedule.hash_mode === 'Sampled') parts.push('Hash Sampled')
+                            if (schedule.reverify) parts.push('Re-verify')
+                            if (schedule.read_limits) parts.push('Read Limit')
                             if (schedule.is_val) parts.push('Validate')
                             return parts.length > 0 ? parts.join(', ') : '-'
                           })()}
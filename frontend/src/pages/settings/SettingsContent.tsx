@@ -33,6 +33,11 @@ interface ConfigSetting<T> {
 
 interface SettingsResponse {
   analysis_threads: ConfigSetting<number>
+  analysis_sample_blocks: ConfigSetting<number>
+  analysis_read_limit_mb_per_sec: ConfigSetting<number>
+  analysis_read_limit_iops: ConfigSetting<number>
+  scan_threads: ConfigSetting<number>
+  tasks_run_window: ConfigSetting<string>
   logging_fspulse: ConfigSetting<string>
   logging_lopdf: ConfigSetting<string>
   server_host: ConfigSetting<string>
@@ -40,8 +45,28 @@ interface SettingsResponse {
   database_dir: ConfigSetting<string>
   mcp_enabled: ConfigSetting<boolean>
   validation_images: ConfigSetting<boolean>
+  validation_video: ConfigSetting<boolean>
   validation_pdf: ConfigSetting<boolean>
   validation_audio: ConfigSetting<boolean>
+  validation_archives: ConfigSetting<boolean>
+  validation_prefer_content: ConfigSetting<boolean>
+  validators: ValidatorInfo[]
+}
+
+interface ValidatorInfo {
+  name: string
+  version: number
+  extensions: string[]
+  config_key: string  // key in the [validation] config section
+}
+
+// Upper-cased extensions validated under a [validation] key, e.g. "JPG, PNG"
+function validatedExtensions(validators: ValidatorInfo[], configKey: string): string {
+  return validators
+    .filter((v) => v.config_key === configKey)
+    .flatMap((v) => v.extensions)
+    .map((ext) => ext.toUpperCase())
+    .join(', ')
 }
 
 export function SettingsContent() {
@@ -194,6 +219,36 @@ export function SettingsContent() {
           return
         }
         requestBody = { analysis_threads: threads }
+      } else if (editingSetting === 'analysis_sample_blocks') {
+        const blocks = parseInt(editValue, 10)
+        if (isNaN(blocks) || blocks < 0 || blocks > 64) {
+          setSaveMessage('Error: Sample blocks must be a number between 0 and 64')
+          return
+        }
+        requestBody = { analysis_sample_blocks: blocks }
+      } else if (editingSetting === 'analysis_read_limit_mb_per_sec') {
+        const limit = parseInt(editValue, 10)
+        if (isNaN(limit) || limit < 0 || limit > 100000) {
+          setSaveMessage('Error: Read limit must be a number between 0 and 100000 MB/s')
+          return
+        }
+        requestBody = { analysis_read_limit_mb_per_sec: limit }
+      } else if (editingSetting === 'analysis_read_limit_iops') {
+        const limit = parseInt(editValue, 10)
+        if (isNaN(limit) || limit < 0 || limit > 1000000) {
+          setSaveMessage('Error: Read limit must be a number between 0 and 1000000 reads/s')
+          return
+        }
+        requestBody = { analysis_read_limit_iops: limit }
+      } else if (editingSetting === 'scan_threads') {
+        const threads = parseInt(editValue, 10)
+        if (isNaN(threads) || threads < 1 || threads > 24) {
+          setSaveMessage('Error: Threads must be a number between 1 and 24')
+          return
+        }
+        requestBody = { scan_threads: threads }
+      } else if (editingSetting === 'tasks_run_window') {
+        requestBody = { tasks_run_window: editValue.trim() }
       } else if (editingSetting === 'server_host') {
         requestBody = { server_host: editValue }
       } else if (editingSetting === 'server_port') {
@@ -213,10 +268,16 @@ export function SettingsContent() {
         requestBody = { mcp_enabled: editValue === 'true' }
       } else if (editingSetting === 'validation_images') {
         requestBody = { validation_images: editValue === 'true' }
+      } else if (editingSetting === 'validation_video') {
+        requestBody = { validation_video: editValue === 'true' }
       } else if (editingSetting === 'validation_pdf') {
         requestBody = { validation_pdf: editValue === 'true' }
       } else if (editingSetting === 'validation_audio') {
         requestBody = { validation_audio: editValue === 'true' }
+      } else if (editingSetting === 'validation_archives') {
+        requestBody = { validation_archives: editValue === 'true' }
+      } else if (editingSetting === 'validation_prefer_content') {
+        requestBody = { validation_prefer_content: editValue === 'true' }
       }
 
       const response = await fetch('/api/settings', {
@@ -493,6 +554,41 @@ export function SettingsContent() {
                       defaultValue={8}
                       settingKey="analysis_threads"
                     />
+                    <SettingRow
+                      name="Sample Blocks"
+                      description="Blocks hashed between the head and tail of each file in Sampled scans"
+                      setting={settings.analysis_sample_blocks}
+                      defaultValue={8}
+                      settingKey="analysis_sample_blocks"
+                    />
+                    <SettingRow
+                      name="Read Limit (MB/s)"
+                      description="Maximum megabytes per second read by analysis, across all threads (0 = unlimited)"
+                      setting={settings.analysis_read_limit_mb_per_sec}
+                      defaultValue={0}
+                      settingKey="analysis_read_limit_mb_per_sec"
+                    />
+                    <SettingRow
+                      name="Read Limit (reads/s)"
+                      description="Maximum reads per second issued by analysis, across all threads (0 = unlimited)"
+                      setting={settings.analysis_read_limit_iops}
+                      defaultValue={0}
+                      settingKey="analysis_read_limit_iops"
+                    />
+                    <SettingRow
+                      name="Scan Threads"
+                      description="Number of directory walker threads for the scanning phase"
+                      setting={settings.scan_threads}
+                      defaultValue={4}
+                      settingKey="scan_threads"
+                    />
+                    <SettingRow
+                      name="Run Window"
+                      description="Local time range (HH:MM-HH:MM) scheduled and watch tasks may run in (empty = any time)"
+                      setting={settings.tasks_run_window}
+                      defaultValue=""
+                      settingKey="tasks_run_window"
+                    />
                     <SettingRow
                       name="fsPulse Log Level"
                       description="Logging verbosity for fsPulse"
@@ -547,25 +643,46 @@ export function SettingsContent() {
                     </tr>
                     <SettingRow
                       name="Validate Images"
-                      description="Validate JPG, JPEG, PNG, GIF, TIFF, BMP files"
+                      description={`Validate ${validatedExtensions(settings.validators, 'images')} files`}
                       setting={settings.validation_images}
                       defaultValue={true}
                       settingKey="validation_images"
                     />
+                    <SettingRow
+                      name="Validate Video Files"
+                      description={`Validate ${validatedExtensions(settings.validators, 'video')} files`}
+                      setting={settings.validation_video}
+                      defaultValue={true}
+                      settingKey="validation_video"
+                    />
                     <SettingRow
                       name="Validate PDF Documents (Experimental)"
-                      description="Validate PDF files"
+                      description={`Validate ${validatedExtensions(settings.validators, 'pdf')} files`}
                       setting={settings.validation_pdf}
                       defaultValue={false}
                       settingKey="validation_pdf"
                     />
                     <SettingRow
                       name="Validate Audio Files"
-                      description="Validate FLAC files"
+                      description={`Validate ${validatedExtensions(settings.validators, 'audio')} files`}
                       setting={settings.validation_audio}
                       defaultValue={true}
                       settingKey="validation_audio"
                     />
+                    <SettingRow
+                      name="Validate Archives"
+                      description={`Validate ${validatedExtensions(settings.validators, 'archives')} files`}
+                      setting={settings.validation_archives}
+                      defaultValue={true}
+                      settingKey="validation_archives"
+                    />
+                    <SettingRow
+                      name="Prefer Detected Content"
+                      description="Choose each file's validator by its magic bytes rather than its extension"
+                      setting={settings.validation_prefer_content}
+                      defaultValue={false}
+                      settingKey="validation_prefer_content"
+                    />
                   </tbody>
                 </table>
               </div>
@@ -598,6 +715,49 @@ export function SettingsContent() {
                     min: 1,
                     max: 24,
                   },
+                  'analysis_sample_blocks': {
+                    title: 'Sample Blocks',
+                    description: 'Blocks hashed between the head and tail of each file in Sampled scans',
+                    setting: settings.analysis_sample_blocks,
+                    defaultValue: 8,
+                    inputType: 'number',
+                    min: 0,
+                    max: 64,
+                  },
+                  'analysis_read_limit_mb_per_sec': {
+                    title: 'Read Limit (MB/s)',
+                    description: 'Maximum megabytes per second read by analysis, across all threads (0 = unlimited). Applies to a running scan immediately.',
+                    setting: settings.analysis_read_limit_mb_per_sec,
+                    defaultValue: 0,
+                    inputType: 'number',
+                    min: 0,
+                    max: 100000,
+                  },
+                  'analysis_read_limit_iops': {
+                    title: 'Read Limit (reads/s)',
+                    description: 'Maximum reads per second issued by analysis, across all threads (0 = unlimited). Applies to a running scan immediately.',
+                    setting: settings.analysis_read_limit_iops,
+                    defaultValue: 0,
+                    inputType: 'number',
+                    min: 0,
+                    max: 1000000,
+                  },
+                  'scan_threads': {
+                    title: 'Scan Threads',
+                    description: 'Number of directory walker threads for the scanning phase',
+                    setting: settings.scan_threads,
+                    defaultValue: 4,
+                    inputType: 'number',
+                    min: 1,
+                    max: 24,
+                  },
+                  'tasks_run_window': {
+                    title: 'Run Window',
+                    description: 'Local time range (HH:MM-HH:MM) scheduled and watch tasks may run in, such as 22:00-06:00 (empty = any time). Schedules can set their own window. A task still running when the window closes is suspended until it reopens.',
+                    setting: settings.tasks_run_window,
+                    defaultValue: '',
+                    inputType: 'text',
+                  },
                   'logging_fspulse': {
                     title: 'fsPulse Log Level',
                     description: 'Logging verbosity for fsPulse',
@@ -647,15 +807,23 @@ export function SettingsContent() {
                   },
                   'validation_images': {
                     title: 'Validate Images',
-                    description: 'Enable or disable structural validation of image files (JPG, JPEG, PNG, GIF, TIFF, BMP). Validation is experimental and may produce false positives.',
+                    description: `Enable or disable structural validation of image files (${validatedExtensions(settings.validators, 'images')}). Validation is experimental and may produce false positives.`,
                     setting: settings.validation_images,
                     defaultValue: true,
                     inputType: 'select',
                     options: ['true', 'false'],
                   },
+                  'validation_video': {
+                    title: 'Validate Video Files',
+                    description: `Enable or disable checking the container structure of video files (${validatedExtensions(settings.validators, 'video')}). Video is not decoded, so damage inside the video stream itself is not detected.`,
+                    setting: settings.validation_video,
+                    defaultValue: true,
+                    inputType: 'select',
+                    options: ['true', 'false'],
+                  },
                   'validation_pdf': {
                     title: 'Validate PDF Documents (Experimental)',
-                    description: 'Enable or disable structural validation of PDF files. This validator is experimental and may produce false positives.',
+                    description: `Enable or disable structural validation of PDF files (${validatedExtensions(settings.validators, 'pdf')}). This validator is experimental and may produce false positives.`,
                     setting: settings.validation_pdf,
                     defaultValue: false,
                     inputType: 'select',
@@ -663,12 +831,28 @@ export function SettingsContent() {
                   },
                   'validation_audio': {
                     title: 'Validate Audio Files',
-                    description: 'Enable or disable structural validation of audio files (FLAC). Validation is experimental and may produce false positives.',
+                    description: `Enable or disable structural validation of audio files (${validatedExtensions(settings.validators, 'audio')}). Validation is experimental and may produce false positives.`,
                     setting: settings.validation_audio,
                     defaultValue: true,
                     inputType: 'select',
                     options: ['true', 'false'],
                   },
+                  'validation_archives': {
+                    title: 'Validate Archives',
+                    description: `Enable or disable checking archives and compressed files (${validatedExtensions(settings.validators, 'archives')}). Every member is decompressed and checked against its CRC, without extracting anything to disk.`,
+                    setting: settings.validation_archives,
+                    defaultValue: true,
+                    inputType: 'select',
+                    options: ['true', 'false'],
+                  },
+                  'validation_prefer_content': {
+                    title: 'Prefer Detected Content',
+                    description: 'Validating scans detect each file\'s type from its first few KB. When false, a file is validated by the validator for its extension, or for its detected type if the extension has none. When true, the detected type comes first and the extension is the fallback.',
+                    setting: settings.validation_prefer_content,
+                    defaultValue: false,
+                    inputType: 'select',
+                    options: ['true', 'false'],
+                  },
                 }[editingSetting]
 
                 if (!settingInfo) return null
@@ -2,6 +2,7 @@ pub mod routes;
 
 // Re-export route handlers for convenience
 pub use routes::app;
+pub use routes::comparisons;
 pub use routes::query;
 pub use routes::integrity;
 pub use routes::scans;
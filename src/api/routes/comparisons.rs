@@ -0,0 +1,138 @@
+use axum::{extract::Path, extract::Query, http::StatusCode, Json};
+use log::error;
+use serde::{Deserialize, Serialize};
+
+use crate::compare::{ComparisonDiff, DiffKind, RootComparison};
+use crate::db::Database;
+use crate::error::FsPulseError;
+
+fn internal_error(context: &str, e: FsPulseError) -> (StatusCode, String) {
+    error!("{}: {}", context, e);
+    (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed: {}", e))
+}
+
+// ---------------------------------------------------------------------------
+// GET /api/comparisons
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Deserialize)]
+pub struct ListParams {
+    /// Only reports with this root on either side
+    pub root_id: Option<i64>,
+    pub offset: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ComparisonListResponse {
+    pub comparisons: Vec<RootComparison>,
+    pub total: i64,
+}
+
+pub async fn list_comparisons(
+    Query(p): Query<ListParams>,
+) -> Result<Json<ComparisonListResponse>, (StatusCode, String)> {
+    let offset = p.offset.unwrap_or(0).max(0);
+    let limit = p.limit.unwrap_or(50).clamp(1, 200);
+
+    let conn = Database::get_connection().map_err(|e| internal_error("Database connection failed", e))?;
+    let total = RootComparison::count(&conn, p.root_id)
+        .map_err(|e| internal_error("Comparison count failed", e))?;
+    let comparisons = RootComparison::list(&conn, p.root_id, limit, offset)
+        .map_err(|e| internal_error("Comparison list failed", e))?;
+
+    Ok(Json(ComparisonListResponse { comparisons, total }))
+}
+
+// ---------------------------------------------------------------------------
+// GET /api/comparisons/:comparison_id
+// ---------------------------------------------------------------------------
+
+pub async fn get_comparison(
+    Path(comparison_id): Path<i64>,
+) -> Result<Json<RootComparison>, (StatusCode, String)> {
+    let conn = Database::get_connection().map_err(|e| internal_error("Database connection failed", e))?;
+    match RootComparison::get_by_id(&conn, comparison_id) {
+        Ok(Some(comparison)) => Ok(Json(comparison)),
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            format!("Comparison {} not found", comparison_id),
+        )),
+        Err(e) => Err(internal_error("Comparison lookup failed", e)),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// GET /api/comparisons/:comparison_id/diffs
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Deserialize)]
+pub struct DiffsParams {
+    /// left_only, right_only, size, hash or mod_date
+    pub diff_kind: Option<String>,
+    pub path_search: Option<String>,
+    pub offset: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DiffsResponse {
+    pub diffs: Vec<ComparisonDiff>,
+    pub total: i64,
+}
+
+pub async fn get_diffs(
+    Path(comparison_id): Path<i64>,
+    Query(p): Query<DiffsParams>,
+) -> Result<Json<DiffsResponse>, (StatusCode, String)> {
+    let diff_kind = match p.diff_kind.as_deref() {
+        None | Some("") => None,
+        Some(s) => Some(DiffKind::from_string(s).ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                format!(
+                    "Invalid diff_kind '{}': must be left_only, right_only, size, hash or mod_date",
+                    s
+                ),
+            )
+        })?),
+    };
+    let path_search = p.path_search.as_deref().filter(|s| !s.is_empty());
+    let offset = p.offset.unwrap_or(0).max(0);
+    let limit = p.limit.unwrap_or(50).clamp(1, 200);
+
+    let conn = Database::get_connection().map_err(|e| internal_error("Database connection failed", e))?;
+    if RootComparison::get_by_id(&conn, comparison_id)
+        .map_err(|e| internal_error("Comparison lookup failed", e))?
+        .is_none()
+    {
+        return Err((
+            StatusCode::NOT_FOUND,
+            format!("Comparison {} not found", comparison_id),
+        ));
+    }
+
+    let total = RootComparison::count_diffs(&conn, comparison_id, diff_kind, path_search)
+        .map_err(|e| internal_error("Comparison diff count failed", e))?;
+    let diffs = RootComparison::get_diffs(&conn, comparison_id, diff_kind, path_search, limit, offset)
+        .map_err(|e| internal_error("Comparison diff query failed", e))?;
+
+    Ok(Json(DiffsResponse { diffs, total }))
+}
+
+// ---------------------------------------------------------------------------
+// DELETE /api/comparisons/:comparison_id
+// ---------------------------------------------------------------------------
+
+pub async fn delete_comparison(
+    Path(comparison_id): Path<i64>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    match RootComparison::delete(comparison_id) {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err((
+            StatusCode::NOT_FOUND,
+            format!("Comparison {} not found", comparison_id),
+        )),
+        Err(e) => Err(internal_error("Comparison delete failed", e)),
+    }
+}
@@ -2,6 +2,7 @@ use axum::{extract::Query, extract::Path, http::StatusCode, Json};
 use log::error;
 use serde::{Deserialize, Serialize};
 
+use crate::hash::HashAlgo;
 use crate::integrity::integrity_api::{self, IntegrityFilter};
 
 // ---------------------------------------------------------------------------
@@ -93,6 +94,10 @@ pub struct ItemSummaryResponse {
     pub hash_reviewed: i64,
     pub val_unreviewed: i64,
     pub val_reviewed: i64,
+    pub manifest_unreviewed: i64,
+    pub manifest_reviewed: i64,
+    pub content_unreviewed: i64,
+    pub content_reviewed: i64,
     pub latest_scan_id: i64,
 }
 
@@ -117,6 +122,10 @@ pub async fn get_items(
                     hash_reviewed: i.hash_reviewed,
                     val_unreviewed: i.val_unreviewed,
                     val_reviewed: i.val_reviewed,
+                    manifest_unreviewed: i.manifest_unreviewed,
+                    manifest_reviewed: i.manifest_reviewed,
+                    content_unreviewed: i.content_unreviewed,
+                    content_reviewed: i.content_reviewed,
                     latest_scan_id: i.latest_scan_id,
                 })
                 .collect();
@@ -155,6 +164,14 @@ pub struct VersionResponse {
     pub val_error: Option<String>,
     pub val_reviewed_at: Option<i64>,
     pub hash_reviewed_at: Option<i64>,
+    pub manifest_state: Option<i64>,
+    pub manifest_path: Option<String>,
+    pub manifest_algo: Option<HashAlgo>,
+    pub manifest_hash: Option<String>,
+    pub manifest_reviewed_at: Option<i64>,
+    pub content_type: Option<String>,
+    pub content_mismatch: bool,
+    pub content_reviewed_at: Option<i64>,
 }
 
 #[derive(Debug, Serialize)]
@@ -185,6 +202,14 @@ pub async fn get_versions(
                     val_error: v.val_error,
                     val_reviewed_at: v.val_reviewed_at,
                     hash_reviewed_at: v.hash_reviewed_at,
+                    manifest_state: v.manifest_state,
+                    manifest_path: v.manifest_path,
+                    manifest_algo: v.manifest_algo,
+                    manifest_hash: v.manifest_hash,
+                    manifest_reviewed_at: v.manifest_reviewed_at,
+                    content_type: v.content_type,
+                    content_mismatch: v.content_mismatch,
+                    content_reviewed_at: v.content_reviewed_at,
                 })
                 .collect();
             Ok(Json(VersionsListResponse {
@@ -210,19 +235,32 @@ pub struct ReviewRequest {
     pub item_version: Option<i64>,
     pub set_val: Option<bool>,
     pub set_hash: Option<bool>,
+    pub set_manifest: Option<bool>,
+    pub set_content: Option<bool>,
 }
 
 pub async fn review(
     Json(req): Json<ReviewRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    if req.set_val.is_none() && req.set_hash.is_none() {
+    if req.set_val.is_none()
+        && req.set_hash.is_none()
+        && req.set_manifest.is_none()
+        && req.set_content.is_none()
+    {
         return Err((
             StatusCode::BAD_REQUEST,
-            "At least one of set_val or set_hash must be provided".to_string(),
+            "At least one of set_val, set_hash, set_manifest or set_content must be provided".to_string(),
         ));
     }
 
-    match integrity_api::set_reviewed(req.item_id, req.item_version, req.set_val, req.set_hash) {
+    match integrity_api::set_reviewed(
+        req.item_id,
+        req.item_version,
+        req.set_val,
+        req.set_hash,
+        req.set_manifest,
+        req.set_content,
+    ) {
         Ok(()) => Ok(Json(serde_json::json!({ "success": true }))),
         Err(e) => {
             error!("review failed for item {}: {}", req.item_id, e);
@@ -253,15 +291,21 @@ pub struct BulkReviewRequest {
     pub filter: Option<BulkReviewFilter>,
     pub set_val: Option<bool>,
     pub set_hash: Option<bool>,
+    pub set_manifest: Option<bool>,
+    pub set_content: Option<bool>,
 }
 
 pub async fn bulk_review(
     Json(req): Json<BulkReviewRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    if req.set_val.is_none() && req.set_hash.is_none() {
+    if req.set_val.is_none()
+        && req.set_hash.is_none()
+        && req.set_manifest.is_none()
+        && req.set_content.is_none()
+    {
         return Err((
             StatusCode::BAD_REQUEST,
-            "At least one of set_val or set_hash must be provided".to_string(),
+            "At least one of set_val, set_hash, set_manifest or set_content must be provided".to_string(),
         ));
     }
 
@@ -272,7 +316,13 @@ pub async fn bulk_review(
                 "item_ids must not be empty".to_string(),
             ));
         }
-        integrity_api::bulk_review_by_ids(&ids, req.set_val, req.set_hash)
+        integrity_api::bulk_review_by_ids(
+            &ids,
+            req.set_val,
+            req.set_hash,
+            req.set_manifest,
+            req.set_content,
+        )
     } else if let Some(f) = req.filter {
         let filter = parse_filter(
             f.root_id,
@@ -282,7 +332,13 @@ pub async fn bulk_review(
             f.path_search,
             f.show_deleted,
         );
-        integrity_api::bulk_review_by_filter(&filter, req.set_val, req.set_hash)
+        integrity_api::bulk_review_by_filter(
+            &filter,
+            req.set_val,
+            req.set_hash,
+            req.set_manifest,
+            req.set_content,
+        )
     } else {
         return Err((
             StatusCode::BAD_REQUEST,
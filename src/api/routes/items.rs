@@ -160,6 +160,7 @@ pub struct ItemResponse {
     pub first_scan_id: i64,
     pub is_added: bool,
     pub is_deleted: bool,
+    pub is_excluded: bool,
     pub mod_date: Option<i64>,
     pub size: Option<i64>,
     pub add_count: Option<i64>,
@@ -189,6 +190,7 @@ pub async fn get_immediate_children(
                     first_scan_id: item.first_scan_id,
                     is_added: item.is_added,
                     is_deleted: item.is_deleted,
+                    is_excluded: item.is_excluded,
                     mod_date: item.mod_date,
                     size: item.size,
                     add_count: item.add_count,
@@ -240,6 +242,7 @@ pub async fn search_items(
                     first_scan_id: item.first_scan_id,
                     is_added: item.is_added,
                     is_deleted: item.is_deleted,
+                    is_excluded: item.is_excluded,
                     mod_date: item.mod_date,
                     size: item.size,
                     add_count: item.add_count,
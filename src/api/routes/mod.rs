@@ -1,4 +1,5 @@
 pub mod app;
+pub mod comparisons;
 pub mod query;
 pub mod integrity;
 pub mod scans;
@@ -4,8 +4,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::error::FsPulseError;
 use crate::query::columns::{
-    ColMap, ColSpec, ColType, HASHES_QUERY_COLS, ITEMS_QUERY_COLS, ROOTS_QUERY_COLS,
-    SCANS_QUERY_COLS, VERSIONS_QUERY_COLS,
+    ColMap, ColSpec, ColType, DUPLICATES_QUERY_COLS, HASHES_QUERY_COLS, ITEMS_QUERY_COLS,
+    ROOTS_QUERY_COLS, SCANS_QUERY_COLS, VERSIONS_QUERY_COLS,
 };
 use crate::query::{ColAlign, QueryProcessor};
 
@@ -115,6 +115,7 @@ pub async fn get_metadata(
         "items" => &ITEMS_QUERY_COLS,
         "versions" => &VERSIONS_QUERY_COLS,
         "hashes" => &HASHES_QUERY_COLS,
+        "duplicates" => &DUPLICATES_QUERY_COLS,
         "scans" => &SCANS_QUERY_COLS,
         "roots" => &ROOTS_QUERY_COLS,
         _ => return Err(StatusCode::NOT_FOUND),
@@ -269,6 +270,7 @@ pub async fn validate_filter(
         "items" => &ITEMS_QUERY_COLS,
         "versions" => &VERSIONS_QUERY_COLS,
         "hashes" => &HASHES_QUERY_COLS,
+        "duplicates" => &DUPLICATES_QUERY_COLS,
         "scans" => &SCANS_QUERY_COLS,
         "roots" => &ROOTS_QUERY_COLS,
         _ => return Err(StatusCode::NOT_FOUND),
@@ -320,6 +322,7 @@ fn get_col_map(domain: &str) -> Option<&'static ColMap> {
         "items" => Some(&ITEMS_QUERY_COLS),
         "versions" => Some(&VERSIONS_QUERY_COLS),
         "hashes" => Some(&HASHES_QUERY_COLS),
+        "duplicates" => Some(&DUPLICATES_QUERY_COLS),
         "scans" => Some(&SCANS_QUERY_COLS),
         "roots" => Some(&ROOTS_QUERY_COLS),
         _ => None,
@@ -1,17 +1,27 @@
-use axum::{extract::Path, http::StatusCode, Json};
+use axum::{
+    extract::{Path, Query},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
 use log::error;
 use serde::{Deserialize, Serialize};
 
 use crate::db::Database;
 use crate::error::FsPulseError;
-use crate::roots::Root;
+use crate::integrity::manifest_export::{ExportSpec, ManifestExport};
+use crate::root_rules::RootRule;
+use crate::roots::{Root, RootOptions};
 use crate::scans::Scan;
 use crate::schedules;
+use crate::watcher::{RootWatchers, WatchStatus};
 
 /// Request structure for creating a new root
 #[derive(Debug, Deserialize)]
 pub struct CreateRootRequest {
     pub path: String,
+    #[serde(flatten)]
+    pub options: RootOptions,
 }
 
 /// Response structure for successful root creation
@@ -27,13 +37,22 @@ pub struct ErrorResponse {
     pub error: String,
 }
 
+/// Request structure for replacing a root's include/exclude rules
+#[derive(Debug, Deserialize)]
+pub struct UpdateRootRulesRequest {
+    pub rules: Vec<RootRule>,
+}
+
 /// Response structure for a root with its last scan information
 #[derive(Debug, Serialize)]
 pub struct RootWithScan {
     pub root_id: i64,
     pub root_path: String,
+    #[serde(flatten)]
+    pub options: RootOptions,
     pub last_scan: Option<ScanInfo>,
     pub schedule_count: i64, // Number of active schedules for this root
+    pub watch_status: Option<WatchStatus>, // None unless the root is being watched
 }
 
 /// Scan information subset for display
@@ -53,7 +72,7 @@ pub async fn create_root(
     Json(req): Json<CreateRootRequest>,
 ) -> Result<(StatusCode, Json<CreateRootResponse>), (StatusCode, Json<ErrorResponse>)> {
     // Attempt to create the root using Root::try_create
-    match Root::try_create(&req.path) {
+    match Root::try_create(&req.path, &req.options) {
         Ok(root) => {
             log::info!(
                 "Created new root: {} (id: {})",
@@ -163,7 +182,10 @@ pub async fn get_roots_with_scans(
 
     // Query all roots
     let mut stmt = conn
-        .prepare_cached("SELECT root_id, root_path FROM roots ORDER BY root_path COLLATE natural_path")
+        .prepare_cached(&format!(
+            "SELECT root_id, root_path, {} FROM roots ORDER BY root_path COLLATE natural_path",
+            RootOptions::COLUMNS
+        ))
         .map_err(|e| {
             error!("Failed to prepare query: {}", e);
             (
@@ -176,7 +198,11 @@ pub async fn get_roots_with_scans(
 
     let roots_iter = stmt
         .query_map([], |row| {
-            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                RootOptions::from_row(row, 2)?,
+            ))
         })
         .map_err(|e| {
             error!("Failed to execute query: {}", e);
@@ -191,7 +217,7 @@ pub async fn get_roots_with_scans(
     // Build response with scan information
     let mut results = Vec::new();
     for root_result in roots_iter {
-        let (root_id, root_path) = root_result.map_err(|e| {
+        let (root_id, root_path, options) = root_result.map_err(|e| {
             error!("Failed to read root row: {}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -227,8 +253,10 @@ pub async fn get_roots_with_scans(
         results.push(RootWithScan {
             root_id,
             root_path,
+            options,
             last_scan,
             schedule_count,
+            watch_status: RootWatchers::status(root_id),
         });
     }
 
@@ -248,6 +276,204 @@ pub async fn get_schedule_count(
     Ok(Json(serde_json::json!({ "count": count })))
 }
 
+/// GET /api/roots/{root_id}/rules
+/// Returns the include/exclude rules for a root in evaluation order
+pub async fn get_root_rules(
+    Path(root_id): Path<i64>,
+) -> Result<Json<Vec<RootRule>>, (StatusCode, Json<ErrorResponse>)> {
+    let result = Database::get_connection().and_then(|conn| {
+        match Root::get_by_id(&conn, root_id)? {
+            Some(_) => RootRule::list_for_root(&conn, root_id).map(Some),
+            None => Ok(None),
+        }
+    });
+
+    match result {
+        Ok(Some(rules)) => Ok(Json(rules)),
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Root with id {} not found", root_id),
+            }),
+        )),
+        Err(e) => {
+            error!("Failed to load rules for root {}: {}", root_id, e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: "Database error occurred".to_string(),
+                }),
+            ))
+        }
+    }
+}
+
+/// PUT /api/roots/{root_id}/rules
+/// Replaces all include/exclude rules for a root. Takes effect on the next scan.
+pub async fn update_root_rules(
+    Path(root_id): Path<i64>,
+    Json(req): Json<UpdateRootRulesRequest>,
+) -> Result<Json<Vec<RootRule>>, (StatusCode, Json<ErrorResponse>)> {
+    match RootRule::replace_for_root(root_id, &req.rules) {
+        Ok(()) => {
+            log::info!("Updated rules for root {} ({} rules)", root_id, req.rules.len());
+            Ok(Json(req.rules))
+        }
+        Err(e) => {
+            let (status_code, error_message) = match &e {
+                FsPulseError::Error(msg) if msg.contains("not found") => (
+                    StatusCode::NOT_FOUND,
+                    format!("Root with id {} not found", root_id),
+                ),
+                FsPulseError::Error(msg) if msg.contains("active scan") => {
+                    (StatusCode::CONFLICT, msg.clone())
+                }
+                FsPulseError::Error(msg) => (StatusCode::BAD_REQUEST, msg.clone()),
+                _ => {
+                    error!("Unexpected error updating rules for root {}: {}", root_id, e);
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "An unexpected error occurred".to_string(),
+                    )
+                }
+            };
+
+            Err((
+                status_code,
+                Json(ErrorResponse {
+                    error: error_message,
+                }),
+            ))
+        }
+    }
+}
+
+/// GET /api/roots/{root_id}/options
+/// Returns the scan options for a root
+pub async fn get_root_options(
+    Path(root_id): Path<i64>,
+) -> Result<Json<RootOptions>, (StatusCode, Json<ErrorResponse>)> {
+    let result = Database::get_connection().and_then(|conn| Root::get_by_id(&conn, root_id));
+
+    match result {
+        Ok(Some(root)) => Ok(Json(root.options().clone())),
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Root with id {} not found", root_id),
+            }),
+        )),
+        Err(e) => {
+            error!("Failed to load options for root {}: {}", root_id, e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: "Database error occurred".to_string(),
+                }),
+            ))
+        }
+    }
+}
+
+/// PUT /api/roots/{root_id}/options
+/// Replaces the scan options for a root. Takes effect on the next scan.
+pub async fn update_root_options(
+    Path(root_id): Path<i64>,
+    Json(options): Json<RootOptions>,
+) -> Result<Json<RootOptions>, (StatusCode, Json<ErrorResponse>)> {
+    match Root::update_options(root_id, &options) {
+        Ok(()) => {
+            log::info!("Updated options for root {}: {:?}", root_id, options);
+            Ok(Json(options))
+        }
+        Err(e) => {
+            let (status_code, error_message) = match &e {
+                FsPulseError::Error(msg) if msg.contains("not found") => (
+                    StatusCode::NOT_FOUND,
+                    format!("Root with id {} not found", root_id),
+                ),
+                FsPulseError::Error(msg) if msg.contains("active scan") => {
+                    (StatusCode::CONFLICT, msg.clone())
+                }
+                FsPulseError::Error(msg) => (StatusCode::BAD_REQUEST, msg.clone()),
+                _ => {
+                    error!("Unexpected error updating options for root {}: {}", root_id, e);
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "An unexpected error occurred".to_string(),
+                    )
+                }
+            };
+
+            Err((
+                status_code,
+                Json(ErrorResponse {
+                    error: error_message,
+                }),
+            ))
+        }
+    }
+}
+
+/// GET /api/roots/{root_id}/manifest?scan_id=&subpath=&format=&algo=
+/// Downloads the Baseline hashes of a root, or of a directory in it, as of a
+/// scan (the latest completed one by default). `format` is `sums` (the default,
+/// sha256sum text) or `json`.
+pub async fn export_manifest(
+    Path(root_id): Path<i64>,
+    Query(spec): Query<ExportSpec>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    let result = Database::get_connection().and_then(|conn| {
+        let export = ManifestExport::resolve(&conn, root_id, &spec)?;
+        let mut body = Vec::new();
+        let summary = export.write(&conn, &mut body, |_| Ok(()))?;
+        log::info!(
+            "Exported {} hashes from scan {} of {} ({} files without a Baseline hash skipped)",
+            summary.exported,
+            export.scan_id(),
+            export.base_path(),
+            summary.skipped
+        );
+        Ok((export.file_name(), body))
+    });
+
+    match result {
+        Ok((file_name, body)) => Ok((
+            [
+                (header::CONTENT_TYPE, spec.format.content_type().to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"{}\"", file_name),
+                ),
+            ],
+            body,
+        )
+            .into_response()),
+        Err(e) => {
+            let (status_code, error_message) = match &e {
+                FsPulseError::Error(msg) if msg.contains("not found") => {
+                    (StatusCode::NOT_FOUND, msg.clone())
+                }
+                FsPulseError::Error(msg) => (StatusCode::BAD_REQUEST, msg.clone()),
+                _ => {
+                    error!("Failed to export manifest for root {}: {}", root_id, e);
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "An unexpected error occurred".to_string(),
+                    )
+                }
+            };
+
+            Err((
+                status_code,
+                Json(ErrorResponse {
+                    error: error_message,
+                }),
+            ))
+        }
+    }
+}
+
 /// DELETE /api/roots/{root_id}
 /// Deletes a root and all associated data (scans, items, versions)
 pub async fn delete_root(
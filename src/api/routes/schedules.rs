@@ -1,4 +1,8 @@
 use crate::db::Database;
+use crate::integrity::reverify::ReverifyBudget;
+use crate::io_throttle::ReadLimits;
+use crate::run_window::RunWindow;
+use crate::scan_scope::ScanScope;
 use crate::task_manager::TaskManager;
 use crate::scans::HashMode;
 use crate::schedules::{
@@ -21,6 +25,18 @@ pub struct CreateScheduleRequest {
     pub interval_unit: Option<IntervalUnit>,
     pub hash_mode: HashMode,
     pub is_val: bool,
+    /// Root-relative directory to scan (omit for the whole root)
+    #[serde(default)]
+    pub subpath: Option<String>,
+    /// Re-hash the files with the stalest hashes within this budget (omit not to)
+    #[serde(default)]
+    pub reverify: Option<ReverifyBudget>,
+    /// Read limits replacing the global ones during analysis (omit to use the global ones)
+    #[serde(default)]
+    pub read_limits: Option<ReadLimits>,
+    /// Daily local time range the schedule's scans may run in (omit to use the global one)
+    #[serde(default)]
+    pub run_window: Option<RunWindow>,
 }
 
 /// Request body for updating a schedule
@@ -35,6 +51,29 @@ pub struct UpdateScheduleRequest {
     pub interval_unit: Option<IntervalUnit>,
     pub hash_mode: HashMode,
     pub is_val: bool,
+    /// Root-relative directory to scan (omit for the whole root)
+    #[serde(default)]
+    pub subpath: Option<String>,
+    /// Re-hash the files with the stalest hashes within this budget (omit not to)
+    #[serde(default)]
+    pub reverify: Option<ReverifyBudget>,
+    /// Read limits replacing the global ones during analysis (omit to use the global ones)
+    #[serde(default)]
+    pub read_limits: Option<ReadLimits>,
+    /// Daily local time range the schedule's scans may run in (omit to use the global one)
+    #[serde(default)]
+    pub run_window: Option<RunWindow>,
+}
+
+/// Normalize a requested subpath, rejecting one that escapes the root
+fn normalize_subpath(subpath: Option<&str>) -> Result<Option<String>, StatusCode> {
+    match subpath.map(ScanScope::normalize_subpath).transpose() {
+        Ok(subpath) => Ok(subpath.flatten()),
+        Err(e) => {
+            log::error!("{}", e);
+            Err(StatusCode::BAD_REQUEST)
+        }
+    }
 }
 
 /// Request body for toggling schedule enabled status
@@ -53,6 +92,8 @@ pub async fn create_schedule(
         StatusCode::INTERNAL_SERVER_ERROR
     })?;
 
+    let subpath = normalize_subpath(request.subpath.as_deref())?;
+
     let schedule = TaskManager::create_schedule(
         &conn,
         CreateScheduleParams {
@@ -66,6 +107,10 @@ pub async fn create_schedule(
             interval_unit: request.interval_unit,
             hash_mode: request.hash_mode,
             is_val: request.is_val,
+            subpath,
+            reverify: request.reverify,
+            read_limits: request.read_limits,
+            run_window: request.run_window,
         },
     )
     .map_err(|e| {
@@ -113,6 +158,10 @@ pub async fn update_schedule(
         interval_unit: request.interval_unit,
         hash_mode: request.hash_mode,
         is_val: request.is_val,
+        subpath: normalize_subpath(request.subpath.as_deref())?,
+        reverify: request.reverify,
+        read_limits: request.read_limits,
+        run_window: request.run_window,
         created_at: existing.created_at, // Preserve
         updated_at: now,
     };
@@ -158,7 +207,8 @@ pub async fn toggle_schedule(
 
 /// GET /api/tasks/upcoming
 /// Get upcoming tasks for display in Tasks page
-/// Returns list of upcoming tasks (excludes currently running task unless paused)
+/// Returns list of upcoming tasks (excludes currently running task unless paused or
+/// suspended outside its run window), with each task's run window state
 pub async fn get_upcoming_tasks() -> Result<Json<Value>, StatusCode> {
     use crate::task_manager::TaskManager;
 
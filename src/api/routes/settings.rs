@@ -2,8 +2,14 @@ use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
 use serde::{Deserialize, Serialize};
 use directories::ProjectDirs;
 
-use crate::config::{self, MIN_ANALYSIS_THREADS, MAX_ANALYSIS_THREADS};
+use crate::config::{
+    self, MAX_ANALYSIS_THREADS, MAX_READ_LIMIT_IOPS, MAX_READ_LIMIT_MB_PER_SEC, MAX_SAMPLE_BLOCKS,
+    MAX_SCAN_THREADS, MIN_ANALYSIS_THREADS, MIN_SAMPLE_BLOCKS, MIN_SCAN_THREADS,
+};
 use crate::api::state::AppState;
+use crate::run_window::RunWindow;
+use crate::validate::registry::ValidatorRegistry;
+use crate::validate::validator::ValidatorInfo;
 
 /// Represents a single configuration setting with complete ConfigValue information
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -21,6 +27,11 @@ pub struct ConfigSetting<T> {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SettingsResponse {
     pub analysis_threads: ConfigSetting<usize>,
+    pub analysis_sample_blocks: ConfigSetting<usize>,
+    pub analysis_read_limit_mb_per_sec: ConfigSetting<usize>,
+    pub analysis_read_limit_iops: ConfigSetting<usize>,
+    pub scan_threads: ConfigSetting<usize>,
+    pub tasks_run_window: ConfigSetting<String>,
     pub logging_fspulse: ConfigSetting<String>,
     pub logging_lopdf: ConfigSetting<String>,
     pub server_host: ConfigSetting<String>,
@@ -28,14 +39,25 @@ pub struct SettingsResponse {
     pub database_dir: ConfigSetting<String>,
     pub mcp_enabled: ConfigSetting<bool>,
     pub validation_images: ConfigSetting<bool>,
+    pub validation_video: ConfigSetting<bool>,
     pub validation_pdf: ConfigSetting<bool>,
     pub validation_audio: ConfigSetting<bool>,
+    pub validation_archives: ConfigSetting<bool>,
+    pub validation_prefer_content: ConfigSetting<bool>,
+    /// Registered validators, with the `[validation]` key that enables each
+    #[serde(skip_deserializing)]
+    pub validators: Vec<&'static ValidatorInfo>,
 }
 
 /// Request structure for PUT /api/settings
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SettingsUpdateRequest {
     pub analysis_threads: Option<usize>,
+    pub analysis_sample_blocks: Option<usize>,
+    pub analysis_read_limit_mb_per_sec: Option<usize>,
+    pub analysis_read_limit_iops: Option<usize>,
+    pub scan_threads: Option<usize>,
+    pub tasks_run_window: Option<String>,
     pub server_host: Option<String>,
     pub server_port: Option<u16>,
     pub logging_fspulse: Option<String>,
@@ -43,8 +65,11 @@ pub struct SettingsUpdateRequest {
     pub database_dir: Option<String>,
     pub mcp_enabled: Option<bool>,
     pub validation_images: Option<bool>,
+    pub validation_video: Option<bool>,
     pub validation_pdf: Option<bool>,
     pub validation_audio: Option<bool>,
+    pub validation_archives: Option<bool>,
+    pub validation_prefer_content: Option<bool>,
 }
 
 /// Request structure for DELETE /api/settings
@@ -68,6 +93,65 @@ pub async fn get_settings() -> Result<Json<SettingsResponse>, (StatusCode, Strin
         editable: threads_value.env_value.is_none(),
     };
 
+    // Analysis Sample Blocks
+    let sample_blocks_value = config::Config::get_analysis_sample_blocks_value();
+    let sample_blocks_setting = ConfigSetting {
+        env_value: sample_blocks_value.env_value,
+        file_value: sample_blocks_value.file_value,
+        file_value_original: sample_blocks_value.file_value_original,
+        default_value: sample_blocks_value.default_value,
+        env_var: "FSPULSE_ANALYSIS_SAMPLE_BLOCKS".to_string(),
+        requires_restart: sample_blocks_value.requires_restart,
+        editable: sample_blocks_value.env_value.is_none(),
+    };
+
+    // Analysis Read Limits
+    let read_mb_value = config::Config::get_analysis_read_limit_mb_per_sec_value();
+    let read_mb_setting = ConfigSetting {
+        env_value: read_mb_value.env_value,
+        file_value: read_mb_value.file_value,
+        file_value_original: read_mb_value.file_value_original,
+        default_value: read_mb_value.default_value,
+        env_var: "FSPULSE_ANALYSIS_READ_LIMIT_MB_PER_SEC".to_string(),
+        requires_restart: read_mb_value.requires_restart,
+        editable: read_mb_value.env_value.is_none(),
+    };
+
+    let read_iops_value = config::Config::get_analysis_read_limit_iops_value();
+    let read_iops_setting = ConfigSetting {
+        env_value: read_iops_value.env_value,
+        file_value: read_iops_value.file_value,
+        file_value_original: read_iops_value.file_value_original,
+        default_value: read_iops_value.default_value,
+        env_var: "FSPULSE_ANALYSIS_READ_LIMIT_IOPS".to_string(),
+        requires_restart: read_iops_value.requires_restart,
+        editable: read_iops_value.env_value.is_none(),
+    };
+
+    // Scan Threads
+    let scan_threads_value = config::Config::get_scan_threads_value();
+    let scan_threads_setting = ConfigSetting {
+        env_value: scan_threads_value.env_value,
+        file_value: scan_threads_value.file_value,
+        file_value_original: scan_threads_value.file_value_original,
+        default_value: scan_threads_value.default_value,
+        env_var: "FSPULSE_SCAN_THREADS".to_string(),
+        requires_restart: scan_threads_value.requires_restart,
+        editable: scan_threads_value.env_value.is_none(),
+    };
+
+    // Tasks Run Window
+    let run_window_value = config::Config::get_tasks_run_window_value();
+    let run_window_setting = ConfigSetting {
+        env_value: run_window_value.env_value.clone(),
+        file_value: run_window_value.file_value.clone(),
+        file_value_original: run_window_value.file_value_original.clone(),
+        default_value: run_window_value.default_value.clone(),
+        env_var: "FSPULSE_TASKS_RUN_WINDOW".to_string(),
+        requires_restart: run_window_value.requires_restart,
+        editable: run_window_value.env_value.is_none(),
+    };
+
     // Logging fsPulse
     let fspulse_value = config::Config::get_logging_fspulse_value();
     let fspulse_setting = ConfigSetting {
@@ -152,6 +236,18 @@ pub async fn get_settings() -> Result<Json<SettingsResponse>, (StatusCode, Strin
         editable: val_images_value.env_value.is_none(),
     };
 
+    // Validation Video
+    let val_video_value = config::Config::get_validation_video_value();
+    let val_video_setting = ConfigSetting {
+        env_value: val_video_value.env_value,
+        file_value: val_video_value.file_value,
+        file_value_original: val_video_value.file_value_original,
+        default_value: val_video_value.default_value,
+        env_var: "FSPULSE_VALIDATION_VIDEO".to_string(),
+        requires_restart: val_video_value.requires_restart,
+        editable: val_video_value.env_value.is_none(),
+    };
+
     // Validation PDF
     let val_pdf_value = config::Config::get_validation_pdf_value();
     let val_pdf_setting = ConfigSetting {
@@ -176,8 +272,37 @@ pub async fn get_settings() -> Result<Json<SettingsResponse>, (StatusCode, Strin
         editable: val_audio_value.env_value.is_none(),
     };
 
+    // Validation Archives
+    let val_archives_value = config::Config::get_validation_archives_value();
+    let val_archives_setting = ConfigSetting {
+        env_value: val_archives_value.env_value,
+        file_value: val_archives_value.file_value,
+        file_value_original: val_archives_value.file_value_original,
+        default_value: val_archives_value.default_value,
+        env_var: "FSPULSE_VALIDATION_ARCHIVES".to_string(),
+        requires_restart: val_archives_value.requires_restart,
+        editable: val_archives_value.env_value.is_none(),
+    };
+
+    // Validation Prefer Content
+    let prefer_content_value = config::Config::get_validation_prefer_content_value();
+    let prefer_content_setting = ConfigSetting {
+        env_value: prefer_content_value.env_value,
+        file_value: prefer_content_value.file_value,
+        file_value_original: prefer_content_value.file_value_original,
+        default_value: prefer_content_value.default_value,
+        env_var: "FSPULSE_VALIDATION_PREFER_CONTENT".to_string(),
+        requires_restart: prefer_content_value.requires_restart,
+        editable: prefer_content_value.env_value.is_none(),
+    };
+
     let response = SettingsResponse {
         analysis_threads: threads_setting,
+        analysis_sample_blocks: sample_blocks_setting,
+        analysis_read_limit_mb_per_sec: read_mb_setting,
+        analysis_read_limit_iops: read_iops_setting,
+        scan_threads: scan_threads_setting,
+        tasks_run_window: run_window_setting,
         logging_fspulse: fspulse_setting,
         logging_lopdf: lopdf_setting,
         server_host: host_setting,
@@ -185,8 +310,12 @@ pub async fn get_settings() -> Result<Json<SettingsResponse>, (StatusCode, Strin
         database_dir: dir_setting,
         mcp_enabled: mcp_setting,
         validation_images: val_images_setting,
+        validation_video: val_video_setting,
         validation_pdf: val_pdf_setting,
         validation_audio: val_audio_setting,
+        validation_archives: val_archives_setting,
+        validation_prefer_content: prefer_content_setting,
+        validators: ValidatorRegistry::global().infos().collect(),
     };
 
     Ok(Json(response))
@@ -237,6 +366,75 @@ pub async fn update_settings(
         updated = true;
     }
 
+    // Update analysis sample blocks if provided
+    if let Some(blocks) = request.analysis_sample_blocks {
+        if !(MIN_SAMPLE_BLOCKS..=MAX_SAMPLE_BLOCKS).contains(&blocks) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("Sample blocks must be between {} and {}", MIN_SAMPLE_BLOCKS, MAX_SAMPLE_BLOCKS),
+            ));
+        }
+
+        config::Config::set_analysis_sample_blocks(blocks, &project_dirs)
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        updated = true;
+    }
+
+    // Update analysis read limits if provided. A running scan picks these up
+    // on its next read.
+    if let Some(limit) = request.analysis_read_limit_mb_per_sec {
+        if limit > MAX_READ_LIMIT_MB_PER_SEC {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("Read limit must be between 0 and {} MB/s", MAX_READ_LIMIT_MB_PER_SEC),
+            ));
+        }
+
+        config::Config::set_analysis_read_limit_mb_per_sec(limit, &project_dirs)
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        updated = true;
+    }
+
+    if let Some(limit) = request.analysis_read_limit_iops {
+        if limit > MAX_READ_LIMIT_IOPS {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("Read limit must be between 0 and {} reads/s", MAX_READ_LIMIT_IOPS),
+            ));
+        }
+
+        config::Config::set_analysis_read_limit_iops(limit, &project_dirs)
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        updated = true;
+    }
+
+    // Update scan threads if provided
+    if let Some(threads) = request.scan_threads {
+        if !(MIN_SCAN_THREADS..=MAX_SCAN_THREADS).contains(&threads) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("Scan threads must be between {} and {}", MIN_SCAN_THREADS, MAX_SCAN_THREADS),
+            ));
+        }
+
+        config::Config::set_scan_threads(threads, &project_dirs)
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        updated = true;
+    }
+
+    // Update tasks run window if provided. An empty window is always open; a
+    // running task is suspended within seconds if the new window is closed.
+    if let Some(window) = request.tasks_run_window {
+        let window = window.trim().to_string();
+        if !window.is_empty() {
+            RunWindow::parse(&window).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+        }
+
+        config::Config::set_tasks_run_window(window, &project_dirs)
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        updated = true;
+    }
+
     // Update logging fspulse if provided
     if let Some(level) = request.logging_fspulse {
         config::Config::set_logging_fspulse(level, &project_dirs)
@@ -272,6 +470,13 @@ pub async fn update_settings(
         updated = true;
     }
 
+    // Update validation video if provided
+    if let Some(val) = request.validation_video {
+        config::Config::set_validation_video(val, &project_dirs)
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        updated = true;
+    }
+
     // Update validation pdf if provided
     if let Some(val) = request.validation_pdf {
         config::Config::set_validation_pdf(val, &project_dirs)
@@ -286,6 +491,20 @@ pub async fn update_settings(
         updated = true;
     }
 
+    // Update validation archives if provided
+    if let Some(val) = request.validation_archives {
+        config::Config::set_validation_archives(val, &project_dirs)
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        updated = true;
+    }
+
+    // Update validation prefer content if provided
+    if let Some(val) = request.validation_prefer_content {
+        config::Config::set_validation_prefer_content(val, &project_dirs)
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        updated = true;
+    }
+
     if updated {
         Ok((StatusCode::OK, "Configuration updated successfully"))
     } else {
@@ -324,6 +543,26 @@ pub async fn delete_settings(
             config::Config::delete_analysis_threads(&project_dirs)
                 .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
         }
+        "analysis_sample_blocks" => {
+            config::Config::delete_analysis_sample_blocks(&project_dirs)
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        }
+        "analysis_read_limit_mb_per_sec" => {
+            config::Config::delete_analysis_read_limit_mb_per_sec(&project_dirs)
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        }
+        "analysis_read_limit_iops" => {
+            config::Config::delete_analysis_read_limit_iops(&project_dirs)
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        }
+        "scan_threads" => {
+            config::Config::delete_scan_threads(&project_dirs)
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        }
+        "tasks_run_window" => {
+            config::Config::delete_tasks_run_window(&project_dirs)
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        }
         "logging_fspulse" => {
             config::Config::delete_logging_fspulse(&project_dirs)
                 .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
@@ -344,6 +583,10 @@ pub async fn delete_settings(
             config::Config::delete_validation_images(&project_dirs)
                 .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
         }
+        "validation_video" => {
+            config::Config::delete_validation_video(&project_dirs)
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        }
         "validation_pdf" => {
             config::Config::delete_validation_pdf(&project_dirs)
                 .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
@@ -352,6 +595,14 @@ pub async fn delete_settings(
             config::Config::delete_validation_audio(&project_dirs)
                 .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
         }
+        "validation_archives" => {
+            config::Config::delete_validation_archives(&project_dirs)
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        }
+        "validation_prefer_content" => {
+            config::Config::delete_validation_prefer_content(&project_dirs)
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        }
         _ => {
             return Err((
                 StatusCode::BAD_REQUEST,
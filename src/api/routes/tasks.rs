@@ -6,9 +6,12 @@ use log::error;
 use serde::{Deserialize, Serialize};
 
 use crate::db::Database;
+use crate::integrity::reverify::ReverifyBudget;
+use crate::io_throttle::ReadLimits;
+use crate::scan_scope::ScanScope;
 use crate::schedules::{SourceType, TaskEntry};
 use crate::scans::HashMode;
-use crate::task::{TaskStatus, TaskType};
+use crate::task::{CompareRootsSettings, ExportManifestSettings, TaskStatus, TaskType};
 use crate::task_manager::TaskManager;
 
 use super::state::AppState;
@@ -19,12 +22,22 @@ pub struct ScheduleScanRequest {
     pub root_id: i64,
     pub hash_mode: String, // "None", "New", "All"
     pub is_val: bool,
+    /// Root-relative directory to scan instead of the whole root
+    #[serde(default)]
+    pub subpath: Option<String>,
+    /// Also re-hash the files with the stalest hashes, within this budget
+    #[serde(default)]
+    pub reverify: Option<ReverifyBudget>,
+    /// Read limits replacing the global ones for this scan's analysis
+    #[serde(default)]
+    pub read_limits: Option<ReadLimits>,
 }
 
 /// POST /api/tasks/scan
 ///
-/// Schedules a new manual scan task.
-/// Returns 200 OK if scan was scheduled.
+/// Schedules a new manual scan task, of the whole root or of `subpath`.
+/// Returns 200 OK if scan was scheduled, 400 if `subpath` is not relative to the
+/// root, `reverify` doesn't suit the hash mode or `read_limits` is out of range.
 pub async fn schedule_scan(
     State(_state): State<AppState>,
     Json(req): Json<ScheduleScanRequest>,
@@ -35,28 +48,125 @@ pub async fn schedule_scan(
         "None" => HashMode::None,
         "New" => HashMode::New,
         "All" => HashMode::All,
+        "Sampled" => HashMode::Sampled,
         _ => {
             error!("Invalid hash_mode: {}", req.hash_mode);
             return Err(StatusCode::BAD_REQUEST);
         }
     };
 
-    TaskManager::schedule_manual_scan(&conn, req.root_id, hash_mode, req.is_val).map_err(
-        |e| {
-            error!("Failed to schedule manual scan: {}", e);
-            if e.to_string().contains("Root not found") {
-                StatusCode::NOT_FOUND
-            } else {
-                StatusCode::INTERNAL_SERVER_ERROR
-            }
-        },
-    )?;
+    let subpath = match req.subpath.as_deref().map(ScanScope::normalize_subpath).transpose() {
+        Ok(subpath) => subpath.flatten(),
+        Err(e) => {
+            error!("{}", e);
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    };
+
+    if let Some(Err(e)) = req.reverify.map(|budget| budget.validate(hash_mode)) {
+        error!("Invalid reverify: {}", e);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    if let Some(Err(e)) = req.read_limits.map(|limits| limits.validate()) {
+        error!("Invalid read_limits: {}", e);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    TaskManager::schedule_manual_scan(
+        &conn,
+        req.root_id,
+        hash_mode,
+        req.is_val,
+        subpath,
+        req.reverify,
+        req.read_limits,
+    )
+    .map_err(|e| {
+        error!("Failed to schedule manual scan: {}", e);
+        if e.to_string().contains("Root not found") {
+            StatusCode::NOT_FOUND
+        } else {
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    })?;
 
     log::info!("Manual scan scheduled for root {}", req.root_id);
 
     Ok(StatusCode::OK)
 }
 
+/// Request structure for scheduling a manifest export
+#[derive(Debug, Deserialize)]
+pub struct ScheduleExportManifestRequest {
+    pub root_id: i64,
+    #[serde(flatten)]
+    pub settings: ExportManifestSettings,
+}
+
+/// POST /api/tasks/export-manifest
+///
+/// Schedules a task that writes a root's Baseline hashes to `output_path` on the server.
+/// Returns 200 OK if the export was scheduled, 400 if the subpath or output path is invalid.
+pub async fn schedule_export_manifest(
+    Json(req): Json<ScheduleExportManifestRequest>,
+) -> Result<StatusCode, StatusCode> {
+    let conn = Database::get_connection().map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let subpath = req.settings.spec.subpath.as_deref().map(ScanScope::normalize_subpath).transpose();
+    if let Err(e) = subpath.and_then(|_| req.settings.validate()) {
+        error!("{}", e);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    TaskManager::schedule_export_manifest(&conn, req.root_id, &req.settings).map_err(|e| {
+        error!("Failed to schedule manifest export: {}", e);
+        if e.to_string().contains("not found") {
+            StatusCode::NOT_FOUND
+        } else {
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    })?;
+
+    log::info!("Manifest export scheduled for root {}", req.root_id);
+
+    Ok(StatusCode::OK)
+}
+
+/// POST /api/tasks/compare-roots
+///
+/// Schedules a task that compares two roots (or folders within them) and stores
+/// the report, which is then available from /api/comparisons.
+/// Returns 200 OK if the comparison was scheduled, 400 if a subpath is invalid.
+pub async fn schedule_compare_roots(
+    Json(settings): Json<CompareRootsSettings>,
+) -> Result<StatusCode, StatusCode> {
+    let conn = Database::get_connection().map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    for side in [&settings.left, &settings.right] {
+        if let Some(Err(e)) = side.subpath.as_deref().map(ScanScope::normalize_subpath) {
+            error!("{}", e);
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    }
+
+    TaskManager::schedule_compare_roots(&conn, &settings).map_err(|e| {
+        error!("Failed to schedule root comparison: {}", e);
+        if e.to_string().contains("not found") {
+            StatusCode::NOT_FOUND
+        } else {
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    })?;
+
+    log::info!(
+        "Comparison scheduled for root {} with root {}",
+        settings.left.root_id, settings.right.root_id
+    );
+
+    Ok(StatusCode::OK)
+}
+
 /// POST /api/tasks/compact-database
 ///
 /// Schedules a database compaction task.
@@ -0,0 +1,725 @@
+use std::cmp::Ordering;
+use std::path::MAIN_SEPARATOR_STR;
+
+use log::{info, warn};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+use crate::db::Database;
+use crate::error::FsPulseError;
+use crate::hash::{Hash, HashAlgo};
+use crate::item_identity::ItemType;
+use crate::roots::Root;
+use crate::scan_scope::ScanScope;
+use crate::scans::Scan;
+
+/// Number of difference rows written per transaction
+const DIFF_BATCH_SIZE: usize = 1000;
+
+/// How a file differs between the two sides of a comparison.
+/// Stored as integer in the database.
+///
+/// A file that differs in several ways is recorded once, under the first of
+/// size, hash and mod date that differs.
+#[repr(i64)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffKind {
+    /// The file exists only on the left
+    LeftOnly = 1,
+    /// The file exists only on the right
+    RightOnly = 2,
+    Size = 3,
+    /// Same size, but the hashes (computed with the same algorithm) differ
+    Hash = 4,
+    /// Same size and no hash difference, but the modification dates differ
+    ModDate = 5,
+}
+
+impl DiffKind {
+    pub fn as_i64(self) -> i64 {
+        self as i64
+    }
+
+    pub fn from_i64(value: i64) -> Self {
+        match value {
+            1 => DiffKind::LeftOnly,
+            2 => DiffKind::RightOnly,
+            3 => DiffKind::Size,
+            4 => DiffKind::Hash,
+            5 => DiffKind::ModDate,
+            _ => {
+                warn!("Invalid DiffKind value in database: {}, defaulting to Size", value);
+                DiffKind::Size
+            }
+        }
+    }
+
+    pub fn full_name(self) -> &'static str {
+        match self {
+            DiffKind::LeftOnly => "Left only",
+            DiffKind::RightOnly => "Right only",
+            DiffKind::Size => "Size",
+            DiffKind::Hash => "Hash",
+            DiffKind::ModDate => "Mod date",
+        }
+    }
+
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "left_only" | "left only" => Some(DiffKind::LeftOnly),
+            "right_only" | "right only" => Some(DiffKind::RightOnly),
+            "size" => Some(DiffKind::Size),
+            "hash" => Some(DiffKind::Hash),
+            "mod_date" | "mod date" => Some(DiffKind::ModDate),
+            _ => None,
+        }
+    }
+}
+
+/// One side of a comparison as requested: a root, or a folder in it, at a scan
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompareSideSpec {
+    pub root_id: i64,
+    /// None means the root's latest completed scan when the comparison runs
+    #[serde(default)]
+    pub scan_id: Option<i64>,
+    /// Root-relative folder to compare instead of the whole root
+    #[serde(default)]
+    pub subpath: Option<String>,
+}
+
+/// One side of a comparison, resolved against the database
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ComparisonSide {
+    pub root_id: i64,
+    pub scan_id: i64,
+    /// The folder compared: the root or a folder in it
+    pub path: String,
+}
+
+impl ComparisonSide {
+    /// Check `spec` and pin down the scan it compares
+    pub fn resolve(conn: &Connection, spec: &CompareSideSpec) -> Result<Self, FsPulseError> {
+        let root = Root::get_by_id(conn, spec.root_id)?
+            .ok_or_else(|| FsPulseError::Error(format!("Root {} not found", spec.root_id)))?;
+        let path = root.subpath_dir(spec.subpath.as_deref())?;
+        let (scan_id, _) = Scan::resolve_completed(conn, spec.root_id, spec.scan_id)?;
+
+        Ok(Self {
+            root_id: spec.root_id,
+            scan_id,
+            path,
+        })
+    }
+}
+
+/// Counts of how the files on the two sides line up
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ComparisonCounts {
+    /// On both sides with equal size, mod date and hash
+    pub match_count: i64,
+    /// On both sides with equal size and mod date, but without hashes in a
+    /// common algorithm to confirm the contents
+    pub unverified_count: i64,
+    pub left_only_count: i64,
+    pub right_only_count: i64,
+    pub size_diff_count: i64,
+    pub hash_diff_count: i64,
+    pub mod_date_diff_count: i64,
+}
+
+impl ComparisonCounts {
+    fn add(&mut self, kind: DiffKind) {
+        match kind {
+            DiffKind::LeftOnly => self.left_only_count += 1,
+            DiffKind::RightOnly => self.right_only_count += 1,
+            DiffKind::Size => self.size_diff_count += 1,
+            DiffKind::Hash => self.hash_diff_count += 1,
+            DiffKind::ModDate => self.mod_date_diff_count += 1,
+        }
+    }
+
+    pub fn diff_count(&self) -> i64 {
+        self.left_only_count
+            + self.right_only_count
+            + self.size_diff_count
+            + self.hash_diff_count
+            + self.mod_date_diff_count
+    }
+}
+
+/// A file that is missing on one side or differs between the sides
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ComparisonDiff {
+    /// Path relative to the compared folders
+    pub rel_path: String,
+    pub diff_kind: DiffKind,
+    pub left_item_id: Option<i64>,
+    pub right_item_id: Option<i64>,
+    pub left_size: Option<i64>,
+    pub right_size: Option<i64>,
+    pub left_mod_date: Option<i64>,
+    pub right_mod_date: Option<i64>,
+    pub left_hash: Option<String>,
+    pub right_hash: Option<String>,
+}
+
+/// A file as seen on one side of a comparison
+#[derive(Debug, Clone)]
+struct SideFile {
+    rel_path: String,
+    item_id: i64,
+    size: Option<i64>,
+    mod_date: Option<i64>,
+    hash: Option<(HashAlgo, Vec<u8>)>,
+}
+
+/// How a file present on both sides compares
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Match,
+    Unverified,
+    Differs(DiffKind),
+}
+
+fn classify(left: &SideFile, right: &SideFile) -> Outcome {
+    if left.size != right.size {
+        return Outcome::Differs(DiffKind::Size);
+    }
+    // Hashes are only comparable when both sides used the same algorithm
+    let hashes_equal = match (&left.hash, &right.hash) {
+        (Some((left_algo, left_hash)), Some((right_algo, right_hash))) if left_algo == right_algo => {
+            Some(left_hash == right_hash)
+        }
+        _ => None,
+    };
+    if hashes_equal == Some(false) {
+        return Outcome::Differs(DiffKind::Hash);
+    }
+    if left.mod_date != right.mod_date {
+        return Outcome::Differs(DiffKind::ModDate);
+    }
+    match hashes_equal {
+        Some(true) => Outcome::Match,
+        _ => Outcome::Unverified,
+    }
+}
+
+/// A report aligning two roots, or folders within them, by relative path.
+/// Maps to the `comparisons` table; the differences are in `comparison_diffs`.
+#[derive(Debug, Clone, Serialize)]
+pub struct RootComparison {
+    pub comparison_id: i64,
+    pub task_id: i64,
+    pub left: ComparisonSide,
+    pub right: ComparisonSide,
+    pub started_at: i64,
+    pub completed_at: i64,
+    #[serde(flatten)]
+    pub counts: ComparisonCounts,
+}
+
+const COMPARISON_COLUMNS: &str = "comparison_id, task_id, left_root_id, left_scan_id, left_path,
+    right_root_id, right_scan_id, right_path, started_at, completed_at,
+    match_count, unverified_count, left_only_count, right_only_count,
+    size_diff_count, hash_diff_count, mod_date_diff_count";
+
+impl RootComparison {
+    /// Compare the files of two sides, writing the report as it goes.
+    ///
+    /// Both sides are read in path order and merged, so memory use doesn't
+    /// grow with the size of the roots. `on_file` is called after each file is
+    /// compared and can stop the comparison by returning an error, in which
+    /// case the caller should remove the partial report with
+    /// `delete_incomplete`.
+    pub fn run(
+        task_id: i64,
+        left: ComparisonSide,
+        right: ComparisonSide,
+        mut on_file: impl FnMut(u64) -> Result<(), FsPulseError>,
+    ) -> Result<Self, FsPulseError> {
+        let conn = Database::get_connection()?;
+        let started_at = chrono::Utc::now().timestamp();
+
+        let comparison_id = Database::immediate_transaction(&conn, |c| {
+            c.execute(
+                "INSERT INTO comparisons (task_id, left_root_id, left_scan_id, left_path,
+                     right_root_id, right_scan_id, right_path, started_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+                params![
+                    task_id,
+                    left.root_id,
+                    left.scan_id,
+                    left.path,
+                    right.root_id,
+                    right.scan_id,
+                    right.path,
+                    started_at
+                ],
+            )?;
+            Ok(c.last_insert_rowid())
+        })?;
+
+        // Both sides are streamed from a separate connection so that batches of
+        // differences can be committed while they are open
+        let read_conn = Database::get_connection()?;
+        let left_sql = Self::side_sql(&left);
+        let right_sql = Self::side_sql(&right);
+        let mut left_stmt = read_conn.prepare(&left_sql)?;
+        let mut right_stmt = read_conn.prepare(&right_sql)?;
+        let file_type = ItemType::File.as_i64();
+        let mut left_rows = left_stmt.query(params![left.root_id, left.scan_id, file_type])?;
+        let mut right_rows = right_stmt.query(params![right.root_id, right.scan_id, file_type])?;
+
+        let mut next_left = Self::next_file(&mut left_rows, &left)?;
+        let mut next_right = Self::next_file(&mut right_rows, &right)?;
+
+        let mut counts = ComparisonCounts::default();
+        let mut batch: Vec<ComparisonDiff> = Vec::with_capacity(DIFF_BATCH_SIZE);
+        let mut compared: u64 = 0;
+
+        loop {
+            let order = match (&next_left, &next_right) {
+                (None, None) => break,
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(l), Some(r)) => l.rel_path.cmp(&r.rel_path),
+            };
+
+            let diff = match order {
+                Ordering::Less => {
+                    let l = next_left.take().unwrap();
+                    next_left = Self::next_file(&mut left_rows, &left)?;
+                    Some(Self::diff(DiffKind::LeftOnly, Some(l), None))
+                }
+                Ordering::Greater => {
+                    let r = next_right.take().unwrap();
+                    next_right = Self::next_file(&mut right_rows, &right)?;
+                    Some(Self::diff(DiffKind::RightOnly, None, Some(r)))
+                }
+                Ordering::Equal => {
+                    let l = next_left.take().unwrap();
+                    let r = next_right.take().unwrap();
+                    next_left = Self::next_file(&mut left_rows, &left)?;
+                    next_right = Self::next_file(&mut right_rows, &right)?;
+                    match classify(&l, &r) {
+                        Outcome::Match => {
+                            counts.match_count += 1;
+                            None
+                        }
+                        Outcome::Unverified => {
+                            counts.unverified_count += 1;
+                            None
+                        }
+                        Outcome::Differs(kind) => Some(Self::diff(kind, Some(l), Some(r))),
+                    }
+                }
+            };
+
+            if let Some(diff) = diff {
+                counts.add(diff.diff_kind);
+                batch.push(diff);
+                if batch.len() >= DIFF_BATCH_SIZE {
+                    Self::write_diffs(&conn, comparison_id, &batch)?;
+                    batch.clear();
+                }
+            }
+
+            compared += 1;
+            on_file(compared)?;
+        }
+
+        Self::write_diffs(&conn, comparison_id, &batch)?;
+
+        let completed_at = chrono::Utc::now().timestamp();
+        Database::immediate_transaction(&conn, |c| {
+            c.execute(
+                "UPDATE comparisons SET completed_at = ?, match_count = ?, unverified_count = ?,
+                     left_only_count = ?, right_only_count = ?, size_diff_count = ?,
+                     hash_diff_count = ?, mod_date_diff_count = ?
+                 WHERE comparison_id = ?",
+                params![
+                    completed_at,
+                    counts.match_count,
+                    counts.unverified_count,
+                    counts.left_only_count,
+                    counts.right_only_count,
+                    counts.size_diff_count,
+                    counts.hash_diff_count,
+                    counts.mod_date_diff_count,
+                    comparison_id
+                ],
+            )?;
+            Ok(())
+        })?;
+
+        info!(
+            "Comparison {}: {} files compared, {} match, {} unverified, {} differences",
+            comparison_id,
+            compared,
+            counts.match_count,
+            counts.unverified_count,
+            counts.diff_count()
+        );
+
+        Ok(Self {
+            comparison_id,
+            task_id,
+            left,
+            right,
+            started_at,
+            completed_at,
+            counts,
+        })
+    }
+
+    /// Files alive at the side's scan within its folder, with the latest hash
+    /// observed for each by then, in path order
+    fn side_sql(side: &ComparisonSide) -> String {
+        let scope = ScanScope::from_recorded(std::slice::from_ref(&side.path));
+        let scope_filter = scope
+            .sql_filter("i.item_path")
+            .map(|filter| format!("AND {}", filter))
+            .unwrap_or_default();
+
+        // With a common prefix, byte order of the full paths is byte order of
+        // the relative paths, which is what the merge compares
+        format!(
+            "SELECT i.item_id, i.item_path, iv.size, iv.mod_date, hv.hash_algo, hv.file_hash
+             FROM item_versions iv
+             JOIN items i ON i.item_id = iv.item_id
+             LEFT JOIN hash_versions hv ON hv.item_id = iv.item_id
+                 AND hv.item_version = iv.item_version
+                 AND hv.first_scan_id = (
+                     SELECT MAX(first_scan_id) FROM hash_versions
+                     WHERE item_id = iv.item_id AND item_version = iv.item_version
+                       AND first_scan_id <= ?2)
+             WHERE iv.root_id = ?1 AND iv.last_scan_id >= ?2 AND iv.first_scan_id <= ?2
+               AND iv.is_deleted = 0 AND i.item_type = ?3 {scope_filter}
+             ORDER BY i.item_path COLLATE BINARY"
+        )
+    }
+
+    fn next_file(
+        rows: &mut rusqlite::Rows<'_>,
+        side: &ComparisonSide,
+    ) -> Result<Option<SideFile>, FsPulseError> {
+        let Some(row) = rows.next()? else {
+            return Ok(None);
+        };
+        let item_path: String = row.get(1)?;
+        let rel_path = item_path
+            .strip_prefix(&side.path)
+            .map(|rest| rest.trim_start_matches(MAIN_SEPARATOR_STR))
+            .unwrap_or(&item_path)
+            .to_string();
+        let algo: Option<i64> = row.get(4)?;
+        let hash: Option<Vec<u8>> = row.get(5)?;
+
+        Ok(Some(SideFile {
+            rel_path,
+            item_id: row.get(0)?,
+            size: row.get(2)?,
+            mod_date: row.get(3)?,
+            hash: algo.zip(hash).map(|(algo, hash)| (HashAlgo::from_i64(algo), hash)),
+        }))
+    }
+
+    fn diff(kind: DiffKind, left: Option<SideFile>, right: Option<SideFile>) -> ComparisonDiff {
+        let rel_path = left
+            .as_ref()
+            .or(right.as_ref())
+            .map(|f| f.rel_path.clone())
+            .unwrap_or_default();
+        let hex = |f: &SideFile| f.hash.as_ref().map(|(_, hash)| Hash::blob_to_hex(hash.clone()));
+
+        ComparisonDiff {
+            rel_path,
+            diff_kind: kind,
+            left_item_id: left.as_ref().map(|f| f.item_id),
+            right_item_id: right.as_ref().map(|f| f.item_id),
+            left_size: left.as_ref().and_then(|f| f.size),
+            right_size: right.as_ref().and_then(|f| f.size),
+            left_mod_date: left.as_ref().and_then(|f| f.mod_date),
+            right_mod_date: right.as_ref().and_then(|f| f.mod_date),
+            left_hash: left.as_ref().and_then(hex),
+            right_hash: right.as_ref().and_then(hex),
+        }
+    }
+
+    fn write_diffs(
+        conn: &Connection,
+        comparison_id: i64,
+        diffs: &[ComparisonDiff],
+    ) -> Result<(), FsPulseError> {
+        if diffs.is_empty() {
+            return Ok(());
+        }
+        Database::immediate_transaction(conn, |c| {
+            let mut stmt = c.prepare(
+                "INSERT INTO comparison_diffs (comparison_id, rel_path, diff_kind,
+                     left_item_id, right_item_id, left_size, right_size,
+                     left_mod_date, right_mod_date, left_hash, right_hash)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )?;
+            for diff in diffs {
+                stmt.execute(params![
+                    comparison_id,
+                    diff.rel_path,
+                    diff.diff_kind.as_i64(),
+                    diff.left_item_id,
+                    diff.right_item_id,
+                    diff.left_size,
+                    diff.right_size,
+                    diff.left_mod_date,
+                    diff.right_mod_date,
+                    diff.left_hash.as_deref().map(Hash::hex_to_blob),
+                    diff.right_hash.as_deref().map(Hash::hex_to_blob),
+                ])?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Remove reports a task left unfinished (stopped, failed or interrupted)
+    pub fn delete_incomplete(task_id: i64) -> Result<(), FsPulseError> {
+        let conn = Database::get_connection()?;
+        Database::immediate_transaction(&conn, |c| {
+            c.execute(
+                "DELETE FROM comparison_diffs WHERE comparison_id IN (
+                     SELECT comparison_id FROM comparisons
+                     WHERE task_id = ? AND completed_at IS NULL)",
+                [task_id],
+            )?;
+            c.execute(
+                "DELETE FROM comparisons WHERE task_id = ? AND completed_at IS NULL",
+                [task_id],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Delete a report. Returns false if it doesn't exist.
+    pub fn delete(comparison_id: i64) -> Result<bool, FsPulseError> {
+        let conn = Database::get_connection()?;
+        Database::immediate_transaction(&conn, |c| {
+            c.execute("DELETE FROM comparison_diffs WHERE comparison_id = ?", [comparison_id])?;
+            let rows = c.execute("DELETE FROM comparisons WHERE comparison_id = ?", [comparison_id])?;
+            Ok(rows > 0)
+        })
+    }
+
+    /// Delete the reports that involve a root, on either side.
+    /// Must be called within a transaction.
+    pub fn delete_for_root(conn: &Connection, root_id: i64) -> Result<(), FsPulseError> {
+        conn.execute(
+            "DELETE FROM comparison_diffs WHERE comparison_id IN (
+                 SELECT comparison_id FROM comparisons
+                 WHERE left_root_id = ?1 OR right_root_id = ?1)",
+            [root_id],
+        )?;
+        conn.execute(
+            "DELETE FROM comparisons WHERE left_root_id = ?1 OR right_root_id = ?1",
+            [root_id],
+        )?;
+        Ok(())
+    }
+
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            comparison_id: row.get(0)?,
+            task_id: row.get(1)?,
+            left: ComparisonSide {
+                root_id: row.get(2)?,
+                scan_id: row.get(3)?,
+                path: row.get(4)?,
+            },
+            right: ComparisonSide {
+                root_id: row.get(5)?,
+                scan_id: row.get(6)?,
+                path: row.get(7)?,
+            },
+            started_at: row.get(8)?,
+            completed_at: row.get(9)?,
+            counts: ComparisonCounts {
+                match_count: row.get(10)?,
+                unverified_count: row.get(11)?,
+                left_only_count: row.get(12)?,
+                right_only_count: row.get(13)?,
+                size_diff_count: row.get(14)?,
+                hash_diff_count: row.get(15)?,
+                mod_date_diff_count: row.get(16)?,
+            },
+        })
+    }
+
+    /// Get a completed report
+    pub fn get_by_id(conn: &Connection, comparison_id: i64) -> Result<Option<Self>, FsPulseError> {
+        let sql = format!(
+            "SELECT {COMPARISON_COLUMNS} FROM comparisons
+             WHERE comparison_id = ? AND completed_at IS NOT NULL"
+        );
+        Ok(conn
+            .query_row(&sql, [comparison_id], Self::from_row)
+            .optional()?)
+    }
+
+    /// Count completed reports, optionally only those involving a root
+    pub fn count(conn: &Connection, root_id: Option<i64>) -> Result<i64, FsPulseError> {
+        Ok(conn.query_row(
+            "SELECT COUNT(*) FROM comparisons
+             WHERE completed_at IS NOT NULL
+               AND (?1 IS NULL OR left_root_id = ?1 OR right_root_id = ?1)",
+            [root_id],
+            |row| row.get(0),
+        )?)
+    }
+
+    /// Completed reports, newest first, optionally only those involving a root
+    pub fn list(
+        conn: &Connection,
+        root_id: Option<i64>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Self>, FsPulseError> {
+        let sql = format!(
+            "SELECT {COMPARISON_COLUMNS} FROM comparisons
+             WHERE completed_at IS NOT NULL
+               AND (?1 IS NULL OR left_root_id = ?1 OR right_root_id = ?1)
+             ORDER BY comparison_id DESC
+             LIMIT ?2 OFFSET ?3"
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params![root_id, limit, offset], Self::from_row)?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// Count a report's differences, optionally of one kind or with a path
+    /// containing `path_search`
+    pub fn count_diffs(
+        conn: &Connection,
+        comparison_id: i64,
+        diff_kind: Option<DiffKind>,
+        path_search: Option<&str>,
+    ) -> Result<i64, FsPulseError> {
+        Ok(conn.query_row(
+            "SELECT COUNT(*) FROM comparison_diffs
+             WHERE comparison_id = ?1
+               AND (?2 IS NULL OR diff_kind = ?2)
+               AND (?3 IS NULL OR rel_path LIKE '%' || ?3 || '%')",
+            params![comparison_id, diff_kind.map(DiffKind::as_i64), path_search],
+            |row| row.get(0),
+        )?)
+    }
+
+    /// A page of a report's differences in path order, filtered as for `count_diffs`
+    pub fn get_diffs(
+        conn: &Connection,
+        comparison_id: i64,
+        diff_kind: Option<DiffKind>,
+        path_search: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<ComparisonDiff>, FsPulseError> {
+        let mut stmt = conn.prepare(
+            "SELECT rel_path, diff_kind, left_item_id, right_item_id, left_size, right_size,
+                    left_mod_date, right_mod_date, left_hash, right_hash
+             FROM comparison_diffs
+             WHERE comparison_id = ?1
+               AND (?2 IS NULL OR diff_kind = ?2)
+               AND (?3 IS NULL OR rel_path LIKE '%' || ?3 || '%')
+             ORDER BY rel_path
+             LIMIT ?4 OFFSET ?5",
+        )?;
+        let rows = stmt.query_map(
+            params![comparison_id, diff_kind.map(DiffKind::as_i64), path_search, limit, offset],
+            |row| {
+                Ok(ComparisonDiff {
+                    rel_path: row.get(0)?,
+                    diff_kind: DiffKind::from_i64(row.get(1)?),
+                    left_item_id: row.get(2)?,
+                    right_item_id: row.get(3)?,
+                    left_size: row.get(4)?,
+                    right_size: row.get(5)?,
+                    left_mod_date: row.get(6)?,
+                    right_mod_date: row.get(7)?,
+                    left_hash: Hash::opt_blob_to_hex(row.get(8)?),
+                    right_hash: Hash::opt_blob_to_hex(row.get(9)?),
+                })
+            },
+        )?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(size: i64, mod_date: i64, hash: Option<(HashAlgo, &[u8])>) -> SideFile {
+        SideFile {
+            rel_path: "a.txt".to_string(),
+            item_id: 1,
+            size: Some(size),
+            mod_date: Some(mod_date),
+            hash: hash.map(|(algo, h)| (algo, h.to_vec())),
+        }
+    }
+
+    #[test]
+    fn test_classify_match_and_unverified() {
+        let sha = |h: &'static [u8]| Some((HashAlgo::Sha256, h));
+        assert_eq!(classify(&file(5, 100, sha(b"aa")), &file(5, 100, sha(b"aa"))), Outcome::Match);
+        assert_eq!(classify(&file(5, 100, None), &file(5, 100, sha(b"aa"))), Outcome::Unverified);
+        // Hashes from different algorithms can't confirm the contents
+        assert_eq!(
+            classify(&file(5, 100, sha(b"aa")), &file(5, 100, Some((HashAlgo::Blake3, b"bb")))),
+            Outcome::Unverified
+        );
+    }
+
+    #[test]
+    fn test_classify_precedence() {
+        let sha = |h: &'static [u8]| Some((HashAlgo::Sha256, h));
+        assert_eq!(
+            classify(&file(5, 100, sha(b"aa")), &file(6, 200, sha(b"bb"))),
+            Outcome::Differs(DiffKind::Size)
+        );
+        assert_eq!(
+            classify(&file(5, 100, sha(b"aa")), &file(5, 200, sha(b"bb"))),
+            Outcome::Differs(DiffKind::Hash)
+        );
+        assert_eq!(
+            classify(&file(5, 100, sha(b"aa")), &file(5, 200, sha(b"aa"))),
+            Outcome::Differs(DiffKind::ModDate)
+        );
+    }
+
+    #[test]
+    fn test_diff_kind_round_trip() {
+        for kind in [
+            DiffKind::LeftOnly,
+            DiffKind::RightOnly,
+            DiffKind::Size,
+            DiffKind::Hash,
+            DiffKind::ModDate,
+        ] {
+            assert_eq!(DiffKind::from_i64(kind.as_i64()), kind);
+            let json = serde_json::to_string(&kind).unwrap();
+            assert_eq!(DiffKind::from_string(json.trim_matches('"')), Some(kind));
+        }
+        assert_eq!(DiffKind::from_string("bogus"), None);
+    }
+
+    #[test]
+    fn test_counts_add() {
+        let mut counts = ComparisonCounts::default();
+        counts.add(DiffKind::LeftOnly);
+        counts.add(DiffKind::Hash);
+        counts.add(DiffKind::Hash);
+        assert_eq!(counts.left_only_count, 1);
+        assert_eq!(counts.hash_diff_count, 2);
+        assert_eq!(counts.diff_count(), 3);
+    }
+}
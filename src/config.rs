@@ -13,6 +13,7 @@ use once_cell::sync::OnceCell;
 use serde::Serialize;
 
 use crate::error::FsPulseError;
+use crate::run_window::RunWindow;
 
 // =============================================================================
 // Constants
@@ -24,6 +25,24 @@ pub const MIN_ANALYSIS_THREADS: usize = 1;
 /// Maximum number of analysis threads
 pub const MAX_ANALYSIS_THREADS: usize = 24;
 
+/// Minimum number of blocks sampled between the head and tail of a file
+pub const MIN_SAMPLE_BLOCKS: usize = 0;
+
+/// Maximum number of blocks sampled between the head and tail of a file
+pub const MAX_SAMPLE_BLOCKS: usize = 64;
+
+/// Maximum analysis read limit in megabytes per second (0 is unlimited)
+pub const MAX_READ_LIMIT_MB_PER_SEC: usize = 100_000;
+
+/// Maximum analysis read limit in reads per second (0 is unlimited)
+pub const MAX_READ_LIMIT_IOPS: usize = 1_000_000;
+
+/// Minimum number of scan (directory walk) threads
+pub const MIN_SCAN_THREADS: usize = 1;
+
+/// Maximum number of scan (directory walk) threads
+pub const MAX_SCAN_THREADS: usize = 24;
+
 // =============================================================================
 // Global Configuration State
 // =============================================================================
@@ -251,13 +270,21 @@ pub struct Config {
     pub server_host: ConfigValue<String>,
     pub server_port: ConfigValue<u16>,
     pub analysis_threads: ConfigValue<usize>,
+    pub analysis_sample_blocks: ConfigValue<usize>,
+    pub analysis_read_limit_mb_per_sec: ConfigValue<usize>,
+    pub analysis_read_limit_iops: ConfigValue<usize>,
+    pub scan_threads: ConfigValue<usize>,
+    pub tasks_run_window: ConfigValue<String>,
     pub logging_fspulse: ConfigValue<String>,
     pub logging_lopdf: ConfigValue<String>,
     pub database_dir: ConfigValue<String>,
     pub mcp_enabled: ConfigValue<bool>,
     pub validation_images: ConfigValue<bool>,
+    pub validation_video: ConfigValue<bool>,
     pub validation_pdf: ConfigValue<bool>,
     pub validation_audio: ConfigValue<bool>,
+    pub validation_archives: ConfigValue<bool>,
+    pub validation_prefer_content: ConfigValue<bool>,
 }
 
 // =============================================================================
@@ -325,6 +352,76 @@ fn validate_threads(value: &toml::Value, source: ConfigSource) -> Result<usize,
     Ok(threads)
 }
 
+fn validate_sample_blocks(value: &toml::Value, source: ConfigSource) -> Result<usize, FsPulseError> {
+    let blocks = extract_usize(value).map_err(|e| {
+        FsPulseError::ConfigError(format!("analysis.sample_blocks {}, from {:?}", e, source))
+    })?;
+
+    if !(MIN_SAMPLE_BLOCKS..=MAX_SAMPLE_BLOCKS).contains(&blocks) {
+        return Err(FsPulseError::ConfigError(format!(
+            "analysis.sample_blocks must be between {} and {}, got {} from {:?}",
+            MIN_SAMPLE_BLOCKS, MAX_SAMPLE_BLOCKS, blocks, source
+        )));
+    }
+    Ok(blocks)
+}
+
+fn validate_read_limit_mb_per_sec(
+    value: &toml::Value,
+    source: ConfigSource,
+) -> Result<usize, FsPulseError> {
+    let limit = extract_usize(value).map_err(|e| {
+        FsPulseError::ConfigError(format!("analysis.read_limit_mb_per_sec {}, from {:?}", e, source))
+    })?;
+
+    if limit > MAX_READ_LIMIT_MB_PER_SEC {
+        return Err(FsPulseError::ConfigError(format!(
+            "analysis.read_limit_mb_per_sec must be between 0 and {}, got {} from {:?}",
+            MAX_READ_LIMIT_MB_PER_SEC, limit, source
+        )));
+    }
+    Ok(limit)
+}
+
+fn validate_read_limit_iops(value: &toml::Value, source: ConfigSource) -> Result<usize, FsPulseError> {
+    let limit = extract_usize(value).map_err(|e| {
+        FsPulseError::ConfigError(format!("analysis.read_limit_iops {}, from {:?}", e, source))
+    })?;
+
+    if limit > MAX_READ_LIMIT_IOPS {
+        return Err(FsPulseError::ConfigError(format!(
+            "analysis.read_limit_iops must be between 0 and {}, got {} from {:?}",
+            MAX_READ_LIMIT_IOPS, limit, source
+        )));
+    }
+    Ok(limit)
+}
+
+fn validate_scan_threads(value: &toml::Value, source: ConfigSource) -> Result<usize, FsPulseError> {
+    let threads = extract_usize(value).map_err(|e| {
+        FsPulseError::ConfigError(format!("scan.threads {}, from {:?}", e, source))
+    })?;
+
+    if !(MIN_SCAN_THREADS..=MAX_SCAN_THREADS).contains(&threads) {
+        return Err(FsPulseError::ConfigError(format!(
+            "scan.threads must be between {} and {}, got {} from {:?}",
+            MIN_SCAN_THREADS, MAX_SCAN_THREADS, threads, source
+        )));
+    }
+    Ok(threads)
+}
+
+fn validate_run_window(value: &toml::Value, source: ConfigSource) -> Result<String, FsPulseError> {
+    let window = extract_string(value);
+    // Empty string is valid - it means "always open"
+    if !window.is_empty() {
+        RunWindow::parse(&window).map_err(|e| {
+            FsPulseError::ConfigError(format!("tasks.run_window: {}, from {:?}", e, source))
+        })?;
+    }
+    Ok(window)
+}
+
 fn validate_port(value: &toml::Value, source: ConfigSource) -> Result<u16, FsPulseError> {
     let port = extract_u16(value)
         .map_err(|e| FsPulseError::ConfigError(format!("server.port {}, from {:?}", e, source)))?;
@@ -459,8 +556,17 @@ fn create_default_config_file(config_path: &PathBuf) -> Result<(), FsPulseError>
 # host = "0.0.0.0"    # Default: "127.0.0.1"
 # port = 8080          # Default: 8080
 #
+# [scan]
+# threads = 4          # Default: 4 (range: 1-24)
+#
+# [tasks]
+# run_window = ""      # Default: "" (always; "HH:MM-HH:MM" limits scheduled and watch tasks to that local time)
+#
 # [analysis]
 # threads = 8          # Default: 8 (range: 1-24)
+# sample_blocks = 8    # Default: 8 (range: 0-64, blocks between head and tail in Sampled scans)
+# read_limit_mb_per_sec = 0  # Default: 0 (unlimited; MB/s read by all analysis threads together)
+# read_limit_iops = 0        # Default: 0 (unlimited; reads/s by all analysis threads together)
 #
 # [logging]
 # fspulse = "info"     # Default: "info" (error, warn, info, debug, trace)
@@ -472,8 +578,11 @@ fn create_default_config_file(config_path: &PathBuf) -> Result<(), FsPulseError>
 #
 # [validation]
 # images = true        # Default: true (validate JPG, PNG, GIF, TIFF, BMP)
+# video = true         # Default: true (check MP4, MOV and MKV/WebM container structure)
 # pdf = false          # Default: false (validate PDF files - experimental)
-# audio = true         # Default: true (validate FLAC files)
+# audio = true         # Default: true (validate FLAC, MP3, Ogg, WAV, AIFF and M4A files)
+# archives = true      # Default: true (validate ZIP, TAR, gzip, xz, zstd and 7z archives)
+# prefer_content = false  # Default: false (choose validators by extension, falling back to detected content)
 "#;
 
     fs::write(config_path, template)
@@ -576,6 +685,31 @@ impl Default for Config {
             ),
             server_port: ConfigValue::new(8080, ("server", "port"), true, validate_port),
             analysis_threads: ConfigValue::new(8, ("analysis", "threads"), false, validate_threads),
+            analysis_sample_blocks: ConfigValue::new(
+                8,
+                ("analysis", "sample_blocks"),
+                false,
+                validate_sample_blocks,
+            ),
+            analysis_read_limit_mb_per_sec: ConfigValue::new(
+                0,
+                ("analysis", "read_limit_mb_per_sec"),
+                false,
+                validate_read_limit_mb_per_sec,
+            ),
+            analysis_read_limit_iops: ConfigValue::new(
+                0,
+                ("analysis", "read_limit_iops"),
+                false,
+                validate_read_limit_iops,
+            ),
+            scan_threads: ConfigValue::new(4, ("scan", "threads"), false, validate_scan_threads),
+            tasks_run_window: ConfigValue::new(
+                String::new(), // Empty string = always open
+                ("tasks", "run_window"),
+                false,
+                validate_run_window,
+            ),
             logging_fspulse: ConfigValue::new(
                 "info".to_string(),
                 ("logging", "fspulse"),
@@ -596,8 +730,11 @@ impl Default for Config {
             ),
             mcp_enabled: ConfigValue::new(false, ("mcp", "enabled"), true, validate_bool),
             validation_images: ConfigValue::new(true, ("validation", "images"), false, validate_bool),
+            validation_video: ConfigValue::new(true, ("validation", "video"), false, validate_bool),
             validation_pdf: ConfigValue::new(false, ("validation", "pdf"), false, validate_bool),
             validation_audio: ConfigValue::new(true, ("validation", "audio"), false, validate_bool),
+            validation_archives: ConfigValue::new(true, ("validation", "archives"), false, validate_bool),
+            validation_prefer_content: ConfigValue::new(false, ("validation", "prefer_content"), false, validate_bool),
         }
     }
 }
@@ -616,7 +753,11 @@ impl Config {
     /// then falls back to OS-specific directories.
     pub fn load_config(project_dirs: &ProjectDirs) -> Result<(), FsPulseError> {
         // Step 1: Load ENV map from Figment
-        let env_figment = Figment::from(Env::prefixed("FSPULSE_").split("_"));
+        // Only the first underscore separates the section from the field, so
+        // FSPULSE_ANALYSIS_SAMPLE_BLOCKS is [analysis] sample_blocks
+        let env_figment = Figment::from(
+            Env::prefixed("FSPULSE_").map(|key| key.as_str().replacen('_', ".", 1).into()),
+        );
         let mut env_map = match env_figment.extract::<toml::Value>() {
             Ok(toml::Value::Table(table)) => table,
             Ok(_) => toml::map::Map::new(), // Shouldn't happen but handle gracefully
@@ -677,13 +818,21 @@ impl Config {
         config.server_host.take(&mut toml_map, &mut env_map)?;
         config.server_port.take(&mut toml_map, &mut env_map)?;
         config.analysis_threads.take(&mut toml_map, &mut env_map)?;
+        config.analysis_sample_blocks.take(&mut toml_map, &mut env_map)?;
+        config.analysis_read_limit_mb_per_sec.take(&mut toml_map, &mut env_map)?;
+        config.analysis_read_limit_iops.take(&mut toml_map, &mut env_map)?;
+        config.scan_threads.take(&mut toml_map, &mut env_map)?;
+        config.tasks_run_window.take(&mut toml_map, &mut env_map)?;
         config.logging_fspulse.take(&mut toml_map, &mut env_map)?;
         config.logging_lopdf.take(&mut toml_map, &mut env_map)?;
         config.database_dir.take(&mut toml_map, &mut env_map)?;
         config.mcp_enabled.take(&mut toml_map, &mut env_map)?;
         config.validation_images.take(&mut toml_map, &mut env_map)?;
+        config.validation_video.take(&mut toml_map, &mut env_map)?;
         config.validation_pdf.take(&mut toml_map, &mut env_map)?;
         config.validation_audio.take(&mut toml_map, &mut env_map)?;
+        config.validation_archives.take(&mut toml_map, &mut env_map)?;
+        config.validation_prefer_content.take(&mut toml_map, &mut env_map)?;
 
         // Step 6: Check for unknown keys
         check_for_unknown_keys(&toml_map, &env_map)?;
@@ -791,6 +940,135 @@ impl Config {
         Self::with_config_write(|config| config.analysis_threads.delete_file_value(&config_path))
     }
 
+    // Analysis Sample Blocks
+
+    pub fn get_analysis_sample_blocks() -> usize {
+        Self::with_config_read(|config| *config.analysis_sample_blocks.get())
+    }
+
+    pub fn get_analysis_sample_blocks_value() -> ConfigValue<usize> {
+        Self::with_config_read(|config| config.analysis_sample_blocks.clone())
+    }
+
+    pub fn set_analysis_sample_blocks(
+        blocks: usize,
+        project_dirs: &ProjectDirs,
+    ) -> Result<(), FsPulseError> {
+        let config_path = get_config_path(project_dirs);
+        Self::with_config_write(|config| {
+            config
+                .analysis_sample_blocks
+                .set_file_value(blocks, &config_path)
+        })
+    }
+
+    pub fn delete_analysis_sample_blocks(project_dirs: &ProjectDirs) -> Result<(), FsPulseError> {
+        let config_path = get_config_path(project_dirs);
+        Self::with_config_write(|config| {
+            config.analysis_sample_blocks.delete_file_value(&config_path)
+        })
+    }
+
+    // Analysis Read Limits
+
+    pub fn get_analysis_read_limit_mb_per_sec() -> usize {
+        Self::with_config_read(|config| *config.analysis_read_limit_mb_per_sec.get())
+    }
+
+    pub fn get_analysis_read_limit_mb_per_sec_value() -> ConfigValue<usize> {
+        Self::with_config_read(|config| config.analysis_read_limit_mb_per_sec.clone())
+    }
+
+    pub fn set_analysis_read_limit_mb_per_sec(
+        limit: usize,
+        project_dirs: &ProjectDirs,
+    ) -> Result<(), FsPulseError> {
+        let config_path = get_config_path(project_dirs);
+        Self::with_config_write(|config| {
+            config
+                .analysis_read_limit_mb_per_sec
+                .set_file_value(limit, &config_path)
+        })
+    }
+
+    pub fn delete_analysis_read_limit_mb_per_sec(
+        project_dirs: &ProjectDirs,
+    ) -> Result<(), FsPulseError> {
+        let config_path = get_config_path(project_dirs);
+        Self::with_config_write(|config| {
+            config
+                .analysis_read_limit_mb_per_sec
+                .delete_file_value(&config_path)
+        })
+    }
+
+    pub fn get_analysis_read_limit_iops() -> usize {
+        Self::with_config_read(|config| *config.analysis_read_limit_iops.get())
+    }
+
+    pub fn get_analysis_read_limit_iops_value() -> ConfigValue<usize> {
+        Self::with_config_read(|config| config.analysis_read_limit_iops.clone())
+    }
+
+    pub fn set_analysis_read_limit_iops(
+        limit: usize,
+        project_dirs: &ProjectDirs,
+    ) -> Result<(), FsPulseError> {
+        let config_path = get_config_path(project_dirs);
+        Self::with_config_write(|config| {
+            config
+                .analysis_read_limit_iops
+                .set_file_value(limit, &config_path)
+        })
+    }
+
+    pub fn delete_analysis_read_limit_iops(project_dirs: &ProjectDirs) -> Result<(), FsPulseError> {
+        let config_path = get_config_path(project_dirs);
+        Self::with_config_write(|config| {
+            config.analysis_read_limit_iops.delete_file_value(&config_path)
+        })
+    }
+
+    // Scan Threads
+
+    pub fn get_scan_threads() -> usize {
+        Self::with_config_read(|config| *config.scan_threads.get())
+    }
+
+    pub fn get_scan_threads_value() -> ConfigValue<usize> {
+        Self::with_config_read(|config| config.scan_threads.clone())
+    }
+
+    pub fn set_scan_threads(threads: usize, project_dirs: &ProjectDirs) -> Result<(), FsPulseError> {
+        let config_path = get_config_path(project_dirs);
+        Self::with_config_write(|config| config.scan_threads.set_file_value(threads, &config_path))
+    }
+
+    pub fn delete_scan_threads(project_dirs: &ProjectDirs) -> Result<(), FsPulseError> {
+        let config_path = get_config_path(project_dirs);
+        Self::with_config_write(|config| config.scan_threads.delete_file_value(&config_path))
+    }
+
+    // Tasks Run Window
+
+    pub fn get_tasks_run_window() -> String {
+        Self::with_config_read(|config| config.tasks_run_window.get().clone())
+    }
+
+    pub fn get_tasks_run_window_value() -> ConfigValue<String> {
+        Self::with_config_read(|config| config.tasks_run_window.clone())
+    }
+
+    pub fn set_tasks_run_window(window: String, project_dirs: &ProjectDirs) -> Result<(), FsPulseError> {
+        let config_path = get_config_path(project_dirs);
+        Self::with_config_write(|config| config.tasks_run_window.set_file_value(window, &config_path))
+    }
+
+    pub fn delete_tasks_run_window(project_dirs: &ProjectDirs) -> Result<(), FsPulseError> {
+        let config_path = get_config_path(project_dirs);
+        Self::with_config_write(|config| config.tasks_run_window.delete_file_value(&config_path))
+    }
+
     // Logging fsPulse
 
     pub fn get_logging_fspulse() -> String {
@@ -897,6 +1175,26 @@ impl Config {
         Self::with_config_write(|config| config.validation_images.delete_file_value(&config_path))
     }
 
+    // Validation Video
+
+    pub fn get_validation_video() -> bool {
+        Self::with_config_read(|config| *config.validation_video.get())
+    }
+
+    pub fn get_validation_video_value() -> ConfigValue<bool> {
+        Self::with_config_read(|config| config.validation_video.clone())
+    }
+
+    pub fn set_validation_video(val: bool, project_dirs: &ProjectDirs) -> Result<(), FsPulseError> {
+        let config_path = get_config_path(project_dirs);
+        Self::with_config_write(|config| config.validation_video.set_file_value(val, &config_path))
+    }
+
+    pub fn delete_validation_video(project_dirs: &ProjectDirs) -> Result<(), FsPulseError> {
+        let config_path = get_config_path(project_dirs);
+        Self::with_config_write(|config| config.validation_video.delete_file_value(&config_path))
+    }
+
     // Validation PDF
 
     pub fn get_validation_pdf() -> bool {
@@ -937,6 +1235,63 @@ impl Config {
         Self::with_config_write(|config| config.validation_audio.delete_file_value(&config_path))
     }
 
+    // Validation Archives
+
+    pub fn get_validation_archives() -> bool {
+        Self::with_config_read(|config| *config.validation_archives.get())
+    }
+
+    pub fn get_validation_archives_value() -> ConfigValue<bool> {
+        Self::with_config_read(|config| config.validation_archives.clone())
+    }
+
+    pub fn set_validation_archives(val: bool, project_dirs: &ProjectDirs) -> Result<(), FsPulseError> {
+        let config_path = get_config_path(project_dirs);
+        Self::with_config_write(|config| config.validation_archives.set_file_value(val, &config_path))
+    }
+
+    pub fn delete_validation_archives(project_dirs: &ProjectDirs) -> Result<(), FsPulseError> {
+        let config_path = get_config_path(project_dirs);
+        Self::with_config_write(|config| config.validation_archives.delete_file_value(&config_path))
+    }
+
+    // Validation Prefer Content
+
+    pub fn get_validation_prefer_content() -> bool {
+        Self::with_config_read(|config| *config.validation_prefer_content.get())
+    }
+
+    pub fn get_validation_prefer_content_value() -> ConfigValue<bool> {
+        Self::with_config_read(|config| config.validation_prefer_content.clone())
+    }
+
+    pub fn set_validation_prefer_content(val: bool, project_dirs: &ProjectDirs) -> Result<(), FsPulseError> {
+        let config_path = get_config_path(project_dirs);
+        Self::with_config_write(|config| config.validation_prefer_content.set_file_value(val, &config_path))
+    }
+
+    pub fn delete_validation_prefer_content(project_dirs: &ProjectDirs) -> Result<(), FsPulseError> {
+        let config_path = get_config_path(project_dirs);
+        Self::with_config_write(|config| config.validation_prefer_content.delete_file_value(&config_path))
+    }
+
+    /// The getter for a `[validation]` key, or None if there's no such key
+    pub fn validation_getter(key: &str) -> Option<fn() -> bool> {
+        match key {
+            "images" => Some(Self::get_validation_images),
+            "video" => Some(Self::get_validation_video),
+            "pdf" => Some(Self::get_validation_pdf),
+            "audio" => Some(Self::get_validation_audio),
+            "archives" => Some(Self::get_validation_archives),
+            _ => None,
+        }
+    }
+
+    /// Whether a `[validation]` key is enabled, or None if there's no such key
+    pub fn get_validation_enabled(key: &str) -> Option<bool> {
+        Self::validation_getter(key).map(|get| get())
+    }
+
     // Data Directory (special, not a ConfigValue - read-only)
 
     pub fn get_data_dir() -> String {
@@ -1051,6 +1406,11 @@ mod tests {
             assert_eq!(Config::get_server_host(), "127.0.0.1");
             assert_eq!(Config::get_server_port(), 8080);
             assert_eq!(Config::get_analysis_threads(), 8);
+            assert_eq!(Config::get_analysis_sample_blocks(), 8);
+            assert_eq!(Config::get_analysis_read_limit_mb_per_sec(), 0);
+            assert_eq!(Config::get_analysis_read_limit_iops(), 0);
+            assert_eq!(Config::get_scan_threads(), 4);
+            assert_eq!(Config::get_tasks_run_window(), "");
             assert_eq!(Config::get_logging_fspulse(), "info");
             assert_eq!(Config::get_logging_lopdf(), "error");
             assert_eq!(Config::get_database_dir(), "");
@@ -1124,6 +1484,29 @@ unknown_field = "value"
         });
     }
 
+    #[test]
+    #[serial]
+    fn test_load_config_env_var_with_underscored_field() {
+        Jail::expect_with(|jail| {
+            // Tests run serially to avoid CONFIG conflicts
+
+            let dir = jail.directory().to_str().unwrap().to_string();
+            jail.set_env("FSPULSE_DATA_DIR", &dir);
+            // Out of range, so the load fails on validation rather than on an
+            // unknown key, without initializing the global config
+            jail.set_env("FSPULSE_ANALYSIS_SAMPLE_BLOCKS", "999");
+
+            let project_dirs = ProjectDirs::from("", "", "fspulse-test").unwrap();
+            let result = Config::load_config(&project_dirs);
+
+            assert!(result.is_err());
+            let err_msg = format!("{}", result.unwrap_err());
+            assert!(err_msg.contains("analysis.sample_blocks must be between"), "{err_msg}");
+
+            Ok(())
+        });
+    }
+
     #[test]
     #[serial]
     fn test_validation_threads_range() {
@@ -1159,6 +1542,26 @@ threads = 100
         });
     }
 
+    #[test]
+    #[serial]
+    fn test_validation_run_window() {
+        Jail::expect_with(|jail| {
+            jail.create_file("config.toml", r#"
+[tasks]
+run_window = "22:00-30:00"
+"#)?;
+
+            let project_dirs = ProjectDirs::from("", "", "fspulse-test").unwrap();
+            let dir = jail.directory().to_str().unwrap().to_string(); jail.set_env("FSPULSE_DATA_DIR", &dir);
+
+            let result = Config::load_config(&project_dirs);
+            assert!(result.is_err());
+            assert!(format!("{}", result.unwrap_err()).contains("tasks.run_window"));
+
+            Ok(())
+        });
+    }
+
     #[test]
     #[serial]
     fn test_validation_log_level() {
@@ -7,7 +7,7 @@ use schema::{
     Migration, CREATE_SCHEMA_SQL, MIGRATION_10_TO_11, MIGRATION_11_TO_12, MIGRATION_12_TO_13,
     MIGRATION_13_TO_14, MIGRATION_14_TO_15, MIGRATION_15_TO_16, MIGRATION_16_TO_17,
     MIGRATION_17_TO_18, MIGRATION_18_TO_19, MIGRATION_19_TO_20, MIGRATION_20_TO_21,
-    MIGRATION_21_TO_22, MIGRATION_22_TO_23, MIGRATION_23_TO_24, MIGRATION_24_TO_25, MIGRATION_25_TO_26, MIGRATION_26_TO_27, MIGRATION_27_TO_28, MIGRATION_28_TO_29, MIGRATION_29_TO_30, MIGRATION_30_TO_31, MIGRATION_2_TO_3, MIGRATION_3_TO_4, MIGRATION_4_TO_5,
+    MIGRATION_21_TO_22, MIGRATION_22_TO_23, MIGRATION_23_TO_24, MIGRATION_24_TO_25, MIGRATION_25_TO_26, MIGRATION_26_TO_27, MIGRATION_27_TO_28, MIGRATION_28_TO_29, MIGRATION_29_TO_30, MIGRATION_30_TO_31, MIGRATION_31_TO_32, MIGRATION_32_TO_33, MIGRATION_33_TO_34, MIGRATION_34_TO_35, MIGRATION_35_TO_36, MIGRATION_36_TO_37, MIGRATION_37_TO_38, MIGRATION_38_TO_39, MIGRATION_39_TO_40, MIGRATION_40_TO_41, MIGRATION_41_TO_42, MIGRATION_42_TO_43, MIGRATION_43_TO_44, MIGRATION_44_TO_45, MIGRATION_45_TO_46, MIGRATION_46_TO_47, MIGRATION_2_TO_3, MIGRATION_3_TO_4, MIGRATION_4_TO_5,
     MIGRATION_5_TO_6, MIGRATION_6_TO_7, MIGRATION_7_TO_8, MIGRATION_8_TO_9, MIGRATION_9_TO_10,
 };
 use log::{error, info};
@@ -23,7 +23,7 @@ use std::time::Duration;
 use std::env;
 
 const DB_FILENAME: &str = "fspulse.db";
-const CURRENT_SCHEMA_VERSION: u32 = 31;
+const CURRENT_SCHEMA_VERSION: u32 = 47;
 
 // Connection pool configuration
 const POOL_MAX_SIZE: u32 = 15;
@@ -546,6 +546,22 @@ fn run_migrations(conn: &Connection, from_version: u32, total_steps: u32) -> Res
             28 => upgrade_schema(conn, db_version, &MIGRATION_28_TO_29, step, total_steps)?,
             29 => upgrade_schema(conn, db_version, &MIGRATION_29_TO_30, step, total_steps)?,
             30 => upgrade_schema(conn, db_version, &MIGRATION_30_TO_31, step, total_steps)?,
+            31 => upgrade_schema(conn, db_version, &MIGRATION_31_TO_32, step, total_steps)?,
+            32 => upgrade_schema(conn, db_version, &MIGRATION_32_TO_33, step, total_steps)?,
+            33 => upgrade_schema(conn, db_version, &MIGRATION_33_TO_34, step, total_steps)?,
+            34 => upgrade_schema(conn, db_version, &MIGRATION_34_TO_35, step, total_steps)?,
+            35 => upgrade_schema(conn, db_version, &MIGRATION_35_TO_36, step, total_steps)?,
+            36 => upgrade_schema(conn, db_version, &MIGRATION_36_TO_37, step, total_steps)?,
+            37 => upgrade_schema(conn, db_version, &MIGRATION_37_TO_38, step, total_steps)?,
+            38 => upgrade_schema(conn, db_version, &MIGRATION_38_TO_39, step, total_steps)?,
+            39 => upgrade_schema(conn, db_version, &MIGRATION_39_TO_40, step, total_steps)?,
+            40 => upgrade_schema(conn, db_version, &MIGRATION_40_TO_41, step, total_steps)?,
+            41 => upgrade_schema(conn, db_version, &MIGRATION_41_TO_42, step, total_steps)?,
+            42 => upgrade_schema(conn, db_version, &MIGRATION_42_TO_43, step, total_steps)?,
+            43 => upgrade_schema(conn, db_version, &MIGRATION_43_TO_44, step, total_steps)?,
+            44 => upgrade_schema(conn, db_version, &MIGRATION_44_TO_45, step, total_steps)?,
+            45 => upgrade_schema(conn, db_version, &MIGRATION_45_TO_46, step, total_steps)?,
+            46 => upgrade_schema(conn, db_version, &MIGRATION_46_TO_47, step, total_steps)?,
             _ => {
                 let msg = format!(
                     "No migration path from schema v{} to v{}",
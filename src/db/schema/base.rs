@@ -55,17 +55,44 @@ CREATE TABLE IF NOT EXISTS meta (
     value TEXT NOT NULL
 );
 
-INSERT OR REPLACE INTO meta (key, value) VALUES ('schema_version', '31');
+INSERT OR REPLACE INTO meta (key, value) VALUES ('schema_version', '47');
 
 -- Roots table stores unique root directories that have been scanned
 CREATE TABLE IF NOT EXISTS roots (
     root_id INTEGER PRIMARY KEY AUTOINCREMENT,
-    root_path TEXT NOT NULL UNIQUE
+    root_path TEXT NOT NULL UNIQUE,
+    -- Don't descend into directories on a different filesystem than the root
+    one_file_system BOOLEAN NOT NULL DEFAULT 0,
+    -- Descend into symlinked directories, with loop detection
+    follow_symlinks BOOLEAN NOT NULL DEFAULT 0,
+    -- Maximum number of nested symlinks followed along any one path
+    max_symlink_depth INTEGER NOT NULL DEFAULT 8,
+    -- Watch for changes and queue scans of the changed directories
+    watch BOOLEAN NOT NULL DEFAULT 0,
+    -- Algorithm used to hash files: 0=SHA-256, 1=BLAKE3, 2=XXH3-128, 3=SHA-512
+    hash_algo INTEGER NOT NULL DEFAULT 0
 );
 
 -- Indexes to optimize queries
 CREATE INDEX IF NOT EXISTS idx_roots_path ON roots (root_path COLLATE natural_path);
 
+-- Root rules: per-root include/exclude patterns applied during the scan walk.
+-- Rules are evaluated in rule_order. An entry is excluded when at least one
+-- exclude rule matches it and no include rule does. Excluded directories are
+-- not descended into.
+CREATE TABLE IF NOT EXISTS root_rules (
+    rule_id      INTEGER PRIMARY KEY AUTOINCREMENT,
+    root_id      INTEGER NOT NULL,
+    rule_order   INTEGER NOT NULL,
+    rule_action  INTEGER NOT NULL CHECK(rule_action IN (0, 1)),   -- 0=exclude, 1=include
+    pattern_type INTEGER NOT NULL CHECK(pattern_type IN (0, 1)),  -- 0=glob, 1=regex
+    match_target INTEGER NOT NULL CHECK(match_target IN (0, 1)),  -- 0=root-relative path, 1=name
+    pattern      TEXT NOT NULL,
+    FOREIGN KEY (root_id) REFERENCES roots(root_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_root_rules_root ON root_rules (root_id, rule_order);
+
 -- Scans table tracks individual scan sessions
 CREATE TABLE IF NOT EXISTS scans (
     scan_id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -94,6 +121,8 @@ CREATE TABLE IF NOT EXISTS scans (
     hash_baseline_count INTEGER DEFAULT NULL,       -- Count of files with baseline (unchanged) hash state
     hash_suspect_count INTEGER DEFAULT NULL,    -- Count of files with suspicious (changed) hash state
     error TEXT DEFAULT NULL,           -- Error message if scan failed
+    move_count INTEGER DEFAULT NULL,   -- Count of items moved or renamed in the scan (not counted as adds/deletes)
+    hash_sampled BOOLEAN NOT NULL DEFAULT 0, -- Sample files that already have a hash, fully hashing any whose sample changed
     FOREIGN KEY (root_id) REFERENCES roots(root_id),
     FOREIGN KEY (schedule_id) REFERENCES scan_schedules(schedule_id)
 );
@@ -136,9 +165,32 @@ CREATE TABLE IF NOT EXISTS item_versions (
     -- Shared fields (all item types)
     is_added        BOOLEAN NOT NULL DEFAULT 0,
     is_deleted      BOOLEAN NOT NULL DEFAULT 0,
-    access          INTEGER NOT NULL DEFAULT 0,
+    access          INTEGER NOT NULL DEFAULT 0,   -- 0=Ok, 1=MetaError, 2=ReadError, 3=MountPoint
     mod_date        INTEGER,
     size            INTEGER,
+    symlink_target  TEXT,               -- link target as stored in the symlink (symlinks only)
+
+    -- Unix metadata (NULL on non-Unix platforms and for versions scanned before
+    -- it was tracked). A change to any of these creates a new version.
+    mode            INTEGER,            -- permission bits (st_mode & 0o7777)
+    uid             INTEGER,
+    gid             INTEGER,
+    inode           INTEGER,
+    nlink           INTEGER,
+    ctime           INTEGER,            -- inode change time (seconds)
+    dev             INTEGER,            -- device the inode lives on
+
+    -- Bitmask of what changed from the previous version (see ChangeReason).
+    -- NULL for new items and deletions.
+    change_reason   INTEGER,
+
+    -- Set on the added version of an item paired with an item deleted in the
+    -- same scan (a move or rename). Points at the deleted item.
+    moved_from_item_id INTEGER,
+
+    -- Excluded tombstone: is_deleted = 1 and is_excluded = 1 means the item was
+    -- hidden by a root rule rather than removed from disk. Not counted as a delete.
+    is_excluded     BOOLEAN NOT NULL DEFAULT 0,
 
     -- Folder-specific descendant change counts (NULL for files).
     -- Each count reflects the scan that created this version:
@@ -155,18 +207,41 @@ CREATE TABLE IF NOT EXISTS item_versions (
     unchanged_count INTEGER,
 
     -- Validation state (files only, NULL for folders and unvalidated files).
-    -- Tightly coupled to this version: validated once when version is created.
+    -- Tightly coupled to this version: validated once when version is created,
+    -- and again only if a newer version of its validator is registered.
     val_scan_id     INTEGER,            -- scan in which this version was validated
     val_state       INTEGER,            -- 1=Valid, 2=Invalid
     val_error       TEXT,               -- error details when val_state=Invalid
+    val_validator   TEXT,               -- name of the validator that ran
+    val_validator_version INTEGER,      -- its version at the time
 
     -- User review of integrity issues on this version.
     -- val_reviewed_at: set when user marks this version's validation issue as reviewed.
     -- hash_reviewed_at: set when user marks this version's hash integrity issue as reviewed.
-    -- Both are user-initiated only: NULL until set by user action, never auto-cleared.
+    -- Both are user-initiated only: NULL until set by user action. val_reviewed_at
+    -- is cleared only if revalidation changes val_state.
     val_reviewed_at  INTEGER DEFAULT NULL,
     hash_reviewed_at INTEGER DEFAULT NULL,
 
+    -- Checksum manifest check (files only, NULL unless a manifest in the root
+    -- lists this file). Made whenever the file is hashed, so it may be later
+    -- than first_scan_id. manifest_reviewed_at is user-initiated like the
+    -- columns above.
+    manifest_scan_id     INTEGER,       -- scan in which the check was made
+    manifest_state       INTEGER,       -- 1=Match, 2=Mismatch
+    manifest_path        TEXT,          -- manifest that lists the file
+    manifest_algo        INTEGER,       -- algorithm of the manifest's hash (HashAlgo)
+    manifest_hash        BLOB,          -- hash the manifest expects
+    manifest_reviewed_at INTEGER DEFAULT NULL,
+
+    -- Content type detected from the version's magic bytes (files only, NULL
+    -- until examined). Examined once per version, by a validating scan.
+    -- content_reviewed_at is user-initiated like the columns above.
+    content_scan_id     INTEGER,        -- scan in which the contents were examined
+    content_type        TEXT,           -- validator name or known type, or 'unknown'
+    content_mismatch    INTEGER,        -- 1 if the extension names a different type
+    content_reviewed_at INTEGER DEFAULT NULL,
+
     PRIMARY KEY (item_id, item_version),
     FOREIGN KEY (item_id) REFERENCES items(item_id),
     FOREIGN KEY (root_id) REFERENCES roots(root_id),
@@ -177,6 +252,12 @@ CREATE TABLE IF NOT EXISTS item_versions (
 CREATE INDEX IF NOT EXISTS idx_versions_first_scan ON item_versions (first_scan_id);
 CREATE INDEX IF NOT EXISTS idx_versions_root_lastscan ON item_versions (root_id, last_scan_id);
 CREATE INDEX IF NOT EXISTS idx_versions_val_scan ON item_versions (val_scan_id, val_state);
+CREATE INDEX IF NOT EXISTS idx_versions_moved_from ON item_versions (moved_from_item_id)
+    WHERE moved_from_item_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_versions_manifest_scan ON item_versions (manifest_scan_id)
+    WHERE manifest_scan_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_versions_content_scan ON item_versions (content_scan_id)
+    WHERE content_scan_id IS NOT NULL;
 
 -- ========================================
 -- Hash versions table (integrity observation log)
@@ -191,6 +272,7 @@ CREATE TABLE IF NOT EXISTS hash_versions (
     last_scan_id     INTEGER NOT NULL,
     file_hash        BLOB NOT NULL,
     hash_state       INTEGER NOT NULL,     -- 1=Baseline, 2=Suspect
+    hash_algo        INTEGER NOT NULL DEFAULT 0, -- algorithm that produced file_hash (see roots.hash_algo)
     PRIMARY KEY (item_id, item_version, first_scan_id),
     FOREIGN KEY (item_id, item_version) REFERENCES item_versions(item_id, item_version),
     FOREIGN KEY (first_scan_id) REFERENCES scans(scan_id),
@@ -199,6 +281,25 @@ CREATE TABLE IF NOT EXISTS hash_versions (
 
 CREATE INDEX IF NOT EXISTS idx_hash_versions_first_scan ON hash_versions (first_scan_id, hash_state);
 
+-- Sampled hash observations per item_version, written by Sampled scans.
+-- A sampled hash covers only some of a file's blocks, so it is never compared
+-- with a full hash; a changed sample is escalated to a full hash instead.
+CREATE TABLE IF NOT EXISTS sampled_hash_versions (
+    item_id          INTEGER NOT NULL,
+    item_version     INTEGER NOT NULL,
+    first_scan_id    INTEGER NOT NULL,
+    last_scan_id     INTEGER NOT NULL,
+    file_hash        BLOB NOT NULL,
+    hash_algo        INTEGER NOT NULL,     -- algorithm that produced file_hash (see roots.hash_algo)
+    sample_blocks    INTEGER NOT NULL,     -- evenly spaced blocks sampled between the head and tail
+    PRIMARY KEY (item_id, item_version, first_scan_id),
+    FOREIGN KEY (item_id, item_version) REFERENCES item_versions(item_id, item_version),
+    FOREIGN KEY (first_scan_id) REFERENCES scans(scan_id),
+    FOREIGN KEY (last_scan_id) REFERENCES scans(scan_id)
+) WITHOUT ROWID;
+
+CREATE INDEX IF NOT EXISTS idx_sampled_hash_versions_first_scan ON sampled_hash_versions (first_scan_id);
+
 -- ========================================
 -- Scan undo log (transient, for rollback support)
 -- ========================================
@@ -226,8 +327,16 @@ CREATE TABLE IF NOT EXISTS scan_schedules (
     day_of_month INTEGER,                                               -- Day (1-31) for monthly schedules
     interval_value INTEGER,                                             -- For interval schedules
     interval_unit INTEGER CHECK(interval_unit IN (0, 1, 2, 3)),       -- 0=minutes, 1=hours, 2=days, 3=weeks
-    hash_mode INTEGER NOT NULL CHECK(hash_mode IN (0, 1, 2)),
+    hash_mode INTEGER NOT NULL CHECK(hash_mode IN (0, 1, 2, 3)),         -- 0=None, 1=New, 2=All, 3=Sampled
     is_val BOOLEAN NOT NULL DEFAULT 0,
+    subpath TEXT,                                                       -- Root-relative directory to scan (NULL = whole root)
+    reverify_days INTEGER,                                              -- Re-hash only hashes confirmed at least this many days ago
+    reverify_max_bytes INTEGER,                                         -- Re-hash at most this many bytes per scan
+    reverify_max_percent REAL,                                          -- Re-hash at most this percentage of the bytes in scope per scan
+    read_limit_mb_per_sec INTEGER,                                      -- Analysis read limit in MB/s (NULL = global, 0 = unlimited)
+    read_limit_iops INTEGER,                                            -- Analysis read limit in reads/s (NULL = global, 0 = unlimited)
+    window_start TEXT,                                                  -- Run window start 'HH:MM' (NULL = global window)
+    window_end TEXT,                                                    -- Run window end 'HH:MM' (may be before start: runs past midnight)
     created_at INTEGER NOT NULL,
     updated_at INTEGER NOT NULL,
     deleted_at INTEGER DEFAULT NULL,                                    -- Soft delete timestamp (NULL for active schedules)
@@ -241,7 +350,7 @@ CREATE INDEX IF NOT EXISTS idx_scan_schedules_deleted ON scan_schedules(deleted_
 -- Tasks table stores work items and their execution history
 CREATE TABLE IF NOT EXISTS tasks (
     task_id INTEGER PRIMARY KEY AUTOINCREMENT,
-    task_type INTEGER NOT NULL DEFAULT 0,          -- TaskType enum: 0=Scan
+    task_type INTEGER NOT NULL DEFAULT 0,          -- TaskType enum: 0=Scan, 1=CompactDatabase, 2=ExportManifest, 3=CompareRoots
 
     -- Lifecycle status
     status INTEGER NOT NULL DEFAULT 0,             -- TaskStatus enum: 0=Pending, 1=Running, 2=Completed, 3=Stopped, 4=Error
@@ -254,7 +363,7 @@ CREATE TABLE IF NOT EXISTS tasks (
 
     -- Scheduling
     run_at INTEGER NOT NULL DEFAULT 0,             -- When eligible to run (0 = immediately)
-    source INTEGER NOT NULL CHECK(source IN (0, 1)), -- 0=manual, 1=scheduled
+    source INTEGER NOT NULL CHECK(source IN (0, 1, 2)), -- 0=manual, 1=scheduled, 2=watch
 
     -- Task configuration (immutable JSON — permanent artifact)
     task_settings TEXT NOT NULL,
@@ -275,5 +384,53 @@ CREATE INDEX IF NOT EXISTS idx_tasks_status_source_runat ON tasks(status, source
 CREATE INDEX IF NOT EXISTS idx_tasks_schedule ON tasks(schedule_id) WHERE schedule_id IS NOT NULL;
 CREATE INDEX IF NOT EXISTS idx_tasks_root ON tasks(root_id);
 
+-- ========================================
+-- Root comparisons (written by Compare Roots tasks)
+-- ========================================
+-- Each report aligns two roots, or folders within them, by relative path at
+-- chosen scans. Only files are compared.
+CREATE TABLE IF NOT EXISTS comparisons (
+    comparison_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    task_id INTEGER NOT NULL,               -- The task that wrote the report
+    left_root_id INTEGER NOT NULL,
+    left_scan_id INTEGER NOT NULL,
+    left_path TEXT NOT NULL,                -- Folder compared on the left: the root or a folder in it
+    right_root_id INTEGER NOT NULL,
+    right_scan_id INTEGER NOT NULL,
+    right_path TEXT NOT NULL,
+    started_at INTEGER NOT NULL,
+    completed_at INTEGER DEFAULT NULL,      -- NULL while the report is being written
+    match_count INTEGER DEFAULT NULL,       -- Files on both sides with equal size, mod date and hash
+    unverified_count INTEGER DEFAULT NULL,  -- Files on both sides with equal size and mod date but no comparable hashes
+    left_only_count INTEGER DEFAULT NULL,
+    right_only_count INTEGER DEFAULT NULL,
+    size_diff_count INTEGER DEFAULT NULL,
+    hash_diff_count INTEGER DEFAULT NULL,
+    mod_date_diff_count INTEGER DEFAULT NULL,
+    FOREIGN KEY (left_root_id) REFERENCES roots(root_id),
+    FOREIGN KEY (right_root_id) REFERENCES roots(root_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_comparisons_task ON comparisons (task_id);
+
+-- One row per file that is missing on a side or differs between them
+CREATE TABLE IF NOT EXISTS comparison_diffs (
+    comparison_id INTEGER NOT NULL,
+    rel_path TEXT NOT NULL,                 -- Path relative to the compared folders
+    diff_kind INTEGER NOT NULL,             -- 1=left only, 2=right only, 3=size, 4=hash, 5=mod date
+    left_item_id INTEGER,                   -- NULL when the file is missing on the left
+    right_item_id INTEGER,                  -- NULL when the file is missing on the right
+    left_size INTEGER,
+    right_size INTEGER,
+    left_mod_date INTEGER,
+    right_mod_date INTEGER,
+    left_hash BLOB,                         -- Latest hash observed by the compared scan, if any
+    right_hash BLOB,
+    PRIMARY KEY (comparison_id, rel_path),
+    FOREIGN KEY (comparison_id) REFERENCES comparisons(comparison_id)
+) WITHOUT ROWID;
+
+CREATE INDEX IF NOT EXISTS idx_comparison_diffs_kind ON comparison_diffs (comparison_id, diff_kind);
+
 COMMIT;
 "#;
@@ -20,6 +20,22 @@ mod v27_to_v28;
 mod v28_to_v29;
 mod v29_to_v30;
 mod v30_to_v31;
+mod v31_to_v32;
+mod v32_to_v33;
+mod v33_to_v34;
+mod v34_to_v35;
+mod v35_to_v36;
+mod v36_to_v37;
+mod v37_to_v38;
+mod v38_to_v39;
+mod v39_to_v40;
+mod v40_to_v41;
+mod v41_to_v42;
+mod v42_to_v43;
+mod v43_to_v44;
+mod v44_to_v45;
+mod v45_to_v46;
+mod v46_to_v47;
 mod v2_to_v3;
 mod v3_to_v4;
 mod v4_to_v5;
@@ -59,6 +75,22 @@ use v27_to_v28::migrate_v27_to_v28;
 use v28_to_v29::{migrate_v28_to_v29, UPGRADE_28_TO_29_PRE_SQL};
 use v29_to_v30::{migrate_v29_to_v30, UPGRADE_29_TO_30_PRE_SQL};
 use v30_to_v31::{migrate_v30_to_v31, UPGRADE_30_TO_31_PRE_SQL};
+use v31_to_v32::UPGRADE_31_TO_32_SQL;
+use v32_to_v33::UPGRADE_32_TO_33_SQL;
+use v33_to_v34::UPGRADE_33_TO_34_SQL;
+use v34_to_v35::UPGRADE_34_TO_35_SQL;
+use v35_to_v36::UPGRADE_35_TO_36_SQL;
+use v36_to_v37::UPGRADE_36_TO_37_SQL;
+use v37_to_v38::UPGRADE_37_TO_38_SQL;
+use v38_to_v39::UPGRADE_38_TO_39_SQL;
+use v39_to_v40::UPGRADE_39_TO_40_SQL;
+use v40_to_v41::UPGRADE_40_TO_41_SQL;
+use v41_to_v42::UPGRADE_41_TO_42_SQL;
+use v42_to_v43::UPGRADE_42_TO_43_SQL;
+use v43_to_v44::UPGRADE_43_TO_44_SQL;
+use v44_to_v45::UPGRADE_44_TO_45_SQL;
+use v45_to_v46::UPGRADE_45_TO_46_SQL;
+use v46_to_v47::UPGRADE_46_TO_47_SQL;
 use v2_to_v3::UPGRADE_2_TO_3_SQL;
 use v3_to_v4::UPGRADE_3_TO_4_SQL;
 use v4_to_v5::UPGRADE_4_TO_5_SQL;
@@ -182,3 +214,19 @@ pub const MIGRATION_30_TO_31: Migration = Migration::Transacted {
     code_fn: Some(migrate_v30_to_v31),
     post_sql: None,
 };
+pub const MIGRATION_31_TO_32: Migration = Migration::sql_only(UPGRADE_31_TO_32_SQL);
+pub const MIGRATION_32_TO_33: Migration = Migration::sql_only(UPGRADE_32_TO_33_SQL);
+pub const MIGRATION_33_TO_34: Migration = Migration::sql_only(UPGRADE_33_TO_34_SQL);
+pub const MIGRATION_34_TO_35: Migration = Migration::sql_only(UPGRADE_34_TO_35_SQL);
+pub const MIGRATION_35_TO_36: Migration = Migration::sql_only(UPGRADE_35_TO_36_SQL);
+pub const MIGRATION_36_TO_37: Migration = Migration::sql_only(UPGRADE_36_TO_37_SQL);
+pub const MIGRATION_37_TO_38: Migration = Migration::sql_only(UPGRADE_37_TO_38_SQL);
+pub const MIGRATION_38_TO_39: Migration = Migration::sql_only(UPGRADE_38_TO_39_SQL);
+pub const MIGRATION_39_TO_40: Migration = Migration::sql_only(UPGRADE_39_TO_40_SQL);
+pub const MIGRATION_40_TO_41: Migration = Migration::sql_only(UPGRADE_40_TO_41_SQL);
+pub const MIGRATION_41_TO_42: Migration = Migration::sql_only(UPGRADE_41_TO_42_SQL);
+pub const MIGRATION_42_TO_43: Migration = Migration::sql_only(UPGRADE_42_TO_43_SQL);
+pub const MIGRATION_43_TO_44: Migration = Migration::sql_only(UPGRADE_43_TO_44_SQL);
+pub const MIGRATION_44_TO_45: Migration = Migration::sql_only(UPGRADE_44_TO_45_SQL);
+pub const MIGRATION_45_TO_46: Migration = Migration::sql_only(UPGRADE_45_TO_46_SQL);
+pub const MIGRATION_46_TO_47: Migration = Migration::sql_only(UPGRADE_46_TO_47_SQL);
@@ -0,0 +1,32 @@
+// ============================================================================
+// Schema Upgrade: Version 31 → 32 — Per-root include/exclude rules
+//
+// 1. Adds the root_rules table. Each row is one include or exclude pattern
+//    (glob or regex) matched against either the root-relative path or the
+//    item name. Rules are evaluated by the scanner during the Phase 1 walk.
+//
+// 2. Adds item_versions.is_excluded. An excluded tombstone is a version with
+//    is_deleted = 1 AND is_excluded = 1: the item still exists on disk but a
+//    root rule now hides it. Excluded tombstones are not counted as deletes.
+//
+// Existing rows default to is_excluded = 0; no data transformation needed.
+// ============================================================================
+
+pub const UPGRADE_31_TO_32_SQL: &str = r#"
+CREATE TABLE IF NOT EXISTS root_rules (
+    rule_id      INTEGER PRIMARY KEY AUTOINCREMENT,
+    root_id      INTEGER NOT NULL,
+    rule_order   INTEGER NOT NULL,
+    rule_action  INTEGER NOT NULL CHECK(rule_action IN (0, 1)),   -- 0=exclude, 1=include
+    pattern_type INTEGER NOT NULL CHECK(pattern_type IN (0, 1)),  -- 0=glob, 1=regex
+    match_target INTEGER NOT NULL CHECK(match_target IN (0, 1)),  -- 0=root-relative path, 1=name
+    pattern      TEXT NOT NULL,
+    FOREIGN KEY (root_id) REFERENCES roots(root_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_root_rules_root ON root_rules (root_id, rule_order);
+
+ALTER TABLE item_versions ADD COLUMN is_excluded BOOLEAN NOT NULL DEFAULT 0;
+
+INSERT OR REPLACE INTO meta (key, value) VALUES ('schema_version', '32');
+"#;
@@ -0,0 +1,18 @@
+// ============================================================================
+// Schema Upgrade: Version 32 → 33 — One-file-system mode per root
+//
+// 1. Adds roots.one_file_system. When set, the scanner does not descend into
+//    directories that are on a different filesystem (st_dev) than the root.
+//
+// 2. A directory that is not traversed for this reason is recorded with
+//    access = 3 (MountPoint). The access column is a plain INTEGER, so the new
+//    value needs no DDL change.
+//
+// Existing roots default to one_file_system = 0; no data transformation needed.
+// ============================================================================
+
+pub const UPGRADE_32_TO_33_SQL: &str = r#"
+ALTER TABLE roots ADD COLUMN one_file_system BOOLEAN NOT NULL DEFAULT 0;
+
+INSERT OR REPLACE INTO meta (key, value) VALUES ('schema_version', '33');
+"#;
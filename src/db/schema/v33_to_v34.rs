@@ -0,0 +1,21 @@
+// ============================================================================
+// Schema Upgrade: Version 33 → 34 — Symlink targets and symlink following
+//
+// 1. Adds item_versions.symlink_target. The target string stored in a symlink
+//    is recorded on each version, so a retargeted link becomes a new version.
+//    Existing symlink versions keep NULL until their next scan, which fills in
+//    the target in place rather than reporting every link as modified.
+//
+// 2. Adds roots.follow_symlinks and roots.max_symlink_depth. When
+//    follow_symlinks is set, the scanner descends into symlinked directories,
+//    following at most max_symlink_depth nested links along any one path.
+// ============================================================================
+
+pub const UPGRADE_33_TO_34_SQL: &str = r#"
+ALTER TABLE item_versions ADD COLUMN symlink_target TEXT;
+
+ALTER TABLE roots ADD COLUMN follow_symlinks BOOLEAN NOT NULL DEFAULT 0;
+ALTER TABLE roots ADD COLUMN max_symlink_depth INTEGER NOT NULL DEFAULT 8;
+
+INSERT OR REPLACE INTO meta (key, value) VALUES ('schema_version', '34');
+"#;
@@ -0,0 +1,24 @@
+// ============================================================================
+// Schema Upgrade: Version 34 → 35 — Extended Unix metadata and change reasons
+//
+// 1. Adds item_versions.mode, uid, gid, inode, nlink and ctime. Permission,
+//    ownership and ctime changes that leave mtime alone (chmod, chown, a sync
+//    tool replacing a file in place) now create a new version.
+//    Existing versions keep NULL until their next scan, which fills the values
+//    in place rather than reporting every item as modified.
+//
+// 2. Adds item_versions.change_reason, a bitmask of what changed from the
+//    previous version. Existing versions keep NULL (unknown).
+// ============================================================================
+
+pub const UPGRADE_34_TO_35_SQL: &str = r#"
+ALTER TABLE item_versions ADD COLUMN mode INTEGER;
+ALTER TABLE item_versions ADD COLUMN uid INTEGER;
+ALTER TABLE item_versions ADD COLUMN gid INTEGER;
+ALTER TABLE item_versions ADD COLUMN inode INTEGER;
+ALTER TABLE item_versions ADD COLUMN nlink INTEGER;
+ALTER TABLE item_versions ADD COLUMN ctime INTEGER;
+ALTER TABLE item_versions ADD COLUMN change_reason INTEGER;
+
+INSERT OR REPLACE INTO meta (key, value) VALUES ('schema_version', '35');
+"#;
@@ -0,0 +1,24 @@
+// ============================================================================
+// Schema Upgrade: Version 35 → 36 — Move and rename detection
+//
+// 1. Adds item_versions.dev, the device the inode lives on. Together with
+//    inode it identifies a file across renames. Existing versions keep NULL
+//    until their next scan fills them in place.
+//
+// 2. Adds item_versions.moved_from_item_id. Set on the added version of an
+//    item that Phase 4 paired with an item deleted in the same scan.
+//
+// 3. Adds scans.move_count. Moved pairs are counted here instead of as one
+//    add plus one delete. Existing scans keep NULL.
+// ============================================================================
+
+pub const UPGRADE_35_TO_36_SQL: &str = r#"
+ALTER TABLE item_versions ADD COLUMN dev INTEGER;
+ALTER TABLE item_versions ADD COLUMN moved_from_item_id INTEGER;
+ALTER TABLE scans ADD COLUMN move_count INTEGER DEFAULT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_versions_moved_from ON item_versions (moved_from_item_id)
+    WHERE moved_from_item_id IS NOT NULL;
+
+INSERT OR REPLACE INTO meta (key, value) VALUES ('schema_version', '36');
+"#;
@@ -0,0 +1,47 @@
+// ============================================================================
+// Schema Upgrade: Version 36 → 37 — Filesystem watching per root
+//
+// Adds roots.watch. When set, the server watches the root for changes and
+// queues a scan of just the changed directories once activity settles.
+//
+// Those scans are queued with a new task source, 2=watch. SQLite can't alter
+// a CHECK constraint, so tasks is rebuilt to allow it.
+//
+// Existing roots default to watch = 0; existing tasks are copied unchanged.
+// ============================================================================
+
+pub const UPGRADE_36_TO_37_SQL: &str = r#"
+ALTER TABLE roots ADD COLUMN watch BOOLEAN NOT NULL DEFAULT 0;
+
+CREATE TABLE tasks_new (
+    task_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    task_type INTEGER NOT NULL DEFAULT 0,
+    status INTEGER NOT NULL DEFAULT 0,
+    root_id INTEGER,
+    schedule_id INTEGER,
+    run_at INTEGER NOT NULL DEFAULT 0,
+    source INTEGER NOT NULL CHECK(source IN (0, 1, 2)), -- 0=manual, 1=scheduled, 2=watch
+    task_settings TEXT NOT NULL,
+    task_state TEXT,
+    created_at INTEGER NOT NULL,
+    started_at INTEGER,
+    completed_at INTEGER,
+    FOREIGN KEY (schedule_id) REFERENCES scan_schedules(schedule_id) ON DELETE SET NULL,
+    FOREIGN KEY (root_id) REFERENCES roots(root_id)
+);
+
+INSERT INTO tasks_new (task_id, task_type, status, root_id, schedule_id, run_at, source,
+                       task_settings, task_state, created_at, started_at, completed_at)
+    SELECT task_id, task_type, status, root_id, schedule_id, run_at, source,
+           task_settings, task_state, created_at, started_at, completed_at
+    FROM tasks;
+
+DROP TABLE tasks;
+ALTER TABLE tasks_new RENAME TO tasks;
+
+CREATE INDEX IF NOT EXISTS idx_tasks_status_source_runat ON tasks(status, source, run_at, task_id);
+CREATE INDEX IF NOT EXISTS idx_tasks_schedule ON tasks(schedule_id) WHERE schedule_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_tasks_root ON tasks(root_id);
+
+INSERT OR REPLACE INTO meta (key, value) VALUES ('schema_version', '37');
+"#;
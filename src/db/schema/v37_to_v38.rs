@@ -0,0 +1,14 @@
+// ============================================================================
+// Schema Upgrade: Version 37 → 38 — Subtree-scoped schedules
+//
+// Adds scan_schedules.subpath. When set, the schedule's scans cover only that
+// root-relative directory instead of the whole root.
+//
+// Existing schedules default to NULL (whole root); no data transformation needed.
+// ============================================================================
+
+pub const UPGRADE_37_TO_38_SQL: &str = r#"
+ALTER TABLE scan_schedules ADD COLUMN subpath TEXT;
+
+INSERT OR REPLACE INTO meta (key, value) VALUES ('schema_version', '38');
+"#;
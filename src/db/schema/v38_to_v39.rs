@@ -0,0 +1,18 @@
+// ============================================================================
+// Schema Upgrade: Version 38 → 39 — Selectable hash algorithms
+//
+// Adds roots.hash_algo, the algorithm used when hashing the root's files, and
+// hash_versions.hash_algo, the algorithm that produced each observed hash.
+// (0=SHA-256, 1=BLAKE3, 2=XXH3-128, 3=SHA-512)
+//
+// Every hash written so far is SHA-256, so both default to 0; no data
+// transformation needed.
+// ============================================================================
+
+pub const UPGRADE_38_TO_39_SQL: &str = r#"
+ALTER TABLE roots ADD COLUMN hash_algo INTEGER NOT NULL DEFAULT 0;
+
+ALTER TABLE hash_versions ADD COLUMN hash_algo INTEGER NOT NULL DEFAULT 0;
+
+INSERT OR REPLACE INTO meta (key, value) VALUES ('schema_version', '39');
+"#;
@@ -0,0 +1,30 @@
+// ============================================================================
+// Schema Upgrade: Version 39 → 40 — Checksum manifest verification
+//
+// Adds columns to item_versions recording the result of checking the version
+// against a checksum manifest (SHA256SUMS, .md5, .sfv, .par2, ...) found in
+// the root:
+//   manifest_scan_id     — scan in which the check was made
+//   manifest_state       — 1=Match, 2=Mismatch
+//   manifest_path        — the manifest that listed the file
+//   manifest_algo        — algorithm of the manifest's hash (HashAlgo)
+//   manifest_hash        — the hash the manifest expects
+//   manifest_reviewed_at — set when the user reviews a mismatch
+//
+// All are NULL for existing versions; no data transformation needed. The
+// partial index keeps scan rollback from scanning every version.
+// ============================================================================
+
+pub const UPGRADE_39_TO_40_SQL: &str = r#"
+ALTER TABLE item_versions ADD COLUMN manifest_scan_id INTEGER;
+ALTER TABLE item_versions ADD COLUMN manifest_state INTEGER;
+ALTER TABLE item_versions ADD COLUMN manifest_path TEXT;
+ALTER TABLE item_versions ADD COLUMN manifest_algo INTEGER;
+ALTER TABLE item_versions ADD COLUMN manifest_hash BLOB;
+ALTER TABLE item_versions ADD COLUMN manifest_reviewed_at INTEGER DEFAULT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_versions_manifest_scan ON item_versions (manifest_scan_id)
+    WHERE manifest_scan_id IS NOT NULL;
+
+INSERT OR REPLACE INTO meta (key, value) VALUES ('schema_version', '40');
+"#;
@@ -0,0 +1,56 @@
+// ============================================================================
+// Schema Upgrade: Version 40 → 41 — Root comparisons
+//
+// Adds the tables holding the reports written by Compare Roots tasks, which
+// align two roots (or folders within them) by relative path at chosen scans:
+//   comparisons      — one row per report, with the compared sides and counts
+//   comparison_diffs — one row per file that is missing on a side or differs
+//
+// New tables only; no data transformation needed.
+// ============================================================================
+
+pub const UPGRADE_40_TO_41_SQL: &str = r#"
+CREATE TABLE IF NOT EXISTS comparisons (
+    comparison_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    task_id INTEGER NOT NULL,
+    left_root_id INTEGER NOT NULL,
+    left_scan_id INTEGER NOT NULL,
+    left_path TEXT NOT NULL,
+    right_root_id INTEGER NOT NULL,
+    right_scan_id INTEGER NOT NULL,
+    right_path TEXT NOT NULL,
+    started_at INTEGER NOT NULL,
+    completed_at INTEGER DEFAULT NULL,
+    match_count INTEGER DEFAULT NULL,
+    unverified_count INTEGER DEFAULT NULL,
+    left_only_count INTEGER DEFAULT NULL,
+    right_only_count INTEGER DEFAULT NULL,
+    size_diff_count INTEGER DEFAULT NULL,
+    hash_diff_count INTEGER DEFAULT NULL,
+    mod_date_diff_count INTEGER DEFAULT NULL,
+    FOREIGN KEY (left_root_id) REFERENCES roots(root_id),
+    FOREIGN KEY (right_root_id) REFERENCES roots(root_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_comparisons_task ON comparisons (task_id);
+
+CREATE TABLE IF NOT EXISTS comparison_diffs (
+    comparison_id INTEGER NOT NULL,
+    rel_path TEXT NOT NULL,
+    diff_kind INTEGER NOT NULL,
+    left_item_id INTEGER,
+    right_item_id INTEGER,
+    left_size INTEGER,
+    right_size INTEGER,
+    left_mod_date INTEGER,
+    right_mod_date INTEGER,
+    left_hash BLOB,
+    right_hash BLOB,
+    PRIMARY KEY (comparison_id, rel_path),
+    FOREIGN KEY (comparison_id) REFERENCES comparisons(comparison_id)
+) WITHOUT ROWID;
+
+CREATE INDEX IF NOT EXISTS idx_comparison_diffs_kind ON comparison_diffs (comparison_id, diff_kind);
+
+INSERT OR REPLACE INTO meta (key, value) VALUES ('schema_version', '41');
+"#;
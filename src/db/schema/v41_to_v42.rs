@@ -0,0 +1,73 @@
+// ============================================================================
+// Schema Upgrade: Version 41 → 42 — Sampled hashing
+//
+// Adds the Sampled hash mode, which hashes a sample of each file's blocks as
+// a cheap screen for changed contents:
+//   scans.hash_sampled    — the scan sampled files it didn't otherwise hash
+//   sampled_hash_versions — sampled hash observations, kept apart from the
+//                           full hashes in hash_versions so the two are never
+//                           compared
+//
+// scan_schedules is rebuilt to allow hash_mode 3 (Sampled) in its CHECK
+// constraint. Migrations run with foreign keys off, so the tables referencing
+// it are unaffected.
+// ============================================================================
+
+pub const UPGRADE_41_TO_42_SQL: &str = r#"
+ALTER TABLE scans ADD COLUMN hash_sampled BOOLEAN NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS sampled_hash_versions (
+    item_id          INTEGER NOT NULL,
+    item_version     INTEGER NOT NULL,
+    first_scan_id    INTEGER NOT NULL,
+    last_scan_id     INTEGER NOT NULL,
+    file_hash        BLOB NOT NULL,
+    hash_algo        INTEGER NOT NULL,
+    sample_blocks    INTEGER NOT NULL,
+    PRIMARY KEY (item_id, item_version, first_scan_id),
+    FOREIGN KEY (item_id, item_version) REFERENCES item_versions(item_id, item_version),
+    FOREIGN KEY (first_scan_id) REFERENCES scans(scan_id),
+    FOREIGN KEY (last_scan_id) REFERENCES scans(scan_id)
+) WITHOUT ROWID;
+
+CREATE INDEX IF NOT EXISTS idx_sampled_hash_versions_first_scan ON sampled_hash_versions (first_scan_id);
+
+CREATE TABLE scan_schedules_new (
+    schedule_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    root_id INTEGER NOT NULL,
+    enabled BOOLEAN NOT NULL DEFAULT 1,
+    schedule_name TEXT NOT NULL,
+    schedule_type INTEGER NOT NULL CHECK(schedule_type IN (0, 1, 2, 3)),
+    time_of_day TEXT,
+    days_of_week TEXT,
+    day_of_month INTEGER,
+    interval_value INTEGER,
+    interval_unit INTEGER CHECK(interval_unit IN (0, 1, 2, 3)),
+    hash_mode INTEGER NOT NULL CHECK(hash_mode IN (0, 1, 2, 3)),
+    is_val BOOLEAN NOT NULL DEFAULT 0,
+    subpath TEXT,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    deleted_at INTEGER DEFAULT NULL,
+    FOREIGN KEY (root_id) REFERENCES roots(root_id)
+);
+
+INSERT INTO scan_schedules_new (schedule_id, root_id, enabled, schedule_name, schedule_type,
+                                time_of_day, days_of_week, day_of_month, interval_value,
+                                interval_unit, hash_mode, is_val, subpath, created_at,
+                                updated_at, deleted_at)
+    SELECT schedule_id, root_id, enabled, schedule_name, schedule_type,
+           time_of_day, days_of_week, day_of_month, interval_value,
+           interval_unit, hash_mode, is_val, subpath, created_at,
+           updated_at, deleted_at
+    FROM scan_schedules;
+
+DROP TABLE scan_schedules;
+ALTER TABLE scan_schedules_new RENAME TO scan_schedules;
+
+CREATE INDEX IF NOT EXISTS idx_scan_schedules_enabled ON scan_schedules(enabled);
+CREATE INDEX IF NOT EXISTS idx_scan_schedules_root ON scan_schedules(root_id);
+CREATE INDEX IF NOT EXISTS idx_scan_schedules_deleted ON scan_schedules(deleted_at);
+
+INSERT OR REPLACE INTO meta (key, value) VALUES ('schema_version', '42');
+"#;
@@ -0,0 +1,20 @@
+// ============================================================================
+// Schema Upgrade: Version 42 → 43 — Re-verification budget on schedules
+//
+// Lets a schedule re-hash the files whose hash was confirmed longest ago, a
+// bounded share per scan:
+//   scan_schedules.reverify_days        — only hashes confirmed at least this
+//                                         many days ago
+//   scan_schedules.reverify_max_bytes   — re-hash at most this many bytes
+//   scan_schedules.reverify_max_percent — re-hash at most this percentage of
+//                                         the bytes in scope
+// All NULL means the schedule doesn't re-verify.
+// ============================================================================
+
+pub const UPGRADE_42_TO_43_SQL: &str = r#"
+ALTER TABLE scan_schedules ADD COLUMN reverify_days INTEGER;
+ALTER TABLE scan_schedules ADD COLUMN reverify_max_bytes INTEGER;
+ALTER TABLE scan_schedules ADD COLUMN reverify_max_percent REAL;
+
+INSERT OR REPLACE INTO meta (key, value) VALUES ('schema_version', '43');
+"#;
@@ -0,0 +1,15 @@
+// ============================================================================
+// Schema Upgrade: Version 43 → 44 — Read limits on schedules
+//
+// Lets a schedule replace the global analysis read limits for its scans:
+//   scan_schedules.read_limit_mb_per_sec — megabytes per second (0 = unlimited)
+//   scan_schedules.read_limit_iops       — reads per second (0 = unlimited)
+// NULL means the schedule uses the global limit.
+// ============================================================================
+
+pub const UPGRADE_43_TO_44_SQL: &str = r#"
+ALTER TABLE scan_schedules ADD COLUMN read_limit_mb_per_sec INTEGER;
+ALTER TABLE scan_schedules ADD COLUMN read_limit_iops INTEGER;
+
+INSERT OR REPLACE INTO meta (key, value) VALUES ('schema_version', '44');
+"#;
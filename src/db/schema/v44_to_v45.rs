@@ -0,0 +1,16 @@
+// ============================================================================
+// Schema Upgrade: Version 44 → 45 — Run windows on schedules
+//
+// Lets a schedule limit its scans to a daily range of local time:
+//   scan_schedules.window_start — 'HH:MM' when the window opens
+//   scan_schedules.window_end   — 'HH:MM' when it closes (before the start
+//                                 for a window that runs past midnight)
+// NULL means the schedule uses the global run window.
+// ============================================================================
+
+pub const UPGRADE_44_TO_45_SQL: &str = r#"
+ALTER TABLE scan_schedules ADD COLUMN window_start TEXT;
+ALTER TABLE scan_schedules ADD COLUMN window_end TEXT;
+
+INSERT OR REPLACE INTO meta (key, value) VALUES ('schema_version', '45');
+"#;
@@ -0,0 +1,36 @@
+// ============================================================================
+// Schema Upgrade: Version 45 → 46 — Validator name and version
+//
+// Records which validator produced each validation, so that a newer version
+// of a validator can redo the validations of older ones:
+//   item_versions.val_validator         — registered name of the validator
+//   item_versions.val_validator_version — its version at the time
+//
+// Existing validations are attributed to version 1 of the validator that
+// handled their file's extension when this migration was written.
+// ============================================================================
+
+pub const UPGRADE_45_TO_46_SQL: &str = r#"
+ALTER TABLE item_versions ADD COLUMN val_validator TEXT;
+ALTER TABLE item_versions ADD COLUMN val_validator_version INTEGER;
+
+UPDATE item_versions
+SET val_validator = (
+    SELECT CASE i.file_extension
+        WHEN 'flac' THEN 'flac'
+        WHEN 'pdf' THEN 'pdf'
+        WHEN 'jpg' THEN 'image'
+        WHEN 'jpeg' THEN 'image'
+        WHEN 'png' THEN 'image'
+        WHEN 'gif' THEN 'image'
+        WHEN 'tiff' THEN 'image'
+        WHEN 'bmp' THEN 'image'
+    END
+    FROM items i WHERE i.item_id = item_versions.item_id
+)
+WHERE val_state IS NOT NULL;
+
+UPDATE item_versions SET val_validator_version = 1 WHERE val_validator IS NOT NULL;
+
+INSERT OR REPLACE INTO meta (key, value) VALUES ('schema_version', '46');
+"#;
@@ -0,0 +1,26 @@
+// ============================================================================
+// Schema Upgrade: Version 46 → 47 — Content type detection
+//
+// Adds columns to item_versions recording the file type detected from the
+// version's magic bytes:
+//   content_scan_id     — scan in which the contents were examined
+//   content_type        — validator name or known type, or 'unknown'
+//   content_mismatch    — 1 if the extension names a different type
+//   content_reviewed_at — set when the user reviews a mismatch
+//
+// All are NULL for existing versions, which are examined by their root's
+// next validating scan. The partial index keeps scan rollback from scanning
+// every version.
+// ============================================================================
+
+pub const UPGRADE_46_TO_47_SQL: &str = r#"
+ALTER TABLE item_versions ADD COLUMN content_scan_id INTEGER;
+ALTER TABLE item_versions ADD COLUMN content_type TEXT;
+ALTER TABLE item_versions ADD COLUMN content_mismatch INTEGER;
+ALTER TABLE item_versions ADD COLUMN content_reviewed_at INTEGER DEFAULT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_versions_content_scan ON item_versions (content_scan_id)
+    WHERE content_scan_id IS NOT NULL;
+
+INSERT OR REPLACE INTO meta (key, value) VALUES ('schema_version', '47');
+"#;
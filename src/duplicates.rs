@@ -0,0 +1,304 @@
+use std::collections::HashMap;
+
+use rusqlite::{Connection, ToSql};
+
+use crate::error::FsPulseError;
+use crate::hash::{Hash, HashAlgo};
+
+/// Files alive at their root's latest completed scan (state 4), each with the
+/// latest hash recorded for it by then. Empty files and files that have never
+/// been hashed are left out.
+macro_rules! live_hashed_files_sql {
+    () => {
+        "SELECT i.root_id, i.item_id, i.item_path, i.file_extension, iv.size, iv.mod_date,
+                hv.hash_algo, hv.file_hash
+         FROM scans s
+         JOIN item_versions iv ON iv.root_id = s.root_id
+             AND iv.last_scan_id >= s.scan_id AND iv.first_scan_id <= s.scan_id
+         JOIN items i ON i.item_id = iv.item_id
+         JOIN hash_versions hv ON hv.item_id = iv.item_id
+             AND hv.item_version = iv.item_version
+             AND hv.first_scan_id = (
+                 SELECT MAX(first_scan_id) FROM hash_versions
+                 WHERE item_id = iv.item_id AND item_version = iv.item_version
+                   AND first_scan_id <= s.scan_id)
+         WHERE s.scan_id IN (SELECT MAX(scan_id) FROM scans WHERE state = 4 GROUP BY root_id)
+           AND iv.is_deleted = 0 AND i.item_type = 0 AND iv.size > 0"
+    };
+}
+
+/// Groups of two or more live files with the same size and hash (in the same
+/// algorithm). `{file_where_clause}` selects the files that are grouped; it
+/// is evaluated before grouping, so its columns are those of a single file.
+macro_rules! duplicate_groups_sql {
+    () => {
+        concat!(
+            "SELECT f.size AS size, f.hash_algo AS hash_algo, f.file_hash AS file_hash,
+                    COUNT(*) AS file_count,
+                    COUNT(DISTINCT f.root_id) AS root_count,
+                    (COUNT(*) - 1) * f.size AS wasted_size,
+                    GROUP_CONCAT(DISTINCT f.root_id) AS root_id,
+                    MIN(f.item_path) AS item_path,
+                    GROUP_CONCAT(DISTINCT f.file_extension) AS file_extension
+             FROM (",
+            crate::duplicates::live_hashed_files_sql!(),
+            ") f
+             {file_where_clause}
+             GROUP BY f.size, f.hash_algo, f.file_hash
+             HAVING COUNT(*) > 1"
+        )
+    };
+}
+
+pub(crate) use duplicate_groups_sql;
+pub(crate) use live_hashed_files_sql;
+
+/// Which files to look for duplicates among
+#[derive(Debug, Clone, Default)]
+pub struct DuplicateFilter {
+    /// Only files in these roots. Empty means all roots.
+    pub root_ids: Vec<i64>,
+    /// Only files whose path contains this substring
+    pub path_search: Option<String>,
+    /// Only files of at least this many bytes
+    pub min_size: Option<i64>,
+}
+
+impl DuplicateFilter {
+    /// WHERE clause over the columns of a single file, with its parameters
+    fn file_where_clause(&self) -> (String, Vec<Box<dyn ToSql>>) {
+        let mut preds = Vec::new();
+        let mut params: Vec<Box<dyn ToSql>> = Vec::new();
+
+        if !self.root_ids.is_empty() {
+            preds.push(format!(
+                "f.root_id IN ({})",
+                vec!["?"; self.root_ids.len()].join(", ")
+            ));
+            params.extend(self.root_ids.iter().map(|id| Box::new(*id) as Box<dyn ToSql>));
+        }
+        if let Some(path_search) = &self.path_search {
+            preds.push("f.item_path LIKE ?".to_string());
+            params.push(Box::new(format!("%{}%", path_search)));
+        }
+        if let Some(min_size) = self.min_size {
+            preds.push("f.size >= ?".to_string());
+            params.push(Box::new(min_size));
+        }
+
+        if preds.is_empty() {
+            (String::new(), params)
+        } else {
+            (format!("WHERE {}", preds.join(" AND ")), params)
+        }
+    }
+}
+
+/// Totals over all the duplicate groups a filter finds
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DuplicateSummary {
+    pub group_count: i64,
+    pub file_count: i64,
+    pub wasted_size: i64,
+}
+
+/// Files with identical contents: the same size and hash
+#[derive(Debug, Clone)]
+pub struct DuplicateGroup {
+    pub size: i64,
+    pub hash_algo: HashAlgo,
+    pub file_hash: String,
+    pub file_count: i64,
+    pub root_count: i64,
+    /// Bytes taken by the copies beyond the first
+    pub wasted_size: i64,
+    /// The group's files in path order, up to the limit asked for
+    pub files: Vec<DuplicateFile>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DuplicateFile {
+    pub root_id: i64,
+    pub item_id: i64,
+    pub item_path: String,
+    pub mod_date: Option<i64>,
+}
+
+pub struct Duplicates;
+
+impl Duplicates {
+    pub fn summary(conn: &Connection, filter: &DuplicateFilter) -> Result<DuplicateSummary, FsPulseError> {
+        let (file_where, params) = filter.file_where_clause();
+        let sql = format!(
+            "SELECT COUNT(*), COALESCE(SUM(file_count), 0), COALESCE(SUM(wasted_size), 0) FROM ({})",
+            duplicate_groups_sql!().replace("{file_where_clause}", &file_where)
+        );
+        let params: Vec<&dyn ToSql> = params.iter().map(|p| &**p).collect();
+
+        Ok(conn.query_row(&sql, &params[..], |row| {
+            Ok(DuplicateSummary {
+                group_count: row.get(0)?,
+                file_count: row.get(1)?,
+                wasted_size: row.get(2)?,
+            })
+        })?)
+    }
+
+    /// A page of duplicate groups, most wasted space first, each with up to
+    /// `files_per_group` of its files
+    pub fn groups(
+        conn: &Connection,
+        filter: &DuplicateFilter,
+        limit: i64,
+        offset: i64,
+        files_per_group: usize,
+    ) -> Result<Vec<DuplicateGroup>, FsPulseError> {
+        let (file_where, params) = filter.file_where_clause();
+        let sql = format!(
+            "SELECT size, hash_algo, file_hash, file_count, root_count, wasted_size FROM ({})
+             ORDER BY wasted_size DESC, size DESC, file_hash
+             LIMIT {} OFFSET {}",
+            duplicate_groups_sql!().replace("{file_where_clause}", &file_where),
+            limit,
+            offset
+        );
+        let param_refs: Vec<&dyn ToSql> = params.iter().map(|p| &**p).collect();
+
+        let mut stmt = conn.prepare(&sql)?;
+        let mut groups = stmt
+            .query_map(&param_refs[..], |row| {
+                Ok(DuplicateGroup {
+                    size: row.get(0)?,
+                    hash_algo: HashAlgo::from_i64(row.get(1)?),
+                    file_hash: Hash::blob_to_hex(row.get(2)?),
+                    file_count: row.get(3)?,
+                    root_count: row.get(4)?,
+                    wasted_size: row.get(5)?,
+                    files: Vec::new(),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if groups.is_empty() || files_per_group == 0 {
+            return Ok(groups);
+        }
+
+        // Fetch the files of every group on the page in one pass
+        let keys = vec!["(?, ?, ?)"; groups.len()].join(", ");
+        let (file_where, mut params) = filter.file_where_clause();
+        let key_params = groups.iter().flat_map(|g| {
+            [
+                Box::new(g.size) as Box<dyn ToSql>,
+                Box::new(g.hash_algo.as_i64()),
+                Box::new(Hash::hex_to_blob(&g.file_hash)),
+            ]
+        });
+        let mut all_params: Vec<Box<dyn ToSql>> = key_params.collect();
+        all_params.append(&mut params);
+        let sql = format!(
+            "WITH page (size, hash_algo, file_hash) AS (VALUES {keys})
+             SELECT f.size, f.hash_algo, f.file_hash, f.root_id, f.item_id, f.item_path, f.mod_date
+             FROM ({}) f
+             JOIN page p ON p.size = f.size AND p.hash_algo = f.hash_algo AND p.file_hash = f.file_hash
+             {file_where}
+             ORDER BY f.item_path COLLATE natural_path",
+            live_hashed_files_sql!()
+        );
+        let param_refs: Vec<&dyn ToSql> = all_params.iter().map(|p| &**p).collect();
+
+        let index: HashMap<(i64, i64, String), usize> = groups
+            .iter()
+            .enumerate()
+            .map(|(i, g)| ((g.size, g.hash_algo.as_i64(), g.file_hash.clone()), i))
+            .collect();
+
+        let mut stmt = conn.prepare(&sql)?;
+        let mut rows = stmt.query(&param_refs[..])?;
+        while let Some(row) = rows.next()? {
+            let key = (row.get(0)?, row.get(1)?, Hash::blob_to_hex(row.get(2)?));
+            let Some(&i) = index.get(&key) else {
+                continue;
+            };
+            let group = &mut groups[i];
+            if group.files.len() < files_per_group {
+                group.files.push(DuplicateFile {
+                    root_id: row.get(3)?,
+                    item_id: row.get(4)?,
+                    item_path: row.get(5)?,
+                    mod_date: row.get(6)?,
+                });
+            }
+        }
+
+        Ok(groups)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_where_clause() {
+        let (clause, params) = DuplicateFilter::default().file_where_clause();
+        assert!(clause.is_empty());
+        assert!(params.is_empty());
+
+        let filter = DuplicateFilter {
+            root_ids: vec![1, 3],
+            path_search: Some("/photos/".to_string()),
+            min_size: Some(1024),
+        };
+        let (clause, params) = filter.file_where_clause();
+        assert_eq!(
+            clause,
+            "WHERE f.root_id IN (?, ?) AND f.item_path LIKE ? AND f.size >= ?"
+        );
+        assert_eq!(params.len(), 4);
+    }
+
+    #[test]
+    fn test_duplicate_groups_sql() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE scans (scan_id INTEGER, root_id INTEGER, state INTEGER);
+             CREATE TABLE items (item_id INTEGER, root_id INTEGER, item_path TEXT,
+                 file_extension TEXT, item_type INTEGER);
+             CREATE TABLE item_versions (item_id INTEGER, item_version INTEGER, root_id INTEGER,
+                 first_scan_id INTEGER, last_scan_id INTEGER, is_deleted INTEGER,
+                 size INTEGER, mod_date INTEGER);
+             CREATE TABLE hash_versions (item_id INTEGER, item_version INTEGER,
+                 first_scan_id INTEGER, hash_algo INTEGER, file_hash BLOB);
+             INSERT INTO scans VALUES (1, 1, 4), (2, 2, 4), (3, 1, 1);
+             INSERT INTO items VALUES
+                 (1, 1, '/a/x.jpg', 'jpg', 0), (2, 1, '/a/y.jpg', 'jpg', 0),
+                 (3, 2, '/b/x.jpg', 'jpg', 0), (4, 2, '/b/gone.jpg', 'jpg', 0),
+                 (5, 2, '/b/other', NULL, 0);
+             INSERT INTO item_versions VALUES
+                 (1, 1, 1, 1, 3, 0, 10, 0), (2, 1, 1, 1, 1, 0, 10, 0),
+                 (3, 1, 2, 2, 2, 0, 10, 0), (4, 1, 2, 2, 2, 1, 10, 0),
+                 (5, 1, 2, 2, 2, 0, 20, 0);
+             INSERT INTO hash_versions VALUES
+                 (1, 1, 1, 0, X'AA'), (2, 1, 1, 0, X'AA'), (3, 1, 2, 0, X'AA'),
+                 (4, 1, 2, 0, X'AA'), (5, 1, 2, 0, X'BB');",
+        )
+        .unwrap();
+
+        let groups = |filter: &str| -> Vec<(i64, i64, String)> {
+            let sql = duplicate_groups_sql!().replace("{file_where_clause}", filter);
+            let mut stmt = conn.prepare(&sql).unwrap();
+            stmt.query_map([], |row| {
+                Ok((row.get("file_count")?, row.get("wasted_size")?, row.get("root_id")?))
+            })
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+        };
+
+        // The deleted copy is left out
+        assert_eq!(groups(""), vec![(3, 20, "1,2".to_string())]);
+        // Per-file filters choose the files before they are grouped
+        assert_eq!(groups("WHERE (root_id = 1)"), vec![(2, 10, "1".to_string())]);
+        assert!(groups("WHERE (item_path LIKE '%/b/%')").is_empty());
+    }
+}
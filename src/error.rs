@@ -1,6 +1,5 @@
 use rusqlite::Error as RusqliteError;
 use std::io;
-use std::path::PathBuf;
 use thiserror::Error;
 
 use crate::query::Rule;
@@ -33,9 +32,6 @@ pub enum FsPulseError {
 
     #[error("Shutting down")]
     ShuttingDown,
-
-    #[error("Directory unreadable: {0}")]
-    DirectoryUnreadable(PathBuf),
 }
 
 #[cfg(test)]
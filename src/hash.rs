@@ -1,7 +1,7 @@
 use std::{
     fmt,
     fs::File,
-    io::Read,
+    io::{Read, Seek, SeekFrom},
     path::Path,
     sync::{
         atomic::{AtomicBool, Ordering},
@@ -11,9 +11,17 @@ use std::{
 
 use hex::encode;
 use log::warn;
-use sha2::{Digest, Sha256};
+use md5::Md5;
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha512};
+use xxhash_rust::xxh3::Xxh3;
 
 use crate::error::FsPulseError;
+use crate::io_throttle::IoThrottle;
+
+/// Size of each block read by a sampled hash
+pub const SAMPLE_BLOCK_SIZE: u64 = 64 * 1024;
 
 /// Represents the hash integrity state of a file.
 /// Stored as integer in the database.
@@ -84,6 +92,169 @@ impl crate::query::QueryEnum for HashState {
     }
 }
 
+/// The algorithm used to hash file contents. Chosen per root and recorded on
+/// each hash observation, since hashes from different algorithms can't be compared.
+/// Stored as integer in the database.
+#[repr(i64)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgo {
+    #[default]
+    Sha256 = 0,
+    Blake3 = 1,
+    /// XXH3 with a 128-bit result. Not cryptographic, but fast and more than
+    /// strong enough to detect corruption.
+    Xxh3 = 2,
+    Sha512 = 3,
+    // The remaining algorithms are only used to check files against checksum
+    // manifests (md5sum, sha1sum and .sfv files). They can't be chosen for a root.
+    Md5 = 4,
+    Sha1 = 5,
+    Crc32 = 6,
+}
+
+impl HashAlgo {
+    pub fn as_i64(&self) -> i64 {
+        *self as i64
+    }
+
+    pub fn from_i64(value: i64) -> Self {
+        match value {
+            0 => HashAlgo::Sha256,
+            1 => HashAlgo::Blake3,
+            2 => HashAlgo::Xxh3,
+            3 => HashAlgo::Sha512,
+            4 => HashAlgo::Md5,
+            5 => HashAlgo::Sha1,
+            6 => HashAlgo::Crc32,
+            _ => {
+                warn!("Invalid HashAlgo value in database: {}, defaulting to SHA-256", value);
+                HashAlgo::Sha256
+            }
+        }
+    }
+
+    pub fn short_name(&self) -> &'static str {
+        match self {
+            HashAlgo::Sha256 => "sha256",
+            HashAlgo::Blake3 => "blake3",
+            HashAlgo::Xxh3 => "xxh3",
+            HashAlgo::Sha512 => "sha512",
+            HashAlgo::Md5 => "md5",
+            HashAlgo::Sha1 => "sha1",
+            HashAlgo::Crc32 => "crc32",
+        }
+    }
+
+    pub fn full_name(&self) -> &'static str {
+        match self {
+            HashAlgo::Sha256 => "SHA-256",
+            HashAlgo::Blake3 => "BLAKE3",
+            HashAlgo::Xxh3 => "XXH3-128",
+            HashAlgo::Sha512 => "SHA-512",
+            HashAlgo::Md5 => "MD5",
+            HashAlgo::Sha1 => "SHA-1",
+            HashAlgo::Crc32 => "CRC32",
+        }
+    }
+
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "sha256" | "sha-256" => Some(HashAlgo::Sha256),
+            "blake3" => Some(HashAlgo::Blake3),
+            "xxh3" | "xxh3-128" => Some(HashAlgo::Xxh3),
+            "sha512" | "sha-512" => Some(HashAlgo::Sha512),
+            "md5" => Some(HashAlgo::Md5),
+            "sha1" | "sha-1" => Some(HashAlgo::Sha1),
+            "crc32" => Some(HashAlgo::Crc32),
+            _ => None,
+        }
+    }
+
+    /// Whether a root can be configured to hash with this algorithm
+    pub fn is_root_algo(&self) -> bool {
+        matches!(
+            self,
+            HashAlgo::Sha256 | HashAlgo::Blake3 | HashAlgo::Xxh3 | HashAlgo::Sha512
+        )
+    }
+
+    /// Length of this algorithm's hash in hex digits
+    pub fn hex_len(&self) -> usize {
+        match self {
+            HashAlgo::Sha256 | HashAlgo::Blake3 => 64,
+            HashAlgo::Xxh3 | HashAlgo::Md5 => 32,
+            HashAlgo::Sha512 => 128,
+            HashAlgo::Sha1 => 40,
+            HashAlgo::Crc32 => 8,
+        }
+    }
+}
+
+impl fmt::Display for HashAlgo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.full_name())
+    }
+}
+
+impl crate::query::QueryEnum for HashAlgo {
+    fn from_token(s: &str) -> Option<i64> {
+        Self::from_string(s).map(|algo| algo.as_i64())
+    }
+}
+
+/// Incremental hasher for any `HashAlgo`
+enum Hasher {
+    Sha256(Sha256),
+    Blake3(Box<blake3::Hasher>),
+    Xxh3(Box<Xxh3>),
+    Sha512(Sha512),
+    Md5(Md5),
+    Sha1(Sha1),
+    Crc32(crc32fast::Hasher),
+}
+
+impl Hasher {
+    fn new(algo: HashAlgo) -> Self {
+        match algo {
+            HashAlgo::Sha256 => Hasher::Sha256(Sha256::new()),
+            HashAlgo::Blake3 => Hasher::Blake3(Box::new(blake3::Hasher::new())),
+            HashAlgo::Xxh3 => Hasher::Xxh3(Box::new(Xxh3::new())),
+            HashAlgo::Sha512 => Hasher::Sha512(Sha512::new()),
+            HashAlgo::Md5 => Hasher::Md5(Md5::new()),
+            HashAlgo::Sha1 => Hasher::Sha1(Sha1::new()),
+            HashAlgo::Crc32 => Hasher::Crc32(crc32fast::Hasher::new()),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Sha256(h) => h.update(data),
+            Hasher::Blake3(h) => {
+                h.update(data);
+            }
+            Hasher::Xxh3(h) => h.update(data),
+            Hasher::Sha512(h) => h.update(data),
+            Hasher::Md5(h) => h.update(data),
+            Hasher::Sha1(h) => h.update(data),
+            Hasher::Crc32(h) => h.update(data),
+        }
+    }
+
+    fn finalize_hex(self) -> String {
+        match self {
+            Hasher::Sha256(h) => encode(h.finalize()),
+            Hasher::Blake3(h) => encode(h.finalize().as_bytes()),
+            // Canonical XXH128 form is big-endian
+            Hasher::Xxh3(h) => encode(h.digest128().to_be_bytes()),
+            Hasher::Sha512(h) => encode(h.finalize()),
+            Hasher::Md5(h) => encode(h.finalize()),
+            Hasher::Sha1(h) => encode(h.finalize()),
+            Hasher::Crc32(h) => format!("{:08x}", h.finalize()),
+        }
+    }
+}
+
 pub struct Hash;
 
 impl Hash {
@@ -102,10 +273,27 @@ impl Hash {
         blob.map(Self::blob_to_hex)
     }
 
-    pub fn compute_sha2_256_hash(
+    /// Hash the contents of the file at `path` with `algo`, returning hex.
+    #[cfg(test)]
+    fn compute_file_hash(
         path: &Path,
+        algo: HashAlgo,
+        throttle: &IoThrottle,
         interrupt_token: &Arc<AtomicBool>,
     ) -> Result<String, FsPulseError> {
+        let mut hashes = Self::compute_file_hashes(path, &[algo], throttle, interrupt_token)?;
+        Ok(hashes.swap_remove(0))
+    }
+
+    /// Hash the contents of the file at `path` with each of `algos` in a
+    /// single read, returning hex in the same order. Reads are paced by
+    /// `throttle`.
+    pub fn compute_file_hashes(
+        path: &Path,
+        algos: &[HashAlgo],
+        throttle: &IoThrottle,
+        interrupt_token: &Arc<AtomicBool>,
+    ) -> Result<Vec<String>, FsPulseError> {
         // Check for interrupt before doing any work
         if interrupt_token.load(Ordering::Acquire) {
             return Err(FsPulseError::TaskInterrupted);
@@ -113,7 +301,7 @@ impl Hash {
 
         let mut f = File::open(path)?;
 
-        let mut hasher = Sha256::new();
+        let mut hashers: Vec<Hasher> = algos.iter().map(|algo| Hasher::new(*algo)).collect();
         let mut buffer = vec![0u8; 4 * 1024 * 1024]; // 4MB on the heap
 
 
@@ -126,17 +314,81 @@ impl Hash {
                 return Err(FsPulseError::TaskInterrupted);
             }
 
+            throttle.wait(interrupt_token)?;
             let bytes_read = f.read(&mut buffer)?;
+            throttle.charge(bytes_read);
             if bytes_read == 0 {
                 break;
             }
-            hasher.update(&buffer[..bytes_read]);
+            for hasher in &mut hashers {
+                hasher.update(&buffer[..bytes_read]);
+            }
         }
 
-        let hash = hasher.finalize();
+        Ok(hashers.into_iter().map(Hasher::finalize_hex).collect())
+    }
+
+    /// Hash a sample of the file at `path` with `algo`: its first and last
+    /// blocks and `blocks` blocks evenly spaced between them. A file too small
+    /// to sample is hashed whole.
+    ///
+    /// A sampled hash is only comparable with another sampled hash taken with
+    /// the same algorithm and block count.
+    pub fn compute_sampled_hash(
+        path: &Path,
+        algo: HashAlgo,
+        blocks: usize,
+        throttle: &IoThrottle,
+        interrupt_token: &Arc<AtomicBool>,
+    ) -> Result<String, FsPulseError> {
+        if interrupt_token.load(Ordering::Acquire) {
+            return Err(FsPulseError::TaskInterrupted);
+        }
 
-        Ok(encode(hash))
+        let mut f = File::open(path)?;
+        let len = f.metadata()?.len();
+
+        let mut hasher = Hasher::new(algo);
+        let mut buffer = vec![0u8; SAMPLE_BLOCK_SIZE as usize];
+
+        for (offset, size) in sample_ranges(len, blocks) {
+            if interrupt_token.load(Ordering::Acquire) {
+                return Err(FsPulseError::TaskInterrupted);
+            }
+
+            f.seek(SeekFrom::Start(offset))?;
+            let mut remaining = size;
+            while remaining > 0 {
+                let want = remaining.min(SAMPLE_BLOCK_SIZE) as usize;
+                throttle.wait(interrupt_token)?;
+                let bytes_read = f.read(&mut buffer[..want])?;
+                throttle.charge(bytes_read);
+                if bytes_read == 0 {
+                    // The file shrank since its length was read
+                    break;
+                }
+                hasher.update(&buffer[..bytes_read]);
+                remaining -= bytes_read as u64;
+            }
+        }
+
+        Ok(hasher.finalize_hex())
+    }
+}
+
+/// The (offset, length) ranges a sampled hash reads from a file of `len`
+/// bytes: the head block, `blocks` blocks evenly spaced through the file and
+/// the tail block. Files no larger than the sample are read whole.
+fn sample_ranges(len: u64, blocks: usize) -> Vec<(u64, u64)> {
+    let count = blocks as u64 + 2;
+    if len <= count * SAMPLE_BLOCK_SIZE {
+        return vec![(0, len)];
     }
+
+    let last = len - SAMPLE_BLOCK_SIZE;
+    (0..count)
+        .map(|i| (last * i / (count - 1), SAMPLE_BLOCK_SIZE))
+        .collect()
 }
 
 #[cfg(test)]
@@ -153,7 +405,7 @@ mod tests {
             .expect("Failed to write to temp file");
 
         let interrupt_token = Arc::new(AtomicBool::new(false));
-        let result = Hash::compute_sha2_256_hash(temp_file.path(), &interrupt_token);
+        let result = Hash::compute_file_hash(temp_file.path(), HashAlgo::Sha256, &IoThrottle::unlimited(), &interrupt_token);
 
         assert!(result.is_ok());
         let hash = result.unwrap();
@@ -172,7 +424,7 @@ mod tests {
             .expect("Failed to write to temp file");
 
         let interrupt_token = Arc::new(AtomicBool::new(false));
-        let result = Hash::compute_sha2_256_hash(temp_file.path(), &interrupt_token);
+        let result = Hash::compute_file_hash(temp_file.path(), HashAlgo::Sha256, &IoThrottle::unlimited(), &interrupt_token);
 
         assert!(result.is_ok());
         let hash = result.unwrap();
@@ -191,7 +443,7 @@ mod tests {
             .expect("Failed to write to temp file");
 
         let interrupt_token = Arc::new(AtomicBool::new(false));
-        let result = Hash::compute_sha2_256_hash(temp_file.path(), &interrupt_token);
+        let result = Hash::compute_file_hash(temp_file.path(), HashAlgo::Sha256, &IoThrottle::unlimited(), &interrupt_token);
 
         assert!(result.is_ok());
         let hash = result.unwrap();
@@ -207,7 +459,7 @@ mod tests {
         let nonexistent_path = std::path::Path::new("/this/path/does/not/exist.txt");
         let interrupt_token = Arc::new(AtomicBool::new(false));
 
-        let result = Hash::compute_sha2_256_hash(nonexistent_path, &interrupt_token);
+        let result = Hash::compute_file_hash(nonexistent_path, HashAlgo::Sha256, &IoThrottle::unlimited(), &interrupt_token);
         assert!(result.is_err());
         assert!(matches!(result.unwrap_err(), FsPulseError::IoError(_)));
     }
@@ -224,9 +476,128 @@ mod tests {
 
         let interrupt_token = Arc::new(AtomicBool::new(true)); // Set to true to trigger interrupt
 
-        let result = Hash::compute_sha2_256_hash(temp_file.path(), &interrupt_token);
+        let result = Hash::compute_file_hash(temp_file.path(), HashAlgo::Sha256, &IoThrottle::unlimited(), &interrupt_token);
 
         assert!(result.is_err());
         assert!(matches!(result.unwrap_err(), FsPulseError::TaskInterrupted));
     }
+
+    #[test]
+    fn test_compute_file_hash_other_algos() {
+        let mut temp_file = NamedTempFile::new().expect("Failed to create temp file");
+        temp_file
+            .write_all(b"")
+            .expect("Failed to write to temp file");
+
+        let interrupt_token = Arc::new(AtomicBool::new(false));
+        let hash = |algo| Hash::compute_file_hash(temp_file.path(), algo, &IoThrottle::unlimited(), &interrupt_token).unwrap();
+
+        assert_eq!(
+            hash(HashAlgo::Blake3),
+            "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+        );
+        assert_eq!(hash(HashAlgo::Xxh3), "99aa06d3014798d86001c324468d497f");
+        assert_eq!(
+            hash(HashAlgo::Sha512),
+            "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e"
+        );
+    }
+
+    #[test]
+    fn test_compute_file_hashes_single_pass() {
+        let mut temp_file = NamedTempFile::new().expect("Failed to create temp file");
+        temp_file
+            .write_all(b"hello world")
+            .expect("Failed to write to temp file");
+
+        let interrupt_token = Arc::new(AtomicBool::new(false));
+        let algos = [HashAlgo::Sha256, HashAlgo::Md5, HashAlgo::Sha1, HashAlgo::Crc32];
+        let hashes = Hash::compute_file_hashes(temp_file.path(), &algos, &IoThrottle::unlimited(), &interrupt_token).unwrap();
+
+        assert_eq!(
+            hashes,
+            vec![
+                "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9",
+                "5eb63bbbe01eeed093cb22bb8f5acdc3",
+                "2aae6c35c94fcfb415dbe95f408b9ce91ee846ed",
+                "0d4a1185",
+            ]
+        );
+        for (algo, hash) in algos.iter().zip(&hashes) {
+            assert_eq!(algo.hex_len(), hash.len());
+        }
+    }
+
+    #[test]
+    fn test_sample_ranges() {
+        let b = SAMPLE_BLOCK_SIZE;
+
+        // Small files are read whole
+        assert_eq!(sample_ranges(0, 8), vec![(0, 0)]);
+        assert_eq!(sample_ranges(10 * b, 8), vec![(0, 10 * b)]);
+
+        // Head, evenly spaced middle blocks, then tail
+        let len = 100 * b + 7;
+        let ranges = sample_ranges(len, 2);
+        assert_eq!(ranges.len(), 4);
+        assert_eq!(ranges[0], (0, b));
+        assert_eq!(ranges[3], (len - b, b));
+        assert!(ranges.windows(2).all(|w| w[0].0 + b <= w[1].0));
+    }
+
+    #[test]
+    fn test_compute_sampled_hash() {
+        let interrupt_token = Arc::new(AtomicBool::new(false));
+
+        // A small file's sampled hash is its full hash
+        let mut small = NamedTempFile::new().expect("Failed to create temp file");
+        small.write_all(b"hello world").expect("Failed to write to temp file");
+        assert_eq!(
+            Hash::compute_sampled_hash(small.path(), HashAlgo::Sha256, 8, &IoThrottle::unlimited(), &interrupt_token).unwrap(),
+            "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9"
+        );
+
+        // A byte changed inside a sampled block changes the hash; one between
+        // blocks does not
+        let mut data = vec![0u8; 40 * SAMPLE_BLOCK_SIZE as usize];
+        let mut large = NamedTempFile::new().expect("Failed to create temp file");
+        large.write_all(&data).expect("Failed to write to temp file");
+        let hash = |file: &NamedTempFile| {
+            Hash::compute_sampled_hash(file.path(), HashAlgo::Blake3, 2, &IoThrottle::unlimited(), &interrupt_token).unwrap()
+        };
+        let original = hash(&large);
+
+        let (middle, _) = sample_ranges(data.len() as u64, 2)[1];
+        data[middle as usize + 1] = 1;
+        let mut sampled = NamedTempFile::new().expect("Failed to create temp file");
+        sampled.write_all(&data).expect("Failed to write to temp file");
+        assert_ne!(hash(&sampled), original);
+
+        data[middle as usize + 1] = 0;
+        data[2 * SAMPLE_BLOCK_SIZE as usize] = 1;
+        let mut unsampled = NamedTempFile::new().expect("Failed to create temp file");
+        unsampled.write_all(&data).expect("Failed to write to temp file");
+        assert_eq!(hash(&unsampled), original);
+    }
+
+    #[test]
+    fn test_hash_algo_round_trip() {
+        for algo in [
+            HashAlgo::Sha256,
+            HashAlgo::Blake3,
+            HashAlgo::Xxh3,
+            HashAlgo::Sha512,
+            HashAlgo::Md5,
+            HashAlgo::Sha1,
+            HashAlgo::Crc32,
+        ] {
+            assert_eq!(HashAlgo::from_i64(algo.as_i64()), algo);
+            assert_eq!(HashAlgo::from_string(algo.short_name()), Some(algo));
+            assert_eq!(HashAlgo::from_string(algo.full_name()), Some(algo));
+        }
+        assert_eq!(HashAlgo::from_i64(99), HashAlgo::Sha256);
+        assert!(HashAlgo::Xxh3.is_root_algo());
+        assert!(!HashAlgo::Md5.is_root_algo());
+        assert_eq!(serde_json::to_string(&HashAlgo::Xxh3).unwrap(), "\"xxh3\"");
+    }
 }
@@ -0,0 +1,287 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::Match;
+use log::{error, warn};
+use serde::{Deserialize, Serialize};
+
+/// Name of the per-directory ignore file honored by the scanner.
+pub const IGNORE_FILE_NAME: &str = ".fspulseignore";
+
+/// An ignore file in effect for a scan, as recorded in the scan's task_state.
+///
+/// The contents are captured when the scan first reads the file, so a resumed
+/// scan evaluates exactly the same patterns even if the file has since changed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IgnoreFile {
+    /// Absolute path of the directory containing the ignore file
+    pub dir: String,
+    pub contents: String,
+}
+
+/// The set of `.fspulseignore` files in effect for a scan.
+///
+/// Patterns use gitignore syntax and apply to the directory containing the file
+/// and everything below it. As in git, a deeper file takes precedence over its
+/// ancestors, and within a file the last matching pattern wins (so `!pattern`
+/// re-includes an entry). An entry below an ignored directory cannot be
+/// re-included, because the walk never descends into that directory.
+///
+/// Ignore files themselves are never ignored — they are tracked as ordinary
+/// items so changes to them show up in scan history.
+pub struct IgnoreFiles {
+    root_path: PathBuf,
+    files: Vec<IgnoreFile>,
+    matchers: HashMap<PathBuf, Gitignore>,
+}
+
+impl IgnoreFiles {
+    /// Build from ignore files previously recorded in task_state.
+    pub fn from_recorded(root_path: &str, files: &[IgnoreFile]) -> Self {
+        let mut ignore_files = IgnoreFiles {
+            root_path: PathBuf::from(root_path),
+            files: Vec::with_capacity(files.len()),
+            matchers: HashMap::with_capacity(files.len()),
+        };
+        for file in files {
+            ignore_files.add(file.clone());
+        }
+        ignore_files
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Whether an ignore file in `dir` has already been recorded. Recorded
+    /// directories (e.g. on resume) keep their recorded contents.
+    pub fn has_dir(&self, dir: &Path) -> bool {
+        self.matchers.contains_key(dir)
+    }
+
+    /// Read the ignore file in `dir`, if one exists. The result is not applied
+    /// until passed to `add`, so callers can read without holding a lock.
+    pub fn read(dir: &Path) -> Option<IgnoreFile> {
+        let ignore_path = dir.join(IGNORE_FILE_NAME);
+        let contents = match fs::read_to_string(&ignore_path) {
+            Ok(contents) => contents,
+            Err(e) => {
+                if !matches!(e.kind(), ErrorKind::NotFound | ErrorKind::IsADirectory) {
+                    error!("Cannot read ignore file '{}': {}", ignore_path.display(), e);
+                }
+                return None;
+            }
+        };
+
+        Some(IgnoreFile {
+            dir: dir.to_string_lossy().into_owned(),
+            contents,
+        })
+    }
+
+    /// Evaluate a single entry. Ancestors are not considered — the walk never
+    /// reaches entries below an ignored directory.
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        if self.matchers.is_empty() || Self::is_ignore_file(path, is_dir) {
+            return false;
+        }
+
+        // Deepest ignore file with an opinion wins
+        for dir in path.ancestors().skip(1) {
+            if let Some(gitignore) = self.matchers.get(dir) {
+                match gitignore.matched(path, is_dir) {
+                    Match::Ignore(_) => return true,
+                    Match::Whitelist(_) => return false,
+                    Match::None => {}
+                }
+            }
+            if dir == self.root_path {
+                break;
+            }
+        }
+
+        false
+    }
+
+    /// Evaluate an entry and each of its ancestor directories below the root.
+    ///
+    /// Used outside the walk (e.g. the sweep), where an item may be hidden because
+    /// a directory above it is ignored.
+    pub fn is_ignored_or_under_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        if self.matchers.is_empty() {
+            return false;
+        }
+
+        let Ok(rel) = path.strip_prefix(&self.root_path) else {
+            return false;
+        };
+
+        let mut current = self.root_path.clone();
+        let mut components = rel.components().peekable();
+        while let Some(component) = components.next() {
+            current.push(component);
+            let current_is_dir = components.peek().is_some() || is_dir;
+            if self.is_ignored(&current, current_is_dir) {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Apply an ignore file to its directory and everything below it.
+    pub fn add(&mut self, file: IgnoreFile) {
+        let dir = PathBuf::from(&file.dir);
+        let mut builder = GitignoreBuilder::new(&dir);
+        for line in file.contents.lines() {
+            if let Err(e) = builder.add_line(None, line) {
+                warn!(
+                    "Skipping invalid pattern in '{}': {}",
+                    dir.join(IGNORE_FILE_NAME).display(),
+                    e
+                );
+            }
+        }
+        let gitignore = builder.build().unwrap_or_else(|e| {
+            warn!(
+                "Ignoring '{}': {}",
+                dir.join(IGNORE_FILE_NAME).display(),
+                e
+            );
+            Gitignore::empty()
+        });
+
+        self.matchers.insert(dir, gitignore);
+        self.files.push(file);
+    }
+
+    fn is_ignore_file(path: &Path, is_dir: bool) -> bool {
+        !is_dir && path.file_name().is_some_and(|name| name == IGNORE_FILE_NAME)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recorded(files: &[(&str, &str)]) -> IgnoreFiles {
+        let files: Vec<IgnoreFile> = files
+            .iter()
+            .map(|(dir, contents)| IgnoreFile {
+                dir: dir.to_string(),
+                contents: contents.to_string(),
+            })
+            .collect();
+        IgnoreFiles::from_recorded("/root", &files)
+    }
+
+    #[test]
+    fn test_empty_ignores_nothing() {
+        let ignores = recorded(&[]);
+        assert!(ignores.is_empty());
+        assert!(!ignores.is_ignored(Path::new("/root/a.tmp"), false));
+        assert!(!ignores.is_ignored_or_under_ignored(Path::new("/root/a/b.tmp"), false));
+    }
+
+    #[test]
+    fn test_patterns_apply_to_own_dir_and_below() {
+        let ignores = recorded(&[("/root/sub", "*.tmp\n")]);
+        assert!(ignores.is_ignored(Path::new("/root/sub/a.tmp"), false));
+        assert!(ignores.is_ignored(Path::new("/root/sub/deep/a.tmp"), false));
+        assert!(!ignores.is_ignored(Path::new("/root/a.tmp"), false));
+        assert!(!ignores.is_ignored(Path::new("/root/other/a.tmp"), false));
+    }
+
+    #[test]
+    fn test_anchored_pattern_is_relative_to_ignore_file() {
+        let ignores = recorded(&[("/root/sub", "/build\n")]);
+        assert!(ignores.is_ignored(Path::new("/root/sub/build"), true));
+        assert!(!ignores.is_ignored(Path::new("/root/sub/x/build"), true));
+    }
+
+    #[test]
+    fn test_directory_only_pattern() {
+        let ignores = recorded(&[("/root", "cache/\n")]);
+        assert!(ignores.is_ignored(Path::new("/root/cache"), true));
+        assert!(!ignores.is_ignored(Path::new("/root/cache"), false));
+    }
+
+    #[test]
+    fn test_negation_within_file() {
+        let ignores = recorded(&[("/root", "*.log\n!keep.log\n")]);
+        assert!(ignores.is_ignored(Path::new("/root/a.log"), false));
+        assert!(!ignores.is_ignored(Path::new("/root/keep.log"), false));
+    }
+
+    #[test]
+    fn test_deeper_file_overrides_parent() {
+        let ignores = recorded(&[("/root", "*.log\n"), ("/root/logs", "!*.log\n")]);
+        assert!(ignores.is_ignored(Path::new("/root/a.log"), false));
+        assert!(!ignores.is_ignored(Path::new("/root/logs/a.log"), false));
+    }
+
+    #[test]
+    fn test_parent_applies_when_child_has_no_opinion() {
+        let ignores = recorded(&[("/root", "*.log\n"), ("/root/sub", "*.tmp\n")]);
+        assert!(ignores.is_ignored(Path::new("/root/sub/a.log"), false));
+        assert!(ignores.is_ignored(Path::new("/root/sub/a.tmp"), false));
+    }
+
+    #[test]
+    fn test_ignore_file_is_never_ignored() {
+        let ignores = recorded(&[("/root", "*\n")]);
+        assert!(ignores.is_ignored(Path::new("/root/anything"), false));
+        assert!(!ignores.is_ignored(Path::new("/root/.fspulseignore"), false));
+    }
+
+    #[test]
+    fn test_under_ignored_dir() {
+        let ignores = recorded(&[("/root", "node_modules/\n")]);
+        assert!(!ignores.is_ignored(Path::new("/root/node_modules/pkg/index.js"), false));
+        assert!(ignores.is_ignored_or_under_ignored(
+            Path::new("/root/node_modules/pkg/index.js"),
+            false
+        ));
+        assert!(!ignores.is_ignored_or_under_ignored(Path::new("/root/src/index.js"), false));
+    }
+
+    #[test]
+    fn test_invalid_pattern_is_skipped() {
+        let ignores = recorded(&[("/root", "a[\n*.tmp\n")]);
+        assert!(ignores.is_ignored(Path::new("/root/x.tmp"), false));
+    }
+
+    #[test]
+    fn test_read_and_add() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_str().unwrap();
+        fs::write(dir.path().join(IGNORE_FILE_NAME), "*.tmp\n").unwrap();
+
+        let mut ignores = IgnoreFiles::from_recorded(root, &[]);
+        assert!(!ignores.has_dir(dir.path()));
+
+        let file = IgnoreFiles::read(dir.path()).unwrap();
+        assert_eq!(file.contents, "*.tmp\n");
+        ignores.add(file);
+
+        assert!(ignores.has_dir(dir.path()));
+        assert!(ignores.is_ignored(&dir.path().join("a.tmp"), false));
+        assert!(!ignores.is_empty());
+    }
+
+    #[test]
+    fn test_read_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(IgnoreFiles::read(dir.path()).is_none());
+    }
+
+    #[test]
+    fn test_recorded_dir_is_known() {
+        let ignores = recorded(&[("/root/sub", "*.old\n")]);
+        assert!(ignores.has_dir(Path::new("/root/sub")));
+        assert!(!ignores.has_dir(Path::new("/root")));
+    }
+}
@@ -10,13 +10,21 @@ use std::{cmp, io::ErrorKind};
 
 use crate::db::Database;
 use crate::error::FsPulseError;
-use crate::hash::Hash;
-use crate::item_identity::Access;
+use crate::hash::{Hash, HashAlgo};
+use crate::io_throttle::IoThrottle;
+use crate::item_identity::{Access, ItemIdentity};
+use crate::roots::Root;
+use crate::scan_scope::ScanScope;
 use crate::scans::{AnalysisSpec, Scan};
+use crate::schedules::TaskEntry;
 use crate::task::{AnalysisTracker, ScanTaskState, TaskProgress};
-use crate::validate::validator::ValidationState;
+use crate::validate::content::{self, ContentCheck};
+use crate::validate::registry::ValidatorRegistry;
+use crate::validate::validator::{file_extension_for_path, ValidationState, ValidatorInfo};
 
 use super::hash_analysis;
+use super::manifest::{self, ManifestCheck, ManifestIndex};
+use super::reverify::ReverifyCutoff;
 use super::val_analysis;
 
 /// Run the file analysis phase (Phase 3 of 4).
@@ -36,7 +44,19 @@ pub fn run_analysis_phase(
     let is_val = scan.analysis_spec().is_val();
 
     // Compute disabled validation extensions once from config
-    let disabled_exts = crate::validate::validator::disabled_extensions();
+    let registry = ValidatorRegistry::global();
+    let disabled_exts = registry.disabled_extensions();
+
+    // Files recorded before a validator for their type was registered pick it up now
+    let synced = ItemIdentity::sync_has_validator(
+        &conn,
+        scan.root_id(),
+        &registry.validator_extensions(),
+        &registry.validator_names(),
+    )?;
+    if synced > 0 {
+        info!("Updated validator availability for {} items", synced);
+    }
 
     // If the scan doesn't hash or validate, skip to scan analyzing
     if !is_hash && !is_val {
@@ -48,12 +68,65 @@ pub fn run_analysis_phase(
     // Compute prev_scan_id once for Case B access versioning (same query as Phase 4)
     let prev_scan_id = query_prev_completed_scan(&conn, scan.root_id(), scan.scan_id())?;
 
+    // Root options can't change while the scan is active, so a resumed scan
+    // hashes with the same algorithm it started with
+    let hash_algo = Root::get_by_id(&conn, scan.root_id())?
+        .ok_or_else(|| FsPulseError::Error(format!("Root {} not found", scan.root_id())))?
+        .options()
+        .hash_algo;
+
+    // Read once so every file in the scan is sampled the same way
+    let sample_blocks = crate::config::Config::get_analysis_sample_blocks();
+    let prefer_content = crate::config::Config::get_validation_prefer_content();
+
+    // Checksum manifests anywhere in the root. Files they list are checked
+    // against them whenever they're hashed.
+    let manifest_index = if is_hash {
+        Arc::new(ManifestIndex::load(&conn, scan.root_id(), scan.scan_id())?)
+    } else {
+        Arc::new(ManifestIndex::default())
+    };
+
     // Parse initial task state for restart resilience (HWM loaded from TaskRow)
-    let initial_state = ScanTaskState::from_task_state(initial_task_state.as_deref())?;
+    let mut initial_state = ScanTaskState::from_task_state(initial_task_state.as_deref())?;
     let initial_hwm = initial_state.high_water_mark;
 
-    let (analyze_total, analyze_done) =
-        AnalysisItem::get_analysis_counts(&conn, scan.root_id(), scan.scan_id(), scan.analysis_spec(), initial_hwm, &disabled_exts)?;
+    // A scan restricted to part of the root only analyzes files inside it
+    let scope_filter = ScanScope::from_recorded(&initial_state.scope).sql_filter("i.item_path");
+
+    // Fix the files a re-verification budget selects before any of them is
+    // re-hashed, so a resumed scan works through the same selection
+    if let (Some(budget), None) = (initial_state.reverify, initial_state.reverify_cutoff) {
+        initial_state.reverify_cutoff =
+            budget.select_cutoff(&conn, scan.root_id(), scan.scan_id(), scope_filter.as_deref())?;
+        if initial_state.reverify_cutoff.is_some() {
+            TaskEntry::set_task_state(task_id, &initial_state.to_json()?)?;
+        }
+    }
+    if let Some(cutoff) = initial_state.reverify_cutoff {
+        task_progress.add_breadcrumb(&format!(
+            "Re-verifying {} files ({} bytes) with the oldest hashes",
+            cutoff.file_count, cutoff.total_size
+        ));
+    }
+    let reverify_cutoff = initial_state.reverify_cutoff;
+
+    // Shared by all worker threads so the read limits apply to the scan as a whole
+    let throttle = Arc::new(IoThrottle::for_analysis(
+        initial_state.read_limits.unwrap_or_default(),
+        Arc::clone(&task_progress),
+    ));
+
+    let (analyze_total, analyze_done) = AnalysisItem::get_analysis_counts(
+        &conn,
+        scan.root_id(),
+        scan.scan_id(),
+        scan.analysis_spec(),
+        initial_hwm,
+        &disabled_exts,
+        scope_filter.as_deref(),
+        reverify_cutoff.as_ref(),
+    )?;
 
     // Set up counter-based progress tracking
     task_progress.set_progress_total(analyze_total, analyze_done, Some("files"));
@@ -82,6 +155,8 @@ pub fn run_analysis_phase(
         let task_progress_clone = Arc::clone(&task_progress);
         let interrupt_token_clone = Arc::clone(interrupt_token);
         let tracker_clone = Arc::clone(&tracker);
+        let manifest_index_clone = Arc::clone(&manifest_index);
+        let throttle_clone = Arc::clone(&throttle);
 
         pool.execute(move || {
             while let Ok(analysis_item) = receiver.recv() {
@@ -89,6 +164,11 @@ pub fn run_analysis_phase(
                     &scan_copy,
                     analysis_item,
                     prev_scan_id,
+                    hash_algo,
+                    sample_blocks,
+                    prefer_content,
+                    &manifest_index_clone,
+                    &throttle_clone,
                     thread_index,
                     &task_progress_clone,
                     &interrupt_token_clone,
@@ -114,6 +194,8 @@ pub fn run_analysis_phase(
             last_item_id,
             100,
             &disabled_exts,
+            scope_filter.as_deref(),
+            reverify_cutoff.as_ref(),
         )?;
 
         if analysis_items.is_empty() {
@@ -145,6 +227,7 @@ pub fn run_analysis_phase(
 
     // Clear thread states and add breadcrumb
     task_progress.clear_thread_states();
+    task_progress.set_io_throughput(None);
     task_progress.add_breadcrumb("Analysis phase complete");
 
     // Advance to next state
@@ -156,10 +239,16 @@ pub fn run_analysis_phase(
 /// Process a single item: compute hash/validate, determine state, persist.
 ///
 /// Called by worker threads. Does not return errors — logs them instead.
+#[allow(clippy::too_many_arguments)]
 fn analyze_item(
     scan: &Scan,
     mut analysis_item: AnalysisItem,
     prev_scan_id: Option<i64>,
+    hash_algo: HashAlgo,
+    sample_blocks: usize,
+    prefer_content: bool,
+    manifest_index: &ManifestIndex,
+    throttle: &IoThrottle,
     thread_index: usize,
     task_progress: &Arc<TaskProgress>,
     interrupt_token: &Arc<AtomicBool>,
@@ -174,20 +263,78 @@ fn analyze_item(
         .unwrap_or(path.as_os_str())
         .to_string_lossy();
 
+    let mut new_sample = None;
     let mut new_hash = None;
+    let mut manifest_check = None;
     let mut read_attempted = false;
     let mut read_succeeded = false;
     let mut read_permission_denied = false;
     let mut file_not_found = false;
 
+    // --- Sampled hash screen ---
+    // A changed sample escalates the file to a full hash below, which is what
+    // decides whether it is Suspect
+    if analysis_item.needs_sample() && !is_interrupted(interrupt_token) {
+        task_progress.set_thread_state(thread_index, "Sampling", "info", Some(&display_path));
+        read_attempted = true;
+
+        match hash_analysis::compute_sampled_hash(&path, hash_algo, sample_blocks, throttle, interrupt_token) {
+            Ok(sample) => {
+                if hash_analysis::sample_changed(&analysis_item, &sample, hash_algo, sample_blocks) {
+                    info!("Sampled hash changed, escalating to full hash: {path:?}");
+                    analysis_item.set_needs_hash(true);
+                }
+                new_sample = Some(sample);
+                read_succeeded = true;
+            }
+            Err(FsPulseError::IoError(ref io_err))
+                if io_err.kind() == ErrorKind::PermissionDenied =>
+            {
+                error!(
+                    "Cannot read file for sampling '{}': Permission denied",
+                    &display_path
+                );
+                read_permission_denied = true;
+            }
+            Err(FsPulseError::IoError(ref io_err))
+                if io_err.kind() == ErrorKind::NotFound =>
+            {
+                log::warn!(
+                    "File not found during sampling '{}': skipping analysis",
+                    &display_path
+                );
+                file_not_found = true;
+            }
+            Err(error) => {
+                error!("Error sampling '{}': {}", &display_path, error);
+            }
+        };
+    }
+
     // --- Hash computation ---
-    if analysis_item.needs_hash() && !is_interrupted(interrupt_token) {
+    if analysis_item.needs_hash()
+        && !read_permission_denied
+        && !file_not_found
+        && !is_interrupted(interrupt_token)
+    {
         task_progress.set_thread_state(thread_index, "Hashing", "info", Some(&display_path));
         read_attempted = true;
 
-        match hash_analysis::compute_hash(&path, interrupt_token) {
-            Ok(hash_s) => {
-                new_hash = Some(hash_s);
+        // Hash with every algorithm the file's manifest entries need in the
+        // same read as the root's own hash
+        let manifest_entries = manifest_index.entries(analysis_item.item_path());
+        let mut algos = vec![hash_algo];
+        for entry in manifest_entries {
+            if !algos.contains(&entry.algo) {
+                algos.push(entry.algo);
+            }
+        }
+
+        match hash_analysis::compute_hashes(&path, &algos, throttle, interrupt_token) {
+            Ok(hashes) => {
+                let computed: Vec<(HashAlgo, String)> = algos.into_iter().zip(hashes).collect();
+                manifest_check = ManifestCheck::evaluate(manifest_entries, &computed);
+                new_hash = computed.into_iter().next().map(|(_, hash)| hash);
                 read_succeeded = true;
             }
             Err(FsPulseError::IoError(ref io_err))
@@ -214,48 +361,104 @@ fn analyze_item(
         };
     }
 
-    // --- Validation ---
-    let mut new_val = ValidationState::Unknown;
-    let mut new_val_error = None;
+    // --- Content detection ---
+    let extension = file_extension_for_path(&path);
+    let mut content_check = None;
 
-    if analysis_item.needs_val()
+    if analysis_item.needs_content()
         && !read_permission_denied
         && !file_not_found
         && !is_interrupted(interrupt_token)
     {
-        task_progress.set_thread_state(thread_index, "Validating", "info-alternate", Some(&display_path));
-        match val_analysis::run_validation(&path, interrupt_token) {
-            Ok((state, err)) => {
-                read_attempted = true;
-                new_val = state;
-                new_val_error = err;
+        task_progress.set_thread_state(thread_index, "Detecting", "info-alternate", Some(&display_path));
+        read_attempted = true;
+
+        match content::sniff(&path, extension.as_deref(), throttle, interrupt_token) {
+            Ok(check) => {
+                if check.mismatch {
+                    info!("Contents of {path:?} are {}, not what its extension claims", check.content_type);
+                }
+                content_check = Some(check);
                 read_succeeded = true;
             }
-            Err(ValAnalysisError::PermissionDenied) => {
-                read_attempted = true;
+            Err(FsPulseError::IoError(ref io_err))
+                if io_err.kind() == ErrorKind::PermissionDenied =>
+            {
                 error!(
-                    "Cannot read file for validation '{}': Permission denied",
+                    "Cannot read file for content detection '{}': Permission denied",
                     &display_path
                 );
                 read_permission_denied = true;
             }
-            Err(ValAnalysisError::NotFound) => {
+            Err(FsPulseError::IoError(ref io_err))
+                if io_err.kind() == ErrorKind::NotFound =>
+            {
                 log::warn!(
-                    "File not found during validation '{}': skipping analysis",
+                    "File not found during content detection '{}': skipping analysis",
                     &display_path
                 );
                 file_not_found = true;
             }
-            Err(ValAnalysisError::NoValidator) => {
-                // Should not happen — needs_val is gated on has_validator.
-                // If it does, just skip validation for this item.
-                log::warn!("NoValidator error for item that passed needs_val check: {:?}", &display_path);
+            Err(error) => {
+                error!("Error detecting content of '{}': {}", &display_path, error);
             }
-            Err(ValAnalysisError::ValidationError(e_str)) => {
-                read_attempted = true;
-                error!("Error validating '{}': {}", &display_path, e_str);
-                new_val = ValidationState::Invalid;
-                new_val_error = Some(e_str);
+        };
+    }
+
+    // --- Validation ---
+    let mut new_val = ValidationState::Unknown;
+    let mut new_val_error = None;
+
+    // The validator for the file's extension or its detected contents,
+    // whichever is preferred, falling back to the other
+    let content_type = content_check
+        .as_ref()
+        .map(|check| check.content_type)
+        .or(analysis_item.content_type());
+    let selected = ValidatorRegistry::global().select(extension.as_deref(), content_type, prefer_content);
+    let has_validator = selected.is_some();
+
+    // Contents detected just now can give a file its first validator
+    if content_check.is_some() && has_validator && !analysis_item.has_validator() {
+        analysis_item.set_needs_val(true);
+    }
+    let validator = selected.filter(|v| v.info().is_enabled());
+
+    if analysis_item.needs_val()
+        && !read_permission_denied
+        && !file_not_found
+        && !is_interrupted(interrupt_token)
+    {
+        if let Some(validator) = validator {
+            task_progress.set_thread_state(thread_index, "Validating", "info-alternate", Some(&display_path));
+            match val_analysis::run_validation(validator, &path, throttle, interrupt_token) {
+                Ok((state, err)) => {
+                    read_attempted = true;
+                    new_val = state;
+                    new_val_error = err;
+                    read_succeeded = true;
+                }
+                Err(ValAnalysisError::PermissionDenied) => {
+                    read_attempted = true;
+                    error!(
+                        "Cannot read file for validation '{}': Permission denied",
+                        &display_path
+                    );
+                    read_permission_denied = true;
+                }
+                Err(ValAnalysisError::NotFound) => {
+                    log::warn!(
+                        "File not found during validation '{}': skipping analysis",
+                        &display_path
+                    );
+                    file_not_found = true;
+                }
+                Err(ValAnalysisError::ValidationError(e_str)) => {
+                    read_attempted = true;
+                    error!("Error validating '{}': {}", &display_path, e_str);
+                    new_val = ValidationState::Invalid;
+                    new_val_error = Some(e_str);
+                }
             }
         }
     }
@@ -263,8 +466,10 @@ fn analyze_item(
     // If the file was not found, disable hash/val analysis — the item was deleted
     // between scan/sweep and analysis. The item will be picked up next scan.
     if file_not_found {
+        analysis_item.set_needs_sample(false);
         analysis_item.set_needs_hash(false);
         analysis_item.set_needs_val(false);
+        analysis_item.set_needs_content(false);
     }
 
     // Determine new access state based on read results
@@ -283,9 +488,15 @@ fn analyze_item(
             scan,
             &analysis_item,
             prev_scan_id,
+            new_sample,
+            sample_blocks,
             new_hash,
+            hash_algo,
+            manifest_check,
             new_val,
             new_val_error,
+            validator.map(|v| v.info()),
+            content_check.map(|check| (check, has_validator)),
             new_access,
             interrupt_token,
         ) {
@@ -321,22 +532,32 @@ fn analyze_item(
 
 /// Persist hash and validation results to the database.
 ///
-/// Writes to `hash_versions` table and val columns on `item_versions`.
+/// Writes to the `hash_versions` and `sampled_hash_versions` tables and val,
+/// manifest and content columns on `item_versions`.
 /// Also handles access state changes on `item_versions`.
 #[allow(clippy::too_many_arguments)]
 fn persist_analysis(
     scan: &Scan,
     analysis_item: &AnalysisItem,
     prev_scan_id: Option<i64>,
+    new_sample: Option<String>,
+    sample_blocks: usize,
     new_hash: Option<String>,
+    hash_algo: HashAlgo,
+    manifest_check: Option<ManifestCheck>,
     new_val: ValidationState,
     new_val_error: Option<String>,
+    val_validator: Option<&ValidatorInfo>,
+    content: Option<(ContentCheck, bool)>,
     new_access: Option<Access>,
     interrupt_token: &Arc<AtomicBool>,
 ) -> Result<(), FsPulseError> {
-    // Determine what needs to be written
+    // Determine what needs to be written. A hash under a different algorithm
+    // than the previous one is written as a new observation even if it happens
+    // to match.
     let hash_changed = analysis_item.needs_hash()
-        && analysis_item.file_hash() != new_hash.as_deref();
+        && (analysis_item.file_hash() != new_hash.as_deref()
+            || (new_hash.is_some() && analysis_item.hash_algo() != Some(hash_algo)));
 
     let mut access_changed = false;
     let mut new_access_value = analysis_item.access();
@@ -351,7 +572,11 @@ fn persist_analysis(
     check_interrupted(interrupt_token)?;
 
     // Pre-write file guard
-    if analysis_item.needs_hash() || analysis_item.needs_val() {
+    if analysis_item.needs_sample()
+        || analysis_item.needs_hash()
+        || analysis_item.needs_val()
+        || analysis_item.needs_content()
+    {
         let path = std::path::Path::new(analysis_item.item_path());
         if !super::file_guard::check_file_unchanged(
             path,
@@ -369,17 +594,32 @@ fn persist_analysis(
     let conn = Database::get_connection()?;
 
     Database::immediate_transaction(&conn, |c| {
+        // Sampled hash persistence — only when the file was actually read
+        if let Some(sample) = new_sample.as_deref().filter(|_| analysis_item.needs_sample()) {
+            hash_analysis::persist_sample(c, scan, analysis_item, sample, hash_algo, sample_blocks)?;
+        }
+
         // Hash persistence
         if analysis_item.needs_hash() {
             hash_analysis::persist_hash(
-                c, scan, analysis_item, new_hash.as_deref(), hash_changed,
+                c, scan, analysis_item, new_hash.as_deref(), hash_algo, hash_changed,
             )?;
+
+            // Manifest persistence — only when the file was actually read
+            if new_hash.is_some() {
+                manifest::persist_manifest(c, scan, analysis_item, manifest_check.as_ref())?;
+            }
+        }
+
+        // Content persistence — only when the file was actually read
+        if let Some((check, has_validator)) = content.as_ref().filter(|_| analysis_item.needs_content()) {
+            val_analysis::persist_content(c, scan, analysis_item, check, *has_validator)?;
         }
 
         // Val persistence
         if analysis_item.needs_val() {
             val_analysis::persist_val(
-                c, scan, analysis_item, new_val, new_val_error.as_deref(),
+                c, scan, analysis_item, val_validator, new_val, new_val_error.as_deref(),
             )?;
         }
 
@@ -390,9 +630,16 @@ fn persist_analysis(
             if analysis_item.version_first_scan_id() == scan.scan_id() {
                 // Case A: Version was created this scan — UPDATE in place.
                 // No undo needed; the entire version is deleted on rollback.
+                // change_reason stays NULL for adds (NULL | x is NULL)
                 c.execute(
-                    "UPDATE item_versions SET access = ? WHERE item_id = ? AND item_version = ?",
-                    rusqlite::params![new_access_value.as_i64(), analysis_item.item_id(), analysis_item.item_version()],
+                    "UPDATE item_versions SET access = ?, change_reason = change_reason | ?
+                     WHERE item_id = ? AND item_version = ?",
+                    rusqlite::params![
+                        new_access_value.as_i64(),
+                        crate::item_version::ChangeReason::ACCESS.as_i64(),
+                        analysis_item.item_id(),
+                        analysis_item.item_version()
+                    ],
                 )?;
             } else {
                 // Case B: Pre-existing version. Close it by restoring last_scan_id
@@ -407,6 +654,10 @@ fn persist_analysis(
                     )?;
                 }
 
+                // Unix metadata isn't part of the analysis item, so carry it from the row
+                let unix = crate::item_version::ItemVersion::get_current(c, analysis_item.item_id())?
+                    .and_then(|v| v.unix().copied());
+
                 crate::item_version::ItemVersion::insert_full(
                     c,
                     analysis_item.item_id(),
@@ -417,6 +668,9 @@ fn persist_analysis(
                     new_access_value,
                     analysis_item.mod_date(),
                     analysis_item.size(),
+                    None,           // symlink_target (files only)
+                    unix.as_ref(),
+                    Some(crate::item_version::ChangeReason::ACCESS),
                     None,           // counts (files only, no folder counts)
                 )?;
             }
@@ -431,8 +685,10 @@ fn persist_analysis(
 /// An item ready for the analysis phase, with its current state and flags
 /// indicating which analysis operations are needed.
 ///
-/// Hash state is sourced from `hash_versions` via LEFT JOIN.
-/// Val state is sourced from `item_versions` columns.
+/// Hash state is sourced from `hash_versions` via LEFT JOIN, and the latest
+/// sample from `sampled_hash_versions` likewise.
+/// Val state and the detected content type are sourced from `item_versions`
+/// columns.
 #[derive(Clone, Debug)]
 pub struct AnalysisItem {
     item_id: i64,
@@ -446,9 +702,20 @@ pub struct AnalysisItem {
     // From hash_versions (NULL if never hashed for this version)
     hash_first_scan_id: Option<i64>,
     file_hash: Option<String>,
+    hash_algo: Option<HashAlgo>,
+    // From sampled_hash_versions (NULL if never sampled for this version)
+    sample_first_scan_id: Option<i64>,
+    sample_last_scan_id: Option<i64>,
+    sample_hash: Option<String>,
+    sample_algo: Option<HashAlgo>,
+    sample_blocks: Option<usize>,
+    // From item_versions (NULL until the contents are examined)
+    content_type: Option<String>,
     // Computed flags
     needs_hash: bool,
     needs_val: bool,
+    needs_sample: bool,
+    needs_content: bool,
 }
 
 impl AnalysisItem {
@@ -480,7 +747,6 @@ impl AnalysisItem {
         self.size
     }
 
-    #[allow(dead_code)]
     pub fn has_validator(&self) -> bool {
         self.has_validator
     }
@@ -493,6 +759,34 @@ impl AnalysisItem {
         self.file_hash.as_deref()
     }
 
+    pub fn hash_algo(&self) -> Option<HashAlgo> {
+        self.hash_algo
+    }
+
+    pub fn sample_first_scan_id(&self) -> Option<i64> {
+        self.sample_first_scan_id
+    }
+
+    pub fn sample_last_scan_id(&self) -> Option<i64> {
+        self.sample_last_scan_id
+    }
+
+    pub fn sample_hash(&self) -> Option<&str> {
+        self.sample_hash.as_deref()
+    }
+
+    pub fn sample_algo(&self) -> Option<HashAlgo> {
+        self.sample_algo
+    }
+
+    pub fn sample_blocks(&self) -> Option<usize> {
+        self.sample_blocks
+    }
+
+    pub fn content_type(&self) -> Option<&str> {
+        self.content_type.as_deref()
+    }
+
     pub fn needs_hash(&self) -> bool {
         self.needs_hash
     }
@@ -509,6 +803,22 @@ impl AnalysisItem {
         self.needs_val = value;
     }
 
+    pub fn needs_sample(&self) -> bool {
+        self.needs_sample
+    }
+
+    pub fn set_needs_sample(&mut self, value: bool) {
+        self.needs_sample = value;
+    }
+
+    pub fn needs_content(&self) -> bool {
+        self.needs_content
+    }
+
+    pub fn set_needs_content(&mut self, value: bool) {
+        self.needs_content = value;
+    }
+
     fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
         Ok(AnalysisItem {
             item_id: row.get(0)?,
@@ -521,8 +831,17 @@ impl AnalysisItem {
             has_validator: row.get::<_, i64>(7)? != 0,
             hash_first_scan_id: row.get(8)?,
             file_hash: Hash::opt_blob_to_hex(row.get(9)?),
-            needs_hash: row.get(10)?,
-            needs_val: row.get(11)?,
+            hash_algo: row.get::<_, Option<i64>>(10)?.map(HashAlgo::from_i64),
+            needs_hash: row.get(11)?,
+            needs_val: row.get(12)?,
+            sample_first_scan_id: row.get(13)?,
+            sample_last_scan_id: row.get(14)?,
+            sample_hash: Hash::opt_blob_to_hex(row.get(15)?),
+            sample_algo: row.get::<_, Option<i64>>(16)?.map(HashAlgo::from_i64),
+            sample_blocks: row.get::<_, Option<i64>>(17)?.map(|blocks| blocks as usize),
+            needs_sample: row.get(18)?,
+            content_type: row.get(19)?,
+            needs_content: row.get(20)?,
         })
     }
 
@@ -555,6 +874,26 @@ impl AnalysisItem {
         }
     }
 
+    /// Build a SQL condition matching validations recorded by an older version
+    /// of their validator than the registered one, so they are redone.
+    /// The values come from the validator registry.
+    fn build_stale_val_condition() -> String {
+        let versions: String = ValidatorRegistry::global()
+            .infos()
+            .map(|info| format!(" WHEN '{}' THEN {}", info.name, info.version))
+            .collect();
+        format!("cv.val_validator_version < CASE cv.val_validator{} END", versions)
+    }
+
+    /// Build a SQL AND clause restricting candidates to a scan's scope.
+    /// Returns empty string for a whole-root scan.
+    fn build_scope_clause(scope_filter: Option<&str>) -> String {
+        scope_filter
+            .map(|filter| format!("\n                    AND {}", filter))
+            .unwrap_or_default()
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn get_analysis_counts(
         conn: &Connection,
         root_id: i64,
@@ -562,21 +901,28 @@ impl AnalysisItem {
         analysis_spec: &AnalysisSpec,
         last_item_id: i64,
         disabled_exts: &[&str],
+        scope_filter: Option<&str>,
+        reverify_cutoff: Option<&ReverifyCutoff>,
     ) -> Result<(u64, u64), FsPulseError> {
         // Driven by idx_versions_root_lastscan (root_id, last_scan_id) to find
         // alive versions for this root at this scan. The item_id filter provides
         // cursor-based pagination.
         let ext_exclusion = Self::build_ext_exclusion_clause(disabled_exts);
+        let stale_val = Self::build_stale_val_condition();
+        let scope_clause = Self::build_scope_clause(scope_filter);
 
         let sql = format!(
             "WITH candidates AS (
                 SELECT
                     hv.last_scan_id AS hash_last_scan,
+                    sv.last_scan_id AS sample_last_scan,
                     cv.val_scan_id,
+                    cv.content_scan_id,
                     CASE
                         WHEN ?1 = 0 THEN 0
                         WHEN ?2 = 1 AND (hv.file_hash IS NULL OR hv.last_scan_id < ?3) THEN 1
                         WHEN hv.file_hash IS NULL THEN 1
+                        WHEN (hv.last_scan_id, cv.item_id) <= (?8, ?9) THEN 1
                         ELSE 0
                     END AS needs_hash,
                     CASE
@@ -584,8 +930,20 @@ impl AnalysisItem {
                         WHEN i.has_validator = 0 THEN 0
                         WHEN i.do_not_validate = 1 THEN 0
                         {ext_exclusion}WHEN cv.val_state IS NULL THEN 1
+                        WHEN {stale_val} THEN 1
+                        ELSE 0
+                    END AS needs_val,
+                    CASE
+                        WHEN ?7 = 0 THEN 0
+                        WHEN sv.file_hash IS NULL OR sv.last_scan_id < ?3 THEN 1
+                        ELSE 0
+                    END AS needs_sample,
+                    CASE
+                        WHEN ?4 = 0 THEN 0
+                        WHEN i.do_not_validate = 1 THEN 0
+                        WHEN cv.content_type IS NULL THEN 1
                         ELSE 0
-                    END AS needs_val
+                    END AS needs_content
                 FROM item_versions cv
                 JOIN items i
                     ON i.item_id = cv.item_id
@@ -596,19 +954,28 @@ impl AnalysisItem {
                         SELECT MAX(first_scan_id) FROM hash_versions
                         WHERE item_id = cv.item_id AND item_version = cv.item_version
                     )
+                LEFT JOIN sampled_hash_versions sv
+                    ON sv.item_id = cv.item_id
+                    AND sv.item_version = cv.item_version
+                    AND sv.first_scan_id = (
+                        SELECT MAX(first_scan_id) FROM sampled_hash_versions
+                        WHERE item_id = cv.item_id AND item_version = cv.item_version
+                    )
                 WHERE
                     cv.root_id = ?6
                     AND cv.last_scan_id = ?3
                     AND i.item_type = 0
                     AND cv.is_deleted = 0
                     AND cv.access <> 1
-                    AND cv.item_id > ?5
+                    AND cv.item_id > ?5{scope_clause}
             )
             SELECT
-                COALESCE(SUM(CASE WHEN needs_hash = 1 OR needs_val = 1 THEN 1 ELSE 0 END), 0) AS total_needed,
+                COALESCE(SUM(CASE WHEN needs_hash = 1 OR needs_val = 1 OR needs_sample = 1 OR needs_content = 1 THEN 1 ELSE 0 END), 0) AS total_needed,
                 COALESCE(SUM(CASE
                     WHEN (needs_hash = 1 AND hash_last_scan = ?3)
                     OR (needs_val = 1 AND val_scan_id = ?3)
+                    OR (needs_sample = 1 AND sample_last_scan = ?3)
+                    OR (needs_content = 1 AND content_scan_id = ?3)
                     THEN 1 ELSE 0 END), 0) AS total_done
             FROM candidates"
         );
@@ -621,6 +988,9 @@ impl AnalysisItem {
             analysis_spec.is_val() as i64,
             last_item_id,
             root_id,
+            analysis_spec.hash_sampled() as i64,
+            reverify_cutoff.map(|c| c.hash_last_scan_id),
+            reverify_cutoff.map(|c| c.item_id),
         ])?;
 
         if let Some(row) = rows.next()? {
@@ -632,6 +1002,7 @@ impl AnalysisItem {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn fetch_next_batch(
         conn: &Connection,
         root_id: i64,
@@ -640,12 +1011,16 @@ impl AnalysisItem {
         last_item_id: i64,
         limit: usize,
         disabled_exts: &[&str],
+        scope_filter: Option<&str>,
+        reverify_cutoff: Option<&ReverifyCutoff>,
     ) -> Result<Vec<AnalysisItem>, FsPulseError> {
         // Driven by idx_versions_root_lastscan (root_id, last_scan_id) to find
         // alive versions for this root at this scan. The item_id filter and ORDER BY
         // provide cursor-based pagination (each item has at most one alive version).
         let ext_exclusion = Self::build_ext_exclusion_clause(disabled_exts);
         let ext_not_in = Self::build_ext_not_in_clause(disabled_exts);
+        let stale_val = Self::build_stale_val_condition();
+        let scope_clause = Self::build_scope_clause(scope_filter);
 
         let query = format!(
             "SELECT
@@ -659,10 +1034,12 @@ impl AnalysisItem {
                 i.has_validator,
                 hv.first_scan_id,
                 hv.file_hash,
+                hv.hash_algo,
                 CASE
                     WHEN ?1 = 0 THEN 0
                     WHEN ?2 = 1 AND (hv.file_hash IS NULL OR hv.last_scan_id < ?3) THEN 1
                     WHEN hv.file_hash IS NULL THEN 1
+                    WHEN (hv.last_scan_id, cv.item_id) <= (?8, ?9) THEN 1
                     ELSE 0
                 END AS needs_hash,
                 CASE
@@ -670,8 +1047,26 @@ impl AnalysisItem {
                     WHEN i.has_validator = 0 THEN 0
                     WHEN i.do_not_validate = 1 THEN 0
                     {ext_exclusion}WHEN cv.val_state IS NULL THEN 1
+                    WHEN {stale_val} THEN 1
+                    ELSE 0
+                END AS needs_val,
+                sv.first_scan_id,
+                sv.last_scan_id,
+                sv.file_hash,
+                sv.hash_algo,
+                sv.sample_blocks,
+                CASE
+                    WHEN ?7 = 0 THEN 0
+                    WHEN sv.file_hash IS NULL OR sv.last_scan_id < ?3 THEN 1
+                    ELSE 0
+                END AS needs_sample,
+                cv.content_type,
+                CASE
+                    WHEN ?4 = 0 THEN 0
+                    WHEN i.do_not_validate = 1 THEN 0
+                    WHEN cv.content_type IS NULL THEN 1
                     ELSE 0
-                END AS needs_val
+                END AS needs_content
             FROM item_versions cv
             JOIN items i
                 ON i.item_id = cv.item_id
@@ -682,20 +1077,32 @@ impl AnalysisItem {
                     SELECT MAX(first_scan_id) FROM hash_versions
                     WHERE item_id = cv.item_id AND item_version = cv.item_version
                 )
+            LEFT JOIN sampled_hash_versions sv
+                ON sv.item_id = cv.item_id
+                AND sv.item_version = cv.item_version
+                AND sv.first_scan_id = (
+                    SELECT MAX(first_scan_id) FROM sampled_hash_versions
+                    WHERE item_id = cv.item_id AND item_version = cv.item_version
+                )
             WHERE
                 cv.root_id = ?6
                 AND cv.last_scan_id = ?3
                 AND i.item_type = 0
                 AND cv.is_deleted = 0
                 AND cv.access <> 1
-                AND cv.item_id > ?5
+                AND cv.item_id > ?5{scope_clause}
                 AND (
                     (?1 = 1 AND (
                         hv.file_hash IS NULL
                         OR (?2 = 1 AND hv.last_scan_id < ?3)
+                        OR (hv.last_scan_id, cv.item_id) <= (?8, ?9)
                     ))
                     OR
-                    (?4 = 1 AND i.has_validator = 1 AND i.do_not_validate = 0 AND cv.val_state IS NULL{ext_not_in})
+                    (?7 = 1 AND (sv.file_hash IS NULL OR sv.last_scan_id < ?3))
+                    OR
+                    (?4 = 1 AND i.has_validator = 1 AND i.do_not_validate = 0 AND (cv.val_state IS NULL OR {stale_val}){ext_not_in})
+                    OR
+                    (?4 = 1 AND i.do_not_validate = 0 AND cv.content_type IS NULL)
                 )
             ORDER BY cv.item_id ASC
             LIMIT {limit}"
@@ -704,13 +1111,16 @@ impl AnalysisItem {
         let mut stmt = conn.prepare(&query)?;
 
         let rows = stmt.query_map(
-            [
+            params![
                 analysis_spec.is_hash() as i64,
                 analysis_spec.hash_all() as i64,
                 scan_id,
                 analysis_spec.is_val() as i64,
                 last_item_id,
                 root_id,
+                analysis_spec.hash_sampled() as i64,
+                reverify_cutoff.map(|c| c.hash_last_scan_id),
+                reverify_cutoff.map(|c| c.item_id),
             ],
             AnalysisItem::from_row,
         )?;
@@ -725,7 +1135,6 @@ impl AnalysisItem {
 pub enum ValAnalysisError {
     PermissionDenied,
     NotFound,
-    NoValidator,
     ValidationError(String),
 }
 
@@ -780,8 +1189,17 @@ mod tests {
             has_validator: true,
             hash_first_scan_id: Some(456),
             file_hash: Some("abc123".to_string()),
+            hash_algo: Some(HashAlgo::Blake3),
+            sample_first_scan_id: Some(400),
+            sample_last_scan_id: Some(450),
+            sample_hash: Some("def456".to_string()),
+            sample_algo: Some(HashAlgo::Blake3),
+            sample_blocks: Some(8),
+            content_type: Some("image".to_string()),
             needs_hash: true,
             needs_val: false,
+            needs_sample: false,
+            needs_content: false,
         };
 
         assert_eq!(analysis_item.item_id(), 123);
@@ -790,7 +1208,40 @@ mod tests {
         assert!(analysis_item.has_validator());
         assert_eq!(analysis_item.hash_first_scan_id(), Some(456));
         assert_eq!(analysis_item.file_hash(), Some("abc123"));
+        assert_eq!(analysis_item.hash_algo(), Some(HashAlgo::Blake3));
         assert!(analysis_item.needs_hash());
         assert!(!analysis_item.needs_val());
+        assert_eq!(analysis_item.sample_hash(), Some("def456"));
+        assert_eq!(analysis_item.sample_blocks(), Some(8));
+        assert!(!analysis_item.needs_sample());
+        assert_eq!(analysis_item.content_type(), Some("image"));
+        assert!(!analysis_item.needs_content());
+    }
+
+    #[test]
+    fn test_stale_val_condition() {
+        let current = ValidatorRegistry::global().for_name("image").unwrap().info().version;
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(&format!(
+            "CREATE TABLE item_versions (item_id INTEGER, val_validator TEXT, val_validator_version INTEGER);
+             INSERT INTO item_versions VALUES
+                 (1, 'image', {}), (2, 'image', {current}), (3, 'retired', 0), (4, NULL, NULL);",
+            current - 1
+        ))
+        .unwrap();
+
+        // Only a validation by an older version of a registered validator is stale
+        let sql = format!(
+            "SELECT item_id FROM item_versions cv WHERE {}",
+            AnalysisItem::build_stale_val_condition()
+        );
+        let stale: Vec<i64> = conn
+            .prepare(&sql)
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(stale, vec![1]);
     }
 }
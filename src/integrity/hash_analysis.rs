@@ -5,30 +5,103 @@ use std::sync::Arc;
 use rusqlite::Connection;
 
 use crate::error::FsPulseError;
-use crate::hash::Hash;
+use crate::hash::{Hash, HashAlgo};
+use crate::io_throttle::IoThrottle;
 use super::analysis::AnalysisItem;
 use crate::scans::Scan;
 use crate::undo_log::UndoLog;
 
 use super::hash_version::{HashState, HashVersion};
+use super::sampled_hash_version::SampledHashVersion;
 
-/// Compute a SHA-256 hash for the file at the given path.
-pub fn compute_hash(
+/// Compute the hashes of the file at the given path: the root's configured
+/// algorithm first, followed by any others its checksum manifests use.
+pub fn compute_hashes(
     path: &Path,
+    algos: &[HashAlgo],
+    throttle: &IoThrottle,
+    interrupt_token: &Arc<AtomicBool>,
+) -> Result<Vec<String>, FsPulseError> {
+    Hash::compute_file_hashes(path, algos, throttle, interrupt_token)
+}
+
+/// Compute a sampled hash of the file at the given path with the root's
+/// algorithm, reading `sample_blocks` blocks between its head and tail.
+pub fn compute_sampled_hash(
+    path: &Path,
+    algo: HashAlgo,
+    sample_blocks: usize,
+    throttle: &IoThrottle,
     interrupt_token: &Arc<AtomicBool>,
 ) -> Result<String, FsPulseError> {
-    Hash::compute_sha2_256_hash(path, interrupt_token)
+    Hash::compute_sampled_hash(path, algo, sample_blocks, throttle, interrupt_token)
+}
+
+/// Whether a new sample shows the file's contents changed. Only a previous
+/// sample taken the same way (algorithm and block count) can be compared; any
+/// other previous sample, or none, is no evidence of change.
+pub fn sample_changed(
+    analysis_item: &AnalysisItem,
+    new_sample: &str,
+    algo: HashAlgo,
+    sample_blocks: usize,
+) -> bool {
+    analysis_item.sample_algo() == Some(algo)
+        && analysis_item.sample_blocks() == Some(sample_blocks)
+        && analysis_item.sample_hash().is_some_and(|prev| prev != new_sample)
+}
+
+/// Persist a sampled hash to `sampled_hash_versions`.
+///
+/// A sample matching the previous one (taken the same way) extends its
+/// `last_scan_id`. Anything else starts a new row.
+pub fn persist_sample(
+    conn: &Connection,
+    scan: &Scan,
+    analysis_item: &AnalysisItem,
+    new_sample: &str,
+    algo: HashAlgo,
+    sample_blocks: usize,
+) -> Result<(), FsPulseError> {
+    let unchanged = analysis_item.sample_algo() == Some(algo)
+        && analysis_item.sample_blocks() == Some(sample_blocks)
+        && analysis_item.sample_hash() == Some(new_sample);
+
+    match (unchanged, analysis_item.sample_first_scan_id(), analysis_item.sample_last_scan_id()) {
+        (true, Some(first_scan_id), Some(last_scan_id)) => {
+            UndoLog::log_sampled_hash_version_extend(
+                conn, analysis_item.item_id(), analysis_item.item_version(), first_scan_id, last_scan_id,
+            )?;
+            SampledHashVersion::extend_last_scan(
+                conn, analysis_item.item_id(), analysis_item.item_version(), first_scan_id, scan.scan_id(),
+            )?;
+        }
+        _ => {
+            SampledHashVersion::insert(
+                conn,
+                analysis_item.item_id(),
+                analysis_item.item_version(),
+                scan.scan_id(),
+                new_sample,
+                algo,
+                sample_blocks,
+            )?;
+        }
+    }
+
+    Ok(())
 }
 
 /// Persist hash results to `hash_versions`.
 ///
-/// If the hash changed (or is new), inserts a new row. If unchanged, extends
-/// `last_scan_id` on the existing row.
+/// If the hash changed (or is new, or was computed with a different algorithm),
+/// inserts a new row. If unchanged, extends `last_scan_id` on the existing row.
 pub fn persist_hash(
     conn: &Connection,
     scan: &Scan,
     analysis_item: &AnalysisItem,
     new_hash: Option<&str>,
+    algo: HashAlgo,
     hash_changed: bool,
 ) -> Result<(), FsPulseError> {
     let hash_state;
@@ -37,6 +110,11 @@ pub fn persist_hash(
         if analysis_item.hash_first_scan_id().is_none() {
             // First hash for this item_version — Baseline
             hash_state = HashState::Baseline;
+        } else if analysis_item.hash_algo() != Some(algo) {
+            // The root switched algorithms. Hashes from different algorithms
+            // can't be compared, so the first one under the new algorithm is a
+            // fresh Baseline.
+            hash_state = HashState::Baseline;
         } else {
             // Hash changed within same item_version — always Suspect.
             // Metadata changes (mod_date, size) always produce a new item_version,
@@ -51,6 +129,7 @@ pub fn persist_hash(
             analysis_item.item_version(),
             scan.scan_id(),
             new_hash.unwrap(),
+            algo,
             hash_state,
         )?;
     } else if let Some(first_scan_id) = analysis_item.hash_first_scan_id() {
@@ -1,6 +1,6 @@
 use rusqlite::{params, Connection, OptionalExtension};
 
-use crate::{error::FsPulseError, hash::Hash};
+use crate::{error::FsPulseError, hash::{Hash, HashAlgo}};
 
 /// Represents the hash integrity state of a file.
 /// Stored as integer in the database.
@@ -44,6 +44,7 @@ pub struct HashVersion {
     last_scan_id: i64,
     file_hash: String,
     hash_state: HashState,
+    hash_algo: HashAlgo,
 }
 
 #[allow(dead_code)]
@@ -72,6 +73,10 @@ impl HashVersion {
         self.hash_state
     }
 
+    pub fn hash_algo(&self) -> HashAlgo {
+        self.hash_algo
+    }
+
     /// Get the most recent hash_version for an item_version (if any).
     pub fn get_current_for_version(
         conn: &Connection,
@@ -79,7 +84,7 @@ impl HashVersion {
         item_version: i64,
     ) -> Result<Option<Self>, FsPulseError> {
         conn.query_row(
-            "SELECT item_id, item_version, first_scan_id, last_scan_id, file_hash, hash_state
+            "SELECT item_id, item_version, first_scan_id, last_scan_id, file_hash, hash_state, hash_algo
              FROM hash_versions
              WHERE item_id = ? AND item_version = ?
              ORDER BY first_scan_id DESC
@@ -98,13 +103,14 @@ impl HashVersion {
         item_version: i64,
         scan_id: i64,
         file_hash: &str,
+        hash_algo: HashAlgo,
         hash_state: HashState,
     ) -> Result<(), FsPulseError> {
         let hash_blob = Hash::hex_to_blob(file_hash);
         conn.execute(
-            "INSERT INTO hash_versions (item_id, item_version, first_scan_id, last_scan_id, file_hash, hash_state)
-             VALUES (?, ?, ?, ?, ?, ?)",
-            params![item_id, item_version, scan_id, scan_id, hash_blob, hash_state.as_i64()],
+            "INSERT INTO hash_versions (item_id, item_version, first_scan_id, last_scan_id, file_hash, hash_state, hash_algo)
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+            params![item_id, item_version, scan_id, scan_id, hash_blob, hash_state.as_i64(), hash_algo.as_i64()],
         )?;
         Ok(())
     }
@@ -133,6 +139,7 @@ impl HashVersion {
             last_scan_id: row.get(3)?,
             file_hash: Hash::blob_to_hex(row.get(4)?),
             hash_state: HashState::from_i64(row.get(5)?),
+            hash_algo: HashAlgo::from_i64(row.get(6)?),
         })
     }
 }
@@ -1,6 +1,10 @@
 use rusqlite::types::Value;
 
-use crate::{db::Database, error::FsPulseError};
+use crate::{
+    db::Database,
+    error::FsPulseError,
+    hash::{Hash, HashAlgo},
+};
 
 // ---------------------------------------------------------------------------
 // Shared query parameters and SQL builders
@@ -9,7 +13,7 @@ use crate::{db::Database, error::FsPulseError};
 /// Filter parameters shared by count, items, and versions queries.
 pub struct IntegrityFilter {
     pub root_id: i64,
-    /// "val", "hash", or None for all
+    /// "val", "hash", "manifest", "content", or None for all
     pub issue_type: Option<String>,
     /// Lowercase extensions to filter by (empty = no filter)
     pub extensions: Vec<String>,
@@ -32,6 +36,12 @@ const HAS_HASH: &str = "EXISTS (
       AND hv.hash_state = 2
 )";
 
+/// This version disagrees with a checksum manifest.
+const HAS_MANIFEST: &str = "iv.manifest_state = 2";
+
+/// This version's contents are a different type than its extension claims.
+const HAS_CONTENT: &str = "iv.content_mismatch = 1";
+
 /// This version has an unreviewed validation issue.
 const VAL_UNREVIEWED: &str = "(iv.val_state = 2 AND iv.val_reviewed_at IS NULL)";
 
@@ -43,6 +53,12 @@ const HASH_UNREVIEWED: &str = "(iv.hash_reviewed_at IS NULL AND EXISTS (
       AND hv.hash_state = 2
 ))";
 
+/// This version has an unreviewed manifest mismatch.
+const MANIFEST_UNREVIEWED: &str = "(iv.manifest_state = 2 AND iv.manifest_reviewed_at IS NULL)";
+
+/// This version has an unreviewed content mismatch.
+const CONTENT_UNREVIEWED: &str = "(iv.content_mismatch = 1 AND iv.content_reviewed_at IS NULL)";
+
 /// Build the version-level inclusion predicate from issue_type + status.
 fn build_inclusion(f: &IntegrityFilter) -> String {
     let issue_type = f.issue_type.as_deref().unwrap_or("all");
@@ -56,11 +72,22 @@ fn build_inclusion(f: &IntegrityFilter) -> String {
         ("hash", "reviewed") => format!("{HAS_HASH} AND iv.hash_reviewed_at IS NOT NULL"),
         ("hash", _) => HAS_HASH.to_string(),
 
-        (_, "unreviewed") => format!("({VAL_UNREVIEWED} OR {HASH_UNREVIEWED})"),
+        ("manifest", "unreviewed") => MANIFEST_UNREVIEWED.to_string(),
+        ("manifest", "reviewed") => format!("{HAS_MANIFEST} AND iv.manifest_reviewed_at IS NOT NULL"),
+        ("manifest", _) => HAS_MANIFEST.to_string(),
+
+        ("content", "unreviewed") => CONTENT_UNREVIEWED.to_string(),
+        ("content", "reviewed") => format!("{HAS_CONTENT} AND iv.content_reviewed_at IS NOT NULL"),
+        ("content", _) => HAS_CONTENT.to_string(),
+
+        (_, "unreviewed") => format!(
+            "({VAL_UNREVIEWED} OR {HASH_UNREVIEWED} OR {MANIFEST_UNREVIEWED} OR {CONTENT_UNREVIEWED})"
+        ),
         (_, "reviewed") => format!(
-            "({HAS_VAL} OR {HAS_HASH}) AND NOT {VAL_UNREVIEWED} AND NOT {HASH_UNREVIEWED}"
+            "({HAS_VAL} OR {HAS_HASH} OR {HAS_MANIFEST} OR {HAS_CONTENT}) AND NOT {VAL_UNREVIEWED} \
+             AND NOT {HASH_UNREVIEWED} AND NOT {MANIFEST_UNREVIEWED} AND NOT {CONTENT_UNREVIEWED}"
         ),
-        _ => format!("({HAS_VAL} OR {HAS_HASH})"),
+        _ => format!("({HAS_VAL} OR {HAS_HASH} OR {HAS_MANIFEST} OR {HAS_CONTENT})"),
     }
 }
 
@@ -140,6 +167,10 @@ pub struct IntegrityItemSummary {
     pub hash_reviewed: i64,
     pub val_unreviewed: i64,
     pub val_reviewed: i64,
+    pub manifest_unreviewed: i64,
+    pub manifest_reviewed: i64,
+    pub content_unreviewed: i64,
+    pub content_reviewed: i64,
 }
 
 pub fn query_items(
@@ -156,10 +187,10 @@ pub fn query_items(
     let issue_type = f.issue_type.as_deref().unwrap_or("all");
 
     // Count expressions respect the issue_type filter:
-    // When issue_type is "hash", val counts are 0. When "val", hash counts are 0.
+    // Only the counts for the selected issue_type are computed; the others are 0.
     // The WHERE clause already filters to matching versions (issue_type + status),
     // so these counts reflect exactly what passes the filters.
-    let (hash_unrev_expr, hash_rev_expr) = if issue_type == "val" {
+    let (hash_unrev_expr, hash_rev_expr) = if matches!(issue_type, "val" | "manifest" | "content") {
         ("0".to_string(), "0".to_string())
     } else {
         (
@@ -168,7 +199,7 @@ pub fn query_items(
         )
     };
 
-    let (val_unrev_expr, val_rev_expr) = if issue_type == "hash" {
+    let (val_unrev_expr, val_rev_expr) = if matches!(issue_type, "hash" | "manifest" | "content") {
         ("0".to_string(), "0".to_string())
     } else {
         (
@@ -177,6 +208,24 @@ pub fn query_items(
         )
     };
 
+    let (manifest_unrev_expr, manifest_rev_expr) = if matches!(issue_type, "hash" | "val" | "content") {
+        ("0".to_string(), "0".to_string())
+    } else {
+        (
+            format!("SUM(CASE WHEN {HAS_MANIFEST} AND iv.manifest_reviewed_at IS NULL THEN 1 ELSE 0 END)"),
+            format!("SUM(CASE WHEN {HAS_MANIFEST} AND iv.manifest_reviewed_at IS NOT NULL THEN 1 ELSE 0 END)"),
+        )
+    };
+
+    let (content_unrev_expr, content_rev_expr) = if matches!(issue_type, "hash" | "val" | "manifest") {
+        ("0".to_string(), "0".to_string())
+    } else {
+        (
+            format!("SUM(CASE WHEN {HAS_CONTENT} AND iv.content_reviewed_at IS NULL THEN 1 ELSE 0 END)"),
+            format!("SUM(CASE WHEN {HAS_CONTENT} AND iv.content_reviewed_at IS NOT NULL THEN 1 ELSE 0 END)"),
+        )
+    };
+
     let sql = format!(
         "SELECT
              i.item_id,
@@ -188,7 +237,11 @@ pub fn query_items(
              {hash_rev_expr} AS hash_reviewed,
              {val_unrev_expr} AS val_unreviewed,
              {val_rev_expr} AS val_reviewed,
-             MAX(iv.last_scan_id) AS latest_scan_id
+             MAX(iv.last_scan_id) AS latest_scan_id,
+             {manifest_unrev_expr} AS manifest_unreviewed,
+             {manifest_rev_expr} AS manifest_reviewed,
+             {content_unrev_expr} AS content_unreviewed,
+             {content_rev_expr} AS content_reviewed
          FROM item_versions iv
          JOIN items i ON i.item_id = iv.item_id
          WHERE {where_clause}
@@ -212,6 +265,10 @@ pub fn query_items(
                 val_unreviewed: row.get(7)?,
                 val_reviewed: row.get(8)?,
                 latest_scan_id: row.get(9)?,
+                manifest_unreviewed: row.get(10)?,
+                manifest_reviewed: row.get(11)?,
+                content_unreviewed: row.get(12)?,
+                content_reviewed: row.get(13)?,
             })
         })?
         .collect::<rusqlite::Result<Vec<_>>>()?;
@@ -233,6 +290,14 @@ pub struct IntegrityVersion {
     pub val_error: Option<String>,
     pub val_reviewed_at: Option<i64>,
     pub hash_reviewed_at: Option<i64>,
+    pub manifest_state: Option<i64>,
+    pub manifest_path: Option<String>,
+    pub manifest_algo: Option<HashAlgo>,
+    pub manifest_hash: Option<String>,
+    pub manifest_reviewed_at: Option<i64>,
+    pub content_type: Option<String>,
+    pub content_mismatch: bool,
+    pub content_reviewed_at: Option<i64>,
 }
 
 pub struct IntegrityVersionResult {
@@ -283,7 +348,15 @@ pub fn query_versions(
              iv.val_state,
              iv.val_error,
              iv.val_reviewed_at,
-             iv.hash_reviewed_at
+             iv.hash_reviewed_at,
+             iv.manifest_state,
+             iv.manifest_path,
+             iv.manifest_algo,
+             iv.manifest_hash,
+             iv.manifest_reviewed_at,
+             iv.content_type,
+             iv.content_mismatch,
+             iv.content_reviewed_at
          FROM item_versions iv
          JOIN scans s ON s.scan_id = iv.first_scan_id
          WHERE {where_clause}
@@ -306,6 +379,14 @@ pub fn query_versions(
                 val_error: row.get(6)?,
                 val_reviewed_at: row.get(7)?,
                 hash_reviewed_at: row.get(8)?,
+                manifest_state: row.get(9)?,
+                manifest_path: row.get(10)?,
+                manifest_algo: row.get::<_, Option<i64>>(11)?.map(HashAlgo::from_i64),
+                manifest_hash: Hash::opt_blob_to_hex(row.get(12)?),
+                manifest_reviewed_at: row.get(13)?,
+                content_type: row.get(14)?,
+                content_mismatch: row.get::<_, Option<i64>>(15)? == Some(1),
+                content_reviewed_at: row.get(16)?,
             })
         })?
         .collect::<rusqlite::Result<Vec<_>>>()?;
@@ -317,11 +398,13 @@ pub fn query_versions(
 // Review: mark reviewed on item or specific version
 // ---------------------------------------------------------------------------
 
-/// Set or clear val_reviewed_at and/or hash_reviewed_at.
+/// Set or clear val_reviewed_at, hash_reviewed_at, manifest_reviewed_at and/or
+/// content_reviewed_at.
 ///
 /// If `item_version` is Some, targets that specific version.
 /// If `item_version` is None, targets all versions of the item that have
-/// the relevant issue (val_state=2 for val, suspect hashes for hash).
+/// the relevant issue (val_state=2 for val, suspect hashes for hash,
+/// manifest_state=2 for manifest, content_mismatch=1 for content).
 ///
 /// A single timestamp is used for all updates in the call.
 pub fn set_reviewed(
@@ -329,6 +412,8 @@ pub fn set_reviewed(
     item_version: Option<i64>,
     set_val: Option<bool>,
     set_hash: Option<bool>,
+    set_manifest: Option<bool>,
+    set_content: Option<bool>,
 ) -> Result<(), FsPulseError> {
     let conn = Database::get_connection()?;
     let now = chrono::Utc::now().timestamp();
@@ -393,6 +478,46 @@ pub fn set_reviewed(
         )?;
     }
 
+    if let Some(val) = set_manifest {
+        let ts: Option<i64> = if val { Some(now) } else { None };
+        let version_filter = match item_version {
+            Some(v) => format!("AND item_version = {v}"),
+            None => String::new(),
+        };
+        let guard = if val {
+            "AND manifest_reviewed_at IS NULL"
+        } else {
+            "AND manifest_reviewed_at IS NOT NULL"
+        };
+        conn.execute(
+            &format!(
+                "UPDATE item_versions SET manifest_reviewed_at = ?
+                 WHERE item_id = ? {version_filter} AND manifest_state = 2 {guard}"
+            ),
+            rusqlite::params![ts, item_id],
+        )?;
+    }
+
+    if let Some(val) = set_content {
+        let ts: Option<i64> = if val { Some(now) } else { None };
+        let version_filter = match item_version {
+            Some(v) => format!("AND item_version = {v}"),
+            None => String::new(),
+        };
+        let guard = if val {
+            "AND content_reviewed_at IS NULL"
+        } else {
+            "AND content_reviewed_at IS NOT NULL"
+        };
+        conn.execute(
+            &format!(
+                "UPDATE item_versions SET content_reviewed_at = ?
+                 WHERE item_id = ? {version_filter} AND content_mismatch = 1 {guard}"
+            ),
+            rusqlite::params![ts, item_id],
+        )?;
+    }
+
     Ok(())
 }
 
@@ -415,6 +540,8 @@ pub fn bulk_review_by_ids(
     item_ids: &[i64],
     set_val: Option<bool>,
     set_hash: Option<bool>,
+    set_manifest: Option<bool>,
+    set_content: Option<bool>,
 ) -> Result<u64, FsPulseError> {
     let conn = Database::get_connection()?;
     let now = chrono::Utc::now().timestamp();
@@ -467,6 +594,44 @@ pub fn bulk_review_by_ids(
                 params.iter().map(|v| v as &dyn rusqlite::ToSql).collect();
             affected += conn.execute(&sql, refs.as_slice())? as u64;
         }
+
+        if let Some(val) = set_manifest {
+            let ts: Option<i64> = if val { Some(now) } else { None };
+            let guard = if val { "AND manifest_reviewed_at IS NULL" } else { "AND manifest_reviewed_at IS NOT NULL" };
+            let sql = format!(
+                "UPDATE item_versions SET manifest_reviewed_at = ?
+                 WHERE item_id IN ({placeholders}) AND manifest_state = 2 {guard}"
+            );
+            let mut params: Vec<Value> = vec![match ts {
+                Some(t) => Value::Integer(t),
+                None => Value::Null,
+            }];
+            for id in chunk {
+                params.push(Value::Integer(*id));
+            }
+            let refs: Vec<&dyn rusqlite::ToSql> =
+                params.iter().map(|v| v as &dyn rusqlite::ToSql).collect();
+            affected += conn.execute(&sql, refs.as_slice())? as u64;
+        }
+
+        if let Some(val) = set_content {
+            let ts: Option<i64> = if val { Some(now) } else { None };
+            let guard = if val { "AND content_reviewed_at IS NULL" } else { "AND content_reviewed_at IS NOT NULL" };
+            let sql = format!(
+                "UPDATE item_versions SET content_reviewed_at = ?
+                 WHERE item_id IN ({placeholders}) AND content_mismatch = 1 {guard}"
+            );
+            let mut params: Vec<Value> = vec![match ts {
+                Some(t) => Value::Integer(t),
+                None => Value::Null,
+            }];
+            for id in chunk {
+                params.push(Value::Integer(*id));
+            }
+            let refs: Vec<&dyn rusqlite::ToSql> =
+                params.iter().map(|v| v as &dyn rusqlite::ToSql).collect();
+            affected += conn.execute(&sql, refs.as_slice())? as u64;
+        }
     }
 
     Ok(affected)
@@ -477,6 +642,8 @@ pub fn bulk_review_by_filter(
     filter: &IntegrityFilter,
     set_val: Option<bool>,
     set_hash: Option<bool>,
+    set_manifest: Option<bool>,
+    set_content: Option<bool>,
 ) -> Result<u64, FsPulseError> {
     let conn = Database::get_connection()?;
     let now = chrono::Utc::now().timestamp();
@@ -524,5 +691,39 @@ pub fn bulk_review_by_filter(
         affected += conn.execute(&sql, params.as_slice())? as u64;
     }
 
+    if let Some(val) = set_manifest {
+        let ts: Option<i64> = if val { Some(now) } else { None };
+        let guard = if val { "AND manifest_reviewed_at IS NULL" } else { "AND manifest_reviewed_at IS NOT NULL" };
+        let sql = format!(
+            "UPDATE item_versions SET manifest_reviewed_at = ?
+             WHERE manifest_state = 2 {guard} AND item_id IN (
+                 SELECT DISTINCT i.item_id
+                 FROM item_versions iv
+                 JOIN items i ON i.item_id = iv.item_id
+                 WHERE {where_clause}
+             )"
+        );
+        let mut params: Vec<&dyn rusqlite::ToSql> = vec![&ts];
+        params.extend(vals.iter().map(|v| v as &dyn rusqlite::ToSql));
+        affected += conn.execute(&sql, params.as_slice())? as u64;
+    }
+
+    if let Some(val) = set_content {
+        let ts: Option<i64> = if val { Some(now) } else { None };
+        let guard = if val { "AND content_reviewed_at IS NULL" } else { "AND content_reviewed_at IS NOT NULL" };
+        let sql = format!(
+            "UPDATE item_versions SET content_reviewed_at = ?
+             WHERE content_mismatch = 1 {guard} AND item_id IN (
+                 SELECT DISTINCT i.item_id
+                 FROM item_versions iv
+                 JOIN items i ON i.item_id = iv.item_id
+                 WHERE {where_clause}
+             )"
+        );
+        let mut params: Vec<&dyn rusqlite::ToSql> = vec![&ts];
+        params.extend(vals.iter().map(|v| v as &dyn rusqlite::ToSql));
+        affected += conn.execute(&sql, params.as_slice())? as u64;
+    }
+
     Ok(affected)
 }
@@ -0,0 +1,625 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use log::{info, warn};
+use rusqlite::{params, Connection};
+
+use crate::error::FsPulseError;
+use crate::hash::{Hash, HashAlgo};
+use crate::scans::Scan;
+
+use super::analysis::AnalysisItem;
+
+/// Manifests larger than this are skipped rather than read into memory
+const MAX_MANIFEST_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Result of checking a file against a checksum manifest.
+/// Stored as integer in the database.
+///
+/// NULL manifest_state on item_versions means no manifest lists the file, or
+/// the file hasn't been hashed since one appeared.
+#[repr(i64)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ManifestState {
+    Match = 1,
+    Mismatch = 2,
+}
+
+impl ManifestState {
+    pub fn as_i64(self) -> i64 {
+        self as i64
+    }
+
+    pub fn from_i64(value: i64) -> Self {
+        match value {
+            1 => ManifestState::Match,
+            2 => ManifestState::Mismatch,
+            _ => {
+                warn!("Invalid ManifestState value in database: {}, defaulting to Match", value);
+                ManifestState::Match
+            }
+        }
+    }
+
+    pub fn short_name(self) -> &'static str {
+        match self {
+            ManifestState::Match => "M",
+            ManifestState::Mismatch => "X",
+        }
+    }
+
+    pub fn full_name(self) -> &'static str {
+        match self {
+            ManifestState::Match => "Match",
+            ManifestState::Mismatch => "Mismatch",
+        }
+    }
+
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s.to_ascii_uppercase().as_str() {
+            "MATCH" | "M" => Some(ManifestState::Match),
+            "MISMATCH" | "X" => Some(ManifestState::Mismatch),
+            _ => None,
+        }
+    }
+}
+
+impl crate::query::QueryEnum for ManifestState {
+    fn from_token(s: &str) -> Option<i64> {
+        Self::from_string(s).map(|state| state.as_i64())
+    }
+}
+
+/// The manifest formats recognized in a root
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ManifestFormat {
+    /// md5sum / sha*sum / b3sum output, in GNU or BSD (`--tag`) style. Carries
+    /// the algorithm the file name implies, if any.
+    Sums(Option<HashAlgo>),
+    /// Simple File Verification: a CRC32 per file
+    Sfv,
+    /// A PAR2 index, whose file description packets hold an MD5 per file
+    Par2,
+}
+
+impl ManifestFormat {
+    /// The format of a manifest with this file name, or None if the name isn't
+    /// one we treat as a manifest.
+    fn from_file_name(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+
+        let base = name.strip_suffix(".txt").unwrap_or(&name);
+        let by_name = match base {
+            "md5sums" | "md5sum" => Some(Some(HashAlgo::Md5)),
+            "sha1sums" | "sha1sum" => Some(Some(HashAlgo::Sha1)),
+            "sha256sums" | "sha256sum" => Some(Some(HashAlgo::Sha256)),
+            "sha512sums" | "sha512sum" => Some(Some(HashAlgo::Sha512)),
+            "b3sums" | "b3sum" => Some(Some(HashAlgo::Blake3)),
+            "checksums" | "checksum" => Some(None),
+            _ => None,
+        };
+        if let Some(algo) = by_name {
+            return Some(ManifestFormat::Sums(algo));
+        }
+
+        let (stem, ext) = name.rsplit_once('.')?;
+        if stem.is_empty() {
+            return None;
+        }
+        match ext {
+            "md5" => Some(ManifestFormat::Sums(Some(HashAlgo::Md5))),
+            "sha1" => Some(ManifestFormat::Sums(Some(HashAlgo::Sha1))),
+            "sha256" => Some(ManifestFormat::Sums(Some(HashAlgo::Sha256))),
+            "sha512" => Some(ManifestFormat::Sums(Some(HashAlgo::Sha512))),
+            "b3" => Some(ManifestFormat::Sums(Some(HashAlgo::Blake3))),
+            "sfv" => Some(ManifestFormat::Sfv),
+            // Recovery volumes (name.vol03+04.par2) repeat the index's file list
+            "par2" if !Self::is_par2_volume(stem) => Some(ManifestFormat::Par2),
+            _ => None,
+        }
+    }
+
+    fn is_par2_volume(stem: &str) -> bool {
+        let Some((_, vol)) = stem.rsplit_once(".vol") else {
+            return false;
+        };
+        let Some((first, count)) = vol.split_once(['+', '-']) else {
+            return false;
+        };
+        let all_digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+        all_digits(first) && all_digits(count)
+    }
+
+    /// Parse a manifest's contents into (listed path, algorithm, lowercase hex)
+    /// entries. Lines or packets that can't be understood are skipped.
+    fn parse(self, contents: &[u8]) -> Vec<(String, HashAlgo, String)> {
+        match self {
+            ManifestFormat::Sums(hint) => parse_sums(&String::from_utf8_lossy(contents), hint),
+            ManifestFormat::Sfv => parse_sfv(&String::from_utf8_lossy(contents)),
+            ManifestFormat::Par2 => parse_par2(contents),
+        }
+    }
+}
+
+fn is_hex(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// The algorithm of a sums-file hash. The file name's algorithm wins when the
+/// length agrees with it; otherwise the length decides.
+fn sums_algo(hex: &str, hint: Option<HashAlgo>) -> Option<HashAlgo> {
+    if let Some(algo) = hint.filter(|algo| algo.hex_len() == hex.len()) {
+        return Some(algo);
+    }
+    match hex.len() {
+        32 => Some(HashAlgo::Md5),
+        40 => Some(HashAlgo::Sha1),
+        64 => Some(HashAlgo::Sha256),
+        128 => Some(HashAlgo::Sha512),
+        _ => None,
+    }
+}
+
+fn parse_sums(text: &str, hint: Option<HashAlgo>) -> Vec<(String, HashAlgo, String)> {
+    let mut entries = Vec::new();
+
+    for line in text.lines() {
+        let line = line.trim_end_matches('\r');
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        // A leading backslash marks a name with escaped backslashes or newlines
+        let (escaped, line) = match line.strip_prefix('\\') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+
+        let parsed = parse_bsd_line(line).or_else(|| {
+            // GNU style: "<hex>  <path>" (text mode) or "<hex> *<path>" (binary mode)
+            let (hex, rest) = line.split_once(' ')?;
+            let path = rest.strip_prefix(' ').or_else(|| rest.strip_prefix('*'))?;
+            Some((sums_algo(hex, hint)?, hex, path))
+        });
+
+        let Some((algo, hex, path)) = parsed else {
+            continue;
+        };
+        if path.is_empty() || !is_hex(hex) || hex.len() != algo.hex_len() {
+            continue;
+        }
+
+        let path = if escaped {
+            path.replace("\\n", "\n").replace("\\\\", "\\")
+        } else {
+            path.to_string()
+        };
+        entries.push((path, algo, hex.to_ascii_lowercase()));
+    }
+
+    entries
+}
+
+/// BSD style, as written by `sha256sum --tag`: "SHA256 (<path>) = <hex>"
+fn parse_bsd_line(line: &str) -> Option<(HashAlgo, &str, &str)> {
+    let (name, rest) = line.split_once(" (")?;
+    let (path, hex) = rest.rsplit_once(") = ")?;
+    let algo = HashAlgo::from_string(name)?;
+    Some((algo, hex, path))
+}
+
+fn parse_sfv(text: &str) -> Vec<(String, HashAlgo, String)> {
+    let mut entries = Vec::new();
+
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') {
+            continue;
+        }
+        // "<path> <crc32>" — the name may itself contain spaces
+        let Some((path, crc)) = line.rsplit_once([' ', '\t']) else {
+            continue;
+        };
+        let path = path.trim_end();
+        if path.is_empty() || crc.len() != 8 || !is_hex(crc) {
+            continue;
+        }
+        entries.push((path.to_string(), HashAlgo::Crc32, crc.to_ascii_lowercase()));
+    }
+
+    entries
+}
+
+const PAR2_MAGIC: &[u8; 8] = b"PAR2\0PKT";
+const PAR2_FILE_DESC: &[u8; 16] = b"PAR 2.0\0FileDesc";
+const PAR2_HEADER_LEN: usize = 64;
+
+/// Read the file description packets of a PAR2 file. Each packet's body is the
+/// file id (16 bytes), the MD5 of the whole file (16), the MD5 of its first
+/// 16 KiB (16), its length (8), then the NUL-padded name.
+fn parse_par2(data: &[u8]) -> Vec<(String, HashAlgo, String)> {
+    let mut entries = Vec::new();
+    let mut offset = 0;
+
+    while offset + PAR2_HEADER_LEN <= data.len() {
+        let header = &data[offset..offset + PAR2_HEADER_LEN];
+        if &header[0..8] != PAR2_MAGIC {
+            break;
+        }
+        let len = u64::from_le_bytes(header[8..16].try_into().unwrap());
+        let len = usize::try_from(len).unwrap_or(usize::MAX);
+        if len < PAR2_HEADER_LEN || len % 4 != 0 || len > data.len() - offset {
+            break;
+        }
+
+        if &header[48..64] == PAR2_FILE_DESC {
+            let body = &data[offset + PAR2_HEADER_LEN..offset + len];
+            if body.len() > 56 {
+                let md5 = hex::encode(&body[16..32]);
+                let name = String::from_utf8_lossy(&body[56..]);
+                let name = name.trim_end_matches('\0');
+                if !name.is_empty() {
+                    entries.push((name.to_string(), HashAlgo::Md5, md5));
+                }
+            }
+        }
+
+        offset += len;
+    }
+
+    entries
+}
+
+/// Resolve a path listed in a manifest against the manifest's directory,
+/// folding away "." and ".." so it can be compared with stored item paths.
+fn resolve_listed_path(manifest_dir: &Path, listed: &str) -> PathBuf {
+    // Manifests written on Windows use backslashes
+    let listed = if !listed.contains('/') && cfg!(not(windows)) {
+        listed.replace('\\', "/")
+    } else {
+        listed.to_string()
+    };
+
+    let mut resolved = PathBuf::new();
+    for component in manifest_dir.join(listed).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            other => resolved.push(other),
+        }
+    }
+    resolved
+}
+
+/// A hash that a manifest expects for a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestEntry {
+    pub manifest_path: String,
+    pub algo: HashAlgo,
+    /// Lowercase hex
+    pub hash: String,
+}
+
+/// The entries of every checksum manifest in a root, by the absolute path of
+/// the file they describe.
+#[derive(Debug, Default)]
+pub struct ManifestIndex {
+    entries: HashMap<String, Vec<ManifestEntry>>,
+}
+
+impl ManifestIndex {
+    /// Find and parse the manifests among the root's live files at this scan.
+    ///
+    /// Covers the whole root even for a scan limited to part of it, since a
+    /// manifest often sits above the files it lists. Manifests that can't be
+    /// read are logged and skipped.
+    pub fn load(conn: &Connection, root_id: i64, scan_id: i64) -> Result<Self, FsPulseError> {
+        let mut stmt = conn.prepare(
+            "SELECT i.item_path, i.item_name
+             FROM item_versions iv
+             JOIN items i ON i.item_id = iv.item_id
+             WHERE iv.root_id = ?
+               AND iv.last_scan_id = ?
+               AND iv.is_deleted = 0
+               AND i.item_type = 0
+               AND (i.file_extension IS NULL
+                    OR i.file_extension IN ('txt', 'md5', 'sha1', 'sha256', 'sha512', 'b3', 'sfv', 'par2'))",
+        )?;
+        let candidates = stmt
+            .query_map(params![root_id, scan_id], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut index = ManifestIndex::default();
+        let mut manifest_count = 0;
+
+        for (path, name) in candidates {
+            let Some(format) = ManifestFormat::from_file_name(&name) else {
+                continue;
+            };
+            match fs::metadata(&path) {
+                Ok(m) if m.len() > MAX_MANIFEST_BYTES => {
+                    warn!("Skipping checksum manifest '{}': larger than {} bytes", path, MAX_MANIFEST_BYTES);
+                    continue;
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    warn!("Skipping checksum manifest '{}': {}", path, e);
+                    continue;
+                }
+            }
+            match fs::read(&path) {
+                Ok(contents) => {
+                    if index.add_manifest(&path, format, &contents) > 0 {
+                        manifest_count += 1;
+                    }
+                }
+                Err(e) => warn!("Skipping checksum manifest '{}': {}", path, e),
+            }
+        }
+
+        info!(
+            "Loaded {} checksum manifest(s) listing {} file(s) for root {}",
+            manifest_count,
+            index.entries.len(),
+            root_id
+        );
+
+        Ok(index)
+    }
+
+    /// Add a manifest's entries, returning how many it contributed
+    fn add_manifest(&mut self, manifest_path: &str, format: ManifestFormat, contents: &[u8]) -> usize {
+        let manifest_dir = Path::new(manifest_path).parent().unwrap_or(Path::new(""));
+        let mut added = 0;
+
+        for (listed, algo, hash) in format.parse(contents) {
+            let file_path = resolve_listed_path(manifest_dir, &listed);
+            let entry = ManifestEntry { manifest_path: manifest_path.to_string(), algo, hash };
+            let file_entries = self
+                .entries
+                .entry(file_path.to_string_lossy().into_owned())
+                .or_default();
+            // PAR2 files may repeat a file's description packet
+            if !file_entries.contains(&entry) {
+                file_entries.push(entry);
+                added += 1;
+            }
+        }
+
+        added
+    }
+
+    /// Manifest entries for the file at `item_path`
+    pub fn entries(&self, item_path: &str) -> &[ManifestEntry] {
+        self.entries.get(item_path).map(Vec::as_slice).unwrap_or(&[])
+    }
+}
+
+/// The outcome of comparing a file's computed hashes with its manifest entries
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestCheck {
+    pub state: ManifestState,
+    pub manifest_path: String,
+    pub algo: HashAlgo,
+    /// The hash the manifest expects, as lowercase hex
+    pub expected: String,
+}
+
+impl ManifestCheck {
+    /// Compare `computed` (algorithm, hex) pairs with the file's manifest
+    /// entries. Any disagreeing entry makes the result a mismatch against that
+    /// manifest; None if no entry could be compared.
+    pub fn evaluate(entries: &[ManifestEntry], computed: &[(HashAlgo, String)]) -> Option<Self> {
+        let mut first_match = None;
+
+        for entry in entries {
+            let Some((_, hash)) = computed.iter().find(|(algo, _)| *algo == entry.algo) else {
+                continue;
+            };
+            let state = if hash.eq_ignore_ascii_case(&entry.hash) {
+                ManifestState::Match
+            } else {
+                ManifestState::Mismatch
+            };
+            let check = ManifestCheck {
+                state,
+                manifest_path: entry.manifest_path.clone(),
+                algo: entry.algo,
+                expected: entry.hash.clone(),
+            };
+            if state == ManifestState::Mismatch {
+                return Some(check);
+            }
+            first_match.get_or_insert(check);
+        }
+
+        first_match
+    }
+}
+
+/// Persist a manifest check on the analyzed `item_versions` row.
+///
+/// Like validation, the result lives on the version and is overwritten each
+/// time the file is hashed. A review is kept while the outcome stays the same.
+/// When no manifest lists the file any more, an earlier result is cleared.
+pub fn persist_manifest(
+    conn: &Connection,
+    scan: &Scan,
+    analysis_item: &AnalysisItem,
+    check: Option<&ManifestCheck>,
+) -> Result<(), FsPulseError> {
+    match check {
+        Some(check) => {
+            let expected = Hash::hex_to_blob(&check.expected);
+            conn.execute(
+                "UPDATE item_versions SET
+                     manifest_reviewed_at = CASE
+                         WHEN manifest_state IS ?1 AND manifest_path IS ?2 AND manifest_hash IS ?3
+                         THEN manifest_reviewed_at ELSE NULL END,
+                     manifest_scan_id = ?4,
+                     manifest_state = ?1,
+                     manifest_path = ?2,
+                     manifest_algo = ?5,
+                     manifest_hash = ?3
+                 WHERE item_id = ?6 AND item_version = ?7",
+                params![
+                    check.state.as_i64(),
+                    check.manifest_path,
+                    expected,
+                    scan.scan_id(),
+                    check.algo.as_i64(),
+                    analysis_item.item_id(),
+                    analysis_item.item_version()
+                ],
+            )?;
+        }
+        None => {
+            conn.execute(
+                "UPDATE item_versions SET
+                     manifest_scan_id = NULL, manifest_state = NULL, manifest_path = NULL,
+                     manifest_algo = NULL, manifest_hash = NULL, manifest_reviewed_at = NULL
+                 WHERE item_id = ? AND item_version = ? AND manifest_scan_id IS NOT NULL",
+                params![analysis_item.item_id(), analysis_item.item_version()],
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EMPTY_MD5: &str = "d41d8cd98f00b204e9800998ecf8427e";
+    const EMPTY_SHA256: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+    #[test]
+    fn test_manifest_format_from_file_name() {
+        assert_eq!(ManifestFormat::from_file_name("SHA256SUMS"), Some(ManifestFormat::Sums(Some(HashAlgo::Sha256))));
+        assert_eq!(ManifestFormat::from_file_name("md5sums.txt"), Some(ManifestFormat::Sums(Some(HashAlgo::Md5))));
+        assert_eq!(ManifestFormat::from_file_name("CHECKSUMS"), Some(ManifestFormat::Sums(None)));
+        assert_eq!(ManifestFormat::from_file_name("album.MD5"), Some(ManifestFormat::Sums(Some(HashAlgo::Md5))));
+        assert_eq!(ManifestFormat::from_file_name("disc1.sfv"), Some(ManifestFormat::Sfv));
+        assert_eq!(ManifestFormat::from_file_name("backup.par2"), Some(ManifestFormat::Par2));
+        assert_eq!(ManifestFormat::from_file_name("backup.vol07+08.par2"), None);
+        assert_eq!(ManifestFormat::from_file_name("notes.txt"), None);
+        assert_eq!(ManifestFormat::from_file_name(".md5"), None);
+    }
+
+    #[test]
+    fn test_parse_sums_gnu_and_bsd() {
+        let text = format!(
+            "# written at ingest\r\n\
+             {EMPTY_SHA256}  a.txt\r\n\
+             {EMPTY_MD5} *dir/b.bin\n\
+             SHA256 (name with (parens).txt) = {EMPTY_SHA256}\n\
+             \\{EMPTY_MD5}  back\\\\slash\n\
+             not a hash line\n\
+             abc  short.txt\n"
+        );
+        let entries = parse_sums(&text, None);
+
+        assert_eq!(
+            entries,
+            vec![
+                ("a.txt".to_string(), HashAlgo::Sha256, EMPTY_SHA256.to_string()),
+                ("dir/b.bin".to_string(), HashAlgo::Md5, EMPTY_MD5.to_string()),
+                ("name with (parens).txt".to_string(), HashAlgo::Sha256, EMPTY_SHA256.to_string()),
+                ("back\\slash".to_string(), HashAlgo::Md5, EMPTY_MD5.to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_sums_name_hint() {
+        // 64 hex digits is SHA-256 unless the file name says BLAKE3
+        let line = format!("{EMPTY_SHA256}  a.txt\n");
+        assert_eq!(parse_sums(&line, Some(HashAlgo::Blake3))[0].1, HashAlgo::Blake3);
+        assert_eq!(parse_sums(&line, Some(HashAlgo::Md5))[0].1, HashAlgo::Sha256);
+    }
+
+    #[test]
+    fn test_parse_sfv() {
+        let text = "; generated by cksfv\r\ntrack 01.flac 0D4A1185\r\nbad.flac 12345\n";
+        assert_eq!(
+            parse_sfv(text),
+            vec![("track 01.flac".to_string(), HashAlgo::Crc32, "0d4a1185".to_string())]
+        );
+    }
+
+    fn par2_packet(packet_type: &[u8; 16], body: &[u8]) -> Vec<u8> {
+        let mut packet = Vec::new();
+        packet.extend_from_slice(PAR2_MAGIC);
+        packet.extend_from_slice(&((PAR2_HEADER_LEN + body.len()) as u64).to_le_bytes());
+        packet.extend_from_slice(&[0u8; 32]); // packet hash and recovery set id
+        packet.extend_from_slice(packet_type);
+        packet.extend_from_slice(body);
+        packet
+    }
+
+    #[test]
+    fn test_parse_par2_file_descriptions() {
+        let mut body = vec![0u8; 16]; // file id
+        body.extend_from_slice(&hex::decode(EMPTY_MD5).unwrap());
+        body.extend_from_slice(&[0u8; 16]); // md5 of first 16k
+        body.extend_from_slice(&0u64.to_le_bytes());
+        body.extend_from_slice(b"sub/empty.dat\0\0\0");
+
+        let mut data = par2_packet(b"PAR 2.0\0Main\0\0\0\0", &[0u8; 12]);
+        data.extend(par2_packet(PAR2_FILE_DESC, &body));
+
+        assert_eq!(
+            parse_par2(&data),
+            vec![("sub/empty.dat".to_string(), HashAlgo::Md5, EMPTY_MD5.to_string())]
+        );
+        // A truncated packet ends the scan without panicking
+        assert!(parse_par2(&data[..data.len() - 4]).len() <= 1);
+    }
+
+    #[test]
+    fn test_resolve_listed_path() {
+        let dir = Path::new("/data/archive");
+        assert_eq!(resolve_listed_path(dir, "./a/b.txt"), PathBuf::from("/data/archive/a/b.txt"));
+        assert_eq!(resolve_listed_path(dir, "../other/c.txt"), PathBuf::from("/data/other/c.txt"));
+        #[cfg(not(windows))]
+        assert_eq!(resolve_listed_path(dir, "win\\d.txt"), PathBuf::from("/data/archive/win/d.txt"));
+    }
+
+    #[test]
+    fn test_index_and_evaluate() {
+        let mut index = ManifestIndex::default();
+        let manifest = format!("{EMPTY_SHA256}  a.txt\n{EMPTY_MD5}  a.txt\n");
+        let format = ManifestFormat::from_file_name("SHA256SUMS").unwrap();
+        assert_eq!(index.add_manifest("/data/SHA256SUMS", format, manifest.as_bytes()), 2);
+        assert!(index.entries("/data/b.txt").is_empty());
+
+        let entries = index.entries("/data/a.txt");
+        let matching = [(HashAlgo::Sha256, EMPTY_SHA256.to_uppercase()), (HashAlgo::Md5, EMPTY_MD5.to_string())];
+        let check = ManifestCheck::evaluate(entries, &matching).unwrap();
+        assert_eq!(check.state, ManifestState::Match);
+        assert_eq!(check.algo, HashAlgo::Sha256);
+
+        let md5_differs = [(HashAlgo::Sha256, EMPTY_SHA256.to_string()), (HashAlgo::Md5, "0".repeat(32))];
+        let check = ManifestCheck::evaluate(entries, &md5_differs).unwrap();
+        assert_eq!(check.state, ManifestState::Mismatch);
+        assert_eq!(check.algo, HashAlgo::Md5);
+        assert_eq!(check.manifest_path, "/data/SHA256SUMS");
+
+        assert!(ManifestCheck::evaluate(entries, &[(HashAlgo::Sha1, "0".repeat(40))]).is_none());
+    }
+
+    #[test]
+    fn test_manifest_state_round_trip() {
+        for state in [ManifestState::Match, ManifestState::Mismatch] {
+            assert_eq!(ManifestState::from_i64(state.as_i64()), state);
+            assert_eq!(ManifestState::from_string(state.short_name()), Some(state));
+            assert_eq!(ManifestState::from_string(state.full_name()), Some(state));
+        }
+    }
+}
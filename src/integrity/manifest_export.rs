@@ -0,0 +1,315 @@
+use std::io::Write;
+use std::path::MAIN_SEPARATOR_STR;
+
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+use crate::error::FsPulseError;
+use crate::hash::{Hash, HashAlgo, HashState};
+use crate::item_identity::ItemType;
+use crate::roots::Root;
+use crate::scan_scope::ScanScope;
+use crate::scans::Scan;
+
+/// The form an exported manifest is written in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// `<hash>  <path>` lines, as written by sha256sum (or by sha512sum, b3sum
+    /// or `xxhsum -H2` for the other algorithms)
+    #[default]
+    #[serde(alias = "sha256sum")]
+    Sums,
+    /// A JSON document that also carries each file's size and modification date
+    Json,
+}
+
+impl ExportFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            ExportFormat::Sums => "text/plain; charset=utf-8",
+            ExportFormat::Json => "application/json",
+        }
+    }
+}
+
+/// What to export: the Baseline hashes of a root, or of one directory in it,
+/// as of a scan.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExportSpec {
+    /// The scan to export as of. None means the root's latest completed scan.
+    #[serde(default)]
+    pub scan_id: Option<i64>,
+    /// Root-relative directory to export instead of the whole root
+    #[serde(default)]
+    pub subpath: Option<String>,
+    #[serde(default)]
+    pub format: ExportFormat,
+    /// Only hashes computed with this algorithm are exported. None means the
+    /// root's current algorithm.
+    #[serde(default)]
+    pub algo: Option<HashAlgo>,
+}
+
+/// Counts reported once a manifest has been written
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExportSummary {
+    /// Files written to the manifest
+    pub exported: u64,
+    /// Files alive at the scan that have no Baseline hash in the export's algorithm
+    pub skipped: u64,
+}
+
+#[derive(Serialize)]
+struct JsonHeader<'a> {
+    root_path: &'a str,
+    base_path: &'a str,
+    scan_id: i64,
+    scan_started_at: i64,
+    algo: HashAlgo,
+    generated_at: i64,
+}
+
+#[derive(Serialize)]
+struct JsonFile<'a> {
+    path: &'a str,
+    hash: &'a str,
+    size: Option<i64>,
+    mod_date: Option<i64>,
+}
+
+/// An export resolved against the database: the root, scan, directory and
+/// algorithm are known to be valid.
+#[derive(Debug, Clone)]
+pub struct ManifestExport {
+    root_id: i64,
+    root_path: String,
+    /// Directory the manifest's paths are relative to: the root or the subpath in it
+    base_path: String,
+    scan_id: i64,
+    scan_started_at: i64,
+    algo: HashAlgo,
+    format: ExportFormat,
+}
+
+impl ManifestExport {
+    /// Check `spec` against root `root_id` and pin down the scan it exports.
+    pub fn resolve(conn: &Connection, root_id: i64, spec: &ExportSpec) -> Result<Self, FsPulseError> {
+        let root = Root::get_by_id(conn, root_id)?
+            .ok_or_else(|| FsPulseError::Error(format!("Root {} not found", root_id)))?;
+
+        let algo = spec.algo.unwrap_or(root.options().hash_algo);
+        if !algo.is_root_algo() {
+            return Err(FsPulseError::Error(format!(
+                "algo must be one of sha256, blake3, xxh3 or sha512 (got {})",
+                algo.short_name()
+            )));
+        }
+
+        let base_path = root.subpath_dir(spec.subpath.as_deref())?;
+
+        let (scan_id, scan_started_at) = Scan::resolve_completed(conn, root_id, spec.scan_id)?;
+
+        Ok(Self {
+            root_id,
+            root_path: root.root_path().to_string(),
+            base_path,
+            scan_id,
+            scan_started_at,
+            algo,
+            format: spec.format,
+        })
+    }
+
+    pub fn scan_id(&self) -> i64 {
+        self.scan_id
+    }
+
+    pub fn base_path(&self) -> &str {
+        &self.base_path
+    }
+
+    /// The conventional file name for the manifest (SHA256SUMS, B3SUMS, ...)
+    pub fn file_name(&self) -> &'static str {
+        match (self.format, self.algo) {
+            (ExportFormat::Json, _) => "manifest.json",
+            (ExportFormat::Sums, HashAlgo::Blake3) => "B3SUMS",
+            (ExportFormat::Sums, HashAlgo::Xxh3) => "XXH128SUMS",
+            (ExportFormat::Sums, HashAlgo::Sha512) => "SHA512SUMS",
+            (ExportFormat::Sums, _) => "SHA256SUMS",
+        }
+    }
+
+    /// Write the manifest to `out`, one file at a time in path order.
+    ///
+    /// Each file alive at the scan is exported with the most recent Baseline
+    /// hash recorded for its version by then, so a file that has since turned
+    /// Suspect is listed with the hash it had when it was last known good.
+    /// `on_file` is called after each file is written and can stop the export
+    /// by returning an error.
+    pub fn write(
+        &self,
+        conn: &Connection,
+        out: &mut impl Write,
+        mut on_file: impl FnMut(u64) -> Result<(), FsPulseError>,
+    ) -> Result<ExportSummary, FsPulseError> {
+        let scope = ScanScope::from_recorded(std::slice::from_ref(&self.base_path));
+        let scope_filter = scope
+            .sql_filter("i.item_path")
+            .map(|filter| format!("AND {}", filter))
+            .unwrap_or_default();
+
+        let sql = format!(
+            "SELECT i.item_path, iv.size, iv.mod_date, hv.file_hash, hv.hash_algo
+             FROM item_versions iv
+             JOIN items i ON i.item_id = iv.item_id
+             LEFT JOIN hash_versions hv ON hv.item_id = iv.item_id
+                 AND hv.item_version = iv.item_version
+                 AND hv.first_scan_id = (
+                     SELECT MAX(first_scan_id) FROM hash_versions
+                     WHERE item_id = iv.item_id AND item_version = iv.item_version
+                       AND first_scan_id <= ?2 AND hash_state = ?3)
+             WHERE iv.root_id = ?1 AND iv.last_scan_id >= ?2 AND iv.first_scan_id <= ?2
+               AND iv.is_deleted = 0 AND i.item_type = ?4 {scope_filter}
+             ORDER BY i.item_path COLLATE natural_path"
+        );
+
+        if self.format == ExportFormat::Json {
+            let header = serde_json::to_string(&JsonHeader {
+                root_path: &self.root_path,
+                base_path: &self.base_path,
+                scan_id: self.scan_id,
+                scan_started_at: self.scan_started_at,
+                algo: self.algo,
+                generated_at: chrono::Utc::now().timestamp(),
+            })
+            .map_err(|e| FsPulseError::Error(format!("Failed to serialize manifest: {}", e)))?;
+            // Reopen the header object to append the files array
+            write!(out, "{},\"files\":[", header.trim_end_matches('}'))?;
+        }
+
+        let mut stmt = conn.prepare(&sql)?;
+        let mut rows = stmt.query(params![
+            self.root_id,
+            self.scan_id,
+            HashState::Baseline.as_i64(),
+            ItemType::File.as_i64(),
+        ])?;
+
+        let mut summary = ExportSummary::default();
+        while let Some(row) = rows.next()? {
+            let item_path: String = row.get(0)?;
+            let hash: Option<Vec<u8>> = row.get(3)?;
+            let algo: Option<i64> = row.get(4)?;
+            let hash = match (hash, algo) {
+                (Some(hash), Some(algo)) if HashAlgo::from_i64(algo) == self.algo => Hash::blob_to_hex(hash),
+                _ => {
+                    summary.skipped += 1;
+                    continue;
+                }
+            };
+            let path = self.relative_path(&item_path);
+
+            match self.format {
+                ExportFormat::Sums => writeln!(out, "{}", sums_line(&hash, &path))?,
+                ExportFormat::Json => {
+                    if summary.exported > 0 {
+                        out.write_all(b",")?;
+                    }
+                    let file = JsonFile {
+                        path: &path,
+                        hash: &hash,
+                        size: row.get(1)?,
+                        mod_date: row.get(2)?,
+                    };
+                    serde_json::to_writer(&mut *out, &file).map_err(|e| {
+                        FsPulseError::Error(format!("Failed to serialize manifest: {}", e))
+                    })?;
+                }
+            }
+
+            summary.exported += 1;
+            on_file(summary.exported)?;
+        }
+
+        if self.format == ExportFormat::Json {
+            out.write_all(b"]}\n")?;
+        }
+        out.flush()?;
+
+        Ok(summary)
+    }
+
+    /// An item's path relative to the export's base directory, with `/` separators
+    fn relative_path(&self, item_path: &str) -> String {
+        let relative = item_path
+            .strip_prefix(&self.base_path)
+            .map(|rest| rest.trim_start_matches(MAIN_SEPARATOR_STR))
+            .unwrap_or(item_path);
+        relative.replace(MAIN_SEPARATOR_STR, "/")
+    }
+}
+
+/// One sha256sum line. As in GNU coreutils, a name containing a backslash or a
+/// line break is escaped and the line is marked with a leading backslash.
+fn sums_line(hash: &str, path: &str) -> String {
+    if path.contains(['\\', '\n', '\r']) {
+        let escaped = path
+            .replace('\\', "\\\\")
+            .replace('\n', "\\n")
+            .replace('\r', "\\r");
+        format!("\\{}  {}", hash, escaped)
+    } else {
+        format!("{}  {}", hash, path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn export(base_path: &str) -> ManifestExport {
+        ManifestExport {
+            root_id: 1,
+            root_path: "/r".to_string(),
+            base_path: base_path.to_string(),
+            scan_id: 1,
+            scan_started_at: 0,
+            algo: HashAlgo::Sha256,
+            format: ExportFormat::Sums,
+        }
+    }
+
+    #[test]
+    fn test_sums_line_plain_and_escaped() {
+        assert_eq!(sums_line("ab12", "dir/a file.txt"), "ab12  dir/a file.txt");
+        assert_eq!(sums_line("ab12", "odd\\name\nx"), "\\ab12  odd\\\\name\\nx");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_relative_path() {
+        assert_eq!(export("/r").relative_path("/r/a/b.txt"), "a/b.txt");
+        assert_eq!(export("/r/a").relative_path("/r/a/b.txt"), "b.txt");
+    }
+
+    #[test]
+    fn test_file_name_follows_algo() {
+        let mut export = export("/r");
+        assert_eq!(export.file_name(), "SHA256SUMS");
+        export.algo = HashAlgo::Blake3;
+        assert_eq!(export.file_name(), "B3SUMS");
+        export.format = ExportFormat::Json;
+        assert_eq!(export.file_name(), "manifest.json");
+    }
+
+    #[test]
+    fn test_export_spec_deserialize() {
+        let spec: ExportSpec =
+            serde_json::from_str(r#"{"scan_id":3,"format":"sha256sum","algo":"blake3"}"#).unwrap();
+        assert_eq!(spec.scan_id, Some(3));
+        assert_eq!(spec.format, ExportFormat::Sums);
+        assert_eq!(spec.algo, Some(HashAlgo::Blake3));
+        assert!(serde_json::from_str::<ExportSpec>(r#"{"format":"csv"}"#).is_err());
+    }
+}
@@ -3,5 +3,9 @@ pub mod file_guard;
 pub mod hash_analysis;
 pub mod hash_version;
 pub mod integrity_api;
+pub mod manifest;
+pub mod manifest_export;
+pub mod reverify;
+pub mod sampled_hash_version;
 pub mod val_analysis;
 pub mod val_version;
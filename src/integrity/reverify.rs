@@ -0,0 +1,260 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use crate::error::FsPulseError;
+use crate::scans::HashMode;
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// A per-scan budget for re-hashing files whose hash was confirmed longest ago.
+///
+/// A scan that only hashes new and changed files never re-reads the rest of
+/// the root. With a budget it also re-hashes the files with the stalest hash
+/// confirmation, up to the limits below, so over a series of scans every file
+/// is re-verified without any single scan reading everything. Unset fields
+/// don't limit the selection.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReverifyBudget {
+    /// Only files whose hash was last confirmed at least this many days ago
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub older_than_days: Option<u32>,
+    /// Re-hash at most this many bytes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_bytes: Option<i64>,
+    /// Re-hash at most this percentage of the bytes of the files in scope
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_percent: Option<f64>,
+}
+
+/// The last file a scan's re-verification budget selected, in the order files
+/// are selected: by the scan that last confirmed their hash, then by item_id.
+/// Every file up to and including it is re-hashed.
+///
+/// Fixed when analysis first starts and kept in the task state, so a resumed
+/// scan re-hashes the same files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReverifyCutoff {
+    pub hash_last_scan_id: i64,
+    pub item_id: i64,
+    pub file_count: u64,
+    pub total_size: u64,
+}
+
+impl ReverifyBudget {
+    /// Build a budget from its stored columns. None when no field is set.
+    pub fn from_columns(
+        older_than_days: Option<u32>,
+        max_bytes: Option<i64>,
+        max_percent: Option<f64>,
+    ) -> Option<Self> {
+        let budget = Self { older_than_days, max_bytes, max_percent };
+        (budget != Self::default()).then_some(budget)
+    }
+
+    /// Check the budget is usable by a scan with the given hash mode
+    pub fn validate(&self, hash_mode: HashMode) -> Result<(), String> {
+        if !matches!(hash_mode, HashMode::New | HashMode::Sampled) {
+            return Err(
+                "re-verification needs a hash mode of New or Sampled (All already re-hashes every file)"
+                    .to_string(),
+            );
+        }
+        if *self == Self::default() {
+            return Err("re-verification needs a minimum age, a byte limit or a percentage".to_string());
+        }
+        if let Some(bytes) = self.max_bytes {
+            if bytes <= 0 {
+                return Err(format!("re-verification max_bytes must be greater than 0, got: {}", bytes));
+            }
+        }
+        if let Some(percent) = self.max_percent {
+            if !(percent > 0.0 && percent <= 100.0) {
+                return Err(format!(
+                    "re-verification max_percent must be greater than 0 and at most 100, got: {}",
+                    percent
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Choose the files this scan re-verifies and return the last of them, or
+    /// None if no file qualifies.
+    ///
+    /// Candidates are the scan's alive files in scope whose hash was last
+    /// confirmed by an earlier scan that started before the age limit. Taken
+    /// stalest first, they're selected until the byte limit (the smaller of
+    /// `max_bytes` and `max_percent` of the files in scope) would be exceeded.
+    /// The first candidate is always selected, so one file larger than the
+    /// whole budget can't hold back every file behind it.
+    pub fn select_cutoff(
+        &self,
+        conn: &Connection,
+        root_id: i64,
+        scan_id: i64,
+        scope_filter: Option<&str>,
+    ) -> Result<Option<ReverifyCutoff>, FsPulseError> {
+        let scope_clause = scope_filter
+            .map(|filter| format!("\n                    AND {}", filter))
+            .unwrap_or_default();
+
+        let max_started_at = match self.older_than_days {
+            Some(days) => chrono::Utc::now().timestamp() - i64::from(days) * SECONDS_PER_DAY,
+            None => i64::MAX,
+        };
+
+        let mut byte_limit = self.max_bytes.unwrap_or(i64::MAX);
+        if let Some(percent) = self.max_percent {
+            let scope_size: i64 = conn.query_row(
+                &format!(
+                    "SELECT COALESCE(SUM(cv.size), 0)
+                    FROM item_versions cv
+                    JOIN items i ON i.item_id = cv.item_id
+                    WHERE
+                        cv.root_id = ?1
+                        AND cv.last_scan_id = ?2
+                        AND i.item_type = 0
+                        AND cv.is_deleted = 0{scope_clause}"
+                ),
+                params![root_id, scan_id],
+                |row| row.get(0),
+            )?;
+            byte_limit = byte_limit.min((scope_size as f64 * percent / 100.0) as i64);
+        }
+
+        let sql = format!(
+            "WITH candidates AS (
+                SELECT
+                    hv.last_scan_id AS hash_last_scan_id,
+                    cv.item_id,
+                    COALESCE(cv.size, 0) AS size
+                FROM item_versions cv
+                JOIN items i
+                    ON i.item_id = cv.item_id
+                JOIN hash_versions hv
+                    ON hv.item_id = cv.item_id
+                    AND hv.item_version = cv.item_version
+                    AND hv.first_scan_id = (
+                        SELECT MAX(first_scan_id) FROM hash_versions
+                        WHERE item_id = cv.item_id AND item_version = cv.item_version
+                    )
+                JOIN scans s
+                    ON s.scan_id = hv.last_scan_id
+                WHERE
+                    cv.root_id = ?1
+                    AND cv.last_scan_id = ?2
+                    AND i.item_type = 0
+                    AND cv.is_deleted = 0
+                    AND cv.access <> 1
+                    AND hv.last_scan_id < ?2
+                    AND s.started_at <= ?3{scope_clause}
+            ),
+            ranked AS (
+                SELECT
+                    hash_last_scan_id,
+                    item_id,
+                    SUM(size) OVER (ORDER BY hash_last_scan_id, item_id) AS running_size,
+                    ROW_NUMBER() OVER (ORDER BY hash_last_scan_id, item_id) AS row_num
+                FROM candidates
+            )
+            SELECT hash_last_scan_id, item_id, row_num, running_size
+            FROM ranked
+            WHERE running_size <= ?4 OR row_num = 1
+            ORDER BY hash_last_scan_id DESC, item_id DESC
+            LIMIT 1"
+        );
+
+        conn.query_row(&sql, params![root_id, scan_id, max_started_at, byte_limit], |row| {
+            Ok(ReverifyCutoff {
+                hash_last_scan_id: row.get(0)?,
+                item_id: row.get(1)?,
+                file_count: row.get::<_, i64>(2)? as u64,
+                total_size: row.get::<_, i64>(3)? as u64,
+            })
+        })
+        .optional()
+        .map_err(FsPulseError::DatabaseError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        let budget = ReverifyBudget { max_percent: Some(5.0), ..Default::default() };
+        assert!(budget.validate(HashMode::New).is_ok());
+        assert!(budget.validate(HashMode::Sampled).is_ok());
+        assert!(budget.validate(HashMode::All).is_err());
+        assert!(budget.validate(HashMode::None).is_err());
+
+        assert!(ReverifyBudget::default().validate(HashMode::New).is_err());
+        let budget = ReverifyBudget { max_percent: Some(150.0), ..Default::default() };
+        assert!(budget.validate(HashMode::New).is_err());
+        let budget = ReverifyBudget { max_bytes: Some(0), ..Default::default() };
+        assert!(budget.validate(HashMode::New).is_err());
+
+        assert_eq!(ReverifyBudget::from_columns(None, None, None), None);
+        assert_eq!(
+            ReverifyBudget::from_columns(Some(30), None, None),
+            Some(ReverifyBudget { older_than_days: Some(30), ..Default::default() })
+        );
+    }
+
+    #[test]
+    fn test_select_cutoff() {
+        let conn = Connection::open_in_memory().unwrap();
+        let now = chrono::Utc::now().timestamp();
+        conn.execute_batch(&format!(
+            "CREATE TABLE scans (scan_id INTEGER, started_at INTEGER);
+             CREATE TABLE items (item_id INTEGER, item_path TEXT, item_type INTEGER);
+             CREATE TABLE item_versions (item_id INTEGER, item_version INTEGER, root_id INTEGER,
+                 last_scan_id INTEGER, is_deleted INTEGER, access INTEGER, size INTEGER);
+             CREATE TABLE hash_versions (item_id INTEGER, item_version INTEGER,
+                 first_scan_id INTEGER, last_scan_id INTEGER);
+             INSERT INTO scans VALUES (1, {old}), (2, {recent}), (3, {now});
+             INSERT INTO items VALUES
+                 (1, '/r/a', 0), (2, '/r/b', 0), (3, '/r/c', 0), (4, '/r/d', 0), (5, '/r/e', 0);
+             INSERT INTO item_versions VALUES
+                 (1, 1, 1, 3, 0, 0, 40), (2, 1, 1, 3, 0, 0, 30), (3, 1, 1, 3, 0, 0, 20),
+                 (4, 1, 1, 3, 0, 0, 10), (5, 1, 1, 3, 0, 0, 100);
+             INSERT INTO hash_versions VALUES
+                 (1, 1, 1, 2), (2, 1, 1, 1), (3, 1, 1, 1), (4, 1, 1, 3);",
+            old = now - 90 * SECONDS_PER_DAY,
+            recent = now - SECONDS_PER_DAY,
+        ))
+        .unwrap();
+
+        let select = |budget: ReverifyBudget, scope: Option<&str>| {
+            budget.select_cutoff(&conn, 1, 3, scope).unwrap()
+        };
+
+        // Stalest first: items 2 and 3 (scan 1), then item 1 (scan 2). Item 4 was
+        // hashed by this scan and item 5 never was.
+        let cutoff = select(ReverifyBudget { max_bytes: Some(60), ..Default::default() }, None).unwrap();
+        assert_eq!((cutoff.hash_last_scan_id, cutoff.item_id), (1, 3));
+        assert_eq!((cutoff.file_count, cutoff.total_size), (2, 50));
+
+        let cutoff = select(ReverifyBudget { max_bytes: Some(1000), ..Default::default() }, None).unwrap();
+        assert_eq!((cutoff.hash_last_scan_id, cutoff.item_id, cutoff.file_count), (2, 1, 3));
+
+        // A file larger than the budget is still selected when it comes first
+        let cutoff = select(ReverifyBudget { max_bytes: Some(1), ..Default::default() }, None).unwrap();
+        assert_eq!((cutoff.item_id, cutoff.file_count), (2, 1));
+
+        // 25% of the 200 bytes in scope, capped further by max_bytes
+        let cutoff = select(ReverifyBudget { max_percent: Some(25.0), ..Default::default() }, None).unwrap();
+        assert_eq!(cutoff.file_count, 2);
+        let budget = ReverifyBudget { max_bytes: Some(40), max_percent: Some(25.0), ..Default::default() };
+        assert_eq!(select(budget, None).unwrap().file_count, 1);
+
+        // Only hashes confirmed at least 30 days ago
+        let cutoff = select(ReverifyBudget { older_than_days: Some(30), ..Default::default() }, None).unwrap();
+        assert_eq!((cutoff.hash_last_scan_id, cutoff.item_id, cutoff.file_count), (1, 3, 2));
+        assert_eq!(select(ReverifyBudget { older_than_days: Some(365), ..Default::default() }, None), None);
+
+        let cutoff = select(ReverifyBudget { max_bytes: Some(1000), ..Default::default() }, Some("i.item_path = '/r/a'")).unwrap();
+        assert_eq!((cutoff.item_id, cutoff.file_count), (1, 1));
+    }
+}
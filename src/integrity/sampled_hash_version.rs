@@ -0,0 +1,50 @@
+use rusqlite::{params, Connection};
+
+use crate::{error::FsPulseError, hash::{Hash, HashAlgo}};
+
+/// A sampled hash observation for a file version. Maps to the
+/// `sampled_hash_versions` table.
+///
+/// Like `HashVersion`, each row covers the scans from `first_scan_id` to
+/// `last_scan_id` over which the same sample was observed. A sampled hash only
+/// covers some of the file's blocks, so it carries no hash state: it is only
+/// compared with an earlier sample taken with the same algorithm and block
+/// count, and a difference sends the file for a full hash.
+pub struct SampledHashVersion;
+
+impl SampledHashVersion {
+    /// Insert a new sampled hash observation.
+    pub fn insert(
+        conn: &Connection,
+        item_id: i64,
+        item_version: i64,
+        scan_id: i64,
+        file_hash: &str,
+        hash_algo: HashAlgo,
+        sample_blocks: usize,
+    ) -> Result<(), FsPulseError> {
+        let hash_blob = Hash::hex_to_blob(file_hash);
+        conn.execute(
+            "INSERT INTO sampled_hash_versions (item_id, item_version, first_scan_id, last_scan_id, file_hash, hash_algo, sample_blocks)
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+            params![item_id, item_version, scan_id, scan_id, hash_blob, hash_algo.as_i64(), sample_blocks as i64],
+        )?;
+        Ok(())
+    }
+
+    /// Extend the last_scan_id on an existing sampled_hash_version (sample re-confirmed unchanged).
+    pub fn extend_last_scan(
+        conn: &Connection,
+        item_id: i64,
+        item_version: i64,
+        first_scan_id: i64,
+        new_last_scan_id: i64,
+    ) -> Result<(), FsPulseError> {
+        conn.execute(
+            "UPDATE sampled_hash_versions SET last_scan_id = ?
+             WHERE item_id = ? AND item_version = ? AND first_scan_id = ?",
+            params![new_last_scan_id, item_id, item_version, first_scan_id],
+        )?;
+        Ok(())
+    }
+}
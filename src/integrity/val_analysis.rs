@@ -6,56 +6,87 @@ use std::sync::Arc;
 use rusqlite::{params, Connection};
 
 use crate::error::FsPulseError;
+use crate::io_throttle::IoThrottle;
 use super::analysis::AnalysisItem;
 use crate::scans::Scan;
-use crate::validate::validator::{self, ValidationState};
+use crate::validate::content::ContentCheck;
+use crate::validate::validator::{ValidationState, Validator, ValidatorInfo};
 
 use super::analysis::ValAnalysisError;
 use super::val_version::ValState;
 
 /// Run validation on a file, returning the result or an error category.
 pub fn run_validation(
+    validator: &dyn Validator,
     path: &Path,
+    throttle: &IoThrottle,
     interrupt_token: &Arc<AtomicBool>,
 ) -> Result<(ValidationState, Option<String>), ValAnalysisError> {
-    let validator = validator::from_path(path);
-    match validator {
-        Some(v) => {
-            match v.validate(path, interrupt_token) {
-                Ok((state, err)) => Ok((state, err)),
-                Err(FsPulseError::IoError(ref io_err))
-                    if io_err.kind() == ErrorKind::PermissionDenied =>
-                {
-                    Err(ValAnalysisError::PermissionDenied)
-                }
-                Err(FsPulseError::IoError(ref io_err))
-                    if io_err.kind() == ErrorKind::NotFound =>
-                {
-                    Err(ValAnalysisError::NotFound)
-                }
-                Err(e) => {
-                    Err(ValAnalysisError::ValidationError(e.to_string()))
-                }
-            }
+    match validator.validate(path, throttle, interrupt_token) {
+        Ok((state, err)) => Ok((state, err)),
+        Err(FsPulseError::IoError(ref io_err))
+            if io_err.kind() == ErrorKind::PermissionDenied =>
+        {
+            Err(ValAnalysisError::PermissionDenied)
         }
-        None => {
-            // Should not reach here — has_validator check in analysis.rs
-            // prevents calling run_validation for files without a validator.
-            log::warn!("run_validation called for file without validator: {:?}", path);
-            Err(ValAnalysisError::NoValidator)
+        Err(FsPulseError::IoError(ref io_err))
+            if io_err.kind() == ErrorKind::NotFound =>
+        {
+            Err(ValAnalysisError::NotFound)
         }
+        Err(e) => {
+            Err(ValAnalysisError::ValidationError(e.to_string()))
+        }
+    }
+}
+
+/// Persist the type detected from a file's contents to `item_versions`.
+///
+/// Like validation, detection is done once per version. `has_validator` on
+/// the item follows the detection, so a file whose contents have a validator
+/// is validated whatever its extension.
+pub fn persist_content(
+    conn: &Connection,
+    scan: &Scan,
+    analysis_item: &AnalysisItem,
+    check: &ContentCheck,
+    has_validator: bool,
+) -> Result<(), FsPulseError> {
+    conn.execute(
+        "UPDATE item_versions
+         SET content_scan_id = ?1, content_type = ?2, content_mismatch = ?3
+         WHERE item_id = ?4 AND item_version = ?5",
+        params![
+            scan.scan_id(),
+            check.content_type,
+            check.mismatch,
+            analysis_item.item_id(),
+            analysis_item.item_version()
+        ],
+    )?;
+
+    if has_validator != analysis_item.has_validator() {
+        conn.execute(
+            "UPDATE items SET has_validator = ? WHERE item_id = ?",
+            params![has_validator, analysis_item.item_id()],
+        )?;
     }
+
+    Ok(())
 }
 
 /// Persist validation results to `item_versions`.
 ///
 /// Validation is tightly coupled to the item_version. The val_scan_id, val_state,
-/// and val_error columns are set on the current version. Validation is a one-time
-/// operation per version — there is no "extend" like hash.
+/// and val_error columns are set on the current version, along with the name and
+/// version of the validator. Validation is a one-time operation per version —
+/// there is no "extend" like hash — unless a newer validator redoes it. A
+/// review only survives revalidation if the state is unchanged.
 pub fn persist_val(
     conn: &Connection,
     scan: &Scan,
     analysis_item: &AnalysisItem,
+    validator: Option<&ValidatorInfo>,
     new_val: ValidationState,
     new_val_error: Option<&str>,
 ) -> Result<(), FsPulseError> {
@@ -66,9 +97,20 @@ pub fn persist_val(
 
     // Write val state directly onto the item_version row
     conn.execute(
-        "UPDATE item_versions SET val_scan_id = ?, val_state = ?, val_error = ?
-         WHERE item_id = ? AND item_version = ?",
-        params![scan.scan_id(), new_val_state.as_i64(), new_val_error, analysis_item.item_id(), analysis_item.item_version()],
+        "UPDATE item_versions
+         SET val_scan_id = ?1, val_state = ?2, val_error = ?3,
+             val_validator = ?4, val_validator_version = ?5,
+             val_reviewed_at = CASE WHEN val_state IS ?2 THEN val_reviewed_at END
+         WHERE item_id = ?6 AND item_version = ?7",
+        params![
+            scan.scan_id(),
+            new_val_state.as_i64(),
+            new_val_error,
+            validator.map(|v| v.name),
+            validator.map(|v| v.version),
+            analysis_item.item_id(),
+            analysis_item.item_version()
+        ],
     )?;
 
     Ok(())
@@ -0,0 +1,340 @@
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{Config, MAX_READ_LIMIT_IOPS, MAX_READ_LIMIT_MB_PER_SEC};
+use crate::error::FsPulseError;
+use crate::task::{TaskIoThroughput, TaskProgress};
+
+const BYTES_PER_MB: f64 = 1_000_000.0;
+
+/// Longest a throttled read sleeps before checking again, so interrupts and
+/// changed limits take effect promptly
+const MAX_WAIT: Duration = Duration::from_millis(100);
+
+/// Buffer size for throttled readers. Decoders tend to issue small reads, and
+/// each one counts against the IOPS limit.
+const READ_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// How often measured throughput is reported
+const REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Caps on how fast analysis reads file contents. An unset limit isn't
+/// capped; a limit of 0 is explicitly unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReadLimits {
+    /// Read at most this many megabytes (10^6 bytes) per second
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mb_per_sec: Option<u32>,
+    /// Issue at most this many reads per second
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iops: Option<u32>,
+}
+
+impl ReadLimits {
+    /// Build limits from their stored columns. None when neither is set.
+    pub fn from_columns(mb_per_sec: Option<u32>, iops: Option<u32>) -> Option<Self> {
+        let limits = Self { mb_per_sec, iops };
+        (limits != Self::default()).then_some(limits)
+    }
+
+    /// Check the limits are within the ranges the global settings allow
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(mb) = self.mb_per_sec {
+            if mb as usize > MAX_READ_LIMIT_MB_PER_SEC {
+                return Err(format!(
+                    "read limit must be between 0 and {} MB/s, got: {}",
+                    MAX_READ_LIMIT_MB_PER_SEC, mb
+                ));
+            }
+        }
+        if let Some(iops) = self.iops {
+            if iops as usize > MAX_READ_LIMIT_IOPS {
+                return Err(format!(
+                    "read limit must be between 0 and {} reads/s, got: {}",
+                    MAX_READ_LIMIT_IOPS, iops
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// The global limits currently in the config
+    fn from_config() -> Self {
+        Self {
+            mb_per_sec: Some(Config::get_analysis_read_limit_mb_per_sec() as u32),
+            iops: Some(Config::get_analysis_read_limit_iops() as u32),
+        }
+    }
+
+    /// Each limit from `self` where set, otherwise from `fallback`
+    fn or(self, fallback: Self) -> Self {
+        Self {
+            mb_per_sec: self.mb_per_sec.or(fallback.mb_per_sec),
+            iops: self.iops.or(fallback.iops),
+        }
+    }
+
+    fn bytes_per_sec(&self) -> Option<f64> {
+        self.mb_per_sec.filter(|&mb| mb > 0).map(|mb| mb as f64 * BYTES_PER_MB)
+    }
+
+    fn reads_per_sec(&self) -> Option<f64> {
+        self.iops.filter(|&iops| iops > 0).map(|iops| iops as f64)
+    }
+}
+
+/// A token bucket holding up to one second of its rate. Reads may overdraw
+/// it, since a read's size isn't known until it completes; the next read then
+/// waits until the balance is repaid.
+#[derive(Default)]
+struct Bucket {
+    tokens: f64,
+}
+
+impl Bucket {
+    fn refill(&mut self, rate: Option<f64>, elapsed: f64) {
+        self.tokens = match rate {
+            Some(rate) => (self.tokens + rate * elapsed).min(rate),
+            // No limit: forget any debt so a new limit starts fresh
+            None => 0.0,
+        };
+    }
+
+    fn delay(&self, rate: Option<f64>) -> Option<Duration> {
+        match rate {
+            Some(rate) if self.tokens < 0.0 => Some(Duration::from_secs_f64(-self.tokens / rate)),
+            _ => None,
+        }
+    }
+}
+
+struct ThrottleState {
+    last_refill: Instant,
+    bytes: Bucket,
+    reads: Bucket,
+    // Throughput measurement
+    window_start: Instant,
+    window_bytes: u64,
+    window_reads: u64,
+}
+
+/// Paces file reads across all of a scan's analysis threads.
+///
+/// Limits set when the throttle is created are fixed for its lifetime. The
+/// rest follow the global config, which is re-read before every read so a
+/// settings change applies to a running scan.
+pub struct IoThrottle {
+    overrides: ReadLimits,
+    follow_config: bool,
+    state: Mutex<ThrottleState>,
+    progress: Option<Arc<TaskProgress>>,
+}
+
+impl IoThrottle {
+    fn new(overrides: ReadLimits, follow_config: bool, progress: Option<Arc<TaskProgress>>) -> Self {
+        let now = Instant::now();
+        Self {
+            overrides,
+            follow_config,
+            state: Mutex::new(ThrottleState {
+                last_refill: now,
+                bytes: Bucket::default(),
+                reads: Bucket::default(),
+                window_start: now,
+                window_bytes: 0,
+                window_reads: 0,
+            }),
+            progress,
+        }
+    }
+
+    /// A throttle that never waits
+    #[allow(dead_code)]
+    pub fn unlimited() -> Self {
+        Self::new(ReadLimits::default(), false, None)
+    }
+
+    /// The throttle for a scan's analysis phase: limits in `overrides` replace
+    /// the global ones. Measured throughput is reported to `progress`.
+    pub fn for_analysis(overrides: ReadLimits, progress: Arc<TaskProgress>) -> Self {
+        Self::new(overrides, true, Some(progress))
+    }
+
+    fn limits(&self) -> ReadLimits {
+        if self.follow_config {
+            self.overrides.or(ReadLimits::from_config())
+        } else {
+            self.overrides
+        }
+    }
+
+    /// Wait until the limits allow another read
+    pub fn wait(&self, interrupt_token: &Arc<AtomicBool>) -> Result<(), FsPulseError> {
+        loop {
+            if interrupt_token.load(Ordering::Acquire) {
+                return Err(FsPulseError::TaskInterrupted);
+            }
+
+            let limits = self.limits();
+            let (bytes_per_sec, reads_per_sec) = (limits.bytes_per_sec(), limits.reads_per_sec());
+
+            let delay = {
+                let mut state = self.state.lock().unwrap();
+                let now = Instant::now();
+                let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+                state.last_refill = now;
+                state.bytes.refill(bytes_per_sec, elapsed);
+                state.reads.refill(reads_per_sec, elapsed);
+                state.bytes.delay(bytes_per_sec).max(state.reads.delay(reads_per_sec))
+            };
+
+            match delay {
+                Some(delay) => std::thread::sleep(delay.min(MAX_WAIT)),
+                None => return Ok(()),
+            }
+        }
+    }
+
+    /// Account for a completed read of `bytes` bytes
+    pub fn charge(&self, bytes: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.bytes.tokens -= bytes as f64;
+        state.reads.tokens -= 1.0;
+        state.window_bytes += bytes as u64;
+        state.window_reads += 1;
+
+        let elapsed = state.window_start.elapsed();
+        if elapsed >= REPORT_INTERVAL {
+            let secs = elapsed.as_secs_f64();
+            let throughput = TaskIoThroughput {
+                bytes_per_sec: (state.window_bytes as f64 / secs) as u64,
+                reads_per_sec: (state.window_reads as f64 / secs) as u64,
+            };
+            state.window_start = Instant::now();
+            state.window_bytes = 0;
+            state.window_reads = 0;
+            if let Some(progress) = &self.progress {
+                progress.set_io_throughput(Some(throughput));
+            }
+        }
+    }
+}
+
+/// A file whose reads are paced by an `IoThrottle`, for readers (such as the
+/// validators' decoders) that drive their own reads.
+///
+/// If the task is interrupted while a read waits, the read fails. The caller
+/// sees an ordinary I/O error, but analysis discards results of interrupted
+/// work.
+pub struct ThrottledFile<'a> {
+    file: File,
+    throttle: &'a IoThrottle,
+    interrupt_token: &'a Arc<AtomicBool>,
+}
+
+impl<'a> ThrottledFile<'a> {
+    pub fn open(
+        path: &Path,
+        throttle: &'a IoThrottle,
+        interrupt_token: &'a Arc<AtomicBool>,
+    ) -> io::Result<Self> {
+        Ok(Self {
+            file: File::open(path)?,
+            throttle,
+            interrupt_token,
+        })
+    }
+
+    /// Open the file behind a large read buffer
+    pub fn open_buffered(
+        path: &Path,
+        throttle: &'a IoThrottle,
+        interrupt_token: &'a Arc<AtomicBool>,
+    ) -> io::Result<BufReader<Self>> {
+        Ok(BufReader::with_capacity(
+            READ_BUFFER_SIZE,
+            Self::open(path, throttle, interrupt_token)?,
+        ))
+    }
+}
+
+impl Read for ThrottledFile<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.throttle
+            .wait(self.interrupt_token)
+            .map_err(|e| io::Error::other(e.to_string()))?;
+        let bytes_read = self.file.read(buf)?;
+        self.throttle.charge(bytes_read);
+        Ok(bytes_read)
+    }
+}
+
+impl Seek for ThrottledFile<'_> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.file.seek(pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_limits_or() {
+        let overrides = ReadLimits { mb_per_sec: Some(0), iops: None };
+        let global = ReadLimits { mb_per_sec: Some(50), iops: Some(200) };
+        let limits = overrides.or(global);
+        assert_eq!(limits, ReadLimits { mb_per_sec: Some(0), iops: Some(200) });
+        // 0 is explicitly unlimited
+        assert_eq!(limits.bytes_per_sec(), None);
+        assert_eq!(limits.reads_per_sec(), Some(200.0));
+
+        assert_eq!(ReadLimits::from_columns(None, None), None);
+        assert_eq!(ReadLimits::from_columns(None, Some(10)), Some(ReadLimits { mb_per_sec: None, iops: Some(10) }));
+
+        assert!(ReadLimits { mb_per_sec: Some(0), iops: Some(500) }.validate().is_ok());
+        assert!(ReadLimits { mb_per_sec: Some(u32::MAX), iops: None }.validate().is_err());
+    }
+
+    #[test]
+    fn test_bucket_overdraw() {
+        let mut bucket = Bucket::default();
+        bucket.refill(Some(100.0), 10.0);
+        // Holds at most one second of its rate
+        assert_eq!(bucket.tokens, 100.0);
+        assert_eq!(bucket.delay(Some(100.0)), None);
+
+        bucket.tokens -= 150.0;
+        assert_eq!(bucket.delay(Some(100.0)), Some(Duration::from_millis(500)));
+        bucket.refill(Some(100.0), 0.5);
+        assert_eq!(bucket.delay(Some(100.0)), None);
+
+        // Removing the limit clears the debt
+        bucket.tokens = -1000.0;
+        bucket.refill(None, 0.0);
+        assert_eq!(bucket.delay(Some(100.0)), None);
+    }
+
+    #[test]
+    fn test_throttle_paces_reads() {
+        let throttle = IoThrottle::new(ReadLimits { mb_per_sec: None, iops: Some(20) }, false, None);
+        let interrupt_token = Arc::new(AtomicBool::new(false));
+
+        let start = Instant::now();
+        for _ in 0..5 {
+            throttle.wait(&interrupt_token).unwrap();
+            throttle.charge(0);
+        }
+        // The first read is free, the next four wait 50ms each
+        assert!(start.elapsed() >= Duration::from_millis(190));
+
+        interrupt_token.store(true, Ordering::Release);
+        assert!(matches!(throttle.wait(&interrupt_token), Err(FsPulseError::TaskInterrupted)));
+    }
+}
@@ -81,6 +81,7 @@ pub enum Access {
     Ok = 0,        // No known access issues (default)
     MetaError = 1, // Can't stat (found during scan phase)
     ReadError = 2, // Can stat, can't read (found during analysis phase)
+    MountPoint = 3, // Directory on another filesystem, not traversed (one-file-system roots)
 }
 
 impl Access {
@@ -93,6 +94,7 @@ impl Access {
             0 => Access::Ok,
             1 => Access::MetaError,
             2 => Access::ReadError,
+            3 => Access::MountPoint,
             _ => {
                 warn!(
                     "Invalid Access value in database: {}, defaulting to Ok",
@@ -108,6 +110,7 @@ impl Access {
             Access::Ok => "N",
             Access::MetaError => "M",
             Access::ReadError => "R",
+            Access::MountPoint => "P",
         }
     }
 
@@ -116,6 +119,7 @@ impl Access {
             Access::Ok => "No Error",
             Access::MetaError => "Meta Error",
             Access::ReadError => "Read Error",
+            Access::MountPoint => "Mount Point",
         }
     }
 
@@ -125,10 +129,12 @@ impl Access {
             "NO ERROR" | "NOERROR" => Some(Access::Ok),
             "META ERROR" | "METAERROR" => Some(Access::MetaError),
             "READ ERROR" | "READERROR" => Some(Access::ReadError),
+            "MOUNT POINT" | "MOUNTPOINT" => Some(Access::MountPoint),
             // Short names
             "N" => Some(Access::Ok),
             "M" => Some(Access::MetaError),
             "R" => Some(Access::ReadError),
+            "P" => Some(Access::MountPoint),
             _ => None,
         }
     }
@@ -168,6 +174,40 @@ impl ItemIdentity {
         let item_id: i64 = conn.query_row("SELECT last_insert_rowid()", [], |row| row.get(0))?;
         Ok(item_id)
     }
+
+    /// Recompute `has_validator` for a root's files from the extensions and
+    /// detected content types that now have validators, so files recorded
+    /// before a validator was added (or after one was removed) are brought in
+    /// line. Returns the number of items changed.
+    pub fn sync_has_validator(
+        conn: &Connection,
+        root_id: i64,
+        validator_extensions: &[&str],
+        validator_names: &[&str],
+    ) -> Result<usize, FsPulseError> {
+        let quote = |values: &[&str]| -> String {
+            values.iter().map(|v| format!("'{}'", v)).collect::<Vec<_>>().join(", ")
+        };
+        let has_validator = format!(
+            "(COALESCE(file_extension IN ({}), 0) OR EXISTS (
+                 SELECT 1 FROM item_versions cv
+                 WHERE cv.item_id = items.item_id
+                   AND cv.item_version = (SELECT MAX(item_version) FROM item_versions WHERE item_id = items.item_id)
+                   AND cv.content_type IN ({})
+             ))",
+            quote(validator_extensions),
+            quote(validator_names)
+        );
+
+        let changed = conn.execute(
+            &format!(
+                "UPDATE items SET has_validator = {has_validator}
+                 WHERE root_id = ? AND item_type = 0 AND has_validator <> {has_validator}"
+            ),
+            params![root_id],
+        )?;
+        Ok(changed)
+    }
 }
 
 /// An existing item's identity combined with its current version.
@@ -194,6 +234,8 @@ impl ExistingItem {
                     iv.is_added, iv.is_deleted, iv.access,
                     iv.mod_date, iv.size,
                     iv.add_count, iv.modify_count, iv.delete_count, iv.unchanged_count,
+                    iv.symlink_target,
+                    iv.mode, iv.uid, iv.gid, iv.inode, iv.nlink, iv.ctime, iv.dev, iv.change_reason,
                     i.item_id
              FROM items i
              JOIN item_versions iv ON iv.item_id = i.item_id
@@ -204,7 +246,7 @@ impl ExistingItem {
             params![root_id, path, item_type.as_i64()],
             |row| {
                 let version = ItemVersion::from_row(row)?;
-                let item_id: i64 = row.get(13)?;
+                let item_id: i64 = row.get(22)?;
                 Ok(ExistingItem { item_id, version })
             },
         )
@@ -1,7 +1,185 @@
+use std::fs::Metadata;
+use std::ops::{BitOr, BitOrAssign};
+
 use rusqlite::{params, Connection, OptionalExtension};
 
 use crate::{error::FsPulseError, item_identity::Access};
 
+/// Unix metadata recorded on each version alongside mod_date and size.
+///
+/// None on non-Unix platforms, and for versions written before it was tracked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnixMetadata {
+    /// Permission bits, including setuid/setgid/sticky (st_mode & 0o7777)
+    pub mode: i64,
+    pub uid: i64,
+    pub gid: i64,
+    pub inode: i64,
+    pub nlink: i64,
+    /// Inode change time, in seconds
+    pub ctime: i64,
+    /// Device the inode lives on; (dev, inode) identifies the file
+    pub dev: i64,
+}
+
+impl UnixMetadata {
+    #[cfg(unix)]
+    pub fn from_metadata(metadata: &Metadata) -> Option<Self> {
+        use std::os::unix::fs::MetadataExt;
+        Some(UnixMetadata {
+            mode: (metadata.mode() & 0o7777) as i64,
+            uid: metadata.uid() as i64,
+            gid: metadata.gid() as i64,
+            inode: metadata.ino() as i64,
+            nlink: metadata.nlink() as i64,
+            ctime: metadata.ctime(),
+            dev: metadata.dev() as i64,
+        })
+    }
+
+    #[cfg(not(unix))]
+    pub fn from_metadata(_metadata: &Metadata) -> Option<Self> {
+        None
+    }
+
+    /// Which of the fields differ between two observations
+    pub fn changes(&self, other: &UnixMetadata) -> ChangeReason {
+        let mut reason = ChangeReason::NONE;
+        if self.mode != other.mode {
+            reason |= ChangeReason::MODE;
+        }
+        if self.uid != other.uid || self.gid != other.gid {
+            reason |= ChangeReason::OWNER;
+        }
+        if self.inode != other.inode || self.dev != other.dev {
+            reason |= ChangeReason::INODE;
+        }
+        if self.nlink != other.nlink {
+            reason |= ChangeReason::NLINK;
+        }
+        if self.ctime != other.ctime {
+            reason |= ChangeReason::CTIME;
+        }
+        reason
+    }
+
+    /// Column values in `mode, uid, gid, inode, nlink, ctime, dev` order
+    fn columns(unix: Option<&UnixMetadata>) -> [Option<i64>; 7] {
+        match unix {
+            Some(u) => [
+                Some(u.mode), Some(u.uid), Some(u.gid), Some(u.inode), Some(u.nlink), Some(u.ctime),
+                Some(u.dev),
+            ],
+            None => [None; 7],
+        }
+    }
+
+    /// Read from `mode, uid, gid, inode, nlink, ctime, dev` starting at column `start`.
+    /// Versions recorded before `dev` was tracked read as None, so they are
+    /// backfilled rather than compared.
+    fn from_columns(row: &rusqlite::Row, start: usize) -> rusqlite::Result<Option<Self>> {
+        let mode: Option<i64> = row.get(start)?;
+        let dev: Option<i64> = row.get(start + 6)?;
+        let (Some(mode), Some(dev)) = (mode, dev) else {
+            return Ok(None);
+        };
+        Ok(Some(UnixMetadata {
+            mode,
+            uid: row.get(start + 1)?,
+            gid: row.get(start + 2)?,
+            inode: row.get(start + 3)?,
+            nlink: row.get(start + 4)?,
+            ctime: row.get(start + 5)?,
+            dev,
+        }))
+    }
+}
+
+/// Bitmask of what changed between an item version and the one before it.
+/// Stored in `item_versions.change_reason`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ChangeReason(i64);
+
+impl ChangeReason {
+    pub const NONE: ChangeReason = ChangeReason(0);
+    pub const SIZE: ChangeReason = ChangeReason(1);
+    pub const MOD_DATE: ChangeReason = ChangeReason(1 << 1);
+    pub const ACCESS: ChangeReason = ChangeReason(1 << 2);
+    pub const SYMLINK_TARGET: ChangeReason = ChangeReason(1 << 3);
+    pub const MODE: ChangeReason = ChangeReason(1 << 4);
+    /// uid and/or gid
+    pub const OWNER: ChangeReason = ChangeReason(1 << 5);
+    pub const INODE: ChangeReason = ChangeReason(1 << 6);
+    pub const NLINK: ChangeReason = ChangeReason(1 << 7);
+    pub const CTIME: ChangeReason = ChangeReason(1 << 8);
+    /// The item came back after a deletion (or exclusion)
+    pub const RESTORED: ChangeReason = ChangeReason(1 << 9);
+    /// Only the folder's descendant counts changed
+    pub const DESCENDANTS: ChangeReason = ChangeReason(1 << 10);
+
+    /// Query tokens, in bit order
+    const NAMES: [(ChangeReason, &'static str); 11] = [
+        (ChangeReason::SIZE, "size"),
+        (ChangeReason::MOD_DATE, "mod_date"),
+        (ChangeReason::ACCESS, "access"),
+        (ChangeReason::SYMLINK_TARGET, "symlink_target"),
+        (ChangeReason::MODE, "mode"),
+        (ChangeReason::OWNER, "owner"),
+        (ChangeReason::INODE, "inode"),
+        (ChangeReason::NLINK, "nlink"),
+        (ChangeReason::CTIME, "ctime"),
+        (ChangeReason::RESTORED, "restored"),
+        (ChangeReason::DESCENDANTS, "descendants"),
+    ];
+
+    pub fn as_i64(self) -> i64 {
+        self.0
+    }
+
+    pub fn from_i64(bits: i64) -> Self {
+        ChangeReason(bits)
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn contains(self, other: ChangeReason) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Names of the reasons set, in bit order
+    pub fn names(self) -> Vec<&'static str> {
+        Self::NAMES
+            .iter()
+            .filter(|(reason, _)| self.contains(*reason))
+            .map(|(_, name)| *name)
+            .collect()
+    }
+
+    /// Parse a single reason name (case-insensitive) and return its bit
+    pub fn from_token(s: &str) -> Option<i64> {
+        Self::NAMES
+            .iter()
+            .find(|(_, name)| name.eq_ignore_ascii_case(s))
+            .map(|(reason, _)| reason.0)
+    }
+}
+
+impl BitOr for ChangeReason {
+    type Output = ChangeReason;
+
+    fn bitor(self, rhs: ChangeReason) -> ChangeReason {
+        ChangeReason(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for ChangeReason {
+    fn bitor_assign(&mut self, rhs: ChangeReason) {
+        self.0 |= rhs.0;
+    }
+}
+
 /// A single temporal version of an item.
 ///
 /// Maps to the `item_versions` table. Each row represents one distinct state of an item.
@@ -24,6 +202,9 @@ pub struct ItemVersion {
     access: Access,
     mod_date: Option<i64>,
     size: Option<i64>,
+    symlink_target: Option<String>,
+    unix: Option<UnixMetadata>,
+    change_reason: Option<ChangeReason>,
     add_count: Option<i64>,
     modify_count: Option<i64>,
     delete_count: Option<i64>,
@@ -68,6 +249,18 @@ impl ItemVersion {
         self.size
     }
 
+    pub fn symlink_target(&self) -> Option<&str> {
+        self.symlink_target.as_deref()
+    }
+
+    pub fn unix(&self) -> Option<&UnixMetadata> {
+        self.unix.as_ref()
+    }
+
+    pub fn change_reason(&self) -> Option<ChangeReason> {
+        self.change_reason
+    }
+
     pub fn add_count(&self) -> Option<i64> {
         self.add_count
     }
@@ -92,7 +285,8 @@ impl ItemVersion {
         conn.query_row(
             "SELECT item_id, item_version, first_scan_id, last_scan_id, is_added, is_deleted, access,
                     mod_date, size,
-                    add_count, modify_count, delete_count, unchanged_count
+                    add_count, modify_count, delete_count, unchanged_count, symlink_target,
+                    mode, uid, gid, inode, nlink, ctime, dev, change_reason
              FROM item_versions
              WHERE item_id = ?
              ORDER BY item_version DESC
@@ -107,7 +301,7 @@ impl ItemVersion {
     /// Insert the first version for a newly discovered item.
     ///
     /// `counts` should be `Some((0, 0, 0, 0))` for folders (add, modify, delete, unchanged),
-    /// `None` for files.
+    /// `None` for files. `symlink_target` is `Some` for symlinks only.
     #[allow(clippy::too_many_arguments)]
     pub fn insert_initial(
         conn: &Connection,
@@ -117,20 +311,26 @@ impl ItemVersion {
         access: Access,
         mod_date: Option<i64>,
         size: Option<i64>,
+        symlink_target: Option<&str>,
+        unix: Option<&UnixMetadata>,
+        change_reason: Option<ChangeReason>,
         counts: Option<(i64, i64, i64, i64)>,
     ) -> Result<(), FsPulseError> {
         let (add_count, modify_count, delete_count, unchanged_count) = match counts {
             Some((a, m, d, u)) => (Some(a), Some(m), Some(d), Some(u)),
             None => (None, None, None, None),
         };
+        let [mode, uid, gid, inode, nlink, ctime, dev] = UnixMetadata::columns(unix);
         conn.execute(
             "INSERT INTO item_versions (
                 item_id, item_version, root_id, first_scan_id, last_scan_id,
-                is_added, is_deleted, access, mod_date, size,
+                is_added, is_deleted, access, mod_date, size, symlink_target,
+                mode, uid, gid, inode, nlink, ctime, dev, change_reason,
                 add_count, modify_count, delete_count, unchanged_count
              ) VALUES (?1, COALESCE((SELECT MAX(item_version) FROM item_versions WHERE item_id = ?1), 0) + 1,
-                        ?2, ?3, ?3, 1, 0, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
-            params![item_id, root_id, scan_id, access.as_i64(), mod_date, size,
+                        ?2, ?3, ?3, 1, 0, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19)",
+            params![item_id, root_id, scan_id, access.as_i64(), mod_date, size, symlink_target,
+                    mode, uid, gid, inode, nlink, ctime, dev, change_reason.map(ChangeReason::as_i64),
                     add_count, modify_count, delete_count, unchanged_count],
         )?;
         Ok(())
@@ -139,6 +339,7 @@ impl ItemVersion {
     /// Insert a new version with all fields specified explicitly.
     ///
     /// `counts` should be `Some((a, m, d, u))` for folders, `None` for files.
+    /// `change_reason` is None when the reason isn't known.
     #[allow(clippy::too_many_arguments)]
     pub fn insert_full(
         conn: &Connection,
@@ -150,22 +351,28 @@ impl ItemVersion {
         access: Access,
         mod_date: Option<i64>,
         size: Option<i64>,
+        symlink_target: Option<&str>,
+        unix: Option<&UnixMetadata>,
+        change_reason: Option<ChangeReason>,
         counts: Option<(i64, i64, i64, i64)>,
     ) -> Result<(), FsPulseError> {
         let (add_count, modify_count, delete_count, unchanged_count) = match counts {
             Some((a, m, d, u)) => (Some(a), Some(m), Some(d), Some(u)),
             None => (None, None, None, None),
         };
+        let [mode, uid, gid, inode, nlink, ctime, dev] = UnixMetadata::columns(unix);
         conn.execute(
             "INSERT INTO item_versions (
                 item_id, item_version, root_id, first_scan_id, last_scan_id,
-                is_added, is_deleted, access, mod_date, size,
+                is_added, is_deleted, access, mod_date, size, symlink_target,
+                mode, uid, gid, inode, nlink, ctime, dev, change_reason,
                 add_count, modify_count, delete_count, unchanged_count
              ) VALUES (?1, COALESCE((SELECT MAX(item_version) FROM item_versions WHERE item_id = ?1), 0) + 1,
-                        ?2, ?3, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                        ?2, ?3, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21)",
             params![
                 item_id, root_id, scan_id, is_added, is_deleted, access.as_i64(),
-                mod_date, size,
+                mod_date, size, symlink_target,
+                mode, uid, gid, inode, nlink, ctime, dev, change_reason.map(ChangeReason::as_i64),
                 add_count, modify_count, delete_count, unchanged_count,
             ],
         )?;
@@ -189,6 +396,9 @@ impl ItemVersion {
         access: Access,
         mod_date: Option<i64>,
         size: Option<i64>,
+        symlink_target: Option<&str>,
+        unix: Option<&UnixMetadata>,
+        change_reason: ChangeReason,
         prev: &ItemVersion,
         is_folder: bool,
     ) -> Result<(), FsPulseError> {
@@ -202,7 +412,7 @@ impl ItemVersion {
         };
         Self::insert_full(
             conn, item_id, root_id, scan_id, false, is_deleted, access, mod_date, size,
-            counts,
+            symlink_target, unix, Some(change_reason), counts,
         )
     }
 
@@ -220,6 +430,41 @@ impl ItemVersion {
         Ok(())
     }
 
+    /// Fill in the target of a symlink version recorded before targets were tracked.
+    /// Not undone on rollback: the target was already true of this version.
+    pub fn backfill_symlink_target(
+        conn: &Connection,
+        item_id: i64,
+        item_version: i64,
+        symlink_target: &str,
+    ) -> Result<(), FsPulseError> {
+        conn.execute(
+            "UPDATE item_versions SET symlink_target = ?
+             WHERE item_id = ? AND item_version = ? AND symlink_target IS NULL",
+            params![symlink_target, item_id, item_version],
+        )?;
+        Ok(())
+    }
+
+    /// Fill in the Unix metadata of a version recorded before it was tracked.
+    /// Not undone on rollback, for the same reason as `backfill_symlink_target`.
+    pub fn backfill_unix(
+        conn: &Connection,
+        item_id: i64,
+        item_version: i64,
+        unix: &UnixMetadata,
+    ) -> Result<(), FsPulseError> {
+        conn.execute(
+            "UPDATE item_versions SET mode = ?, uid = ?, gid = ?, inode = ?, nlink = ?, ctime = ?, dev = ?
+             WHERE item_id = ? AND item_version = ? AND dev IS NULL",
+            params![
+                unix.mode, unix.uid, unix.gid, unix.inode, unix.nlink, unix.ctime, unix.dev,
+                item_id, item_version
+            ],
+        )?;
+        Ok(())
+    }
+
     pub(crate) fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
         Ok(ItemVersion {
             item_id: row.get(0)?,
@@ -235,6 +480,90 @@ impl ItemVersion {
             modify_count: row.get(10)?,
             delete_count: row.get(11)?,
             unchanged_count: row.get(12)?,
+            symlink_target: row.get(13)?,
+            unix: UnixMetadata::from_columns(row, 14)?,
+            change_reason: row.get::<_, Option<i64>>(21)?.map(ChangeReason::from_i64),
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unix() -> UnixMetadata {
+        UnixMetadata {
+            mode: 0o644,
+            uid: 1000,
+            gid: 1000,
+            inode: 42,
+            nlink: 1,
+            ctime: 1_700_000_000,
+            dev: 2049,
+        }
+    }
+
+    #[test]
+    fn test_unix_metadata_no_changes() {
+        assert!(unix().changes(&unix()).is_empty());
+    }
+
+    #[test]
+    fn test_unix_metadata_changes() {
+        let old = unix();
+        let new = UnixMetadata {
+            mode: 0o600,
+            gid: 0,
+            ctime: old.ctime + 1,
+            ..old
+        };
+
+        let reason = old.changes(&new);
+        assert_eq!(reason, ChangeReason::MODE | ChangeReason::OWNER | ChangeReason::CTIME);
+        assert!(!reason.contains(ChangeReason::INODE));
+        assert!(!reason.contains(ChangeReason::NLINK));
+    }
+
+    #[test]
+    fn test_change_reason_bits() {
+        let mut reason = ChangeReason::NONE;
+        assert!(reason.is_empty());
+
+        reason |= ChangeReason::SIZE;
+        reason |= ChangeReason::MOD_DATE;
+        assert_eq!(reason.as_i64(), 3);
+        assert!(reason.contains(ChangeReason::SIZE));
+        assert!(!reason.contains(ChangeReason::SIZE | ChangeReason::ACCESS));
+        assert_eq!(ChangeReason::from_i64(reason.as_i64()), reason);
+    }
+
+    #[test]
+    fn test_change_reason_names() {
+        let reason = ChangeReason::MOD_DATE | ChangeReason::SIZE | ChangeReason::RESTORED;
+        assert_eq!(reason.names(), vec!["size", "mod_date", "restored"]);
+        assert!(ChangeReason::NONE.names().is_empty());
+    }
+
+    #[test]
+    fn test_change_reason_from_token() {
+        assert_eq!(ChangeReason::from_token("size"), Some(1));
+        assert_eq!(ChangeReason::from_token("Mod_Date"), Some(2));
+        assert_eq!(ChangeReason::from_token("descendants"), Some(1 << 10));
+        assert_eq!(ChangeReason::from_token("type"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unix_metadata_from_metadata() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("f");
+        std::fs::write(&path, "data").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o640)).unwrap();
+
+        let unix = UnixMetadata::from_metadata(&std::fs::symlink_metadata(&path).unwrap()).unwrap();
+        assert_eq!(unix.mode, 0o640);
+        assert_eq!(unix.nlink, 1);
+    }
+}
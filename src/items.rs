@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize, Serializer};
 use std::path::MAIN_SEPARATOR_STR;
 
 use crate::{
-    db::Database, error::FsPulseError, utils::Utils,
+    db::Database, error::FsPulseError, hash::HashAlgo, item_version::ChangeReason, utils::Utils,
 };
 
 // Re-export types that were moved to item_identity.rs.
@@ -101,6 +101,7 @@ pub struct TemporalTreeItem {
     pub first_scan_id: i64,
     pub is_added: bool,
     pub is_deleted: bool,
+    pub is_excluded: bool,
     pub mod_date: Option<i64>,
     pub size: Option<i64>,
     pub add_count: Option<i64>,
@@ -206,7 +207,7 @@ pub fn get_temporal_immediate_children(
         "SELECT i.item_id, i.item_path, i.item_name, i.item_type, i.has_validator,
                 iv.first_scan_id, iv.is_added, iv.is_deleted, iv.mod_date, iv.size,
                 iv.add_count, iv.modify_count, iv.delete_count, iv.unchanged_count,
-                iv.val_state, hv.hash_state
+                iv.val_state, hv.hash_state, iv.is_excluded
          FROM items i
          JOIN item_versions iv ON iv.item_id = i.item_id
          LEFT JOIN hash_versions hv ON hv.item_id = i.item_id
@@ -242,6 +243,7 @@ pub fn get_temporal_immediate_children(
                 unchanged_count: row.get(13)?,
                 val_state: row.get(14)?,
                 hash_state: row.get(15)?,
+                is_excluded: row.get(16)?,
             })
         },
     )?;
@@ -318,7 +320,7 @@ pub fn get_temporal_search_items(
         "SELECT i.item_id, i.item_path, i.item_name, i.item_type, i.has_validator,
                 iv.first_scan_id, iv.is_added, iv.is_deleted, iv.mod_date, iv.size,
                 iv.add_count, iv.modify_count, iv.delete_count, iv.unchanged_count,
-                iv.val_state, hv.hash_state
+                iv.val_state, hv.hash_state, iv.is_excluded
          {} {} ORDER BY i.item_path COLLATE natural_path ASC{}{}",
         TEMPORAL_SEARCH_FROM, TEMPORAL_SEARCH_WHERE, limit_clause, offset_clause
     );
@@ -345,6 +347,7 @@ pub fn get_temporal_search_items(
                 unchanged_count: row.get(13)?,
                 val_state: row.get(14)?,
                 hash_state: row.get(15)?,
+                is_excluded: row.get(16)?,
             })
         },
     )?;
@@ -383,9 +386,25 @@ pub struct VersionHistoryEntry {
     pub last_scan_date: i64,
     pub is_added: bool,
     pub is_deleted: bool,
+    pub is_excluded: bool,
     pub access: i64,
     pub mod_date: Option<i64>,
     pub size: Option<i64>,
+    pub symlink_target: Option<String>,
+    /// Names of what changed from the previous version (NULL for adds, deletes
+    /// and versions recorded before reasons were tracked)
+    pub change_reason: Option<Vec<&'static str>>,
+    // Move links: the item an add was moved from, or the item a delete was moved to
+    pub moved_from_item_id: Option<i64>,
+    pub moved_from_path: Option<String>,
+    pub moved_to_path: Option<String>,
+    // Unix metadata (NULL when not recorded)
+    pub mode: Option<i64>,
+    pub uid: Option<i64>,
+    pub gid: Option<i64>,
+    pub inode: Option<i64>,
+    pub nlink: Option<i64>,
+    pub ctime: Option<i64>,
     // Folder counts (NULL for files)
     pub add_count: Option<i64>,
     pub modify_count: Option<i64>,
@@ -397,6 +416,9 @@ pub struct VersionHistoryEntry {
     pub file_hash: Option<Vec<u8>>,
     pub val_state: Option<i64>,
     pub val_error: Option<String>,
+    /// Name and version of the validator behind val_state
+    pub val_validator: Option<String>,
+    pub val_validator_version: Option<i64>,
     // Review timestamps
     pub val_reviewed_at: Option<i64>,
     pub hash_reviewed_at: Option<i64>,
@@ -425,6 +447,22 @@ impl VersionHistoryEntry {
             val_error: row.get(17)?,
             val_reviewed_at: row.get(18)?,
             hash_reviewed_at: row.get(19)?,
+            is_excluded: row.get(20)?,
+            symlink_target: row.get(21)?,
+            mode: row.get(22)?,
+            uid: row.get(23)?,
+            gid: row.get(24)?,
+            inode: row.get(25)?,
+            nlink: row.get(26)?,
+            ctime: row.get(27)?,
+            change_reason: row
+                .get::<_, Option<i64>>(28)?
+                .map(|bits| ChangeReason::from_i64(bits).names()),
+            moved_from_item_id: row.get(29)?,
+            moved_from_path: row.get(30)?,
+            moved_to_path: row.get(31)?,
+            val_validator: row.get(32)?,
+            val_validator_version: row.get(33)?,
         })
     }
 }
@@ -436,11 +474,19 @@ const VERSION_HISTORY_COLUMNS: &str =
      v.mod_date, v.size, \
      v.add_count, v.modify_count, v.delete_count, v.unchanged_count, \
      hv.hash_state, hv.file_hash, v.val_state, v.val_error, \
-     v.val_reviewed_at, v.hash_reviewed_at";
+     v.val_reviewed_at, v.hash_reviewed_at, v.is_excluded, v.symlink_target, \
+     v.mode, v.uid, v.gid, v.inode, v.nlink, v.ctime, v.change_reason, \
+     v.moved_from_item_id, mf.item_path, \
+     (SELECT mi.item_path FROM item_versions mv \
+      JOIN items mi ON mi.item_id = mv.item_id \
+      WHERE v.is_deleted = 1 AND mv.moved_from_item_id = v.item_id \
+        AND mv.first_scan_id = v.first_scan_id), \
+     v.val_validator, v.val_validator_version";
 
 const VERSION_HISTORY_JOINS: &str =
     "JOIN scans s1 ON s1.scan_id = v.first_scan_id \
      JOIN scans s2 ON s2.scan_id = v.last_scan_id \
+     LEFT JOIN items mf ON mf.item_id = v.moved_from_item_id \
      LEFT JOIN hash_versions hv ON hv.item_id = v.item_id \
        AND hv.item_version = v.item_version \
        AND hv.first_scan_id = ( \
@@ -589,6 +635,7 @@ pub struct HashHistoryEntry {
     #[serde(serialize_with = "serialize_hash")]
     pub file_hash: Vec<u8>,
     pub hash_state: i64,
+    pub hash_algo: HashAlgo,
 }
 
 impl HashHistoryEntry {
@@ -599,6 +646,7 @@ impl HashHistoryEntry {
             scan_started_at: row.get(2)?,
             file_hash: row.get(3)?,
             hash_state: row.get(4)?,
+            hash_algo: HashAlgo::from_i64(row.get(5)?),
         })
     }
 }
@@ -611,7 +659,7 @@ pub fn get_hash_history(
     let conn = Database::get_connection()?;
 
     let sql = r#"
-        SELECT hv.first_scan_id, hv.last_scan_id, s.started_at, hv.file_hash, hv.hash_state
+        SELECT hv.first_scan_id, hv.last_scan_id, s.started_at, hv.file_hash, hv.hash_state, hv.hash_algo
         FROM hash_versions hv
         JOIN scans s ON s.scan_id = hv.first_scan_id
         WHERE hv.item_id = ? AND hv.item_version = ?
@@ -775,6 +823,7 @@ mod tests {
         assert_eq!(Access::Ok.as_i64(), 0);
         assert_eq!(Access::MetaError.as_i64(), 1);
         assert_eq!(Access::ReadError.as_i64(), 2);
+        assert_eq!(Access::MountPoint.as_i64(), 3);
     }
 
     #[test]
@@ -782,6 +831,7 @@ mod tests {
         assert_eq!(Access::from_i64(0), Access::Ok);
         assert_eq!(Access::from_i64(1), Access::MetaError);
         assert_eq!(Access::from_i64(2), Access::ReadError);
+        assert_eq!(Access::from_i64(3), Access::MountPoint);
 
         // Invalid values should default to Ok
         assert_eq!(Access::from_i64(999), Access::Ok);
@@ -793,6 +843,7 @@ mod tests {
         assert_eq!(Access::Ok.short_name(), "N");
         assert_eq!(Access::MetaError.short_name(), "M");
         assert_eq!(Access::ReadError.short_name(), "R");
+        assert_eq!(Access::MountPoint.short_name(), "P");
     }
 
     #[test]
@@ -800,6 +851,7 @@ mod tests {
         assert_eq!(Access::Ok.full_name(), "No Error");
         assert_eq!(Access::MetaError.full_name(), "Meta Error");
         assert_eq!(Access::ReadError.full_name(), "Read Error");
+        assert_eq!(Access::MountPoint.full_name(), "Mount Point");
     }
 
     #[test]
@@ -811,11 +863,14 @@ mod tests {
         assert_eq!(Access::from_string("MetaError"), Some(Access::MetaError));
         assert_eq!(Access::from_string("Read Error"), Some(Access::ReadError));
         assert_eq!(Access::from_string("ReadError"), Some(Access::ReadError));
+        assert_eq!(Access::from_string("Mount Point"), Some(Access::MountPoint));
+        assert_eq!(Access::from_string("MountPoint"), Some(Access::MountPoint));
 
         // Short names
         assert_eq!(Access::from_string("N"), Some(Access::Ok));
         assert_eq!(Access::from_string("M"), Some(Access::MetaError));
         assert_eq!(Access::from_string("R"), Some(Access::ReadError));
+        assert_eq!(Access::from_string("P"), Some(Access::MountPoint));
 
         // Case insensitive
         assert_eq!(Access::from_string("no error"), Some(Access::Ok));
@@ -835,7 +890,12 @@ mod tests {
 
     #[test]
     fn test_access_round_trip() {
-        let states = [Access::Ok, Access::MetaError, Access::ReadError];
+        let states = [
+            Access::Ok,
+            Access::MetaError,
+            Access::ReadError,
+            Access::MountPoint,
+        ];
 
         for access in states {
             let str_val = access.short_name();
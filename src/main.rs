@@ -1,16 +1,24 @@
 mod api;
 mod cli;
+mod compare;
 mod config;
 mod db;
+mod duplicates;
 mod error;
 mod hash;
+mod ignore_files;
 mod integrity;
+mod io_throttle;
 mod item_identity;
 mod item_version;
 mod items;
 mod mcp;
+mod moves;
 mod query;
+mod root_rules;
 mod roots;
+mod run_window;
+mod scan_scope;
 mod task_manager;
 mod scanner;
 mod scans;
@@ -21,6 +29,8 @@ mod task;
 mod undo_log;
 mod utils;
 mod validate;
+mod walker;
+mod watcher;
 
 use std::path::PathBuf;
 use std::time::Instant;
@@ -9,7 +9,8 @@ fsPulse tracks filesystem state over time using temporal versioning:
 - **scans** — A scan is a point-in-time snapshot of a root. Records file/folder counts, sizes, and change/integrity stats.
 - **items** — Stable identity of a file or directory (path, name, type). Mutable state lives in versions, not here.
 - **versions** — Each version captures the full state of an item at a point in time (size, mod_date, val_state, etc.). A new version is created only when state changes. Filter with `is_current:(T)` for latest state. **IMPORTANT**: `is_current:(T)` returns the latest version of *every* item, including items whose latest version is a deletion record (`is_deleted:(T)`). To analyze only live (non-deleted) items, always combine both filters: `is_current:(T), is_deleted:(F)`.
-- **hashes** — SHA-256 hash observations on file versions. Hash state is Baseline (expected) or Suspect (hash changed without metadata change).
+- **hashes** — Hash observations on file versions. `hash_algo` records the algorithm (sha256, blake3, xxh3, sha512); each root chooses its own, and the first hash after a root switches algorithms is a new Baseline. Hash state is Baseline (expected) or Suspect (hash changed without metadata change).
+- **duplicates** — Derived, not stored: groups of files alive at their root's latest completed scan that share a size and hash, across all roots. Only files that have been hashed are included, and empty files are left out.
 
 Relationships:
 - A root has zero or more scans and zero or more items.
@@ -19,12 +20,14 @@ Relationships:
 Scan modes and integrity:
 - Every scan walks the filesystem and detects adds, modifications, and deletes. This always happens regardless of settings.
 - Integrity actions are optional per scan, controlled by two settings:
-  - **Hashing** (`is_hash`, `hash_all` on scans): Three modes — None (no hashing), New/Changed (`is_hash:(T), hash_all:(F)` — hashes only versions that have never been hashed), or All (`is_hash:(T), hash_all:(T)` — recomputes hashes for every file). Only "All" scans can detect suspect hashes, because only they recompute and compare hashes for previously hashed versions.
+  - **Hashing** (`is_hash`, `hash_all`, `hash_sampled` on scans): Four modes — None (no hashing), New/Changed (`is_hash:(T), hash_all:(F)` — hashes only versions that have never been hashed), All (`is_hash:(T), hash_all:(T)` — recomputes hashes for every file), or Sampled (`hash_sampled:(T)` — like New/Changed, plus a cheap hash of the head, tail and a few evenly spaced blocks of every other file; a file whose sample changed is fully hashed in the same scan). Only "All" and "Sampled" scans can detect suspect hashes, because only they re-check previously hashed versions. Sampled scans miss changes that fall entirely between sampled blocks.
   - **Validation** (`is_val` on scans): On or off. Validating scans check the structural integrity of files that have a validator and have not yet been validated for this version. Validation is never repeated on an already-validated version.
 - If a version has no hash observations or `val_state` is Unknown, it means no scan with the appropriate integrity setting has run since that version was created — it is not an error.
 
 Integrity review:
 - Users can mark integrity issues (validation failures, suspect hashes) as reviewed. `val_reviewed_at` and `hash_reviewed_at` on versions record when this happened (NULL = not yet reviewed). Filter for unreviewed issues with `val_reviewed_at:(null)` or `hash_reviewed_at:(null)`.
+- Checksum manifests (SHA256SUMS, `.md5`, `.sfv`, `.par2` and similar) found in a root are checked whenever a listed file is hashed. The versions domain records the result in `manifest_state` (Match or Mismatch) and `manifest_path`; a mismatch is its own integrity issue, reviewed via `manifest_reviewed_at`.
+- Validating scans also detect each file's type from its magic bytes, recorded in `content_type` on versions. `content_mismatch` is true when the extension claims a different type (such as an executable named `.pdf`); a mismatch is its own integrity issue, reviewed via `content_reviewed_at`.
 
 ## fspulse Query DSL
 
@@ -38,7 +41,8 @@ DOMAIN [WHERE ...] [GROUP BY ...] [SHOW ...] [ORDER BY ...] [LIMIT ...] [OFFSET
 
 - **items** — Item identity (path, name, extension, type)
 - **versions** — Item versions over time (size, mod_date, val_state, etc.). Filter with `is_current:(T), is_deleted:(F)` for latest state of live items. Using `is_current:(T)` alone includes deleted items.
-- **hashes** — Hash observations on item versions (file_hash, hash_state)
+- **hashes** — Hash observations on item versions (file_hash, hash_state, hash_algo)
+- **duplicates** — Groups of live files with the same size and hash (file_count, root_count, wasted_size). Filters on `root_id`, `item_path` and `file_extension` choose the files that are grouped; filters on other columns apply to the groups.
 - **scans** — Scan sessions (timestamps, counts, integrity findings)
 - **roots** — Monitored root directories
 
@@ -55,6 +59,7 @@ Filters use the syntax: `column_name:(value1, value2, ...)`
 | Path | `'/photos'`, `'report.pdf'` — **see path matching note below** |
 | Val State | `V`, `I`, `N`, `U` (Valid, Invalid, No Validator, Unknown) |
 | Hash State | `V`, `S`, `U` (Valid, Suspect, Unknown) |
+| Hash Algo | `sha256`, `blake3`, `xxh3`, `sha512` |
 | Item Type | `F`, `D`, `S`, `U` (File, Directory, Symlink, Unknown) |
 
 **Filter logic:** Values within a single filter's parentheses are OR'd; separate filters are AND'd and **must be comma-separated**.
@@ -82,7 +87,7 @@ Controls displayed columns. Use `default` for defaults, `all` for everything.
 
 **Format modifiers** — append `@mode` to a column name to control display format:
 
-- **Date columns** (`mod_date`, `started_at`, `ended_at`, `created_at`, `updated_at`, `val_reviewed_at`, `hash_reviewed_at`):
+- **Date columns** (`mod_date`, `started_at`, `ended_at`, `created_at`, `updated_at`, `val_reviewed_at`, `hash_reviewed_at`, `manifest_reviewed_at`, `content_reviewed_at`):
   - `@short` (default) — date only: `2026-03-30`
   - `@full` — date and time with second precision: `2026-03-30 18:44:11`
   - `@timestamp` — raw Unix epoch (seconds, UTC): `1743364800`
@@ -145,6 +150,7 @@ items where file_extension:('pdf') show item_path, item_name
 versions where is_current:(T), is_deleted:(F), item_type:(F) group by file_extension show file_extension, count(*), sum(size) order by sum(size) desc
 scans group by root_id show root_id, count(*), max(total_size) order by count(*) desc
 hashes group by hash_state show hash_state, count(*)
+duplicates where root_id:(1, 2), size:(> 1048576) order by wasted_size desc limit 20
 ```
 
 Pagination example:
@@ -162,11 +168,12 @@ pub(super) fn domain_help(domain: &str) -> Result<String, rmcp::ErrorData> {
         "items" => &ITEMS_QUERY_COLS,
         "versions" => &VERSIONS_QUERY_COLS,
         "hashes" => &HASHES_QUERY_COLS,
+        "duplicates" => &DUPLICATES_QUERY_COLS,
         "scans" => &SCANS_QUERY_COLS,
         "roots" => &ROOTS_QUERY_COLS,
         _ => {
             return Err(rmcp::ErrorData::invalid_params(
-                format!("Unknown domain '{}'. Valid domains: items, versions, hashes, scans, roots", domain),
+                format!("Unknown domain '{}'. Valid domains: items, versions, hashes, duplicates, scans, roots", domain),
                 None,
             ));
         }
@@ -16,7 +16,7 @@ pub struct QueryCountParams {
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 pub struct QueryHelpParams {
-    /// Optional domain: "roots", "scans", "items", "versions", or "hashes"
+    /// Optional domain: "roots", "scans", "items", "versions", "hashes", or "duplicates"
     pub domain: Option<String>,
 }
 
@@ -24,7 +24,7 @@ pub struct QueryHelpParams {
 pub struct IntegrityReportParams {
     /// Root ID to check
     pub root_id: i64,
-    /// Filter by issue type: "val", "hash", or "all" (default: "all")
+    /// Filter by issue type: "val", "hash", "manifest", "content", or "all" (default: "all")
     pub issue_type: Option<String>,
     /// Filter by review status: "unreviewed", "reviewed", or "all" (default: "unreviewed")
     pub status: Option<String>,
@@ -90,10 +90,72 @@ pub struct ItemDetailParams {
 pub struct ScanChangesParams {
     /// Scan ID
     pub scan_id: i64,
-    /// Filter by change type: "added", "modified", "deleted", or "all" (default: "all")
+    /// Filter by change type: "added", "modified", "deleted", "moved", "excluded", or "all" (default: "all").
+    /// Moves are listed once, at their new path, and are not repeated under "added" or "deleted"
     pub change_type: Option<String>,
     /// Maximum items to return (default 50, max 200)
     pub limit: Option<i64>,
     /// Number of items to skip for pagination (default 0)
     pub offset: Option<i64>,
 }
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct ScanSubtreeParams {
+    /// Root ID
+    pub root_id: i64,
+    /// Directory to scan, relative to the root (e.g. "Photos/2019")
+    pub subpath: String,
+    /// Hashing: "none", "new" (new and changed files), "all", or "sampled" (new and
+    /// changed files, plus a sampled screen of the rest) (default: "new")
+    pub hash_mode: Option<String>,
+    /// Validate new and changed files (default: false)
+    pub validate: Option<bool>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct CompareRootsParams {
+    /// Root ID of the left side (e.g. the primary copy)
+    pub left_root_id: i64,
+    /// Root ID of the right side (e.g. the backup or mirror)
+    pub right_root_id: i64,
+    /// Scan of the left root to compare as of (default: its latest completed scan)
+    pub left_scan_id: Option<i64>,
+    /// Scan of the right root to compare as of (default: its latest completed scan)
+    pub right_scan_id: Option<i64>,
+    /// Folder within the left root to compare instead of the whole root
+    pub left_subpath: Option<String>,
+    /// Folder within the right root to compare instead of the whole root
+    pub right_subpath: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct ComparisonReportParams {
+    /// Comparison to show. Omit to list recent comparison reports.
+    pub comparison_id: Option<i64>,
+    /// When listing, only reports with this root on either side
+    pub root_id: Option<i64>,
+    /// Filter differences by kind: "left_only", "right_only", "size", "hash", or "mod_date"
+    pub diff_kind: Option<String>,
+    /// Search substring in relative paths
+    pub path_search: Option<String>,
+    /// Maximum rows to return (default 50, max 200)
+    pub limit: Option<i64>,
+    /// Number of rows to skip for pagination (default 0)
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct FindDuplicatesParams {
+    /// Only look at files in these roots (default: all roots)
+    pub root_ids: Option<Vec<i64>>,
+    /// Only look at files whose path contains this substring
+    pub path_search: Option<String>,
+    /// Only look at files of at least this many bytes
+    pub min_size: Option<i64>,
+    /// Paths to list per group (default 10)
+    pub files_per_group: Option<i64>,
+    /// Maximum groups to return (default 50, max 200)
+    pub limit: Option<i64>,
+    /// Number of groups to skip for pagination (default 0)
+    pub offset: Option<i64>,
+}
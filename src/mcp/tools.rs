@@ -5,8 +5,15 @@ use rmcp::{
 };
 use rmcp::handler::server::wrapper::Parameters;
 
+use crate::compare::{CompareSideSpec, ComparisonSide, DiffKind, RootComparison};
 use crate::db::Database;
+use crate::duplicates::{DuplicateFilter, Duplicates};
 use crate::query::{QueryProcessor, QueryResultData};
+use crate::roots::Root;
+use crate::scan_scope::ScanScope;
+use crate::scans::HashMode;
+use crate::task::CompareRootsSettings;
+use crate::task_manager::TaskManager;
 
 use super::formatting::{format_table, effective_limit, fmt_ts, fmt_opt_ts, MAX_RESULT_ROWS};
 use super::help::{general_help, domain_help};
@@ -104,6 +111,24 @@ impl FsPulseMcp {
                 )
                 .unwrap_or(0);
 
+            let manifest_issues: i64 = conn
+                .query_row(
+                    "SELECT COUNT(DISTINCT iv.item_id) FROM item_versions iv
+                     WHERE iv.manifest_state = 2 AND iv.manifest_reviewed_at IS NULL",
+                    [],
+                    |row| row.get(0),
+                )
+                .unwrap_or(0);
+
+            let content_issues: i64 = conn
+                .query_row(
+                    "SELECT COUNT(DISTINCT iv.item_id) FROM item_versions iv
+                     WHERE iv.content_mismatch = 1 AND iv.content_reviewed_at IS NULL",
+                    [],
+                    |row| row.get(0),
+                )
+                .unwrap_or(0);
+
             // Database stats
             let db_path = Database::get_path().unwrap_or_default();
             let db_size = std::fs::metadata(&db_path)
@@ -121,8 +146,8 @@ impl FsPulseMcp {
                 }
             }
             out.push_str(&format!(
-                "\n## Integrity Issues (Unreviewed)\n\n- Suspect hashes: {}\n- Validation failures: {}\n",
-                hash_issues, val_issues
+                "\n## Integrity Issues (Unreviewed)\n\n- Suspect hashes: {}\n- Validation failures: {}\n- Manifest mismatches: {}\n- Content mismatches: {}\n",
+                hash_issues, val_issues, manifest_issues, content_issues
             ));
             out.push_str(&format!(
                 "\n## Database\n\n- Path: {}\n- Size: {} bytes\n",
@@ -139,7 +164,7 @@ impl FsPulseMcp {
         Ok(CallToolResult::success(vec![Content::text(result)]))
     }
 
-    #[tool(description = "Execute a fspulse query using the built-in DSL. Supports five domains: roots, scans, items, versions, hashes. Date display: @short (default, date only), @full (date + time), @timestamp (Unix epoch). Date filters accept all three forms: 2025-01-01, 2025-01-01 14:30:00, or 1735689600 — any output format can be used directly as filter input. Use LIMIT and OFFSET in the query string to paginate (max 200 rows per call). Use query_count to get total row counts. Use query_help for syntax details. Returns results as a markdown table.")]
+    #[tool(description = "Execute a fspulse query using the built-in DSL. Supports six domains: roots, scans, items, versions, hashes, duplicates. Date display: @short (default, date only), @full (date + time), @timestamp (Unix epoch). Date filters accept all three forms: 2025-01-01, 2025-01-01 14:30:00, or 1735689600 — any output format can be used directly as filter input. Use LIMIT and OFFSET in the query string to paginate (max 200 rows per call). Use query_count to get total row counts. Use query_help for syntax details. Returns results as a markdown table.")]
     async fn query_data(
         &self,
         Parameters(params): Parameters<QueryDataParams>,
@@ -208,7 +233,7 @@ impl FsPulseMcp {
         Ok(CallToolResult::success(vec![Content::text(text)]))
     }
 
-    #[tool(description = "Get a report of integrity issues (validation failures, suspect hashes) for a monitored root. Supports pagination via limit/offset parameters. Returns total count in response.")]
+    #[tool(description = "Get a report of integrity issues (validation failures, suspect hashes, checksum manifest mismatches, content mismatches) for a monitored root. Supports pagination via limit/offset parameters. Returns total count in response.")]
     async fn integrity_report(
         &self,
         Parameters(params): Parameters<IntegrityReportParams>,
@@ -241,20 +266,26 @@ impl FsPulseMcp {
                 return Ok(out);
             }
 
-            out.push_str("| Item | Path | Hash Issues | Val Issues |\n");
-            out.push_str("|------|------|-------------|------------|\n");
+            out.push_str("| Item | Path | Hash Issues | Val Issues | Manifest Issues | Content Issues |\n");
+            out.push_str("|------|------|-------------|------------|-----------------|----------------|\n");
 
             for item in &items {
                 let hash_total = item.hash_unreviewed + item.hash_reviewed;
                 let val_total = item.val_unreviewed + item.val_reviewed;
+                let manifest_total = item.manifest_unreviewed + item.manifest_reviewed;
+                let content_total = item.content_unreviewed + item.content_reviewed;
                 out.push_str(&format!(
-                    "| {} | {} | {} ({} unreviewed) | {} ({} unreviewed) |\n",
+                    "| {} | {} | {} ({} unreviewed) | {} ({} unreviewed) | {} ({} unreviewed) | {} ({} unreviewed) |\n",
                     item.item_id,
                     item.item_path,
                     hash_total,
                     item.hash_unreviewed,
                     val_total,
                     item.val_unreviewed,
+                    manifest_total,
+                    item.manifest_unreviewed,
+                    content_total,
+                    item.content_unreviewed,
                 ));
             }
 
@@ -429,7 +460,8 @@ impl FsPulseMcp {
                     crate::items::ItemType::Symlink => "Sym",
                     crate::items::ItemType::Unknown => "?",
                 };
-                let status = if child.is_deleted { "deleted" }
+                let status = if child.is_excluded { "excluded" }
+                    else if child.is_deleted { "deleted" }
                     else if child.is_added { "added" }
                     else { "" };
 
@@ -571,8 +603,8 @@ impl FsPulseMcp {
             out.push_str(&format!("\n## Version History ({} total)\n\n", version_count));
 
             if !versions.is_empty() {
-                out.push_str("| Version | Scans | Size | Mod Date | Added | Deleted | Val State |\n");
-                out.push_str("|---------|-------|------|----------|-------|---------|----------|\n");
+                out.push_str("| Version | Scans | Size | Mod Date | Added | Deleted | Changed | Val State |\n");
+                out.push_str("|---------|-------|------|----------|-------|---------|---------|----------|\n");
 
                 for v in &versions {
                     let val = match v.val_state {
@@ -580,8 +612,13 @@ impl FsPulseMcp {
                         Some(2) => "Invalid",
                         _ => "",
                     };
+                    let changed = match (&v.moved_from_path, &v.moved_to_path) {
+                        (Some(from), _) => format!("moved from {}", from),
+                        (None, Some(to)) => format!("moved to {}", to),
+                        _ => v.change_reason.as_deref().map(|r| r.join(", ")).unwrap_or_default(),
+                    };
                     out.push_str(&format!(
-                        "| {} | {}..{} | {} | {} | {} | {} | {} |\n",
+                        "| {} | {}..{} | {} | {} | {} | {} | {} | {} |\n",
                         v.item_version,
                         v.first_scan_id,
                         v.last_scan_id,
@@ -589,6 +626,7 @@ impl FsPulseMcp {
                         fmt_opt_ts(v.mod_date),
                         v.is_added,
                         v.is_deleted,
+                        changed,
                         val,
                     ));
                 }
@@ -611,7 +649,7 @@ impl FsPulseMcp {
         Ok(CallToolResult::success(vec![Content::text(result)]))
     }
 
-    #[tool(description = "Show what files were added, modified, or deleted in a specific scan. Returns all changes without path filtering. If you need changes for a specific directory or file pattern, use query_data on the versions domain with an item_path filter instead — it is more efficient for targeted questions. Supports pagination via limit/offset parameters. Returns total count in response.")]
+    #[tool(description = "Show what files were added, modified, deleted, or moved in a specific scan. Returns all changes without path filtering. If you need changes for a specific directory or file pattern, use query_data on the versions domain with an item_path filter instead — it is more efficient for targeted questions. Supports pagination via limit/offset parameters. Returns total count in response.")]
     async fn scan_changes(
         &self,
         Parameters(params): Parameters<ScanChangesParams>,
@@ -623,15 +661,18 @@ impl FsPulseMcp {
 
         // Build the WHERE clause based on change_type
         let where_clause = match change_type.as_str() {
-            "added" => format!("first_scan_id:({}), is_added:(T)", scan_id),
-            "deleted" => format!("first_scan_id:({}), is_deleted:(T)", scan_id),
+            "added" => format!("first_scan_id:({}), is_added:(T), is_moved:(F)", scan_id),
+            "deleted" => format!("first_scan_id:({}), is_deleted:(T), is_excluded:(F), is_moved:(F)", scan_id),
+            "moved" => format!("first_scan_id:({}), is_added:(T), is_moved:(T)", scan_id),
+            "excluded" => format!("first_scan_id:({}), is_excluded:(T)", scan_id),
             "modified" => format!("first_scan_id:({}), is_added:(F), is_deleted:(F)", scan_id),
             _ => format!("first_scan_id:({})", scan_id),
         };
 
         let show_clause = match change_type.as_str() {
-            "added" | "deleted" | "modified" => "show item_path, item_type, size",
-            _ => "show item_path, item_type, is_added, is_deleted, size",
+            "added" | "deleted" | "excluded" | "modified" => "show item_path, item_type, size",
+            "moved" => "show item_path, moved_from_path, item_type, size",
+            _ => "show item_path, item_type, is_added, is_deleted, is_excluded, is_moved, moved_from_path, size",
         };
 
         // Build count and data queries
@@ -658,20 +699,21 @@ impl FsPulseMcp {
                 let row_count = rows.len() as i64;
                 let summary = tokio::task::spawn_blocking(move || -> Result<String, String> {
                     let conn = Database::get_connection().map_err(|e| e.to_string())?;
-                    let (adds, mods, dels): (Option<i64>, Option<i64>, Option<i64>) = conn
+                    let (adds, mods, dels, moves): (Option<i64>, Option<i64>, Option<i64>, Option<i64>) = conn
                         .query_row(
-                            "SELECT add_count, modify_count, delete_count FROM scans WHERE scan_id = ?",
+                            "SELECT add_count, modify_count, delete_count, move_count FROM scans WHERE scan_id = ?",
                             [scan_id],
-                            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+                            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
                         )
                         .map_err(|e| format!("Scan {} not found: {}", scan_id, e))?;
 
                     Ok(format!(
-                        "Scan {} — {} added, {} modified, {} deleted\n\n",
+                        "Scan {} — {} added, {} modified, {} deleted, {} moved\n\n",
                         scan_id,
                         adds.unwrap_or(0),
                         mods.unwrap_or(0),
                         dels.unwrap_or(0),
+                        moves.unwrap_or(0),
                     ))
                 })
                 .await
@@ -698,6 +740,310 @@ impl FsPulseMcp {
             }
         }
     }
+
+    #[tool(description = "Queue a scan of one directory within a monitored root instead of the whole root, e.g. after restoring a folder from backup. Only that directory is walked, checked for deletions, hashed and validated; the rest of the root is carried forward unchanged and the sizes of the folders above it are recomputed. The scan runs in the background: use scan_history or scan_changes afterwards to see its results.")]
+    async fn scan_subtree(
+        &self,
+        Parameters(params): Parameters<ScanSubtreeParams>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let result = tokio::task::spawn_blocking(move || -> Result<String, String> {
+            let hash_mode = match params.hash_mode.as_deref().unwrap_or("new") {
+                "none" => HashMode::None,
+                "new" => HashMode::New,
+                "all" => HashMode::All,
+                "sampled" => HashMode::Sampled,
+                other => {
+                    return Err(format!(
+                        "Invalid hash_mode '{}': use \"none\", \"new\", \"all\" or \"sampled\"",
+                        other
+                    ))
+                }
+            };
+            let subpath = ScanScope::normalize_subpath(&params.subpath).map_err(|e| e.to_string())?;
+
+            let conn = Database::get_connection().map_err(|e| e.to_string())?;
+            let root = Root::get_by_id(&conn, params.root_id)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("Root {} not found", params.root_id))?;
+
+            let target = match &subpath {
+                Some(subpath) => format!("'{}' in root {} ({})", subpath, root.root_id(), root.root_path()),
+                None => format!("all of root {} ({})", root.root_id(), root.root_path()),
+            };
+            TaskManager::schedule_manual_scan(
+                &conn,
+                params.root_id,
+                hash_mode,
+                params.validate.unwrap_or(false),
+                subpath,
+                None,
+                None,
+            )
+            .map_err(|e| e.to_string())?;
+
+            Ok(format!(
+                "Queued a scan of {}. It starts when no other task is running.",
+                target
+            ))
+        })
+        .await
+        .map_err(|e| rmcp::ErrorData::internal_error(e.to_string(), None))?;
+
+        match result {
+            Ok(text) => Ok(CallToolResult::success(vec![Content::text(text)])),
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(e)])),
+        }
+    }
+
+    #[tool(description = "Queue a comparison of two monitored roots (or folders within them), e.g. a primary copy and its backup. Files are aligned by path relative to each side and reported as missing on either side, or differing in size, hash or modification date. Hashes are only compared when both sides were hashed with the same algorithm. Each side is compared as of a completed scan (default: the latest), so scan both roots first. The comparison runs in the background: use comparison_report afterwards to see the result.")]
+    async fn compare_roots(
+        &self,
+        Parameters(params): Parameters<CompareRootsParams>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let result = tokio::task::spawn_blocking(move || -> Result<String, String> {
+            let settings = CompareRootsSettings {
+                left: CompareSideSpec {
+                    root_id: params.left_root_id,
+                    scan_id: params.left_scan_id,
+                    subpath: params.left_subpath,
+                },
+                right: CompareSideSpec {
+                    root_id: params.right_root_id,
+                    scan_id: params.right_scan_id,
+                    subpath: params.right_subpath,
+                },
+            };
+
+            // Resolve both sides now so that bad roots, scans or subpaths are
+            // reported to the caller rather than failing in the background
+            let conn = Database::get_connection().map_err(|e| e.to_string())?;
+            let mut described = Vec::with_capacity(2);
+            for spec in [&settings.left, &settings.right] {
+                let side = ComparisonSide::resolve(&conn, spec).map_err(|e| e.to_string())?;
+                described.push(format!("{} (root {}, scan #{})", side.path, side.root_id, side.scan_id));
+            }
+
+            TaskManager::schedule_compare_roots(&conn, &settings).map_err(|e| e.to_string())?;
+
+            Ok(format!(
+                "Queued a comparison of {} with {}. It starts when no other task is running; \
+                 use comparison_report with root_id {} to find the result.",
+                described[0], described[1], settings.left.root_id
+            ))
+        })
+        .await
+        .map_err(|e| rmcp::ErrorData::internal_error(e.to_string(), None))?;
+
+        match result {
+            Ok(text) => Ok(CallToolResult::success(vec![Content::text(text)])),
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(e)])),
+        }
+    }
+
+    #[tool(description = "Get the results of root comparisons queued with compare_roots. Without comparison_id, lists completed comparison reports, newest first. With comparison_id, returns the report's counts (matching, unverified, left only, right only, size, hash and mod date differences) and a table of the differing files, filterable by diff_kind and path. Supports pagination via limit/offset parameters. Returns total count in response.")]
+    async fn comparison_report(
+        &self,
+        Parameters(params): Parameters<ComparisonReportParams>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let result = tokio::task::spawn_blocking(move || -> Result<String, String> {
+            let conn = Database::get_connection().map_err(|e| e.to_string())?;
+            let limit = effective_limit(params.limit);
+            let offset = params.offset.unwrap_or(0).max(0);
+
+            let Some(comparison_id) = params.comparison_id else {
+                let total = RootComparison::count(&conn, params.root_id).map_err(|e| e.to_string())?;
+                let comparisons = RootComparison::list(&conn, params.root_id, limit, offset)
+                    .map_err(|e| e.to_string())?;
+
+                let mut out = format!("Found {} comparison report(s).\n\n", total);
+                if comparisons.is_empty() {
+                    return Ok(out);
+                }
+
+                out.push_str("| Comparison | Completed | Left | Right | Match | Unverified | Differences |\n");
+                out.push_str("|------------|-----------|------|-------|-------|------------|-------------|\n");
+                for c in &comparisons {
+                    out.push_str(&format!(
+                        "| {} | {} | {} (scan #{}) | {} (scan #{}) | {} | {} | {} |\n",
+                        c.comparison_id,
+                        fmt_ts(c.completed_at),
+                        c.left.path,
+                        c.left.scan_id,
+                        c.right.path,
+                        c.right.scan_id,
+                        c.counts.match_count,
+                        c.counts.unverified_count,
+                        c.counts.diff_count(),
+                    ));
+                }
+
+                if total > comparisons.len() as i64 + offset {
+                    out.push_str(&format!(
+                        "\n({}. Use offset to paginate.)\n",
+                        pagination_summary(offset, comparisons.len() as i64, total)
+                    ));
+                }
+                return Ok(out);
+            };
+
+            let diff_kind = match params.diff_kind.as_deref() {
+                None => None,
+                Some(s) => Some(DiffKind::from_string(s).ok_or_else(|| {
+                    format!(
+                        "Invalid diff_kind '{}': use \"left_only\", \"right_only\", \"size\", \"hash\" or \"mod_date\"",
+                        s
+                    )
+                })?),
+            };
+            let path_search = params.path_search.as_deref();
+
+            let c = RootComparison::get_by_id(&conn, comparison_id)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("Comparison {} not found", comparison_id))?;
+
+            let counts = &c.counts;
+            let mut out = format!(
+                "Comparison {} of {} (root {}, scan #{}) with {} (root {}, scan #{}), completed {}.\n\n\
+                 - Matching (same size, mod date and hash): {}\n\
+                 - Unverified (same size and mod date, no comparable hashes): {}\n\
+                 - Left only: {}\n\
+                 - Right only: {}\n\
+                 - Size differs: {}\n\
+                 - Hash differs: {}\n\
+                 - Mod date differs: {}\n\n",
+                c.comparison_id,
+                c.left.path,
+                c.left.root_id,
+                c.left.scan_id,
+                c.right.path,
+                c.right.root_id,
+                c.right.scan_id,
+                fmt_ts(c.completed_at),
+                counts.match_count,
+                counts.unverified_count,
+                counts.left_only_count,
+                counts.right_only_count,
+                counts.size_diff_count,
+                counts.hash_diff_count,
+                counts.mod_date_diff_count,
+            );
+
+            let total = RootComparison::count_diffs(&conn, comparison_id, diff_kind, path_search)
+                .map_err(|e| e.to_string())?;
+            let diffs = RootComparison::get_diffs(&conn, comparison_id, diff_kind, path_search, limit, offset)
+                .map_err(|e| e.to_string())?;
+
+            out.push_str(&format!("Found {} difference(s).\n\n", total));
+            if diffs.is_empty() {
+                return Ok(out);
+            }
+
+            let opt = |v: Option<i64>| v.map(|v| v.to_string()).unwrap_or_else(|| "-".to_string());
+            out.push_str("| Path | Difference | Left Size | Right Size | Left Modified | Right Modified |\n");
+            out.push_str("|------|------------|-----------|------------|---------------|----------------|\n");
+            for d in &diffs {
+                out.push_str(&format!(
+                    "| {} | {} | {} | {} | {} | {} |\n",
+                    d.rel_path,
+                    d.diff_kind.full_name(),
+                    opt(d.left_size),
+                    opt(d.right_size),
+                    fmt_opt_ts(d.left_mod_date),
+                    fmt_opt_ts(d.right_mod_date),
+                ));
+            }
+
+            if total > diffs.len() as i64 + offset {
+                out.push_str(&format!(
+                    "\n({}. Use offset to paginate.)\n",
+                    pagination_summary(offset, diffs.len() as i64, total)
+                ));
+            }
+
+            Ok(out)
+        })
+        .await
+        .map_err(|e| rmcp::ErrorData::internal_error(e.to_string(), None))?;
+
+        match result {
+            Ok(text) => Ok(CallToolResult::success(vec![Content::text(text)])),
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(e)])),
+        }
+    }
+
+    #[tool(description = "Find duplicate files: groups of files with the same size and hash, within or across monitored roots. Each root is looked at as of its latest completed scan, and only files that have been hashed are found, so run scans with hashing first. Empty files are left out. Groups are listed by wasted space (the bytes taken by the copies beyond the first), each with its file paths. Supports pagination via limit/offset parameters. Returns total count in response. For grouping or filtering beyond this, use query_data on the duplicates domain.")]
+    async fn find_duplicates(
+        &self,
+        Parameters(params): Parameters<FindDuplicatesParams>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let result = tokio::task::spawn_blocking(move || -> Result<String, String> {
+            let conn = Database::get_connection().map_err(|e| e.to_string())?;
+            let limit = effective_limit(params.limit);
+            let offset = params.offset.unwrap_or(0).max(0);
+            let files_per_group = params.files_per_group.unwrap_or(10).clamp(1, MAX_RESULT_ROWS) as usize;
+
+            let filter = DuplicateFilter {
+                root_ids: params.root_ids.unwrap_or_default(),
+                path_search: params.path_search,
+                min_size: params.min_size,
+            };
+
+            let summary = Duplicates::summary(&conn, &filter).map_err(|e| e.to_string())?;
+            let groups = Duplicates::groups(&conn, &filter, limit, offset, files_per_group)
+                .map_err(|e| e.to_string())?;
+
+            let mut out = format!(
+                "Found {} duplicate group(s): {} files, {} bytes wasted.\n\n",
+                summary.group_count, summary.file_count, summary.wasted_size
+            );
+
+            for g in &groups {
+                out.push_str(&format!(
+                    "### {} files of {} bytes in {} root(s), {} bytes wasted\n\n{}: `{}`\n\n",
+                    g.file_count,
+                    g.size,
+                    g.root_count,
+                    g.wasted_size,
+                    g.hash_algo.full_name(),
+                    g.file_hash,
+                ));
+                out.push_str("| Root | Item ID | Path | Modified |\n");
+                out.push_str("|------|---------|------|----------|\n");
+                for f in &g.files {
+                    out.push_str(&format!(
+                        "| {} | {} | {} | {} |\n",
+                        f.root_id,
+                        f.item_id,
+                        f.item_path,
+                        fmt_opt_ts(f.mod_date),
+                    ));
+                }
+                if g.file_count > g.files.len() as i64 {
+                    out.push_str(&format!(
+                        "\n({} more file(s) not shown. Raise files_per_group to list them.)\n",
+                        g.file_count - g.files.len() as i64
+                    ));
+                }
+                out.push('\n');
+            }
+
+            if summary.group_count > groups.len() as i64 + offset {
+                out.push_str(&format!(
+                    "({}. Use offset to paginate.)\n",
+                    pagination_summary(offset, groups.len() as i64, summary.group_count)
+                ));
+            }
+
+            Ok(out)
+        })
+        .await
+        .map_err(|e| rmcp::ErrorData::internal_error(e.to_string(), None))?;
+
+        match result {
+            Ok(text) => Ok(CallToolResult::success(vec![Content::text(text)])),
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(e)])),
+        }
+    }
 }
 
 #[tool_handler]
@@ -0,0 +1,274 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use log::info;
+use rusqlite::{params, Connection};
+
+use crate::error::FsPulseError;
+use crate::item_identity::ItemType;
+
+/// An item added or deleted in a scan, as seen by move detection.
+///
+/// For a deletion, the fields describe the item's last live version (the
+/// tombstone carries them forward; the hash is read from the version before it).
+#[derive(Clone, Debug)]
+pub struct MoveCandidate {
+    pub item_id: i64,
+    pub item_version: i64,
+    pub item_type: ItemType,
+    pub dev: Option<i64>,
+    pub inode: Option<i64>,
+    pub size: Option<i64>,
+    pub mod_date: Option<i64>,
+    pub file_hash: Option<Vec<u8>>,
+}
+
+/// A matched pair: the added version `(item_id, item_version)` moved from `from_item_id`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MovePair {
+    pub item_id: i64,
+    pub item_version: i64,
+    pub from_item_id: i64,
+}
+
+/// Pairs items deleted in a scan with items added in the same scan, so that a
+/// rename shows up as a move rather than a delete plus an add.
+pub struct MoveDetector;
+
+impl MoveDetector {
+    /// Detect moves in `scan_id` and record them on the added versions.
+    ///
+    /// Runs in Phase 4. Safe to re-run on a resumed scan: the links written by
+    /// an earlier attempt are cleared first. Links live only on versions created
+    /// by this scan, so rollback removes them along with the versions.
+    pub fn detect_moves(
+        conn: &Connection,
+        root_id: i64,
+        scan_id: i64,
+    ) -> Result<usize, FsPulseError> {
+        let added = Self::query_candidates(conn, root_id, scan_id, false)?;
+        let deleted = Self::query_candidates(conn, root_id, scan_id, true)?;
+        let pairs = Self::pair(&added, &deleted);
+
+        conn.execute(
+            "UPDATE item_versions SET moved_from_item_id = NULL
+             WHERE root_id = ? AND first_scan_id = ? AND moved_from_item_id IS NOT NULL",
+            params![root_id, scan_id],
+        )?;
+
+        let mut stmt = conn.prepare(
+            "UPDATE item_versions SET moved_from_item_id = ?
+             WHERE item_id = ? AND item_version = ?",
+        )?;
+        for pair in &pairs {
+            stmt.execute(params![pair.from_item_id, pair.item_id, pair.item_version])?;
+        }
+
+        info!("Move detection: {} of {} added items were moves", pairs.len(), added.len());
+
+        Ok(pairs.len())
+    }
+
+    /// Match added items to deleted items.
+    ///
+    /// First by (dev, inode), which survives a rename on the same filesystem.
+    /// Inodes are reused as soon as they are freed, so for files the size and
+    /// modification date must also agree — a rename keeps both, a new file that
+    /// happens to land on the old inode rarely does.
+    ///
+    /// Then, for files not matched that way, by (size, hash) — a copy to the
+    /// new location followed by a delete of the old one. Only unambiguous
+    /// matches count: a key shared by several added or several deleted items
+    /// pairs nothing, and empty files are never matched by hash.
+    pub fn pair(added: &[MoveCandidate], deleted: &[MoveCandidate]) -> Vec<MovePair> {
+        let mut pairs = Vec::new();
+        let mut added_done = HashSet::new();
+        let mut deleted_done = HashSet::new();
+
+        let inode_key = |c: &MoveCandidate| match (c.dev, c.inode) {
+            (Some(dev), Some(inode)) if c.item_type == ItemType::File => {
+                Some((c.item_type.as_i64(), dev, inode, c.size, c.mod_date))
+            }
+            (Some(dev), Some(inode)) => Some((c.item_type.as_i64(), dev, inode, None, None)),
+            _ => None,
+        };
+        Self::pair_by_key(added, deleted, inode_key, &mut added_done, &mut deleted_done, &mut pairs);
+
+        let hash_key = |c: &MoveCandidate| match (c.item_type, c.size, &c.file_hash) {
+            (ItemType::File, Some(size), Some(hash)) if size > 0 => Some((size, hash.clone())),
+            _ => None,
+        };
+        Self::pair_by_key(added, deleted, hash_key, &mut added_done, &mut deleted_done, &mut pairs);
+
+        pairs
+    }
+
+    fn pair_by_key<K: Eq + Hash>(
+        added: &[MoveCandidate],
+        deleted: &[MoveCandidate],
+        key: impl Fn(&MoveCandidate) -> Option<K>,
+        added_done: &mut HashSet<i64>,
+        deleted_done: &mut HashSet<i64>,
+        pairs: &mut Vec<MovePair>,
+    ) {
+        // Key -> the single candidate with it, or None once a second one shows up
+        fn index<'a, K: Eq + Hash>(
+            candidates: &'a [MoveCandidate],
+            done: &HashSet<i64>,
+            key: &impl Fn(&MoveCandidate) -> Option<K>,
+        ) -> HashMap<K, Option<&'a MoveCandidate>> {
+            let mut map: HashMap<K, Option<&MoveCandidate>> = HashMap::new();
+            for c in candidates.iter().filter(|c| !done.contains(&c.item_id)) {
+                if let Some(k) = key(c) {
+                    map.entry(k)
+                        .and_modify(|slot| *slot = None)
+                        .or_insert(Some(c));
+                }
+            }
+            map
+        }
+
+        let deleted_by_key = index(deleted, deleted_done, &key);
+        let added_by_key = index(added, added_done, &key);
+
+        for (k, add) in &added_by_key {
+            let (Some(add), Some(Some(del))) = (add, deleted_by_key.get(k)) else {
+                continue;
+            };
+            // An item can't move onto itself
+            if add.item_id == del.item_id {
+                continue;
+            }
+            added_done.insert(add.item_id);
+            deleted_done.insert(del.item_id);
+            pairs.push(MovePair {
+                item_id: add.item_id,
+                item_version: add.item_version,
+                from_item_id: del.item_id,
+            });
+        }
+    }
+
+    /// Versions created by this scan that add (`deleted = false`) or delete
+    /// (`deleted = true`) an item. Excluded tombstones are not candidates.
+    fn query_candidates(
+        conn: &Connection,
+        root_id: i64,
+        scan_id: i64,
+        deleted: bool,
+    ) -> Result<Vec<MoveCandidate>, FsPulseError> {
+        let (filter, hash_version) = if deleted {
+            ("iv.is_deleted = 1 AND iv.is_excluded = 0", "iv.item_version - 1")
+        } else {
+            ("iv.is_added = 1 AND iv.is_deleted = 0", "iv.item_version")
+        };
+        let sql = format!(
+            "SELECT iv.item_id, iv.item_version, i.item_type, iv.dev, iv.inode, iv.size, iv.mod_date,
+                    (SELECT hv.file_hash FROM hash_versions hv
+                     WHERE hv.item_id = iv.item_id AND hv.item_version = {hash_version}
+                     ORDER BY hv.first_scan_id DESC LIMIT 1)
+             FROM item_versions iv
+             JOIN items i ON i.item_id = iv.item_id
+             WHERE iv.first_scan_id = ? AND iv.root_id = ? AND {filter}"
+        );
+
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params![scan_id, root_id], |row| {
+            Ok(MoveCandidate {
+                item_id: row.get(0)?,
+                item_version: row.get(1)?,
+                item_type: ItemType::from_i64(row.get(2)?),
+                dev: row.get(3)?,
+                inode: row.get(4)?,
+                size: row.get(5)?,
+                mod_date: row.get(6)?,
+                file_hash: row.get(7)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(item_id: i64, inode: Option<i64>, size: i64, hash: Option<&[u8]>) -> MoveCandidate {
+        MoveCandidate {
+            item_id,
+            item_version: 1,
+            item_type: ItemType::File,
+            dev: inode.map(|_| 1),
+            inode,
+            size: Some(size),
+            mod_date: Some(1_700_000_000),
+            file_hash: hash.map(|h| h.to_vec()),
+        }
+    }
+
+    fn from_ids(pairs: &[MovePair]) -> Vec<(i64, i64)> {
+        let mut ids: Vec<_> = pairs.iter().map(|p| (p.item_id, p.from_item_id)).collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn test_pair_by_inode() {
+        let added = vec![file(10, Some(100), 5, None), file(11, Some(101), 5, None)];
+        let deleted = vec![file(1, Some(101), 5, None), file(2, Some(100), 5, None)];
+
+        assert_eq!(from_ids(&MoveDetector::pair(&added, &deleted)), vec![(10, 2), (11, 1)]);
+    }
+
+    #[test]
+    fn test_inode_requires_same_type() {
+        let mut dir = file(10, Some(100), 0, None);
+        dir.item_type = ItemType::Directory;
+        let deleted = vec![file(1, Some(100), 0, None)];
+
+        assert!(MoveDetector::pair(&[dir], &deleted).is_empty());
+    }
+
+    #[test]
+    fn test_reused_inode_not_paired() {
+        // A new file landed on the freed inode: different mtime, so no inode match
+        let mut added = file(10, Some(100), 5, None);
+        added.mod_date = Some(1_800_000_000);
+        let deleted = vec![file(1, Some(100), 5, None)];
+
+        assert!(MoveDetector::pair(&[added], &deleted).is_empty());
+    }
+
+    #[test]
+    fn test_pair_by_hash_fallback() {
+        let added = vec![file(10, None, 5, Some(b"abc"))];
+        let deleted = vec![file(1, Some(100), 5, Some(b"abc"))];
+
+        assert_eq!(from_ids(&MoveDetector::pair(&added, &deleted)), vec![(10, 1)]);
+    }
+
+    #[test]
+    fn test_ambiguous_hash_not_paired() {
+        let added = vec![file(10, None, 5, Some(b"abc")), file(11, None, 5, Some(b"abc"))];
+        let deleted = vec![file(1, None, 5, Some(b"abc"))];
+
+        assert!(MoveDetector::pair(&added, &deleted).is_empty());
+    }
+
+    #[test]
+    fn test_empty_files_not_paired_by_hash() {
+        let added = vec![file(10, None, 0, Some(b"e3b0"))];
+        let deleted = vec![file(1, None, 0, Some(b"e3b0"))];
+
+        assert!(MoveDetector::pair(&added, &deleted).is_empty());
+    }
+
+    #[test]
+    fn test_inode_match_takes_precedence() {
+        // 10 has 1's inode and 2's content: the inode match wins, and 2 is left unpaired
+        let added = vec![file(10, Some(100), 5, Some(b"abc"))];
+        let deleted = vec![file(1, Some(100), 5, Some(b"xyz")), file(2, Some(200), 5, Some(b"abc"))];
+
+        assert_eq!(from_ids(&MoveDetector::pair(&added, &deleted)), vec![(10, 1)]);
+    }
+}
@@ -42,7 +42,10 @@ pub enum ColType {
     ItemType,
     ScanState,
     Access,
+    ChangeReason,
     HashState,
+    HashAlgo,
+    ManifestState,
     Int,
 }
 
@@ -102,13 +105,28 @@ impl ColType {
             ColType::Access => ColTypeInfo::new(
                 Rule::access_filter_EOI,
                 "Access",
-                "Access states: N (No Error), M (Meta Error), R (Read Error)\nComma-separated values (null and not null also ok)",
+                "Access states: N (No Error), M (Meta Error), R (Read Error), P (Mount Point)\nComma-separated values (null and not null also ok)",
+            ),
+            ColType::ChangeReason => ColTypeInfo::new(
+                Rule::change_reason_filter_EOI,
+                "Change Reason",
+                "Reasons: size, mod_date, access, symlink_target, mode, owner, inode, nlink, ctime, restored, descendants\nComma-separated values match versions with any of them (null and not null also ok)",
             ),
             ColType::HashState => ColTypeInfo::new(
                 Rule::hash_state_filter_EOI,
                 "Hash State",
                 "Hash states: U (Unknown), V (Valid), S (Suspect)\nComma-separated values (null and not null also ok)",
             ),
+            ColType::HashAlgo => ColTypeInfo::new(
+                Rule::hash_algo_filter_EOI,
+                "Hash Algorithm",
+                "Algorithms: sha256, blake3, xxh3, sha512\nComma-separated values",
+            ),
+            ColType::ManifestState => ColTypeInfo::new(
+                Rule::manifest_state_filter_EOI,
+                "Manifest State",
+                "Manifest states: M (Match), X (Mismatch)\nComma-separated values (null and not null also ok)",
+            ),
         }
     }
 
@@ -165,6 +183,7 @@ pub const SCANS_QUERY_COLS: ColMap = phf_ordered_map! {
     "scan_state" => ColSpec::new("state", "State", true, ColType::ScanState, ColAlign::Center, "Current scan state (Scanning, Completed, Error, etc.)"),
     "is_hash" => ColSpec::new("is_hash", "Is Hash", true, ColType::Bool, ColAlign::Center, "Whether this scan hashed new/changed files"),
     "hash_all" => ColSpec::new("hash_all", "Hash All", false, ColType::Bool, ColAlign::Center, "Whether this scan hashed all files including unchanged"),
+    "hash_sampled" => ColSpec::new("hash_sampled", "Hash Sampled", false, ColType::Bool, ColAlign::Center, "Whether this scan sampled previously hashed files, fully hashing any whose sample changed"),
     "is_val" => ColSpec::new("is_val", "Is Val", true, ColType::Bool, ColAlign::Center, "Whether this scan validated file contents"),
     "file_count" => ColSpec::new("file_count", "Files", true, ColType::Int, ColAlign::Right, "Total files found in this scan"),
     "folder_count" => ColSpec::new("folder_count", "Folders", true, ColType::Int, ColAlign::Right, "Total folders found in this scan"),
@@ -174,6 +193,7 @@ pub const SCANS_QUERY_COLS: ColMap = phf_ordered_map! {
     "add_count" => ColSpec::new("add_count", "Adds", true, ColType::Int, ColAlign::Right, "Items added in this scan"),
     "modify_count" => ColSpec::new("modify_count", "Modifies", true, ColType::Int, ColAlign::Right, "Items modified in this scan"),
     "delete_count" => ColSpec::new("delete_count", "Deletes", true, ColType::Int, ColAlign::Right, "Items deleted in this scan"),
+    "move_count" => ColSpec::new("move_count", "Moves", true, ColType::Int, ColAlign::Right, "Items moved or renamed in this scan (not counted as adds or deletes)"),
     "val_unknown_count" => ColSpec::new("val_unknown_count", "Val Unknown", false, ColType::Int, ColAlign::Right, "Files with unknown validation state at scan completion"),
     "val_valid_count" => ColSpec::new("val_valid_count", "Val Valid", false, ColType::Int, ColAlign::Right, "Files with valid validation state at scan completion"),
     "val_invalid_count" => ColSpec::new("val_invalid_count", "Val Invalid", false, ColType::Int, ColAlign::Right, "Files with invalid validation state at scan completion"),
@@ -193,6 +213,12 @@ pub const ITEMS_QUERY_COLS: ColMap = phf_ordered_map! {
     "item_type" => ColSpec::new("i.item_type", "Type", true, ColType::ItemType, ColAlign::Center, "File (F), Directory (D), or Symlink (S)"),
     "has_validator" => ColSpec::new("i.has_validator", "Has Validator", false, ColType::Bool, ColAlign::Center, "Whether a structural validator exists for this file type"),
     "do_not_validate" => ColSpec::new("i.do_not_validate", "Do Not Validate", false, ColType::Bool, ColAlign::Center, "Whether user has opted this item out of validation"),
+    "mode" => ColSpec::new("(SELECT mode FROM item_versions WHERE item_id = i.item_id ORDER BY item_version DESC LIMIT 1)", "Mode", false, ColType::Int, ColAlign::Right, "Permission bits of the latest version as an integer, e.g. 420 for 0644 (NULL if not recorded)"),
+    "uid" => ColSpec::new("(SELECT uid FROM item_versions WHERE item_id = i.item_id ORDER BY item_version DESC LIMIT 1)", "UID", false, ColType::Int, ColAlign::Right, "Owning user id of the latest version (NULL if not recorded)"),
+    "gid" => ColSpec::new("(SELECT gid FROM item_versions WHERE item_id = i.item_id ORDER BY item_version DESC LIMIT 1)", "GID", false, ColType::Int, ColAlign::Right, "Owning group id of the latest version (NULL if not recorded)"),
+    "inode" => ColSpec::new("(SELECT inode FROM item_versions WHERE item_id = i.item_id ORDER BY item_version DESC LIMIT 1)", "Inode", false, ColType::Int, ColAlign::Right, "Inode number of the latest version (NULL if not recorded)"),
+    "nlink" => ColSpec::new("(SELECT nlink FROM item_versions WHERE item_id = i.item_id ORDER BY item_version DESC LIMIT 1)", "Links", false, ColType::Int, ColAlign::Right, "Hard link count of the latest version (NULL if not recorded)"),
+    "ctime" => ColSpec::new("(SELECT ctime FROM item_versions WHERE item_id = i.item_id ORDER BY item_version DESC LIMIT 1)", "Ctime", false, ColType::Date, ColAlign::Center, "Inode change timestamp of the latest version (NULL if not recorded)"),
 };
 
 pub const VERSIONS_QUERY_COLS: ColMap = phf_ordered_map! {
@@ -207,10 +233,22 @@ pub const VERSIONS_QUERY_COLS: ColMap = phf_ordered_map! {
     "last_scan_id" => ColSpec::new("iv.last_scan_id", "Last Scan", true, ColType::Id, ColAlign::Right, "Last scan where this version was still current"),
     "is_added" => ColSpec::new("iv.is_added", "Added", false, ColType::Bool, ColAlign::Center, "True if this version represents an add (new item or restoration of a deleted item)"),
     "is_deleted" => ColSpec::new("iv.is_deleted", "Deleted", true, ColType::Bool, ColAlign::Center, "True if this version represents a deletion"),
+    "is_excluded" => ColSpec::new("iv.is_excluded", "Excluded", false, ColType::Bool, ColAlign::Center, "True if this deletion is an excluded tombstone (item hidden by a root rule, not removed from disk)"),
+    "is_moved" => ColSpec::new("(iv.moved_from_item_id IS NOT NULL OR (iv.is_deleted = 1 AND EXISTS (SELECT 1 FROM item_versions mv WHERE mv.moved_from_item_id = iv.item_id AND mv.first_scan_id = iv.first_scan_id)))", "Moved", false, ColType::Bool, ColAlign::Center, "True if this add or delete is one half of a move detected in the same scan"),
+    "moved_from_item_id" => ColSpec::new("iv.moved_from_item_id", "Moved From", false, ColType::Id, ColAlign::Right, "Item this version was moved from (NULL unless the add was detected as a move)"),
+    "moved_from_path" => ColSpec::new("(SELECT item_path FROM items WHERE item_id = iv.moved_from_item_id)", "Moved From Path", false, ColType::Path, ColAlign::Left, "Path of the item this version was moved from (NULL unless the add was detected as a move)"),
     "is_current" => ColSpec::new("(iv.first_scan_id = (SELECT MAX(first_scan_id) FROM item_versions WHERE item_id = iv.item_id))", "Current", false, ColType::Bool, ColAlign::Center, "True for the latest version of each item (includes deleted items — combine with is_deleted:(F) for live items)"),
-    "access" => ColSpec::new("iv.access", "Access", false, ColType::Access, ColAlign::Center, "Filesystem access state: No Error, Meta Error, or Read Error"),
+    "access" => ColSpec::new("iv.access", "Access", false, ColType::Access, ColAlign::Center, "Filesystem access state: No Error, Meta Error, Read Error, or Mount Point (not traversed)"),
     "mod_date" => ColSpec::new("iv.mod_date", "Mod Date", true, ColType::Date, ColAlign::Center, "Filesystem modification timestamp"),
     "size" => ColSpec::new("iv.size", "Size", true, ColType::Int, ColAlign::Right, "Size in bytes"),
+    "symlink_target" => ColSpec::new("iv.symlink_target", "Symlink Target", false, ColType::String, ColAlign::Left, "Target stored in the symlink, as written (NULL for non-symlinks)"),
+    "change_reason" => ColSpec::new("iv.change_reason", "Change Reason", false, ColType::ChangeReason, ColAlign::Left, "What changed from the previous version (NULL for new items, deletions, and versions recorded before reasons were tracked)"),
+    "mode" => ColSpec::new("iv.mode", "Mode", false, ColType::Int, ColAlign::Right, "Permission bits as an integer, e.g. 420 for 0644 (NULL if not recorded)"),
+    "uid" => ColSpec::new("iv.uid", "UID", false, ColType::Int, ColAlign::Right, "Owning user id (NULL if not recorded)"),
+    "gid" => ColSpec::new("iv.gid", "GID", false, ColType::Int, ColAlign::Right, "Owning group id (NULL if not recorded)"),
+    "inode" => ColSpec::new("iv.inode", "Inode", false, ColType::Int, ColAlign::Right, "Inode number (NULL if not recorded)"),
+    "nlink" => ColSpec::new("iv.nlink", "Links", false, ColType::Int, ColAlign::Right, "Hard link count (NULL if not recorded)"),
+    "ctime" => ColSpec::new("iv.ctime", "Ctime", false, ColType::Date, ColAlign::Center, "Inode change timestamp (NULL if not recorded)"),
     "add_count" => ColSpec::new("iv.add_count", "Adds", false, ColType::Int, ColAlign::Right, "Descendant items added (folders only; NULL for files)"),
     "modify_count" => ColSpec::new("iv.modify_count", "Modifies", false, ColType::Int, ColAlign::Right, "Descendant items modified (folders only; NULL for files)"),
     "delete_count" => ColSpec::new("iv.delete_count", "Deletes", false, ColType::Int, ColAlign::Right, "Descendant items deleted (folders only; NULL for files)"),
@@ -220,6 +258,14 @@ pub const VERSIONS_QUERY_COLS: ColMap = phf_ordered_map! {
     "val_error" => ColSpec::new("iv.val_error", "Val Error", false, ColType::String, ColAlign::Left, "Validation error details (NULL unless val_state is Invalid)"),
     "val_reviewed_at" => ColSpec::new("iv.val_reviewed_at", "Val Reviewed", false, ColType::Date, ColAlign::Center, "When user marked this validation issue as reviewed (NULL until reviewed)"),
     "hash_reviewed_at" => ColSpec::new("iv.hash_reviewed_at", "Hash Reviewed", false, ColType::Date, ColAlign::Center, "When user marked this hash integrity issue as reviewed (NULL until reviewed)"),
+    "manifest_scan_id" => ColSpec::new("iv.manifest_scan_id", "Manifest Scan", false, ColType::Id, ColAlign::Right, "Scan in which this version was last checked against a checksum manifest (NULL if no manifest lists it)"),
+    "manifest_state" => ColSpec::new("iv.manifest_state", "Manifest State", false, ColType::ManifestState, ColAlign::Center, "Checksum manifest result: Match or Mismatch (NULL if not checked)"),
+    "manifest_path" => ColSpec::new("iv.manifest_path", "Manifest Path", false, ColType::Path, ColAlign::Left, "Checksum manifest (SHA256SUMS, .md5, .sfv, .par2, ...) that lists this file"),
+    "manifest_reviewed_at" => ColSpec::new("iv.manifest_reviewed_at", "Manifest Reviewed", false, ColType::Date, ColAlign::Center, "When user marked this manifest mismatch as reviewed (NULL until reviewed)"),
+    "content_scan_id" => ColSpec::new("iv.content_scan_id", "Content Scan", false, ColType::Id, ColAlign::Right, "Scan in which this version's contents were examined for their type (NULL if not examined)"),
+    "content_type" => ColSpec::new("iv.content_type", "Content Type", false, ColType::String, ColAlign::Left, "Type detected from the file's magic bytes: a validator name, a known type such as executable, or unknown (NULL if not examined)"),
+    "content_mismatch" => ColSpec::new("COALESCE(iv.content_mismatch, 0)", "Content Mismatch", false, ColType::Bool, ColAlign::Center, "True if the detected content type differs from the type the extension claims"),
+    "content_reviewed_at" => ColSpec::new("iv.content_reviewed_at", "Content Reviewed", false, ColType::Date, ColAlign::Center, "When user marked this content mismatch as reviewed (NULL until reviewed)"),
 };
 
 pub const HASHES_QUERY_COLS: ColMap = phf_ordered_map! {
@@ -229,10 +275,29 @@ pub const HASHES_QUERY_COLS: ColMap = phf_ordered_map! {
     "item_name" => ColSpec::new("i.item_name", "Item Name", false, ColType::Path, ColAlign::Left, "File name only"),
     "first_scan_id" => ColSpec::new("hv.first_scan_id", "First Scan", true, ColType::Id, ColAlign::Right, "Scan that first computed this hash"),
     "last_scan_id" => ColSpec::new("hv.last_scan_id", "Last Scan", true, ColType::Id, ColAlign::Right, "Last scan where this hash was still current"),
-    "file_hash" => ColSpec::new("hv.file_hash", "File Hash", true, ColType::Hash, ColAlign::Left, "SHA-256 hash of file contents"),
+    "file_hash" => ColSpec::new("hv.file_hash", "File Hash", true, ColType::Hash, ColAlign::Left, "Hash of file contents, computed with hash_algo"),
     "hash_state" => ColSpec::new("hv.hash_state", "Hash State", true, ColType::HashState, ColAlign::Center, "Baseline (first/expected hash) or Suspect (hash changed without metadata change)"),
+    "hash_algo" => ColSpec::new("hv.hash_algo", "Hash Algo", false, ColType::HashAlgo, ColAlign::Center, "Algorithm that produced file_hash: SHA-256, BLAKE3, XXH3-128, or SHA-512"),
 };
 
+// Each row is a group of live files sharing a size and hash. Filters on root_id,
+// item_path and file_extension choose files before they are grouped (see
+// DUPLICATE_FILE_FILTER_COLS); filters on the other columns apply to groups.
+pub const DUPLICATES_QUERY_COLS: ColMap = phf_ordered_map! {
+    "file_hash" => ColSpec::new("file_hash", "File Hash", true, ColType::Hash, ColAlign::Left, "Hash shared by every file in the group"),
+    "hash_algo" => ColSpec::new("hash_algo", "Hash Algo", false, ColType::HashAlgo, ColAlign::Center, "Algorithm that produced file_hash: SHA-256, BLAKE3, XXH3-128, or SHA-512"),
+    "size" => ColSpec::new("size", "Size", true, ColType::Int, ColAlign::Right, "Size of each file in bytes"),
+    "file_count" => ColSpec::new("file_count", "Files", true, ColType::Int, ColAlign::Right, "Number of files in the group"),
+    "root_count" => ColSpec::new("root_count", "Roots", true, ColType::Int, ColAlign::Right, "Number of roots the group's files are in"),
+    "wasted_size" => ColSpec::new("wasted_size", "Wasted Size", true, ColType::Int, ColAlign::Right, "Bytes taken by the copies beyond the first: (file_count - 1) * size"),
+    "root_id" => ColSpec::new("root_id", "Root Ids", false, ColType::Id, ColAlign::Left, "Roots the group's files are in, comma separated. Filtering on it selects files by root"),
+    "item_path" => ColSpec::new("item_path", "Item Path", true, ColType::Path, ColAlign::Left, "First path in the group. Filtering on it selects files by path"),
+    "file_extension" => ColSpec::new("file_extension", "Extension", false, ColType::String, ColAlign::Left, "Extensions of the group's files, comma separated. Filtering on it selects files by extension"),
+};
+
+/// Duplicates columns whose filters select files rather than groups
+pub const DUPLICATE_FILE_FILTER_COLS: &[&str] = &["root_id", "item_path", "file_extension"];
+
 #[derive(Debug, Copy, Clone)]
 pub struct ColSet {
     col_map: &'static ColMap,
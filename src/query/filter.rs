@@ -1,6 +1,8 @@
 use crate::{
     error::FsPulseError,
-    hash::HashState,
+    hash::{HashAlgo, HashState},
+    integrity::manifest::ManifestState,
+    item_version::ChangeReason,
     items::{Access, ItemType},
     scans::ScanState,
     utils::Utils,
@@ -634,6 +636,8 @@ static ENUM_PARSERS: Map<&'static str, EnumParser> = phf_map! {
 "val_state" => ValidationState::from_token,
 "access" => Access::from_token,
 "hash_state" => HashState::from_token,
+"hash_algo" => HashAlgo::from_token,
+"manifest_state" => ManifestState::from_token,
 };
 
 /// Filter for integer-backed enums (like scan_state)
@@ -788,6 +792,96 @@ impl EnumFilter {
     }
 }
 
+// ==================================================================================
+// Change Reason Filter (bitmask column)
+// ==================================================================================
+
+/// Filter for the change_reason bitmask. Listed reasons are OR'd: a version
+/// matches if any of them is set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangeReasonFilter {
+    change_reason_col_db: &'static str,
+    mask: i64,
+    match_null: bool,
+    match_not_null: bool,
+}
+
+impl Filter for ChangeReasonFilter {
+    fn to_predicate_parts(&self) -> Result<(String, Vec<Box<dyn ToSql>>), FsPulseError> {
+        let mut preds = Vec::new();
+        let mut pred_vec: Vec<Box<dyn ToSql>> = Vec::new();
+
+        if self.match_null {
+            preds.push(format!("({} IS NULL)", self.change_reason_col_db));
+        }
+        if self.match_not_null {
+            preds.push(format!("({} IS NOT NULL)", self.change_reason_col_db));
+        }
+        if self.mask != 0 {
+            preds.push(format!("(({} & ?) != 0)", self.change_reason_col_db));
+            pred_vec.push(Box::new(self.mask));
+        }
+
+        let pred_str = if preds.len() > 1 {
+            format!("({})", preds.join(" OR "))
+        } else {
+            preds.concat()
+        };
+
+        Ok((pred_str, pred_vec))
+    }
+}
+
+impl ChangeReasonFilter {
+    fn new(change_reason_col_db: &'static str) -> Self {
+        ChangeReasonFilter {
+            change_reason_col_db,
+            mask: 0,
+            match_null: false,
+            match_not_null: false,
+        }
+    }
+
+    pub fn add_change_reason_filter_to_query(
+        change_reason_filter_pair: Pair<Rule>,
+        query: &mut dyn Query,
+    ) -> Result<(), FsPulseError> {
+        let mut iter = change_reason_filter_pair.into_inner();
+        let col = iter.next().unwrap().as_str().to_owned();
+
+        let mut filter = match query.col_set().col_name_to_db(&col) {
+            Some(col_db) => Self::new(col_db),
+            None => {
+                return Err(FsPulseError::CustomParsingError(format!(
+                    "Column not found: '{col}'"
+                )))
+            }
+        };
+
+        for spec_pair in iter {
+            match spec_pair.as_rule() {
+                Rule::null => filter.match_null = true,
+                Rule::not_null => filter.match_not_null = true,
+                _ => {
+                    let token_str = spec_pair.as_str();
+                    match ChangeReason::from_token(token_str) {
+                        Some(bit) => filter.mask |= bit,
+                        None => {
+                            return Err(FsPulseError::CustomParsingError(format!(
+                                "Invalid change_reason value: '{token_str}'"
+                            )))
+                        }
+                    }
+                }
+            }
+        }
+
+        query.add_filter(Box::new(filter));
+
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PathFilter {
     path_col_db: &'static str,
@@ -1984,4 +2078,85 @@ mod tests {
             result.err()
         );
     }
+
+    // ==================================================================================
+    // Hash Algorithm Filter Tests
+    // ==================================================================================
+
+    #[test]
+    fn test_hash_algo_filter_values() {
+        for input in ["sha256", "BLAKE3, xxh3", "SHA-512", "xxh3-128", "null"] {
+            let result = QueryParser::parse(Rule::hash_algo_filter_EOI, input);
+            assert!(result.is_ok(), "Failed to parse '{input}': {:?}", result.err());
+            for pair in result.unwrap().flatten().filter(|p| p.as_rule() == Rule::hash_algo) {
+                assert!(HashAlgo::from_token(pair.as_str()).is_some(), "No value for '{}'", pair.as_str());
+            }
+        }
+        assert!(QueryParser::parse(Rule::hash_algo_filter_EOI, "md5").is_err());
+    }
+
+    // ==================================================================================
+    // Manifest State Filter Tests
+    // ==================================================================================
+
+    #[test]
+    fn test_manifest_state_filter_values() {
+        for input in ["mismatch", "Match, X", "M", "not null"] {
+            let result = QueryParser::parse(Rule::manifest_state_filter_EOI, input);
+            assert!(result.is_ok(), "Failed to parse '{input}': {:?}", result.err());
+            for pair in result.unwrap().flatten().filter(|p| p.as_rule() == Rule::manifest_state) {
+                assert!(ManifestState::from_token(pair.as_str()).is_some(), "No value for '{}'", pair.as_str());
+            }
+        }
+        assert!(QueryParser::parse(Rule::manifest_state_filter_EOI, "suspect").is_err());
+    }
+
+    // ==================================================================================
+    // Change Reason Filter Tests
+    // ==================================================================================
+
+    #[test]
+    fn test_change_reason_filter_values() {
+        for input in ["size", "size, mod_date", "MODE, owner", "restored", "null", "not null, ctime"] {
+            let result = QueryParser::parse(Rule::change_reason_filter_EOI, input);
+            assert!(result.is_ok(), "Failed to parse '{input}': {:?}", result.err());
+        }
+    }
+
+    #[test]
+    fn test_change_reason_filter_invalid_value() {
+        let result = QueryParser::parse(Rule::change_reason_filter_EOI, "type");
+        assert!(result.is_err(), "Should reject unknown reason 'type'");
+    }
+
+    #[test]
+    fn test_change_reason_filter_predicate_mask() {
+        let filter = ChangeReasonFilter {
+            change_reason_col_db: "iv.change_reason",
+            mask: (ChangeReason::SIZE | ChangeReason::MOD_DATE).as_i64(),
+            match_null: false,
+            match_not_null: false,
+        };
+
+        let (pred_str, pred_vec) = filter.to_predicate_parts().unwrap();
+        assert_eq!(pred_str, "((iv.change_reason & ?) != 0)");
+        assert_eq!(pred_vec.len(), 1);
+    }
+
+    #[test]
+    fn test_change_reason_filter_predicate_null_with_mask() {
+        let filter = ChangeReasonFilter {
+            change_reason_col_db: "iv.change_reason",
+            mask: ChangeReason::ACCESS.as_i64(),
+            match_null: true,
+            match_not_null: false,
+        };
+
+        let (pred_str, pred_vec) = filter.to_predicate_parts().unwrap();
+        assert_eq!(
+            pred_str,
+            "((iv.change_reason IS NULL) OR ((iv.change_reason & ?) != 0))"
+        );
+        assert_eq!(pred_vec.len(), 1);
+    }
 }
@@ -4,10 +4,10 @@ use rusqlite::{Row, Statement, ToSql};
 
 use super::{
     columns::{
-        ColSet, HASHES_QUERY_COLS, ITEMS_QUERY_COLS, ROOTS_QUERY_COLS, SCANS_QUERY_COLS,
-        VERSIONS_QUERY_COLS,
+        ColSet, DUPLICATES_QUERY_COLS, DUPLICATE_FILE_FILTER_COLS, HASHES_QUERY_COLS,
+        ITEMS_QUERY_COLS, ROOTS_QUERY_COLS, SCANS_QUERY_COLS, VERSIONS_QUERY_COLS,
     },
-    filter::{BoolFilter, EnumFilter, IntFilter},
+    filter::{BoolFilter, ChangeReasonFilter, EnumFilter, IntFilter},
     show::{Format, Show},
 };
 
@@ -173,25 +173,11 @@ pub trait Query {
             self.cols_as_select_list()
         };
 
-        // Build WHERE clause and collect parameters
+        // Build WHERE clauses and collect parameters. The file-level clause
+        // comes first in the templates that have one, so its parameters do too.
         let mut params_vec: Vec<Box<dyn ToSql>> = Vec::new();
-        let mut where_clause = String::new();
-
-        if !self.query_impl().filters.is_empty() {
-            let mut first = true;
-            where_clause.push_str("\nWHERE ");
-            for filter in &self.query_impl().filters {
-                if !first {
-                    where_clause.push_str(" AND ");
-                }
-                first = false;
-
-                // Note: This unwrap is safe because filters validate during parsing
-                let (pred_str, pred_vec) = filter.to_predicate_parts().unwrap();
-                where_clause.push_str(&pred_str);
-                params_vec.extend(pred_vec);
-            }
-        }
+        let file_where_clause = filters_as_where_clause(&self.query_impl().file_filters, &mut params_vec);
+        let where_clause = filters_as_where_clause(&self.query_impl().filters, &mut params_vec);
 
         // Build ORDER BY, LIMIT, OFFSET clauses (omitted for count queries)
         let order_clause = if count_only {
@@ -241,6 +227,7 @@ pub trait Query {
                 .query_impl()
                 .sql_template
                 .replace("{select_list}", "1")
+                .replace("{file_where_clause}", &file_where_clause)
                 .replace("{where_clause}", &where_clause)
                 .replace("{group_clause}", &group_clause)
                 .replace("{order_clause}", "")
@@ -252,6 +239,7 @@ pub trait Query {
             self.query_impl()
                 .sql_template
                 .replace("{select_list}", &select_list)
+                .replace("{file_where_clause}", &file_where_clause)
                 .replace("{where_clause}", &where_clause)
                 .replace("{group_clause}", &group_clause)
                 .replace("{order_clause}", &order_clause)
@@ -337,6 +325,30 @@ pub trait Query {
     }
 }
 
+/// Join filters into a WHERE clause, appending their parameters to `params_vec`.
+/// No filters gives an empty clause.
+fn filters_as_where_clause(filters: &[Box<dyn Filter>], params_vec: &mut Vec<Box<dyn ToSql>>) -> String {
+    let mut where_clause = String::new();
+
+    if !filters.is_empty() {
+        let mut first = true;
+        where_clause.push_str("\nWHERE ");
+        for filter in filters {
+            if !first {
+                where_clause.push_str(" AND ");
+            }
+            first = false;
+
+            // Note: This unwrap is safe because filters validate during parsing
+            let (pred_str, pred_vec) = filter.to_predicate_parts().unwrap();
+            where_clause.push_str(&pred_str);
+            params_vec.extend(pred_vec);
+        }
+    }
+
+    where_clause
+}
+
 fn make_query(query_type: &str, count_only: bool) -> Box<dyn Query> {
     match (query_type, count_only) {
         ("roots", _) => Box::new(RootsQuery {
@@ -366,6 +378,13 @@ fn make_query(query_type: &str, count_only: bool) -> Box<dyn Query> {
                 ColSet::new(&HASHES_QUERY_COLS),
             ),
         }),
+        ("duplicates", _) => Box::new(DuplicatesQuery {
+            imp: QueryImpl::new(
+                QueryImpl::DUPLICATES_SQL_QUERY,
+                ColSet::new(&DUPLICATES_QUERY_COLS),
+            )
+            .with_file_filter_cols(DUPLICATE_FILE_FILTER_COLS),
+        }),
         _ => unreachable!(),
     }
 }
@@ -475,6 +494,12 @@ impl ItemsQuery {
                 "item_type" => Format::format_item_type(item.item_type, col.format)?,
                 "has_validator" => Format::format_bool(item.has_validator, col.format)?,
                 "do_not_validate" => Format::format_bool(item.do_not_validate, col.format)?,
+                "mode" => Format::format_opt_i64(item.mode),
+                "uid" => Format::format_opt_i64(item.uid),
+                "gid" => Format::format_opt_i64(item.gid),
+                "inode" => Format::format_opt_i64(item.inode),
+                "nlink" => Format::format_opt_i64(item.nlink),
+                "ctime" => Format::format_opt_date(item.ctime, col.format)?,
                 _ => {
                     return Err(FsPulseError::Error("Invalid column".into()));
                 }
@@ -538,6 +563,7 @@ impl ScansQuery {
                 "scan_state" => Format::format_scan_state(scan.state, col.format)?,
                 "is_hash" => Format::format_bool(scan.is_hash, col.format)?,
                 "hash_all" => Format::format_bool(scan.hash_all, col.format)?,
+                "hash_sampled" => Format::format_bool(scan.hash_sampled, col.format)?,
                 "is_val" => Format::format_bool(scan.is_val, col.format)?,
                 "file_count" => Format::format_opt_i64(scan.file_count),
                 "folder_count" => Format::format_opt_i64(scan.folder_count),
@@ -547,6 +573,7 @@ impl ScansQuery {
                 "add_count" => Format::format_opt_i64(scan.add_count),
                 "modify_count" => Format::format_opt_i64(scan.modify_count),
                 "delete_count" => Format::format_opt_i64(scan.delete_count),
+                "move_count" => Format::format_opt_i64(scan.move_count),
                 "val_unknown_count" => Format::format_opt_i64(scan.val_unknown_count),
                 "val_valid_count" => Format::format_opt_i64(scan.val_valid_count),
                 "val_invalid_count" => Format::format_opt_i64(scan.val_invalid_count),
@@ -621,10 +648,22 @@ impl VersionsQuery {
                 "last_scan_id" => Format::format_i64(version.last_scan_id),
                 "is_added" => Format::format_bool(version.is_added, col.format)?,
                 "is_deleted" => Format::format_bool(version.is_deleted, col.format)?,
+                "is_excluded" => Format::format_bool(version.is_excluded, col.format)?,
+                "is_moved" => Format::format_bool(version.is_moved, col.format)?,
+                "moved_from_item_id" => Format::format_opt_i64(version.moved_from_item_id),
+                "moved_from_path" => Format::format_opt_path(&version.moved_from_path, col.format)?,
                 "is_current" => Format::format_bool(version.is_current, col.format)?,
                 "access" => Format::format_access(version.access, col.format)?,
                 "mod_date" => Format::format_opt_date(version.mod_date, col.format)?,
                 "size" => Format::format_opt_i64(version.size),
+                "symlink_target" => Format::format_opt_string(&version.symlink_target),
+                "change_reason" => Format::format_change_reason(version.change_reason),
+                "mode" => Format::format_opt_i64(version.mode),
+                "uid" => Format::format_opt_i64(version.uid),
+                "gid" => Format::format_opt_i64(version.gid),
+                "inode" => Format::format_opt_i64(version.inode),
+                "nlink" => Format::format_opt_i64(version.nlink),
+                "ctime" => Format::format_opt_date(version.ctime, col.format)?,
                 "add_count" => Format::format_opt_i64(version.add_count),
                 "modify_count" => Format::format_opt_i64(version.modify_count),
                 "delete_count" => Format::format_opt_i64(version.delete_count),
@@ -637,6 +676,14 @@ impl VersionsQuery {
                 "val_error" => Format::format_opt_string(&version.val_error),
                 "val_reviewed_at" => Format::format_opt_date(version.val_reviewed_at, col.format)?,
                 "hash_reviewed_at" => Format::format_opt_date(version.hash_reviewed_at, col.format)?,
+                "manifest_scan_id" => Format::format_opt_i64(version.manifest_scan_id),
+                "manifest_state" => Format::format_manifest_state(version.manifest_state, col.format)?,
+                "manifest_path" => Format::format_opt_path(&version.manifest_path, col.format)?,
+                "manifest_reviewed_at" => Format::format_opt_date(version.manifest_reviewed_at, col.format)?,
+                "content_scan_id" => Format::format_opt_i64(version.content_scan_id),
+                "content_type" => Format::format_opt_string(&version.content_type),
+                "content_mismatch" => Format::format_bool(version.content_mismatch, col.format)?,
+                "content_reviewed_at" => Format::format_opt_date(version.content_reviewed_at, col.format)?,
                 _ => {
                     return Err(FsPulseError::Error("Invalid column".into()));
                 }
@@ -700,6 +747,7 @@ impl HashesQuery {
                 "last_scan_id" => Format::format_i64(hash.last_scan_id),
                 "file_hash" => Format::format_opt_string(&hash.file_hash),
                 "hash_state" => Format::format_hash_state(hash.hash_state, col.format)?,
+                "hash_algo" => Format::format_hash_algo(hash.hash_algo, col.format)?,
                 _ => {
                     return Err(FsPulseError::Error("Invalid column".into()));
                 }
@@ -723,6 +771,7 @@ struct HashesQueryRow {
     last_scan_id: i64,
     file_hash: Option<String>,
     hash_state: Option<i64>,
+    hash_algo: i64,
 }
 
 impl HashesQueryRow {
@@ -736,6 +785,99 @@ impl HashesQueryRow {
             last_scan_id: row.get(5)?,
             file_hash: Hash::opt_blob_to_hex(row.get(6)?),
             hash_state: row.get(7)?,
+            hash_algo: row.get(8)?,
+        })
+    }
+}
+
+impl Query for DuplicatesQuery {
+    fn query_impl(&self) -> &QueryImpl {
+        &self.imp
+    }
+    fn query_impl_mut(&mut self) -> &mut QueryImpl {
+        &mut self.imp
+    }
+
+    fn build_query_result(
+        &mut self,
+        sql_statement: &mut Statement,
+        sql_params: &[&dyn ToSql],
+        query_result: &mut dyn QueryResult,
+    ) -> Result<(), FsPulseError> {
+        let rows = sql_statement.query_map(sql_params, DuplicatesQueryRow::from_row)?;
+
+        query_result.prepare(&mut self.query_impl_mut().show);
+
+        for row in rows {
+            let duplicates_query_row: DuplicatesQueryRow = row?;
+            self.append_duplicates_row(&duplicates_query_row, query_result)?;
+        }
+
+        Ok(())
+    }
+}
+
+struct DuplicatesQuery {
+    imp: QueryImpl,
+}
+
+impl DuplicatesQuery {
+    pub fn append_duplicates_row(
+        &self,
+        group: &DuplicatesQueryRow,
+        query_result: &mut dyn QueryResult,
+    ) -> Result<(), FsPulseError> {
+        let mut row: Vec<String> = Vec::new();
+
+        for col in &self.show().display_cols {
+            let col_string = match col.display_col {
+                "file_hash" => Format::format_opt_string(&group.file_hash),
+                "hash_algo" => Format::format_hash_algo(group.hash_algo, col.format)?,
+                "size" => Format::format_i64(group.size),
+                "file_count" => Format::format_i64(group.file_count),
+                "root_count" => Format::format_i64(group.root_count),
+                "wasted_size" => Format::format_i64(group.wasted_size),
+                "root_id" => Format::format_string(&group.root_id),
+                "item_path" => Format::format_path(&group.item_path, col.format)?,
+                "file_extension" => Format::format_opt_string(&group.file_extension),
+                _ => {
+                    return Err(FsPulseError::Error("Invalid column".into()));
+                }
+            };
+
+            row.push(col_string);
+        }
+
+        query_result.add_row(row);
+
+        Ok(())
+    }
+}
+
+struct DuplicatesQueryRow {
+    file_hash: Option<String>,
+    hash_algo: i64,
+    size: i64,
+    file_count: i64,
+    root_count: i64,
+    wasted_size: i64,
+    root_id: String,
+    item_path: String,
+    file_extension: Option<String>,
+}
+
+impl DuplicatesQueryRow {
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+        Ok(DuplicatesQueryRow {
+            file_hash: Hash::opt_blob_to_hex(row.get(0)?),
+            hash_algo: row.get(1)?,
+            size: row.get(2)?,
+            file_count: row.get(3)?,
+            root_count: row.get(4)?,
+            wasted_size: row.get(5)?,
+            root_id: row.get(6)?,
+            item_path: row.get(7)?,
+            file_extension: row.get(8)?,
         })
     }
 }
@@ -748,6 +890,10 @@ pub struct QueryImpl {
     col_set: ColSet,
 
     filters: Vec<Box<dyn Filter>>,
+    /// Filters rendered into the template's {file_where_clause}, for domains
+    /// whose rows aggregate files and can also be narrowed file by file
+    file_filters: Vec<Box<dyn Filter>>,
+    file_filter_cols: &'static [&'static str],
     group_by: Vec<&'static str>,
     show: Show,
     order: Option<Order>,
@@ -807,12 +953,26 @@ impl QueryImpl {
         {limit_clause}
         {offset_clause}";
 
+    const DUPLICATES_SQL_QUERY: &str = concat!(
+        "SELECT {select_list}
+        FROM (",
+        crate::duplicates::duplicate_groups_sql!(),
+        ") dg
+        {where_clause}
+        {group_clause}
+        {order_clause}
+        {limit_clause}
+        {offset_clause}"
+    );
+
     fn new(sql_template: &'static str, col_set: ColSet) -> Self {
         QueryImpl {
             sql_template,
             col_set,
 
             filters: Vec::new(),
+            file_filters: Vec::new(),
+            file_filter_cols: &[],
             group_by: Vec::new(),
             show: Show::new(col_set),
             order: None,
@@ -820,6 +980,11 @@ impl QueryImpl {
             offset: None,
         }
     }
+
+    fn with_file_filter_cols(mut self, file_filter_cols: &'static [&'static str]) -> Self {
+        self.file_filter_cols = file_filter_cols;
+        self
+    }
 }
 
 struct RootsQueryRow {
@@ -845,6 +1010,12 @@ struct ItemsQueryRow {
     item_type: ItemType,
     has_validator: bool,
     do_not_validate: bool,
+    mode: Option<i64>,
+    uid: Option<i64>,
+    gid: Option<i64>,
+    inode: Option<i64>,
+    nlink: Option<i64>,
+    ctime: Option<i64>,
 }
 
 impl ItemsQueryRow {
@@ -858,6 +1029,12 @@ impl ItemsQueryRow {
             item_type: ItemType::from_i64(row.get(5)?),
             has_validator: row.get(6)?,
             do_not_validate: row.get(7)?,
+            mode: row.get(8)?,
+            uid: row.get(9)?,
+            gid: row.get(10)?,
+            inode: row.get(11)?,
+            nlink: row.get(12)?,
+            ctime: row.get(13)?,
         })
     }
 }
@@ -874,10 +1051,22 @@ struct VersionsQueryRow {
     last_scan_id: i64,
     is_added: bool,
     is_deleted: bool,
+    is_excluded: bool,
+    is_moved: bool,
+    moved_from_item_id: Option<i64>,
+    moved_from_path: Option<String>,
     is_current: bool,
     access: Access,
     mod_date: Option<i64>,
     size: Option<i64>,
+    symlink_target: Option<String>,
+    change_reason: Option<i64>,
+    mode: Option<i64>,
+    uid: Option<i64>,
+    gid: Option<i64>,
+    inode: Option<i64>,
+    nlink: Option<i64>,
+    ctime: Option<i64>,
     add_count: Option<i64>,
     modify_count: Option<i64>,
     delete_count: Option<i64>,
@@ -887,6 +1076,14 @@ struct VersionsQueryRow {
     val_error: Option<String>,
     val_reviewed_at: Option<i64>,
     hash_reviewed_at: Option<i64>,
+    manifest_scan_id: Option<i64>,
+    manifest_state: Option<i64>,
+    manifest_path: Option<String>,
+    manifest_reviewed_at: Option<i64>,
+    content_scan_id: Option<i64>,
+    content_type: Option<String>,
+    content_mismatch: bool,
+    content_reviewed_at: Option<i64>,
 }
 
 impl VersionsQueryRow {
@@ -903,19 +1100,39 @@ impl VersionsQueryRow {
             last_scan_id: row.get(8)?,
             is_added: row.get(9)?,
             is_deleted: row.get(10)?,
-            is_current: row.get(11)?,
-            access: Access::from_i64(row.get(12)?),
-            mod_date: row.get(13)?,
-            size: row.get(14)?,
-            add_count: row.get(15)?,
-            modify_count: row.get(16)?,
-            delete_count: row.get(17)?,
-            unchanged_count: row.get(18)?,
-            val_scan_id: row.get(19)?,
-            val_state: row.get(20)?,
-            val_error: row.get(21)?,
-            val_reviewed_at: row.get(22)?,
-            hash_reviewed_at: row.get(23)?,
+            is_excluded: row.get(11)?,
+            is_moved: row.get(12)?,
+            moved_from_item_id: row.get(13)?,
+            moved_from_path: row.get(14)?,
+            is_current: row.get(15)?,
+            access: Access::from_i64(row.get(16)?),
+            mod_date: row.get(17)?,
+            size: row.get(18)?,
+            symlink_target: row.get(19)?,
+            change_reason: row.get(20)?,
+            mode: row.get(21)?,
+            uid: row.get(22)?,
+            gid: row.get(23)?,
+            inode: row.get(24)?,
+            nlink: row.get(25)?,
+            ctime: row.get(26)?,
+            add_count: row.get(27)?,
+            modify_count: row.get(28)?,
+            delete_count: row.get(29)?,
+            unchanged_count: row.get(30)?,
+            val_scan_id: row.get(31)?,
+            val_state: row.get(32)?,
+            val_error: row.get(33)?,
+            val_reviewed_at: row.get(34)?,
+            hash_reviewed_at: row.get(35)?,
+            manifest_scan_id: row.get(36)?,
+            manifest_state: row.get(37)?,
+            manifest_path: row.get(38)?,
+            manifest_reviewed_at: row.get(39)?,
+            content_scan_id: row.get(40)?,
+            content_type: row.get(41)?,
+            content_mismatch: row.get(42)?,
+            content_reviewed_at: row.get(43)?,
         })
     }
 }
@@ -930,6 +1147,7 @@ pub struct ScansQueryRow {
     state: ScanState,
     is_hash: bool,
     hash_all: bool,
+    hash_sampled: bool,
     is_val: bool,
     file_count: Option<i64>,
     folder_count: Option<i64>,
@@ -939,6 +1157,7 @@ pub struct ScansQueryRow {
     add_count: Option<i64>,
     modify_count: Option<i64>,
     delete_count: Option<i64>,
+    move_count: Option<i64>,
     val_unknown_count: Option<i64>,
     val_valid_count: Option<i64>,
     val_invalid_count: Option<i64>,
@@ -962,23 +1181,25 @@ impl ScansQueryRow {
             state: ScanState::from_i64(state_i64),
             is_hash: row.get(7)?,
             hash_all: row.get(8)?,
-            is_val: row.get(9)?,
-            file_count: row.get(10)?,
-            folder_count: row.get(11)?,
-            total_size: row.get(12)?,
-            new_hash_suspect_count: row.get(13)?,
-            new_val_invalid_count: row.get(14)?,
-            add_count: row.get(15)?,
-            modify_count: row.get(16)?,
-            delete_count: row.get(17)?,
-            val_unknown_count: row.get(18)?,
-            val_valid_count: row.get(19)?,
-            val_invalid_count: row.get(20)?,
-            val_no_validator_count: row.get(21)?,
-            hash_unknown_count: row.get(22)?,
-            hash_baseline_count: row.get(23)?,
-            hash_suspect_count: row.get(24)?,
-            error: row.get(25)?,
+            hash_sampled: row.get(9)?,
+            is_val: row.get(10)?,
+            file_count: row.get(11)?,
+            folder_count: row.get(12)?,
+            total_size: row.get(13)?,
+            new_hash_suspect_count: row.get(14)?,
+            new_val_invalid_count: row.get(15)?,
+            add_count: row.get(16)?,
+            modify_count: row.get(17)?,
+            delete_count: row.get(18)?,
+            move_count: row.get(19)?,
+            val_unknown_count: row.get(20)?,
+            val_valid_count: row.get(21)?,
+            val_invalid_count: row.get(22)?,
+            val_no_validator_count: row.get(23)?,
+            hash_unknown_count: row.get(24)?,
+            hash_baseline_count: row.get(25)?,
+            hash_suspect_count: row.get(26)?,
+            error: row.get(27)?,
         })
     }
 }
@@ -1096,6 +1317,15 @@ impl QueryProcessor {
     fn build(query: &mut dyn Query, query_iter: &mut Pairs<Rule>) -> Result<(), FsPulseError> {
         for token in query_iter {
             //println!("{:?}", token.as_rule());
+            // A filter on a file-level column selects the files a row is built
+            // from, so it goes to the file filters once it has been added
+            let filter_count = query.query_impl().filters.len();
+            let file_level = token
+                .clone()
+                .into_inner()
+                .next()
+                .is_some_and(|col| query.query_impl().file_filter_cols.contains(&col.as_str()));
+
             match token.as_rule() {
                 Rule::id_filter => {
                     IdFilter::add_to_query(token, query)?;
@@ -1110,7 +1340,9 @@ impl QueryProcessor {
                 | Rule::item_type_filter
                 | Rule::val_state_filter
                 | Rule::access_filter
-                | Rule::hash_state_filter => {
+                | Rule::hash_state_filter
+                | Rule::hash_algo_filter
+                | Rule::manifest_state_filter => {
                     EnumFilter::add_enum_filter_to_query(token, query)?;
                 }
                 Rule::path_filter => {
@@ -1125,6 +1357,9 @@ impl QueryProcessor {
                 Rule::int_filter => {
                     IntFilter::add_int_filter_to_query(token, query)?;
                 }
+                Rule::change_reason_filter => {
+                    ChangeReasonFilter::add_change_reason_filter_to_query(token, query)?;
+                }
                 Rule::group_list => {
                     for group_col_pair in token.into_inner() {
                         let col_name = group_col_pair.into_inner().next().unwrap().as_str();
@@ -1150,6 +1385,12 @@ impl QueryProcessor {
                 }
                 _ => {}
             }
+
+            if file_level && query.query_impl().filters.len() > filter_count {
+                let imp = query.query_impl_mut();
+                let filter = imp.filters.pop().unwrap();
+                imp.file_filters.push(filter);
+            }
         }
 
         // Validate aggregate query constraints
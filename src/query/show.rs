@@ -1,4 +1,6 @@
-use crate::hash::HashState;
+use crate::hash::{HashAlgo, HashState};
+use crate::integrity::manifest::ManifestState;
+use crate::item_version::ChangeReason;
 use crate::items::{Access, ItemType};
 use crate::query::columns::ColAlign;
 use crate::validate::validator::ValidationState;
@@ -98,6 +100,13 @@ impl Format {
         }
     }
 
+    pub fn format_opt_path(val: &Option<String>, format: Format) -> Result<String, FsPulseError> {
+        match val {
+            Some(val) => Self::format_path(val, format),
+            None => Ok("-".into()),
+        }
+    }
+
     pub fn format_item_type(item_type: ItemType, format: Format) -> Result<String, FsPulseError> {
         match format {
             Format::Short | Format::None => Ok(item_type.short_name().to_owned()),
@@ -146,6 +155,13 @@ impl Format {
         }
     }
 
+    pub fn format_change_reason(change_reason: Option<i64>) -> String {
+        match change_reason {
+            Some(bits) => ChangeReason::from_i64(bits).names().join(", "),
+            None => "-".into(),
+        }
+    }
+
     pub fn format_hash_state(
         hash_state: Option<i64>,
         format: Format,
@@ -162,6 +178,32 @@ impl Format {
             None => Ok("-".into()),
         }
     }
+
+    pub fn format_hash_algo(hash_algo: i64, format: Format) -> Result<String, FsPulseError> {
+        let algo = HashAlgo::from_i64(hash_algo);
+        match format {
+            Format::Short => Ok(algo.short_name().to_owned()),
+            Format::Full | Format::None => Ok(algo.full_name().to_owned()),
+            _ => Err(FsPulseError::Error("Invalid hash_algo format".into())),
+        }
+    }
+
+    pub fn format_manifest_state(
+        manifest_state: Option<i64>,
+        format: Format,
+    ) -> Result<String, FsPulseError> {
+        match manifest_state {
+            Some(v) => {
+                let state = ManifestState::from_i64(v);
+                match format {
+                    Format::Short | Format::None => Ok(state.short_name().to_owned()),
+                    Format::Full => Ok(state.full_name().to_owned()),
+                    _ => Err(FsPulseError::Error("Invalid manifest_state format".into())),
+                }
+            }
+            None => Ok("-".into()),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -324,7 +366,10 @@ impl Show {
                 | Rule::item_type_show
                 | Rule::scan_state_show
                 | Rule::access_show
-                | Rule::hash_state_show => {
+                | Rule::change_reason_show
+                | Rule::hash_state_show
+                | Rule::hash_algo_show
+                | Rule::manifest_state_show => {
                     let mut path_show_parts = element.into_inner();
                     let display_col = path_show_parts.next().unwrap().as_str();
 
@@ -0,0 +1,492 @@
+use std::path::{Component, Path, PathBuf};
+
+use globset::{GlobBuilder, GlobMatcher};
+use log::warn;
+use regex::Regex;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+use crate::db::Database;
+use crate::error::FsPulseError;
+use crate::schedules::root_has_active_scan_immediate;
+
+/// Whether a matching rule hides an entry or carves an exception out of an exclusion.
+#[repr(i64)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleAction {
+    Exclude = 0,
+    Include = 1,
+}
+
+impl RuleAction {
+    pub fn as_i64(&self) -> i64 {
+        *self as i64
+    }
+
+    pub fn from_i64(value: i64) -> Self {
+        match value {
+            1 => RuleAction::Include,
+            0 => RuleAction::Exclude,
+            _ => {
+                warn!("Invalid RuleAction value in database: {}, defaulting to Exclude", value);
+                RuleAction::Exclude
+            }
+        }
+    }
+}
+
+/// Pattern syntax of a rule.
+#[repr(i64)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PatternType {
+    Glob = 0,
+    Regex = 1,
+}
+
+impl PatternType {
+    pub fn as_i64(&self) -> i64 {
+        *self as i64
+    }
+
+    pub fn from_i64(value: i64) -> Self {
+        match value {
+            1 => PatternType::Regex,
+            0 => PatternType::Glob,
+            _ => {
+                warn!("Invalid PatternType value in database: {}, defaulting to Glob", value);
+                PatternType::Glob
+            }
+        }
+    }
+}
+
+/// What a rule's pattern is matched against.
+///
+/// - `Path`: the root-relative path with `/` separators (e.g. `photos/.cache`)
+/// - `Name`: the last path segment only (e.g. `.cache`)
+#[repr(i64)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchTarget {
+    Path = 0,
+    Name = 1,
+}
+
+impl MatchTarget {
+    pub fn as_i64(&self) -> i64 {
+        *self as i64
+    }
+
+    pub fn from_i64(value: i64) -> Self {
+        match value {
+            1 => MatchTarget::Name,
+            0 => MatchTarget::Path,
+            _ => {
+                warn!("Invalid MatchTarget value in database: {}, defaulting to Path", value);
+                MatchTarget::Path
+            }
+        }
+    }
+}
+
+/// A single include/exclude rule as stored in `root_rules`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RootRule {
+    pub action: RuleAction,
+    pub pattern_type: PatternType,
+    pub target: MatchTarget,
+    pub pattern: String,
+}
+
+impl RootRule {
+    /// Load the rules for a root in evaluation order.
+    pub fn list_for_root(conn: &Connection, root_id: i64) -> Result<Vec<RootRule>, FsPulseError> {
+        let mut stmt = conn.prepare(
+            "SELECT rule_action, pattern_type, match_target, pattern
+             FROM root_rules
+             WHERE root_id = ?
+             ORDER BY rule_order, rule_id",
+        )?;
+
+        let rows = stmt.query_map([root_id], |row| {
+            Ok(RootRule {
+                action: RuleAction::from_i64(row.get(0)?),
+                pattern_type: PatternType::from_i64(row.get(1)?),
+                target: MatchTarget::from_i64(row.get(2)?),
+                pattern: row.get(3)?,
+            })
+        })?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(FsPulseError::DatabaseError)
+    }
+
+    /// Replace all rules for a root.
+    ///
+    /// Every pattern is compiled before anything is written, so an invalid rule
+    /// leaves the existing rules untouched. Rules cannot be changed while the root
+    /// has an active scan: the walk and the sweep must see the same rules.
+    pub fn replace_for_root(root_id: i64, rules: &[RootRule]) -> Result<(), FsPulseError> {
+        RootRules::compile("", rules)?;
+
+        let conn = Database::get_connection()?;
+        Database::immediate_transaction(&conn, |c| {
+            let root_exists: bool = c
+                .query_row("SELECT 1 FROM roots WHERE root_id = ?", [root_id], |_| Ok(true))
+                .unwrap_or(false);
+            if !root_exists {
+                return Err(FsPulseError::Error(format!(
+                    "Root with id {} not found",
+                    root_id
+                )));
+            }
+
+            if root_has_active_scan_immediate(c, root_id)? {
+                return Err(FsPulseError::Error(
+                    "Cannot change rules while the root has an active scan in progress".to_string(),
+                ));
+            }
+
+            c.execute("DELETE FROM root_rules WHERE root_id = ?", [root_id])?;
+
+            for (order, rule) in rules.iter().enumerate() {
+                c.execute(
+                    "INSERT INTO root_rules (root_id, rule_order, rule_action, pattern_type, match_target, pattern)
+                     VALUES (?, ?, ?, ?, ?, ?)",
+                    params![
+                        root_id,
+                        order as i64,
+                        rule.action.as_i64(),
+                        rule.pattern_type.as_i64(),
+                        rule.target.as_i64(),
+                        rule.pattern,
+                    ],
+                )?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+enum Matcher {
+    Glob(GlobMatcher),
+    Regex(Regex),
+}
+
+impl Matcher {
+    fn is_match(&self, s: &str) -> bool {
+        match self {
+            Matcher::Glob(g) => g.is_match(s),
+            Matcher::Regex(r) => r.is_match(s),
+        }
+    }
+}
+
+struct CompiledRule {
+    action: RuleAction,
+    target: MatchTarget,
+    matcher: Matcher,
+}
+
+/// Compiled rule set for a root, evaluated by the scanner.
+///
+/// An entry is excluded when at least one exclude rule matches it and no include
+/// rule does. Include rules only carve exceptions out of exclusions; they never
+/// hide anything. Because the walk does not descend into excluded directories,
+/// an include rule cannot rescue an entry whose parent directory is excluded.
+pub struct RootRules {
+    root_path: PathBuf,
+    rules: Vec<CompiledRule>,
+}
+
+impl RootRules {
+    /// Compile rules for the root at `root_path`. Fails on the first invalid pattern.
+    pub fn compile(root_path: &str, rules: &[RootRule]) -> Result<Self, FsPulseError> {
+        let mut compiled = Vec::with_capacity(rules.len());
+
+        for rule in rules {
+            if rule.pattern.trim().is_empty() {
+                return Err(FsPulseError::Error("Rule pattern cannot be empty".to_string()));
+            }
+
+            let matcher = match rule.pattern_type {
+                PatternType::Glob => {
+                    let glob = GlobBuilder::new(&rule.pattern)
+                        .literal_separator(true)
+                        .build()
+                        .map_err(|e| {
+                            FsPulseError::Error(format!(
+                                "Invalid glob pattern '{}': {}",
+                                rule.pattern, e
+                            ))
+                        })?;
+                    Matcher::Glob(glob.compile_matcher())
+                }
+                PatternType::Regex => {
+                    let re = Regex::new(&rule.pattern).map_err(|e| {
+                        FsPulseError::Error(format!(
+                            "Invalid regex pattern '{}': {}",
+                            rule.pattern, e
+                        ))
+                    })?;
+                    Matcher::Regex(re)
+                }
+            };
+
+            compiled.push(CompiledRule {
+                action: rule.action,
+                target: rule.target,
+                matcher,
+            });
+        }
+
+        Ok(RootRules {
+            root_path: PathBuf::from(root_path),
+            rules: compiled,
+        })
+    }
+
+    /// Load and compile the stored rules for a root.
+    pub fn load(conn: &Connection, root_id: i64, root_path: &str) -> Result<Self, FsPulseError> {
+        let rules = RootRule::list_for_root(conn, root_id)?;
+        RootRules::compile(root_path, &rules)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Evaluate the rules against a single entry. Ancestors are not considered —
+    /// the walk never reaches entries below an excluded directory.
+    pub fn is_excluded(&self, path: &Path) -> bool {
+        if self.rules.is_empty() {
+            return false;
+        }
+
+        match self.relative_segments(path) {
+            Some(segments) if !segments.is_empty() => {
+                self.evaluate(&segments.join("/"), segments[segments.len() - 1])
+            }
+            _ => false,
+        }
+    }
+
+    /// Evaluate the rules against an entry and each of its ancestors below the root.
+    ///
+    /// Used outside the walk (e.g. the sweep), where an item may be hidden because
+    /// a directory above it is excluded.
+    pub fn is_excluded_or_under_excluded(&self, path: &Path) -> bool {
+        if self.rules.is_empty() {
+            return false;
+        }
+
+        let Some(segments) = self.relative_segments(path) else {
+            return false;
+        };
+
+        (1..=segments.len()).any(|depth| {
+            self.evaluate(&segments[..depth].join("/"), segments[depth - 1])
+        })
+    }
+
+    fn evaluate(&self, rel_path: &str, name: &str) -> bool {
+        let mut excluded = false;
+
+        for rule in &self.rules {
+            let subject = match rule.target {
+                MatchTarget::Path => rel_path,
+                MatchTarget::Name => name,
+            };
+            if rule.matcher.is_match(subject) {
+                match rule.action {
+                    RuleAction::Include => return false,
+                    RuleAction::Exclude => excluded = true,
+                }
+            }
+        }
+
+        excluded
+    }
+
+    fn relative_segments<'p>(&self, path: &'p Path) -> Option<Vec<&'p str>> {
+        let rel = path.strip_prefix(&self.root_path).ok()?;
+        rel.components()
+            .map(|c| match c {
+                Component::Normal(s) => s.to_str(),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(action: RuleAction, pattern_type: PatternType, target: MatchTarget, pattern: &str) -> RootRule {
+        RootRule {
+            action,
+            pattern_type,
+            target,
+            pattern: pattern.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_enum_round_trip() {
+        for a in [RuleAction::Exclude, RuleAction::Include] {
+            assert_eq!(RuleAction::from_i64(a.as_i64()), a);
+        }
+        for p in [PatternType::Glob, PatternType::Regex] {
+            assert_eq!(PatternType::from_i64(p.as_i64()), p);
+        }
+        for t in [MatchTarget::Path, MatchTarget::Name] {
+            assert_eq!(MatchTarget::from_i64(t.as_i64()), t);
+        }
+    }
+
+    #[test]
+    fn test_no_rules_excludes_nothing() {
+        let rules = RootRules::compile("/root", &[]).unwrap();
+        assert!(rules.is_empty());
+        assert!(!rules.is_excluded(Path::new("/root/node_modules")));
+    }
+
+    #[test]
+    fn test_name_glob() {
+        let rules = RootRules::compile(
+            "/root",
+            &[rule(RuleAction::Exclude, PatternType::Glob, MatchTarget::Name, "node_modules")],
+        )
+        .unwrap();
+
+        assert!(rules.is_excluded(Path::new("/root/node_modules")));
+        assert!(rules.is_excluded(Path::new("/root/a/b/node_modules")));
+        assert!(!rules.is_excluded(Path::new("/root/a/node_modules_old")));
+    }
+
+    #[test]
+    fn test_path_glob_respects_separators() {
+        let rules = RootRules::compile(
+            "/root",
+            &[rule(RuleAction::Exclude, PatternType::Glob, MatchTarget::Path, "photos/*.tmp")],
+        )
+        .unwrap();
+
+        assert!(rules.is_excluded(Path::new("/root/photos/a.tmp")));
+        assert!(!rules.is_excluded(Path::new("/root/photos/sub/a.tmp")));
+        assert!(!rules.is_excluded(Path::new("/root/a.tmp")));
+
+        let rules = RootRules::compile(
+            "/root",
+            &[rule(RuleAction::Exclude, PatternType::Glob, MatchTarget::Path, "**/.cache")],
+        )
+        .unwrap();
+
+        assert!(rules.is_excluded(Path::new("/root/.cache")));
+        assert!(rules.is_excluded(Path::new("/root/x/y/.cache")));
+    }
+
+    #[test]
+    fn test_regex_rules() {
+        let rules = RootRules::compile(
+            "/root",
+            &[
+                rule(RuleAction::Exclude, PatternType::Regex, MatchTarget::Name, r"^@eaDir$"),
+                rule(RuleAction::Exclude, PatternType::Regex, MatchTarget::Path, r"^volume\d+/\.snapshot$"),
+            ],
+        )
+        .unwrap();
+
+        assert!(rules.is_excluded(Path::new("/root/photos/@eaDir")));
+        assert!(rules.is_excluded(Path::new("/root/volume1/.snapshot")));
+        assert!(!rules.is_excluded(Path::new("/root/other/.snapshot")));
+    }
+
+    #[test]
+    fn test_include_overrides_exclude() {
+        let rules = RootRules::compile(
+            "/root",
+            &[
+                rule(RuleAction::Exclude, PatternType::Glob, MatchTarget::Name, "*.log"),
+                rule(RuleAction::Include, PatternType::Glob, MatchTarget::Name, "keep.log"),
+            ],
+        )
+        .unwrap();
+
+        assert!(rules.is_excluded(Path::new("/root/a/debug.log")));
+        assert!(!rules.is_excluded(Path::new("/root/a/keep.log")));
+    }
+
+    #[test]
+    fn test_include_only_hides_nothing() {
+        let rules = RootRules::compile(
+            "/root",
+            &[rule(RuleAction::Include, PatternType::Glob, MatchTarget::Name, "*.jpg")],
+        )
+        .unwrap();
+
+        assert!(!rules.is_excluded(Path::new("/root/a.png")));
+    }
+
+    #[test]
+    fn test_ancestor_exclusion() {
+        let rules = RootRules::compile(
+            "/root",
+            &[rule(RuleAction::Exclude, PatternType::Glob, MatchTarget::Name, ".cache")],
+        )
+        .unwrap();
+
+        let deep = Path::new("/root/home/.cache/x/y.bin");
+        assert!(!rules.is_excluded(deep));
+        assert!(rules.is_excluded_or_under_excluded(deep));
+        assert!(!rules.is_excluded_or_under_excluded(Path::new("/root/home/x/y.bin")));
+    }
+
+    #[test]
+    fn test_root_itself_and_outside_paths_not_excluded() {
+        let rules = RootRules::compile(
+            "/root",
+            &[rule(RuleAction::Exclude, PatternType::Regex, MatchTarget::Path, ".*")],
+        )
+        .unwrap();
+
+        assert!(!rules.is_excluded(Path::new("/root")));
+        assert!(!rules.is_excluded(Path::new("/elsewhere/file")));
+        assert!(rules.is_excluded(Path::new("/root/file")));
+    }
+
+    #[test]
+    fn test_invalid_patterns_rejected() {
+        let err = RootRules::compile(
+            "/root",
+            &[rule(RuleAction::Exclude, PatternType::Regex, MatchTarget::Name, "(unclosed")],
+        );
+        assert!(matches!(err, Err(FsPulseError::Error(msg)) if msg.contains("Invalid regex")));
+
+        let err = RootRules::compile(
+            "/root",
+            &[rule(RuleAction::Exclude, PatternType::Glob, MatchTarget::Name, "[unclosed")],
+        );
+        assert!(matches!(err, Err(FsPulseError::Error(msg)) if msg.contains("Invalid glob")));
+
+        let err = RootRules::compile(
+            "/root",
+            &[rule(RuleAction::Exclude, PatternType::Glob, MatchTarget::Name, "  ")],
+        );
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn test_rule_json_shape() {
+        let json = r#"{"action":"exclude","pattern_type":"glob","target":"name","pattern":"node_modules"}"#;
+        let parsed: RootRule = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            parsed,
+            rule(RuleAction::Exclude, PatternType::Glob, MatchTarget::Name, "node_modules")
+        );
+    }
+}
@@ -1,11 +1,81 @@
 use std::path::{Path, PathBuf};
 use std::{env, fs};
 
+use crate::compare::RootComparison;
 use crate::db::Database;
 use crate::error::FsPulseError;
+use crate::hash::HashAlgo;
+use crate::scan_scope::ScanScope;
 use crate::schedules::root_has_active_scan_immediate;
-use rusqlite::{Connection, OptionalExtension};
-use serde::Serialize;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+/// Upper bound accepted for `RootOptions::max_symlink_depth`
+pub const MAX_SYMLINK_DEPTH_LIMIT: u32 = 40;
+
+/// Per-root scan options. Changes take effect on the next scan.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RootOptions {
+    /// Don't descend into directories on a different filesystem than the root.
+    /// Such directories are recorded with `Access::MountPoint`.
+    pub one_file_system: bool,
+    /// Descend into symlinks that point to directories. A link that leads back
+    /// to one of its own ancestors is recorded but not followed.
+    pub follow_symlinks: bool,
+    /// Maximum number of nested symlinks followed along any one path
+    pub max_symlink_depth: u32,
+    /// Watch the root for changes while the server runs, and queue a scan of
+    /// the changed directories once activity settles
+    pub watch: bool,
+    /// Algorithm used to hash the root's files. After a change, each file's
+    /// next hash is a fresh Baseline under the new algorithm.
+    pub hash_algo: HashAlgo,
+}
+
+impl Default for RootOptions {
+    fn default() -> Self {
+        Self {
+            one_file_system: false,
+            follow_symlinks: false,
+            max_symlink_depth: 8,
+            watch: false,
+            hash_algo: HashAlgo::Sha256,
+        }
+    }
+}
+
+impl RootOptions {
+    /// Columns read by `from_row`, in order
+    pub const COLUMNS: &'static str = "one_file_system, follow_symlinks, max_symlink_depth, watch, hash_algo";
+
+    /// Read the options from `COLUMNS`, starting at column index `start`
+    pub fn from_row(row: &rusqlite::Row, start: usize) -> rusqlite::Result<Self> {
+        Ok(Self {
+            one_file_system: row.get(start)?,
+            follow_symlinks: row.get(start + 1)?,
+            max_symlink_depth: row.get(start + 2)?,
+            watch: row.get(start + 3)?,
+            hash_algo: HashAlgo::from_i64(row.get(start + 4)?),
+        })
+    }
+
+    pub fn validate(&self) -> Result<(), FsPulseError> {
+        if !(1..=MAX_SYMLINK_DEPTH_LIMIT).contains(&self.max_symlink_depth) {
+            return Err(FsPulseError::Error(format!(
+                "max_symlink_depth must be between 1 and {}",
+                MAX_SYMLINK_DEPTH_LIMIT
+            )));
+        }
+        if !self.hash_algo.is_root_algo() {
+            return Err(FsPulseError::Error(format!(
+                "hash_algo must be one of sha256, blake3, xxh3 or sha512 (got {})",
+                self.hash_algo.short_name()
+            )));
+        }
+        Ok(())
+    }
+}
 
 #[derive(Clone, Debug, Default, Serialize)]
 pub struct Root {
@@ -13,17 +83,20 @@ pub struct Root {
     root_id: i64,
     #[serde(rename = "path")]
     root_path: String,
+    #[serde(flatten)]
+    options: RootOptions,
 }
 
 impl Root {
     pub fn get_by_id(conn: &Connection, root_id: i64) -> Result<Option<Self>, FsPulseError> {
         conn.query_row(
-            "SELECT root_path FROM roots WHERE root_id = ?",
+            &format!("SELECT root_path, {} FROM roots WHERE root_id = ?", RootOptions::COLUMNS),
             [root_id],
             |row| {
                 Ok(Root {
                     root_id,
                     root_path: row.get(0)?,
+                    options: RootOptions::from_row(row, 1)?,
                 })
             },
         )
@@ -31,23 +104,75 @@ impl Root {
         .map_err(FsPulseError::DatabaseError)
     }
 
-    pub fn try_create(root_path: &str) -> Result<Self, FsPulseError> {
+    pub fn try_create(root_path: &str, options: &RootOptions) -> Result<Self, FsPulseError> {
+        options.validate()?;
         let path_buf = Root::validate_and_canonicalize_path(root_path)?;
         let canon_root_path = path_buf.to_string_lossy().to_string();
         let conn = Database::get_connection()?;
-        Root::create(&conn, &canon_root_path)
+        Root::create(&conn, &canon_root_path, options)
     }
 
-    pub fn create(conn: &Connection, root_path: &str) -> Result<Self, FsPulseError> {
+    pub fn create(
+        conn: &Connection,
+        root_path: &str,
+        options: &RootOptions,
+    ) -> Result<Self, FsPulseError> {
         let root_id: i64 = conn.query_row(
-            "INSERT INTO roots (root_path) VALUES (?) RETURNING root_id",
-            [root_path],
+            "INSERT INTO roots (root_path, one_file_system, follow_symlinks, max_symlink_depth, watch, hash_algo)
+             VALUES (?, ?, ?, ?, ?, ?) RETURNING root_id",
+            params![
+                root_path,
+                options.one_file_system,
+                options.follow_symlinks,
+                options.max_symlink_depth,
+                options.watch,
+                options.hash_algo.as_i64()
+            ],
             |row| row.get(0),
         )?;
 
         Ok(Root {
             root_id,
             root_path: root_path.to_owned(),
+            options: options.clone(),
+        })
+    }
+
+    /// Replace a root's scan options. Not allowed while the root has an active scan,
+    /// since a resumed scan must walk with the options it started with.
+    pub fn update_options(root_id: i64, options: &RootOptions) -> Result<(), FsPulseError> {
+        options.validate()?;
+        let conn = Database::get_connection()?;
+        Database::immediate_transaction(&conn, |c| {
+            if root_has_active_scan_immediate(c, root_id)? {
+                return Err(FsPulseError::Error(
+                    "Cannot change options while the root has an active scan in progress"
+                        .to_string(),
+                ));
+            }
+
+            let rows_affected = c.execute(
+                "UPDATE roots SET one_file_system = ?, follow_symlinks = ?, max_symlink_depth = ?, watch = ?,
+                     hash_algo = ?
+                 WHERE root_id = ?",
+                params![
+                    options.one_file_system,
+                    options.follow_symlinks,
+                    options.max_symlink_depth,
+                    options.watch,
+                    options.hash_algo.as_i64(),
+                    root_id
+                ],
+            )?;
+
+            if rows_affected == 0 {
+                return Err(FsPulseError::Error(format!(
+                    "Root with id {} not found",
+                    root_id
+                )));
+            }
+
+            Ok(())
         })
     }
 
@@ -59,6 +184,19 @@ impl Root {
         &self.root_path
     }
 
+    pub fn options(&self) -> &RootOptions {
+        &self.options
+    }
+
+    /// The absolute path of a root-relative directory, or of the root itself
+    /// when `subpath` is None or names the root
+    pub fn subpath_dir(&self, subpath: Option<&str>) -> Result<String, FsPulseError> {
+        match subpath.map(ScanScope::normalize_subpath).transpose()?.flatten() {
+            Some(subpath) => Ok(Path::new(&self.root_path).join(subpath).to_string_lossy().into_owned()),
+            None => Ok(self.root_path.clone()),
+        }
+    }
+
     /// Delete a root and all associated data (scans, items, versions, schedules).
     /// This operation is performed within a transaction to ensure atomicity.
     /// Returns Ok(()) if successful, or an error if the root doesn't exist, has an active scan, or deletion fails.
@@ -75,6 +213,10 @@ impl Root {
             // Delete all tasks and schedules for this root
             c.execute("DELETE FROM tasks WHERE root_id = ?", [root_id])?;
             c.execute("DELETE FROM scan_schedules WHERE root_id = ?", [root_id])?;
+            c.execute("DELETE FROM root_rules WHERE root_id = ?", [root_id])?;
+
+            // Comparison reports involving this root, on either side
+            RootComparison::delete_for_root(c, root_id)?;
 
             // Delete in order based on foreign key constraints:
             // 1. hash_versions (references item_version_id from item_versions)
@@ -94,6 +236,14 @@ impl Root {
                 [root_id],
             )?;
 
+            // Delete sampled hash versions for this root
+            c.execute(
+                "DELETE FROM sampled_hash_versions WHERE item_id IN (
+                    SELECT item_id FROM items WHERE root_id = ?
+                )",
+                [root_id],
+            )?;
+
             // Delete item versions for this root
             c.execute(
                 "DELETE FROM item_versions WHERE item_id IN (
@@ -177,10 +327,22 @@ mod tests {
         let root = Root {
             root_id: 123,
             root_path: "/test/path".to_string(),
+            options: RootOptions {
+                one_file_system: true,
+                follow_symlinks: true,
+                max_symlink_depth: 4,
+                watch: true,
+                hash_algo: HashAlgo::Blake3,
+            },
         };
 
         assert_eq!(root.root_id(), 123);
         assert_eq!(root.root_path(), "/test/path");
+        assert!(root.options().one_file_system);
+        assert!(root.options().follow_symlinks);
+        assert_eq!(root.options().max_symlink_depth, 4);
+        assert!(root.options().watch);
+        assert_eq!(root.options().hash_algo, HashAlgo::Blake3);
     }
 
     #[test]
@@ -189,6 +351,47 @@ mod tests {
 
         assert_eq!(root.root_id(), 0);
         assert_eq!(root.root_path(), "");
+        assert_eq!(root.options(), &RootOptions::default());
+    }
+
+    #[test]
+    fn test_root_options_deserialize_defaults() {
+        let options: RootOptions = serde_json::from_str("{}").unwrap();
+        assert!(!options.one_file_system);
+        assert!(!options.follow_symlinks);
+        assert_eq!(options.max_symlink_depth, 8);
+        assert!(!options.watch);
+        assert_eq!(options.hash_algo, HashAlgo::Sha256);
+
+        let options: RootOptions =
+            serde_json::from_str(r#"{"one_file_system":true,"follow_symlinks":true}"#).unwrap();
+        assert!(options.one_file_system);
+        assert!(options.follow_symlinks);
+        assert_eq!(options.max_symlink_depth, 8);
+    }
+
+    #[test]
+    fn test_root_options_validate_symlink_depth() {
+        let mut options = RootOptions::default();
+        assert!(options.validate().is_ok());
+
+        options.max_symlink_depth = 0;
+        assert!(options.validate().is_err());
+
+        options.max_symlink_depth = MAX_SYMLINK_DEPTH_LIMIT;
+        assert!(options.validate().is_ok());
+
+        options.max_symlink_depth = MAX_SYMLINK_DEPTH_LIMIT + 1;
+        assert!(options.validate().is_err());
+    }
+
+    #[test]
+    fn test_root_options_validate_hash_algo() {
+        let mut options = RootOptions { hash_algo: HashAlgo::Sha512, ..RootOptions::default() };
+        assert!(options.validate().is_ok());
+
+        options.hash_algo = HashAlgo::Md5;
+        assert!(options.validate().is_err());
     }
 
     #[test]
@@ -0,0 +1,163 @@
+use std::fmt;
+
+use chrono::{Duration, Local, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+
+/// A daily range of local time during which queued work may run.
+///
+/// `start` and `end` are 'HH:MM' (24-hour). A window whose end is earlier than
+/// its start runs past midnight, so 22:00-06:00 is open overnight. The window
+/// includes its start and excludes its end.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunWindow {
+    pub start: String,
+    pub end: String,
+}
+
+impl RunWindow {
+    /// Build a window from its stored columns. None unless both are set.
+    pub fn from_columns(start: Option<String>, end: Option<String>) -> Option<Self> {
+        Some(Self { start: start?, end: end? })
+    }
+
+    /// Parse the 'HH:MM-HH:MM' form of the global setting
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let (start, end) = value.split_once('-').ok_or_else(|| {
+            format!("run window must be in HH:MM-HH:MM format, got: {}", value)
+        })?;
+        let window = Self {
+            start: start.trim().to_string(),
+            end: end.trim().to_string(),
+        };
+        window.validate()?;
+        Ok(window)
+    }
+
+    /// The global run window, or None when the setting is empty
+    pub fn global() -> Option<Self> {
+        let value = Config::get_tasks_run_window();
+        if value.is_empty() {
+            return None;
+        }
+        // Validated when the setting was loaded or changed
+        Self::parse(&value).ok()
+    }
+
+    /// Check both times are 'HH:MM' and differ
+    pub fn validate(&self) -> Result<(), String> {
+        let start = parse_time(&self.start)?;
+        let end = parse_time(&self.end)?;
+        if start == end {
+            return Err(format!("run window start and end must differ, got: {}", self));
+        }
+        Ok(())
+    }
+
+    fn bounds(&self) -> Option<(NaiveTime, NaiveTime)> {
+        Some((parse_time(&self.start).ok()?, parse_time(&self.end).ok()?))
+    }
+
+    /// Whether the window is open at a Unix timestamp
+    pub fn is_open_at(&self, time: i64) -> bool {
+        let (Some((start, end)), Some(local)) = (self.bounds(), Local.timestamp_opt(time, 0).single())
+        else {
+            return true;
+        };
+        let now = local.time();
+
+        if start < end {
+            start <= now && now < end
+        } else {
+            now >= start || now < end
+        }
+    }
+
+    /// When the window next opens: `time` itself if it's open then, otherwise
+    /// the window's next start
+    pub fn next_open_at(&self, time: i64) -> i64 {
+        if self.is_open_at(time) {
+            return time;
+        }
+        let (Some((start, _)), Some(local)) = (self.bounds(), Local.timestamp_opt(time, 0).single())
+        else {
+            return time;
+        };
+
+        let mut next = local.date_naive().and_time(start);
+        if next <= local.naive_local() {
+            next += Duration::days(1);
+        }
+        // A start skipped by a DST change opens the window an hour late
+        Local
+            .from_local_datetime(&next)
+            .earliest()
+            .or_else(|| Local.from_local_datetime(&(next + Duration::hours(1))).earliest())
+            .map(|dt| dt.timestamp())
+            .unwrap_or(time)
+    }
+}
+
+impl fmt::Display for RunWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+fn parse_time(time: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(time, "%H:%M")
+        .map_err(|_| format!("run window times must be in HH:MM format, got: {}", time))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(hour: u32, minute: u32) -> i64 {
+        Local.with_ymd_and_hms(2025, 1, 15, hour, minute, 0).unwrap().timestamp()
+    }
+
+    #[test]
+    fn test_parse_and_validate() {
+        let window = RunWindow::parse("22:00-06:30").unwrap();
+        assert_eq!(window, RunWindow { start: "22:00".into(), end: "06:30".into() });
+        assert_eq!(window.to_string(), "22:00-06:30");
+        assert_eq!(RunWindow::parse(" 01:00 - 05:00 ").unwrap().start, "01:00");
+
+        assert!(RunWindow::parse("22:00").is_err());
+        assert!(RunWindow::parse("25:00-06:00").is_err());
+        assert!(RunWindow::parse("09:00-09:00").is_err());
+
+        assert_eq!(RunWindow::from_columns(Some("01:00".into()), None), None);
+    }
+
+    #[test]
+    fn test_is_open_at() {
+        let day = RunWindow::parse("09:00-17:00").unwrap();
+        assert!(!day.is_open_at(local(8, 59)));
+        assert!(day.is_open_at(local(9, 0)));
+        assert!(day.is_open_at(local(16, 59)));
+        assert!(!day.is_open_at(local(17, 0)));
+
+        let night = RunWindow::parse("22:00-06:00").unwrap();
+        assert!(night.is_open_at(local(23, 0)));
+        assert!(night.is_open_at(local(5, 59)));
+        assert!(!night.is_open_at(local(6, 0)));
+        assert!(!night.is_open_at(local(12, 0)));
+    }
+
+    #[test]
+    fn test_next_open_at() {
+        let night = RunWindow::parse("22:00-06:00").unwrap();
+        assert_eq!(night.next_open_at(local(23, 0)), local(23, 0));
+        assert_eq!(night.next_open_at(local(12, 0)), local(22, 0));
+
+        // Already past today's start: opens tomorrow
+        let early = RunWindow::parse("01:00-05:00").unwrap();
+        assert_eq!(
+            early.next_open_at(local(9, 0)),
+            Local.with_ymd_and_hms(2025, 1, 16, 1, 0, 0).unwrap().timestamp()
+        );
+    }
+}
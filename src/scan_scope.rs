@@ -0,0 +1,277 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Component, Path, PathBuf, MAIN_SEPARATOR, MAIN_SEPARATOR_STR};
+
+use crate::error::FsPulseError;
+use crate::roots::RootOptions;
+use crate::walker::device_id;
+
+/// The part of a root covered by a scan.
+///
+/// An empty scope is the whole root. Otherwise the scan walks only the listed
+/// directories (absolute, none nested inside another). Everything outside them
+/// is carried forward unchanged, and the directories between them and the root
+/// have their sizes recomputed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScanScope {
+    dirs: Vec<PathBuf>,
+}
+
+impl ScanScope {
+    /// Check that `subpath` is relative to the root and stays inside it.
+    pub fn validate_subpath(subpath: &str) -> Result<PathBuf, FsPulseError> {
+        let mut relative = PathBuf::new();
+        for component in Path::new(subpath).components() {
+            match component {
+                Component::Normal(name) => relative.push(name),
+                Component::CurDir => {}
+                _ => {
+                    return Err(FsPulseError::Error(format!(
+                        "Invalid subpath '{}': must be relative to the root and may not contain '..'",
+                        subpath
+                    )))
+                }
+            }
+        }
+        Ok(relative)
+    }
+
+    /// Validate a subpath supplied for a scan and put it in the form stored in
+    /// scan settings. None means the subpath names the root itself.
+    pub fn normalize_subpath(subpath: &str) -> Result<Option<String>, FsPulseError> {
+        let relative = Self::validate_subpath(subpath)?;
+        if relative.as_os_str().is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(relative.to_string_lossy().into_owned()))
+        }
+    }
+
+    /// Resolve root-relative subpaths to the directories a scan will walk.
+    ///
+    /// Each subpath is cut back to its deepest ancestor that is a real directory
+    /// the scan would descend into: a missing path, a file, or a symlink resolves
+    /// to the directory containing it, as does a directory on another filesystem
+    /// under a one-file-system root. Nested results are dropped, and a subpath
+    /// that resolves to the root makes the scope the whole root.
+    pub fn resolve(
+        root_path: &str,
+        options: &RootOptions,
+        subpaths: &[String],
+    ) -> Result<Self, FsPulseError> {
+        let root = Path::new(root_path);
+        let root_dev = if options.one_file_system {
+            fs::metadata(root).ok().as_ref().and_then(device_id)
+        } else {
+            None
+        };
+
+        let mut dirs = Vec::with_capacity(subpaths.len());
+        for subpath in subpaths {
+            let relative = Self::validate_subpath(subpath)?;
+
+            let mut dir = root.to_path_buf();
+            for component in relative.components() {
+                let next = dir.join(component);
+                let walked = match fs::symlink_metadata(&next) {
+                    Ok(m) => m.is_dir() && root_dev.is_none_or(|dev| device_id(&m) == Some(dev)),
+                    Err(_) => false,
+                };
+                if !walked {
+                    break;
+                }
+                dir = next;
+            }
+
+            if dir == root {
+                return Ok(Self::default());
+            }
+            dirs.push(dir);
+        }
+
+        Ok(Self { dirs: Self::outermost(dirs) })
+    }
+
+    /// Rebuild a scope recorded in task_state
+    pub fn from_recorded(dirs: &[String]) -> Self {
+        Self { dirs: dirs.iter().map(PathBuf::from).collect() }
+    }
+
+    /// The scope in the form recorded in task_state
+    pub fn to_recorded(&self) -> Vec<String> {
+        self.dirs.iter().map(|d| d.to_string_lossy().into_owned()).collect()
+    }
+
+    pub fn is_whole_root(&self) -> bool {
+        self.dirs.is_empty()
+    }
+
+    pub fn dirs(&self) -> &[PathBuf] {
+        &self.dirs
+    }
+
+    /// Directories strictly between the root and the scope's directories,
+    /// deepest first, so each comes after all of its scoped descendants.
+    pub fn ancestors(&self, root_path: &Path) -> Vec<PathBuf> {
+        let mut ancestors = BTreeSet::new();
+        for dir in &self.dirs {
+            for ancestor in dir.ancestors().skip(1) {
+                if ancestor == root_path || !ancestor.starts_with(root_path) {
+                    break;
+                }
+                ancestors.insert(ancestor.to_path_buf());
+            }
+        }
+
+        let mut ancestors: Vec<PathBuf> = ancestors.into_iter().collect();
+        ancestors.sort_by_key(|a| std::cmp::Reverse(a.components().count()));
+        ancestors
+    }
+
+    /// SQL condition that is true when `column` holds a path inside the scope,
+    /// or None for the whole root. Paths are embedded as quoted literals, since
+    /// the number of directories varies.
+    pub fn sql_filter(&self, column: &str) -> Option<String> {
+        if self.is_whole_root() {
+            return None;
+        }
+
+        let terms: Vec<String> = self
+            .dirs
+            .iter()
+            .map(|dir| {
+                let dir = dir.to_string_lossy();
+                // Descendants sort between "dir/" and "dir" + the character after the separator
+                format!(
+                    "{column} = {} OR ({column} >= {} AND {column} < {})",
+                    Self::quote(&dir),
+                    Self::quote(&format!("{}{}", dir, MAIN_SEPARATOR_STR)),
+                    Self::quote(&format!("{}{}", dir, char::from(MAIN_SEPARATOR as u8 + 1))),
+                )
+            })
+            .collect();
+        Some(format!("({})", terms.join(" OR ")))
+    }
+
+    fn quote(value: &str) -> String {
+        format!("'{}'", value.replace('\'', "''"))
+    }
+
+    /// Drop directories that lie inside another one in the list
+    fn outermost(mut dirs: Vec<PathBuf>) -> Vec<PathBuf> {
+        // Path ordering is component-wise, so a directory sorts directly before its descendants
+        dirs.sort();
+        let mut outermost: Vec<PathBuf> = Vec::with_capacity(dirs.len());
+        for dir in dirs {
+            if outermost.last().is_some_and(|last| dir.starts_with(last)) {
+                continue;
+            }
+            outermost.push(dir);
+        }
+        outermost
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+    use tempfile::TempDir;
+
+    fn tree() -> TempDir {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir_all(temp.path().join("photos/2024/june")).unwrap();
+        fs::create_dir_all(temp.path().join("docs")).unwrap();
+        fs::write(temp.path().join("docs/notes.txt"), "notes").unwrap();
+        temp
+    }
+
+    fn resolve(root: &Path, subpaths: &[&str]) -> ScanScope {
+        let subpaths: Vec<String> = subpaths.iter().map(|s| s.to_string()).collect();
+        ScanScope::resolve(&root.to_string_lossy(), &RootOptions::default(), &subpaths).unwrap()
+    }
+
+    #[test]
+    fn test_resolve_existing_dirs() {
+        let temp = tree();
+        let scope = resolve(temp.path(), &["photos/2024", "docs"]);
+        assert_eq!(scope.dirs(), &[temp.path().join("docs"), temp.path().join("photos/2024")]);
+    }
+
+    #[test]
+    fn test_resolve_missing_or_file_uses_parent() {
+        let temp = tree();
+        let scope = resolve(temp.path(), &["photos/2024/july/day1", "docs/notes.txt"]);
+        assert_eq!(scope.dirs(), &[temp.path().join("docs"), temp.path().join("photos/2024")]);
+    }
+
+    #[test]
+    fn test_resolve_drops_nested() {
+        let temp = tree();
+        let scope = resolve(temp.path(), &["photos/2024/june", "photos", "photos/2024"]);
+        assert_eq!(scope.dirs(), &[temp.path().join("photos")]);
+    }
+
+    #[test]
+    fn test_resolve_root_is_whole_root() {
+        let temp = tree();
+        assert!(resolve(temp.path(), &["docs", "missing"]).is_whole_root());
+        assert!(resolve(temp.path(), &["."]).is_whole_root());
+        assert!(resolve(temp.path(), &[]).is_whole_root());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_stops_at_symlink() {
+        let temp = tree();
+        std::os::unix::fs::symlink(temp.path().join("photos"), temp.path().join("docs/link")).unwrap();
+        let scope = resolve(temp.path(), &["docs/link/2024"]);
+        assert_eq!(scope.dirs(), &[temp.path().join("docs")]);
+    }
+
+    #[test]
+    fn test_validate_subpath_rejects_escapes() {
+        assert!(ScanScope::validate_subpath("../etc").is_err());
+        assert!(ScanScope::validate_subpath("a/../../b").is_err());
+        assert!(ScanScope::validate_subpath("/etc").is_err());
+        assert_eq!(ScanScope::validate_subpath("./a/b").unwrap(), PathBuf::from("a/b"));
+    }
+
+    #[test]
+    fn test_normalize_subpath() {
+        assert_eq!(ScanScope::normalize_subpath("Photos//2019/").unwrap(), Some("Photos/2019".to_string()));
+        assert_eq!(ScanScope::normalize_subpath("./").unwrap(), None);
+        assert_eq!(ScanScope::normalize_subpath("").unwrap(), None);
+        assert!(ScanScope::normalize_subpath("../x").is_err());
+    }
+
+    #[test]
+    fn test_ancestors_deepest_first() {
+        let scope = ScanScope::from_recorded(&["/r/a/b/c".to_string(), "/r/a/d".to_string()]);
+        assert_eq!(
+            scope.ancestors(Path::new("/r")),
+            vec![PathBuf::from("/r/a/b"), PathBuf::from("/r/a")]
+        );
+    }
+
+    #[test]
+    fn test_sql_filter_matches_scope() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE TABLE t (p TEXT)").unwrap();
+        for p in ["/r/it's", "/r/it's/x", "/r/it's0", "/r/it'sx", "/r/other", "/r"] {
+            conn.execute("INSERT INTO t VALUES (?)", [p]).unwrap();
+        }
+
+        let scope = ScanScope::from_recorded(&["/r/it's".to_string()]);
+        let filter = scope.sql_filter("p").unwrap();
+        let mut stmt = conn.prepare(&format!("SELECT p FROM t WHERE {} ORDER BY p", filter)).unwrap();
+        let matched: Vec<String> = stmt
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(matched, vec!["/r/it's", "/r/it's/x"]);
+
+        assert!(ScanScope::default().sql_filter("p").is_none());
+    }
+}
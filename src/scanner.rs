@@ -17,34 +17,46 @@
 // 4. Completed
 // 5. Stopped
 
+use crate::config::Config;
+use crate::ignore_files::{IgnoreFile, IgnoreFiles};
 use crate::item_identity::{Access, ExistingItem, ItemIdentity, ItemType};
-use crate::item_version::ItemVersion;
+use crate::item_version::{ChangeReason, ItemVersion, UnixMetadata};
+use crate::moves::MoveDetector;
+use crate::root_rules::RootRules;
 use crate::roots::Root;
+use crate::scan_scope::ScanScope;
 use crate::scans::ScanState;
-use crate::task::TaskProgress;
-use crate::undo_log::UndoLog;
+use crate::task::{ScanTaskState, TaskProgress};
+use crate::undo_log::{UndoLog, UndoLogType};
 use crate::utils::Utils;
+use crate::validate::registry::ValidatorRegistry;
 use crate::validate::validator;
+use crate::walker::{DirStatus, WalkEvent, Walker};
 use crate::{db::Database, error::FsPulseError, scans::Scan};
 
-use log::{error, info, trace, Level};
+use crossbeam_channel::{bounded, Receiver};
+use log::{error, info, Level};
 use logging_timer::timer;
 use rusqlite::{params, Connection, OptionalExtension};
 
-use std::fs::Metadata;
-use std::io::ErrorKind;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs::{self, Metadata};
 use std::path::{Path, PathBuf};
 use std::path::MAIN_SEPARATOR_STR;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::thread;
 use std::time::{Duration, Instant};
-use std::fs;
 
 pub struct Scanner {}
 
 /// Batch size for database write operations during scanning
 const SCAN_BATCH_SIZE: usize = 2000;
 
+/// Maximum number of directory listings queued between the walker threads and
+/// the DB writer
+const WALK_CHANNEL_SIZE: usize = 256;
+
 /// A pending folder count write, collected during the recursive walk and
 /// applied in batched transactions afterward.
 struct FolderCountWrite {
@@ -55,10 +67,54 @@ struct FolderCountWrite {
     unchanged: i64,
 }
 
+/// A directory seen by the writer whose own item can't be written until its
+/// subdirectories are done (its size is the sum of everything below it).
+struct PendingDir {
+    /// None for the root, which is not stored as an item
+    parent: Option<PathBuf>,
+    metadata: Option<Metadata>,
+    size: i64,
+    subdirs_remaining: usize,
+    /// Whether this directory's own listing has been received
+    listed: bool,
+    /// Ok, or ReadError if the directory's contents couldn't be listed
+    dir_access: Access,
+    /// A followed symlink. Its item was written when it was seen, as a symlink.
+    followed_link: bool,
+}
+
+impl PendingDir {
+    fn new(parent: Option<PathBuf>, metadata: Option<Metadata>) -> Self {
+        Self {
+            parent,
+            metadata,
+            size: 0,
+            subdirs_remaining: 0,
+            listed: false,
+            dir_access: Access::Ok,
+            followed_link: false,
+        }
+    }
+
+    fn followed_link(parent: PathBuf) -> Self {
+        Self {
+            followed_link: true,
+            ..Self::new(Some(parent), None)
+        }
+    }
+}
+
 /// Context passed through recursive directory scanning to avoid large parameter lists
 struct ScanContext<'a> {
     conn: &'a Connection,
     scan: &'a Scan,
+    task_id: i64,
+    task_state: &'a mut ScanTaskState,
+    /// Topmost directories whose entire subtree has been written in this scan.
+    /// Persisted to task_state on each flush as the Phase 1 resume checkpoint.
+    completed_dirs: BTreeSet<String>,
+    /// Whether task_state has changed since it was last persisted
+    task_state_dirty: bool,
     task_progress: &'a Arc<TaskProgress>,
     interrupt_token: &'a Arc<AtomicBool>,
     batch_count: usize,
@@ -70,12 +126,19 @@ impl<'a> ScanContext<'a> {
     fn new(
         conn: &'a Connection,
         scan: &'a Scan,
+        task_id: i64,
+        task_state: &'a mut ScanTaskState,
         task_progress: &'a Arc<TaskProgress>,
         interrupt_token: &'a Arc<AtomicBool>,
     ) -> Self {
+        let completed_dirs = task_state.completed_dirs.iter().cloned().collect();
         Self {
             conn,
             scan,
+            task_id,
+            task_state,
+            completed_dirs,
+            task_state_dirty: false,
             task_progress,
             interrupt_token,
             batch_count: 0,
@@ -101,6 +164,39 @@ impl<'a> ScanContext<'a> {
         ));
     }
 
+    /// Record an ignore file the walker read for the first time in this scan. It is
+    /// persisted to task_state on the next flush, so the record commits together
+    /// with the items it affected.
+    fn record_ignore_file(&mut self, file: IgnoreFile) {
+        self.task_state.ignore_files.push(file);
+        self.task_state_dirty = true;
+    }
+
+    /// Checkpoint a directory whose entire subtree has now been written. Its
+    /// completed subdirectories are dropped from the checkpoint, since a resumed
+    /// walk that skips this directory never reaches them.
+    fn mark_dir_completed(&mut self, dir: &Path) {
+        let dir = dir.to_string_lossy().into_owned();
+        let prefix = format!("{}{}", dir, MAIN_SEPARATOR_STR);
+        let descendants: Vec<String> = self
+            .completed_dirs
+            .range(prefix.clone()..)
+            .take_while(|d| d.starts_with(&prefix))
+            .cloned()
+            .collect();
+        for d in descendants {
+            self.completed_dirs.remove(&d);
+        }
+        self.completed_dirs.insert(dir);
+        self.task_state_dirty = true;
+    }
+
+    /// The walk is complete: checkpoints are no longer needed.
+    fn clear_completed_dirs(&mut self) {
+        self.completed_dirs.clear();
+        self.task_state_dirty = true;
+    }
+
     fn execute_batch_write<F, T>(&mut self, f: F) -> Result<T, FsPulseError>
     where
         F: FnOnce(&Connection) -> Result<T, FsPulseError>,
@@ -125,6 +221,24 @@ impl<'a> ScanContext<'a> {
 
     fn flush(&mut self) -> Result<(), FsPulseError> {
         let _tmr = timer!(Level::Trace; "ScanContext.flush", "{}", self.batch_count);
+
+        // Persist task_state in the same transaction as the writes it describes, so a
+        // resumed scan never skips a directory whose items were rolled back
+        if self.task_state_dirty {
+            if self.batch_count == 0 {
+                self.conn
+                    .execute("BEGIN IMMEDIATE", [])
+                    .map_err(FsPulseError::DatabaseError)?;
+            }
+            self.task_state.completed_dirs = self.completed_dirs.iter().cloned().collect();
+            self.conn.execute(
+                "UPDATE tasks SET task_state = ? WHERE task_id = ? AND status = 1",
+                params![self.task_state.to_json()?, self.task_id],
+            )?;
+            self.batch_count += 1;
+            self.task_state_dirty = false;
+        }
+
         if self.batch_count > 0 {
             self.conn
                 .execute("COMMIT", [])
@@ -154,7 +268,7 @@ impl Scanner {
         scan: &mut Scan,
         root: &Root,
         task_id: i64,
-        mut initial_task_state: Option<String>,
+        initial_task_state: Option<String>,
         task_progress: Arc<TaskProgress>,
         interrupt_token: Arc<AtomicBool>,
     ) -> Result<(), FsPulseError> {
@@ -180,6 +294,8 @@ impl Scanner {
             UndoLog::warn_and_clear_if_not_empty(&conn)?;
         }
 
+        let mut task_state = ScanTaskState::from_task_state(initial_task_state.as_deref())?;
+
         // Loop through all states, even if resuming, to allow progress updates
         let mut loop_state = ScanState::Scanning;
 
@@ -198,21 +314,21 @@ impl Scanner {
                 ScanState::Scanning => {
                     task_progress.set_phase("Phase 1 of 4: Scanning");
                     if scan.state() == ScanState::Scanning {
-                        Scanner::do_state_scanning(root, scan, task_progress.clone(), &interrupt_token)?;
+                        Scanner::do_state_scanning(root, scan, task_id, &mut task_state, task_progress.clone(), &interrupt_token)?;
                     }
                     loop_state = ScanState::Sweeping;
                 }
                 ScanState::Sweeping => {
                     task_progress.set_phase("Phase 2 of 4: Sweeping");
                     if scan.state() == ScanState::Sweeping {
-                        Scanner::do_state_sweeping(scan, task_progress.clone(), &interrupt_token)?;
+                        Scanner::do_state_sweeping(root, scan, &task_state, task_progress.clone(), &interrupt_token)?;
                     }
                     loop_state = ScanState::AnalyzingFiles;
                 }
                 ScanState::AnalyzingFiles => {
                     task_progress.set_phase("Phase 3 of 4: Analyzing Files");
                     if scan.state() == ScanState::AnalyzingFiles {
-                        crate::integrity::analysis::run_analysis_phase(scan, task_id, initial_task_state.take(), task_progress.clone(), &interrupt_token)?;
+                        crate::integrity::analysis::run_analysis_phase(scan, task_id, Some(task_state.to_json()?), task_progress.clone(), &interrupt_token)?;
                     }
                     loop_state = ScanState::AnalyzingScan;
                 }
@@ -234,214 +350,319 @@ impl Scanner {
         Ok(())
     }
 
-    fn scan_directory_recursive(ctx: &mut ScanContext, path: &Path) -> Result<i64, FsPulseError> {
-        let _tmr = timer!(Level::Trace; "scan_directory_recursive", "{}", path.display());
+    /// Consume directory listings from the walker and write their items.
+    ///
+    /// Folder sizes are aggregated bottom-up: a directory's own item is written once
+    /// all of its subdirectories have been written, so its size covers everything
+    /// below it. The walker sends a parent's listing before any child's, so every
+    /// listing arrives for a directory already registered here. `root_path` is the
+    /// directory the walk started from; its own item is not written here. Returns
+    /// its total size.
+    fn write_walk_results(
+        ctx: &mut ScanContext,
+        root_path: &Path,
+        receiver: Receiver<WalkEvent>,
+    ) -> Result<i64, FsPulseError> {
+        let mut pending: HashMap<PathBuf, PendingDir> = HashMap::new();
+        pending.insert(root_path.to_path_buf(), PendingDir::new(None, None));
+        let mut root_size = None;
 
-        Scanner::check_interrupted(ctx.interrupt_token)?;
+        for event in receiver {
+            Scanner::check_interrupted(ctx.interrupt_token)?;
 
-        ctx.increment_directories_scanned();
+            let listing = match event {
+                WalkEvent::Listing(listing) => listing,
+                WalkEvent::Error(e) => return Err(e),
+            };
 
-        // Try to read directory contents, handling access errors
-        let items = match fs::read_dir(path) {
-            Ok(items) => items,
-            Err(e) => {
-                match e.kind() {
-                    ErrorKind::PermissionDenied => {
-                        // Can't list directory contents - signal to caller
-                        return Err(FsPulseError::DirectoryUnreadable(path.to_path_buf()));
-                    }
-                    ErrorKind::NotFound => {
-                        // Directory disappeared during scan
-                        trace!("Directory disappeared during scan: '{}'", path.display());
-                        return Ok(0);
-                    }
-                    _ => {
-                        error!(
-                            "Unexpected error reading directory '{}': {} (kind: {:?})",
-                            path.display(),
-                            e,
-                            e.kind()
-                        );
-                        return Err(FsPulseError::from(e));
-                    }
+            ctx.increment_directories_scanned();
+
+            if let Some(file) = listing.new_ignore_file {
+                ctx.record_ignore_file(file);
+            }
+
+            let dir = listing.dir;
+            let dir_read_error = listing.status == DirStatus::Unreadable;
+            if dir_read_error {
+                if dir == root_path {
+                    // Root directory is unreadable - scan cannot proceed
+                    error!(
+                        "Cannot read root directory '{}': Permission denied. Scan cannot proceed.",
+                        dir.display()
+                    );
+                    return Err(FsPulseError::Error(format!(
+                        "Root directory '{}' is unreadable",
+                        dir.display()
+                    )));
                 }
+                // If we can't read the directory contents, we still handle it as an item
+                error!(
+                    "Cannot read directory contents for '{}': Permission denied",
+                    dir.display()
+                );
             }
-        };
 
-        let mut total_size: i64 = 0;
-
-        for item in items {
-            // Handle errors during directory iteration
-            let item = match item {
-                Ok(entry) => entry,
-                Err(e) => {
-                    match e.kind() {
-                        ErrorKind::PermissionDenied => {
-                            error!(
-                                "Permission denied reading directory entry in '{}': {}",
-                                path.display(),
-                                e
-                            );
-                            continue;
-                        }
-                        _ => {
-                            // Other I/O errors during iteration - log and continue
-                            error!(
-                                "Error reading directory entry in '{}': {}",
-                                path.display(),
-                                e
-                            );
-                            continue;
-                        }
+            let mut files_size: i64 = 0;
+            let mut subdir_count = 0;
+
+            for entry in listing.entries {
+                ctx.increment_files_scanned();
+
+                match entry.metadata {
+                    Some(ref metadata) if entry.completed && metadata.is_dir() => {
+                        // Finished by an earlier run of this scan - not walked again.
+                        // A completed followed link falls through to the symlink case,
+                        // which finds its item already written by this scan
+                        files_size += Scanner::completed_dir_size(ctx, &entry.path)?;
                     }
-                }
-            };
-            let item_path = item.path();
-
-            // Try to get metadata, handling access errors gracefully
-            let item_metadata = match fs::symlink_metadata(&item_path) {
-                Ok(metadata) => Some(metadata),
-                Err(e) => {
-                    match e.kind() {
-                        ErrorKind::NotFound => {
-                            // File disappeared during scan (race condition) - skip it
-                            trace!("File disappeared during scan: '{}'", item_path.display());
-                            continue;
-                        }
-                        ErrorKind::PermissionDenied => {
-                            // Can't access metadata - treat as file with MetaError access state
-                            error!(
-                                "Cannot access metadata for '{}': {}. Treating as file with MetaError.",
-                                item_path.display(),
-                                e
-                            );
+                    Some(ref metadata) if entry.mount_point => {
+                        // Another filesystem under a one-file-system root: recorded but not
+                        // traversed, and contributes nothing to the parent's size
+                        Scanner::handle_scan_item(
+                            ctx,
+                            ItemType::Directory,
+                            &entry.path,
+                            Some(metadata),
+                            Some(0),
+                            Access::MountPoint,
+                            None,
+                        )?;
+                    }
+                    Some(metadata) if metadata.is_dir() => {
+                        // Written when its own listing (and all of its subdirectories) complete
+                        pending.insert(entry.path, PendingDir::new(Some(dir.clone()), Some(metadata)));
+                        subdir_count += 1;
+                    }
+                    Some(ref metadata) => {
+                        // Handle files, symlinks, and other items
+                        let item_type = if metadata.is_file() {
+                            ItemType::File
+                        } else if metadata.is_symlink() {
+                            ItemType::Symlink
+                        } else {
+                            ItemType::Unknown
+                        };
+
+                        // Files have meaningful sizes, symlinks and other don't
+                        let item_size = if metadata.is_file() {
+                            Some(metadata.len() as i64)
+                        } else {
                             None
+                        };
+
+                        files_size += Scanner::handle_scan_item(
+                            ctx,
+                            item_type,
+                            &entry.path,
+                            Some(metadata),
+                            item_size,
+                            Access::Ok, // Only directories have a listing access state
+                            entry.symlink_target.as_deref(),
+                        )?;
+
+                        if entry.followed && !entry.completed {
+                            // The link's contents are listed under its own path
+                            pending.insert(entry.path, PendingDir::followed_link(dir.clone()));
+                            subdir_count += 1;
                         }
-                        _ => {
-                            // Other errors - log and propagate
-                            error!(
-                                "Unexpected error getting metadata for '{}': {} (kind: {:?})",
-                                item_path.display(),
-                                e,
-                                e.kind()
-                            );
-                            return Err(FsPulseError::from(e));
-                        }
+                    }
+                    None => {
+                        // Metadata unavailable (permission denied) - treat as Unknown with MetaError.
+                        // Don't add to the size since we don't know it
+                        Scanner::handle_scan_item(
+                            ctx,
+                            ItemType::Unknown,
+                            &entry.path,
+                            None, // No metadata available
+                            None, // No size available
+                            Access::Ok,
+                            None,
+                        )?;
                     }
                 }
+            }
+
+            let state = Scanner::pending_dir(&mut pending, &dir)?;
+            state.size += files_size;
+            state.subdirs_remaining += subdir_count;
+            state.dir_access = if dir_read_error {
+                Access::ReadError
+            } else {
+                Access::Ok
             };
+            state.listed = true;
 
-            ctx.increment_files_scanned();
-
-            match item_metadata {
-                Some(ref metadata) if metadata.is_dir() => {
-                    // Recursively scan the subdirectory and get its size
-                    // If we can't read the directory contents, we still handle it as an item
-                    let (subdir_size, dir_read_error) =
-                        match Scanner::scan_directory_recursive(ctx, &item_path) {
-                            Ok(size) => (size, false),
-                            Err(FsPulseError::DirectoryUnreadable(ref p)) => {
-                                error!(
-                                    "Cannot read directory contents for '{}': Permission denied",
-                                    p.display()
-                                );
-                                (0, true)
-                            }
-                            Err(e) => return Err(e),
-                        };
+            if state.subdirs_remaining == 0 {
+                root_size = Scanner::complete_dirs(ctx, &mut pending, dir)?.or(root_size);
+            }
+        }
 
-                    // Handle the subdirectory with its computed size
-                    let returned_size = Scanner::handle_scan_item(
-                        ctx,
-                        ItemType::Directory,
-                        &item_path,
-                        Some(metadata),
-                        Some(subdir_size),
-                        dir_read_error,
-                    )?;
+        // The channel closes when every walker thread has exited, either because
+        // the walk finished or because it was interrupted
+        Scanner::check_interrupted(ctx.interrupt_token)?;
 
-                    total_size += returned_size;
-                }
-                Some(ref metadata) => {
-                    Scanner::check_interrupted(ctx.interrupt_token)?;
-
-                    // Handle files, symlinks, and other items
-                    let item_type = if metadata.is_file() {
-                        ItemType::File
-                    } else if metadata.is_symlink() {
-                        ItemType::Symlink
-                    } else {
-                        ItemType::Unknown
-                    };
-
-                    // Files have meaningful sizes, symlinks and other don't
-                    let item_size = if metadata.is_file() {
-                        Some(metadata.len() as i64)
-                    } else {
-                        None
-                    };
-
-                    let returned_size = Scanner::handle_scan_item(
-                        ctx,
-                        item_type,
-                        &item_path,
-                        Some(metadata),
-                        item_size,
-                        false, // No directory read error for non-directories
-                    )?;
+        root_size.ok_or_else(|| {
+            FsPulseError::Error(format!(
+                "Directory walk ended with {} directories incomplete",
+                pending.len()
+            ))
+        })
+    }
 
-                    total_size += returned_size;
-                }
-                None => {
-                    // Metadata unavailable (permission denied) - treat as Unknown with MetaError
-                    Scanner::check_interrupted(ctx.interrupt_token)?;
-
-                    Scanner::handle_scan_item(
-                        ctx,
-                        ItemType::Unknown,
-                        &item_path,
-                        None, // No metadata available
-                        None, // No size available
-                        false,
-                    )?;
-                    // Don't add to total_size since we don't know the size
-                }
+    /// Write a completed directory's item, then walk up the tree writing each
+    /// ancestor that this completes. Returns the walk's total size if its starting
+    /// directory was completed.
+    fn complete_dirs(
+        ctx: &mut ScanContext,
+        pending: &mut HashMap<PathBuf, PendingDir>,
+        dir: PathBuf,
+    ) -> Result<Option<i64>, FsPulseError> {
+        let mut current = dir;
+
+        loop {
+            let state = pending.remove(&current).ok_or_else(|| {
+                FsPulseError::Error(format!("Unexpected directory in walk: '{}'", current.display()))
+            })?;
+
+            // The directory the walk started from is written by the caller
+            let Some(parent) = state.parent else {
+                return Ok(Some(state.size));
+            };
+
+            // Handle the subdirectory with its computed size. Symlinks have no size,
+            // so a followed link's contents don't count toward its parent
+            let returned_size = if state.followed_link {
+                0
+            } else {
+                Scanner::handle_scan_item(
+                    ctx,
+                    ItemType::Directory,
+                    &current,
+                    state.metadata.as_ref(),
+                    Some(state.size),
+                    state.dir_access,
+                    None,
+                )?
+            };
+            ctx.mark_dir_completed(&current);
+
+            let parent_state = Scanner::pending_dir(pending, &parent)?;
+            parent_state.size += returned_size;
+            parent_state.subdirs_remaining -= 1;
+
+            if !parent_state.listed || parent_state.subdirs_remaining > 0 {
+                return Ok(None);
+            }
+            current = parent;
+        }
+    }
+
+    /// Size of a directory checkpointed by an earlier run of this scan. Its item was
+    /// written in the same transaction as the checkpoint, so it must be present.
+    fn completed_dir_size(ctx: &ScanContext, path: &Path) -> Result<i64, FsPulseError> {
+        let existing = ExistingItem::get_by_root_path_type(
+            ctx.conn,
+            ctx.scan.root_id(),
+            &path.to_string_lossy(),
+            ItemType::Directory,
+        )?;
+
+        match existing {
+            Some(existing) if existing.version.last_scan_id() == ctx.scan.scan_id() => {
+                Ok(existing.version.size().unwrap_or(0))
             }
+            _ => Err(FsPulseError::Error(format!(
+                "Checkpointed directory '{}' was not written by this scan",
+                path.display()
+            ))),
         }
+    }
 
-        Ok(total_size)
+    fn pending_dir<'p>(
+        pending: &'p mut HashMap<PathBuf, PendingDir>,
+        dir: &Path,
+    ) -> Result<&'p mut PendingDir, FsPulseError> {
+        pending.get_mut(dir).ok_or_else(|| {
+            FsPulseError::Error(format!("Unexpected directory in walk: '{}'", dir.display()))
+        })
     }
 
     fn do_state_scanning(
         root: &Root,
         scan: &mut Scan,
+        task_id: i64,
+        task_state: &mut ScanTaskState,
         task_progress: Arc<TaskProgress>,
         interrupt_token: &Arc<AtomicBool>,
     ) -> Result<(), FsPulseError> {
         let conn = Database::get_connection()?;
         let root_path_buf = PathBuf::from(root.root_path());
+        let rules = RootRules::load(&conn, root.root_id(), root.root_path())?;
+        let scope = ScanScope::from_recorded(&task_state.scope);
+
+        // On resume, ignore files recorded by the interrupted run are reused as-is
+        let mut ignores = IgnoreFiles::from_recorded(root.root_path(), &task_state.ignore_files);
+
+        // A scoped walk never lists the directories above its scope, so their
+        // ignore files are read here. Excluded or ignored scope directories are
+        // not walked; the sweep tombstones their contents as excluded.
+        let mut scope_ancestors = Vec::new();
+        let mut scope_dirs = Vec::new();
+        let mut ancestor_ignore_files = Vec::new();
+        if !scope.is_whole_root() {
+            scope_ancestors = scope.ancestors(&root_path_buf);
+            for dir in scope_ancestors.iter().rev().chain([&root_path_buf]) {
+                if !ignores.has_dir(dir) {
+                    if let Some(file) = IgnoreFiles::read(dir) {
+                        ignores.add(file.clone());
+                        ancestor_ignore_files.push(file);
+                    }
+                }
+            }
+            let is_walked = |dir: &Path| {
+                !rules.is_excluded_or_under_excluded(dir) && !ignores.is_ignored_or_under_ignored(dir, true)
+            };
+            scope_ancestors.retain(|dir| is_walked(dir));
+            scope_dirs = scope.dirs().iter().filter(|dir| is_walked(dir)).cloned().collect();
+        }
+
+        // On resume, directories completed by the interrupted run are not walked again
+        let completed_dirs: HashSet<PathBuf> =
+            task_state.completed_dirs.iter().map(PathBuf::from).collect();
+        if !completed_dirs.is_empty() {
+            task_progress.add_breadcrumb(&format!(
+                "Resuming scan with {} completed directories",
+                completed_dirs.len()
+            ));
+        }
+        let walker = Walker::new(&rules, ignores, completed_dirs, root.options(), interrupt_token);
+        let thread_count = Config::get_scan_threads();
 
         // Create scanning context
-        let mut ctx = ScanContext::new(&conn, scan, &task_progress, interrupt_token);
+        let mut ctx = ScanContext::new(&conn, scan, task_id, task_state, &task_progress, interrupt_token);
+        for file in ancestor_ignore_files {
+            ctx.record_ignore_file(file);
+        }
 
-        // Recursively scan the root directory and get the total size
         // Note: We don't store the root directory itself as an item in the database
-        let total_size = match Scanner::scan_directory_recursive(&mut ctx, &root_path_buf) {
-            Ok(size) => size,
-            Err(FsPulseError::DirectoryUnreadable(ref p)) => {
-                // Root directory is unreadable - scan cannot proceed
-                error!(
-                    "Cannot read root directory '{}': Permission denied. Scan cannot proceed.",
-                    p.display()
-                );
-                return Err(FsPulseError::Error(format!(
-                    "Root directory '{}' is unreadable",
-                    p.display()
-                )));
+        let total_size = if scope.is_whole_root() {
+            Scanner::walk_dir(&mut ctx, &walker, &root_path_buf, &root_path_buf, thread_count)?
+        } else {
+            for dir in &scope_dirs {
+                Scanner::scan_scope_dir(&mut ctx, &walker, &root_path_buf, dir, thread_count)?;
+            }
+            // Directories above the scope, deepest first, then the root: sizes come
+            // from their children's current versions
+            let scope_filter = scope.sql_filter("i.item_path").unwrap_or_default();
+            for dir in &scope_ancestors {
+                Scanner::write_scope_ancestor(&mut ctx, dir, &scope_filter)?;
             }
-            Err(e) => return Err(e),
+            Scanner::scoped_dir_size(ctx.conn, ctx.scan, &root_path_buf, &scope_filter)?
         };
 
+        // The walk is complete: checkpoints are no longer needed
+        ctx.clear_completed_dirs();
+
         // Flush any remaining batched writes
         ctx.flush()?;
 
@@ -450,6 +671,12 @@ impl Scanner {
             "Scanned {} files in {} directories",
             ctx.files_scanned, ctx.directories_scanned
         ));
+        if !ctx.task_state.ignore_files.is_empty() {
+            ctx.task_progress.add_breadcrumb(&format!(
+                "Applied {} ignore files",
+                ctx.task_state.ignore_files.len()
+            ));
+        }
 
         // Drop ctx to release the immutable borrow of scan before we mutably borrow it
         drop(ctx);
@@ -459,19 +686,188 @@ impl Scanner {
         // to be set to NULL if the scan ends in stoppage or error
         scan.set_total_size(&conn, total_size)?;
 
-        scan.set_state_sweeping(&conn)
+        match scope.sql_filter("i.item_path") {
+            None => scan.set_state_sweeping(&conn),
+            Some(scope_filter) => Database::immediate_transaction(&conn, |c| {
+                let carried = Scanner::carry_forward_unscoped(c, scan, &scope_filter)?;
+                task_progress.add_breadcrumb(&format!(
+                    "Carried forward {} items outside the scanned directories",
+                    carried
+                ));
+                scan.set_state_sweeping(c)
+            }),
+        }
+    }
+
+    /// Walk the tree below `start` and write everything in it except `start`
+    /// itself. Walker threads list directories in parallel; this thread is the
+    /// single DB writer. Returns the total size below `start`.
+    fn walk_dir(
+        ctx: &mut ScanContext,
+        walker: &Walker,
+        root_path: &Path,
+        start: &Path,
+        thread_count: usize,
+    ) -> Result<i64, FsPulseError> {
+        let (sender, receiver) = bounded::<WalkEvent>(WALK_CHANNEL_SIZE);
+        thread::scope(|s| {
+            s.spawn(|| walker.run(root_path, start, thread_count, sender));
+            Scanner::write_walk_results(ctx, start, receiver)
+        })
+    }
+
+    /// Walk one directory of a scoped scan, then write its own item with the
+    /// walked size. A directory whose contents can't be read is recorded with
+    /// `Access::ReadError`, as a full walk would record it.
+    fn scan_scope_dir(
+        ctx: &mut ScanContext,
+        walker: &Walker,
+        root_path: &Path,
+        dir: &Path,
+        thread_count: usize,
+    ) -> Result<(), FsPulseError> {
+        // Finished by an earlier run of this scan
+        if ctx.completed_dirs.contains(dir.to_string_lossy().as_ref()) {
+            return Ok(());
+        }
+
+        let (size, access) = if fs::read_dir(dir).is_ok() {
+            (Scanner::walk_dir(ctx, walker, root_path, dir, thread_count)?, Access::Ok)
+        } else {
+            error!("Cannot read directory contents for '{}'", dir.display());
+            (0, Access::ReadError)
+        };
+
+        let metadata = fs::symlink_metadata(dir).ok();
+        Scanner::handle_scan_item(ctx, ItemType::Directory, dir, metadata.as_ref(), Some(size), access, None)?;
+        ctx.mark_dir_completed(dir);
+        Ok(())
+    }
+
+    /// Write a directory between the root and a scoped scan's directories. It
+    /// isn't walked, so its size is recomputed from its children.
+    fn write_scope_ancestor(
+        ctx: &mut ScanContext,
+        dir: &Path,
+        scope_filter: &str,
+    ) -> Result<(), FsPulseError> {
+        let size = Scanner::scoped_dir_size(ctx.conn, ctx.scan, dir, scope_filter)?;
+        let access = if fs::read_dir(dir).is_ok() {
+            Access::Ok
+        } else {
+            Access::ReadError
+        };
+        let metadata = fs::symlink_metadata(dir).ok();
+        Scanner::handle_scan_item(ctx, ItemType::Directory, dir, metadata.as_ref(), Some(size), access, None)?;
+        Ok(())
+    }
+
+    /// Total size of a directory's immediate children as of a scoped scan: those
+    /// written by this scan, plus those outside the scope, which the scan carries
+    /// forward unchanged. Scope directories the scan didn't write (because they
+    /// are now excluded) don't count.
+    fn scoped_dir_size(
+        conn: &Connection,
+        scan: &Scan,
+        dir: &Path,
+        scope_filter: &str,
+    ) -> Result<i64, FsPulseError> {
+        let (path_prefix, path_upper) = Scanner::child_path_range(&dir.to_string_lossy());
+        let sql = format!(
+            "SELECT COALESCE(SUM(COALESCE(iv.size, 0)), 0)
+             FROM items i
+             JOIN item_versions iv ON iv.item_id = i.item_id
+             WHERE i.root_id = ?1
+               AND iv.first_scan_id = (
+                   SELECT MAX(first_scan_id) FROM item_versions
+                   WHERE item_id = i.item_id
+               )
+               AND iv.is_deleted = 0
+               AND i.item_path >= ?2
+               AND i.item_path < ?3
+               AND SUBSTR(i.item_path, LENGTH(?2) + 1) NOT LIKE '%{}%'
+               AND (iv.last_scan_id = ?4 OR NOT {})",
+            MAIN_SEPARATOR_STR, scope_filter
+        );
+
+        let size = conn.query_row(
+            &sql,
+            params![scan.root_id(), path_prefix, path_upper, scan.scan_id()],
+            |row| row.get(0),
+        )?;
+        Ok(size)
+    }
+
+    /// Extend every alive item outside a scoped scan's directories to this scan,
+    /// as a full scan would for an unchanged item. The prior `last_scan_id` of
+    /// each is recorded in the undo log first, so a stopped scan restores it.
+    ///
+    /// Returns the number of items carried forward.
+    fn carry_forward_unscoped(
+        conn: &Connection,
+        scan: &Scan,
+        scope_filter: &str,
+    ) -> Result<usize, FsPulseError> {
+        let unscoped = format!(
+            "iv.root_id = ?1
+               AND iv.last_scan_id < ?2
+               AND iv.is_deleted = 0
+               AND iv.first_scan_id = (
+                   SELECT MAX(iv2.first_scan_id)
+                   FROM item_versions iv2
+                   WHERE iv2.item_id = iv.item_id
+               )
+               AND iv.item_id IN (
+                   SELECT i.item_id FROM items i
+                   WHERE i.root_id = ?1 AND NOT {}
+               )",
+            scope_filter
+        );
+
+        conn.execute(
+            &format!(
+                "INSERT INTO scan_undo_log (log_type, ref_id1, ref_id2, ref_id3, old_last_scan_id)
+                 SELECT ?3, iv.item_id, iv.item_version, 0, iv.last_scan_id
+                 FROM item_versions iv
+                 WHERE {}",
+                unscoped
+            ),
+            params![scan.root_id(), scan.scan_id(), UndoLogType::ItemVersion as i64],
+        )?;
+
+        let count = conn.execute(
+            &format!("UPDATE item_versions AS iv SET last_scan_id = ?2 WHERE {}", unscoped),
+            params![scan.root_id(), scan.scan_id()],
+        )?;
+        Ok(count)
     }
 
     fn do_state_sweeping(
+        root: &Root,
         scan: &mut Scan,
+        task_state: &ScanTaskState,
         task_progress: Arc<TaskProgress>,
         interrupt_token: &Arc<AtomicBool>,
     ) -> Result<(), FsPulseError> {
         Scanner::check_interrupted(interrupt_token)?;
 
         let conn = Database::get_connection()?;
+        let rules = RootRules::load(&conn, root.root_id(), root.root_path())?;
+        let ignores = IgnoreFiles::from_recorded(root.root_path(), &task_state.ignore_files);
+
+        let excluded_count = Database::immediate_transaction(&conn, |c| {
+            // Items not seen because a root rule or ignore file now hides them, or
+            // because they are below a mount point or symlink that wasn't traversed,
+            // get an excluded tombstone first. The bulk insert below then skips them,
+            // since their latest version is already deleted.
+            let untraversed =
+                Scanner::query_untraversed_dirs(c, scan, !root.options().follow_symlinks)?;
+            let excluded_count = if rules.is_empty() && ignores.is_empty() && untraversed.is_empty() {
+                0
+            } else {
+                Scanner::tombstone_excluded_items(c, scan, &rules, &ignores, &untraversed)?
+            };
 
-        Database::immediate_transaction(&conn, |c| {
             // NEW MODEL: bulk-insert deletion versions for alive items not seen in this scan.
             // Carries forward all state from the current version. No undo log needed — the
             // previous version is not modified (closing is conceptual), and the new deletion
@@ -479,14 +875,16 @@ impl Scanner {
             c.execute(
                 "INSERT INTO item_versions (
                     item_id, item_version, root_id, first_scan_id, last_scan_id,
-                    is_added, is_deleted, access, mod_date, size,
+                    is_added, is_deleted, access, mod_date, size, symlink_target,
+                    mode, uid, gid, inode, nlink, ctime, dev,
                     add_count, modify_count, delete_count, unchanged_count
                  )
                  SELECT
                     iv.item_id,
                     COALESCE((SELECT MAX(iv3.item_version) FROM item_versions iv3 WHERE iv3.item_id = iv.item_id), 0) + 1,
                     i.root_id, ?, ?,
-                    0, 1, iv.access, iv.mod_date, iv.size,
+                    0, 1, iv.access, iv.mod_date, iv.size, iv.symlink_target,
+                    iv.mode, iv.uid, iv.gid, iv.inode, iv.nlink, iv.ctime, iv.dev,
                     CASE WHEN i.item_type = 1 THEN 0 ELSE NULL END,
                     CASE WHEN i.item_type = 1 THEN 0 ELSE NULL END,
                     CASE WHEN i.item_type = 1 THEN 0 ELSE NULL END,
@@ -506,14 +904,119 @@ impl Scanner {
 
             scan.set_state_analyzing_files(c)?;
 
-            Ok(())
+            Ok(excluded_count)
         })?;
 
         task_progress.add_breadcrumb("Tombstoned deleted items");
+        if excluded_count > 0 {
+            task_progress.add_breadcrumb(&format!(
+                "Tombstoned {} newly excluded items",
+                excluded_count
+            ));
+        }
 
         Ok(())
     }
 
+    /// Insert excluded tombstones for alive items not seen in this scan whose path,
+    /// or any ancestor directory below the root, is excluded by a root rule or
+    /// ignored by one of the scan's ignore files.
+    ///
+    /// Returns the number of items tombstoned.
+    fn tombstone_excluded_items(
+        conn: &Connection,
+        scan: &Scan,
+        rules: &RootRules,
+        ignores: &IgnoreFiles,
+        untraversed: &HashSet<PathBuf>,
+    ) -> Result<usize, FsPulseError> {
+        let unseen: Vec<(i64, String, i64)> = {
+            let mut stmt = conn.prepare(
+                "SELECT i.item_id, i.item_path, i.item_type
+                 FROM item_versions iv
+                 JOIN items i ON i.item_id = iv.item_id
+                 WHERE iv.root_id = ?1
+                   AND iv.last_scan_id < ?2
+                   AND iv.is_deleted = 0
+                   AND iv.first_scan_id = (
+                       SELECT MAX(iv2.first_scan_id)
+                       FROM item_versions iv2
+                       WHERE iv2.item_id = iv.item_id
+                   )",
+            )?;
+            let rows = stmt.query_map(params![scan.root_id(), scan.scan_id()], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })?;
+            rows.collect::<Result<Vec<_>, _>>()?
+        };
+
+        let mut insert = conn.prepare(
+            "INSERT INTO item_versions (
+                item_id, item_version, root_id, first_scan_id, last_scan_id,
+                is_added, is_deleted, is_excluded, access, mod_date, size, symlink_target,
+                mode, uid, gid, inode, nlink, ctime, dev,
+                add_count, modify_count, delete_count, unchanged_count
+             )
+             SELECT
+                iv.item_id, iv.item_version + 1, iv.root_id, ?2, ?2,
+                0, 1, 1, iv.access, iv.mod_date, iv.size, iv.symlink_target,
+                iv.mode, iv.uid, iv.gid, iv.inode, iv.nlink, iv.ctime, iv.dev,
+                CASE WHEN i.item_type = 1 THEN 0 ELSE NULL END,
+                CASE WHEN i.item_type = 1 THEN 0 ELSE NULL END,
+                CASE WHEN i.item_type = 1 THEN 0 ELSE NULL END,
+                CASE WHEN i.item_type = 1 THEN 0 ELSE NULL END
+             FROM item_versions iv
+             JOIN items i ON i.item_id = iv.item_id
+             WHERE iv.item_id = ?1
+               AND iv.item_version = (SELECT MAX(item_version) FROM item_versions WHERE item_id = ?1)",
+        )?;
+
+        let mut count = 0;
+        for (item_id, item_path, item_type) in &unseen {
+            let path = Path::new(item_path);
+            let is_dir = *item_type == ItemType::Directory.as_i64();
+            if rules.is_excluded_or_under_excluded(path)
+                || ignores.is_ignored_or_under_ignored(path, is_dir)
+                || path.ancestors().skip(1).any(|a| untraversed.contains(a))
+            {
+                insert.execute(params![item_id, scan.scan_id()])?;
+                count += 1;
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// Items seen in this scan whose contents were not walked: directories recorded
+    /// as mount points and, when the root doesn't follow symlinks, symlinks. Items
+    /// below them still exist on disk, so they are excluded rather than deleted.
+    fn query_untraversed_dirs(
+        conn: &Connection,
+        scan: &Scan,
+        include_symlinks: bool,
+    ) -> Result<HashSet<PathBuf>, FsPulseError> {
+        let mut stmt = conn.prepare(
+            "SELECT i.item_path
+             FROM item_versions iv
+             JOIN items i ON i.item_id = iv.item_id
+             WHERE iv.root_id = ?1
+               AND iv.last_scan_id = ?2
+               AND iv.is_deleted = 0
+               AND (iv.access = ?3 OR (?4 AND i.item_type = ?5))",
+        )?;
+        let rows = stmt.query_map(
+            params![
+                scan.root_id(),
+                scan.scan_id(),
+                Access::MountPoint.as_i64(),
+                include_symlinks,
+                ItemType::Symlink.as_i64()
+            ],
+            |row| row.get::<_, String>(0).map(PathBuf::from),
+        )?;
+        Ok(rows.collect::<Result<HashSet<_>, _>>()?)
+    }
+
     /// Phase 4: Compute folder descendant change counts for the current scan.
     ///
     /// Walks the folder tree in the database depth-first, computing add/modify/delete
@@ -575,6 +1078,9 @@ impl Scanner {
         let conn = Database::get_connection()?;
         let prev_scan_id = Scanner::query_prev_completed_scan(&conn, root_id, scan_id)?;
 
+        Database::immediate_transaction(&conn, |c| MoveDetector::detect_moves(c, root_id, scan_id))?;
+        Scanner::check_interrupted(interrupt_token)?;
+
         let mut writes = Vec::new();
         Scanner::walk_folder_counts(&conn, root_id, scan_id, root_path, interrupt_token, &mut writes)?;
 
@@ -606,7 +1112,11 @@ impl Scanner {
 
     /// Recursive depth-first walk of the folder tree, computing descendant change counts
     ///
-    /// Returns the cumulative `(adds, mods, dels)` for all descendants.
+    /// Returns the cumulative `(adds, mods, dels, gone, arrived)` for all descendants.
+    /// Descendants that are gone (excluded, or moved elsewhere) are not reported as
+    /// deletes, but they are no longer alive, so they still reduce the folder's
+    /// unchanged count. Descendants that arrived by a move are not reported as adds;
+    /// they join the unchanged count of their new folder.
     /// Appends a `FolderCountWrite` entry for each folder whose counts actually differ
     /// from its previous version's counts.
     fn walk_folder_counts(
@@ -616,12 +1126,14 @@ impl Scanner {
         parent_path: &str,
         interrupt_token: &Arc<AtomicBool>,
         writes: &mut Vec<FolderCountWrite>,
-    ) -> Result<(i64, i64, i64), FsPulseError> {
+    ) -> Result<(i64, i64, i64, i64, i64), FsPulseError> {
         Scanner::check_interrupted(interrupt_token)?;
 
         let mut adds = 0i64;
         let mut mods = 0i64;
         let mut dels = 0i64;
+        let mut gone = 0i64;
+        let mut arrived = 0i64;
 
         // 1. Get immediate directory children alive at this scan
         //    (including dirs deleted AT this scan — needed to recurse into deleted subtrees)
@@ -629,26 +1141,31 @@ impl Scanner {
 
         // 2. Recurse into each directory child
         for (_child_id, child_path) in &dir_children {
-            let (sa, sm, sd) = Scanner::walk_folder_counts(
+            let (sa, sm, sd, sg, sv) = Scanner::walk_folder_counts(
                 conn, root_id, scan_id, child_path, interrupt_token, writes,
             )?;
             adds += sa;
             mods += sm;
             dels += sd;
+            gone += sg;
+            arrived += sv;
         }
 
         // 3. Count direct children that changed in this scan
-        let (da, dm, dd) = Scanner::query_direct_change_counts(conn, root_id, parent_path, scan_id)?;
+        let (da, dm, dd, dg, dv) = Scanner::query_direct_change_counts(conn, root_id, parent_path, scan_id)?;
         adds += da;
         mods += dm;
         dels += dd;
+        gone += dg;
+        arrived += dv;
 
-        // 4. Write a new folder version if any descendant was added, modified,
-        //    or deleted this scan.
-        if adds > 0 || mods > 0 || dels > 0 {
+        // 4. Write a new folder version if any descendant was added, modified or
+        //    deleted this scan, or the number alive changed. A move within the
+        //    folder leaves its counts as they were.
+        if adds > 0 || mods > 0 || dels > 0 || gone != arrived {
             if let Some(folder_item_id) = Scanner::lookup_folder_item_id(conn, root_id, parent_path)? {
                 let prev_alive = Scanner::query_prev_alive(conn, folder_item_id, scan_id)?;
-                let unchanged = prev_alive - mods - dels;
+                let unchanged = prev_alive - mods - dels - gone + arrived;
 
                 writes.push(FolderCountWrite {
                     folder_item_id,
@@ -660,17 +1177,12 @@ impl Scanner {
             }
         }
 
-        Ok((adds, mods, dels))
+        Ok((adds, mods, dels, gone, arrived))
     }
 
-    /// Query immediate directory children of `parent_path` that are alive at `scan_id`
-    /// (or deleted AT `scan_id`, so we can recurse into deleted subtrees).
-    fn query_immediate_dir_children(
-        conn: &Connection,
-        root_id: i64,
-        parent_path: &str,
-        scan_id: i64,
-    ) -> Result<Vec<(i64, String)>, FsPulseError> {
+    /// Bounds of the item paths below `parent_path`, for a range scan:
+    /// `prefix <= path < upper`.
+    fn child_path_range(parent_path: &str) -> (String, String) {
         let path_prefix = if parent_path.ends_with(MAIN_SEPARATOR_STR) {
             parent_path.to_string()
         } else {
@@ -685,6 +1197,19 @@ impl Scanner {
             char::from(std::path::MAIN_SEPARATOR as u8 + 1)
         );
 
+        (path_prefix, path_upper)
+    }
+
+    /// Query immediate directory children of `parent_path` that are alive at `scan_id`
+    /// (or deleted AT `scan_id`, so we can recurse into deleted subtrees).
+    fn query_immediate_dir_children(
+        conn: &Connection,
+        root_id: i64,
+        parent_path: &str,
+        scan_id: i64,
+    ) -> Result<Vec<(i64, String)>, FsPulseError> {
+        let (path_prefix, path_upper) = Scanner::child_path_range(parent_path);
+
         let sql = format!(
             "SELECT i.item_id, i.item_path
              FROM items i
@@ -714,35 +1239,29 @@ impl Scanner {
     }
 
     /// Count direct children of `parent_path` that changed in this scan, classified
-    /// as add/modify/delete by comparing the current version with the previous version.
+    /// as add/modify/delete/gone/arrived by comparing the current version with the
+    /// previous version. Excluded tombstones and the tombstones of moved items are
+    /// gone, never deletes. Items that moved here arrived, and are not adds.
     fn query_direct_change_counts(
         conn: &Connection,
         root_id: i64,
         parent_path: &str,
         scan_id: i64,
-    ) -> Result<(i64, i64, i64), FsPulseError> {
-        let path_prefix = if parent_path.ends_with(MAIN_SEPARATOR_STR) {
-            parent_path.to_string()
-        } else {
-            format!("{}{}", parent_path, MAIN_SEPARATOR_STR)
-        };
-
-        // Upper bound for range scan: replace trailing separator with next ASCII char.
-        // Unix: '/' (0x2F) + 1 = '0' (0x30). Windows: '\' (0x5C) + 1 = ']' (0x5D).
-        let path_upper = format!(
-            "{}{}",
-            &path_prefix[..path_prefix.len() - MAIN_SEPARATOR_STR.len()],
-            char::from(std::path::MAIN_SEPARATOR as u8 + 1)
-        );
+    ) -> Result<(i64, i64, i64, i64, i64), FsPulseError> {
+        let (path_prefix, path_upper) = Scanner::child_path_range(parent_path);
 
         let sql = format!(
             "SELECT
-                COALESCE(SUM(CASE WHEN cv.is_deleted = 0
+                COALESCE(SUM(CASE WHEN cv.is_deleted = 0 AND cv.moved_from_item_id IS NULL
                     AND (pv.item_id IS NULL OR pv.is_deleted = 1) THEN 1 ELSE 0 END), 0),
                 COALESCE(SUM(CASE WHEN cv.is_deleted = 0
                     AND pv.item_id IS NOT NULL AND pv.is_deleted = 0 THEN 1 ELSE 0 END), 0),
-                COALESCE(SUM(CASE WHEN cv.is_deleted = 1
-                    AND pv.item_id IS NOT NULL AND pv.is_deleted = 0 THEN 1 ELSE 0 END), 0)
+                COALESCE(SUM(CASE WHEN cv.is_deleted = 1 AND cv.is_excluded = 0 AND mv.item_id IS NULL
+                    AND pv.item_id IS NOT NULL AND pv.is_deleted = 0 THEN 1 ELSE 0 END), 0),
+                COALESCE(SUM(CASE WHEN cv.is_deleted = 1 AND (cv.is_excluded = 1 OR mv.item_id IS NOT NULL)
+                    AND pv.item_id IS NOT NULL AND pv.is_deleted = 0 THEN 1 ELSE 0 END), 0),
+                COALESCE(SUM(CASE WHEN cv.is_deleted = 0 AND cv.moved_from_item_id IS NOT NULL
+                    THEN 1 ELSE 0 END), 0)
              FROM items i
              JOIN item_versions cv ON cv.item_id = i.item_id AND cv.first_scan_id = ?1
              LEFT JOIN item_versions pv ON pv.item_id = i.item_id
@@ -750,6 +1269,9 @@ impl Scanner {
                      SELECT MAX(first_scan_id) FROM item_versions
                      WHERE item_id = i.item_id AND first_scan_id < cv.first_scan_id
                  )
+             LEFT JOIN item_versions mv ON cv.is_deleted = 1
+                 AND mv.moved_from_item_id = i.item_id
+                 AND mv.first_scan_id = cv.first_scan_id
              WHERE i.root_id = ?2
                AND i.item_path >= ?3
                AND i.item_path < ?4
@@ -761,7 +1283,7 @@ impl Scanner {
         let mut stmt = conn.prepare(&sql)?;
         let result = stmt.query_row(
             params![scan_id, root_id, &path_prefix, &path_upper, parent_path],
-            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
         )?;
 
         Ok(result)
@@ -863,12 +1385,19 @@ impl Scanner {
             .unwrap_or(false);
 
         if existing {
-            // Case A: UPDATE the existing version's counts
+            // Case A: UPDATE the existing version's counts. A modified folder whose
+            // descendants also changed records both (NULL stays NULL for adds)
+            let descendants = if w.adds + w.mods + w.dels > 0 {
+                ChangeReason::DESCENDANTS
+            } else {
+                ChangeReason::NONE
+            };
             conn.execute(
                 "UPDATE item_versions SET
-                    add_count = ?, modify_count = ?, delete_count = ?, unchanged_count = ?
+                    add_count = ?, modify_count = ?, delete_count = ?, unchanged_count = ?,
+                    change_reason = change_reason | ?
                  WHERE item_id = ? AND first_scan_id = ?",
-                params![w.adds, w.mods, w.dels, w.unchanged, w.folder_item_id, scan_id],
+                params![w.adds, w.mods, w.dels, w.unchanged, descendants.as_i64(), w.folder_item_id, scan_id],
             )?;
         } else {
             // Case B: Folder metadata unchanged but descendants changed.
@@ -895,6 +1424,9 @@ impl Scanner {
                     version.access(),
                     version.mod_date(),
                     version.size(),
+                    version.symlink_target(),
+                    version.unix(),
+                    Some(ChangeReason::DESCENDANTS),
                     Some((w.adds, w.mods, w.dels, w.unchanged)),
                 )?;
             }
@@ -907,12 +1439,13 @@ impl Scanner {
     ///
     /// Priority (highest to lowest):
     /// 1. meta_error=true → MetaError (can't stat at all)
-    /// 2. dir_read_error=true (directories only) → ReadError (can stat but can't list)
+    /// 2. Directories take the access state from this scan's walk (`dir_access`)
     /// 3. Otherwise, clear MetaError (stat worked), preserve ReadError for non-directories
     ///
     /// For directories:
     /// - If meta_error: MetaError
     /// - If read_dir failed: ReadError (can stat but can't list contents)
+    /// - If on another filesystem of a one-file-system root: MountPoint (not listed)
     /// - If read_dir succeeded: Ok (stat and read both work, clear any previous error)
     ///
     /// For files/symlinks/other:
@@ -923,7 +1456,7 @@ impl Scanner {
     fn calculate_new_access(
         item_type: ItemType,
         old_access: Access,
-        dir_read_error: bool,
+        dir_access: Access,
         meta_error: bool,
     ) -> Access {
         // MetaError takes priority - if we can't stat, that's the access state
@@ -932,18 +1465,13 @@ impl Scanner {
         }
 
         if item_type == ItemType::Directory {
-            if dir_read_error {
-                Access::ReadError
-            } else {
-                // Directory stat and read_dir both succeeded - clear any errors
-                Access::Ok
-            }
+            dir_access
         } else {
             // For non-directories, clear MetaError (stat worked), preserve ReadError
             match old_access {
                 Access::MetaError => Access::Ok,
                 Access::ReadError => Access::ReadError,
-                Access::Ok => Access::Ok,
+                Access::Ok | Access::MountPoint => Access::Ok,
             }
         }
     }
@@ -954,7 +1482,8 @@ impl Scanner {
         path: &Path,
         metadata: Option<&Metadata>,
         computed_size: Option<i64>,
-        dir_read_error: bool,
+        dir_access: Access,
+        symlink_target: Option<&str>,
     ) -> Result<i64, FsPulseError> {
         let _tmr = timer!(Level::Trace; "handle_scan_item", "{}", path.display());
 
@@ -973,6 +1502,7 @@ impl Scanner {
         });
 
         let size = computed_size;
+        let unix = metadata.and_then(UnixMetadata::from_metadata);
 
         match existing_item {
             Some(ref existing) if existing.version.last_scan_id() == ctx.scan.scan_id() => {
@@ -986,7 +1516,9 @@ impl Scanner {
                     item_type,
                     mod_date,
                     size,
-                    dir_read_error,
+                    symlink_target,
+                    unix.as_ref(),
+                    dir_access,
                     meta_error,
                 )?;
                 Ok(computed_size.unwrap_or(0))
@@ -998,7 +1530,9 @@ impl Scanner {
                     &path_str,
                     mod_date,
                     size,
-                    dir_read_error,
+                    symlink_target,
+                    unix.as_ref(),
+                    dir_access,
                     meta_error,
                 )?;
                 Ok(computed_size.unwrap_or(0))
@@ -1007,53 +1541,93 @@ impl Scanner {
     }
 
     /// Handle an existing item (non-tombstone or tombstone)
+    #[allow(clippy::too_many_arguments)]
     fn handle_existing_item(
         ctx: &mut ScanContext,
         existing_item: &ExistingItem,
         item_type: ItemType,
         mod_date: Option<i64>,
         size: Option<i64>,
-        dir_read_error: bool,
+        symlink_target: Option<&str>,
+        unix: Option<&UnixMetadata>,
+        dir_access: Access,
         meta_error: bool,
     ) -> Result<(), FsPulseError> {
         let old_access = existing_item.version.access();
         let new_access =
-            Scanner::calculate_new_access(item_type, old_access, dir_read_error, meta_error);
+            Scanner::calculate_new_access(item_type, old_access, dir_access, meta_error);
         let access_changed = old_access != new_access;
-        let meta_change = existing_item.version.mod_date() != mod_date || existing_item.version.size() != size;
+
+        // Symlink versions recorded before targets were tracked have no target.
+        // It is filled in place rather than treated as a retarget.
+        let old_target = existing_item.version.symlink_target();
+        let target_backfill = if old_target.is_none() { symlink_target } else { None };
+        let target_change = target_backfill.is_none() && old_target != symlink_target;
+
+        // Likewise for Unix metadata on versions recorded before it was tracked
+        let old_unix = existing_item.version.unix();
+        let unix_backfill = if old_unix.is_none() { unix } else { None };
+
+        let mut reason = ChangeReason::NONE;
+        if existing_item.version.size() != size {
+            reason |= ChangeReason::SIZE;
+        }
+        if existing_item.version.mod_date() != mod_date {
+            reason |= ChangeReason::MOD_DATE;
+        }
+        if access_changed {
+            reason |= ChangeReason::ACCESS;
+        }
+        if target_change {
+            reason |= ChangeReason::SYMLINK_TARGET;
+        }
+        if let (Some(old), Some(new)) = (old_unix, unix) {
+            reason |= old.changes(new);
+        }
 
         if existing_item.version.is_deleted() {
+            // The tombstone carries the last known state, so the diff still
+            // says what changed while the item was gone
             Scanner::handle_tombstone_rehydration(
                 ctx,
                 existing_item,
                 item_type,
                 mod_date,
                 size,
+                symlink_target,
+                unix,
+                reason | ChangeReason::RESTORED,
                 new_access,
             )
-        } else if meta_change || access_changed {
+        } else if !reason.is_empty() {
             Scanner::handle_item_modification(
                 ctx,
                 existing_item,
                 item_type,
                 mod_date,
                 size,
-                old_access,
+                symlink_target,
+                unix,
+                reason,
                 new_access,
             )
         } else {
             // No change at all - just update last_scan
-            Scanner::handle_item_no_change(ctx, existing_item)
+            Scanner::handle_item_no_change(ctx, existing_item, target_backfill, unix_backfill)
         }
     }
 
     /// Handle tombstone rehydration (item coming back from deletion)
+    #[allow(clippy::too_many_arguments)]
     fn handle_tombstone_rehydration(
         ctx: &mut ScanContext,
         existing_item: &ExistingItem,
         item_type: ItemType,
         mod_date: Option<i64>,
         size: Option<i64>,
+        symlink_target: Option<&str>,
+        unix: Option<&UnixMetadata>,
+        reason: ChangeReason,
         new_access: Access,
     ) -> Result<(), FsPulseError> {
         ctx.execute_batch_write(|c| {
@@ -1062,21 +1636,26 @@ impl Scanner {
             // and temporal queries resolve via MAX(first_scan_id), not last_scan_id)
             let counts = if item_type == ItemType::Directory { Some((0, 0, 0, 0)) } else { None };
             ItemVersion::insert_initial(
-                c, existing_item.item_id, ctx.scan.root_id(), ctx.scan.scan_id(), new_access, mod_date, size, counts,
+                c, existing_item.item_id, ctx.scan.root_id(), ctx.scan.scan_id(), new_access, mod_date, size,
+                symlink_target, unix, Some(reason), counts,
             )?;
 
             Ok(())
         })
     }
 
-    /// Handle item modification (metadata and/or access change)
+    /// Handle item modification (metadata, symlink target, Unix metadata and/or
+    /// access change). `reason` records which of these changed.
+    #[allow(clippy::too_many_arguments)]
     fn handle_item_modification(
         ctx: &mut ScanContext,
         existing_item: &ExistingItem,
         item_type: ItemType,
         mod_date: Option<i64>,
         size: Option<i64>,
-        _old_access: Access,
+        symlink_target: Option<&str>,
+        unix: Option<&UnixMetadata>,
+        reason: ChangeReason,
         new_access: Access,
     ) -> Result<(), FsPulseError> {
         ctx.execute_batch_write(|c| {
@@ -1085,7 +1664,7 @@ impl Scanner {
             // last confirmed, and temporal queries resolve via MAX(first_scan_id))
             ItemVersion::insert_with_carry_forward(
                 c, existing_item.item_id, ctx.scan.root_id(), ctx.scan.scan_id(),
-                false, new_access, mod_date, size, &existing_item.version,
+                false, new_access, mod_date, size, symlink_target, unix, reason, &existing_item.version,
                 item_type == ItemType::Directory,
             )?;
 
@@ -1093,37 +1672,48 @@ impl Scanner {
         })
     }
 
-    /// Handle item with no changes - just update last_scan
+    /// Handle item with no changes - just update last_scan (and fill in a
+    /// symlink target or Unix metadata not recorded by an earlier version of fsPulse)
     fn handle_item_no_change(
         ctx: &mut ScanContext,
         existing_item: &ExistingItem,
+        target_backfill: Option<&str>,
+        unix_backfill: Option<&UnixMetadata>,
     ) -> Result<(), FsPulseError> {
         ctx.execute_batch_write(|c| {
             UndoLog::log_update(c, &existing_item.version)?;
             ItemVersion::touch_last_scan(c, existing_item.version.item_id(), existing_item.version.item_version(), ctx.scan.scan_id())?;
+            if let Some(target) = target_backfill {
+                ItemVersion::backfill_symlink_target(c, existing_item.version.item_id(), existing_item.version.item_version(), target)?;
+            }
+            if let Some(unix) = unix_backfill {
+                ItemVersion::backfill_unix(c, existing_item.version.item_id(), existing_item.version.item_version(), unix)?;
+            }
 
             Ok(())
         })
     }
 
     /// Handle a new item (never seen before)
+    #[allow(clippy::too_many_arguments)]
     fn handle_new_item(
         ctx: &mut ScanContext,
         item_type: ItemType,
         path_str: &str,
         mod_date: Option<i64>,
         size: Option<i64>,
-        dir_read_error: bool,
+        symlink_target: Option<&str>,
+        unix: Option<&UnixMetadata>,
+        dir_access: Access,
         meta_error: bool,
     ) -> Result<(), FsPulseError> {
         // For new items, calculate access based on error conditions
-        // Priority: meta_error (can't stat) > dir_read_error (can stat, can't read_dir) > Ok
+        // Priority: meta_error (can't stat) > dir_access (ReadError or MountPoint for
+        // directories, always Ok for everything else)
         let new_access = if meta_error {
             Access::MetaError
-        } else if dir_read_error {
-            Access::ReadError
         } else {
-            Access::Ok
+            dir_access
         };
 
         let file_extension = if item_type == ItemType::File {
@@ -1131,12 +1721,12 @@ impl Scanner {
         } else {
             None
         };
-        let has_validator = file_extension.as_deref().is_some_and(validator::has_validator_extension);
+        let has_validator = file_extension.as_deref().is_some_and(|ext| ValidatorRegistry::global().has_validator_extension(ext));
 
         ctx.execute_batch_write(|c| {
             let item_id = ItemIdentity::insert(c, ctx.scan.root_id(), path_str, item_type, has_validator, file_extension.as_deref())?;
             let counts = if item_type == ItemType::Directory { Some((0, 0, 0, 0)) } else { None };
-            ItemVersion::insert_initial(c, item_id, ctx.scan.root_id(), ctx.scan.scan_id(), new_access, mod_date, size, counts)?;
+            ItemVersion::insert_initial(c, item_id, ctx.scan.root_id(), ctx.scan.scan_id(), new_access, mod_date, size, symlink_target, unix, None, counts)?;
 
             Ok(())
         })
@@ -11,12 +11,12 @@ use serde::Serialize;
 use std::fmt;
 
 const SQL_SCAN_ID_OR_LATEST: &str =
-    "SELECT scan_id, root_id, schedule_id, started_at, ended_at, was_restarted, state, is_hash, hash_all, is_val, file_count, folder_count, total_size, new_hash_suspect_count, new_val_invalid_count, add_count, modify_count, delete_count, val_unknown_count, val_valid_count, val_invalid_count, val_no_validator_count, hash_unknown_count, hash_baseline_count, hash_suspect_count, error
+    "SELECT scan_id, root_id, schedule_id, started_at, ended_at, was_restarted, state, is_hash, hash_all, is_val, file_count, folder_count, total_size, new_hash_suspect_count, new_val_invalid_count, add_count, modify_count, delete_count, val_unknown_count, val_valid_count, val_invalid_count, val_no_validator_count, hash_unknown_count, hash_baseline_count, hash_suspect_count, error, move_count, hash_sampled
         FROM scans
         WHERE scan_id = IFNULL(?1, (SELECT MAX(scan_id) FROM scans))";
 
 const SQL_LATEST_FOR_ROOT: &str =
-    "SELECT scan_id, root_id, schedule_id, started_at, ended_at, was_restarted, state, is_hash, hash_all, is_val, file_count, folder_count, total_size, new_hash_suspect_count, new_val_invalid_count, add_count, modify_count, delete_count, val_unknown_count, val_valid_count, val_invalid_count, val_no_validator_count, hash_unknown_count, hash_baseline_count, hash_suspect_count, error
+    "SELECT scan_id, root_id, schedule_id, started_at, ended_at, was_restarted, state, is_hash, hash_all, is_val, file_count, folder_count, total_size, new_hash_suspect_count, new_val_invalid_count, add_count, modify_count, delete_count, val_unknown_count, val_valid_count, val_invalid_count, val_no_validator_count, hash_unknown_count, hash_baseline_count, hash_suspect_count, error, move_count, hash_sampled
         FROM scans
         WHERE root_id = ?
         ORDER BY scan_id DESC LIMIT 1";
@@ -27,6 +27,9 @@ pub enum HashMode {
     None = 0,
     New = 1,
     All = 2,
+    /// Hash files that have no hash yet and sample the rest, fully hashing
+    /// any whose sample changed
+    Sampled = 3,
 }
 
 impl HashMode {
@@ -35,6 +38,7 @@ impl HashMode {
             0 => Some(Self::None),
             1 => Some(Self::New),
             2 => Some(Self::All),
+            3 => Some(Self::Sampled),
             _ => None,
         }
     }
@@ -66,6 +70,10 @@ impl AnalysisSpec {
         self.hash_mode == HashMode::All
     }
 
+    pub fn hash_sampled(&self) -> bool {
+        self.hash_mode == HashMode::Sampled
+    }
+
     pub fn is_val(&self) -> bool {
         self.is_val
     }
@@ -93,6 +101,7 @@ pub struct Scan {
     add_count: Option<i64>,
     modify_count: Option<i64>,
     delete_count: Option<i64>,
+    move_count: Option<i64>,
     val_unknown_count: Option<i64>,
     val_valid_count: Option<i64>,
     val_invalid_count: Option<i64>,
@@ -221,6 +230,7 @@ impl Scan {
             add_count: None,
             modify_count: None,
             delete_count: None,
+            move_count: None,
             val_unknown_count: None,
             val_valid_count: None,
             val_invalid_count: None,
@@ -239,8 +249,8 @@ impl Scan {
         analysis_spec: &AnalysisSpec,
     ) -> Result<Self, FsPulseError> {
         let (scan_id, started_at): (i64, i64) = conn.query_row(
-            "INSERT INTO scans (root_id, schedule_id, state, is_hash, hash_all, is_val, started_at)
-             VALUES (?, ?, ?, ?, ?, ?, strftime('%s', 'now', 'utc'))
+            "INSERT INTO scans (root_id, schedule_id, state, is_hash, hash_all, hash_sampled, is_val, started_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, strftime('%s', 'now', 'utc'))
              RETURNING scan_id, started_at",
             params![
                 root.root_id(),
@@ -248,6 +258,7 @@ impl Scan {
                 ScanState::Scanning.as_i64(),
                 analysis_spec.is_hash() as i64,
                 analysis_spec.hash_all() as i64,
+                analysis_spec.hash_sampled() as i64,
                 analysis_spec.is_val() as i64,
             ],
             |row| Ok((row.get(0)?, row.get(1)?)),
@@ -286,9 +297,11 @@ impl Scan {
             .query_row(query, params![query_param], |row| {
                 let is_hash = row.get(7)?;
                 let hash_all = row.get(8)?;
-                let hash_mode = match (is_hash, hash_all) {
-                    (false, _) => HashMode::None,
-                    (_, true) => HashMode::All,
+                let hash_sampled = row.get(27)?;
+                let hash_mode = match (is_hash, hash_all, hash_sampled) {
+                    (false, _, _) => HashMode::None,
+                    (_, true, _) => HashMode::All,
+                    (_, _, true) => HashMode::Sampled,
                     _ => HashMode::New,
                 };
 
@@ -314,6 +327,7 @@ impl Scan {
                     add_count: row.get(15)?,
                     modify_count: row.get(16)?,
                     delete_count: row.get(17)?,
+                    move_count: row.get(26)?,
                     val_unknown_count: row.get(18)?,
                     val_valid_count: row.get(19)?,
                     val_invalid_count: row.get(20)?,
@@ -408,6 +422,43 @@ impl Scan {
         }
     }
 
+    /// Check that `scan_id` is a completed scan of `root_id`, or when None find
+    /// the root's most recent completed scan. Returns (scan_id, started_at).
+    pub fn resolve_completed(
+        conn: &Connection,
+        root_id: i64,
+        scan_id: Option<i64>,
+    ) -> Result<(i64, i64), FsPulseError> {
+        let completed = ScanState::Completed.as_i64();
+        let result: Option<(i64, i64)> = match scan_id {
+            Some(scan_id) => conn
+                .query_row(
+                    "SELECT scan_id, started_at FROM scans
+                     WHERE scan_id = ? AND root_id = ? AND state = ?",
+                    params![scan_id, root_id, completed],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .optional()?,
+            None => conn
+                .query_row(
+                    "SELECT scan_id, started_at FROM scans
+                     WHERE root_id = ? AND state = ?
+                     ORDER BY scan_id DESC LIMIT 1",
+                    params![root_id, completed],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .optional()?,
+        };
+
+        result.ok_or_else(|| match scan_id {
+            Some(scan_id) => FsPulseError::Error(format!(
+                "Scan {} is not a completed scan of root {}",
+                scan_id, root_id
+            )),
+            None => FsPulseError::Error(format!("Root {} has no completed scan", root_id)),
+        })
+    }
+
     pub fn set_total_size(
         &mut self,
         conn: &Connection,
@@ -503,16 +554,26 @@ impl Scan {
                             )
                             .unwrap_or(0);
 
-                        // Compute add_count, modify_count, delete_count from versions created this scan
-                        let (add_count, modify_count, delete_count): (i64, i64, i64) = c
+                        // Compute add_count, modify_count, delete_count, move_count from versions
+                        // created this scan. Excluded tombstones (hidden by a root rule) are not
+                        // deletes. A move is counted once, on the added side; neither half of the
+                        // pair counts as an add or a delete.
+                        let (add_count, modify_count, delete_count, move_count): (i64, i64, i64, i64) = c
                             .query_row(
                                 "SELECT
                                     COALESCE(COUNT(*) FILTER (WHERE iv.is_deleted = 0
+                                        AND iv.moved_from_item_id IS NULL
                                         AND (pv.item_id IS NULL OR pv.is_deleted = 1)), 0),
                                     COALESCE(COUNT(*) FILTER (WHERE iv.is_deleted = 0
                                         AND pv.item_id IS NOT NULL AND pv.is_deleted = 0), 0),
-                                    COALESCE(COUNT(*) FILTER (WHERE iv.is_deleted = 1
-                                        AND pv.item_id IS NOT NULL AND pv.is_deleted = 0), 0)
+                                    COALESCE(COUNT(*) FILTER (WHERE iv.is_deleted = 1 AND iv.is_excluded = 0
+                                        AND pv.item_id IS NOT NULL AND pv.is_deleted = 0
+                                        AND NOT EXISTS (
+                                            SELECT 1 FROM item_versions mv
+                                            WHERE mv.moved_from_item_id = iv.item_id
+                                              AND mv.first_scan_id = iv.first_scan_id
+                                        )), 0),
+                                    COALESCE(COUNT(*) FILTER (WHERE iv.moved_from_item_id IS NOT NULL), 0)
                                  FROM item_versions iv
                                  LEFT JOIN item_versions pv ON pv.item_id = iv.item_id
                                      AND pv.first_scan_id = (
@@ -521,9 +582,9 @@ impl Scan {
                                      )
                                  WHERE iv.root_id = ? AND iv.first_scan_id = ?",
                                 params![self.root_id, self.scan_id],
-                                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+                                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
                             )
-                            .unwrap_or((0, 0, 0));
+                            .unwrap_or((0, 0, 0, 0));
 
                         // Compute val/hash state counts for alive files at this scan.
                         //
@@ -571,6 +632,7 @@ impl Scan {
                                 add_count = ?,
                                 modify_count = ?,
                                 delete_count = ?,
+                                move_count = ?,
                                 val_unknown_count = ?,
                                 val_valid_count = ?,
                                 val_invalid_count = ?,
@@ -584,7 +646,7 @@ impl Scan {
                             params![
                                 file_count, folder_count,
                                 new_hash_suspect_count, new_val_invalid_count,
-                                add_count, modify_count, delete_count,
+                                add_count, modify_count, delete_count, move_count,
                                 vu, vv, vi, vn, hu, hv, hs,
                                 ScanState::Completed.as_i64(),
                                 self.scan_id,
@@ -598,13 +660,13 @@ impl Scan {
 
                         Ok((file_count, folder_count,
                             new_hash_suspect_count, new_val_invalid_count,
-                            add_count, modify_count, delete_count,
+                            add_count, modify_count, delete_count, move_count,
                             vu, vv, vi, vn, hu, hv, hs))
                     })?;
 
                 let (file_count, folder_count,
                      new_hash_suspect_count, new_val_invalid_count,
-                     add_count, modify_count, delete_count,
+                     add_count, modify_count, delete_count, move_count,
                      vu, vv, vi, vn, hu, hv, hs) = result;
 
                 // Update in-memory struct
@@ -616,6 +678,7 @@ impl Scan {
                 self.add_count = Some(add_count);
                 self.modify_count = Some(modify_count);
                 self.delete_count = Some(delete_count);
+                self.move_count = Some(move_count);
                 self.val_unknown_count = Some(vu);
                 self.val_valid_count = Some(vv);
                 self.val_invalid_count = Some(vi);
@@ -863,6 +926,19 @@ mod tests {
         assert_eq!(HashMode::None, HashMode::None);
         assert_ne!(HashMode::None, HashMode::New);
         assert_ne!(HashMode::New, HashMode::All);
+
+        for mode in [HashMode::None, HashMode::New, HashMode::All, HashMode::Sampled] {
+            assert_eq!(HashMode::from_i32(mode.as_i32()), Some(mode));
+        }
+        assert_eq!(HashMode::from_i32(4), None);
+    }
+
+    #[test]
+    fn test_analysis_spec_sampled() {
+        let spec = AnalysisSpec::new(HashMode::Sampled, false);
+        assert!(spec.is_hash());
+        assert!(!spec.hash_all());
+        assert!(spec.hash_sampled());
     }
 
     #[test]
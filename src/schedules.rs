@@ -1,7 +1,14 @@
 use crate::db::Database;
 use crate::error::FsPulseError;
+use crate::integrity::reverify::ReverifyBudget;
+use crate::io_throttle::ReadLimits;
+use crate::run_window::RunWindow;
+use crate::scan_scope::ScanScope;
 use crate::scans::HashMode;
-use crate::task::{CompactDatabaseSettings, CompactDatabaseTask, ScanSettings, ScanTask, Task, TaskStatus, TaskType};
+use crate::task::{
+    CompactDatabaseSettings, CompactDatabaseTask, CompareRootsSettings, CompareRootsTask,
+    ExportManifestSettings, ExportManifestTask, ScanSettings, ScanTask, Task, TaskStatus, TaskType,
+};
 use rusqlite::{Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
 
@@ -73,6 +80,8 @@ impl IntervalUnit {
 pub enum SourceType {
     Manual = 0,
     Scheduled = 1,
+    /// Queued by a root's filesystem watcher
+    Watch = 2,
 }
 
 impl SourceType {
@@ -80,6 +89,7 @@ impl SourceType {
         match value {
             0 => Some(Self::Manual),
             1 => Some(Self::Scheduled),
+            2 => Some(Self::Watch),
             _ => None,
         }
     }
@@ -101,6 +111,10 @@ pub struct CreateScheduleParams {
     pub interval_unit: Option<IntervalUnit>,
     pub hash_mode: HashMode,
     pub is_val: bool,
+    pub subpath: Option<String>,
+    pub reverify: Option<ReverifyBudget>,
+    pub read_limits: Option<ReadLimits>,
+    pub run_window: Option<RunWindow>,
 }
 
 /// A scan schedule configuration
@@ -130,6 +144,18 @@ pub struct Schedule {
     pub hash_mode: HashMode,
     pub is_val: bool,
 
+    // Root-relative directory to scan (None = whole root)
+    pub subpath: Option<String>,
+
+    // Re-hash the files with the stalest hashes within this budget (None = don't)
+    pub reverify: Option<ReverifyBudget>,
+
+    // Read limits replacing the global ones during analysis (None = use global)
+    pub read_limits: Option<ReadLimits>,
+
+    // Daily local time range its scans may run in (None = use global)
+    pub run_window: Option<RunWindow>,
+
     // Metadata
     pub created_at: i64, // Unix timestamp (UTC)
     pub updated_at: i64, // Unix timestamp (UTC)
@@ -226,9 +252,35 @@ impl Schedule {
 
         // day_of_month validation is done in Monthly match arm above
 
+        if let Some(ref subpath) = self.subpath {
+            ScanScope::validate_subpath(subpath).map_err(|_| {
+                format!("subpath '{}' must be relative to the root and may not contain '..'", subpath)
+            })?;
+        }
+
+        if let Some(ref reverify) = self.reverify {
+            reverify.validate(self.hash_mode)?;
+        }
+
+        if let Some(ref read_limits) = self.read_limits {
+            read_limits.validate()?;
+        }
+
+        if let Some(ref run_window) = self.run_window {
+            run_window.validate()?;
+        }
+
         Ok(())
     }
 
+    /// Settings for the scan tasks this schedule queues
+    fn scan_settings(&self) -> ScanSettings {
+        ScanSettings::new(self.hash_mode, self.is_val)
+            .with_subpaths(self.subpath.iter().cloned().collect())
+            .with_reverify(self.reverify)
+            .with_read_limits(self.read_limits)
+    }
+
     /// Validate time_of_day is in 'HH:MM' format
     fn validate_time_of_day(&self, time: &str) -> Result<(), String> {
         let parts: Vec<&str> = time.split(':').collect();
@@ -544,6 +596,10 @@ impl Schedule {
             interval_unit: params.interval_unit,
             hash_mode: params.hash_mode,
             is_val: params.is_val,
+            subpath: params.subpath,
+            reverify: params.reverify,
+            read_limits: params.read_limits,
+            run_window: params.run_window,
             created_at: now,
             updated_at: now,
         };
@@ -567,9 +623,12 @@ impl Schedule {
                 root_id, enabled, schedule_name, schedule_type,
                 time_of_day, days_of_week, day_of_month,
                 interval_value, interval_unit,
-                hash_mode, is_val,
+                hash_mode, is_val, subpath,
+                reverify_days, reverify_max_bytes, reverify_max_percent,
+                read_limit_mb_per_sec, read_limit_iops,
+                window_start, window_end,
                 created_at, updated_at
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             RETURNING schedule_id",
                 rusqlite::params![
                     schedule.root_id,
@@ -583,6 +642,14 @@ impl Schedule {
                     schedule.interval_unit.map(|u| u.as_i32()),
                     schedule.hash_mode.as_i32(),
                     schedule.is_val,
+                    schedule.subpath,
+                    schedule.reverify.and_then(|b| b.older_than_days),
+                    schedule.reverify.and_then(|b| b.max_bytes),
+                    schedule.reverify.and_then(|b| b.max_percent),
+                    schedule.read_limits.and_then(|l| l.mb_per_sec),
+                    schedule.read_limits.and_then(|l| l.iops),
+                    schedule.run_window.as_ref().map(|w| &w.start),
+                    schedule.run_window.as_ref().map(|w| &w.end),
                     schedule.created_at,
                     schedule.updated_at,
                 ],
@@ -591,7 +658,7 @@ impl Schedule {
             .map_err(FsPulseError::DatabaseError)?;
 
         // Build task_settings using typed struct
-        let task_settings = ScanSettings::new(schedule.hash_mode, schedule.is_val).to_json()?;
+        let task_settings = schedule.scan_settings().to_json()?;
 
         // Insert task entry (schedule is enabled by default)
         conn.execute(
@@ -628,7 +695,10 @@ impl Schedule {
                 time_of_day, days_of_week, day_of_month,
                 interval_value, interval_unit,
                 hash_mode, is_val,
-                created_at, updated_at
+                created_at, updated_at, subpath,
+                reverify_days, reverify_max_bytes, reverify_max_percent,
+                read_limit_mb_per_sec, read_limit_iops,
+                window_start, window_end
             FROM scan_schedules
             WHERE schedule_id = ?",
             [schedule_id],
@@ -669,6 +739,10 @@ impl Schedule {
                         )
                     })?,
                     is_val: row.get(11)?,
+                    subpath: row.get(14)?,
+                    reverify: ReverifyBudget::from_columns(row.get(15)?, row.get(16)?, row.get(17)?),
+                    read_limits: ReadLimits::from_columns(row.get(18)?, row.get(19)?),
+                    run_window: RunWindow::from_columns(row.get(20)?, row.get(21)?),
                     created_at: row.get(12)?,
                     updated_at: row.get(13)?,
                 })
@@ -706,6 +780,14 @@ impl Schedule {
                 interval_unit = ?,
                 hash_mode = ?,
                 is_val = ?,
+                subpath = ?,
+                reverify_days = ?,
+                reverify_max_bytes = ?,
+                reverify_max_percent = ?,
+                read_limit_mb_per_sec = ?,
+                read_limit_iops = ?,
+                window_start = ?,
+                window_end = ?,
                 updated_at = ?
             WHERE schedule_id = ? AND deleted_at IS NULL",
                 rusqlite::params![
@@ -718,6 +800,14 @@ impl Schedule {
                     self.interval_unit.map(|u| u.as_i32()),
                     self.hash_mode.as_i32(),
                     self.is_val,
+                    self.subpath,
+                    self.reverify.and_then(|b| b.older_than_days),
+                    self.reverify.and_then(|b| b.max_bytes),
+                    self.reverify.and_then(|b| b.max_percent),
+                    self.read_limits.and_then(|l| l.mb_per_sec),
+                    self.read_limits.and_then(|l| l.iops),
+                    self.run_window.as_ref().map(|w| &w.start),
+                    self.run_window.as_ref().map(|w| &w.end),
                     now,
                     self.schedule_id,
                 ],
@@ -731,10 +821,10 @@ impl Schedule {
             )));
         }
 
-        // Update run_at on the Pending task (if one exists)
+        // Update run_at and settings on the Pending task (if one exists)
         conn.execute(
-            "UPDATE tasks SET run_at = ? WHERE schedule_id = ? AND status = 0",
-            rusqlite::params![next_scan_time, self.schedule_id],
+            "UPDATE tasks SET run_at = ?, task_settings = ? WHERE schedule_id = ? AND status = 0",
+            rusqlite::params![next_scan_time, self.scan_settings().to_json()?, self.schedule_id],
         )
         .map_err(FsPulseError::DatabaseError)?;
 
@@ -802,8 +892,7 @@ impl Schedule {
                         FsPulseError::Error(format!("Schedule {} not found", schedule_id))
                     })?;
 
-                    let task_settings =
-                        ScanSettings::new(schedule.hash_mode, schedule.is_val).to_json()?;
+                    let task_settings = schedule.scan_settings().to_json()?;
 
                     c.execute(
                         "INSERT INTO tasks (
@@ -872,6 +961,13 @@ impl Schedule {
     }
 }
 
+/// Selects the columns of a TaskRow from `tasks t`, joined to its root and schedule
+const TASK_ROW_SELECT: &str = "SELECT t.task_id, t.task_type, t.status, t.root_id, r.root_path,
+        t.schedule_id, t.task_settings, t.task_state, t.source, s.window_start, s.window_end
+     FROM tasks t
+     LEFT JOIN roots r ON t.root_id = r.root_id
+     LEFT JOIN scan_schedules s ON t.schedule_id = s.schedule_id";
+
 /// A row from the tasks table, used internally to dispatch to task-type-specific factories
 struct TaskRow {
     task_id: i64,
@@ -882,6 +978,50 @@ struct TaskRow {
     schedule_id: Option<i64>,
     task_settings: String,
     task_state: Option<String>,
+    source: SourceType,
+    schedule_window: Option<RunWindow>,
+}
+
+impl TaskRow {
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+        Ok(TaskRow {
+            task_id: row.get(0)?,
+            task_type: TaskType::from_i64(row.get(1)?),
+            status: TaskStatus::from_i64(row.get(2)?),
+            root_id: row.get(3)?,
+            root_path: row.get(4)?,
+            schedule_id: row.get(5)?,
+            task_settings: row.get(6)?,
+            task_state: row.get(7)?,
+            source: SourceType::from_i32(row.get(8)?).ok_or_else(|| {
+                rusqlite::Error::InvalidColumnType(
+                    8,
+                    "source".to_string(),
+                    rusqlite::types::Type::Integer,
+                )
+            })?,
+            schedule_window: RunWindow::from_columns(row.get(9)?, row.get(10)?),
+        })
+    }
+
+    fn run_window(&self) -> Option<RunWindow> {
+        task_run_window(self.source, self.schedule_window.clone())
+    }
+
+    fn is_window_open(&self, now: i64) -> bool {
+        self.run_window().is_none_or(|window| window.is_open_at(now))
+    }
+}
+
+/// The run window a task may run in. Manual tasks run whenever they're
+/// queued. Scheduled tasks use their schedule's window, falling back (like
+/// watch tasks) to the global one.
+fn task_run_window(source: SourceType, schedule_window: Option<RunWindow>) -> Option<RunWindow> {
+    match source {
+        SourceType::Manual => None,
+        SourceType::Scheduled => schedule_window.or_else(RunWindow::global),
+        SourceType::Watch => RunWindow::global(),
+    }
 }
 
 /// Task operations
@@ -896,7 +1036,7 @@ struct TaskRow {
 /// - `root_id`: Optional root reference
 /// - `schedule_id`: Optional schedule reference
 /// - `run_at`: When to run (0 for immediate)
-/// - `source`: Manual (0) or Scheduled (1)
+/// - `source`: Manual (0), Scheduled (1) or Watch (2)
 /// - `task_settings`: JSON config (e.g., ScanSettings)
 /// - `task_state`: Execution state JSON (e.g., ScanTaskState with scan_id and HWM)
 /// - `created_at`, `started_at`, `completed_at`: Lifecycle timestamps
@@ -914,6 +1054,9 @@ impl TaskEntry {
         root_id: i64,
         hash_mode: HashMode,
         is_val: bool,
+        subpath: Option<String>,
+        reverify: Option<ReverifyBudget>,
+        read_limits: Option<ReadLimits>,
     ) -> Result<(), FsPulseError> {
         let now = chrono::Utc::now().timestamp();
 
@@ -930,7 +1073,11 @@ impl TaskEntry {
         }
 
         // Build task_settings using typed struct
-        let task_settings = ScanSettings::new(hash_mode, is_val).to_json()?;
+        let task_settings = ScanSettings::new(hash_mode, is_val)
+            .with_subpaths(subpath.into_iter().collect())
+            .with_reverify(reverify)
+            .with_read_limits(read_limits)
+            .to_json()?;
 
         // Create task entry with Pending status
         conn.execute(
@@ -951,6 +1098,60 @@ impl TaskEntry {
         Ok(())
     }
 
+    /// Queue a scan of the root-relative directories a root's watcher saw change
+    /// (empty for the whole root), due now. If a watch scan of the root is
+    /// already pending, the directories are merged into it instead.
+    /// Must be called within a transaction for atomicity.
+    pub fn create_or_merge_watch(
+        conn: &rusqlite::Connection,
+        root_id: i64,
+        subpaths: &[String],
+    ) -> Result<(), FsPulseError> {
+        let pending: Option<(i64, String)> = conn
+            .query_row(
+                "SELECT task_id, task_settings FROM tasks
+                 WHERE task_type = ? AND status = 0 AND root_id = ? AND source = ?",
+                rusqlite::params![TaskType::Scan.as_i64(), root_id, SourceType::Watch.as_i32()],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()
+            .map_err(FsPulseError::DatabaseError)?;
+
+        if let Some((task_id, settings_json)) = pending {
+            let mut settings = ScanSettings::from_json(&settings_json)?;
+            settings.merge_subpaths(subpaths);
+            conn.execute(
+                "UPDATE tasks SET task_settings = ? WHERE task_id = ?",
+                rusqlite::params![settings.to_json()?, task_id],
+            )
+            .map_err(FsPulseError::DatabaseError)?;
+            return Ok(());
+        }
+
+        let now = chrono::Utc::now().timestamp();
+        let task_settings = ScanSettings::new(HashMode::New, true)
+            .with_subpaths(subpaths.to_vec())
+            .to_json()?;
+
+        conn.execute(
+            "INSERT INTO tasks (
+                task_type, status, root_id, run_at,
+                source, task_settings, created_at
+            ) VALUES (?, 0, ?, ?, ?, ?, ?)",
+            rusqlite::params![
+                TaskType::Scan.as_i64(),
+                root_id,
+                now,
+                SourceType::Watch.as_i32(),
+                task_settings,
+                now,
+            ],
+        )
+        .map_err(FsPulseError::DatabaseError)?;
+
+        Ok(())
+    }
+
     /// Create a new compact database task entry, or no-op if one already exists.
     /// This is the singleton check — prevents duplicate pending compact tasks.
     /// Must be called within a transaction for atomicity.
@@ -989,70 +1190,132 @@ impl TaskEntry {
         Ok(())
     }
 
+    /// Create a manual export manifest task entry for a root.
+    /// Must be called within a transaction for atomicity.
+    pub fn create_export_manifest(
+        conn: &rusqlite::Connection,
+        root_id: i64,
+        settings: &ExportManifestSettings,
+    ) -> Result<(), FsPulseError> {
+        let now = chrono::Utc::now().timestamp();
+
+        // Verify root exists (within same transaction)
+        let root_exists = conn
+            .query_row("SELECT 1 FROM roots WHERE root_id = ?", [root_id], |_| {
+                Ok(())
+            })
+            .optional()
+            .map_err(FsPulseError::DatabaseError)?;
+
+        if root_exists.is_none() {
+            return Err(FsPulseError::Error(format!("Root {} not found", root_id)));
+        }
+
+        conn.execute(
+            "INSERT INTO tasks (
+                task_type, status, root_id, run_at,
+                source, task_settings, created_at
+            ) VALUES (?, 0, ?, 0, ?, ?, ?)",
+            rusqlite::params![
+                TaskType::ExportManifest.as_i64(),
+                root_id,
+                SourceType::Manual.as_i32(),
+                settings.to_json()?,
+                now,
+            ],
+        )
+        .map_err(FsPulseError::DatabaseError)?;
+
+        Ok(())
+    }
+
+    /// Create a manual compare roots task entry. The entry's root_id is the left root.
+    /// Must be called within a transaction for atomicity.
+    pub fn create_compare_roots(
+        conn: &rusqlite::Connection,
+        settings: &CompareRootsSettings,
+    ) -> Result<(), FsPulseError> {
+        let now = chrono::Utc::now().timestamp();
+
+        // Verify both roots exist (within same transaction)
+        for root_id in [settings.left.root_id, settings.right.root_id] {
+            let root_exists = conn
+                .query_row("SELECT 1 FROM roots WHERE root_id = ?", [root_id], |_| {
+                    Ok(())
+                })
+                .optional()
+                .map_err(FsPulseError::DatabaseError)?;
+
+            if root_exists.is_none() {
+                return Err(FsPulseError::Error(format!("Root {} not found", root_id)));
+            }
+        }
+
+        conn.execute(
+            "INSERT INTO tasks (
+                task_type, status, root_id, run_at,
+                source, task_settings, created_at
+            ) VALUES (?, 0, ?, 0, ?, ?, ?)",
+            rusqlite::params![
+                TaskType::CompareRoots.as_i64(),
+                settings.left.root_id,
+                SourceType::Manual.as_i32(),
+                settings.to_json()?,
+                now,
+            ],
+        )
+        .map_err(FsPulseError::DatabaseError)?;
+
+        Ok(())
+    }
+
     /// Find the next task to process (generic across all task types)
     ///
     /// Priority order:
     /// 1. Running tasks (resume interrupted task from crash/restart)
     /// 2. Pending manual tasks (FIFO by task_id)
     /// 3. Pending scheduled tasks that are due (by run_at, then task_id)
+    /// 4. Pending watch tasks (due when queued)
+    ///
+    /// Tasks whose run window is closed are passed over. A Running task
+    /// outside its window was suspended when the window closed; it holds the
+    /// queue until the window opens again.
     fn find_next_pending_task(conn: &Connection, now: i64) -> Result<Option<TaskRow>, FsPulseError> {
         // Step 1: Check for Running task (resume case — process died while executing)
         let active = conn
             .query_row(
-                "SELECT t.task_id, t.task_type, t.status, t.root_id, r.root_path,
-                        t.schedule_id, t.task_settings, t.task_state
-                 FROM tasks t
-                 LEFT JOIN roots r ON t.root_id = r.root_id
-                 WHERE t.status = 1 LIMIT 1",
+                &format!("{} WHERE t.status = 1 LIMIT 1", TASK_ROW_SELECT),
                 [],
-                |row| {
-                    Ok(TaskRow {
-                        task_id: row.get(0)?,
-                        task_type: TaskType::from_i64(row.get(1)?),
-                        status: TaskStatus::from_i64(row.get(2)?),
-                        root_id: row.get(3)?,
-                        root_path: row.get(4)?,
-                        schedule_id: row.get(5)?,
-                        task_settings: row.get(6)?,
-                        task_state: row.get(7)?,
-                    })
-                },
+                TaskRow::from_row,
             )
             .optional()
             .map_err(FsPulseError::DatabaseError)?;
 
         if let Some(row) = active {
-            return Ok(Some(row));
+            return Ok(row.is_window_open(now).then_some(row));
         }
 
         // Step 2: Find highest priority Pending work (manual first, then scheduled due)
-        let work = conn
-            .query_row(
-                "SELECT t.task_id, t.task_type, t.status, t.root_id, r.root_path,
-                        t.schedule_id, t.task_settings, t.task_state
-                 FROM tasks t
-                 LEFT JOIN roots r ON t.root_id = r.root_id
-                 WHERE t.status = 0 AND (t.source = ? OR t.run_at <= ?)
-                 ORDER BY t.source ASC, t.run_at ASC, t.task_id ASC
-                 LIMIT 1",
-                rusqlite::params![SourceType::Manual.as_i32(), now],
-                |row| {
-                    Ok(TaskRow {
-                        task_id: row.get(0)?,
-                        task_type: TaskType::from_i64(row.get(1)?),
-                        status: TaskStatus::from_i64(row.get(2)?),
-                        root_id: row.get(3)?,
-                        root_path: row.get(4)?,
-                        schedule_id: row.get(5)?,
-                        task_settings: row.get(6)?,
-                        task_state: row.get(7)?,
-                    })
-                },
-            )
-            .optional()
+        let mut stmt = conn
+            .prepare(&format!(
+                "{} WHERE t.status = 0 AND (t.source = ? OR t.run_at <= ?)
+                 ORDER BY t.source ASC, t.run_at ASC, t.task_id ASC",
+                TASK_ROW_SELECT
+            ))
+            .map_err(FsPulseError::DatabaseError)?;
+
+        let rows = stmt
+            .query_map(rusqlite::params![SourceType::Manual.as_i32(), now], TaskRow::from_row)
             .map_err(FsPulseError::DatabaseError)?;
 
-        Ok(work)
+        for row in rows {
+            let row = row.map_err(FsPulseError::DatabaseError)?;
+            if row.is_window_open(now) {
+                return Ok(Some(row));
+            }
+        }
+
+        Ok(None)
     }
 
     /// Atomically activate a Pending task: mark it Running and, if scheduled,
@@ -1167,12 +1430,44 @@ impl TaskEntry {
                 TaskType::CompactDatabase => {
                     Box::new(CompactDatabaseTask::new(row.task_id))
                 }
+                TaskType::ExportManifest => {
+                    let root_id = row.root_id.ok_or_else(|| {
+                        FsPulseError::Error(format!(
+                            "Export manifest task {} has NULL root_id",
+                            row.task_id
+                        ))
+                    })?;
+                    Box::new(ExportManifestTask::new(
+                        row.task_id,
+                        root_id,
+                        &row.task_settings,
+                    )?)
+                }
+                TaskType::CompareRoots => {
+                    Box::new(CompareRootsTask::new(row.task_id, &row.task_settings)?)
+                }
             };
 
             Ok(Some(task))
         })
     }
 
+    /// The run window a task may run in, or None if it may run any time. Read
+    /// afresh so that changes to the schedule or the global setting apply to a
+    /// task that's already running.
+    pub fn get_run_window(conn: &Connection, task_id: i64) -> Result<Option<RunWindow>, FsPulseError> {
+        let row = conn
+            .query_row(
+                &format!("{} WHERE t.task_id = ?", TASK_ROW_SELECT),
+                [task_id],
+                TaskRow::from_row,
+            )
+            .optional()
+            .map_err(FsPulseError::DatabaseError)?;
+
+        Ok(row.and_then(|row| row.run_window()))
+    }
+
     /// Complete a task by setting its terminal status and completion timestamp.
     /// Tasks are never deleted — they become historical records.
     pub fn complete_task(conn: &Connection, task_id: i64, terminal_status: TaskStatus) -> Result<(), FsPulseError> {
@@ -1219,12 +1514,14 @@ impl TaskEntry {
     }
 
     /// Get upcoming tasks for display in UI
-    /// Returns task entries with root_path and schedule_name joined
+    /// Returns task entries with root_path and schedule_name joined, and the state
+    /// of each task's run window
     /// Limited to next 10 upcoming tasks, ordered by priority (Running first, then manual, then by time)
-    /// If tasks_are_paused is true, includes the Running task as first row
+    /// If include_running is true (paused, or suspended outside its run window),
+    /// includes the Running task as first row
     pub fn get_upcoming_tasks(
         limit: i64,
-        tasks_are_paused: bool,
+        include_running: bool,
     ) -> Result<Vec<UpcomingTask>, FsPulseError> {
         let now = chrono::Utc::now().timestamp();
         let conn = Database::get_connection()?;
@@ -1233,7 +1530,7 @@ impl TaskEntry {
         let running = TaskStatus::Running.as_i64();
 
         // Build WHERE clause based on pause state
-        let where_clause = if tasks_are_paused {
+        let where_clause = if include_running {
             // Include Running and Pending tasks
             format!("WHERE q.status IN ({}, {})", pending, running)
         } else {
@@ -1251,7 +1548,9 @@ impl TaskEntry {
                 s.schedule_name,
                 q.run_at,
                 q.source,
-                q.status
+                q.status,
+                s.window_start,
+                s.window_end
              FROM tasks q
              LEFT JOIN roots r ON q.root_id = r.root_id
              LEFT JOIN scan_schedules s ON q.schedule_id = s.schedule_id
@@ -1266,7 +1565,19 @@ impl TaskEntry {
         let tasks = stmt
             .query_map([limit], |row| {
                 let run_at: i64 = row.get(6)?;
-                let source: i32 = row.get(7)?;
+                let source = SourceType::from_i32(row.get(7)?).ok_or_else(|| {
+                    rusqlite::Error::InvalidColumnType(
+                        7,
+                        "source".to_string(),
+                        rusqlite::types::Type::Integer,
+                    )
+                })?;
+                let run_window =
+                    task_run_window(source, RunWindow::from_columns(row.get(9)?, row.get(10)?));
+                let window_opens_at = run_window
+                    .as_ref()
+                    .map(|window| window.next_open_at(now))
+                    .filter(|&opens_at| opens_at > now);
 
                 Ok(UpcomingTask {
                     task_id: row.get(0)?,
@@ -1276,15 +1587,12 @@ impl TaskEntry {
                     schedule_id: row.get(4)?,
                     schedule_name: row.get(5)?,
                     run_at,
-                    source: SourceType::from_i32(source).ok_or_else(|| {
-                        rusqlite::Error::InvalidColumnType(
-                            7,
-                            "source".to_string(),
-                            rusqlite::types::Type::Integer,
-                        )
-                    })?,
+                    source,
                     is_ready: run_at <= now,
                     status: row.get(8)?,
+                    run_window,
+                    window_open: window_opens_at.is_none(),
+                    window_opens_at,
                 })
             })
             .map_err(FsPulseError::DatabaseError)?
@@ -1481,6 +1789,9 @@ pub struct UpcomingTask {
     pub source: SourceType,
     pub is_ready: bool,  // true if run_at <= now (eligible to start)
     pub status: i64,     // TaskStatus as integer (0=Pending, 1=Running)
+    pub run_window: Option<RunWindow>, // Window the task may run in (None = any time)
+    pub window_open: bool,             // false if the task is waiting for its window
+    pub window_opens_at: Option<i64>,  // When a closed window next opens (Unix timestamp)
 }
 
 /// A completed task for history display (internal — mapped to API response type before serialization)
@@ -1528,7 +1839,11 @@ pub fn list_schedules() -> Result<Vec<ScheduleWithRoot>, FsPulseError> {
             s.hash_mode, s.is_val,
             s.created_at, s.updated_at,
             r.root_path,
-            q.run_at
+            q.run_at,
+            s.subpath,
+            s.reverify_days, s.reverify_max_bytes, s.reverify_max_percent,
+            s.read_limit_mb_per_sec, s.read_limit_iops,
+            s.window_start, s.window_end
         FROM scan_schedules s
         INNER JOIN roots r ON s.root_id = r.root_id
         LEFT JOIN tasks q ON s.schedule_id = q.schedule_id AND q.status = 0
@@ -1574,6 +1889,10 @@ pub fn list_schedules() -> Result<Vec<ScheduleWithRoot>, FsPulseError> {
                     )
                 })?,
                 is_val: row.get(11)?,
+                subpath: row.get(16)?,
+                reverify: ReverifyBudget::from_columns(row.get(17)?, row.get(18)?, row.get(19)?),
+                read_limits: ReadLimits::from_columns(row.get(20)?, row.get(21)?),
+                run_window: RunWindow::from_columns(row.get(22)?, row.get(23)?),
                 created_at: row.get(12)?,
                 updated_at: row.get(13)?,
             },
@@ -1631,6 +1950,10 @@ mod tests {
             interval_unit: None,
             hash_mode: HashMode::New,
             is_val: false,
+            subpath: None,
+            reverify: None,
+            read_limits: None,
+            run_window: None,
             created_at: 0,
             updated_at: 0,
         };
@@ -1653,6 +1976,10 @@ mod tests {
             interval_unit: None,
             hash_mode: HashMode::New,
             is_val: false,
+            subpath: None,
+            reverify: None,
+            read_limits: None,
+            run_window: None,
             created_at: 0,
             updated_at: 0,
         };
@@ -1675,6 +2002,10 @@ mod tests {
             interval_unit: Some(IntervalUnit::Minutes),
             hash_mode: HashMode::None,
             is_val: false,
+            subpath: None,
+            reverify: None,
+            read_limits: None,
+            run_window: None,
             created_at: 0,
             updated_at: 0,
         };
@@ -1697,6 +2028,10 @@ mod tests {
             interval_unit: None,
             hash_mode: HashMode::New,
             is_val: false,
+            subpath: None,
+            reverify: None,
+            read_limits: None,
+            run_window: None,
             created_at: 0,
             updated_at: 0,
         };
@@ -1719,6 +2054,10 @@ mod tests {
             interval_unit: None,
             hash_mode: HashMode::All,
             is_val: true,
+            subpath: None,
+            reverify: None,
+            read_limits: None,
+            run_window: None,
             created_at: 0,
             updated_at: 0,
         };
@@ -1741,6 +2080,10 @@ mod tests {
             interval_unit: None,
             hash_mode: HashMode::New,
             is_val: true,
+            subpath: None,
+            reverify: None,
+            read_limits: None,
+            run_window: None,
             created_at: 0,
             updated_at: 0,
         };
@@ -1763,6 +2106,10 @@ mod tests {
             interval_unit: None,
             hash_mode: HashMode::New,
             is_val: true,
+            subpath: None,
+            reverify: None,
+            read_limits: None,
+            run_window: None,
             created_at: 0,
             updated_at: 0,
         };
@@ -1770,6 +2117,134 @@ mod tests {
         assert!(schedule.validate().is_err());
     }
 
+    #[test]
+    fn test_validate_schedule_subpath() {
+        let mut schedule = Schedule {
+            schedule_id: 1,
+            root_id: 1,
+            enabled: true,
+            schedule_name: "Photos".to_string(),
+            schedule_type: ScheduleType::Daily,
+            time_of_day: Some("02:00".to_string()),
+            days_of_week: None,
+            day_of_month: None,
+            interval_value: None,
+            interval_unit: None,
+            hash_mode: HashMode::New,
+            is_val: false,
+            subpath: Some("Photos/2019".to_string()),
+            reverify: None,
+            read_limits: None,
+            run_window: None,
+            created_at: 0,
+            updated_at: 0,
+        };
+
+        assert!(schedule.validate().is_ok());
+        assert_eq!(schedule.scan_settings().subpaths, vec!["Photos/2019"]);
+
+        schedule.subpath = Some("../Photos".to_string());
+        assert!(schedule.validate().is_err());
+
+        schedule.subpath = None;
+        assert!(schedule.scan_settings().subpaths.is_empty());
+    }
+
+    #[test]
+    fn test_validate_schedule_reverify() {
+        let mut schedule = Schedule {
+            schedule_id: 1,
+            root_id: 1,
+            enabled: true,
+            schedule_name: "Archive".to_string(),
+            schedule_type: ScheduleType::Daily,
+            time_of_day: Some("02:00".to_string()),
+            days_of_week: None,
+            day_of_month: None,
+            interval_value: None,
+            interval_unit: None,
+            hash_mode: HashMode::New,
+            is_val: false,
+            subpath: None,
+            reverify: Some(ReverifyBudget { max_percent: Some(5.0), ..Default::default() }),
+            read_limits: None,
+            run_window: None,
+            created_at: 0,
+            updated_at: 0,
+        };
+
+        assert!(schedule.validate().is_ok());
+        assert_eq!(schedule.scan_settings().reverify, schedule.reverify);
+
+        // Hash All already re-hashes everything
+        schedule.hash_mode = HashMode::All;
+        assert!(schedule.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_schedule_read_limits() {
+        let mut schedule = Schedule {
+            schedule_id: 1,
+            root_id: 1,
+            enabled: true,
+            schedule_name: "NAS".to_string(),
+            schedule_type: ScheduleType::Daily,
+            time_of_day: Some("02:00".to_string()),
+            days_of_week: None,
+            day_of_month: None,
+            interval_value: None,
+            interval_unit: None,
+            hash_mode: HashMode::All,
+            is_val: true,
+            subpath: None,
+            reverify: None,
+            read_limits: Some(ReadLimits { mb_per_sec: Some(40), iops: None }),
+            run_window: None,
+            created_at: 0,
+            updated_at: 0,
+        };
+
+        assert!(schedule.validate().is_ok());
+        assert_eq!(schedule.scan_settings().read_limits, schedule.read_limits);
+
+        schedule.read_limits = Some(ReadLimits { mb_per_sec: None, iops: Some(u32::MAX) });
+        assert!(schedule.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_schedule_run_window() {
+        let night = RunWindow::parse("22:00-06:00").unwrap();
+        let mut schedule = Schedule {
+            schedule_id: 1,
+            root_id: 1,
+            enabled: true,
+            schedule_name: "Nightly".to_string(),
+            schedule_type: ScheduleType::Daily,
+            time_of_day: Some("23:00".to_string()),
+            days_of_week: None,
+            day_of_month: None,
+            interval_value: None,
+            interval_unit: None,
+            hash_mode: HashMode::All,
+            is_val: true,
+            subpath: None,
+            reverify: None,
+            read_limits: None,
+            run_window: Some(night.clone()),
+            created_at: 0,
+            updated_at: 0,
+        };
+
+        assert!(schedule.validate().is_ok());
+
+        // A schedule's own window applies to its tasks; manual tasks have none
+        assert_eq!(task_run_window(SourceType::Scheduled, Some(night.clone())), Some(night));
+        assert_eq!(task_run_window(SourceType::Manual, schedule.run_window.clone()), None);
+
+        schedule.run_window = Some(RunWindow { start: "22:00".to_string(), end: "6am".to_string() });
+        assert!(schedule.validate().is_err());
+    }
+
     // ========================================
     // Tests for calculate_next_scan_time
     // ========================================
@@ -1792,6 +2267,10 @@ mod tests {
             interval_unit: Some(IntervalUnit::Minutes),
             hash_mode: HashMode::None,
             is_val: false,
+            subpath: None,
+            reverify: None,
+            read_limits: None,
+            run_window: None,
             created_at: 0,
             updated_at: 0,
         };
@@ -1816,6 +2295,10 @@ mod tests {
             interval_unit: Some(IntervalUnit::Hours),
             hash_mode: HashMode::None,
             is_val: false,
+            subpath: None,
+            reverify: None,
+            read_limits: None,
+            run_window: None,
             created_at: 0,
             updated_at: 0,
         };
@@ -1840,6 +2323,10 @@ mod tests {
             interval_unit: Some(IntervalUnit::Days),
             hash_mode: HashMode::None,
             is_val: false,
+            subpath: None,
+            reverify: None,
+            read_limits: None,
+            run_window: None,
             created_at: 0,
             updated_at: 0,
         };
@@ -1866,6 +2353,10 @@ mod tests {
             interval_unit: None,
             hash_mode: HashMode::None,
             is_val: false,
+            subpath: None,
+            reverify: None,
+            read_limits: None,
+            run_window: None,
             created_at: 0,
             updated_at: 0,
         };
@@ -1899,6 +2390,10 @@ mod tests {
             interval_unit: None,
             hash_mode: HashMode::None,
             is_val: false,
+            subpath: None,
+            reverify: None,
+            read_limits: None,
+            run_window: None,
             created_at: 0,
             updated_at: 0,
         };
@@ -1932,6 +2427,10 @@ mod tests {
             interval_unit: None,
             hash_mode: HashMode::None,
             is_val: false,
+            subpath: None,
+            reverify: None,
+            read_limits: None,
+            run_window: None,
             created_at: 0,
             updated_at: 0,
         };
@@ -1965,6 +2464,10 @@ mod tests {
             interval_unit: None,
             hash_mode: HashMode::None,
             is_val: false,
+            subpath: None,
+            reverify: None,
+            read_limits: None,
+            run_window: None,
             created_at: 0,
             updated_at: 0,
         };
@@ -1998,6 +2501,10 @@ mod tests {
             interval_unit: None,
             hash_mode: HashMode::None,
             is_val: false,
+            subpath: None,
+            reverify: None,
+            read_limits: None,
+            run_window: None,
             created_at: 0,
             updated_at: 0,
         };
@@ -2031,6 +2538,10 @@ mod tests {
             interval_unit: None,
             hash_mode: HashMode::None,
             is_val: false,
+            subpath: None,
+            reverify: None,
+            read_limits: None,
+            run_window: None,
             created_at: 0,
             updated_at: 0,
         };
@@ -2064,6 +2575,10 @@ mod tests {
             interval_unit: None,
             hash_mode: HashMode::None,
             is_val: false,
+            subpath: None,
+            reverify: None,
+            read_limits: None,
+            run_window: None,
             created_at: 0,
             updated_at: 0,
         };
@@ -2097,6 +2612,10 @@ mod tests {
             interval_unit: None,
             hash_mode: HashMode::None,
             is_val: false,
+            subpath: None,
+            reverify: None,
+            read_limits: None,
+            run_window: None,
             created_at: 0,
             updated_at: 0,
         };
@@ -2130,6 +2649,10 @@ mod tests {
             interval_unit: None,
             hash_mode: HashMode::None,
             is_val: false,
+            subpath: None,
+            reverify: None,
+            read_limits: None,
+            run_window: None,
             created_at: 0,
             updated_at: 0,
         };
@@ -2163,6 +2686,10 @@ mod tests {
             interval_unit: None,
             hash_mode: HashMode::None,
             is_val: false,
+            subpath: None,
+            reverify: None,
+            read_limits: None,
+            run_window: None,
             created_at: 0,
             updated_at: 0,
         };
@@ -2202,6 +2729,10 @@ mod tests {
             interval_unit: None,
             hash_mode: HashMode::None,
             is_val: false,
+            subpath: None,
+            reverify: None,
+            read_limits: None,
+            run_window: None,
             created_at: 0,
             updated_at: 0,
         };
@@ -2241,6 +2772,10 @@ mod tests {
             interval_unit: None,
             hash_mode: HashMode::None,
             is_val: false,
+            subpath: None,
+            reverify: None,
+            read_limits: None,
+            run_window: None,
             created_at: 0,
             updated_at: 0,
         };
@@ -30,6 +30,7 @@ use crate::db::migration::{maintenance, MigrationProgress, ReadyFlag};
 use crate::error::FsPulseError;
 use crate::mcp::FsPulseMcp;
 use crate::task_manager::TaskManager;
+use crate::watcher::{RootWatchers, WATCH_POLL_INTERVAL};
 
 use rmcp::transport::streamable_http_server::{
     StreamableHttpService, StreamableHttpServerConfig,
@@ -239,6 +240,8 @@ impl WebServer {
             // Task scheduling endpoints
             .route("/api/tasks/scan", post(api::tasks::schedule_scan))
             .route("/api/tasks/compact-database", post(api::tasks::schedule_compact_database))
+            .route("/api/tasks/export-manifest", post(api::tasks::schedule_export_manifest))
+            .route("/api/tasks/compare-roots", post(api::tasks::schedule_compare_roots))
             .route("/api/tasks/{task_id}/stop", post(api::scans::stop_task))
             // Pause endpoints
             .route("/api/pause", post(api::scans::set_pause))
@@ -254,6 +257,28 @@ impl WebServer {
                 "/api/roots/{root_id}/schedule-count",
                 get(api::roots::get_schedule_count),
             )
+            .route(
+                "/api/roots/{root_id}/rules",
+                get(api::roots::get_root_rules).put(api::roots::update_root_rules),
+            )
+            .route(
+                "/api/roots/{root_id}/options",
+                get(api::roots::get_root_options).put(api::roots::update_root_options),
+            )
+            .route(
+                "/api/roots/{root_id}/manifest",
+                get(api::roots::export_manifest),
+            )
+            // Comparison report endpoints
+            .route("/api/comparisons", get(api::comparisons::list_comparisons))
+            .route(
+                "/api/comparisons/{comparison_id}",
+                get(api::comparisons::get_comparison).delete(api::comparisons::delete_comparison),
+            )
+            .route(
+                "/api/comparisons/{comparison_id}/diffs",
+                get(api::comparisons::get_diffs),
+            )
             // Schedule endpoints
             .route("/api/schedules", get(api::schedules::list_schedules))
             .route("/api/schedules", post(api::schedules::create_schedule))
@@ -421,6 +446,29 @@ fn start_app_services(shutdown_tx: broadcast::Sender<()>) -> Result<(), FsPulseE
         }
     });
 
+    // Start watching roots that have watch enabled. Setting up a recursive watch
+    // walks the root, so polls run on the blocking pool.
+    let mut watch_shutdown_rx = shutdown_tx.subscribe();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(WATCH_POLL_INTERVAL);
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    match tokio::task::spawn_blocking(RootWatchers::poll).await {
+                        Ok(Err(e)) => log::error!("Root watcher error: {}", e),
+                        Err(e) => log::error!("Root watcher task failed: {}", e),
+                        Ok(Ok(())) => {}
+                    }
+                }
+                _ = watch_shutdown_rx.recv() => {
+                    RootWatchers::shutdown();
+                    break;
+                }
+            }
+        }
+    });
+
     Ok(())
 }
 
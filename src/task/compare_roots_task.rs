@@ -0,0 +1,184 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::compare::{CompareSideSpec, ComparisonSide, RootComparison};
+use crate::db::Database;
+use crate::error::FsPulseError;
+
+use super::progress::TaskProgress;
+use super::task_type::TaskType;
+use super::traits::Task;
+
+// ============================================================================
+// CompareRootsSettings - Compare-specific task settings
+// ============================================================================
+
+/// Settings for a compare roots task: the two sides to align by relative
+/// path. The task's root_id is the left root.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CompareRootsSettings {
+    pub left: CompareSideSpec,
+    pub right: CompareSideSpec,
+}
+
+impl CompareRootsSettings {
+    /// Serialize to JSON string for storage in database
+    pub fn to_json(&self) -> Result<String, FsPulseError> {
+        serde_json::to_string(self).map_err(|e| {
+            FsPulseError::Error(format!("Failed to serialize CompareRootsSettings: {}", e))
+        })
+    }
+
+    /// Deserialize from JSON string retrieved from database
+    pub fn from_json(json: &str) -> Result<Self, FsPulseError> {
+        serde_json::from_str(json).map_err(|e| {
+            FsPulseError::Error(format!("Failed to deserialize CompareRootsSettings: {}", e))
+        })
+    }
+}
+
+// ============================================================================
+// CompareRootsTask
+// ============================================================================
+
+/// Compares two roots (or folders within them) as of chosen scans and stores
+/// the result as a comparison report.
+///
+/// The report is written as the comparison goes and only becomes visible once
+/// complete. Stopping or failing removes the partial report, and a paused
+/// comparison starts over when it resumes.
+pub struct CompareRootsTask {
+    task_id: i64,
+    settings: CompareRootsSettings,
+}
+
+impl CompareRootsTask {
+    pub fn new(task_id: i64, settings_json: &str) -> Result<Self, FsPulseError> {
+        Ok(Self {
+            task_id,
+            settings: CompareRootsSettings::from_json(settings_json)?,
+        })
+    }
+}
+
+impl Task for CompareRootsTask {
+    fn run(
+        &mut self,
+        progress: Arc<TaskProgress>,
+        interrupt_token: Arc<AtomicBool>,
+    ) -> Result<(), FsPulseError> {
+        // A report left behind by an interrupted run of this task
+        RootComparison::delete_incomplete(self.task_id)?;
+
+        let (left, right) = {
+            let conn = Database::get_connection()?;
+            (
+                ComparisonSide::resolve(&conn, &self.settings.left)?,
+                ComparisonSide::resolve(&conn, &self.settings.right)?,
+            )
+        };
+        progress.set_phase(&format!(
+            "Comparing scan #{} with scan #{}",
+            left.scan_id, right.scan_id
+        ));
+        progress.set_indeterminate_progress("0 files");
+
+        let comparison = RootComparison::run(self.task_id, left, right, |compared| {
+            if interrupt_token.load(Ordering::Acquire) {
+                return Err(FsPulseError::TaskInterrupted);
+            }
+            if compared % 1000 == 0 {
+                progress.set_indeterminate_progress(&format!("{} files", compared));
+            }
+            Ok(())
+        })?;
+
+        let counts = comparison.counts;
+        progress.add_breadcrumb(&format!(
+            "Comparison #{}: {} match, {} unverified, {} differences",
+            comparison.comparison_id,
+            counts.match_count,
+            counts.unverified_count,
+            counts.diff_count()
+        ));
+
+        Ok(())
+    }
+
+    fn task_type(&self) -> TaskType {
+        TaskType::CompareRoots
+    }
+
+    fn task_id(&self) -> i64 {
+        self.task_id
+    }
+
+    fn active_root_id(&self) -> Option<i64> {
+        Some(self.settings.left.root_id)
+    }
+
+    fn action(&self) -> &str {
+        "Comparing"
+    }
+
+    fn display_target(&self) -> String {
+        format!(
+            "root {} with root {}",
+            self.settings.left.root_id, self.settings.right.root_id
+        )
+    }
+
+    fn on_stopped(&mut self) -> Result<(), FsPulseError> {
+        RootComparison::delete_incomplete(self.task_id)
+    }
+
+    fn on_error(&mut self, _error_msg: &str) -> Result<(), FsPulseError> {
+        RootComparison::delete_incomplete(self.task_id)
+    }
+
+    fn is_exclusive(&self) -> bool {
+        false
+    }
+
+    fn is_stoppable(&self) -> bool {
+        true
+    }
+
+    fn is_pausable(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_roots_settings_round_trip() {
+        let settings = CompareRootsSettings {
+            left: CompareSideSpec {
+                root_id: 1,
+                scan_id: Some(7),
+                subpath: Some("photos".to_string()),
+            },
+            right: CompareSideSpec {
+                root_id: 2,
+                scan_id: None,
+                subpath: None,
+            },
+        };
+        let json = settings.to_json().unwrap();
+        let restored = CompareRootsSettings::from_json(&json).unwrap();
+        assert_eq!(settings, restored);
+    }
+
+    #[test]
+    fn test_compare_roots_settings_defaults() {
+        let settings =
+            CompareRootsSettings::from_json(r#"{"left":{"root_id":1},"right":{"root_id":2}}"#).unwrap();
+        assert_eq!(settings.left.scan_id, None);
+        assert_eq!(settings.right.subpath, None);
+    }
+}
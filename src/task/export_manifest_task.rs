@@ -0,0 +1,237 @@
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::db::Database;
+use crate::error::FsPulseError;
+use crate::integrity::manifest_export::{ExportSpec, ManifestExport};
+
+use super::progress::TaskProgress;
+use super::task_type::TaskType;
+use super::traits::Task;
+
+// ============================================================================
+// ExportManifestSettings - Export-specific task settings
+// ============================================================================
+
+/// Settings for an export manifest task: what to export, and the file on the
+/// server to write it to. The root is the task's root_id.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ExportManifestSettings {
+    #[serde(flatten)]
+    pub spec: ExportSpec,
+    /// Absolute path of the manifest file. Replaced if it exists.
+    pub output_path: String,
+}
+
+impl ExportManifestSettings {
+    /// Check that the output path is absolute and its directory exists
+    pub fn validate(&self) -> Result<(), FsPulseError> {
+        let path = Path::new(&self.output_path);
+        if !path.is_absolute() {
+            return Err(FsPulseError::Error(format!(
+                "Invalid output_path '{}': must be an absolute path",
+                self.output_path
+            )));
+        }
+        if path.is_dir() {
+            return Err(FsPulseError::Error(format!(
+                "Invalid output_path '{}': is a directory",
+                self.output_path
+            )));
+        }
+        if !path.parent().is_some_and(Path::is_dir) {
+            return Err(FsPulseError::Error(format!(
+                "Invalid output_path '{}': directory does not exist",
+                self.output_path
+            )));
+        }
+        Ok(())
+    }
+
+    /// Serialize to JSON string for storage in database
+    pub fn to_json(&self) -> Result<String, FsPulseError> {
+        serde_json::to_string(self).map_err(|e| {
+            FsPulseError::Error(format!("Failed to serialize ExportManifestSettings: {}", e))
+        })
+    }
+
+    /// Deserialize from JSON string retrieved from database
+    pub fn from_json(json: &str) -> Result<Self, FsPulseError> {
+        serde_json::from_str(json).map_err(|e| {
+            FsPulseError::Error(format!(
+                "Failed to deserialize ExportManifestSettings: {}",
+                e
+            ))
+        })
+    }
+}
+
+// ============================================================================
+// ExportManifestTask
+// ============================================================================
+
+/// Writes the Baseline hashes of a root (or a directory in it) to a manifest
+/// file on the server.
+///
+/// The manifest is written to a `.partial` file next to the output and renamed
+/// into place when complete, so the output is never left half written. The
+/// task only reads the database: stopping it just removes the partial file,
+/// and a paused export starts over when it resumes.
+pub struct ExportManifestTask {
+    task_id: i64,
+    root_id: i64,
+    settings: ExportManifestSettings,
+}
+
+impl ExportManifestTask {
+    pub fn new(task_id: i64, root_id: i64, settings_json: &str) -> Result<Self, FsPulseError> {
+        Ok(Self {
+            task_id,
+            root_id,
+            settings: ExportManifestSettings::from_json(settings_json)?,
+        })
+    }
+
+    fn partial_path(&self) -> PathBuf {
+        PathBuf::from(format!("{}.partial", self.settings.output_path))
+    }
+
+    fn remove_partial(&self) -> Result<(), FsPulseError> {
+        match fs::remove_file(self.partial_path()) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Task for ExportManifestTask {
+    fn run(
+        &mut self,
+        progress: Arc<TaskProgress>,
+        interrupt_token: Arc<AtomicBool>,
+    ) -> Result<(), FsPulseError> {
+        self.settings.validate()?;
+
+        let conn = Database::get_connection()?;
+        let export = ManifestExport::resolve(&conn, self.root_id, &self.settings.spec)?;
+        progress.set_phase(&format!("Exporting scan #{}", export.scan_id()));
+        progress.set_indeterminate_progress("0 files");
+
+        let partial_path = self.partial_path();
+        let mut out = BufWriter::new(File::create(&partial_path)?);
+        let summary = export.write(&conn, &mut out, |exported| {
+            if interrupt_token.load(Ordering::Acquire) {
+                return Err(FsPulseError::TaskInterrupted);
+            }
+            if exported % 1000 == 0 {
+                progress.set_indeterminate_progress(&format!("{} files", exported));
+            }
+            Ok(())
+        })?;
+        drop(out);
+
+        fs::rename(&partial_path, &self.settings.output_path)?;
+
+        info!(
+            "Exported {} hashes from scan {} of {} to {} ({} files without a Baseline hash skipped)",
+            summary.exported,
+            export.scan_id(),
+            export.base_path(),
+            self.settings.output_path,
+            summary.skipped
+        );
+        progress.add_breadcrumb(&format!(
+            "Exported {} files ({} without a Baseline hash skipped)",
+            summary.exported, summary.skipped
+        ));
+
+        Ok(())
+    }
+
+    fn task_type(&self) -> TaskType {
+        TaskType::ExportManifest
+    }
+
+    fn task_id(&self) -> i64 {
+        self.task_id
+    }
+
+    fn active_root_id(&self) -> Option<i64> {
+        Some(self.root_id)
+    }
+
+    fn action(&self) -> &str {
+        "Exporting manifest"
+    }
+
+    fn display_target(&self) -> String {
+        self.settings.output_path.clone()
+    }
+
+    fn on_stopped(&mut self) -> Result<(), FsPulseError> {
+        self.remove_partial()
+    }
+
+    fn on_error(&mut self, _error_msg: &str) -> Result<(), FsPulseError> {
+        self.remove_partial()
+    }
+
+    fn is_exclusive(&self) -> bool {
+        false
+    }
+
+    fn is_stoppable(&self) -> bool {
+        true
+    }
+
+    fn is_pausable(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integrity::manifest_export::ExportFormat;
+
+    fn settings(output_path: &str) -> ExportManifestSettings {
+        ExportManifestSettings {
+            spec: ExportSpec {
+                subpath: Some("photos".to_string()),
+                format: ExportFormat::Json,
+                ..Default::default()
+            },
+            output_path: output_path.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_export_manifest_settings_round_trip() {
+        let settings = settings("/tmp/SHA256SUMS");
+        let json = settings.to_json().unwrap();
+        let restored = ExportManifestSettings::from_json(&json).unwrap();
+        assert_eq!(settings, restored);
+    }
+
+    #[test]
+    fn test_export_manifest_settings_defaults() {
+        let settings = ExportManifestSettings::from_json(r#"{"output_path":"/tmp/x"}"#).unwrap();
+        assert_eq!(settings.spec, ExportSpec::default());
+    }
+
+    #[test]
+    fn test_export_manifest_settings_validate() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let out = temp.path().join("SHA256SUMS");
+        assert!(settings(&out.to_string_lossy()).validate().is_ok());
+        assert!(settings("SHA256SUMS").validate().is_err());
+        assert!(settings(&temp.path().to_string_lossy()).validate().is_err());
+        assert!(settings(&temp.path().join("missing/SHA256SUMS").to_string_lossy()).validate().is_err());
+    }
+}
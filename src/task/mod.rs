@@ -1,4 +1,6 @@
 mod compact_database_task;
+mod compare_roots_task;
+mod export_manifest_task;
 mod progress;
 mod scan_task;
 mod task_status;
@@ -6,7 +8,9 @@ mod task_type;
 mod traits;
 
 pub use compact_database_task::{CompactDatabaseSettings, CompactDatabaseTask};
-pub use progress::{BroadcastMessage, TaskProgress};
+pub use compare_roots_task::{CompareRootsSettings, CompareRootsTask};
+pub use export_manifest_task::{ExportManifestSettings, ExportManifestTask};
+pub use progress::{BroadcastMessage, TaskIoThroughput, TaskProgress};
 pub use scan_task::{AnalysisTracker, ScanSettings, ScanTask, ScanTaskState};
 pub use task_status::TaskStatus;
 pub use task_type::TaskType;
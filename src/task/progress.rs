@@ -39,6 +39,13 @@ impl TaskThreadState {
     }
 }
 
+/// Measured read throughput for the protocol
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskIoThroughput {
+    pub bytes_per_sec: u64,
+    pub reads_per_sec: u64,
+}
+
 /// Complete task progress state for the protocol
 /// This is what gets broadcast to web clients
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -57,6 +64,7 @@ pub struct TaskProgressState {
     pub phase: Option<String>,
     pub progress_bar: Option<TaskProgressBar>,
     pub thread_states: Option<Vec<TaskThreadState>>,
+    pub io_throughput: Option<TaskIoThroughput>,
 }
 
 /// Broadcast message type for WebSocket protocol
@@ -102,6 +110,9 @@ struct TaskProgressInternalState {
 
     // Thread states
     thread_states: Vec<TaskThreadState>,
+
+    // Read throughput
+    io_throughput: Option<TaskIoThroughput>,
 }
 
 impl TaskProgressInternalState {
@@ -165,6 +176,7 @@ impl TaskProgress {
                 progress_total: 0,
                 progress_unit: None,
                 thread_states: Vec::new(),
+                io_throughput: None,
             }),
         })
     }
@@ -264,6 +276,15 @@ impl TaskProgress {
         self.state.lock().unwrap().thread_states.clear();
     }
 
+    // ========================================================================
+    // Read Throughput
+    // ========================================================================
+
+    /// Set the measured read throughput, or clear it with None
+    pub fn set_io_throughput(&self, throughput: Option<TaskIoThroughput>) {
+        self.state.lock().unwrap().io_throughput = throughput;
+    }
+
     // ========================================================================
     // Status
     // ========================================================================
@@ -320,6 +341,7 @@ impl TaskProgress {
             } else {
                 Some(state.thread_states.clone())
             },
+            io_throughput: state.io_throughput,
         }
     }
 }
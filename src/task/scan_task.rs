@@ -6,7 +6,11 @@ use serde::{Deserialize, Serialize};
 
 use crate::db::Database;
 use crate::error::FsPulseError;
+use crate::ignore_files::IgnoreFile;
+use crate::integrity::reverify::{ReverifyBudget, ReverifyCutoff};
+use crate::io_throttle::ReadLimits;
 use crate::roots::Root;
+use crate::scan_scope::ScanScope;
 use crate::scanner::Scanner;
 use crate::schedules::TaskEntry;
 use crate::scans::{AnalysisSpec, HashMode, Scan};
@@ -36,6 +40,15 @@ use super::traits::Task;
 pub struct ScanSettings {
     pub hash_mode: HashMode,
     pub is_val: bool,
+    /// Root-relative directories to scan. Empty scans the whole root.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub subpaths: Vec<String>,
+    /// Also re-hash the files with the stalest hashes, within this budget
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reverify: Option<ReverifyBudget>,
+    /// Read limits replacing the global ones for this scan's analysis
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub read_limits: Option<ReadLimits>,
 }
 
 impl ScanSettings {
@@ -44,9 +57,45 @@ impl ScanSettings {
         Self {
             hash_mode,
             is_val,
+            subpaths: Vec::new(),
+            reverify: None,
+            read_limits: None,
         }
     }
 
+    /// Restrict the scan to the given root-relative directories
+    pub fn with_subpaths(mut self, subpaths: Vec<String>) -> Self {
+        self.subpaths = subpaths;
+        self
+    }
+
+    /// Re-verify the files with the stalest hashes within `budget`
+    pub fn with_reverify(mut self, budget: Option<ReverifyBudget>) -> Self {
+        self.reverify = budget;
+        self
+    }
+
+    /// Replace the global read limits for this scan's analysis
+    pub fn with_read_limits(mut self, limits: Option<ReadLimits>) -> Self {
+        self.read_limits = limits;
+        self
+    }
+
+    /// Widen the scan to also cover `subpaths`. Either side being empty (the
+    /// whole root) leaves the whole root.
+    pub fn merge_subpaths(&mut self, subpaths: &[String]) {
+        if self.subpaths.is_empty() {
+            return;
+        }
+        if subpaths.is_empty() {
+            self.subpaths.clear();
+            return;
+        }
+        self.subpaths.extend(subpaths.iter().cloned());
+        self.subpaths.sort();
+        self.subpaths.dedup();
+    }
+
     /// Serialize to JSON string for storage in database
     pub fn to_json(&self) -> Result<String, FsPulseError> {
         serde_json::to_string(self)
@@ -74,6 +123,9 @@ impl ScanSettings {
         Ok(Self {
             hash_mode: legacy.hash_mode,
             is_val: legacy.validate_mode != "None",
+            subpaths: Vec::new(),
+            reverify: None,
+            read_limits: None,
         })
     }
 }
@@ -101,7 +153,22 @@ impl<'de> Deserialize<'de> for ScanSettings {
             return Err(serde::de::Error::missing_field("is_val"));
         };
 
-        Ok(ScanSettings { hash_mode, is_val })
+        let subpaths: Vec<String> = match obj.get("subpaths") {
+            Some(v) => serde_json::from_value(v.clone()).map_err(serde::de::Error::custom)?,
+            None => Vec::new(),
+        };
+
+        let reverify: Option<ReverifyBudget> = match obj.get("reverify") {
+            Some(v) => serde_json::from_value(v.clone()).map_err(serde::de::Error::custom)?,
+            None => None,
+        };
+
+        let read_limits: Option<ReadLimits> = match obj.get("read_limits") {
+            Some(v) => serde_json::from_value(v.clone()).map_err(serde::de::Error::custom)?,
+            None => None,
+        };
+
+        Ok(ScanSettings { hash_mode, is_val, subpaths, reverify, read_limits })
     }
 }
 
@@ -117,6 +184,27 @@ pub struct ScanTaskState {
     #[serde(default)]
     pub scan_id: Option<i64>,
     pub high_water_mark: i64,
+    /// `.fspulseignore` files read during Phase 1, so a resumed scan applies the
+    /// same ignore rules
+    #[serde(default)]
+    pub ignore_files: Vec<IgnoreFile>,
+    /// Phase 1 checkpoint: the topmost directories whose entire subtree has been
+    /// written by this scan. A resumed walk doesn't descend into them.
+    #[serde(default)]
+    pub completed_dirs: Vec<String>,
+    /// Directories the scan is restricted to, resolved from the settings'
+    /// subpaths when the scan is created. Empty for a whole-root scan.
+    #[serde(default)]
+    pub scope: Vec<String>,
+    /// Re-verification budget, copied from the settings when the scan is created
+    #[serde(default)]
+    pub reverify: Option<ReverifyBudget>,
+    /// The files the budget selected, fixed when analysis first starts
+    #[serde(default)]
+    pub reverify_cutoff: Option<ReverifyCutoff>,
+    /// Read limit overrides, copied from the settings when the scan is created
+    #[serde(default)]
+    pub read_limits: Option<ReadLimits>,
 }
 
 impl ScanTaskState {
@@ -124,6 +212,12 @@ impl ScanTaskState {
         Self {
             scan_id: None,
             high_water_mark: 0,
+            ignore_files: Vec::new(),
+            completed_dirs: Vec::new(),
+            scope: Vec::new(),
+            reverify: None,
+            reverify_cutoff: None,
+            read_limits: None,
         }
     }
 
@@ -322,6 +416,11 @@ impl ScanTask {
             let analysis_spec =
                 AnalysisSpec::new(self.settings.hash_mode, self.settings.is_val);
 
+            let scope = ScanScope::resolve(root.root_path(), root.options(), &self.settings.subpaths)?;
+            self.initial_state.scope = scope.to_recorded();
+            self.initial_state.reverify = self.settings.reverify;
+            self.initial_state.read_limits = self.settings.read_limits;
+
             Database::immediate_transaction(&conn, |c| {
                 let scan = Scan::create(c, &root, self.schedule_id, &analysis_spec)?;
 
@@ -457,4 +556,86 @@ mod tests {
         assert_eq!(settings_none.hash_mode, HashMode::All);
         assert!(!settings_none.is_val);
     }
+
+    #[test]
+    fn test_scan_task_state_without_ignore_files() {
+        // Task state written before ignore files were recorded
+        let json = r#"{"scan_id":7,"high_water_mark":42}"#;
+        let state = ScanTaskState::from_task_state(Some(json)).unwrap();
+        assert_eq!(state.scan_id, Some(7));
+        assert_eq!(state.high_water_mark, 42);
+        assert!(state.ignore_files.is_empty());
+        assert!(state.completed_dirs.is_empty());
+        assert!(state.scope.is_empty());
+        assert!(state.reverify.is_none());
+        assert!(state.reverify_cutoff.is_none());
+        assert!(state.read_limits.is_none());
+    }
+
+    #[test]
+    fn test_scan_settings_subpaths() {
+        // Whole-root settings don't write the field, and older JSON reads as whole-root
+        let json = ScanSettings::new(HashMode::New, false).to_json().unwrap();
+        assert!(!json.contains("subpaths"));
+        assert!(ScanSettings::from_json(&json).unwrap().subpaths.is_empty());
+
+        let settings = ScanSettings::new(HashMode::New, false)
+            .with_subpaths(vec!["photos/2024".to_string()]);
+        let restored = ScanSettings::from_json(&settings.to_json().unwrap()).unwrap();
+        assert_eq!(restored, settings);
+    }
+
+    #[test]
+    fn test_scan_settings_reverify() {
+        let json = ScanSettings::new(HashMode::New, false).to_json().unwrap();
+        assert!(!json.contains("reverify"));
+        assert!(ScanSettings::from_json(&json).unwrap().reverify.is_none());
+
+        let settings = ScanSettings::new(HashMode::New, false).with_reverify(Some(ReverifyBudget {
+            older_than_days: Some(90),
+            max_percent: Some(5.0),
+            ..Default::default()
+        }));
+        let restored = ScanSettings::from_json(&settings.to_json().unwrap()).unwrap();
+        assert_eq!(restored, settings);
+    }
+
+    #[test]
+    fn test_scan_settings_read_limits() {
+        let json = ScanSettings::new(HashMode::All, false).to_json().unwrap();
+        assert!(!json.contains("read_limits"));
+        assert!(ScanSettings::from_json(&json).unwrap().read_limits.is_none());
+
+        let settings = ScanSettings::new(HashMode::All, false)
+            .with_read_limits(Some(ReadLimits { mb_per_sec: Some(20), iops: None }));
+        let restored = ScanSettings::from_json(&settings.to_json().unwrap()).unwrap();
+        assert_eq!(restored, settings);
+    }
+
+    #[test]
+    fn test_scan_settings_merge_subpaths() {
+        let mut settings = ScanSettings::new(HashMode::New, true)
+            .with_subpaths(vec!["b".to_string(), "a".to_string()]);
+        settings.merge_subpaths(&["a".to_string(), "c".to_string()]);
+        assert_eq!(settings.subpaths, vec!["a", "b", "c"]);
+
+        settings.merge_subpaths(&[]);
+        assert!(settings.subpaths.is_empty());
+
+        // Once whole-root, stays whole-root
+        settings.merge_subpaths(&["a".to_string()]);
+        assert!(settings.subpaths.is_empty());
+    }
+
+    #[test]
+    fn test_scan_task_state_ignore_files_round_trip() {
+        let mut state = ScanTaskState::new();
+        state.ignore_files.push(IgnoreFile {
+            dir: "/data/photos".to_string(),
+            contents: "*.tmp\n".to_string(),
+        });
+        let json = state.to_json().unwrap();
+        let restored = ScanTaskState::from_task_state(Some(&json)).unwrap();
+        assert_eq!(restored.ignore_files, state.ignore_files);
+    }
 }
@@ -12,6 +12,10 @@ pub enum TaskType {
     Scan = 0,
     #[serde(rename = "compact_database")]
     CompactDatabase = 1,
+    #[serde(rename = "export_manifest")]
+    ExportManifest = 2,
+    #[serde(rename = "compare_roots")]
+    CompareRoots = 3,
 }
 
 impl TaskType {
@@ -23,6 +27,8 @@ impl TaskType {
         match value {
             0 => TaskType::Scan,
             1 => TaskType::CompactDatabase,
+            2 => TaskType::ExportManifest,
+            3 => TaskType::CompareRoots,
             _ => {
                 warn!(
                     "Invalid TaskType value in database: {}, defaulting to Scan",
@@ -38,6 +44,8 @@ impl TaskType {
         match self {
             TaskType::Scan => "S",
             TaskType::CompactDatabase => "CD",
+            TaskType::ExportManifest => "EM",
+            TaskType::CompareRoots => "CR",
         }
     }
 
@@ -45,6 +53,8 @@ impl TaskType {
         match self {
             TaskType::Scan => "Scan",
             TaskType::CompactDatabase => "Compact Database",
+            TaskType::ExportManifest => "Export Manifest",
+            TaskType::CompareRoots => "Compare Roots",
         }
     }
 
@@ -53,9 +63,13 @@ impl TaskType {
             // Full names
             "SCAN" => Some(TaskType::Scan),
             "COMPACT DATABASE" | "COMPACTDATABASE" => Some(TaskType::CompactDatabase),
+            "EXPORT MANIFEST" | "EXPORTMANIFEST" => Some(TaskType::ExportManifest),
+            "COMPARE ROOTS" | "COMPAREROOTS" => Some(TaskType::CompareRoots),
             // Short names
             "S" => Some(TaskType::Scan),
             "CD" => Some(TaskType::CompactDatabase),
+            "EM" => Some(TaskType::ExportManifest),
+            "CR" => Some(TaskType::CompareRoots),
             _ => None,
         }
     }
@@ -81,12 +95,16 @@ mod tests {
     fn test_task_type_integer_values() {
         assert_eq!(TaskType::Scan.as_i64(), 0);
         assert_eq!(TaskType::CompactDatabase.as_i64(), 1);
+        assert_eq!(TaskType::ExportManifest.as_i64(), 2);
+        assert_eq!(TaskType::CompareRoots.as_i64(), 3);
     }
 
     #[test]
     fn test_task_type_from_i64() {
         assert_eq!(TaskType::from_i64(0), TaskType::Scan);
         assert_eq!(TaskType::from_i64(1), TaskType::CompactDatabase);
+        assert_eq!(TaskType::from_i64(2), TaskType::ExportManifest);
+        assert_eq!(TaskType::from_i64(3), TaskType::CompareRoots);
         // Invalid values should default to Scan
         assert_eq!(TaskType::from_i64(999), TaskType::Scan);
         assert_eq!(TaskType::from_i64(-1), TaskType::Scan);
@@ -131,6 +149,16 @@ mod tests {
             TaskType::from_string("cd"),
             Some(TaskType::CompactDatabase)
         );
+        assert_eq!(
+            TaskType::from_string("export manifest"),
+            Some(TaskType::ExportManifest)
+        );
+        assert_eq!(TaskType::from_string("em"), Some(TaskType::ExportManifest));
+        assert_eq!(
+            TaskType::from_string("compare roots"),
+            Some(TaskType::CompareRoots)
+        );
+        assert_eq!(TaskType::from_string("cr"), Some(TaskType::CompareRoots));
         assert_eq!(TaskType::from_string("invalid"), None);
     }
 
@@ -153,6 +181,18 @@ mod tests {
         assert_eq!(json, "\"compact_database\"");
         let restored: TaskType = serde_json::from_str(&json).unwrap();
         assert_eq!(compact, restored);
+
+        let export = TaskType::ExportManifest;
+        let json = serde_json::to_string(&export).unwrap();
+        assert_eq!(json, "\"export_manifest\"");
+        let restored: TaskType = serde_json::from_str(&json).unwrap();
+        assert_eq!(export, restored);
+
+        let compare = TaskType::CompareRoots;
+        let json = serde_json::to_string(&compare).unwrap();
+        assert_eq!(json, "\"compare_roots\"");
+        let restored: TaskType = serde_json::from_str(&json).unwrap();
+        assert_eq!(compare, restored);
     }
 
     #[test]
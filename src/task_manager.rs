@@ -1,6 +1,10 @@
 use crate::db::Database;
 use crate::error::FsPulseError;
-use crate::task::{BroadcastMessage, TaskProgress, TaskStatus};
+use crate::task::{
+    BroadcastMessage, CompareRootsSettings, ExportManifestSettings, TaskProgress, TaskStatus,
+};
+use crate::integrity::reverify::ReverifyBudget;
+use crate::io_throttle::ReadLimits;
 use crate::scans::HashMode;
 use crate::schedules::{TaskEntry, Schedule};
 use log::{error, info, Level};
@@ -40,6 +44,9 @@ pub struct TaskManager {
 struct ActiveTaskInfo {
     task_id: i64,
     is_exclusive: bool,
+    is_pausable: bool,
+    /// Interrupted because its run window closed; resumes when it reopens
+    is_suspended: bool,
     interrupt_token: Arc<AtomicBool>,
     task_progress: Arc<TaskProgress>,
     task_handle: Option<JoinHandle<()>>,
@@ -91,20 +98,45 @@ impl TaskManager {
         Ok(())
     }
 
-    /// Schedule a manual scan task.
-    /// Creates queue entry and immediately tries to start it.
+    /// Schedule a manual scan task, of the whole root or of one root-relative
+    /// directory (`subpath`), optionally re-verifying the stalest hashes within
+    /// `reverify` and replacing the global analysis read limits with
+    /// `read_limits`. Creates queue entry and immediately tries to start it.
     pub fn schedule_manual_scan(
         conn: &Connection,
         root_id: i64,
         hash_mode: HashMode,
         is_val: bool,
+        subpath: Option<String>,
+        reverify: Option<ReverifyBudget>,
+        read_limits: Option<ReadLimits>,
+    ) -> Result<(), FsPulseError> {
+        let mut manager = Self::instance().lock().unwrap();
+
+        manager.check_activity_allowed_locked()?;
+
+        Database::immediate_transaction(conn, |conn| {
+            TaskEntry::create_manual(conn, root_id, hash_mode, is_val, subpath, reverify, read_limits)
+        })?;
+
+        manager.try_start_next_task_locked(conn)?;
+
+        Ok(())
+    }
+
+    /// Queue a scan of the directories a root's watcher saw change, then try to
+    /// start it. Called by the root watchers once activity settles.
+    pub fn schedule_watch_scan(
+        conn: &Connection,
+        root_id: i64,
+        subpaths: &[String],
     ) -> Result<(), FsPulseError> {
         let mut manager = Self::instance().lock().unwrap();
 
         manager.check_activity_allowed_locked()?;
 
         Database::immediate_transaction(conn, |conn| {
-            TaskEntry::create_manual(conn, root_id, hash_mode, is_val)
+            TaskEntry::create_or_merge_watch(conn, root_id, subpaths)
         })?;
 
         manager.try_start_next_task_locked(conn)?;
@@ -129,6 +161,45 @@ impl TaskManager {
         Ok(())
     }
 
+    /// Schedule a manual task that exports a root's Baseline hashes to a
+    /// manifest file. Creates queue entry and immediately tries to start it.
+    pub fn schedule_export_manifest(
+        conn: &Connection,
+        root_id: i64,
+        settings: &ExportManifestSettings,
+    ) -> Result<(), FsPulseError> {
+        let mut manager = Self::instance().lock().unwrap();
+
+        manager.check_activity_allowed_locked()?;
+
+        Database::immediate_transaction(conn, |conn| {
+            TaskEntry::create_export_manifest(conn, root_id, settings)
+        })?;
+
+        manager.try_start_next_task_locked(conn)?;
+
+        Ok(())
+    }
+
+    /// Schedule a manual task that compares two roots and stores the report.
+    /// Creates queue entry and immediately tries to start it.
+    pub fn schedule_compare_roots(
+        conn: &Connection,
+        settings: &CompareRootsSettings,
+    ) -> Result<(), FsPulseError> {
+        let mut manager = Self::instance().lock().unwrap();
+
+        manager.check_activity_allowed_locked()?;
+
+        Database::immediate_transaction(conn, |conn| {
+            TaskEntry::create_compare_roots(conn, settings)
+        })?;
+
+        manager.try_start_next_task_locked(conn)?;
+
+        Ok(())
+    }
+
     /// Create a new schedule
     /// Creates schedule and queue entry atomically
     /// Returns the created schedule with assigned schedule_id
@@ -188,7 +259,11 @@ impl TaskManager {
         let _tmr = timer!(Level::Trace; "TaskManager::poll_queue");
         let mut manager = Self::instance().lock().unwrap();
 
-        // Try to start next task - it's fine if nothing happens
+        // Suspend the active task if its run window has closed
+        manager.suspend_if_window_closed_locked(conn)?;
+
+        // Try to start next task - it's fine if nothing happens. This also
+        // resumes a suspended task once its window opens.
         manager.try_start_next_task_locked(conn)?;
 
         Ok(())
@@ -239,6 +314,49 @@ impl TaskManager {
         manager.is_paused
     }
 
+    fn is_suspended(task_id: i64) -> bool {
+        let manager = Self::instance().lock().unwrap();
+        manager
+            .current_task
+            .as_ref()
+            .is_some_and(|active| active.task_id == task_id && active.is_suspended)
+    }
+
+    /// Suspend the active task if its run window has closed. The task is
+    /// interrupted like a pause: it stays Running in the queue, and
+    /// try_start_next_task_locked resumes it once the window opens again.
+    /// Called with mutex already held
+    fn suspend_if_window_closed_locked(&mut self, conn: &Connection) -> Result<(), FsPulseError> {
+        if self.is_shutting_down {
+            return Ok(());
+        }
+
+        let active = match &mut self.current_task {
+            Some(active)
+                if active.is_pausable
+                    && matches!(active.task_progress.get_status(), TaskStatus::Running) =>
+            {
+                active
+            }
+            _ => return Ok(()),
+        };
+
+        let now = chrono::Utc::now().timestamp();
+        if let Some(window) = TaskEntry::get_run_window(conn, active.task_id)? {
+            if !window.is_open_at(now) {
+                info!(
+                    "Run window {} closed, suspending task (task_id {})",
+                    window, active.task_id
+                );
+                active.is_suspended = true;
+                active.task_progress.set_status(TaskStatus::Pausing);
+                active.interrupt_token.store(true, Ordering::Release);
+            }
+        }
+
+        Ok(())
+    }
+
     /// Shared logic: Find and start next task
     /// Called with mutex already held
     /// Uses the factory function to create a task via the Task trait
@@ -351,6 +469,12 @@ impl TaskManager {
                         if TaskManager::is_paused() {
                             info!("Task (task_id {}) was paused", task_id);
                             task_progress.set_status(TaskStatus::Completed);
+                        } else if TaskManager::is_suspended(task_id) {
+                            info!(
+                                "Task (task_id {}) was suspended until its run window opens",
+                                task_id
+                            );
+                            task_progress.set_status(TaskStatus::Completed);
                         } else {
                             info!("Task (task_id {}) was stopped, rolling back changes", task_id);
                             if let Err(stop_err) = task.on_stopped() {
@@ -396,6 +520,8 @@ impl TaskManager {
         self.current_task = Some(ActiveTaskInfo {
             task_id,
             is_exclusive,
+            is_pausable,
+            is_suspended: false,
             interrupt_token: interrupt_token_for_storage,
             task_progress: task_progress_for_storage,
             task_handle: Some(task_handle),
@@ -411,9 +537,15 @@ impl TaskManager {
         let _tmr = timer!(Level::Trace; "TaskManager::on_task_complete mutex");
         let mut manager = Self::instance().lock().unwrap();
 
+        let is_suspended = manager
+            .current_task
+            .as_ref()
+            .is_some_and(|active| active.task_id == task_id && active.is_suspended);
+
         // Complete the task (set terminal status and timestamp)
-        // If we're shutting down or paused, leave the task as Running for resume on next start
-        if !manager.is_shutting_down && !manager.is_paused {
+        // If we're shutting down, paused or suspended outside the task's run window,
+        // leave the task as Running for resume on next start
+        if !manager.is_shutting_down && !manager.is_paused && !is_suspended {
             // Determine terminal status from task progress.
             // We expect the status to already be terminal (Completed/Stopped/Error) since
             // the spawn_blocking closure sets it before calling on_task_complete. The
@@ -690,16 +822,18 @@ impl TaskManager {
     }
 
     /// Get upcoming tasks, synchronized with task manager state
-    /// If paused, includes the in-progress task as first entry
+    /// If paused, or if the in-progress task is suspended outside its run window,
+    /// includes the in-progress task as first entry
     pub fn get_upcoming_tasks(
         limit: i64,
     ) -> Result<Vec<crate::schedules::UpcomingTask>, FsPulseError> {
         let manager = Self::instance().lock().unwrap();
-        let tasks_are_paused = manager.is_paused;
+        // With no active task, a Running task in the queue is waiting to resume
+        let include_running = manager.is_paused || manager.current_task.is_none();
 
         // Note: TaskEntry::get_upcoming_tasks gets its own connection internally
         // Call schedules method while holding mutex to synchronize with try_start_next_task
-        TaskEntry::get_upcoming_tasks(limit, tasks_are_paused)
+        TaskEntry::get_upcoming_tasks(limit, include_running)
     }
 
     /// Check if task-related activity is allowed.
@@ -7,12 +7,14 @@ use crate::{error::FsPulseError, item_version::ItemVersion};
 ///
 /// - ItemVersion (0): ref_id1 = item_id, ref_id2 = item_version, ref_id3 = 0
 /// - HashVersion (1): ref_id1 = item_id, ref_id2 = item_version, ref_id3 = first_scan_id
+/// - SampledHashVersion (2): ref_id1 = item_id, ref_id2 = item_version, ref_id3 = first_scan_id
 #[repr(i64)]
 #[derive(Debug, Clone, Copy)]
 #[allow(clippy::enum_variant_names)]
 pub enum UndoLogType {
     ItemVersion = 0,
     HashVersion = 1,
+    SampledHashVersion = 2,
 }
 
 /// Transient undo log for batched scan rollback.
@@ -20,8 +22,8 @@ pub enum UndoLogType {
 /// Records the prior values of mutable fields before in-place updates.
 /// Cleared on scan completion. Consumed on scan stop to restore state.
 ///
-/// The schema uses a log_type discriminator to handle item_versions and
-/// hash_versions in a single table. Val state lives on item_versions and
+/// The schema uses a log_type discriminator to handle item_versions,
+/// hash_versions and sampled_hash_versions in a single table. Val state lives on item_versions and
 /// is handled as part of item_version undo.
 pub struct UndoLog;
 
@@ -72,6 +74,30 @@ impl UndoLog {
         Ok(())
     }
 
+    /// Record the current last_scan_id of a sampled_hash_version before extending it.
+    ///
+    /// Called before SampledHashVersion::extend_last_scan to enable rollback.
+    pub fn log_sampled_hash_version_extend(
+        conn: &Connection,
+        item_id: i64,
+        item_version: i64,
+        first_scan_id: i64,
+        old_last_scan_id: i64,
+    ) -> Result<(), FsPulseError> {
+        conn.execute(
+            "INSERT INTO scan_undo_log (log_type, ref_id1, ref_id2, ref_id3, old_last_scan_id)
+             VALUES (?, ?, ?, ?, ?)",
+            params![
+                UndoLogType::SampledHashVersion as i64,
+                item_id,
+                item_version,
+                first_scan_id,
+                old_last_scan_id,
+            ],
+        )?;
+        Ok(())
+    }
+
     /// Clear the entire undo log. Called on scan completion.
     ///
     /// SQLite's truncate optimization makes DELETE without WHERE effectively O(1).
@@ -107,12 +133,15 @@ impl UndoLog {
     ///
     /// Steps, in order:
     /// 1. Replay item_version undo entries — restore pre-scan `last_scan_id`.
-    /// 2. Replay hash_version undo entries — restore pre-scan `last_scan_id`.
-    /// 3. Delete hash_versions created in this scan (before item_versions to
-    ///    satisfy FK constraint without full-table scan).
+    /// 2. Replay hash_version and sampled_hash_version undo entries — restore
+    ///    pre-scan `last_scan_id`.
+    /// 3. Delete hash_versions and sampled_hash_versions created in this scan
+    ///    (before item_versions to satisfy FK constraint without full-table scan).
     /// 4. Delete item_versions created in this scan (val state goes with them).
-    /// 5. NULL out val columns on item_versions whose last_scan_id was reverted
-    ///    and whose val_scan_id now exceeds last_scan_id.
+    /// 5. NULL out val, manifest and content columns on item_versions whose
+    ///    last_scan_id was reverted and whose val_scan_id, manifest_scan_id or
+    ///    content_scan_id now exceeds last_scan_id. A manifest result from an earlier scan that this scan
+    ///    replaced is not restored; the next hashing scan records it again.
     /// 6. Delete orphaned identity rows (items with no remaining versions).
     /// 7. Clear undo log.
     ///
@@ -142,6 +171,15 @@ impl UndoLog {
                AND hash_versions.first_scan_id = u.ref_id3",
             [],
         )?;
+        let restored_samples = conn.execute(
+            "UPDATE sampled_hash_versions SET last_scan_id = u.old_last_scan_id
+             FROM scan_undo_log u
+             WHERE u.log_type = 2
+               AND sampled_hash_versions.item_id = u.ref_id1
+               AND sampled_hash_versions.item_version = u.ref_id2
+               AND sampled_hash_versions.first_scan_id = u.ref_id3",
+            [],
+        )?;
 
         // Step 3: Delete hash_versions created in this scan
         // Must come before item_versions deletion — hash_versions has FK to
@@ -151,6 +189,10 @@ impl UndoLog {
             "DELETE FROM hash_versions WHERE first_scan_id = ?",
             [scan_id],
         )?;
+        let deleted_samples = conn.execute(
+            "DELETE FROM sampled_hash_versions WHERE first_scan_id = ?",
+            [scan_id],
+        )?;
 
         // Step 4: Delete item_versions created in this scan
         let deleted_versions = conn.execute(
@@ -158,14 +200,29 @@ impl UndoLog {
             [scan_id],
         )?;
 
-        // Step 5: NULL out val columns on reverted versions where val_scan_id
-        // now exceeds the restored last_scan_id
+        // Step 5: NULL out val, manifest and content columns on reverted versions
+        // where their scan id now exceeds the restored last_scan_id
         let cleared_val = conn.execute(
             "UPDATE item_versions
-             SET val_scan_id = NULL, val_state = NULL, val_error = NULL
+             SET val_scan_id = NULL, val_state = NULL, val_error = NULL,
+                 val_validator = NULL, val_validator_version = NULL
              WHERE val_scan_id IS NOT NULL AND val_scan_id > last_scan_id",
             [],
         )?;
+        let cleared_manifest = conn.execute(
+            "UPDATE item_versions
+             SET manifest_scan_id = NULL, manifest_state = NULL, manifest_path = NULL,
+                 manifest_algo = NULL, manifest_hash = NULL, manifest_reviewed_at = NULL
+             WHERE manifest_scan_id IS NOT NULL AND manifest_scan_id > last_scan_id",
+            [],
+        )?;
+        let cleared_content = conn.execute(
+            "UPDATE item_versions
+             SET content_scan_id = NULL, content_type = NULL, content_mismatch = NULL,
+                 content_reviewed_at = NULL
+             WHERE content_scan_id IS NOT NULL AND content_scan_id > last_scan_id",
+            [],
+        )?;
 
         // Step 6: Delete orphaned identity rows — items whose only version was
         // created this scan and deleted in step 4. Uses LEFT JOIN for efficient
@@ -184,10 +241,13 @@ impl UndoLog {
         conn.execute("DELETE FROM scan_undo_log", [])?;
 
         info!(
-            "Rollback complete for scan {}: restored {} version(s), {} hash version(s); \
-             deleted {} version(s), {} hash version(s), {} item(s); cleared val on {} version(s)",
-            scan_id, restored_versions, restored_hashes,
-            deleted_versions, deleted_hashes, deleted_items, cleared_val
+            "Rollback complete for scan {}: restored {} version(s), {} hash version(s), \
+             {} sampled hash version(s); deleted {} version(s), {} hash version(s), \
+             {} sampled hash version(s), {} item(s); cleared val on {} version(s), \
+             manifest on {} version(s), content on {} version(s)",
+            scan_id, restored_versions, restored_hashes, restored_samples,
+            deleted_versions, deleted_hashes, deleted_samples, deleted_items, cleared_val,
+            cleared_manifest, cleared_content
         );
 
         Ok(())
@@ -4,8 +4,17 @@ use std::{path::Path, sync::Arc};
 use claxon::{Block, FlacReader};
 
 use crate::error::FsPulseError;
+use crate::io_throttle::{IoThrottle, ThrottledFile};
 
-use super::validator::{ValidationState, Validator};
+use super::validator::{Signature, ValidationState, Validator, ValidatorInfo};
+
+static INFO: ValidatorInfo = ValidatorInfo {
+    name: "flac",
+    version: 1,
+    extensions: &["flac"],
+    signatures: &[Signature::new(0, b"fLaC")],
+    config_key: "audio",
+};
 
 /// Validator implementation for FLAC audio files using the Claxon crate.
 pub struct ClaxonValidator;
@@ -18,12 +27,21 @@ impl ClaxonValidator {
 }
 
 impl Validator for ClaxonValidator {
+    fn info(&self) -> &'static ValidatorInfo {
+        &INFO
+    }
+
     fn validate(
         &self,
         path: &Path,
+        throttle: &IoThrottle,
         interrupt_token: &Arc<AtomicBool>,
     ) -> Result<(ValidationState, Option<String>), FsPulseError> {
-        let mut reader = match FlacReader::open(path) {
+        let file = match ThrottledFile::open_buffered(path, throttle, interrupt_token) {
+            Ok(file) => file,
+            Err(e) => return Ok((ValidationState::Invalid, Some(e.to_string()))),
+        };
+        let mut reader = match FlacReader::new(file) {
             Ok(reader) => reader,
             Err(e) => return Ok((ValidationState::Invalid, Some(e.to_string()))),
         };
@@ -64,7 +82,7 @@ mod tests {
         let nonexistent_path = Path::new("/this/path/does/not/exist.flac");
         let interrupt_token = Arc::new(AtomicBool::new(false));
 
-        let result = validator.validate(nonexistent_path, &interrupt_token);
+        let result = validator.validate(nonexistent_path, &IoThrottle::unlimited(), &interrupt_token);
         assert!(result.is_ok());
 
         let (state, error_msg) = result.unwrap();
@@ -98,7 +116,7 @@ mod tests {
             .expect("Failed to write temp file");
 
         let interrupt_token = Arc::new(AtomicBool::new(false));
-        let result = validator.validate(temp_file.path(), &interrupt_token);
+        let result = validator.validate(temp_file.path(), &IoThrottle::unlimited(), &interrupt_token);
         assert!(result.is_ok());
 
         let (state, error_msg) = result.unwrap();
@@ -0,0 +1,245 @@
+use std::io::{self, Read};
+use std::sync::atomic::AtomicBool;
+use std::{path::Path, sync::Arc};
+
+use flate2::read::MultiGzDecoder;
+use lzma_rust2::XzReader;
+
+use crate::error::FsPulseError;
+use crate::io_throttle::{IoThrottle, ThrottledFile};
+use crate::try_invalid;
+
+use super::tar;
+use super::validator::{file_extension_for_path, Signature, ValidationState, Validator, ValidatorInfo};
+
+static GZIP_INFO: ValidatorInfo = ValidatorInfo {
+    name: "gzip",
+    version: 1,
+    extensions: &["gz", "tgz"],
+    signatures: &[Signature::new(0, b"\x1F\x8B\x08")],
+    config_key: "archives",
+};
+
+static XZ_INFO: ValidatorInfo = ValidatorInfo {
+    name: "xz",
+    version: 1,
+    extensions: &["xz", "txz"],
+    signatures: &[Signature::new(0, b"\xFD7zXZ\0")],
+    config_key: "archives",
+};
+
+static ZSTD_INFO: ValidatorInfo = ValidatorInfo {
+    name: "zstd",
+    version: 1,
+    extensions: &["zst", "tzst"],
+    signatures: &[Signature::new(0, b"\x28\xB5\x2F\xFD")],
+    config_key: "archives",
+};
+
+/// Validator implementation for gzip streams using the flate2 crate.
+pub struct GzipValidator;
+
+impl GzipValidator {
+    /// Constructs a new GzipValidator instance.
+    pub fn new() -> Self {
+        GzipValidator
+    }
+}
+
+impl Validator for GzipValidator {
+    fn info(&self) -> &'static ValidatorInfo {
+        &GZIP_INFO
+    }
+
+    fn validate(
+        &self,
+        path: &Path,
+        throttle: &IoThrottle,
+        interrupt_token: &Arc<AtomicBool>,
+    ) -> Result<(ValidationState, Option<String>), FsPulseError> {
+        let file = try_invalid!(ThrottledFile::open_buffered(path, throttle, interrupt_token));
+        // Reads every member of a concatenated stream, checking each one's CRC
+        // and length
+        check_stream(MultiGzDecoder::new(file), path, interrupt_token)
+    }
+}
+
+/// Validator implementation for xz streams using the lzma-rust2 crate.
+pub struct XzValidator;
+
+impl XzValidator {
+    /// Constructs a new XzValidator instance.
+    pub fn new() -> Self {
+        XzValidator
+    }
+}
+
+impl Validator for XzValidator {
+    fn info(&self) -> &'static ValidatorInfo {
+        &XZ_INFO
+    }
+
+    fn validate(
+        &self,
+        path: &Path,
+        throttle: &IoThrottle,
+        interrupt_token: &Arc<AtomicBool>,
+    ) -> Result<(ValidationState, Option<String>), FsPulseError> {
+        let file = try_invalid!(ThrottledFile::open_buffered(path, throttle, interrupt_token));
+        // Checks each block's CRC32, CRC64 or SHA-256
+        check_stream(XzReader::new(file, true), path, interrupt_token)
+    }
+}
+
+/// Validator implementation for Zstandard streams using the zstd crate.
+pub struct ZstdValidator;
+
+impl ZstdValidator {
+    /// Constructs a new ZstdValidator instance.
+    pub fn new() -> Self {
+        ZstdValidator
+    }
+}
+
+impl Validator for ZstdValidator {
+    fn info(&self) -> &'static ValidatorInfo {
+        &ZSTD_INFO
+    }
+
+    fn validate(
+        &self,
+        path: &Path,
+        throttle: &IoThrottle,
+        interrupt_token: &Arc<AtomicBool>,
+    ) -> Result<(ValidationState, Option<String>), FsPulseError> {
+        let file = try_invalid!(ThrottledFile::open_buffered(path, throttle, interrupt_token));
+        // Checks each frame's content checksum, for frames written with one
+        let decoder = try_invalid!(zstd::stream::read::Decoder::with_buffer(file));
+        check_stream(decoder, path, interrupt_token)
+    }
+}
+
+/// Decompress a whole stream. A compressed tar archive is read member by
+/// member, so an error names the member it's in.
+fn check_stream<R: Read>(
+    mut decoder: R,
+    path: &Path,
+    interrupt_token: &Arc<AtomicBool>,
+) -> Result<(ValidationState, Option<String>), FsPulseError> {
+    if holds_tar(path) {
+        let result = tar::check_members(&mut decoder, interrupt_token)?;
+        if result.0 != ValidationState::Valid {
+            return Ok(result);
+        }
+    }
+    // The stream's checksum comes at its end, after the tar archive's end
+    try_invalid!(io::copy(&mut decoder, &mut io::sink()));
+    Ok((ValidationState::Valid, None))
+}
+
+/// Whether a compressed file is a tar archive, by its name: `.tgz` and the
+/// like, or `.tar` before the compression extension
+fn holds_tar(path: &Path) -> bool {
+    match file_extension_for_path(path).as_deref() {
+        Some("tgz" | "txz" | "tzst") => true,
+        _ => path
+            .file_stem()
+            .and_then(|stem| file_extension_for_path(Path::new(stem)))
+            .is_some_and(|ext| ext == "tar"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validate::{tar_bytes, validate_bytes};
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_holds_tar() {
+        assert!(holds_tar(Path::new("backup.tar.gz")));
+        assert!(holds_tar(Path::new("/data/backup.TAR.zst")));
+        assert!(holds_tar(Path::new("backup.tgz")));
+        assert!(!holds_tar(Path::new("notes.txt.gz")));
+        assert!(!holds_tar(Path::new("tar.gz")));
+    }
+
+    #[test]
+    fn test_gzip_validator() {
+        let validator = GzipValidator::new();
+        let compressed = gzip(b"some text worth keeping");
+        assert_eq!(validate_bytes(&validator, ".gz", &compressed), (ValidationState::Valid, None));
+
+        // A damaged CRC in the trailer
+        let mut damaged = compressed.clone();
+        let crc_offset = damaged.len() - 8;
+        damaged[crc_offset] ^= 0xFF;
+        let (state, error) = validate_bytes(&validator, ".gz", &damaged);
+        assert_eq!(state, ValidationState::Invalid);
+        assert!(error.is_some());
+
+        let (state, _) = validate_bytes(&validator, ".gz", &compressed[..compressed.len() - 4]);
+        assert_eq!(state, ValidationState::Invalid);
+    }
+
+    #[test]
+    fn test_gzip_validator_reads_tar_members() {
+        let validator = GzipValidator::new();
+        let tar = tar_bytes();
+        assert_eq!(
+            validate_bytes(&validator, ".tar.gz", &gzip(&tar)),
+            (ValidationState::Valid, None)
+        );
+
+        // Intact compression around a truncated archive
+        let (state, error) = validate_bytes(&validator, ".tgz", &gzip(&tar[..512 * 3 + 4]));
+        assert_eq!(state, ValidationState::Invalid);
+        assert!(error.unwrap().starts_with("second.txt: "));
+
+        // A damaged CRC is found past the end of an intact archive
+        let mut damaged = gzip(&tar);
+        let crc_offset = damaged.len() - 8;
+        damaged[crc_offset] ^= 0xFF;
+        let (state, _) = validate_bytes(&validator, ".tar.gz", &damaged);
+        assert_eq!(state, ValidationState::Invalid);
+    }
+
+    #[test]
+    fn test_xz_validator() {
+        let validator = XzValidator::new();
+        let mut writer = lzma_rust2::XzWriter::new(Vec::new(), lzma_rust2::XzOptions::with_preset(6)).unwrap();
+        writer.write_all(b"some text worth keeping").unwrap();
+        let compressed = writer.finish().unwrap();
+        assert_eq!(validate_bytes(&validator, ".xz", &compressed), (ValidationState::Valid, None));
+
+        let (state, error) = validate_bytes(&validator, ".xz", &compressed[..compressed.len() / 2]);
+        assert_eq!(state, ValidationState::Invalid);
+        assert!(error.is_some());
+    }
+
+    #[test]
+    fn test_zstd_validator() {
+        let validator = ZstdValidator::new();
+        let mut encoder = zstd::stream::write::Encoder::new(Vec::new(), 3).unwrap();
+        encoder.include_checksum(true).unwrap();
+        encoder.write_all(b"some text worth keeping").unwrap();
+        let compressed = encoder.finish().unwrap();
+        assert_eq!(validate_bytes(&validator, ".zst", &compressed), (ValidationState::Valid, None));
+
+        // A damaged content checksum
+        let mut damaged = compressed.clone();
+        let checksum_offset = damaged.len() - 1;
+        damaged[checksum_offset] ^= 0xFF;
+        let (state, error) = validate_bytes(&validator, ".zst", &damaged);
+        assert_eq!(state, ValidationState::Invalid);
+        assert!(error.is_some());
+    }
+}
@@ -0,0 +1,208 @@
+use std::io::Read;
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use crate::error::FsPulseError;
+use crate::io_throttle::{IoThrottle, ThrottledFile};
+
+use super::registry::ValidatorRegistry;
+use super::validator::Signature;
+
+/// How much of the start of a file is read to detect its type
+pub const SNIFF_BYTES: u64 = 4096;
+
+/// Recorded for files whose contents match no known signature
+pub const UNKNOWN_CONTENT: &str = "unknown";
+
+const EXECUTABLE: &str = "executable";
+
+/// A file type that is recognized by its contents but has no validator.
+/// Detecting one still shows when a file isn't what its extension claims.
+struct KnownType {
+    name: &'static str,
+    extensions: &'static [&'static str],
+    signatures: &'static [Signature],
+}
+
+static KNOWN_TYPES: &[KnownType] = &[
+    KnownType {
+        name: EXECUTABLE,
+        extensions: &["exe", "dll", "sys", "scr", "so", "dylib"],
+        // Windows executables are recognized by `is_pe` instead: "MZ" alone
+        // is too short to trust
+        signatures: &[
+            Signature::new(0, b"\x7FELF"),
+            Signature::new(0, b"\xFE\xED\xFA\xCE"),
+            Signature::new(0, b"\xFE\xED\xFA\xCF"),
+            Signature::new(0, b"\xCE\xFA\xED\xFE"),
+            Signature::new(0, b"\xCF\xFA\xED\xFE"),
+        ],
+    },
+];
+
+/// Validators for one container format, split by what the file holds. The
+/// brand in an MPEG-4 file doesn't reliably say whether it's audio or video,
+/// so neither is a mismatch for the other's extensions.
+static SAME_CONTAINER: &[&[&str]] = &[&["m4a", "mp4"]];
+
+/// What a file's contents say it is, next to what its extension says.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentCheck {
+    /// Validator name or known type matched by the contents, or
+    /// `UNKNOWN_CONTENT`
+    pub content_type: &'static str,
+    /// Both the extension and the contents name a type, and they differ
+    pub mismatch: bool,
+}
+
+impl ContentCheck {
+    fn new(extension: Option<&str>, content_type: &'static str) -> Self {
+        let mismatch = content_type != UNKNOWN_CONTENT
+            && extension
+                .and_then(type_for_extension)
+                .is_some_and(|ext_type| ext_type != content_type && !same_container(ext_type, content_type));
+        ContentCheck { content_type, mismatch }
+    }
+}
+
+/// Read the start of a file and detect its type from its magic bytes.
+pub fn sniff(
+    path: &Path,
+    extension: Option<&str>,
+    throttle: &IoThrottle,
+    interrupt_token: &Arc<AtomicBool>,
+) -> Result<ContentCheck, FsPulseError> {
+    let file = ThrottledFile::open(path, throttle, interrupt_token)?;
+    let mut head = Vec::with_capacity(SNIFF_BYTES as usize);
+    file.take(SNIFF_BYTES).read_to_end(&mut head)?;
+
+    Ok(ContentCheck::new(extension, detect(&head)))
+}
+
+/// The type whose signature the start of a file matches. Validators' own
+/// signatures are checked first, so a detected type with a validator is
+/// named after it.
+pub fn detect(head: &[u8]) -> &'static str {
+    let registry = ValidatorRegistry::global();
+    if let Some(info) = registry.infos().find(|info| matches_any(head, info.signatures)) {
+        return info.name;
+    }
+    if is_pe(head) {
+        return EXECUTABLE;
+    }
+    KNOWN_TYPES
+        .iter()
+        .find(|known| matches_any(head, known.signatures))
+        .map_or(UNKNOWN_CONTENT, |known| known.name)
+}
+
+/// The type a lowercase extension claims: its validator's name, or a known
+/// type. None for extensions that don't say.
+pub fn type_for_extension(extension: &str) -> Option<&'static str> {
+    if let Some(validator) = ValidatorRegistry::global().for_extension(extension) {
+        return Some(validator.info().name);
+    }
+    KNOWN_TYPES
+        .iter()
+        .find(|known| known.extensions.contains(&extension))
+        .map(|known| known.name)
+}
+
+fn same_container(a: &str, b: &str) -> bool {
+    SAME_CONTAINER
+        .iter()
+        .any(|family| family.contains(&a) && family.contains(&b))
+}
+
+fn matches_any(head: &[u8], signatures: &[Signature]) -> bool {
+    signatures
+        .iter()
+        .any(|sig| head.get(sig.offset..sig.offset + sig.bytes.len()) == Some(sig.bytes))
+}
+
+/// A DOS header whose `e_lfanew` field points at a PE signature
+fn is_pe(head: &[u8]) -> bool {
+    if !head.starts_with(b"MZ") {
+        return false;
+    }
+    let Some(lfanew) = head.get(0x3C..0x40) else {
+        return false;
+    };
+    let offset = u32::from_le_bytes([lfanew[0], lfanew[1], lfanew[2], lfanew[3]]) as usize;
+    head.get(offset..offset + 4) == Some(b"PE\0\0")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pe_header() -> Vec<u8> {
+        let mut head = vec![0u8; 0x80];
+        head[..2].copy_from_slice(b"MZ");
+        head[0x3C..0x40].copy_from_slice(&0x40u32.to_le_bytes());
+        head[0x40..0x44].copy_from_slice(b"PE\0\0");
+        head
+    }
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(detect(b"\x89PNG\r\n\x1A\n\0\0\0\rIHDR"), "image");
+        assert_eq!(detect(b"%PDF-1.7\n"), "pdf");
+        assert_eq!(detect(b"fLaC\0\0\0\x22"), "flac");
+        assert_eq!(detect(b"\x7FELF\x02\x01\x01"), "executable");
+        assert_eq!(detect(&pe_header()), "executable");
+        assert_eq!(detect(b"PK\x03\x04\x14\0"), "zip");
+        assert_eq!(detect(b"\x1F\x8B\x08\0"), "gzip");
+        assert_eq!(detect(b"7z\xBC\xAF\x27\x1C\0\x04"), "7z");
+        assert_eq!(detect(b"\0\0\0\x20ftypM4A \0\0\0\0"), "m4a");
+        assert_eq!(detect(b"\0\0\0\x20ftypisom\0\0\x02\0"), "mp4");
+        assert_eq!(detect(b"\x1A\x45\xDF\xA3\x9F\x42\x86\x81"), "mkv");
+
+        let mut tar = vec![0u8; 512];
+        tar[257..263].copy_from_slice(b"ustar\0");
+        assert_eq!(detect(&tar), "tar");
+
+        assert_eq!(detect(b""), UNKNOWN_CONTENT);
+        assert_eq!(detect(b"MZ plain text"), UNKNOWN_CONTENT);
+        assert_eq!(detect(b"BMW service notes"), UNKNOWN_CONTENT);
+        // HEIF images share the MPEG-4 box structure, but aren't movies
+        assert_eq!(detect(b"\0\0\0\x18ftypheic\0\0\0\0"), UNKNOWN_CONTENT);
+    }
+
+    #[test]
+    fn test_type_for_extension() {
+        assert_eq!(type_for_extension("jpg"), Some("image"));
+        assert_eq!(type_for_extension("pdf"), Some("pdf"));
+        assert_eq!(type_for_extension("exe"), Some("executable"));
+        assert_eq!(type_for_extension("docx"), Some("zip"));
+        assert_eq!(type_for_extension("txt"), None);
+    }
+
+    #[test]
+    fn test_content_check_mismatch() {
+        // A renamed executable
+        let check = ContentCheck::new(Some("pdf"), detect(&pe_header()));
+        assert_eq!(check, ContentCheck { content_type: "executable", mismatch: true });
+
+        assert!(!ContentCheck::new(Some("jpeg"), "image").mismatch);
+        assert!(!ContentCheck::new(Some("m4a"), "mp4").mismatch);
+        assert!(ContentCheck::new(Some("mov"), "mkv").mismatch);
+        // Contents that match nothing, or an extension that claims nothing,
+        // can't disagree
+        assert!(!ContentCheck::new(Some("pdf"), UNKNOWN_CONTENT).mismatch);
+        assert!(!ContentCheck::new(Some("bin"), "executable").mismatch);
+        assert!(!ContentCheck::new(None, "image").mismatch);
+    }
+
+    #[test]
+    fn test_known_types_distinct_from_validators() {
+        let registry = ValidatorRegistry::global();
+        for known in KNOWN_TYPES {
+            assert!(registry.for_name(known.name).is_none(), "{} is a validator", known.name);
+            for ext in known.extensions {
+                assert!(!registry.has_validator_extension(ext), "{ext} has a validator");
+            }
+        }
+    }
+}
@@ -1,13 +1,30 @@
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::{path::Path, sync::Arc};
 
-use image::ImageReader;
+use image::{ImageFormat, ImageReader};
 
 use crate::error::FsPulseError;
-
-use super::validator::{ValidationState, Validator};
-
-/// Validator implementation for FLAC audio files using the Claxon crate.
+use crate::io_throttle::{IoThrottle, ThrottledFile};
+
+use super::validator::{Signature, ValidationState, Validator, ValidatorInfo};
+
+static INFO: ValidatorInfo = ValidatorInfo {
+    name: "image",
+    version: 2,
+    extensions: &["jpg", "jpeg", "png", "gif", "tiff", "bmp"],
+    signatures: &[
+        Signature::new(0, b"\xFF\xD8\xFF"),
+        Signature::new(0, b"\x89PNG\r\n\x1A\n"),
+        Signature::new(0, b"GIF87a"),
+        Signature::new(0, b"GIF89a"),
+        Signature::new(0, b"II*\0"),
+        Signature::new(0, b"MM\0*"),
+        // BMP's two-byte "BM" is too common at the start of text to sniff
+    ],
+    config_key: "images",
+};
+
+/// Validator implementation for image files using the image crate.
 pub struct ImageValidator;
 
 impl ImageValidator {
@@ -18,9 +35,14 @@ impl ImageValidator {
 }
 
 impl Validator for ImageValidator {
+    fn info(&self) -> &'static ValidatorInfo {
+        &INFO
+    }
+
     fn validate(
         &self,
         path: &Path,
+        throttle: &IoThrottle,
         interrupt_token: &Arc<AtomicBool>,
     ) -> Result<(ValidationState, Option<String>), FsPulseError> {
 
@@ -30,14 +52,22 @@ impl Validator for ImageValidator {
             return Err(FsPulseError::TaskInterrupted);
         }
 
-        let open_result = ImageReader::open(path);
-        let reader = match open_result {
+        let open_result = ThrottledFile::open_buffered(path, throttle, interrupt_token)
+            .and_then(|file| ImageReader::new(file).with_guessed_format());
+        let mut reader = match open_result {
             Ok(reader) => reader,
             Err(e) => {
                 let e_str = e.to_string();
                 return Ok((ValidationState::Invalid, Some(e_str)));
             }
         };
+        // Decode as the format the contents show, so files selected by
+        // content aren't decoded as whatever their extension claims
+        if reader.format().is_none() {
+            if let Ok(format) = ImageFormat::from_path(path) {
+                reader.set_format(format);
+            }
+        }
 
         match reader.decode() {
             Ok(_) => Ok((ValidationState::Valid, None)),
@@ -61,7 +91,7 @@ mod tests {
         let nonexistent_path = Path::new("/this/path/does/not/exist.jpg");
         let interrupt_token = Arc::new(AtomicBool::new(false));
 
-        let result = validator.validate(nonexistent_path, &interrupt_token);
+        let result = validator.validate(nonexistent_path, &IoThrottle::unlimited(), &interrupt_token);
         assert!(result.is_ok());
         
         let (state, error_msg) = result.unwrap();
@@ -93,7 +123,7 @@ mod tests {
         temp_file.write_all(b"not an image file").expect("Failed to write temp file");
 
         let interrupt_token = Arc::new(AtomicBool::new(false));
-        let result = validator.validate(temp_file.path(), &interrupt_token);
+        let result = validator.validate(temp_file.path(), &IoThrottle::unlimited(), &interrupt_token);
         assert!(result.is_ok());
         
         let (state, error_msg) = result.unwrap();
@@ -101,6 +131,24 @@ mod tests {
         assert!(error_msg.is_some());
     }
 
+    #[test]
+    fn test_image_validator_detects_format_from_contents() {
+        let validator = ImageValidator::new();
+
+        // A valid PNG whose extension names no image format
+        let temp_file = tempfile::Builder::new()
+            .suffix(".bin")
+            .tempfile()
+            .expect("Failed to create temp file");
+        image::RgbImage::new(4, 4)
+            .save_with_format(temp_file.path(), ImageFormat::Png)
+            .expect("Failed to write temp file");
+
+        let interrupt_token = Arc::new(AtomicBool::new(false));
+        let result = validator.validate(temp_file.path(), &IoThrottle::unlimited(), &interrupt_token);
+        assert_eq!(result.unwrap(), (ValidationState::Valid, None));
+    }
+
     #[test]
     fn test_image_validator_empty_file() {
         let validator = ImageValidator::new();
@@ -111,7 +159,7 @@ mod tests {
         let temp_file = NamedTempFile::new().expect("Failed to create temp file");
 
         let interrupt_token = Arc::new(AtomicBool::new(false));
-        let result = validator.validate(temp_file.path(), &interrupt_token);
+        let result = validator.validate(temp_file.path(), &IoThrottle::unlimited(), &interrupt_token);
         assert!(result.is_ok());
         
         let (state, error_msg) = result.unwrap();
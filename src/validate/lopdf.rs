@@ -5,10 +5,19 @@ use log::warn;
 use lopdf::{Document, Object};
 
 use crate::error::FsPulseError;
+use crate::io_throttle::{IoThrottle, ThrottledFile};
 use crate::try_invalid;
 use crate::validate::validator::Validator;
 
-use super::validator::ValidationState;
+use super::validator::{Signature, ValidationState, ValidatorInfo};
+
+static INFO: ValidatorInfo = ValidatorInfo {
+    name: "pdf",
+    version: 1,
+    extensions: &["pdf"],
+    signatures: &[Signature::new(0, b"%PDF-")],
+    config_key: "pdf",
+};
 
 
 /// Validator implementation for pdf audio files using the lopdf crate.
@@ -22,12 +31,18 @@ impl LopdfValidator {
 }
 
 impl Validator for LopdfValidator {
+    fn info(&self) -> &'static ValidatorInfo {
+        &INFO
+    }
+
     fn validate(
         &self,
         path: &Path,
+        throttle: &IoThrottle,
         interrupt_token: &Arc<AtomicBool>,
     ) -> Result<(ValidationState, Option<String>), FsPulseError> {
-        let doc = try_invalid!(Document::load(path));
+        let file = try_invalid!(ThrottledFile::open_buffered(path, throttle, interrupt_token));
+        let doc = try_invalid!(Document::load_from(file));
 
         // Traverse and validate all objects in the document.
         let mut object_count = 0;
@@ -53,17 +68,15 @@ impl LopdfValidator {
     /// Other object types are considered valid if they have been parsed.
     fn validate_object(object: &Object) -> Result<(), lopdf::Error> {
         match object {
-            Object::Stream(stream) => {
-                // Validate the stream by attempting to decompress its content.
-                if stream.is_compressed() {
-                    match stream.decompressed_content() {
-                        Ok(_) => {}
-                        Err(lopdf::Error::Unimplemented(reason)) => {
-                            warn!("Lopdf unimplemented feature: {reason}");
-                        }
-                        Err(err) => {
-                            return Err(err);
-                        }
+            // Validate the stream by attempting to decompress its content.
+            Object::Stream(stream) if stream.is_compressed() => {
+                match stream.decompressed_content() {
+                    Ok(_) => {}
+                    Err(lopdf::Error::Unimplemented(reason)) => {
+                        warn!("Lopdf unimplemented feature: {reason}");
+                    }
+                    Err(err) => {
+                        return Err(err);
                     }
                 }
             }
@@ -99,7 +112,7 @@ mod tests {
         let nonexistent_path = Path::new("/this/path/does/not/exist.pdf");
         let interrupt_token = Arc::new(AtomicBool::new(false));
 
-        let result = validator.validate(nonexistent_path, &interrupt_token);
+        let result = validator.validate(nonexistent_path, &IoThrottle::unlimited(), &interrupt_token);
         assert!(result.is_ok());
         
         let (state, error_msg) = result.unwrap();
@@ -131,7 +144,7 @@ mod tests {
         temp_file.write_all(b"not a pdf file").expect("Failed to write temp file");
 
         let interrupt_token = Arc::new(AtomicBool::new(false));
-        let result = validator.validate(temp_file.path(), &interrupt_token);
+        let result = validator.validate(temp_file.path(), &IoThrottle::unlimited(), &interrupt_token);
         assert!(result.is_ok());
         
         let (state, error_msg) = result.unwrap();
@@ -149,7 +162,7 @@ mod tests {
         let temp_file = NamedTempFile::new().expect("Failed to create temp file");
 
         let interrupt_token = Arc::new(AtomicBool::new(false));
-        let result = validator.validate(temp_file.path(), &interrupt_token);
+        let result = validator.validate(temp_file.path(), &IoThrottle::unlimited(), &interrupt_token);
         assert!(result.is_ok());
         
         let (state, error_msg) = result.unwrap();
@@ -0,0 +1,499 @@
+use std::io::{BufReader, Read, Seek};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::{path::Path, sync::Arc};
+
+use crate::error::FsPulseError;
+use crate::io_throttle::{IoThrottle, ThrottledFile};
+use crate::try_invalid;
+
+use super::validator::{Signature, ValidationState, Validator, ValidatorInfo, WalkError};
+
+static INFO: ValidatorInfo = ValidatorInfo {
+    name: "mkv",
+    version: 1,
+    extensions: &["mkv", "webm", "mk3d"],
+    signatures: &[Signature::new(0, b"\x1A\x45\xDF\xA3")],
+    config_key: "video",
+};
+
+const EBML_HEADER: u32 = 0x1A45_DFA3;
+const SEGMENT: u32 = 0x1853_8067;
+const SEEK_HEAD: u32 = 0x114D_9B74;
+const SEGMENT_INFO: u32 = 0x1549_A966;
+const TRACKS: u32 = 0x1654_AE6B;
+const CLUSTER: u32 = 0x1F43_B675;
+const CUES: u32 = 0x1C53_BB6B;
+const CHAPTERS: u32 = 0x1043_A770;
+const TAGS: u32 = 0x1254_C367;
+const ATTACHMENTS: u32 = 0x1941_A469;
+const BLOCK_GROUP: u32 = 0xA0;
+const BLOCK: u32 = 0xA1;
+const SIMPLE_BLOCK: u32 = 0xA3;
+
+/// The elements a segment holds. A cluster of unknown size ends where the
+/// next of them starts.
+const SEGMENT_CHILDREN: &[u32] = &[SEEK_HEAD, SEGMENT_INFO, TRACKS, CLUSTER, CUES, CHAPTERS, TAGS, ATTACHMENTS];
+
+/// Validator for Matroska and WebM files. Walks the EBML element tree down to
+/// the blocks in each cluster, checking that each element fits in the one
+/// that holds it and in the file, and that each block's header is whole.
+/// Nothing is decoded.
+pub struct MkvValidator;
+
+impl MkvValidator {
+    /// Constructs a new MkvValidator instance.
+    pub fn new() -> Self {
+        MkvValidator
+    }
+}
+
+impl Validator for MkvValidator {
+    fn info(&self) -> &'static ValidatorInfo {
+        &INFO
+    }
+
+    fn validate(
+        &self,
+        path: &Path,
+        throttle: &IoThrottle,
+        interrupt_token: &Arc<AtomicBool>,
+    ) -> Result<(ValidationState, Option<String>), FsPulseError> {
+        let file_len = try_invalid!(std::fs::metadata(path)).len();
+        let file = try_invalid!(ThrottledFile::open_buffered(path, throttle, interrupt_token));
+        let mut elements = Elements { reader: file, position: 0, file_len };
+
+        match check_file(&mut elements, interrupt_token) {
+            Ok(()) => Ok((ValidationState::Valid, None)),
+            Err(WalkError::Invalid(error)) => Ok((ValidationState::Invalid, Some(error))),
+            Err(WalkError::Interrupted) => Err(FsPulseError::TaskInterrupted),
+        }
+    }
+}
+
+/// An element's header
+struct Element {
+    id: u32,
+    /// Where the element's header starts
+    offset: u64,
+    header_len: u64,
+    /// The length of the element's data, or None if it wasn't known when the
+    /// file was written
+    size: Option<u64>,
+}
+
+impl Element {
+    fn data_start(&self) -> u64 {
+        self.offset + self.header_len
+    }
+}
+
+/// Reads element headers from a buffered file, skipping forward over their
+/// data without dropping what's already buffered
+struct Elements<R> {
+    reader: BufReader<R>,
+    position: u64,
+    file_len: u64,
+}
+
+impl<R: Read + Seek> Elements<R> {
+    /// Read the header of the element at `offset`
+    fn read_element(&mut self, offset: u64) -> Result<Element, WalkError> {
+        self.reader.seek_relative(offset as i64 - self.position as i64)?;
+        self.position = offset;
+
+        // An ID keeps its length marker; 1 to 4 bytes
+        let first = self.read_byte(offset)?;
+        let id_len = first.leading_zeros() + 1;
+        if id_len > 4 {
+            return Err(WalkError::Invalid(format!("invalid element ID at byte {offset}")));
+        }
+        let mut id = first as u32;
+        for _ in 1..id_len {
+            id = (id << 8) | self.read_byte(offset)? as u32;
+        }
+
+        // A size drops its length marker; 1 to 8 bytes, all ones if unknown
+        let size_offset = self.position;
+        let first = self.read_byte(offset)?;
+        let size_len = first.leading_zeros() + 1;
+        if size_len > 8 {
+            return Err(WalkError::Invalid(format!("invalid element size at byte {size_offset}")));
+        }
+        let value_mask = (0xFFu16 >> size_len) as u8;
+        let mut size = (first & value_mask) as u64;
+        let mut unknown = first & value_mask == value_mask;
+        for _ in 1..size_len {
+            let byte = self.read_byte(offset)?;
+            size = (size << 8) | byte as u64;
+            unknown &= byte == 0xFF;
+        }
+
+        Ok(Element {
+            id,
+            offset,
+            header_len: self.position - offset,
+            size: (!unknown).then_some(size),
+        })
+    }
+
+    fn read_byte(&mut self, element_offset: u64) -> Result<u8, WalkError> {
+        let mut byte = [0u8];
+        if self.reader.read(&mut byte)? == 0 {
+            return Err(WalkError::Invalid(format!(
+                "{} at byte {} is truncated in its header",
+                element_name(0),
+                element_offset
+            )));
+        }
+        self.position += 1;
+        Ok(byte[0])
+    }
+
+    /// Where an element of known size ends, checking that it fits in its
+    /// parent (an ID and where it ends) and in the file
+    fn end_of(&self, element: &Element, parent: Option<(u32, u64)>) -> Result<u64, WalkError> {
+        let Some(size) = element.size else {
+            return Err(WalkError::Invalid(format!(
+                "{} at byte {} has an unknown size",
+                element_name(element.id),
+                element.offset
+            )));
+        };
+        let end = element.data_start().saturating_add(size);
+        if end > self.file_len {
+            return Err(WalkError::Invalid(format!(
+                "{} at byte {} runs past the end of the file: {} bytes declared, {} present",
+                element_name(element.id),
+                element.offset,
+                size,
+                self.file_len.saturating_sub(element.data_start())
+            )));
+        }
+        if let Some((parent_id, parent_end)) = parent {
+            if end > parent_end {
+                return Err(WalkError::Invalid(format!(
+                    "{} at byte {} runs past the end of its {}",
+                    element_name(element.id),
+                    element.offset,
+                    element_name(parent_id)
+                )));
+            }
+        }
+        Ok(end)
+    }
+
+    /// Check the header of a block whose own header was just read: a track
+    /// number, then a 2-byte timecode and a flags byte
+    fn check_block(&mut self, block: &Element) -> Result<(), WalkError> {
+        let size = block.size.unwrap_or(0);
+        let first = if size > 0 { self.read_byte(block.offset)? } else { 0xFF };
+        // The track number is a variable-length integer of 1 to 8 bytes
+        if first == 0 {
+            return Err(WalkError::Invalid(format!(
+                "{} at byte {} has an invalid track number",
+                element_name(block.id),
+                block.offset
+            )));
+        }
+        let header_len = first.leading_zeros() as u64 + 1 + 3;
+        if size < header_len {
+            return Err(WalkError::Invalid(format!(
+                "{} at byte {} is too short for its header: {} bytes",
+                element_name(block.id),
+                block.offset,
+                size
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Walk the elements at the top of the file: the EBML header, then one or
+/// more segments
+fn check_file<R: Read + Seek>(elements: &mut Elements<R>, interrupt_token: &Arc<AtomicBool>) -> Result<(), WalkError> {
+    let header = elements.read_element(0)?;
+    if header.id != EBML_HEADER {
+        return Err(WalkError::Invalid("no EBML header".to_string()));
+    }
+    let mut offset = elements.end_of(&header, None)?;
+
+    let mut segments = 0;
+    while offset < elements.file_len {
+        let element = elements.read_element(offset)?;
+        offset = if element.id == SEGMENT {
+            segments += 1;
+            check_segment(elements, &element, interrupt_token)?
+        } else {
+            elements.end_of(&element, None)?
+        };
+    }
+    if segments == 0 {
+        return Err(WalkError::Invalid("no Segment element".to_string()));
+    }
+    Ok(())
+}
+
+/// Walk a segment's elements, and the blocks in each cluster. Returns where
+/// the segment ends.
+fn check_segment<R: Read + Seek>(
+    elements: &mut Elements<R>,
+    segment: &Element,
+    interrupt_token: &Arc<AtomicBool>,
+) -> Result<u64, WalkError> {
+    // A segment of unknown size runs to the end of the file
+    let end = match segment.size {
+        Some(_) => elements.end_of(segment, None)?,
+        None => elements.file_len,
+    };
+
+    let mut offset = segment.data_start();
+    let mut has_info = false;
+    let mut has_tracks = false;
+    while offset < end {
+        if interrupt_token.load(Ordering::Acquire) {
+            return Err(WalkError::Interrupted);
+        }
+        let element = elements.read_element(offset)?;
+        has_info |= element.id == SEGMENT_INFO;
+        has_tracks |= element.id == TRACKS;
+        offset = match element.id {
+            CLUSTER => check_cluster(elements, &element, end)?,
+            _ => elements.end_of(&element, Some((SEGMENT, end)))?,
+        };
+    }
+
+    for (present, id) in [(has_info, SEGMENT_INFO), (has_tracks, TRACKS)] {
+        if !present {
+            return Err(WalkError::Invalid(format!(
+                "Segment at byte {} has no {} element",
+                segment.offset,
+                element_name(id)
+            )));
+        }
+    }
+    Ok(end)
+}
+
+/// Walk a cluster's elements. Returns where the cluster ends, which for one
+/// of unknown size is where the segment's next element starts.
+fn check_cluster<R: Read + Seek>(
+    elements: &mut Elements<R>,
+    cluster: &Element,
+    segment_end: u64,
+) -> Result<u64, WalkError> {
+    let end = match cluster.size {
+        Some(_) => elements.end_of(cluster, Some((SEGMENT, segment_end)))?,
+        None => segment_end,
+    };
+
+    let mut offset = cluster.data_start();
+    while offset < end {
+        let element = elements.read_element(offset)?;
+        if cluster.size.is_none() && SEGMENT_CHILDREN.contains(&element.id) {
+            return Ok(offset);
+        }
+        offset = elements.end_of(&element, Some((CLUSTER, end)))?;
+        match element.id {
+            SIMPLE_BLOCK => elements.check_block(&element)?,
+            BLOCK_GROUP => check_block_group(elements, &element, offset)?,
+            _ => {}
+        }
+    }
+    Ok(end)
+}
+
+/// Walk a block group's elements, which must include its block
+fn check_block_group<R: Read + Seek>(
+    elements: &mut Elements<R>,
+    group: &Element,
+    end: u64,
+) -> Result<(), WalkError> {
+    let mut offset = group.data_start();
+    let mut has_block = false;
+    while offset < end {
+        let element = elements.read_element(offset)?;
+        offset = elements.end_of(&element, Some((BLOCK_GROUP, end)))?;
+        if element.id == BLOCK {
+            elements.check_block(&element)?;
+            has_block = true;
+        }
+    }
+    if !has_block {
+        return Err(WalkError::Invalid(format!("BlockGroup at byte {} has no Block", group.offset)));
+    }
+    Ok(())
+}
+
+/// An element's name for `val_error`. 0 stands for an element whose ID
+/// couldn't be read.
+fn element_name(id: u32) -> String {
+    let name = match id {
+        0 => "element",
+        EBML_HEADER => "EBML header",
+        SEGMENT => "Segment",
+        SEEK_HEAD => "SeekHead",
+        SEGMENT_INFO => "Info",
+        TRACKS => "Tracks",
+        CLUSTER => "Cluster",
+        CUES => "Cues",
+        CHAPTERS => "Chapters",
+        TAGS => "Tags",
+        ATTACHMENTS => "Attachments",
+        BLOCK_GROUP => "BlockGroup",
+        BLOCK => "Block",
+        SIMPLE_BLOCK => "SimpleBlock",
+        _ => return format!("element 0x{id:X}"),
+    };
+    name.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validate::validate_bytes;
+
+    /// An element with a 1-byte size, or an unknown size if `data` is None
+    fn element(id: &[u8], data: Option<&[u8]>) -> Vec<u8> {
+        let mut bytes = id.to_vec();
+        match data {
+            Some(data) => {
+                assert!(data.len() < 127);
+                bytes.push(0x80 | data.len() as u8);
+                bytes.extend_from_slice(data);
+            }
+            None => bytes.push(0xFF),
+        }
+        bytes
+    }
+
+    /// Track 1, timecode 0, a keyframe, then four bytes of frame data
+    const BLOCK_DATA: &[u8] = &[0x81, 0, 0, 0x80, 1, 2, 3, 4];
+
+    /// A cluster holding a timecode, then `blocks`
+    fn cluster_with(blocks: &[u8], known_size: bool) -> Vec<u8> {
+        let mut data = element(b"\xE7", Some(b"\x00"));
+        data.extend_from_slice(blocks);
+        if known_size {
+            element(b"\x1F\x43\xB6\x75", Some(&data))
+        } else {
+            [element(b"\x1F\x43\xB6\x75", None), data].concat()
+        }
+    }
+
+    fn cluster(known_size: bool) -> Vec<u8> {
+        cluster_with(&element(b"\xA3", Some(BLOCK_DATA)), known_size)
+    }
+
+    /// A WebM file with two clusters
+    fn webm_bytes(known_size: bool) -> Vec<u8> {
+        webm_with(&[cluster(known_size), cluster(known_size)].concat())
+    }
+
+    /// A WebM file holding `clusters` after its Info and Tracks
+    fn webm_with(clusters: &[u8]) -> Vec<u8> {
+        let mut segment = element(b"\x15\x49\xA9\x66", Some(&element(b"\x2A\xD7\xB1", Some(b"\x0F\x42\x40"))));
+        let track = element(b"\xAE", Some(&element(b"\xD7", Some(b"\x01"))));
+        segment.extend(element(b"\x16\x54\xAE\x6B", Some(&track)));
+        segment.extend_from_slice(clusters);
+
+        let mut bytes = element(b"\x1A\x45\xDF\xA3", Some(&element(b"\x42\x82", Some(b"webm"))));
+        bytes.extend(element(b"\x18\x53\x80\x67", Some(&segment)));
+        bytes
+    }
+
+    /// Where the segment starts in `webm_bytes`
+    const SEGMENT_OFFSET: usize = 12;
+
+    #[test]
+    fn test_mkv_validator_valid_file() {
+        assert_eq!(
+            validate_bytes(&MkvValidator::new(), ".mkv", &webm_bytes(true)),
+            (ValidationState::Valid, None)
+        );
+        // Live recordings leave cluster sizes unknown
+        assert_eq!(
+            validate_bytes(&MkvValidator::new(), ".mkv", &webm_bytes(false)),
+            (ValidationState::Valid, None)
+        );
+    }
+
+    #[test]
+    fn test_mkv_validator_truncated_file() {
+        let bytes = webm_bytes(true);
+        let segment_size = bytes.len() - SEGMENT_OFFSET - 5;
+        let expected = format!(
+            "Segment at byte {} runs past the end of the file: {} bytes declared, {} present",
+            SEGMENT_OFFSET,
+            segment_size,
+            segment_size - 10
+        );
+        assert_eq!(
+            validate_bytes(&MkvValidator::new(), ".mkv", &bytes[..bytes.len() - 10]),
+            (ValidationState::Invalid, Some(expected))
+        );
+    }
+
+    #[test]
+    fn test_mkv_validator_block_overruns_cluster() {
+        let mut bytes = webm_bytes(true);
+        // Grow the first block past the end of its cluster
+        let block = bytes.windows(2).position(|w| w == [0xA3, 0x88]).unwrap();
+        bytes[block + 1] = 0x8A;
+        let expected = format!("SimpleBlock at byte {block} runs past the end of its Cluster");
+        assert_eq!(
+            validate_bytes(&MkvValidator::new(), ".mkv", &bytes),
+            (ValidationState::Invalid, Some(expected))
+        );
+    }
+
+    #[test]
+    fn test_mkv_validator_block_headers() {
+        let mkv = MkvValidator::new();
+        let bytes = webm_with(&cluster_with(&element(b"\xA3", Some(&[0x00, 0, 0, 0x80])), true));
+        let block = bytes.len() - 6;
+        let expected = format!("SimpleBlock at byte {block} has an invalid track number");
+        assert_eq!(validate_bytes(&mkv, ".mkv", &bytes), (ValidationState::Invalid, Some(expected)));
+
+        let bytes = webm_with(&cluster_with(&element(b"\xA3", Some(&[0x81, 0, 0])), true));
+        let block = bytes.len() - 5;
+        let expected = format!("SimpleBlock at byte {block} is too short for its header: 3 bytes");
+        assert_eq!(validate_bytes(&mkv, ".mkv", &bytes), (ValidationState::Invalid, Some(expected)));
+    }
+
+    #[test]
+    fn test_mkv_validator_block_groups() {
+        let mkv = MkvValidator::new();
+        let group = element(b"\xA0", Some(&element(b"\xA1", Some(BLOCK_DATA))));
+        assert_eq!(
+            validate_bytes(&mkv, ".mkv", &webm_with(&cluster_with(&group, true))),
+            (ValidationState::Valid, None)
+        );
+
+        // The block inside the group is checked too
+        let group = element(b"\xA0", Some(&element(b"\xA1", Some(&[0x81, 0]))));
+        let bytes = webm_with(&cluster_with(&group, true));
+        let block = bytes.len() - 4;
+        let expected = format!("Block at byte {block} is too short for its header: 2 bytes");
+        assert_eq!(validate_bytes(&mkv, ".mkv", &bytes), (ValidationState::Invalid, Some(expected)));
+
+        // A duration, but no block
+        let group = element(b"\xA0", Some(&element(b"\x9B", Some(b"\x10"))));
+        let bytes = webm_with(&cluster_with(&group, true));
+        let expected = format!("BlockGroup at byte {} has no Block", bytes.len() - 5);
+        assert_eq!(validate_bytes(&mkv, ".mkv", &bytes), (ValidationState::Invalid, Some(expected)));
+    }
+
+    #[test]
+    fn test_mkv_validator_missing_tracks() {
+        let segment = element(b"\x15\x49\xA9\x66", Some(&element(b"\x2A\xD7\xB1", Some(b"\x0F\x42\x40"))));
+        let mut bytes = element(b"\x1A\x45\xDF\xA3", Some(&element(b"\x42\x82", Some(b"webm"))));
+        bytes.extend(element(b"\x18\x53\x80\x67", Some(&segment)));
+        assert_eq!(
+            validate_bytes(&MkvValidator::new(), ".mkv", &bytes),
+            (
+                ValidationState::Invalid,
+                Some(format!("Segment at byte {SEGMENT_OFFSET} has no Tracks element"))
+            )
+        );
+    }
+}
@@ -1,6 +1,52 @@
 pub mod claxon;
+pub mod compressed;
+pub mod content;
 pub mod image;
 pub mod lopdf;
+pub mod mkv;
+pub mod mp3;
+pub mod mp4;
+pub mod ogg;
+pub mod registry;
+pub mod riff;
+pub mod sevenz;
+pub mod tar;
 pub mod validator;
+pub mod zip;
 
 //pub use Validator;
+
+/// Write `bytes` to a temp file named with `suffix` and validate it
+#[cfg(test)]
+pub(crate) fn validate_bytes(
+    validator: &dyn validator::Validator,
+    suffix: &str,
+    bytes: &[u8],
+) -> (validator::ValidationState, Option<String>) {
+    use std::io::Write;
+    use std::sync::{atomic::AtomicBool, Arc};
+
+    let mut temp_file = tempfile::Builder::new()
+        .suffix(suffix)
+        .tempfile()
+        .expect("Failed to create temp file");
+    temp_file.write_all(bytes).expect("Failed to write temp file");
+    let interrupt_token = Arc::new(AtomicBool::new(false));
+    validator
+        .validate(temp_file.path(), &crate::io_throttle::IoThrottle::unlimited(), &interrupt_token)
+        .unwrap()
+}
+
+/// A tar archive holding two small files
+#[cfg(test)]
+pub(crate) fn tar_bytes() -> Vec<u8> {
+    let mut builder = ::tar::Builder::new(Vec::new());
+    for (name, data) in [("first.txt", &b"first member"[..]), ("second.txt", &b"second member"[..])] {
+        let mut header = ::tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, name, data).unwrap();
+    }
+    builder.into_inner().unwrap()
+}
@@ -0,0 +1,396 @@
+use std::io::{self, Cursor, Read};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::{path::Path, sync::Arc};
+
+use crc::{Crc, CRC_16_CMS};
+
+use crate::error::FsPulseError;
+use crate::io_throttle::{IoThrottle, ThrottledFile};
+use crate::try_invalid;
+
+use super::validator::{read_up_to, Signature, ValidationState, Validator, ValidatorInfo};
+
+static INFO: ValidatorInfo = ValidatorInfo {
+    name: "mp3",
+    version: 1,
+    extensions: &["mp3"],
+    // An ID3v2 tag, or a Layer III frame header with or without a CRC
+    signatures: &[
+        Signature::new(0, b"ID3"),
+        Signature::new(0, b"\xFF\xFB"),
+        Signature::new(0, b"\xFF\xFA"),
+        Signature::new(0, b"\xFF\xF3"),
+        Signature::new(0, b"\xFF\xF2"),
+    ],
+    config_key: "audio",
+};
+
+/// The CRC protecting MPEG audio frames
+const FRAME_CRC: Crc<u16> = Crc::<u16>::new(&CRC_16_CMS);
+
+/// How far past the ID3v2 tags to look for the first frame
+const FIRST_FRAME_SEARCH: usize = 64 * 1024;
+
+/// Tags that may follow the last frame: ID3v1, APE, Lyrics3 and ID3v2
+const TRAILING_TAGS: &[&[u8]] = &[b"TAG", b"APET", b"LYRI", b"ID3"];
+
+/// Bitrates in kbit/s by bitrate index, for MPEG-1 Layers I, II and III
+const MPEG1_BITRATES: [[u32; 15]; 3] = [
+    [0, 32, 64, 96, 128, 160, 192, 224, 256, 288, 320, 352, 384, 416, 448],
+    [0, 32, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 384],
+    [0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320],
+];
+
+/// Bitrates in kbit/s by bitrate index, for MPEG-2 and 2.5 Layer I, and
+/// Layers II and III
+const MPEG2_BITRATES: [[u32; 15]; 2] = [
+    [0, 32, 48, 56, 64, 80, 96, 112, 128, 144, 160, 176, 192, 224, 256],
+    [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160],
+];
+
+/// Sample rates by sample rate index, for MPEG-1, 2 and 2.5
+const SAMPLE_RATES: [[u32; 3]; 3] = [[44100, 48000, 32000], [22050, 24000, 16000], [11025, 12000, 8000]];
+
+/// Validator for MP3 files. Walks the stream frame by frame, checking that
+/// each frame is whole, that the next one follows it directly, and the CRC of
+/// Layer III frames that carry one.
+pub struct Mp3Validator;
+
+impl Mp3Validator {
+    /// Constructs a new Mp3Validator instance.
+    pub fn new() -> Self {
+        Mp3Validator
+    }
+}
+
+impl Validator for Mp3Validator {
+    fn info(&self) -> &'static ValidatorInfo {
+        &INFO
+    }
+
+    fn validate(
+        &self,
+        path: &Path,
+        throttle: &IoThrottle,
+        interrupt_token: &Arc<AtomicBool>,
+    ) -> Result<(ValidationState, Option<String>), FsPulseError> {
+        let mut file = try_invalid!(ThrottledFile::open_buffered(path, throttle, interrupt_token));
+        let mut offset = 0u64;
+
+        // Skip the ID3v2 tags at the start
+        let mut head = [0u8; 10];
+        let head_len = loop {
+            let read = try_invalid!(read_up_to(&mut file, &mut head));
+            if read < head.len() || &head[..3] != b"ID3" {
+                break read;
+            }
+            let footer = if head[5] & 0x10 != 0 { 10 } else { 0 };
+            let size = syncsafe(&head[6..10]) + footer;
+            let skipped = try_invalid!(io::copy(&mut (&mut file).take(size), &mut io::sink()));
+            if skipped < size {
+                let error = format!("ID3v2 tag at byte {offset} runs past the end of the file");
+                return Ok((ValidationState::Invalid, Some(error)));
+            }
+            offset += head.len() as u64 + size;
+        };
+
+        // Encoders may leave junk between the tags and the first frame
+        let mut window = head[..head_len].to_vec();
+        try_invalid!((&mut file)
+            .take((FIRST_FRAME_SEARCH - head_len) as u64)
+            .read_to_end(&mut window));
+        let at_eof = window.len() < FIRST_FRAME_SEARCH;
+        let start = match find_first_frame(&window, at_eof) {
+            Some(start) => start,
+            // Free-format frames don't give their length, so can't be walked
+            None if window.len() >= 4 && is_free_format(&window[..4]) => {
+                return Ok((ValidationState::Valid, None));
+            }
+            None => return Ok((ValidationState::Invalid, Some("no MPEG audio frames found".to_string()))),
+        };
+
+        let mut cursor = Cursor::new(window);
+        cursor.set_position(start as u64);
+        check_frames(&mut cursor.chain(file), offset + start as u64, interrupt_token)
+    }
+}
+
+/// A parsed frame header
+#[derive(Debug, PartialEq)]
+struct FrameHeader {
+    /// The length of the whole frame, header included
+    len: usize,
+    /// For Layer III frames protected by a CRC, the length of the side
+    /// information the CRC covers. Layer I and II CRCs depend on the bit
+    /// allocation, so aren't checked.
+    side_info_len: Option<usize>,
+}
+
+/// Parse a 4-byte frame header. None if it isn't one, or is free-format.
+fn parse_header(bytes: &[u8]) -> Option<FrameHeader> {
+    if bytes[0] != 0xFF || bytes[1] & 0xE0 != 0xE0 {
+        return None;
+    }
+    // MPEG-1, 2 or 2.5, and Layer I, II or III, by their table indexes
+    let version = match (bytes[1] >> 3) & 0x03 {
+        3 => 0,
+        2 => 1,
+        0 => 2,
+        _ => return None,
+    };
+    let layer = match (bytes[1] >> 1) & 0x03 {
+        3 => 0,
+        2 => 1,
+        1 => 2,
+        _ => return None,
+    };
+    let protected = bytes[1] & 0x01 == 0;
+    let bitrate_index = (bytes[2] >> 4) as usize;
+    let sample_rate_index = ((bytes[2] >> 2) & 0x03) as usize;
+    if bitrate_index == 0 || bitrate_index == 15 || sample_rate_index == 3 || bytes[3] & 0x03 == 2 {
+        return None;
+    }
+    let padding = ((bytes[2] >> 1) & 0x01) as u32;
+    let mono = bytes[3] >> 6 == 3;
+
+    let bitrate = match version {
+        0 => MPEG1_BITRATES[layer][bitrate_index],
+        _ => MPEG2_BITRATES[layer.min(1)][bitrate_index],
+    } * 1000;
+    let sample_rate = SAMPLE_RATES[version][sample_rate_index];
+    let len = match (layer, version) {
+        (0, _) => (12 * bitrate / sample_rate + padding) * 4,
+        (1, _) | (2, 0) => 144 * bitrate / sample_rate + padding,
+        _ => 72 * bitrate / sample_rate + padding,
+    } as usize;
+
+    let side_info_len = match (protected && layer == 2, version, mono) {
+        (false, _, _) => None,
+        (true, 0, true) => Some(17),
+        (true, 0, false) => Some(32),
+        (true, _, true) => Some(9),
+        (true, _, false) => Some(17),
+    };
+    Some(FrameHeader { len, side_info_len })
+}
+
+/// A frame header with a bitrate index of 0
+fn is_free_format(bytes: &[u8]) -> bool {
+    bytes[2] >> 4 == 0 && parse_header(&[bytes[0], bytes[1], bytes[2] | 0x10, bytes[3]]).is_some()
+}
+
+/// The offset of the first frame header that another frame or a trailing tag
+/// follows directly, or that ends the file
+fn find_first_frame(window: &[u8], at_eof: bool) -> Option<usize> {
+    (0..window.len().saturating_sub(3)).find(|&start| {
+        let Some(header) = parse_header(&window[start..start + 4]) else {
+            return false;
+        };
+        let next = start + header.len;
+        match window.get(next..next + 4) {
+            Some(bytes) => parse_header(bytes).is_some() || TRAILING_TAGS.iter().any(|tag| bytes.starts_with(tag)),
+            None => at_eof && next == window.len(),
+        }
+    })
+}
+
+/// Walk frames from the first one to the end of the stream
+fn check_frames<R: Read>(
+    stream: &mut R,
+    mut offset: u64,
+    interrupt_token: &Arc<AtomicBool>,
+) -> Result<(ValidationState, Option<String>), FsPulseError> {
+    let mut frame = Vec::new();
+    for count in 0u64.. {
+        if count % 1024 == 0 && interrupt_token.load(Ordering::Acquire) {
+            return Err(FsPulseError::TaskInterrupted);
+        }
+
+        let mut bytes = [0u8; 4];
+        let read = try_invalid!(read_up_to(stream, &mut bytes));
+        if read == 0 {
+            break;
+        }
+        let Some(header) = parse_header(&bytes).filter(|_| read == bytes.len()) else {
+            return check_trailer(&bytes[..read], stream, offset);
+        };
+
+        frame.clear();
+        frame.extend_from_slice(&bytes);
+        try_invalid!((&mut *stream).take((header.len - bytes.len()) as u64).read_to_end(&mut frame));
+        if frame.len() < header.len {
+            let error = format!(
+                "frame at byte {} is truncated: {} of {} bytes",
+                offset,
+                frame.len(),
+                header.len
+            );
+            return Ok((ValidationState::Invalid, Some(error)));
+        }
+
+        if let Some(side_info_len) = header.side_info_len {
+            // The CRC covers the last two header bytes and the side information
+            let mut digest = FRAME_CRC.digest();
+            digest.update(&frame[2..4]);
+            digest.update(&frame[6..(6 + side_info_len).min(frame.len())]);
+            if digest.finalize() != u16::from_be_bytes([frame[4], frame[5]]) {
+                let error = format!("CRC mismatch in frame at byte {offset}");
+                return Ok((ValidationState::Invalid, Some(error)));
+            }
+        }
+        offset += header.len as u64;
+    }
+
+    Ok((ValidationState::Valid, None))
+}
+
+/// Where the frames stop, only tags or zero padding may follow
+fn check_trailer<R: Read>(
+    start: &[u8],
+    stream: &mut R,
+    offset: u64,
+) -> Result<(ValidationState, Option<String>), FsPulseError> {
+    if TRAILING_TAGS.iter().any(|tag| start.starts_with(tag)) {
+        return Ok((ValidationState::Valid, None));
+    }
+    if start.iter().all(|&b| b == 0) {
+        let mut at = offset + start.len() as u64;
+        let mut buf = [0u8; 8192];
+        loop {
+            let read = try_invalid!(read_up_to(stream, &mut buf));
+            if read == 0 {
+                return Ok((ValidationState::Valid, None));
+            }
+            if let Some(index) = buf[..read].iter().position(|&b| b != 0) {
+                let error = format!("lost frame sync at byte {}", at + index as u64);
+                return Ok((ValidationState::Invalid, Some(error)));
+            }
+            at += read as u64;
+        }
+    }
+
+    let error = if start.len() < 4 {
+        format!("truncated frame header at byte {offset}")
+    } else {
+        format!("lost frame sync at byte {offset}")
+    };
+    Ok((ValidationState::Invalid, Some(error)))
+}
+
+/// An ID3v2 size: 28 bits spread over 4 bytes, 7 to a byte
+fn syncsafe(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0, |size, &b| (size << 7) | (b & 0x7F) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validate::validate_bytes;
+
+    /// MPEG-1 Layer III, 128 kbit/s, 44.1 kHz, stereo: 417 bytes a frame
+    const FRAME_LEN: usize = 417;
+
+    /// A frame with silent side information, protected by a CRC if asked
+    fn frame(protected: bool) -> Vec<u8> {
+        let mut frame = vec![0u8; FRAME_LEN];
+        frame[..4].copy_from_slice(&[0xFF, if protected { 0xFA } else { 0xFB }, 0x90, 0x00]);
+        if protected {
+            let mut digest = FRAME_CRC.digest();
+            digest.update(&frame[2..4]);
+            digest.update(&frame[6..38]);
+            frame[4..6].copy_from_slice(&digest.finalize().to_be_bytes());
+        }
+        frame
+    }
+
+    /// An ID3v2 tag, frames and an ID3v1 tag
+    fn mp3_bytes(protected: bool) -> Vec<u8> {
+        let mut bytes = b"ID3\x04\x00\x00\x00\x00\x00\x14".to_vec();
+        bytes.extend_from_slice(&[0u8; 20]);
+        for _ in 0..5 {
+            bytes.extend(frame(protected));
+        }
+        bytes.extend_from_slice(b"TAG");
+        bytes.extend_from_slice(&[0u8; 125]);
+        bytes
+    }
+
+    /// Where the frames start in `mp3_bytes`
+    const FIRST_FRAME: usize = 30;
+
+    #[test]
+    fn test_parse_header() {
+        assert_eq!(
+            parse_header(&[0xFF, 0xFB, 0x90, 0x00]),
+            Some(FrameHeader { len: 417, side_info_len: None })
+        );
+        // Padded, protected and mono
+        assert_eq!(
+            parse_header(&[0xFF, 0xFA, 0x92, 0xC0]),
+            Some(FrameHeader { len: 418, side_info_len: Some(17) })
+        );
+        // MPEG-2 Layer III, 64 kbit/s, 22.05 kHz
+        assert_eq!(
+            parse_header(&[0xFF, 0xF3, 0x80, 0x00]),
+            Some(FrameHeader { len: 208, side_info_len: None })
+        );
+        // A bad bitrate index, and a reserved layer
+        assert_eq!(parse_header(&[0xFF, 0xFB, 0xF0, 0x00]), None);
+        assert_eq!(parse_header(&[0xFF, 0xF9, 0x90, 0x00]), None);
+    }
+
+    #[test]
+    fn test_mp3_validator_valid_file() {
+        assert_eq!(
+            validate_bytes(&Mp3Validator::new(), ".mp3", &mp3_bytes(false)),
+            (ValidationState::Valid, None)
+        );
+        assert_eq!(
+            validate_bytes(&Mp3Validator::new(), ".mp3", &mp3_bytes(true)),
+            (ValidationState::Valid, None)
+        );
+    }
+
+    #[test]
+    fn test_mp3_validator_truncated_frame() {
+        let bytes = mp3_bytes(false);
+        let end = FIRST_FRAME + FRAME_LEN * 4 + 100;
+        let expected = format!("frame at byte {} is truncated: 100 of 417 bytes", FIRST_FRAME + FRAME_LEN * 4);
+        assert_eq!(
+            validate_bytes(&Mp3Validator::new(), ".mp3", &bytes[..end]),
+            (ValidationState::Invalid, Some(expected))
+        );
+    }
+
+    #[test]
+    fn test_mp3_validator_crc_mismatch() {
+        let mut bytes = mp3_bytes(true);
+        let second = FIRST_FRAME + FRAME_LEN;
+        bytes[second + 10] ^= 0xFF;
+        let expected = format!("CRC mismatch in frame at byte {second}");
+        assert_eq!(
+            validate_bytes(&Mp3Validator::new(), ".mp3", &bytes),
+            (ValidationState::Invalid, Some(expected))
+        );
+    }
+
+    #[test]
+    fn test_mp3_validator_lost_sync() {
+        let mut bytes = mp3_bytes(false);
+        let third = FIRST_FRAME + FRAME_LEN * 2;
+        bytes[third] = 0x00;
+        let expected = format!("lost frame sync at byte {third}");
+        assert_eq!(
+            validate_bytes(&Mp3Validator::new(), ".mp3", &bytes),
+            (ValidationState::Invalid, Some(expected))
+        );
+    }
+
+    #[test]
+    fn test_mp3_validator_no_frames() {
+        assert_eq!(
+            validate_bytes(&Mp3Validator::new(), ".mp3", b"not an MP3 at all"),
+            (ValidationState::Invalid, Some("no MPEG audio frames found".to_string()))
+        );
+    }
+}
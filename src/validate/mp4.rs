@@ -0,0 +1,574 @@
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::{path::Path, sync::Arc};
+
+use crate::error::FsPulseError;
+use crate::io_throttle::{IoThrottle, ThrottledFile};
+use crate::try_invalid;
+
+use super::validator::{Signature, ValidationState, Validator, ValidatorInfo, WalkError};
+
+static M4A_INFO: ValidatorInfo = ValidatorInfo {
+    name: "m4a",
+    version: 1,
+    extensions: &["m4a", "m4b", "m4p"],
+    // The ftyp box's major brand: audio, audiobook or protected audio
+    signatures: &[
+        Signature::new(4, b"ftypM4A "),
+        Signature::new(4, b"ftypM4B "),
+        Signature::new(4, b"ftypM4P "),
+    ],
+    config_key: "audio",
+};
+
+static MP4_INFO: ValidatorInfo = ValidatorInfo {
+    name: "mp4",
+    version: 1,
+    extensions: &["mp4", "m4v", "mov"],
+    // The common ftyp brands for video. Other brands, such as HEIF images,
+    // share the box structure but not the movie. Older QuickTime movies start
+    // without an ftyp box.
+    signatures: &[
+        Signature::new(4, b"ftypisom"),
+        Signature::new(4, b"ftypiso2"),
+        Signature::new(4, b"ftypiso4"),
+        Signature::new(4, b"ftypiso5"),
+        Signature::new(4, b"ftypiso6"),
+        Signature::new(4, b"ftypmp41"),
+        Signature::new(4, b"ftypmp42"),
+        Signature::new(4, b"ftypavc1"),
+        Signature::new(4, b"ftypdash"),
+        Signature::new(4, b"ftypM4V "),
+        Signature::new(4, b"ftypM4VH"),
+        Signature::new(4, b"ftypM4VP"),
+        Signature::new(4, b"ftypqt  "),
+        Signature::new(4, b"ftyp3gp4"),
+        Signature::new(4, b"ftyp3gp5"),
+        Signature::new(4, b"ftyp3gp6"),
+        Signature::new(4, b"ftyp3g2a"),
+        Signature::new(4, b"ftypXAVC"),
+        Signature::new(4, b"moov"),
+        Signature::new(4, b"mdat"),
+        Signature::new(4, b"wide"),
+    ],
+    config_key: "video",
+};
+
+/// Boxes that hold nothing but other boxes
+const CONTAINERS: &[&[u8; 4]] = &[
+    b"moov", b"trak", b"mdia", b"minf", b"stbl", b"dinf", b"edts", b"mvex", b"moof", b"traf", b"mfra",
+];
+
+/// How many containers deep the walk goes before calling the file invalid.
+/// Real files nest a handful of levels; this keeps crafted ones from
+/// overflowing the stack.
+const MAX_DEPTH: usize = 32;
+
+/// Validator for MPEG-4 audio files. Walks the box tree, checking that each
+/// box fits in its parent and the file, and that the movie and media data
+/// are there.
+pub struct M4aValidator;
+
+impl M4aValidator {
+    /// Constructs a new M4aValidator instance.
+    pub fn new() -> Self {
+        M4aValidator
+    }
+}
+
+impl Validator for M4aValidator {
+    fn info(&self) -> &'static ValidatorInfo {
+        &M4A_INFO
+    }
+
+    fn validate(
+        &self,
+        path: &Path,
+        throttle: &IoThrottle,
+        interrupt_token: &Arc<AtomicBool>,
+    ) -> Result<(ValidationState, Option<String>), FsPulseError> {
+        let file_len = try_invalid!(std::fs::metadata(path)).len();
+        check_movie(path, file_len, throttle, interrupt_token, |_, _, _| Ok(()))
+    }
+}
+
+/// Validator for MP4 and QuickTime movies. Walks the box tree like the M4A
+/// validator, then checks that every chunk of samples each track's sample
+/// tables place in the file lies within it. Nothing is decoded.
+pub struct Mp4Validator;
+
+impl Mp4Validator {
+    /// Constructs a new Mp4Validator instance.
+    pub fn new() -> Self {
+        Mp4Validator
+    }
+}
+
+impl Validator for Mp4Validator {
+    fn info(&self) -> &'static ValidatorInfo {
+        &MP4_INFO
+    }
+
+    fn validate(
+        &self,
+        path: &Path,
+        throttle: &IoThrottle,
+        interrupt_token: &Arc<AtomicBool>,
+    ) -> Result<(ValidationState, Option<String>), FsPulseError> {
+        let file_len = try_invalid!(std::fs::metadata(path)).len();
+        let mut tracks: Vec<SampleTables> = Vec::new();
+        let result = check_movie(path, file_len, throttle, interrupt_token, |header, ancestors, reader| {
+            if &header.kind == b"trak" {
+                tracks.push(SampleTables::default());
+            } else if ancestors.last() == Some(b"stbl") {
+                if let Some(track) = tracks.last_mut() {
+                    track.read(header, reader)?;
+                }
+            }
+            Ok(())
+        })?;
+        if result.0 != ValidationState::Valid {
+            return Ok(result);
+        }
+
+        for (index, track) in tracks.iter().enumerate() {
+            if let Err(error) = track.check(index + 1, file_len) {
+                return Ok((ValidationState::Invalid, Some(error)));
+            }
+        }
+        Ok((ValidationState::Valid, None))
+    }
+}
+
+/// Walk a movie's box tree, passing each box to `visit`, and check that the
+/// movie and its media data are there
+fn check_movie<'a, F>(
+    path: &Path,
+    file_len: u64,
+    throttle: &'a IoThrottle,
+    interrupt_token: &'a Arc<AtomicBool>,
+    mut visit: F,
+) -> Result<(ValidationState, Option<String>), FsPulseError>
+where
+    F: FnMut(&BoxHeader, &[[u8; 4]], &mut BufReader<ThrottledFile<'a>>) -> Result<(), WalkError>,
+{
+    let mut file = try_invalid!(ThrottledFile::open_buffered(path, throttle, interrupt_token));
+
+    let mut top_level = Vec::new();
+    let walked = walk_boxes(&mut file, file_len, interrupt_token, &mut |header, ancestors, reader| {
+        if ancestors.is_empty() {
+            top_level.push(header.kind);
+        }
+        visit(header, ancestors, reader)
+    });
+    match walked {
+        Ok(()) => {}
+        Err(WalkError::Invalid(error)) => return Ok((ValidationState::Invalid, Some(error))),
+        Err(WalkError::Interrupted) => return Err(FsPulseError::TaskInterrupted),
+    }
+
+    for required in [b"moov", b"mdat"] {
+        if !top_level.contains(required) {
+            let error = format!("no {} box", String::from_utf8_lossy(required));
+            return Ok((ValidationState::Invalid, Some(error)));
+        }
+    }
+    Ok((ValidationState::Valid, None))
+}
+
+/// What a track's sample tables say about where its samples are
+#[derive(Default)]
+struct SampleTables {
+    /// The size of every sample, or 0 if they're listed in `sample_sizes`
+    sample_size: u32,
+    sample_count: usize,
+    sample_sizes: Vec<u32>,
+    /// Runs of chunks with the same number of samples: the first chunk of
+    /// each run, counting from 1, and its samples per chunk
+    sample_to_chunk: Vec<(u32, u32)>,
+    chunk_offsets: Vec<u64>,
+}
+
+impl SampleTables {
+    /// Read a sample table box, with the reader at its data
+    fn read<R: Read>(&mut self, header: &BoxHeader, reader: &mut R) -> Result<(), WalkError> {
+        match &header.kind {
+            b"stsz" | b"stsc" | b"stco" | b"co64" => {}
+            _ => return Ok(()),
+        }
+        let mut data = Vec::new();
+        reader.take(header.size - header.header_len).read_to_end(&mut data)?;
+
+        match &header.kind {
+            b"stsz" => {
+                if data.len() < 12 {
+                    return Err(too_short(header));
+                }
+                self.sample_size = be_u32(&data[4..8]);
+                self.sample_count = be_u32(&data[8..12]) as usize;
+                if self.sample_size == 0 {
+                    self.sample_sizes = table_entries(header, &data, 4, 4)?.map(be_u32).collect();
+                }
+            }
+            b"stsc" => {
+                self.sample_to_chunk = table_entries(header, &data, 0, 12)?
+                    .map(|entry| (be_u32(&entry[0..4]), be_u32(&entry[4..8])))
+                    .collect();
+            }
+            b"stco" => {
+                self.chunk_offsets = table_entries(header, &data, 0, 4)?.map(|entry| be_u32(entry) as u64).collect();
+            }
+            _ => {
+                self.chunk_offsets = table_entries(header, &data, 0, 8)?
+                    .map(|entry| u64::from_be_bytes(entry.try_into().expect("8 bytes")))
+                    .collect();
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that each chunk of samples lies within the file
+    fn check(&self, track: usize, file_len: u64) -> Result<(), String> {
+        let mut entry = 0;
+        let mut sample = 0;
+        for (index, &offset) in self.chunk_offsets.iter().enumerate() {
+            let chunk = index as u32 + 1;
+            while self.sample_to_chunk.get(entry + 1).is_some_and(|&(first, _)| first <= chunk) {
+                entry += 1;
+            }
+            let samples = match self.sample_to_chunk.get(entry) {
+                Some(&(first, samples)) if first <= chunk => samples as usize,
+                _ => 0,
+            };
+            let samples = samples.min(self.sample_count.saturating_sub(sample));
+            let size = if self.sample_size != 0 {
+                samples as u64 * self.sample_size as u64
+            } else {
+                self.sample_sizes[sample..sample + samples].iter().map(|&size| size as u64).sum()
+            };
+            sample += samples;
+
+            if offset.saturating_add(size) > file_len {
+                return Err(format!(
+                    "track {}: chunk {} at byte {} runs past the end of the file: {} bytes of samples, {} present",
+                    track,
+                    chunk,
+                    offset,
+                    size,
+                    file_len.saturating_sub(offset)
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The entries of a sample table: after the version and flags and `skip`
+/// more bytes, a count and then that many entries of `width` bytes
+fn table_entries<'d>(
+    header: &BoxHeader,
+    data: &'d [u8],
+    skip: usize,
+    width: usize,
+) -> Result<std::slice::ChunksExact<'d, u8>, WalkError> {
+    let start = 4 + skip + 4;
+    if data.len() < start {
+        return Err(too_short(header));
+    }
+    let count = be_u32(&data[start - 4..start]) as usize;
+    match count.checked_mul(width).map(|len| start + len) {
+        Some(end) if end <= data.len() => Ok(data[start..end].chunks_exact(width)),
+        _ => Err(WalkError::Invalid(format!(
+            "'{}' box at byte {} is too short for its {} entries",
+            header.name(),
+            header.offset,
+            count
+        ))),
+    }
+}
+
+fn too_short(header: &BoxHeader) -> WalkError {
+    WalkError::Invalid(format!("'{}' box at byte {} is too short", header.name(), header.offset))
+}
+
+fn be_u32(bytes: &[u8]) -> u32 {
+    u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+/// A box's header
+struct BoxHeader {
+    kind: [u8; 4],
+    /// Where the box's header starts
+    offset: u64,
+    /// 8 bytes, or 16 with a 64-bit size
+    header_len: u64,
+    /// The length of the whole box, header included
+    size: u64,
+}
+
+impl BoxHeader {
+    fn name(&self) -> String {
+        String::from_utf8_lossy(&self.kind).into_owned()
+    }
+}
+
+/// Walk the whole box tree, checking that each box fits in its parent and
+/// the file. `visit` sees each box with the kinds of the boxes it's in, and
+/// the reader at the start of its data.
+fn walk_boxes<R, F>(
+    reader: &mut R,
+    file_len: u64,
+    interrupt_token: &Arc<AtomicBool>,
+    visit: &mut F,
+) -> Result<(), WalkError>
+where
+    R: Read + Seek,
+    F: FnMut(&BoxHeader, &[[u8; 4]], &mut R) -> Result<(), WalkError>,
+{
+    walk_range(reader, 0, file_len, &mut Vec::new(), interrupt_token, visit)
+}
+
+fn walk_range<R, F>(
+    reader: &mut R,
+    start: u64,
+    end: u64,
+    ancestors: &mut Vec<[u8; 4]>,
+    interrupt_token: &Arc<AtomicBool>,
+    visit: &mut F,
+) -> Result<(), WalkError>
+where
+    R: Read + Seek,
+    F: FnMut(&BoxHeader, &[[u8; 4]], &mut R) -> Result<(), WalkError>,
+{
+    let mut offset = start;
+    // Fewer than 8 bytes can't hold a box. QuickTime ends some lists of
+    // boxes with 4 zero bytes.
+    while end - offset >= 8 {
+        if interrupt_token.load(Ordering::Acquire) {
+            return Err(WalkError::Interrupted);
+        }
+
+        reader.seek(SeekFrom::Start(offset))?;
+        let mut bytes = [0u8; 8];
+        reader.read_exact(&mut bytes)?;
+        let kind = [bytes[4], bytes[5], bytes[6], bytes[7]];
+        let (size, header_len) = match u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) {
+            1 => {
+                if end - offset < 16 {
+                    return Err(WalkError::Invalid(format!(
+                        "'{}' box at byte {} is truncated in its header",
+                        String::from_utf8_lossy(&kind),
+                        offset
+                    )));
+                }
+                let mut large = [0u8; 8];
+                reader.read_exact(&mut large)?;
+                (u64::from_be_bytes(large), 16)
+            }
+            // The last box in the file can run to its end
+            0 if ancestors.is_empty() => (end - offset, 8),
+            size => (size as u64, 8),
+        };
+        let header = BoxHeader { kind, offset, header_len, size };
+
+        if header.size < header.header_len {
+            return Err(WalkError::Invalid(format!(
+                "'{}' box at byte {} has an invalid size of {} bytes",
+                header.name(),
+                header.offset,
+                header.size
+            )));
+        }
+        if header.size > end - header.offset {
+            let error = match ancestors.last() {
+                None => format!(
+                    "'{}' box at byte {} runs past the end of the file: {} bytes declared, {} present",
+                    header.name(),
+                    header.offset,
+                    header.size,
+                    end - header.offset
+                ),
+                Some(parent) => format!(
+                    "'{}' box at byte {} runs past the end of its '{}' box",
+                    header.name(),
+                    header.offset,
+                    String::from_utf8_lossy(parent)
+                ),
+            };
+            return Err(WalkError::Invalid(error));
+        }
+
+        visit(&header, ancestors, reader)?;
+        if CONTAINERS.contains(&&header.kind) {
+            if ancestors.len() == MAX_DEPTH {
+                return Err(WalkError::Invalid(format!(
+                    "'{}' box at byte {} is nested more than {} boxes deep",
+                    header.name(),
+                    header.offset,
+                    MAX_DEPTH
+                )));
+            }
+            ancestors.push(header.kind);
+            let data_start = header.offset + header.header_len;
+            walk_range(reader, data_start, header.offset + header.size, ancestors, interrupt_token, visit)?;
+            ancestors.pop();
+        }
+        offset += header.size;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validate::validate_bytes;
+
+    fn mp4_box(kind: &[u8; 4], data: &[u8]) -> Vec<u8> {
+        let mut bytes = ((8 + data.len()) as u32).to_be_bytes().to_vec();
+        bytes.extend_from_slice(kind);
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    /// An ftyp box, a movie box holding an empty track, and media data
+    fn m4a_bytes() -> Vec<u8> {
+        let mut bytes = mp4_box(b"ftyp", b"M4A \0\0\0\0M4A mp42");
+        let track = mp4_box(b"trak", &mp4_box(b"tkhd", &[0u8; 84]));
+        let mut movie = mp4_box(b"mvhd", &[0u8; 100]);
+        movie.extend(track);
+        bytes.extend(mp4_box(b"moov", &movie));
+        bytes.extend(mp4_box(b"mdat", &[0u8; 64]));
+        bytes
+    }
+
+    /// A movie with one track of four samples, two to a chunk. The second
+    /// chunk starts `second_chunk` bytes into the media data, which the
+    /// first chunk's 30 bytes and the second's 70 fill.
+    fn mp4_bytes(second_chunk: u32) -> Vec<u8> {
+        let full_box = |kind: &[u8; 4], fields: &[u32]| {
+            let data: Vec<u8> = [0u32].iter().chain(fields).flat_map(|field: &u32| field.to_be_bytes()).collect();
+            mp4_box(kind, &data)
+        };
+        let movie = |chunk_offsets: [u32; 2]| {
+            let tables = [
+                full_box(b"stsz", &[0, 4, 10, 20, 30, 40]),
+                full_box(b"stsc", &[1, 1, 2, 1]),
+                full_box(b"stco", &[2, chunk_offsets[0], chunk_offsets[1]]),
+            ];
+            let stbl = mp4_box(b"stbl", &tables.concat());
+            mp4_box(b"moov", &mp4_box(b"trak", &mp4_box(b"mdia", &mp4_box(b"minf", &stbl))))
+        };
+
+        let mut bytes = mp4_box(b"ftyp", b"isom\0\0\x02\0isomiso2mp41");
+        let media_start = (bytes.len() + movie([0, 0]).len() + 8) as u32;
+        bytes.extend(movie([media_start, media_start + second_chunk]));
+        bytes.extend(mp4_box(b"mdat", &[0u8; 100]));
+        bytes
+    }
+
+    #[test]
+    fn test_m4a_validator_valid_file() {
+        assert_eq!(
+            validate_bytes(&M4aValidator::new(), ".m4a", &m4a_bytes()),
+            (ValidationState::Valid, None)
+        );
+
+        // A 64-bit size on the media data
+        let mut bytes = m4a_bytes();
+        let mdat = bytes.len() - 72;
+        bytes.truncate(mdat);
+        bytes.extend_from_slice(&1u32.to_be_bytes());
+        bytes.extend_from_slice(b"mdat");
+        bytes.extend_from_slice(&80u64.to_be_bytes());
+        bytes.extend_from_slice(&[0u8; 64]);
+        assert_eq!(validate_bytes(&M4aValidator::new(), ".m4a", &bytes), (ValidationState::Valid, None));
+    }
+
+    #[test]
+    fn test_m4a_validator_truncated_media_data() {
+        let bytes = m4a_bytes();
+        let mdat = bytes.len() - 72;
+        let expected = format!("'mdat' box at byte {mdat} runs past the end of the file: 72 bytes declared, 40 present");
+        assert_eq!(
+            validate_bytes(&M4aValidator::new(), ".m4a", &bytes[..bytes.len() - 32]),
+            (ValidationState::Invalid, Some(expected))
+        );
+    }
+
+    #[test]
+    fn test_m4a_validator_box_overruns_parent() {
+        let mut bytes = m4a_bytes();
+        // Grow the track header past the end of its track
+        let tkhd = bytes.windows(4).position(|w| w == b"tkhd").unwrap() - 4;
+        bytes[tkhd + 3] += 8;
+        let expected = format!("'tkhd' box at byte {tkhd} runs past the end of its 'trak' box");
+        assert_eq!(
+            validate_bytes(&M4aValidator::new(), ".m4a", &bytes),
+            (ValidationState::Invalid, Some(expected))
+        );
+    }
+
+    #[test]
+    fn test_m4a_validator_missing_movie() {
+        let mut bytes = mp4_box(b"ftyp", b"M4A \0\0\0\0M4A mp42");
+        bytes.extend(mp4_box(b"mdat", &[0u8; 64]));
+        assert_eq!(
+            validate_bytes(&M4aValidator::new(), ".m4a", &bytes),
+            (ValidationState::Invalid, Some("no moov box".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_m4a_validator_nested_too_deep() {
+        // A movie holding 40 tracks, each inside the last
+        let mut nested = mp4_box(b"tkhd", &[0u8; 84]);
+        for _ in 0..40 {
+            nested = mp4_box(b"trak", &nested);
+        }
+        let mut bytes = mp4_box(b"ftyp", b"M4A \0\0\0\0M4A mp42");
+        let moov = bytes.len();
+        bytes.extend(mp4_box(b"moov", &nested));
+        bytes.extend(mp4_box(b"mdat", &[0u8; 64]));
+
+        // The movie is the first container, so the 32nd track is one too many
+        let expected = format!("'trak' box at byte {} is nested more than 32 boxes deep", moov + 32 * 8);
+        assert_eq!(
+            validate_bytes(&M4aValidator::new(), ".m4a", &bytes),
+            (ValidationState::Invalid, Some(expected))
+        );
+    }
+
+    #[test]
+    fn test_mp4_validator_valid_file() {
+        assert_eq!(
+            validate_bytes(&Mp4Validator::new(), ".mp4", &mp4_bytes(30)),
+            (ValidationState::Valid, None)
+        );
+    }
+
+    #[test]
+    fn test_mp4_validator_chunk_past_end() {
+        let bytes = mp4_bytes(50);
+        let second_chunk = bytes.len() - 50;
+        let expected = format!(
+            "track 1: chunk 2 at byte {second_chunk} runs past the end of the file: 70 bytes of samples, 50 present"
+        );
+        assert_eq!(
+            validate_bytes(&Mp4Validator::new(), ".mp4", &bytes),
+            (ValidationState::Invalid, Some(expected))
+        );
+    }
+
+    #[test]
+    fn test_mp4_validator_truncated_sample_table() {
+        let mut bytes = mp4_bytes(30);
+        // Claim a fifth chunk offset the stco box has no room for
+        let stco = bytes.windows(4).position(|w| w == b"stco").unwrap() - 4;
+        bytes[stco + 15] = 5;
+        let expected = format!("'stco' box at byte {stco} is too short for its 5 entries");
+        assert_eq!(
+            validate_bytes(&Mp4Validator::new(), ".mp4", &bytes),
+            (ValidationState::Invalid, Some(expected))
+        );
+    }
+}
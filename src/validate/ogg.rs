@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::{path::Path, sync::Arc};
+
+use crc::{Algorithm, Crc};
+
+use crate::error::FsPulseError;
+use crate::io_throttle::{IoThrottle, ThrottledFile};
+use crate::try_invalid;
+
+use super::validator::{read_up_to, Signature, ValidationState, Validator, ValidatorInfo};
+
+static INFO: ValidatorInfo = ValidatorInfo {
+    name: "ogg",
+    version: 1,
+    // Vorbis, FLAC and Opus audio in an Ogg container
+    extensions: &["ogg", "oga", "opus"],
+    signatures: &[Signature::new(0, b"OggS")],
+    config_key: "audio",
+};
+
+/// The CRC in each page header: CRC-32 with no reflection and a zero
+/// initial value, unlike the usual CRC-32
+const PAGE_CRC: Crc<u32> = Crc::<u32>::new(&Algorithm {
+    width: 32,
+    poly: 0x04C1_1DB7,
+    init: 0,
+    refin: false,
+    refout: false,
+    xorout: 0,
+    check: 0x89A1_897F,
+    residue: 0,
+});
+
+/// The fixed part of a page header, before its segment table
+const PAGE_HEADER_LEN: usize = 27;
+
+const FIRST_PAGE: u8 = 0x02;
+const LAST_PAGE: u8 = 0x04;
+
+/// Validator for Ogg files. Checks every page's CRC, and that each logical
+/// stream's pages run in sequence from its first page to its last.
+pub struct OggValidator;
+
+impl OggValidator {
+    /// Constructs a new OggValidator instance.
+    pub fn new() -> Self {
+        OggValidator
+    }
+}
+
+/// A logical stream's progress through the file
+struct StreamState {
+    next_sequence: u32,
+    ended: bool,
+}
+
+impl Validator for OggValidator {
+    fn info(&self) -> &'static ValidatorInfo {
+        &INFO
+    }
+
+    fn validate(
+        &self,
+        path: &Path,
+        throttle: &IoThrottle,
+        interrupt_token: &Arc<AtomicBool>,
+    ) -> Result<(ValidationState, Option<String>), FsPulseError> {
+        let mut file = try_invalid!(ThrottledFile::open_buffered(path, throttle, interrupt_token));
+        let mut streams: HashMap<u32, StreamState> = HashMap::new();
+        let mut offset = 0u64;
+        let mut body = Vec::new();
+
+        for count in 0u64.. {
+            if count % 256 == 0 && interrupt_token.load(Ordering::Acquire) {
+                return Err(FsPulseError::TaskInterrupted);
+            }
+
+            let mut header = [0u8; PAGE_HEADER_LEN + 255];
+            let read = try_invalid!(read_up_to(&mut file, &mut header[..PAGE_HEADER_LEN]));
+            if read == 0 {
+                break;
+            }
+            if !b"OggS".starts_with(&header[..read.min(4)]) {
+                let error = format!("no page at byte {offset}");
+                return Ok((ValidationState::Invalid, Some(error)));
+            }
+            if read < PAGE_HEADER_LEN {
+                let error = format!("page at byte {offset} is truncated in its header");
+                return Ok((ValidationState::Invalid, Some(error)));
+            }
+            if header[4] != 0 {
+                let error = format!("page at byte {} has unknown version {}", offset, header[4]);
+                return Ok((ValidationState::Invalid, Some(error)));
+            }
+            let segments = header[PAGE_HEADER_LEN - 1] as usize;
+            let header_len = PAGE_HEADER_LEN + segments;
+            let read = read + try_invalid!(read_up_to(&mut file, &mut header[PAGE_HEADER_LEN..header_len]));
+            if read < header_len {
+                let error = format!("page at byte {offset} is truncated in its header");
+                return Ok((ValidationState::Invalid, Some(error)));
+            }
+
+            let body_len: usize = header[PAGE_HEADER_LEN..header_len].iter().map(|&b| b as usize).sum();
+            body.clear();
+            try_invalid!((&mut file).take(body_len as u64).read_to_end(&mut body));
+            if body.len() < body_len {
+                let error = format!(
+                    "page at byte {} is truncated: {} of {} bytes",
+                    offset,
+                    header_len + body.len(),
+                    header_len + body_len
+                );
+                return Ok((ValidationState::Invalid, Some(error)));
+            }
+
+            // The CRC is computed with its own field zeroed
+            let stored_crc = u32::from_le_bytes([header[22], header[23], header[24], header[25]]);
+            header[22..26].fill(0);
+            let mut digest = PAGE_CRC.digest();
+            digest.update(&header[..header_len]);
+            digest.update(&body);
+            if digest.finalize() != stored_crc {
+                let error = format!("CRC mismatch in page at byte {offset}");
+                return Ok((ValidationState::Invalid, Some(error)));
+            }
+
+            let flags = header[5];
+            let serial = u32::from_le_bytes([header[14], header[15], header[16], header[17]]);
+            let sequence = u32::from_le_bytes([header[18], header[19], header[20], header[21]]);
+            if flags & FIRST_PAGE != 0 {
+                streams.insert(serial, StreamState { next_sequence: sequence, ended: false });
+            }
+            let Some(stream) = streams.get_mut(&serial) else {
+                let error = format!("page at byte {offset} belongs to stream {serial}, which has no first page");
+                return Ok((ValidationState::Invalid, Some(error)));
+            };
+            if stream.ended {
+                let error = format!("page at byte {offset} follows the last page of stream {serial}");
+                return Ok((ValidationState::Invalid, Some(error)));
+            }
+            if sequence != stream.next_sequence {
+                let error = format!(
+                    "page at byte {} is out of sequence: expected page {} of stream {}, found page {}",
+                    offset, stream.next_sequence, serial, sequence
+                );
+                return Ok((ValidationState::Invalid, Some(error)));
+            }
+            stream.next_sequence = sequence.wrapping_add(1);
+            stream.ended = flags & LAST_PAGE != 0;
+
+            offset += (header_len + body_len) as u64;
+        }
+
+        if streams.is_empty() {
+            return Ok((ValidationState::Invalid, Some("no Ogg pages found".to_string())));
+        }
+        // A file cut off at a page boundary is missing its streams' last pages
+        let unended = streams.iter().filter(|(_, stream)| !stream.ended).map(|(&serial, _)| serial).min();
+        if let Some(serial) = unended {
+            let error = format!("stream {serial} ends at byte {offset} without its last page");
+            return Ok((ValidationState::Invalid, Some(error)));
+        }
+
+        Ok((ValidationState::Valid, None))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validate::validate_bytes;
+
+    /// A page of one stream, with a correct CRC
+    fn page(flags: u8, serial: u32, sequence: u32, body: &[u8]) -> Vec<u8> {
+        let mut page = b"OggS\x00".to_vec();
+        page.push(flags);
+        page.extend_from_slice(&0u64.to_le_bytes());
+        page.extend_from_slice(&serial.to_le_bytes());
+        page.extend_from_slice(&sequence.to_le_bytes());
+        page.extend_from_slice(&[0; 4]);
+        page.push(1);
+        page.push(body.len() as u8);
+        page.extend_from_slice(body);
+        let crc = PAGE_CRC.checksum(&page);
+        page[22..26].copy_from_slice(&crc.to_le_bytes());
+        page
+    }
+
+    /// One stream of three pages
+    fn ogg_bytes() -> Vec<u8> {
+        let mut bytes = page(FIRST_PAGE, 7, 0, b"first page");
+        bytes.extend(page(0, 7, 1, b"middle page"));
+        bytes.extend(page(LAST_PAGE, 7, 2, b"last page"));
+        bytes
+    }
+
+    #[test]
+    fn test_page_crc() {
+        assert_eq!(PAGE_CRC.checksum(b"123456789"), 0x89A1_897F);
+    }
+
+    #[test]
+    fn test_ogg_validator_valid_file() {
+        assert_eq!(
+            validate_bytes(&OggValidator::new(), ".ogg", &ogg_bytes()),
+            (ValidationState::Valid, None)
+        );
+    }
+
+    #[test]
+    fn test_ogg_validator_crc_mismatch() {
+        let mut bytes = ogg_bytes();
+        let second = page(FIRST_PAGE, 7, 0, b"first page").len();
+        bytes[second + 30] ^= 0xFF;
+        let expected = format!("CRC mismatch in page at byte {second}");
+        assert_eq!(
+            validate_bytes(&OggValidator::new(), ".ogg", &bytes),
+            (ValidationState::Invalid, Some(expected))
+        );
+    }
+
+    #[test]
+    fn test_ogg_validator_truncated_page() {
+        let bytes = ogg_bytes();
+        let (state, error) = validate_bytes(&OggValidator::new(), ".ogg", &bytes[..bytes.len() - 3]);
+        assert_eq!(state, ValidationState::Invalid);
+        assert!(error.unwrap().ends_with("is truncated: 34 of 37 bytes"));
+    }
+
+    #[test]
+    fn test_ogg_validator_missing_pages() {
+        // The middle page is gone
+        let mut bytes = page(FIRST_PAGE, 7, 0, b"first page");
+        let second = bytes.len();
+        bytes.extend(page(LAST_PAGE, 7, 2, b"last page"));
+        let expected = format!("page at byte {second} is out of sequence: expected page 1 of stream 7, found page 2");
+        assert_eq!(
+            validate_bytes(&OggValidator::new(), ".ogg", &bytes),
+            (ValidationState::Invalid, Some(expected))
+        );
+
+        // The last page is gone
+        let bytes = ogg_bytes();
+        let end = bytes.len() - page(LAST_PAGE, 7, 2, b"last page").len();
+        let expected = format!("stream 7 ends at byte {end} without its last page");
+        assert_eq!(
+            validate_bytes(&OggValidator::new(), ".ogg", &bytes[..end]),
+            (ValidationState::Invalid, Some(expected))
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use once_cell::sync::Lazy;
+
+use super::claxon::ClaxonValidator;
+use super::compressed::{GzipValidator, XzValidator, ZstdValidator};
+use super::image::ImageValidator;
+use super::lopdf::LopdfValidator;
+use super::mp3::Mp3Validator;
+use super::mkv::MkvValidator;
+use super::mp4::{M4aValidator, Mp4Validator};
+use super::ogg::OggValidator;
+use super::riff::{AiffValidator, WavValidator};
+use super::sevenz::SevenZValidator;
+use super::tar::TarValidator;
+use super::validator::{Validator, ValidatorInfo};
+use super::zip::ZipValidator;
+
+static REGISTRY: Lazy<ValidatorRegistry> = Lazy::new(|| {
+    ValidatorRegistry::new(vec![
+        Box::new(ImageValidator::new()),
+        Box::new(LopdfValidator::new()),
+        Box::new(ClaxonValidator::new()),
+        Box::new(Mp3Validator::new()),
+        Box::new(OggValidator::new()),
+        Box::new(WavValidator::new()),
+        Box::new(AiffValidator::new()),
+        Box::new(M4aValidator::new()),
+        Box::new(Mp4Validator::new()),
+        Box::new(MkvValidator::new()),
+        Box::new(ZipValidator::new()),
+        Box::new(TarValidator::new()),
+        Box::new(GzipValidator::new()),
+        Box::new(XzValidator::new()),
+        Box::new(ZstdValidator::new()),
+        Box::new(SevenZValidator::new()),
+    ])
+});
+
+/// The validators fsPulse knows about. Everything that asks which files can
+/// be validated goes through here, so it always agrees with what runs.
+pub struct ValidatorRegistry {
+    validators: Vec<Box<dyn Validator>>,
+}
+
+impl ValidatorRegistry {
+    fn new(validators: Vec<Box<dyn Validator>>) -> Self {
+        ValidatorRegistry { validators }
+    }
+
+    pub fn global() -> &'static ValidatorRegistry {
+        &REGISTRY
+    }
+
+    /// Every registered validator's info, in registration order
+    pub fn infos(&self) -> impl Iterator<Item = &'static ValidatorInfo> + '_ {
+        self.validators.iter().map(|v| v.info())
+    }
+
+    /// The validator for a file extension (case-insensitive)
+    pub fn for_extension(&self, ext: &str) -> Option<&dyn Validator> {
+        let ext = ext.to_ascii_lowercase();
+        self.validators
+            .iter()
+            .find(|v| v.info().extensions.contains(&ext.as_str()))
+            .map(|v| v.as_ref())
+    }
+
+    /// The validator registered under a name
+    pub fn for_name(&self, name: &str) -> Option<&dyn Validator> {
+        self.validators
+            .iter()
+            .find(|v| v.info().name == name)
+            .map(|v| v.as_ref())
+    }
+
+    /// The validator for a file, given its lowercase extension and the type
+    /// detected from its contents. One is preferred and the other is the
+    /// fallback, so there is a validator if either has one.
+    pub fn select(
+        &self,
+        extension: Option<&str>,
+        content_type: Option<&str>,
+        prefer_content: bool,
+    ) -> Option<&dyn Validator> {
+        let by_extension = extension.and_then(|ext| self.for_extension(ext));
+        let by_content = content_type.and_then(|name| self.for_name(name));
+        if prefer_content {
+            by_content.or(by_extension)
+        } else {
+            by_extension.or(by_content)
+        }
+    }
+
+    /// Every registered validator's name
+    pub fn validator_names(&self) -> Vec<&'static str> {
+        self.infos().map(|info| info.name).collect()
+    }
+
+    /// Check whether a lowercase extension string has a validator.
+    pub fn has_validator_extension(&self, ext: &str) -> bool {
+        self.infos().any(|info| info.extensions.contains(&ext))
+    }
+
+    /// Every extension with a validator, enabled or not
+    pub fn validator_extensions(&self) -> Vec<&'static str> {
+        self.infos().flat_map(|info| info.extensions.iter().copied()).collect()
+    }
+
+    /// Extensions whose validators are disabled in the config. Files with
+    /// them are left unvalidated.
+    pub fn disabled_extensions(&self) -> Vec<&'static str> {
+        self.infos()
+            .filter(|info| !info.is_enabled())
+            .flat_map(|info| info.extensions.iter().copied())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn test_for_extension() {
+        let registry = ValidatorRegistry::global();
+        for ext in ["flac", "jpg", "jpeg", "png", "gif", "tiff", "bmp", "pdf"] {
+            assert!(registry.for_extension(ext).is_some(), "no validator for {ext}");
+            assert!(registry.has_validator_extension(ext));
+        }
+        assert!(registry.for_extension("txt").is_none());
+        assert!(registry.for_extension("unknown").is_none());
+        assert!(!registry.has_validator_extension("txt"));
+
+        // Lookup ignores case
+        assert_eq!(registry.for_extension("FLAC").unwrap().info().name, "flac");
+        assert_eq!(registry.for_extension("JPG").unwrap().info().name, "image");
+        assert_eq!(registry.for_extension("PDF").unwrap().info().name, "pdf");
+    }
+
+    #[test]
+    fn test_select() {
+        let registry = ValidatorRegistry::global();
+        let name = |v: Option<&dyn Validator>| v.map(|v| v.info().name);
+
+        // A renamed image: the extension wins unless content is preferred
+        assert_eq!(name(registry.select(Some("pdf"), Some("image"), false)), Some("pdf"));
+        assert_eq!(name(registry.select(Some("pdf"), Some("image"), true)), Some("image"));
+
+        // Either side falls back to the other
+        assert_eq!(name(registry.select(Some("bin"), Some("image"), false)), Some("image"));
+        assert_eq!(name(registry.select(Some("flac"), Some("unknown"), true)), Some("flac"));
+        assert_eq!(name(registry.select(None, Some("executable"), true)), None);
+    }
+
+    #[test]
+    fn test_registered_validators() {
+        let registry = ValidatorRegistry::global();
+        let mut names = Vec::new();
+        let mut extensions = Vec::new();
+        for info in registry.infos() {
+            assert!(!names.contains(&info.name), "duplicate validator name {}", info.name);
+            names.push(info.name);
+            assert!(info.version >= 1);
+            assert!(!info.signatures.is_empty());
+            assert!(
+                Config::validation_getter(info.config_key).is_some(),
+                "unknown config key {}",
+                info.config_key
+            );
+            for ext in info.extensions {
+                assert_eq!(*ext, ext.to_ascii_lowercase());
+                assert!(!extensions.contains(ext), "{ext} registered twice");
+                extensions.push(*ext);
+            }
+        }
+        assert_eq!(registry.validator_extensions(), extensions);
+    }
+}
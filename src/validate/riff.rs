@@ -0,0 +1,545 @@
+use std::io::{Read, Seek, SeekFrom};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::{path::Path, sync::Arc};
+
+use crate::error::FsPulseError;
+use crate::io_throttle::{IoThrottle, ThrottledFile};
+use crate::try_invalid;
+
+use super::validator::{Signature, ValidationState, Validator, ValidatorInfo, WalkError};
+
+static WAV_INFO: ValidatorInfo = ValidatorInfo {
+    name: "wav",
+    version: 1,
+    extensions: &["wav"],
+    signatures: &[Signature::new(8, b"WAVE")],
+    config_key: "audio",
+};
+
+static AIFF_INFO: ValidatorInfo = ValidatorInfo {
+    name: "aiff",
+    version: 1,
+    extensions: &["aif", "aiff", "aifc"],
+    signatures: &[Signature::new(8, b"AIFF"), Signature::new(8, b"AIFC")],
+    config_key: "audio",
+};
+
+/// Form sizes that streaming writers leave in the header when they can't go
+/// back and fill in the real one
+const UNKNOWN_SIZES: [u32; 2] = [0, u32::MAX];
+
+/// Validator for WAV files. Walks the RIFF chunks, checking that each fits
+/// in the file, and that the fmt and data chunks are there and agree.
+pub struct WavValidator;
+
+impl WavValidator {
+    /// Constructs a new WavValidator instance.
+    pub fn new() -> Self {
+        WavValidator
+    }
+}
+
+impl Validator for WavValidator {
+    fn info(&self) -> &'static ValidatorInfo {
+        &WAV_INFO
+    }
+
+    fn validate(
+        &self,
+        path: &Path,
+        throttle: &IoThrottle,
+        interrupt_token: &Arc<AtomicBool>,
+    ) -> Result<(ValidationState, Option<String>), FsPulseError> {
+        let file_len = try_invalid!(std::fs::metadata(path)).len();
+        let file = try_invalid!(ThrottledFile::open_buffered(path, throttle, interrupt_token));
+        match check_wav(file, file_len, interrupt_token) {
+            Ok(()) => Ok((ValidationState::Valid, None)),
+            Err(WalkError::Invalid(error)) => Ok((ValidationState::Invalid, Some(error))),
+            Err(WalkError::Interrupted) => Err(FsPulseError::TaskInterrupted),
+        }
+    }
+}
+
+/// Validator for AIFF and AIFF-C files. Walks the IFF chunks, checking that
+/// each fits in the file, and that the sound data is as long as the COMM
+/// chunk says.
+pub struct AiffValidator;
+
+impl AiffValidator {
+    /// Constructs a new AiffValidator instance.
+    pub fn new() -> Self {
+        AiffValidator
+    }
+}
+
+impl Validator for AiffValidator {
+    fn info(&self) -> &'static ValidatorInfo {
+        &AIFF_INFO
+    }
+
+    fn validate(
+        &self,
+        path: &Path,
+        throttle: &IoThrottle,
+        interrupt_token: &Arc<AtomicBool>,
+    ) -> Result<(ValidationState, Option<String>), FsPulseError> {
+        let file_len = try_invalid!(std::fs::metadata(path)).len();
+        let file = try_invalid!(ThrottledFile::open_buffered(path, throttle, interrupt_token));
+        match check_aiff(file, file_len, interrupt_token) {
+            Ok(()) => Ok((ValidationState::Valid, None)),
+            Err(WalkError::Invalid(error)) => Ok((ValidationState::Invalid, Some(error))),
+            Err(WalkError::Interrupted) => Err(FsPulseError::TaskInterrupted),
+        }
+    }
+}
+
+/// A chunk's header
+struct Chunk {
+    id: [u8; 4],
+    /// Where the chunk's header starts
+    offset: u64,
+    /// The length of the chunk's data, not counting its header or padding
+    size: u64,
+}
+
+impl Chunk {
+    fn name(&self) -> String {
+        String::from_utf8_lossy(&self.id).trim_end().to_string()
+    }
+}
+
+/// Walks the chunks of a RIFF or IFF form, checking that each one fits in
+/// the form and the file.
+struct Chunks<'a, R> {
+    reader: R,
+    interrupt_token: &'a Arc<AtomicBool>,
+    /// Where the next chunk's header starts
+    next: u64,
+    /// Where the form ends, by its header
+    end: u64,
+    file_len: u64,
+    big_endian: bool,
+    /// The data chunk's size from an RF64 ds64 chunk
+    data_size: Option<u64>,
+}
+
+impl<'a, R: Read + Seek> Chunks<'a, R> {
+    /// Start after a 12-byte form header giving `form_size`
+    fn new(
+        reader: R,
+        interrupt_token: &'a Arc<AtomicBool>,
+        form_size: u32,
+        file_len: u64,
+        big_endian: bool,
+    ) -> Self {
+        let end = if UNKNOWN_SIZES.contains(&form_size) {
+            file_len
+        } else {
+            8 + form_size as u64
+        };
+        Chunks {
+            reader,
+            interrupt_token,
+            next: 12,
+            end,
+            file_len,
+            big_endian,
+            data_size: None,
+        }
+    }
+
+    /// The next chunk, with the reader at the start of its data. None at the
+    /// end of the form.
+    fn next_chunk(&mut self) -> Result<Option<Chunk>, WalkError> {
+        if self.interrupt_token.load(Ordering::Acquire) {
+            return Err(WalkError::Interrupted);
+        }
+        if self.next + 8 > self.end.min(self.file_len) {
+            // A missing pad byte after the last chunk is harmless
+            if self.end > self.file_len.max(self.next) {
+                return Err(WalkError::Invalid(format!(
+                    "file is truncated at byte {}: its header gives {} bytes",
+                    self.file_len, self.end
+                )));
+            }
+            return Ok(None);
+        }
+
+        self.reader.seek(SeekFrom::Start(self.next))?;
+        let mut header = [0u8; 8];
+        self.reader.read_exact(&mut header)?;
+        let id = [header[0], header[1], header[2], header[3]];
+        let size_bytes = [header[4], header[5], header[6], header[7]];
+        let size = if self.big_endian {
+            u32::from_be_bytes(size_bytes)
+        } else {
+            u32::from_le_bytes(size_bytes)
+        };
+        let size = match (&id, self.data_size) {
+            (b"data", Some(data_size)) if size == u32::MAX => data_size,
+            _ => size as u64,
+        };
+        let chunk = Chunk { id, offset: self.next, size };
+
+        let data_start = chunk.offset + 8;
+        // RF64 sizes are 64 bits, so the sum can overflow
+        let Some(data_end) = data_start.checked_add(chunk.size).filter(|&end| end <= self.file_len) else {
+            return Err(WalkError::Invalid(format!(
+                "'{}' chunk at byte {} runs past the end of the file: {} bytes declared, {} present",
+                chunk.name(),
+                chunk.offset,
+                chunk.size,
+                self.file_len - data_start
+            )));
+        };
+        if data_end > self.end {
+            return Err(WalkError::Invalid(format!(
+                "'{}' chunk at byte {} runs past the end of its form",
+                chunk.name(),
+                chunk.offset
+            )));
+        }
+        // Chunks start on even offsets
+        let next = data_end + (chunk.size & 1);
+        if next <= self.next {
+            return Err(WalkError::Invalid(format!(
+                "'{}' chunk at byte {} does not advance past its header",
+                chunk.name(),
+                chunk.offset
+            )));
+        }
+        self.next = next;
+        Ok(Some(chunk))
+    }
+
+    /// Read the first `len` bytes of the chunk `next_chunk` just returned
+    fn read_data(&mut self, chunk: &Chunk, len: usize) -> Result<Vec<u8>, WalkError> {
+        if chunk.size < len as u64 {
+            return Err(WalkError::Invalid(format!(
+                "'{}' chunk at byte {} is too short",
+                chunk.name(),
+                chunk.offset
+            )));
+        }
+        let mut data = vec![0u8; len];
+        self.reader.read_exact(&mut data)?;
+        Ok(data)
+    }
+}
+
+/// Walk a WAV file's chunks
+fn check_wav<R: Read + Seek>(
+    mut reader: R,
+    file_len: u64,
+    interrupt_token: &Arc<AtomicBool>,
+) -> Result<(), WalkError> {
+    let mut header = [0u8; 12];
+    reader
+        .read_exact(&mut header)
+        .map_err(|_| WalkError::Invalid("file is too short for a RIFF header".to_string()))?;
+    // RF64 and BW64 keep 64-bit sizes in a ds64 chunk, for files over 4 GB
+    let rf64 = match &header[..4] {
+        b"RIFF" => false,
+        b"RF64" | b"BW64" => true,
+        _ => return Err(WalkError::Invalid("no RIFF header".to_string())),
+    };
+    if &header[8..12] != b"WAVE" {
+        return Err(WalkError::Invalid("RIFF form is not WAVE".to_string()));
+    }
+
+    let form_size = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+    let mut chunks = Chunks::new(reader, interrupt_token, form_size, file_len, false);
+    // The format tag and the bytes in each sample frame
+    let mut format: Option<(u16, u16)> = None;
+    let mut has_data = false;
+
+    while let Some(chunk) = chunks.next_chunk()? {
+        match &chunk.id {
+            b"ds64" if rf64 => {
+                let data = chunks.read_data(&chunk, 16)?;
+                let riff_size = u64::from_le_bytes(data[0..8].try_into().expect("8 bytes"));
+                chunks.end = riff_size.checked_add(8).ok_or_else(|| {
+                    WalkError::Invalid(format!("ds64 chunk at byte {} gives an impossible RIFF size", chunk.offset))
+                })?;
+                chunks.data_size = Some(u64::from_le_bytes(data[8..16].try_into().expect("8 bytes")));
+            }
+            b"fmt " => {
+                let data = chunks.read_data(&chunk, 16)?;
+                let format_tag = u16::from_le_bytes([data[0], data[1]]);
+                let block_align = u16::from_le_bytes([data[12], data[13]]);
+                format = Some((format_tag, block_align));
+            }
+            b"data" => {
+                let Some((format_tag, block_align)) = format else {
+                    return Err(WalkError::Invalid(format!(
+                        "data chunk at byte {} comes before the fmt chunk",
+                        chunk.offset
+                    )));
+                };
+                // PCM, IEEE float and extensible data is whole sample frames
+                if matches!(format_tag, 1 | 3 | 0xFFFE)
+                    && block_align > 0
+                    && chunk.size % block_align as u64 != 0
+                {
+                    return Err(WalkError::Invalid(format!(
+                        "data chunk at byte {} ends partway through a sample frame: {} bytes in {}-byte frames",
+                        chunk.offset, chunk.size, block_align
+                    )));
+                }
+                has_data = true;
+            }
+            _ => {}
+        }
+    }
+
+    if format.is_none() {
+        return Err(WalkError::Invalid("no fmt chunk".to_string()));
+    }
+    if !has_data {
+        return Err(WalkError::Invalid("no data chunk".to_string()));
+    }
+    Ok(())
+}
+
+/// Walk an AIFF or AIFF-C file's chunks
+fn check_aiff<R: Read + Seek>(
+    mut reader: R,
+    file_len: u64,
+    interrupt_token: &Arc<AtomicBool>,
+) -> Result<(), WalkError> {
+    let mut header = [0u8; 12];
+    reader
+        .read_exact(&mut header)
+        .map_err(|_| WalkError::Invalid("file is too short for a FORM header".to_string()))?;
+    if &header[..4] != b"FORM" {
+        return Err(WalkError::Invalid("no FORM header".to_string()));
+    }
+    let aifc = match &header[8..12] {
+        b"AIFF" => false,
+        b"AIFC" => true,
+        _ => return Err(WalkError::Invalid("FORM is not AIFF or AIFF-C".to_string())),
+    };
+
+    let form_size = u32::from_be_bytes([header[4], header[5], header[6], header[7]]);
+    let mut chunks = Chunks::new(reader, interrupt_token, form_size, file_len, true);
+    // Channels, sample frames, bits per sample and compression type
+    let mut common: Option<(u16, u32, u16, [u8; 4])> = None;
+    // The SSND chunk's offset and the bytes of samples in it
+    let mut sound: Option<(u64, u64)> = None;
+
+    while let Some(chunk) = chunks.next_chunk()? {
+        match &chunk.id {
+            b"COMM" => {
+                let data = chunks.read_data(&chunk, if aifc { 22 } else { 18 })?;
+                let compression = if aifc {
+                    [data[18], data[19], data[20], data[21]]
+                } else {
+                    *b"NONE"
+                };
+                common = Some((
+                    u16::from_be_bytes([data[0], data[1]]),
+                    u32::from_be_bytes([data[2], data[3], data[4], data[5]]),
+                    u16::from_be_bytes([data[6], data[7]]),
+                    compression,
+                ));
+            }
+            b"SSND" => {
+                let data = chunks.read_data(&chunk, 8)?;
+                let data_offset = u32::from_be_bytes([data[0], data[1], data[2], data[3]]) as u64;
+                let Some(bytes) = (chunk.size - 8).checked_sub(data_offset) else {
+                    return Err(WalkError::Invalid(format!(
+                        "SSND chunk at byte {} has a data offset past its end",
+                        chunk.offset
+                    )));
+                };
+                sound = Some((chunk.offset, bytes));
+            }
+            _ => {}
+        }
+    }
+
+    let Some((channels, frames, bits, compression)) = common else {
+        return Err(WalkError::Invalid("no COMM chunk".to_string()));
+    };
+    if frames == 0 {
+        return Ok(());
+    }
+    let Some((sound_offset, sound_bytes)) = sound else {
+        return Err(WalkError::Invalid("no SSND chunk".to_string()));
+    };
+    // Only uncompressed samples have a known length
+    if matches!(&compression, b"NONE" | b"twos" | b"sowt") {
+        let expected = frames as u64 * channels as u64 * bits.div_ceil(8) as u64;
+        if sound_bytes < expected {
+            return Err(WalkError::Invalid(format!(
+                "SSND chunk at byte {} holds {} bytes of samples, but the COMM chunk declares {}",
+                sound_offset, sound_bytes, expected
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validate::validate_bytes;
+
+    /// 16-bit stereo PCM: a fmt chunk, then `data_len` bytes of samples
+    fn wav_bytes(data_len: u32) -> Vec<u8> {
+        let mut bytes = b"RIFF".to_vec();
+        bytes.extend_from_slice(&(4 + 24 + 8 + data_len + (data_len & 1)).to_le_bytes());
+        bytes.extend_from_slice(b"WAVEfmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&2u16.to_le_bytes());
+        bytes.extend_from_slice(&44100u32.to_le_bytes());
+        bytes.extend_from_slice(&(44100u32 * 4).to_le_bytes());
+        bytes.extend_from_slice(&4u16.to_le_bytes());
+        bytes.extend_from_slice(&16u16.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&data_len.to_le_bytes());
+        bytes.resize(bytes.len() + (data_len + (data_len & 1)) as usize, 0);
+        bytes
+    }
+
+    /// `wav_bytes(40)` as RF64, with the given sizes in its ds64 chunk
+    fn rf64_bytes(riff_size: u64, data_size: u64) -> Vec<u8> {
+        let wav = wav_bytes(40);
+        let mut bytes = b"RF64".to_vec();
+        bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+        bytes.extend_from_slice(b"WAVEds64");
+        bytes.extend_from_slice(&28u32.to_le_bytes());
+        bytes.extend_from_slice(&riff_size.to_le_bytes());
+        bytes.extend_from_slice(&data_size.to_le_bytes());
+        bytes.extend_from_slice(&[0u8; 12]);
+        // The fmt chunk, then a data chunk whose size is in the ds64 chunk
+        bytes.extend_from_slice(&wav[12..40]);
+        bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+        bytes.extend_from_slice(&wav[44..]);
+        bytes
+    }
+
+    /// 16-bit stereo: a COMM chunk declaring `frames`, then an SSND chunk
+    /// with `sound_len` bytes of samples
+    fn aiff_bytes(frames: u32, sound_len: u32) -> Vec<u8> {
+        let mut bytes = b"FORM".to_vec();
+        bytes.extend_from_slice(&(4 + 26 + 16 + sound_len).to_be_bytes());
+        bytes.extend_from_slice(b"AIFFCOMM");
+        bytes.extend_from_slice(&18u32.to_be_bytes());
+        bytes.extend_from_slice(&2u16.to_be_bytes());
+        bytes.extend_from_slice(&frames.to_be_bytes());
+        bytes.extend_from_slice(&16u16.to_be_bytes());
+        // 44100 as an 80-bit extended float
+        bytes.extend_from_slice(&[0x40, 0x0E, 0xAC, 0x44, 0, 0, 0, 0, 0, 0]);
+        bytes.extend_from_slice(b"SSND");
+        bytes.extend_from_slice(&(8 + sound_len).to_be_bytes());
+        bytes.extend_from_slice(&[0u8; 8]);
+        bytes.resize(bytes.len() + sound_len as usize, 0);
+        bytes
+    }
+
+    #[test]
+    fn test_wav_validator_valid_file() {
+        assert_eq!(
+            validate_bytes(&WavValidator::new(), ".wav", &wav_bytes(40)),
+            (ValidationState::Valid, None)
+        );
+    }
+
+    #[test]
+    fn test_wav_validator_truncated_data() {
+        let bytes = wav_bytes(40);
+        assert_eq!(
+            validate_bytes(&WavValidator::new(), ".wav", &bytes[..bytes.len() - 20]),
+            (
+                ValidationState::Invalid,
+                Some("'data' chunk at byte 36 runs past the end of the file: 40 bytes declared, 20 present".to_string())
+            )
+        );
+    }
+
+    #[test]
+    fn test_wav_validator_partial_frame() {
+        assert_eq!(
+            validate_bytes(&WavValidator::new(), ".wav", &wav_bytes(41)),
+            (
+                ValidationState::Invalid,
+                Some("data chunk at byte 36 ends partway through a sample frame: 41 bytes in 4-byte frames".to_string())
+            )
+        );
+    }
+
+    #[test]
+    fn test_wav_validator_missing_fmt() {
+        let mut bytes = wav_bytes(40);
+        bytes[12..16].copy_from_slice(b"junk");
+        assert_eq!(
+            validate_bytes(&WavValidator::new(), ".wav", &bytes),
+            (
+                ValidationState::Invalid,
+                Some("data chunk at byte 36 comes before the fmt chunk".to_string())
+            )
+        );
+    }
+
+    #[test]
+    fn test_wav_validator_rf64() {
+        let len = rf64_bytes(0, 40).len() as u64;
+        assert_eq!(
+            validate_bytes(&WavValidator::new(), ".wav", &rf64_bytes(len - 8, 40)),
+            (ValidationState::Valid, None)
+        );
+
+        // 64-bit sizes too big to add to an offset
+        assert_eq!(
+            validate_bytes(&WavValidator::new(), ".wav", &rf64_bytes(len - 8, u64::MAX - 4)),
+            (
+                ValidationState::Invalid,
+                Some(format!(
+                    "'data' chunk at byte 72 runs past the end of the file: {} bytes declared, 40 present",
+                    u64::MAX - 4
+                ))
+            )
+        );
+        assert_eq!(
+            validate_bytes(&WavValidator::new(), ".wav", &rf64_bytes(u64::MAX, 40)),
+            (
+                ValidationState::Invalid,
+                Some("ds64 chunk at byte 12 gives an impossible RIFF size".to_string())
+            )
+        );
+    }
+
+    #[test]
+    fn test_aiff_validator_valid_file() {
+        assert_eq!(
+            validate_bytes(&AiffValidator::new(), ".aiff", &aiff_bytes(10, 40)),
+            (ValidationState::Valid, None)
+        );
+    }
+
+    #[test]
+    fn test_aiff_validator_short_sound_data() {
+        assert_eq!(
+            validate_bytes(&AiffValidator::new(), ".aiff", &aiff_bytes(20, 40)),
+            (
+                ValidationState::Invalid,
+                Some("SSND chunk at byte 38 holds 40 bytes of samples, but the COMM chunk declares 80".to_string())
+            )
+        );
+    }
+
+    #[test]
+    fn test_aiff_validator_truncated_form() {
+        // Cut off after the COMM chunk
+        let bytes = aiff_bytes(10, 40);
+        assert_eq!(
+            validate_bytes(&AiffValidator::new(), ".aiff", &bytes[..38]),
+            (
+                ValidationState::Invalid,
+                Some("file is truncated at byte 38: its header gives 94 bytes".to_string())
+            )
+        );
+    }
+}
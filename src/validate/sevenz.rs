@@ -0,0 +1,163 @@
+use std::io::{self, Read};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::{path::Path, sync::Arc};
+
+use sevenz_rust::{Archive, BlockDecoder, Error};
+
+use crate::error::FsPulseError;
+use crate::io_throttle::{IoThrottle, ThrottledFile};
+use crate::try_invalid;
+
+use super::validator::{Signature, ValidationState, Validator, ValidatorInfo};
+
+static INFO: ValidatorInfo = ValidatorInfo {
+    name: "7z",
+    version: 1,
+    extensions: &["7z"],
+    signatures: &[Signature::new(0, b"7z\xBC\xAF\x27\x1C")],
+    config_key: "archives",
+};
+
+/// Validator implementation for 7z archives using the sevenz-rust crate.
+pub struct SevenZValidator;
+
+impl SevenZValidator {
+    /// Constructs a new SevenZValidator instance.
+    pub fn new() -> Self {
+        SevenZValidator
+    }
+}
+
+impl Validator for SevenZValidator {
+    fn info(&self) -> &'static ValidatorInfo {
+        &INFO
+    }
+
+    fn validate(
+        &self,
+        path: &Path,
+        throttle: &IoThrottle,
+        interrupt_token: &Arc<AtomicBool>,
+    ) -> Result<(ValidationState, Option<String>), FsPulseError> {
+        let len = try_invalid!(std::fs::metadata(path)).len();
+        let mut file = try_invalid!(ThrottledFile::open_buffered(path, throttle, interrupt_token));
+        let archive = match Archive::read(&mut file, len, &[]) {
+            Ok(archive) => archive,
+            // The file list itself is encrypted, so there's nothing to check
+            Err(Error::PasswordRequired) => return Ok((ValidationState::Valid, None)),
+            Err(e) => return Ok((ValidationState::Invalid, Some(describe(&e)))),
+        };
+
+        // Members are compressed together in folders. Decompress each folder,
+        // checking the CRC of each member in it.
+        let mut failure: Option<String> = None;
+        let mut interrupted = false;
+        for folder_index in 0..archive.folders.len() {
+            let decoder = BlockDecoder::new(folder_index, &archive, &[], &mut file);
+            // Until a member is read, errors belong to the folder's first
+            let mut current = decoder.entries().first().map(|entry| entry.name().to_string());
+
+            let result = decoder.for_each_entries(&mut |entry, reader: &mut dyn Read| {
+                if interrupt_token.load(Ordering::Acquire) {
+                    interrupted = true;
+                    return Ok(false);
+                }
+                current = Some(entry.name().to_string());
+                let read = io::copy(reader, &mut io::sink())?;
+                if read != entry.size() {
+                    failure = Some(format!(
+                        "{}: truncated after {} of {} bytes",
+                        entry.name(),
+                        read,
+                        entry.size()
+                    ));
+                    return Ok(false);
+                }
+                Ok(true)
+            });
+
+            if interrupted {
+                return Err(FsPulseError::TaskInterrupted);
+            }
+            if let Some(error) = failure {
+                return Ok((ValidationState::Invalid, Some(error)));
+            }
+            match result {
+                Ok(_) => {}
+                // Encrypted members can't be checked without the password
+                Err(Error::PasswordRequired) => {}
+                Err(e) => {
+                    let error = match current {
+                        Some(name) => format!("{}: {}", name, describe(&e)),
+                        None => describe(&e),
+                    };
+                    return Ok((ValidationState::Invalid, Some(error)));
+                }
+            }
+        }
+
+        Ok((ValidationState::Valid, None))
+    }
+}
+
+/// sevenz-rust displays its errors in their Debug form. Checksum failures
+/// arrive wrapped in I/O errors.
+fn describe(error: &Error) -> String {
+    match error {
+        Error::ChecksumVerificationFailed => "CRC mismatch".to_string(),
+        Error::NextHeaderCrcMismatch => "header CRC mismatch".to_string(),
+        Error::Io(e, _) | Error::MaybeBadPassword(e) => match e.get_ref() {
+            Some(inner) if inner.is::<Error>() => {
+                describe(inner.downcast_ref::<Error>().expect("checked by is"))
+            }
+            _ => e.to_string(),
+        },
+        _ => error.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validate::validate_bytes;
+    use std::io::Cursor;
+    use sevenz_rust::{SevenZArchiveEntry, SevenZWriter};
+
+    /// A 7z archive holding two small files. LZMA2 stores data too short to
+    /// compress as it is.
+    fn sevenz_bytes() -> Vec<u8> {
+        let mut writer = SevenZWriter::new(Cursor::new(Vec::new())).unwrap();
+        for (name, data) in [("first.txt", b"first member"), ("second.txt", b"other member")] {
+            let mut entry = SevenZArchiveEntry::new();
+            entry.name = name.to_string();
+            writer.push_archive_entry(entry, Some(&data[..])).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_sevenz_validator_valid_archive() {
+        assert_eq!(
+            validate_bytes(&SevenZValidator::new(), ".7z", &sevenz_bytes()),
+            (ValidationState::Valid, None)
+        );
+    }
+
+    #[test]
+    fn test_sevenz_validator_crc_mismatch() {
+        let mut bytes = sevenz_bytes();
+        let offset = bytes.windows(12).position(|w| w == b"other member").unwrap();
+        bytes[offset] ^= 0xFF;
+        assert_eq!(
+            validate_bytes(&SevenZValidator::new(), ".7z", &bytes),
+            (ValidationState::Invalid, Some("second.txt: CRC mismatch".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_sevenz_validator_not_an_archive() {
+        let (state, error) = validate_bytes(&SevenZValidator::new(), ".7z", b"7z but not really");
+        assert_eq!(state, ValidationState::Invalid);
+        assert!(error.is_some());
+    }
+}
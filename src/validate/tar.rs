@@ -0,0 +1,122 @@
+use std::io::{self, Read};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::{path::Path, sync::Arc};
+
+use tar::Archive;
+
+use crate::error::FsPulseError;
+use crate::io_throttle::{IoThrottle, ThrottledFile};
+use crate::try_invalid;
+
+use super::validator::{Signature, ValidationState, Validator, ValidatorInfo};
+
+static INFO: ValidatorInfo = ValidatorInfo {
+    name: "tar",
+    version: 1,
+    extensions: &["tar"],
+    // POSIX and GNU archives both carry "ustar" in the first header
+    signatures: &[Signature::new(257, b"ustar")],
+    config_key: "archives",
+};
+
+/// Validator implementation for tar archives using the tar crate.
+pub struct TarValidator;
+
+impl TarValidator {
+    /// Constructs a new TarValidator instance.
+    pub fn new() -> Self {
+        TarValidator
+    }
+}
+
+impl Validator for TarValidator {
+    fn info(&self) -> &'static ValidatorInfo {
+        &INFO
+    }
+
+    fn validate(
+        &self,
+        path: &Path,
+        throttle: &IoThrottle,
+        interrupt_token: &Arc<AtomicBool>,
+    ) -> Result<(ValidationState, Option<String>), FsPulseError> {
+        let file = try_invalid!(ThrottledFile::open_buffered(path, throttle, interrupt_token));
+        check_members(file, interrupt_token)
+    }
+}
+
+/// Read every member of a tar stream to its end. Tar has no checksum over
+/// member data, so this finds damaged headers and truncated members. The
+/// error names the member that failed.
+pub(super) fn check_members<R: Read>(
+    reader: R,
+    interrupt_token: &Arc<AtomicBool>,
+) -> Result<(ValidationState, Option<String>), FsPulseError> {
+    let mut archive = Archive::new(reader);
+    let entries = try_invalid!(archive.entries());
+    let mut previous: Option<String> = None;
+
+    for entry in entries {
+        if interrupt_token.load(Ordering::Acquire) {
+            return Err(FsPulseError::TaskInterrupted);
+        }
+
+        let mut entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                let error = match &previous {
+                    Some(name) => format!("header after {name}: {e}"),
+                    None => e.to_string(),
+                };
+                return Ok((ValidationState::Invalid, Some(error)));
+            }
+        };
+        let name = String::from_utf8_lossy(&entry.path_bytes()).into_owned();
+        let size = entry.size();
+
+        match io::copy(&mut entry, &mut io::sink()) {
+            Ok(read) if read == size => {}
+            Ok(read) => {
+                let error = format!("{name}: truncated after {read} of {size} bytes");
+                return Ok((ValidationState::Invalid, Some(error)));
+            }
+            Err(e) => return Ok((ValidationState::Invalid, Some(format!("{name}: {e}")))),
+        }
+        previous = Some(name);
+    }
+
+    Ok((ValidationState::Valid, None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validate::{tar_bytes, validate_bytes};
+
+    #[test]
+    fn test_tar_validator_valid_archive() {
+        assert_eq!(
+            validate_bytes(&TarValidator::new(), ".tar", &tar_bytes()),
+            (ValidationState::Valid, None)
+        );
+    }
+
+    #[test]
+    fn test_tar_validator_truncated_member() {
+        let bytes = tar_bytes();
+        // Cut the archive partway through the second member's data
+        let (state, error) = validate_bytes(&TarValidator::new(), ".tar", &bytes[..512 * 3 + 4]);
+        assert_eq!(state, ValidationState::Invalid);
+        assert!(error.unwrap().starts_with("second.txt: "));
+    }
+
+    #[test]
+    fn test_tar_validator_damaged_header() {
+        let mut bytes = tar_bytes();
+        // Corrupt the second member's name, which breaks its header checksum
+        bytes[512 * 2] ^= 0xFF;
+        let (state, error) = validate_bytes(&TarValidator::new(), ".tar", &bytes);
+        assert_eq!(state, ValidationState::Invalid);
+        assert!(error.unwrap().starts_with("header after first.txt: "));
+    }
+}
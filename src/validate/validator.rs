@@ -1,11 +1,12 @@
+use std::io::{self, Read};
 use std::sync::atomic::AtomicBool;
-use std::{ffi::OsStr, fmt, path::Path, sync::Arc};
+use std::{fmt, path::Path, sync::Arc};
 
 use log::warn;
+use serde::{Serialize, Serializer};
 
 use crate::error::FsPulseError;
-
-use super::{claxon::ClaxonValidator, image::ImageValidator, lopdf::LopdfValidator};
+use crate::io_throttle::IoThrottle;
 
 /// Represents the validation state of an item.
 /// Stored as integer in the database.
@@ -97,8 +98,8 @@ impl crate::query::QueryEnum for ValidationState {
 }
 
 /// Extract the lowercase file extension from a path, or None if there is none.
-/// The caller can check whether the returned extension has a validator by
-/// calling `has_validator_extension` on the result.
+/// The caller can check whether the returned extension has a validator with
+/// `ValidatorRegistry::has_validator_extension`.
 pub fn file_extension_for_path<P: AsRef<Path>>(path: P) -> Option<String> {
     path.as_ref()
         .extension()
@@ -106,63 +107,84 @@ pub fn file_extension_for_path<P: AsRef<Path>>(path: P) -> Option<String> {
         .map(|e| e.to_ascii_lowercase())
 }
 
-/// Extensions belonging to the Image validator group.
-pub const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "gif", "tiff", "bmp"];
-/// Extensions belonging to the PDF validator group.
-pub const PDF_EXTENSIONS: &[&str] = &["pdf"];
-/// Extensions belonging to the Audio validator group.
-pub const AUDIO_EXTENSIONS: &[&str] = &["flac"];
-
-/// Collect extensions that are disabled based on config settings.
-/// Returns a Vec of lowercase extension strings that should be excluded from validation.
-pub fn disabled_extensions() -> Vec<&'static str> {
-    let mut disabled = Vec::new();
-    if !crate::config::Config::get_validation_images() {
-        disabled.extend_from_slice(IMAGE_EXTENSIONS);
-    }
-    if !crate::config::Config::get_validation_pdf() {
-        disabled.extend_from_slice(PDF_EXTENSIONS);
-    }
-    if !crate::config::Config::get_validation_audio() {
-        disabled.extend_from_slice(AUDIO_EXTENSIONS);
+/// Fill as much of `buf` as the reader has left. Returns the number of bytes
+/// read, which is short of `buf.len()` only at the end of the input.
+pub fn read_up_to<R: Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
     }
-    disabled
+    Ok(filled)
+}
+
+/// Why a walk through a file's structure stopped early
+pub enum WalkError {
+    /// A message for `val_error`
+    Invalid(String),
+    Interrupted,
 }
 
-/// Check whether a lowercase extension string has a structural validator.
-pub fn has_validator_extension(ext: &str) -> bool {
-    matches!(
-        ext,
-        "flac" | "jpg" | "jpeg" | "png" | "gif" | "tiff" | "bmp" | "pdf"
-    )
+impl From<io::Error> for WalkError {
+    fn from(error: io::Error) -> Self {
+        WalkError::Invalid(error.to_string())
+    }
 }
 
-pub fn from_extension<S>(ext: S) -> Option<Box<dyn Validator>>
-where
-    S: AsRef<OsStr>,
-{
-    let ext = ext.as_ref().to_str()?.to_ascii_lowercase();
+/// A byte pattern found at a fixed offset in files a validator handles
+#[derive(Debug, Serialize)]
+pub struct Signature {
+    pub offset: usize,
+    #[serde(serialize_with = "serialize_signature_bytes")]
+    pub bytes: &'static [u8],
+}
 
-    match ext.as_str() {
-        "flac" => Some(Box::new(ClaxonValidator::new())),
-        "jpg" | "jpeg" | "png" | "gif" | "tiff" | "bmp" => Some(Box::new(ImageValidator::new())),
-        "pdf" => Some(Box::new(LopdfValidator::new())),
-        _ => None,
+impl Signature {
+    pub const fn new(offset: usize, bytes: &'static [u8]) -> Self {
+        Signature { offset, bytes }
     }
 }
 
-pub fn from_path<P>(path: P) -> Option<Box<dyn Validator>>
-where
-    P: AsRef<Path>,
-{
-    path.as_ref().extension().and_then(from_extension)
+fn serialize_signature_bytes<S: Serializer>(bytes: &&'static [u8], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&hex::encode(bytes))
+}
+
+/// What a validator handles, and how its results are recorded.
+#[derive(Debug, Serialize)]
+pub struct ValidatorInfo {
+    /// Recorded with each validation it performs
+    pub name: &'static str,
+    /// Recorded with each validation. Bump it when the validator starts to
+    /// accept or reject files differently, so files it validated are
+    /// validated again.
+    pub version: i64,
+    /// Lowercase file extensions it validates
+    pub extensions: &'static [&'static str],
+    /// Magic bytes that identify its files
+    pub signatures: &'static [Signature],
+    /// Key in the `[validation]` config section that enables it
+    pub config_key: &'static str,
+}
+
+impl ValidatorInfo {
+    /// Whether the validator is enabled in the config
+    pub fn is_enabled(&self) -> bool {
+        crate::config::Config::get_validation_enabled(self.config_key).unwrap_or(true)
+    }
 }
 
 /// Defines the behavior of a validator.
-pub trait Validator {
+pub trait Validator: Send + Sync {
+    fn info(&self) -> &'static ValidatorInfo;
+
     fn validate(
         &self,
         path: &Path,
+        throttle: &IoThrottle,
         interrupt_token: &Arc<AtomicBool>,
     ) -> Result<(ValidationState, Option<String>), FsPulseError>;
 }
@@ -170,7 +192,6 @@ pub trait Validator {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::Path;
 
     #[test]
     fn test_validation_state_integer_values() {
@@ -215,41 +236,4 @@ mod tests {
         assert_eq!(format!("{}", ValidationState::Invalid), "Invalid");
         assert_eq!(format!("{}", ValidationState::NoValidator), "No Validator");
     }
-
-
-    #[test]
-    fn test_from_extension() {
-        assert!(from_extension("flac").is_some());
-        assert!(from_extension("jpg").is_some());
-        assert!(from_extension("jpeg").is_some());
-        assert!(from_extension("png").is_some());
-        assert!(from_extension("gif").is_some());
-        assert!(from_extension("tiff").is_some());
-        assert!(from_extension("bmp").is_some());
-        assert!(from_extension("pdf").is_some());
-        assert!(from_extension("txt").is_none());
-        assert!(from_extension("unknown").is_none());
-    }
-
-    #[test]
-    fn test_from_extension_case_insensitive() {
-        assert!(from_extension("FLAC").is_some());
-        assert!(from_extension("JPG").is_some());
-        assert!(from_extension("PDF").is_some());
-    }
-
-    #[test]
-    fn test_from_path() {
-        assert!(from_path(Path::new("test.flac")).is_some());
-        assert!(from_path(Path::new("image.jpg")).is_some());
-        assert!(from_path(Path::new("document.pdf")).is_some());
-        assert!(from_path(Path::new("readme.txt")).is_none());
-        assert!(from_path(Path::new("no_extension")).is_none());
-    }
-
-    #[test]
-    fn test_from_path_with_directory() {
-        assert!(from_path(Path::new("/path/to/audio.flac")).is_some());
-        assert!(from_path(Path::new("./relative/path/photo.png")).is_some());
-    }
 }
@@ -0,0 +1,122 @@
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::{path::Path, sync::Arc};
+
+use zip::ZipArchive;
+
+use crate::error::FsPulseError;
+use crate::io_throttle::{IoThrottle, ThrottledFile};
+use crate::try_invalid;
+
+use super::validator::{Signature, ValidationState, Validator, ValidatorInfo};
+
+static INFO: ValidatorInfo = ValidatorInfo {
+    name: "zip",
+    version: 1,
+    // Office documents, e-books and Java and Android packages are ZIP files
+    extensions: &[
+        "zip", "jar", "apk", "epub", "cbz", "docx", "xlsx", "pptx", "odt", "ods", "odp",
+    ],
+    signatures: &[Signature::new(0, b"PK\x03\x04"), Signature::new(0, b"PK\x05\x06")],
+    config_key: "archives",
+};
+
+/// Validator implementation for ZIP archives using the zip crate.
+pub struct ZipValidator;
+
+impl ZipValidator {
+    /// Constructs a new ZipValidator instance.
+    pub fn new() -> Self {
+        ZipValidator
+    }
+}
+
+impl Validator for ZipValidator {
+    fn info(&self) -> &'static ValidatorInfo {
+        &INFO
+    }
+
+    fn validate(
+        &self,
+        path: &Path,
+        throttle: &IoThrottle,
+        interrupt_token: &Arc<AtomicBool>,
+    ) -> Result<(ValidationState, Option<String>), FsPulseError> {
+        let file = try_invalid!(ThrottledFile::open_buffered(path, throttle, interrupt_token));
+        let mut archive = try_invalid!(ZipArchive::new(file));
+
+        // Decompress each member to its end, where the zip crate checks its CRC
+        for index in 0..archive.len() {
+            if interrupt_token.load(Ordering::Acquire) {
+                return Err(FsPulseError::TaskInterrupted);
+            }
+
+            // Encrypted members can't be checked without the password
+            let encrypted = match archive.by_index_raw(index) {
+                Ok(member) => member.encrypted(),
+                Err(e) => return Ok((ValidationState::Invalid, Some(format!("member {index}: {e}")))),
+            };
+            if encrypted {
+                continue;
+            }
+
+            let mut member = match archive.by_index(index) {
+                Ok(member) => member,
+                Err(e) => return Ok((ValidationState::Invalid, Some(format!("member {index}: {e}")))),
+            };
+            if let Err(e) = io::copy(&mut member, &mut io::sink()) {
+                let error = format!("{}: {}", member.name(), e);
+                return Ok((ValidationState::Invalid, Some(error)));
+            }
+        }
+
+        Ok((ValidationState::Valid, None))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validate::validate_bytes;
+    use std::io::{Cursor, Write};
+    use zip::write::SimpleFileOptions;
+    use zip::CompressionMethod;
+
+    /// A ZIP archive holding two stored files
+    fn zip_bytes() -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+        for (name, data) in [("first.txt", b"first member"), ("second.txt", b"other member")] {
+            writer.start_file(name, options).unwrap();
+            writer.write_all(data).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_zip_validator_valid_archive() {
+        assert_eq!(
+            validate_bytes(&ZipValidator::new(), ".zip", &zip_bytes()),
+            (ValidationState::Valid, None)
+        );
+    }
+
+    #[test]
+    fn test_zip_validator_crc_mismatch() {
+        let mut bytes = zip_bytes();
+        // Flip a byte of the second member's stored data
+        let offset = bytes.windows(12).position(|w| w == b"other member").unwrap();
+        bytes[offset] ^= 0xFF;
+        let (state, error) = validate_bytes(&ZipValidator::new(), ".zip", &bytes);
+        assert_eq!(state, ValidationState::Invalid);
+        assert!(error.unwrap().starts_with("second.txt: "));
+    }
+
+    #[test]
+    fn test_zip_validator_truncated_archive() {
+        let bytes = zip_bytes();
+        let (state, error) = validate_bytes(&ZipValidator::new(), ".zip", &bytes[..bytes.len() / 2]);
+        assert_eq!(state, ValidationState::Invalid);
+        assert!(error.is_some());
+    }
+}
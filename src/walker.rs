@@ -0,0 +1,793 @@
+// Parallel directory walk for Phase 1 scanning.
+//
+// Walker threads share a work-stealing queue of directories. Each thread lists
+// a directory, stats its entries, applies root rules and ignore files, and sends
+// the result to the scanner's single DB-writer thread as a `DirListing`. The
+// listing is sent before the directory's subdirectories are queued, so the
+// writer always receives a parent's listing before any of its children's.
+//
+// The walker never touches the database. Everything that depends on DB state
+// (item lookups, versioning, folder size aggregation) happens in the writer.
+//
+// When a root follows symlinks, each queued directory carries the (device, inode)
+// ids of the directories above it, so a link that leads back to one of its own
+// ancestors is recorded but not followed.
+
+use std::collections::HashSet;
+use std::fs::{self, Metadata};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex, RwLock};
+use std::thread;
+
+use crossbeam_channel::Sender;
+use crossbeam_deque::{Injector, Steal, Stealer, Worker};
+use log::{error, trace};
+
+use crate::error::FsPulseError;
+use crate::ignore_files::{IgnoreFile, IgnoreFiles, IGNORE_FILE_NAME};
+use crate::root_rules::RootRules;
+use crate::roots::RootOptions;
+
+/// Outcome of listing a directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DirStatus {
+    Listed,
+    /// Permission denied reading the directory's contents
+    Unreadable,
+    /// The directory disappeared between being queued and being listed
+    Vanished,
+}
+
+/// A directory entry that survived root rules and ignore files.
+pub struct WalkEntry {
+    pub path: PathBuf,
+    /// None when the entry could not be stat'ed (permission denied)
+    pub metadata: Option<Metadata>,
+    /// A directory completed by an earlier run of this scan. It is not descended into.
+    pub completed: bool,
+    /// A directory on another filesystem under a one-file-system root. It is not
+    /// descended into.
+    pub mount_point: bool,
+    /// The target stored in a symlink, as written (not resolved)
+    pub symlink_target: Option<String>,
+    /// A symlink to a directory that the walk descends into (unless completed)
+    pub followed: bool,
+}
+
+impl WalkEntry {
+    /// Whether the walk descends into this entry.
+    pub fn is_walked_dir(&self) -> bool {
+        !self.completed
+            && (self.followed
+                || (!self.mount_point && self.metadata.as_ref().is_some_and(|m| m.is_dir())))
+    }
+}
+
+/// The contents of one directory, as sent to the DB writer.
+pub struct DirListing {
+    pub dir: PathBuf,
+    pub status: DirStatus,
+    pub entries: Vec<WalkEntry>,
+    /// Ignore file read from this directory for the first time in this scan
+    pub new_ignore_file: Option<IgnoreFile>,
+}
+
+pub enum WalkEvent {
+    Listing(DirListing),
+    /// Unexpected I/O error. The walk stops and the scan fails.
+    Error(FsPulseError),
+}
+
+/// (device, inode) of a directory
+type FileId = (u64, u64);
+
+/// The ids of a directory and every directory above it, innermost first.
+struct DirChain {
+    id: FileId,
+    parent: Option<Arc<DirChain>>,
+}
+
+impl DirChain {
+    fn contains(&self, id: FileId) -> bool {
+        let mut current = Some(self);
+        while let Some(link) = current {
+            if link.id == id {
+                return true;
+            }
+            current = link.parent.as_deref();
+        }
+        false
+    }
+
+    /// Extend `parent` with a child directory. None when ancestry isn't tracked.
+    fn child(parent: &Option<Arc<DirChain>>, metadata: &Metadata) -> Option<Arc<DirChain>> {
+        let parent = parent.as_ref()?;
+        Some(Arc::new(DirChain {
+            id: file_id(metadata)?,
+            parent: Some(Arc::clone(parent)),
+        }))
+    }
+}
+
+/// A directory waiting to be listed.
+struct QueuedDir {
+    path: PathBuf,
+    /// Only tracked when following symlinks
+    ancestry: Option<Arc<DirChain>>,
+    /// Number of symlinks followed to reach this directory
+    link_depth: u32,
+}
+
+pub struct Walker<'a> {
+    rules: &'a RootRules,
+    ignores: RwLock<IgnoreFiles>,
+    completed_dirs: HashSet<PathBuf>,
+    options: RootOptions,
+    interrupt_token: &'a Arc<AtomicBool>,
+    injector: Injector<QueuedDir>,
+    /// Directories queued or being listed. The walk is done when this reaches zero.
+    pending: AtomicUsize,
+    /// Set when any thread stops early (interrupt, error, or writer gone)
+    stopped: AtomicBool,
+    /// Idle threads wait on `wakeup` until work is queued, the walk finishes,
+    /// or it stops. Wakers take `idle` so a thread can't miss a wakeup between
+    /// looking for work and waiting.
+    idle: Mutex<()>,
+    wakeup: Condvar,
+}
+
+impl<'a> Walker<'a> {
+    pub fn new(
+        rules: &'a RootRules,
+        ignores: IgnoreFiles,
+        completed_dirs: HashSet<PathBuf>,
+        options: &RootOptions,
+        interrupt_token: &'a Arc<AtomicBool>,
+    ) -> Self {
+        Self {
+            rules,
+            ignores: RwLock::new(ignores),
+            completed_dirs,
+            options: options.clone(),
+            interrupt_token,
+            injector: Injector::new(),
+            pending: AtomicUsize::new(0),
+            stopped: AtomicBool::new(false),
+            idle: Mutex::new(()),
+            wakeup: Condvar::new(),
+        }
+    }
+
+    /// Walk the tree below `start` (the root, or a directory inside it) on
+    /// `thread_count` threads, sending one listing per directory (including `start`).
+    /// Returns once every directory has been listed or the walk has stopped early.
+    /// The sender is dropped on return, which tells the writer no more listings
+    /// are coming.
+    pub fn run(&self, root: &Path, start: &Path, thread_count: usize, sender: Sender<WalkEvent>) {
+        // Filesystem boundary for one-file-system roots. If the root can't be
+        // stat'ed, listing it reports the problem.
+        let root_dev = if self.options.one_file_system {
+            fs::metadata(root).ok().as_ref().and_then(device_id)
+        } else {
+            None
+        };
+        // When following symlinks, the ancestry chain runs from the root down to `start`
+        let ancestry = if self.options.follow_symlinks {
+            let mut dirs: Vec<&Path> = start.ancestors().take_while(|a| a.starts_with(root)).collect();
+            dirs.reverse();
+            dirs.into_iter().fold(None, |parent, dir| {
+                match fs::metadata(dir).ok().as_ref().and_then(file_id) {
+                    Some(id) => Some(Arc::new(DirChain { id, parent })),
+                    None => parent,
+                }
+            })
+        } else {
+            None
+        };
+
+        self.pending.store(1, Ordering::SeqCst);
+        self.injector.push(QueuedDir {
+            path: start.to_path_buf(),
+            ancestry,
+            link_depth: 0,
+        });
+
+        let workers: Vec<Worker<QueuedDir>> =
+            (0..thread_count.max(1)).map(|_| Worker::new_lifo()).collect();
+        let stealers: Vec<Stealer<QueuedDir>> = workers.iter().map(|w| w.stealer()).collect();
+
+        thread::scope(|s| {
+            for (index, worker) in workers.into_iter().enumerate() {
+                let sender = sender.clone();
+                let stealers = &stealers;
+                s.spawn(move || self.run_thread(index, worker, stealers, root_dev, sender));
+            }
+        });
+    }
+
+    fn run_thread(
+        &self,
+        index: usize,
+        local: Worker<QueuedDir>,
+        stealers: &[Stealer<QueuedDir>],
+        root_dev: Option<u64>,
+        sender: Sender<WalkEvent>,
+    ) {
+        loop {
+            if self.stopped.load(Ordering::Acquire) {
+                return;
+            }
+            if self.interrupt_token.load(Ordering::Acquire) {
+                self.stop();
+                return;
+            }
+
+            let Some(dir) = self.find_work(index, &local, stealers) else {
+                if self.pending.load(Ordering::SeqCst) == 0 {
+                    return;
+                }
+                self.wait_for_work(stealers);
+                continue;
+            };
+
+            let (event, subdirs) = match self.list_dir(&dir, root_dev) {
+                Ok((listing, subdirs)) => (WalkEvent::Listing(listing), subdirs),
+                Err(e) => {
+                    self.stop();
+                    (WalkEvent::Error(e), Vec::new())
+                }
+            };
+
+            // Send before queueing children so the writer sees parents first
+            if sender.send(event).is_err() {
+                // Writer has gone away (error or interrupt) - stop everyone
+                self.stop();
+                return;
+            }
+
+            let queued = !subdirs.is_empty();
+            self.pending.fetch_add(subdirs.len(), Ordering::SeqCst);
+            for subdir in subdirs {
+                local.push(subdir);
+            }
+            let finished = self.pending.fetch_sub(1, Ordering::SeqCst) == 1;
+            if queued || finished {
+                self.wake_idle();
+            }
+        }
+    }
+
+    /// Stop the walk on every thread.
+    fn stop(&self) {
+        self.stopped.store(true, Ordering::Release);
+        self.wake_idle();
+    }
+
+    fn wake_idle(&self) {
+        let _idle = self.idle.lock().unwrap();
+        self.wakeup.notify_all();
+    }
+
+    /// Block an idle thread until another thread queues work, the walk
+    /// finishes, or it stops. Returns at once if any of those already happened.
+    fn wait_for_work(&self, stealers: &[Stealer<QueuedDir>]) {
+        let idle = self.idle.lock().unwrap();
+        let has_work = !self.injector.is_empty() || stealers.iter().any(|s| !s.is_empty());
+        if has_work || self.pending.load(Ordering::SeqCst) == 0 || self.stopped.load(Ordering::Acquire) {
+            return;
+        }
+        drop(self.wakeup.wait(idle).unwrap());
+    }
+
+    fn find_work(
+        &self,
+        index: usize,
+        local: &Worker<QueuedDir>,
+        stealers: &[Stealer<QueuedDir>],
+    ) -> Option<QueuedDir> {
+        local.pop().or_else(|| loop {
+            let steal = self.injector.steal_batch_and_pop(local).or_else(|| {
+                stealers
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| *i != index)
+                    .map(|(_, s)| s.steal())
+                    .collect()
+            });
+            match steal {
+                Steal::Success(dir) => return Some(dir),
+                Steal::Empty => return None,
+                Steal::Retry => continue,
+            }
+        })
+    }
+
+    /// List one directory. Returns its listing and the subdirectories to walk next.
+    fn list_dir(
+        &self,
+        queued: &QueuedDir,
+        root_dev: Option<u64>,
+    ) -> Result<(DirListing, Vec<QueuedDir>), FsPulseError> {
+        let dir = queued.path.as_path();
+        let mut listing = DirListing {
+            dir: dir.to_path_buf(),
+            status: DirStatus::Listed,
+            entries: Vec::new(),
+            new_ignore_file: None,
+        };
+        let mut subdirs = Vec::new();
+
+        // Try to read directory contents, handling access errors
+        let items = match fs::read_dir(dir) {
+            Ok(items) => items,
+            Err(e) => match e.kind() {
+                ErrorKind::PermissionDenied => {
+                    // Can't list directory contents - the writer records a ReadError
+                    listing.status = DirStatus::Unreadable;
+                    return Ok((listing, subdirs));
+                }
+                ErrorKind::NotFound => {
+                    trace!("Directory disappeared during scan: '{}'", dir.display());
+                    listing.status = DirStatus::Vanished;
+                    return Ok((listing, subdirs));
+                }
+                _ => {
+                    error!(
+                        "Unexpected error reading directory '{}': {} (kind: {:?})",
+                        dir.display(),
+                        e,
+                        e.kind()
+                    );
+                    return Err(FsPulseError::from(e));
+                }
+            },
+        };
+
+        // The directory's own ignore file applies to its entries. Directories
+        // recorded by an interrupted run keep their recorded contents.
+        if !self.ignores.read().unwrap().has_dir(dir) {
+            if let Some(file) = IgnoreFiles::read(dir) {
+                trace!("Applying {} in '{}'", IGNORE_FILE_NAME, file.dir);
+                self.ignores.write().unwrap().add(file.clone());
+                listing.new_ignore_file = Some(file);
+            }
+        }
+
+        let ignores = self.ignores.read().unwrap();
+
+        for item in items {
+            // Handle errors during directory iteration
+            let item = match item {
+                Ok(entry) => entry,
+                Err(e) => {
+                    match e.kind() {
+                        ErrorKind::PermissionDenied => {
+                            error!(
+                                "Permission denied reading directory entry in '{}': {}",
+                                dir.display(),
+                                e
+                            );
+                        }
+                        _ => {
+                            // Other I/O errors during iteration - log and continue
+                            error!("Error reading directory entry in '{}': {}", dir.display(), e);
+                        }
+                    }
+                    continue;
+                }
+            };
+            let item_path = item.path();
+
+            // Excluded entries are skipped entirely: not stat'ed, not counted, and
+            // excluded directories are not descended into
+            if self.rules.is_excluded(&item_path) {
+                trace!("Excluded by root rule: '{}'", item_path.display());
+                continue;
+            }
+
+            // Ignored entries are handled the same way. file_type() doesn't follow
+            // symlinks, so a symlink to a directory is matched as a file
+            let is_dir = item.file_type().is_ok_and(|t| t.is_dir());
+            if ignores.is_ignored(&item_path, is_dir) {
+                trace!("Ignored by {}: '{}'", IGNORE_FILE_NAME, item_path.display());
+                continue;
+            }
+
+            // Try to get metadata, handling access errors gracefully
+            let metadata = match fs::symlink_metadata(&item_path) {
+                Ok(metadata) => Some(metadata),
+                Err(e) => match e.kind() {
+                    ErrorKind::NotFound => {
+                        // File disappeared during scan (race condition) - skip it
+                        trace!("File disappeared during scan: '{}'", item_path.display());
+                        continue;
+                    }
+                    ErrorKind::PermissionDenied => {
+                        // Can't access metadata - treat as file with MetaError access state
+                        error!(
+                            "Cannot access metadata for '{}': {}. Treating as file with MetaError.",
+                            item_path.display(),
+                            e
+                        );
+                        None
+                    }
+                    _ => {
+                        error!(
+                            "Unexpected error getting metadata for '{}': {} (kind: {:?})",
+                            item_path.display(),
+                            e,
+                            e.kind()
+                        );
+                        return Err(FsPulseError::from(e));
+                    }
+                },
+            };
+
+            let is_dir = metadata.as_ref().is_some_and(|m| m.is_dir());
+            let mount_point = is_dir
+                && root_dev.is_some()
+                && metadata.as_ref().and_then(device_id) != root_dev;
+            if mount_point {
+                trace!("Not crossing filesystem boundary: '{}'", item_path.display());
+            }
+
+            let is_symlink = metadata.as_ref().is_some_and(|m| m.is_symlink());
+            let symlink_target = if is_symlink {
+                match fs::read_link(&item_path) {
+                    Ok(target) => Some(target.to_string_lossy().into_owned()),
+                    Err(e) => {
+                        error!("Cannot read symlink '{}': {}", item_path.display(), e);
+                        None
+                    }
+                }
+            } else {
+                None
+            };
+
+            // The directory to walk below this entry, if any
+            let walked = match &metadata {
+                Some(m) if is_dir && !mount_point => Some(QueuedDir {
+                    path: item_path.clone(),
+                    ancestry: DirChain::child(&queued.ancestry, m),
+                    link_depth: queued.link_depth,
+                }),
+                Some(_) if is_symlink => self.follow_link(queued, &item_path, root_dev),
+                _ => None,
+            };
+            let followed = is_symlink && walked.is_some();
+            let completed = walked.is_some() && self.completed_dirs.contains(&item_path);
+
+            let entry = WalkEntry {
+                path: item_path,
+                metadata,
+                completed,
+                mount_point,
+                symlink_target,
+                followed,
+            };
+            if entry.is_walked_dir() {
+                subdirs.extend(walked);
+            }
+            listing.entries.push(entry);
+        }
+
+        Ok((listing, subdirs))
+    }
+
+    /// Decide whether to descend into a symlink found in `parent`. Returns the
+    /// directory to walk, named by the link's own path, or None if the link isn't
+    /// followed: following is off, the link doesn't lead to a directory, it
+    /// leads back to one of its own ancestors, or the depth cap is reached.
+    fn follow_link(
+        &self,
+        parent: &QueuedDir,
+        link: &Path,
+        root_dev: Option<u64>,
+    ) -> Option<QueuedDir> {
+        // Ancestry is only tracked when following symlinks on a platform with
+        // file ids, so there is no loop detection without it
+        let ancestry = parent.ancestry.as_ref()?;
+
+        let target = fs::metadata(link).ok().filter(|m| m.is_dir())?;
+        let id = file_id(&target)?;
+
+        if root_dev.is_some_and(|dev| dev != id.0) {
+            trace!("Not following symlink across filesystem boundary: '{}'", link.display());
+            return None;
+        }
+        if ancestry.contains(id) {
+            trace!("Not following symlink loop: '{}'", link.display());
+            return None;
+        }
+        if parent.link_depth >= self.options.max_symlink_depth {
+            trace!("Not following symlink beyond depth cap: '{}'", link.display());
+            return None;
+        }
+
+        Some(QueuedDir {
+            path: link.to_path_buf(),
+            ancestry: Some(Arc::new(DirChain {
+                id,
+                parent: Some(Arc::clone(ancestry)),
+            })),
+            link_depth: parent.link_depth + 1,
+        })
+    }
+}
+
+/// Id of the device (filesystem) holding an entry. Not available on non-Unix
+/// platforms, where one-file-system mode has no effect.
+#[cfg(unix)]
+pub(crate) fn device_id(metadata: &Metadata) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.dev())
+}
+
+#[cfg(not(unix))]
+pub(crate) fn device_id(_metadata: &Metadata) -> Option<u64> {
+    None
+}
+
+/// (device, inode) identifying a directory, for symlink loop detection. Not
+/// available on non-Unix platforms, where symlinks are never followed.
+#[cfg(unix)]
+fn file_id(metadata: &Metadata) -> Option<FileId> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_id(_metadata: &Metadata) -> Option<FileId> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossbeam_channel::unbounded;
+    use std::collections::HashMap;
+
+    fn walk(root: &Path, rules: &RootRules, threads: usize) -> HashMap<PathBuf, DirListing> {
+        walk_resumed(root, rules, threads, HashSet::new())
+    }
+
+    fn walk_resumed(
+        root: &Path,
+        rules: &RootRules,
+        threads: usize,
+        completed_dirs: HashSet<PathBuf>,
+    ) -> HashMap<PathBuf, DirListing> {
+        walk_with_options(root, rules, threads, completed_dirs, &RootOptions::default())
+    }
+
+    fn walk_with_options(
+        root: &Path,
+        rules: &RootRules,
+        threads: usize,
+        completed_dirs: HashSet<PathBuf>,
+        options: &RootOptions,
+    ) -> HashMap<PathBuf, DirListing> {
+        let token = Arc::new(AtomicBool::new(false));
+        let ignores = IgnoreFiles::from_recorded(root.to_str().unwrap(), &[]);
+        let walker = Walker::new(rules, ignores, completed_dirs, options, &token);
+        let (tx, rx) = unbounded();
+        walker.run(root, root, threads, tx);
+
+        rx.into_iter()
+            .map(|event| match event {
+                WalkEvent::Listing(listing) => (listing.dir.clone(), listing),
+                WalkEvent::Error(e) => panic!("walk error: {}", e),
+            })
+            .collect()
+    }
+
+    fn make_tree(root: &Path) {
+        for d in ["a/b/c", "a/d", "e"] {
+            fs::create_dir_all(root.join(d)).unwrap();
+        }
+        for f in ["x.txt", "a/y.txt", "a/b/c/z.txt", "a/d/w.txt", "e/v.txt"] {
+            fs::write(root.join(f), "data").unwrap();
+        }
+    }
+
+    #[test]
+    fn test_walk_lists_every_directory_once() {
+        let dir = tempfile::tempdir().unwrap();
+        make_tree(dir.path());
+        let rules = RootRules::compile(dir.path().to_str().unwrap(), &[]).unwrap();
+
+        for threads in [1, 4] {
+            let listings = walk(dir.path(), &rules, threads);
+            assert_eq!(listings.len(), 6); // root, a, a/b, a/b/c, a/d, e
+
+            let entry_count: usize = listings.values().map(|l| l.entries.len()).sum();
+            assert_eq!(entry_count, 10); // 5 dirs + 5 files
+            assert!(listings.values().all(|l| l.status == DirStatus::Listed));
+        }
+    }
+
+    #[test]
+    fn test_walk_applies_ignore_files() {
+        let dir = tempfile::tempdir().unwrap();
+        make_tree(dir.path());
+        fs::write(dir.path().join("a").join(IGNORE_FILE_NAME), "b/\n*.txt\n").unwrap();
+        let rules = RootRules::compile(dir.path().to_str().unwrap(), &[]).unwrap();
+
+        let listings = walk(dir.path(), &rules, 2);
+        // a/b and a/b/c are never listed
+        assert_eq!(listings.len(), 4);
+
+        let a = &listings[&dir.path().join("a")];
+        assert!(a.new_ignore_file.is_some());
+        let names: Vec<_> = a
+            .entries
+            .iter()
+            .map(|e| e.path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert!(names.contains(&IGNORE_FILE_NAME.to_string()));
+        assert!(names.contains(&"d".to_string()));
+        assert!(!names.contains(&"b".to_string()));
+        assert!(!names.contains(&"y.txt".to_string()));
+
+        // Applies below a/ too
+        assert!(listings[&dir.path().join("a/d")].entries.is_empty());
+    }
+
+    #[test]
+    fn test_walk_from_subdirectory() {
+        let dir = tempfile::tempdir().unwrap();
+        make_tree(dir.path());
+        let rules = RootRules::compile(dir.path().to_str().unwrap(), &[]).unwrap();
+        let token = Arc::new(AtomicBool::new(false));
+        let ignores = IgnoreFiles::from_recorded(dir.path().to_str().unwrap(), &[]);
+        let walker = Walker::new(&rules, ignores, HashSet::new(), &RootOptions::default(), &token);
+        let (tx, rx) = unbounded();
+
+        walker.run(dir.path(), &dir.path().join("a"), 2, tx);
+        let mut listed: Vec<PathBuf> = rx
+            .into_iter()
+            .map(|event| match event {
+                WalkEvent::Listing(listing) => listing.dir,
+                WalkEvent::Error(e) => panic!("walk error: {}", e),
+            })
+            .collect();
+        listed.sort();
+        let expected: Vec<PathBuf> = ["a", "a/b", "a/b/c", "a/d"].iter().map(|d| dir.path().join(d)).collect();
+        assert_eq!(listed, expected);
+    }
+
+    #[test]
+    fn test_walk_vanished_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("missing");
+        let rules = RootRules::compile(missing.to_str().unwrap(), &[]).unwrap();
+
+        let listings = walk(&missing, &rules, 2);
+        assert_eq!(listings.len(), 1);
+        assert_eq!(listings[&missing].status, DirStatus::Vanished);
+    }
+
+    #[test]
+    fn test_walk_stops_on_interrupt() {
+        let dir = tempfile::tempdir().unwrap();
+        make_tree(dir.path());
+        let rules = RootRules::compile(dir.path().to_str().unwrap(), &[]).unwrap();
+        let token = Arc::new(AtomicBool::new(true));
+        let ignores = IgnoreFiles::from_recorded(dir.path().to_str().unwrap(), &[]);
+        let walker = Walker::new(&rules, ignores, HashSet::new(), &RootOptions::default(), &token);
+        let (tx, rx) = unbounded();
+
+        walker.run(dir.path(), dir.path(), 4, tx);
+        assert_eq!(rx.into_iter().count(), 0);
+    }
+
+    #[test]
+    fn test_walk_skips_completed_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        make_tree(dir.path());
+        let rules = RootRules::compile(dir.path().to_str().unwrap(), &[]).unwrap();
+        let completed = HashSet::from([dir.path().join("a/b")]);
+
+        let listings = walk_resumed(dir.path(), &rules, 2, completed);
+        // a/b and a/b/c are not listed
+        assert_eq!(listings.len(), 4);
+
+        // a/b is still reported as an entry of a, marked completed
+        let a = &listings[&dir.path().join("a")];
+        let b = a.entries.iter().find(|e| e.path.ends_with("a/b")).unwrap();
+        assert!(b.completed);
+        assert!(!b.is_walked_dir());
+        assert!(a.entries.iter().filter(|e| e.path != b.path).all(|e| !e.completed));
+    }
+
+    #[cfg(unix)]
+    fn follow_options(max_symlink_depth: u32) -> RootOptions {
+        RootOptions {
+            follow_symlinks: true,
+            max_symlink_depth,
+            ..RootOptions::default()
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_walk_records_symlink_targets_without_following() {
+        let dir = tempfile::tempdir().unwrap();
+        make_tree(dir.path());
+        std::os::unix::fs::symlink("a/b", dir.path().join("link")).unwrap();
+        let rules = RootRules::compile(dir.path().to_str().unwrap(), &[]).unwrap();
+
+        let listings = walk(dir.path(), &rules, 2);
+        assert_eq!(listings.len(), 6);
+
+        let root = &listings[&dir.path().to_path_buf()];
+        let link = root.entries.iter().find(|e| e.path.ends_with("link")).unwrap();
+        assert_eq!(link.symlink_target.as_deref(), Some("a/b"));
+        assert!(!link.followed);
+        assert!(!link.is_walked_dir());
+        assert!(root.entries.iter().filter(|e| e.path != link.path).all(|e| e.symlink_target.is_none()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_walk_follows_symlinked_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        make_tree(dir.path());
+        fs::write(outside.path().join("o.txt"), "data").unwrap();
+        std::os::unix::fs::symlink(outside.path(), dir.path().join("e/out")).unwrap();
+        let rules = RootRules::compile(dir.path().to_str().unwrap(), &[]).unwrap();
+
+        let listings = walk_with_options(dir.path(), &rules, 2, HashSet::new(), &follow_options(8));
+        // The link is listed under its own path
+        let out = &listings[&dir.path().join("e/out")];
+        assert_eq!(out.entries.len(), 1);
+        assert!(out.entries[0].path.ends_with("e/out/o.txt"));
+
+        let e = &listings[&dir.path().join("e")];
+        let link = e.entries.iter().find(|e| e.path.ends_with("out")).unwrap();
+        assert!(link.followed);
+        assert!(link.is_walked_dir());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_walk_does_not_follow_symlink_loops() {
+        let dir = tempfile::tempdir().unwrap();
+        make_tree(dir.path());
+        // Points back at an ancestor of the link
+        std::os::unix::fs::symlink("..", dir.path().join("a/b/up")).unwrap();
+        let rules = RootRules::compile(dir.path().to_str().unwrap(), &[]).unwrap();
+
+        let listings = walk_with_options(dir.path(), &rules, 4, HashSet::new(), &follow_options(8));
+        assert_eq!(listings.len(), 6);
+
+        let b = &listings[&dir.path().join("a/b")];
+        let up = b.entries.iter().find(|e| e.path.ends_with("up")).unwrap();
+        assert!(!up.followed);
+        assert_eq!(up.symlink_target.as_deref(), Some(".."));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_walk_caps_nested_symlink_depth() {
+        let dir = tempfile::tempdir().unwrap();
+        let chain = tempfile::tempdir().unwrap();
+        // root/l1 -> chain/c1, chain/c1/l2 -> chain/c2, chain/c2/l3 -> chain/c3
+        for d in ["c1", "c2", "c3"] {
+            fs::create_dir(chain.path().join(d)).unwrap();
+        }
+        std::os::unix::fs::symlink(chain.path().join("c1"), dir.path().join("l1")).unwrap();
+        std::os::unix::fs::symlink(chain.path().join("c2"), chain.path().join("c1/l2")).unwrap();
+        std::os::unix::fs::symlink(chain.path().join("c3"), chain.path().join("c2/l3")).unwrap();
+        let rules = RootRules::compile(dir.path().to_str().unwrap(), &[]).unwrap();
+
+        let listings = walk_with_options(dir.path(), &rules, 2, HashSet::new(), &follow_options(2));
+        // root, l1, l1/l2 - but not l1/l2/l3
+        assert_eq!(listings.len(), 3);
+        let l2 = &listings[&dir.path().join("l1/l2")];
+        assert!(!l2.entries[0].followed);
+    }
+}
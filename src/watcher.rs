@@ -0,0 +1,453 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use log::{error, info, warn};
+use notify::event::{EventKind, MetadataKind, ModifyKind};
+use notify::{ErrorKind, Event, RecommendedWatcher, RecursiveMode, Watcher};
+use once_cell::sync::Lazy;
+use rusqlite::Connection;
+use serde::Serialize;
+
+use crate::db::Database;
+use crate::error::FsPulseError;
+use crate::roots::RootOptions;
+use crate::task_manager::TaskManager;
+
+/// How often the watchers are reconciled with the roots table and checked for
+/// settled changes
+pub const WATCH_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// A root's changes are scanned once no new change has arrived for this long
+const SETTLE_TIME: Duration = Duration::from_secs(10);
+
+/// Upper bound on how long continuous activity can hold back a scan
+const MAX_SETTLE_DELAY: Duration = Duration::from_secs(300);
+
+/// Beyond this many separate changed directories, the whole root is scanned
+const MAX_DIRTY_DIRS: usize = 100;
+
+/// The changed directories below a root that haven't been queued for a scan yet.
+///
+/// A change to a path shows up in its parent directory's listing, so the parent
+/// is what gets scanned. Directories below another changed directory are
+/// covered by it and not kept.
+pub struct DirtyDirs {
+    root_path: PathBuf,
+    dirs: BTreeSet<PathBuf>,
+    whole_root: bool,
+    first_change: Option<Instant>,
+    last_change: Option<Instant>,
+}
+
+impl DirtyDirs {
+    pub fn new(root_path: &Path) -> Self {
+        Self {
+            root_path: root_path.to_path_buf(),
+            dirs: BTreeSet::new(),
+            whole_root: false,
+            first_change: None,
+            last_change: None,
+        }
+    }
+
+    /// Record a change to `path`. Paths outside the root are ignored.
+    pub fn record(&mut self, path: &Path, now: Instant) {
+        if !path.starts_with(&self.root_path) {
+            return;
+        }
+        self.touch(now);
+        if self.whole_root {
+            return;
+        }
+
+        let dir = match path.parent() {
+            Some(parent) if path != self.root_path && parent != self.root_path => parent,
+            _ => {
+                self.whole_root = true;
+                self.dirs.clear();
+                return;
+            }
+        };
+
+        if self.dirs.iter().any(|d| dir.starts_with(d)) {
+            return;
+        }
+        self.dirs.retain(|d| !d.starts_with(dir));
+        self.dirs.insert(dir.to_path_buf());
+
+        if self.dirs.len() > MAX_DIRTY_DIRS {
+            self.whole_root = true;
+            self.dirs.clear();
+        }
+    }
+
+    /// Record a change that could be anywhere in the root, such as lost events
+    pub fn record_whole_root(&mut self, now: Instant) {
+        self.touch(now);
+        self.whole_root = true;
+        self.dirs.clear();
+    }
+
+    /// The changed directories as root-relative subpaths (empty for the whole
+    /// root), once changes have settled: nothing new for `settle`, or activity
+    /// ongoing for longer than `max_delay`. None while still settling.
+    pub fn settled(&self, now: Instant, settle: Duration, max_delay: Duration) -> Option<Vec<String>> {
+        let (first, last) = (self.first_change?, self.last_change?);
+        if now.duration_since(last) < settle && now.duration_since(first) < max_delay {
+            return None;
+        }
+
+        if self.whole_root {
+            return Some(Vec::new());
+        }
+        Some(
+            self.dirs
+                .iter()
+                .filter_map(|d| d.strip_prefix(&self.root_path).ok())
+                .map(|d| d.to_string_lossy().into_owned())
+                .collect(),
+        )
+    }
+
+    /// Forget the recorded changes, once their scan is queued
+    pub fn clear(&mut self) {
+        self.dirs.clear();
+        self.whole_root = false;
+        self.first_change = None;
+        self.last_change = None;
+    }
+
+    fn touch(&mut self, now: Instant) {
+        self.first_change.get_or_insert(now);
+        self.last_change = Some(now);
+    }
+}
+
+/// Whether a root is being watched, as reported on the roots API
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum WatchStatus {
+    Watching,
+    /// Not watching. The root is still covered by manual and scheduled scans.
+    Degraded { reason: String },
+}
+
+/// State shared between a root's watcher callback and the poll loop
+struct WatchShared {
+    dirty: DirtyDirs,
+    /// Set when the watcher can no longer see every change
+    failure: Option<String>,
+}
+
+/// The watcher for one root
+pub struct RootWatch {
+    options: RootOptions,
+    /// None once degraded
+    watcher: Option<RecommendedWatcher>,
+    shared: Arc<Mutex<WatchShared>>,
+}
+
+impl RootWatch {
+    /// Start watching `root_path` recursively. Events under `ignore_dir` (the
+    /// database directory, if it lies inside the root) are not changes to scan.
+    ///
+    /// When the OS watch limit is hit the root is left unwatched and reported as
+    /// degraded, rather than watched partially and silently missing changes.
+    pub fn start(root_path: &Path, options: &RootOptions, ignore_dir: Option<PathBuf>) -> Self {
+        let shared = Arc::new(Mutex::new(WatchShared {
+            dirty: DirtyDirs::new(root_path),
+            failure: None,
+        }));
+
+        let handler_shared = Arc::clone(&shared);
+        let handler = move |result: notify::Result<Event>| {
+            let mut shared = handler_shared.lock().unwrap();
+            let now = Instant::now();
+            match result {
+                Ok(event) if event.need_rescan() => shared.dirty.record_whole_root(now),
+                Ok(event) if is_change(&event.kind) => {
+                    for path in &event.paths {
+                        if ignore_dir.as_ref().is_some_and(|d| path.starts_with(d)) {
+                            continue;
+                        }
+                        shared.dirty.record(path, now);
+                    }
+                }
+                Ok(_) => {}
+                Err(e) if matches!(e.kind, ErrorKind::MaxFilesWatch) => {
+                    // A directory created since the watch started couldn't be
+                    // watched. Scan everything once to catch up, then stop.
+                    shared.dirty.record_whole_root(now);
+                    shared.failure = Some(e.to_string());
+                }
+                Err(e) => warn!("Watch error: {}", e),
+            }
+        };
+
+        let config = notify::Config::default().with_follow_symlinks(options.follow_symlinks);
+        let watcher = RecommendedWatcher::new(handler, config).and_then(|mut watcher| {
+            watcher.watch(root_path, RecursiveMode::Recursive)?;
+            Ok(watcher)
+        });
+
+        let watcher = match watcher {
+            Ok(watcher) => {
+                info!("Watching '{}' for changes", root_path.display());
+                Some(watcher)
+            }
+            Err(e) => {
+                warn!(
+                    "Cannot watch '{}': {}. Falling back to manual and scheduled scans",
+                    root_path.display(),
+                    e
+                );
+                shared.lock().unwrap().failure = Some(e.to_string());
+                None
+            }
+        };
+
+        Self {
+            options: options.clone(),
+            watcher,
+            shared,
+        }
+    }
+
+    pub fn status(&self) -> WatchStatus {
+        match &self.shared.lock().unwrap().failure {
+            None => WatchStatus::Watching,
+            Some(reason) => WatchStatus::Degraded {
+                reason: reason.clone(),
+            },
+        }
+    }
+
+    /// Stop watching if the watcher has failed. Changes it recorded are kept.
+    fn degrade_if_failed(&mut self, root_path: &str) {
+        if self.watcher.is_some() && self.shared.lock().unwrap().failure.is_some() {
+            warn!(
+                "Stopped watching '{}': OS file watch limit reached. Falling back to manual and scheduled scans",
+                root_path
+            );
+            self.watcher = None;
+        }
+    }
+}
+
+/// Only changes to content, names or metadata count. Reads (including the
+/// scanner's own hashing and validation) don't.
+fn is_change(kind: &EventKind) -> bool {
+    !matches!(
+        kind,
+        EventKind::Access(_) | EventKind::Modify(ModifyKind::Metadata(MetadataKind::AccessTime))
+    )
+}
+
+/// The watchers for all roots with `watch` enabled.
+pub struct RootWatchers {
+    watches: HashMap<i64, RootWatch>,
+}
+
+static ROOT_WATCHERS: Lazy<Mutex<RootWatchers>> = Lazy::new(|| {
+    Mutex::new(RootWatchers {
+        watches: HashMap::new(),
+    })
+});
+
+impl RootWatchers {
+    /// Bring the watchers in line with the roots table, then queue a scan for
+    /// each root whose changes have settled. Called every `WATCH_POLL_INTERVAL`.
+    ///
+    /// A degraded root stays unwatched until its options change or the server
+    /// restarts.
+    pub fn poll() -> Result<(), FsPulseError> {
+        let conn = Database::get_connection()?;
+        let roots = Self::query_watched_roots(&conn)?;
+        let ignore_dir = Database::get_path()
+            .ok()
+            .and_then(|p| p.parent().map(Path::to_path_buf));
+
+        let mut watchers = ROOT_WATCHERS.lock().unwrap();
+        watchers.watches.retain(|root_id, _| roots.iter().any(|(id, _, _)| id == root_id));
+
+        let now = Instant::now();
+        for (root_id, root_path, options) in &roots {
+            let current = watchers.watches.get(root_id).is_some_and(|w| &w.options == options);
+            if !current {
+                let watch = RootWatch::start(Path::new(root_path), options, ignore_dir.clone());
+                watchers.watches.insert(*root_id, watch);
+            }
+
+            let watch = watchers.watches.get_mut(root_id).unwrap();
+            watch.degrade_if_failed(root_path);
+
+            let mut shared = watch.shared.lock().unwrap();
+            let Some(subpaths) = shared.dirty.settled(now, SETTLE_TIME, MAX_SETTLE_DELAY) else {
+                continue;
+            };
+            // If the scan can't be queued now, the changes are kept for the next poll
+            match TaskManager::schedule_watch_scan(&conn, *root_id, &subpaths) {
+                Ok(()) => {
+                    info!(
+                        "Queued watch scan of '{}' ({})",
+                        root_path,
+                        if subpaths.is_empty() {
+                            "whole root".to_string()
+                        } else {
+                            format!("{} directories", subpaths.len())
+                        }
+                    );
+                    shared.dirty.clear();
+                }
+                Err(FsPulseError::ShuttingDown) => {}
+                Err(e) => error!("Failed to queue watch scan of '{}': {}", root_path, e),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Watch status of a root, or None if it isn't watched
+    pub fn status(root_id: i64) -> Option<WatchStatus> {
+        let watchers = ROOT_WATCHERS.lock().unwrap();
+        watchers.watches.get(&root_id).map(RootWatch::status)
+    }
+
+    /// Stop all watchers
+    pub fn shutdown() {
+        ROOT_WATCHERS.lock().unwrap().watches.clear();
+    }
+
+    fn query_watched_roots(conn: &Connection) -> Result<Vec<(i64, String, RootOptions)>, FsPulseError> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT root_id, root_path, {} FROM roots WHERE watch = 1",
+            RootOptions::COLUMNS
+        ))?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get(0)?, row.get(1)?, RootOptions::from_row(row, 2)?))
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn relative(dirty: &DirtyDirs) -> Vec<String> {
+        dirty.settled(Instant::now() + SETTLE_TIME, SETTLE_TIME, MAX_SETTLE_DELAY).unwrap()
+    }
+
+    #[test]
+    fn test_dirty_dirs_records_parents() {
+        let mut dirty = DirtyDirs::new(Path::new("/r"));
+        let now = Instant::now();
+        dirty.record(Path::new("/r/a/b/file.txt"), now);
+        dirty.record(Path::new("/r/c/new_dir"), now);
+        dirty.record(Path::new("/elsewhere/x"), now);
+
+        assert_eq!(relative(&dirty), vec!["a/b", "c"]);
+    }
+
+    #[test]
+    fn test_dirty_dirs_collapses_nested() {
+        let mut dirty = DirtyDirs::new(Path::new("/r"));
+        let now = Instant::now();
+        dirty.record(Path::new("/r/a/b/c/file.txt"), now);
+        dirty.record(Path::new("/r/a/b/other.txt"), now);
+        dirty.record(Path::new("/r/a/b/c/d/deeper.txt"), now);
+
+        assert_eq!(relative(&dirty), vec!["a/b"]);
+    }
+
+    #[test]
+    fn test_dirty_dirs_root_level_change_is_whole_root() {
+        let mut dirty = DirtyDirs::new(Path::new("/r"));
+        let now = Instant::now();
+        dirty.record(Path::new("/r/a/file.txt"), now);
+        dirty.record(Path::new("/r/top.txt"), now);
+        dirty.record(Path::new("/r/a/more.txt"), now);
+
+        assert!(relative(&dirty).is_empty());
+        assert!(dirty.first_change.is_some());
+    }
+
+    #[test]
+    fn test_dirty_dirs_too_many_is_whole_root() {
+        let mut dirty = DirtyDirs::new(Path::new("/r"));
+        let now = Instant::now();
+        for i in 0..=MAX_DIRTY_DIRS {
+            dirty.record(&Path::new("/r").join(i.to_string()).join("f"), now);
+        }
+        assert!(relative(&dirty).is_empty());
+    }
+
+    #[test]
+    fn test_dirty_dirs_settle() {
+        let mut dirty = DirtyDirs::new(Path::new("/r"));
+        let start = Instant::now();
+        let settle = Duration::from_secs(10);
+        let max_delay = Duration::from_secs(60);
+        assert!(dirty.settled(start, settle, max_delay).is_none());
+
+        dirty.record(Path::new("/r/a/f"), start);
+        assert!(dirty.settled(start + Duration::from_secs(5), settle, max_delay).is_none());
+        assert!(dirty.settled(start + Duration::from_secs(10), settle, max_delay).is_some());
+
+        // Steady activity holds the scan back only up to max_delay
+        for secs in (5..60).step_by(5) {
+            let t = start + Duration::from_secs(secs);
+            dirty.record(Path::new("/r/a/f"), t);
+            assert!(dirty.settled(t, settle, max_delay).is_none());
+        }
+        assert!(dirty.settled(start + max_delay, settle, max_delay).is_some());
+
+        dirty.clear();
+        assert!(dirty.first_change.is_none());
+        assert!(dirty.settled(start + max_delay, settle, max_delay).is_none());
+    }
+
+    #[test]
+    fn test_reads_are_not_changes() {
+        use notify::event::{AccessKind, CreateKind, DataChange};
+        assert!(!is_change(&EventKind::Access(AccessKind::Any)));
+        assert!(!is_change(&EventKind::Modify(ModifyKind::Metadata(MetadataKind::AccessTime))));
+        assert!(is_change(&EventKind::Create(CreateKind::File)));
+        assert!(is_change(&EventKind::Modify(ModifyKind::Data(DataChange::Any))));
+    }
+
+    #[test]
+    fn test_watch_temp_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        fs::create_dir_all(root.join("photos/2024")).unwrap();
+        fs::create_dir_all(root.join("docs")).unwrap();
+
+        let watch = RootWatch::start(&root, &RootOptions::default(), Some(root.join("docs")));
+        assert_eq!(watch.status(), WatchStatus::Watching);
+
+        // Changes under the ignored directory don't count
+        fs::write(root.join("docs/notes.txt"), "notes").unwrap();
+        fs::write(root.join("photos/2024/img.jpg"), "jpeg").unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while watch.shared.lock().unwrap().dirty.first_change.is_none() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        // Let any trailing events for the same write arrive
+        std::thread::sleep(Duration::from_millis(200));
+
+        assert_eq!(relative(&watch.shared.lock().unwrap().dirty), vec!["photos/2024"]);
+    }
+
+    #[test]
+    fn test_watch_missing_root_is_degraded() {
+        let dir = tempfile::tempdir().unwrap();
+        let watch = RootWatch::start(&dir.path().join("missing"), &RootOptions::default(), None);
+        assert!(matches!(watch.status(), WatchStatus::Degraded { .. }));
+        assert!(watch.watcher.is_none());
+    }
+}